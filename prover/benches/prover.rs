@@ -0,0 +1,232 @@
+//! Baseline benchmarks for proving, trace generation and the hash gadgets
+//!
+//! Run with
+//!
+//! ```text
+//! cargo bench --features test-utils
+//! ```
+//!
+//! To compare a change against the current tree, save a baseline first and then run the
+//! benchmarks again against it on the changed tree:
+//!
+//! ```text
+//! git stash && cargo bench --features test-utils -- --save-baseline before
+//! git stash pop && cargo bench --features test-utils -- --baseline before
+//! ```
+//!
+//! Criterion reports the change per benchmark and flags the ones outside its noise threshold.
+//! Reports land in `target/criterion/`. Compare runs on the same machine with nothing else
+//! running, and pass a filter (e.g. `-- generate_pob_trace`) to rerun a single group.
+//!
+//! The MPT and proving inputs come from `test_utils::mock_account_proof`, an 8-layer trie with
+//! dense branch nodes, so the MPT numbers match a typical mainnet account proof.
+
+use alloy_primitives::U256;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use proof_of_burn_stwo::circuits::proof_of_burn::ProofOfBurnInputs;
+use proof_of_burn_stwo::circuits::proof_of_burn_air::generate_pob_trace;
+use proof_of_burn_stwo::circuits::spend::SpendInputs;
+use proof_of_burn_stwo::coins;
+use proof_of_burn_stwo::constants::{poseidon_nullifier_prefix, MAINNET_CHAIN_ID};
+use proof_of_burn_stwo::field::ops::simd::{add_columns, dot_columns};
+use proof_of_burn_stwo::field::ops::{add_slices, dot};
+use proof_of_burn_stwo::field::M31;
+use proof_of_burn_stwo::secret::Secret;
+use proof_of_burn_stwo::test_utils::mock_account_proof;
+use proof_of_burn_stwo::utils::burn_address::compute_burn_address_hash;
+use proof_of_burn_stwo::utils::mpt::verify_mpt_proof;
+use proof_of_burn_stwo::utils::poseidon2_stwo::{
+    poseidon2_permutation, poseidon2_permutation_packed, N_STATE,
+};
+use proof_of_burn_stwo::utils::pow::compute_pow_hash;
+use proof_of_burn_stwo::prover::{generate_batch_table_trace, generate_pob_batch_trace};
+use proof_of_burn_stwo::{
+    prove_proof_of_burn, prove_spend, verify_proof_of_burn, StarkConfig,
+};
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::m31::PackedBaseField;
+
+/// Depth of the benchmarked account proof
+const MPT_LAYERS: usize = 8;
+
+fn burn_key() -> M31 {
+    M31::from(12345)
+}
+
+fn burn_extra_commitment() -> M31 {
+    M31::from(100)
+}
+
+fn reveal_amount() -> U256 {
+    U256::from(500_000_000_000_000_000u64)
+}
+
+fn balance() -> U256 {
+    U256::from(1_000_000_000_000_000_000u64)
+}
+
+fn address_hash() -> [u8; 32] {
+    compute_burn_address_hash(burn_key(), reveal_amount(), burn_extra_commitment())
+}
+
+fn pob_inputs() -> ProofOfBurnInputs {
+    let proof = mock_account_proof(&address_hash(), balance(), MPT_LAYERS);
+    ProofOfBurnInputs {
+        burn_key: Secret::new(burn_key()),
+        actual_balance: balance(),
+        intended_balance: balance(),
+        reveal_amount: reveal_amount(),
+        burn_extra_commitment: burn_extra_commitment(),
+        layers: proof.layers,
+        block_header: proof.block_header,
+        header_chain: Vec::new(),
+        chain_id: MAINNET_CHAIN_ID,
+        valid_until_block: None,
+        num_leaf_address_nibbles: proof.num_leaf_address_nibbles,
+        byte_security_relax: 0,
+        proof_extra_commitment: M31::from(200),
+        expected_burn_address: None,
+    }
+}
+
+fn spend_inputs() -> SpendInputs {
+    SpendInputs {
+        burn_key: Secret::new(burn_key()),
+        balance: reveal_amount(),
+        withdrawn_balance: U256::from(200_000_000_000_000_000u64),
+        extra_commitment: M31::from(300),
+        recipient: None,
+        chain_id: MAINNET_CHAIN_ID,
+        expected_coin: None,
+    }
+}
+
+fn bench_poseidon2(c: &mut Criterion) {
+    let state: [BaseField; N_STATE] = std::array::from_fn(|i| BaseField::from(i as u32));
+    let packed = state.map(PackedBaseField::broadcast);
+
+    let mut group = c.benchmark_group("poseidon2_permutation");
+    group.bench_function("scalar", |b| b.iter(|| poseidon2_permutation(black_box(state))));
+    group.bench_function("packed", |b| b.iter(|| poseidon2_permutation_packed(black_box(packed))));
+    group.finish();
+}
+
+fn bench_generate_pob_trace(c: &mut Criterion) {
+    let inputs = pob_inputs();
+    let mut group = c.benchmark_group("generate_pob_trace");
+    for log_size in [6, 10, 14] {
+        group.bench_with_input(BenchmarkId::from_parameter(log_size), &log_size, |b, &log_size| {
+            b.iter(|| generate_pob_trace(log_size, black_box(&inputs)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Poseidon2 table trace of a batch of identical instances, with and without the permutation
+/// cache: every input state after the first instance's repeats
+fn bench_batch_table_trace(c: &mut Criterion) {
+    let batch = vec![pob_inputs(); 64];
+    let (_, _, lookup_data) = generate_pob_batch_trace(&batch, &StarkConfig::default()).unwrap();
+    let mut group = c.benchmark_group("batch_table_trace");
+    group.bench_function("uncached", |b| {
+        b.iter(|| generate_batch_table_trace(black_box(&lookup_data), false).unwrap())
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| generate_batch_table_trace(black_box(&lookup_data), true).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_prove(c: &mut Criterion) {
+    let pob = pob_inputs();
+    let spend = spend_inputs();
+
+    let mut group = c.benchmark_group("prove");
+    group.sample_size(10);
+    for log_n_rows in [6, 10] {
+        group.bench_with_input(
+            BenchmarkId::new("prove_proof_of_burn", log_n_rows),
+            &log_n_rows,
+            |b, &log_n_rows| b.iter(|| prove_proof_of_burn(&pob, log_n_rows, StarkConfig::default()).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("prove_spend", log_n_rows),
+            &log_n_rows,
+            |b, &log_n_rows| b.iter(|| prove_spend(&spend, log_n_rows, StarkConfig::default()).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn bench_verify_proof_of_burn(c: &mut Criterion) {
+    let (components, proof) = prove_proof_of_burn(&pob_inputs(), 6, StarkConfig::default()).unwrap();
+    let mut group = c.benchmark_group("verify_proof_of_burn");
+    group.sample_size(10);
+    group.bench_function("6", |b| {
+        b.iter(|| verify_proof_of_burn(&components, proof.clone()).unwrap())
+    });
+    group.finish();
+}
+
+/// The prefix accessors sit on the witness hot path: every coin and nullifier starts from one
+fn bench_poseidon_prefix(c: &mut Criterion) {
+    let mut group = c.benchmark_group("poseidon_prefix");
+    group.bench_function("nullifier_prefix", |b| b.iter(|| black_box(poseidon_nullifier_prefix())));
+    group.bench_function("coin", |b| {
+        b.iter(|| coins::coin(black_box(burn_key()), black_box(balance())))
+    });
+    group.finish();
+}
+
+fn bench_compute_pow_hash(c: &mut Criterion) {
+    c.bench_function("compute_pow_hash", |b| {
+        b.iter(|| compute_pow_hash(black_box(burn_key()), reveal_amount(), burn_extra_commitment()))
+    });
+}
+
+fn bench_verify_mpt_proof(c: &mut Criterion) {
+    let address_hash = address_hash();
+    let proof = mock_account_proof(&address_hash, balance(), MPT_LAYERS);
+    c.bench_function("verify_mpt_proof", |b| {
+        b.iter(|| {
+            verify_mpt_proof(black_box(&proof.layers), &proof.state_root, &address_hash).unwrap()
+        })
+    });
+}
+
+fn bench_field_ops(c: &mut Criterion) {
+    const LEN: u32 = 1 << 16;
+    let values = |seed: u32| (0..LEN).map(move |i| i.wrapping_mul(seed) % M31::PRIME);
+    let a: Vec<M31> = values(7).map(M31::from).collect();
+    let b: Vec<M31> = values(13).map(M31::from).collect();
+    let col_a: BaseColumn = values(7).map(BaseField::from).collect();
+    let col_b: BaseColumn = values(13).map(BaseField::from).collect();
+
+    let mut group = c.benchmark_group("field_ops");
+    group.bench_function("dot/slice", |bench| bench.iter(|| dot(black_box(&a), black_box(&b)).unwrap()));
+    group.bench_function("dot/simd", |bench| {
+        bench.iter(|| dot_columns(black_box(&col_a), black_box(&col_b)).unwrap())
+    });
+    group.bench_function("add/slice", |bench| {
+        bench.iter(|| add_slices(black_box(&a), black_box(&b)).unwrap())
+    });
+    group.bench_function("add/simd", |bench| {
+        bench.iter(|| add_columns(black_box(&col_a), black_box(&col_b)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_poseidon2,
+    bench_generate_pob_trace,
+    bench_batch_table_trace,
+    bench_prove,
+    bench_verify_proof_of_burn,
+    bench_poseidon_prefix,
+    bench_compute_pow_hash,
+    bench_verify_mpt_proof,
+    bench_field_ops,
+);
+criterion_main!(benches);
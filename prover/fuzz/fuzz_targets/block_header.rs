@@ -0,0 +1,17 @@
+//! `BlockHeader::decode` and the circuit's fixed-offset state root read on arbitrary bytes:
+//! never panic, a header that decodes re-encodes to the input, and a checked state root is the
+//! one the circuit reads
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proof_of_burn_stwo::utils::header::{state_root_at_offset, BlockHeader};
+
+fuzz_target!(|data: &[u8]| {
+    let at_offset = state_root_at_offset(data);
+    if let Ok(header) = BlockHeader::decode(data) {
+        assert_eq!(header.encode(), data);
+        if let Ok(state_root) = header.state_root() {
+            assert_eq!(Some(state_root), at_offset);
+        }
+    }
+});
@@ -0,0 +1,15 @@
+//! `ProofEnvelope::from_bytes_auto` on arbitrary bytes: never panics, never inflates past
+//! `MAX_ENVELOPE_LEN`, and an envelope that decodes re-encodes stably
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proof_of_burn_stwo::prover::ProofEnvelope;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(envelope) = ProofEnvelope::from_bytes_auto(data) {
+        let _ = envelope.stark_proof();
+        let bytes = envelope.to_bytes().expect("a decoded envelope re-encodes");
+        let decoded = ProofEnvelope::from_bytes_auto(&bytes).expect("an encoded envelope decodes");
+        assert_eq!(decoded.to_bytes().expect("a decoded envelope re-encodes"), bytes);
+    }
+});
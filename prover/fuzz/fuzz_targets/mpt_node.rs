@@ -0,0 +1,14 @@
+//! `MptNode::decode` on arbitrary bytes: never panics, and a node that decodes re-encodes to
+//! the input
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proof_of_burn_stwo::utils::mpt::MptNode;
+use proof_of_burn_stwo::utils::rlp::rlp_list_items;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(node) = MptNode::decode(data) {
+        assert_eq!(node.encode(), data);
+    }
+    let _ = rlp_list_items(data);
+});
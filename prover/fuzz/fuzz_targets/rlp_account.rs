@@ -0,0 +1,15 @@
+//! `Account::decode` on arbitrary bytes: never panics, and an account that decodes re-encodes
+//! to exactly the bytes it was decoded from
+#![no_main]
+
+use alloy_rlp::Decodable;
+use libfuzzer_sys::fuzz_target;
+use proof_of_burn_stwo::utils::rlp::Account;
+
+fuzz_target!(|data: &[u8]| {
+    let mut buf = data;
+    if let Ok(account) = Account::decode(&mut buf) {
+        let consumed = data.len() - buf.len();
+        assert_eq!(account.encode_to_vec(), &data[..consumed]);
+    }
+});
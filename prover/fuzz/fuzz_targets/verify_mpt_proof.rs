@@ -0,0 +1,19 @@
+//! `verify_mpt_proof` on arbitrary layers: never panics
+//!
+//! With `root_matches` the state root is the hash of the first layer, so inputs get past the
+//! root check into the path and leaf decoding.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use proof_of_burn_stwo::utils::keccak::keccak256;
+use proof_of_burn_stwo::utils::mpt::{layer_issues, verify_mpt_proof};
+
+fuzz_target!(|input: (Vec<Vec<u8>>, [u8; 32], bool)| {
+    let (layers, address_hash, root_matches) = input;
+    let state_root = match layers.first() {
+        Some(root) if root_matches => keccak256(root),
+        _ => [0u8; 32],
+    };
+    let _ = layer_issues(&layers);
+    let _ = verify_mpt_proof(&layers, &state_root, &address_hash);
+});
@@ -0,0 +1,203 @@
+// Proof cost analysis
+// Choosing FRI parameters trades proof size and on-chain verification work against security.
+// `config_report` measures both on a dry-run burn proof instead of estimating them from the
+// parameters, so the numbers include whatever stwo actually decommits for this AIR.
+
+use alloy_primitives::U256;
+use serde::Serialize;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::pcs::TreeVec;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::constants::MAINNET_CHAIN_ID;
+use crate::field::M31;
+use crate::prover::{prove_proof_of_burn, StarkConfig};
+use crate::secret::Secret;
+
+/// Proof size and verification work of a burn proof under one configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ConfigReport {
+    /// Log2 of the PoB trace rows
+    pub log_n_rows: u32,
+    /// Conjectured security, see `StarkConfig::security_bits`
+    pub security_bits: u32,
+    /// FRI queries
+    pub n_queries: usize,
+    /// Log2 of the FRI blowup factor
+    pub log_blowup_factor: u32,
+    /// Proof-of-work grinding bits
+    pub pow_bits: u32,
+    /// Size of the JSON-serialized proof, as carried by `ProofEnvelope`
+    pub proof_size_bytes: usize,
+    /// Merkle trees the verifier checks openings against: the committed traces plus one per FRI layer
+    pub merkle_decommitments: usize,
+    /// Sibling hashes across all Merkle decommitments
+    pub witness_hashes: usize,
+    /// Base field elements the verifier reads at the query positions: queried trace values,
+    /// Merkle column witnesses and FRI layer witnesses (4 per `SecureField`)
+    pub queried_field_elements: usize,
+    /// Rough count of Merkle hash operations a Solidity verifier performs
+    ///
+    /// One compression per sibling hash plus one leaf hash per query and tree; path sharing
+    /// between queries makes the real count somewhat lower.
+    pub hash_operations: usize,
+    /// Whether the prover kept the trace polynomials' coefficients, see `StarkConfig::store_poly_coeffs`
+    pub store_poly_coeffs: bool,
+    /// Memory the trace polynomials' coefficients take while proving: one M31 per row of every
+    /// committed trace column
+    ///
+    /// Held until the proof is done when `store_poly_coeffs` is set, and saved otherwise.
+    pub coefficient_bytes: usize,
+}
+
+/// Measure `config` on a dry-run burn proof over `2^log_n_rows` rows
+///
+/// The inputs are fixed dummies: the proof's shape depends on the trace sizes and the
+/// configuration, not on the witness values. The prover falls back to `fri_params_for` when
+/// `config` does not fit the trace; the report shows the configuration as requested.
+pub fn config_report(log_n_rows: u32, config: StarkConfig) -> Result<ConfigReport, AnalysisError> {
+    let security_bits = config.security_bits();
+    let pow_bits = config.pow_bits;
+    let n_queries = config.fri_config.n_queries;
+    let log_blowup_factor = config.fri_config.log_blowup_factor;
+    let store_poly_coeffs = config.store_poly_coeffs;
+
+    let (components, proof) =
+        prove_proof_of_burn(&dry_run_inputs(), log_n_rows, config).map_err(AnalysisError::Prove)?;
+    let measured = measure(&proof)?;
+
+    Ok(ConfigReport {
+        log_n_rows,
+        security_bits,
+        n_queries,
+        log_blowup_factor,
+        pow_bits,
+        proof_size_bytes: measured.proof_size_bytes,
+        merkle_decommitments: measured.merkle_decommitments,
+        witness_hashes: measured.witness_hashes,
+        queried_field_elements: measured.queried_field_elements,
+        hash_operations: measured.witness_hashes + n_queries * measured.merkle_decommitments,
+        store_poly_coeffs,
+        coefficient_bytes: coefficient_bytes(&components.trace_log_degree_bounds()),
+    })
+}
+
+/// Bytes of the coefficients of trace columns with these log sizes
+fn coefficient_bytes(log_sizes: &TreeVec<Vec<u32>>) -> usize {
+    log_sizes.iter().flatten().map(|&log_size| (1usize << log_size) * std::mem::size_of::<M31>()).sum()
+}
+
+/// Counts read off a proof
+struct Measured {
+    proof_size_bytes: usize,
+    merkle_decommitments: usize,
+    witness_hashes: usize,
+    queried_field_elements: usize,
+}
+
+fn measure(proof: &StarkProof<Blake2sMerkleHasher>) -> Result<Measured, AnalysisError> {
+    let fri_layers: Vec<_> = std::iter::once(&proof.fri_proof.first_layer)
+        .chain(&proof.fri_proof.inner_layers)
+        .collect();
+    let decommitments: Vec<_> = proof
+        .decommitments
+        .iter()
+        .chain(fri_layers.iter().map(|layer| &layer.decommitment))
+        .collect();
+
+    let queried_values: usize = proof.queried_values.iter().flatten().map(Vec::len).sum();
+    let column_witness: usize = decommitments.iter().map(|d| d.column_witness.len()).sum();
+    let fri_witness: usize = fri_layers.iter().map(|layer| layer.fri_witness.len()).sum();
+
+    Ok(Measured {
+        proof_size_bytes: serde_json::to_vec(proof)?.len(),
+        merkle_decommitments: decommitments.len(),
+        witness_hashes: decommitments.iter().map(|d| d.hash_witness.len()).sum(),
+        queried_field_elements: queried_values + column_witness + fri_witness * SECURE_EXTENSION_DEGREE,
+    })
+}
+
+/// Burn inputs for the dry run: a 1 ETH burn with a typical header length
+fn dry_run_inputs() -> ProofOfBurnInputs {
+    ProofOfBurnInputs {
+        burn_key: Secret::new(M31::from(12345)),
+        actual_balance: U256::from(1_000_000_000_000_000_000u64),
+        intended_balance: U256::from(1_000_000_000_000_000_000u64),
+        reveal_amount: U256::from(500_000_000_000_000_000u64),
+        burn_extra_commitment: M31::from(100),
+        layers: vec![vec![0u8; 100], vec![0u8; 80]],
+        block_header: vec![0u8; 643],
+        header_chain: Vec::new(),
+        chain_id: MAINNET_CHAIN_ID,
+        valid_until_block: None,
+        num_leaf_address_nibbles: 50,
+        byte_security_relax: 0,
+        proof_extra_commitment: M31::from(200),
+        expected_burn_address: None,
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnalysisError {
+    #[error("Dry-run proof failed: {0}")]
+    Prove(anyhow::Error),
+
+    #[error("Proof serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG_N_ROWS: u32 = 5;
+
+    #[test]
+    fn test_more_queries_cost_more() {
+        let at_64 = config_report(LOG_N_ROWS, StarkConfig::default()).unwrap();
+        let mut config = StarkConfig::default();
+        config.fri_config.n_queries = 96;
+        let at_96 = config_report(LOG_N_ROWS, config).unwrap();
+
+        assert_eq!(at_64.n_queries, 64);
+        assert_eq!(at_96.security_bits, at_64.security_bits + 32);
+        // Same trees, opened at more positions
+        assert_eq!(at_96.merkle_decommitments, at_64.merkle_decommitments);
+        assert!(at_96.proof_size_bytes > at_64.proof_size_bytes);
+        assert!(at_96.queried_field_elements > at_64.queried_field_elements);
+        assert!(at_96.hash_operations > at_64.hash_operations);
+    }
+
+    #[test]
+    fn test_decommitments_cover_every_tree() {
+        let report = config_report(LOG_N_ROWS, StarkConfig::default()).unwrap();
+        // Preprocessed, main, interaction and composition trees, plus at least the first FRI layer
+        assert!(report.merkle_decommitments >= 5);
+        assert!(report.hash_operations >= report.n_queries * report.merkle_decommitments);
+    }
+
+    #[test]
+    fn test_coefficient_bytes_at_log_14() {
+        let report = |store_poly_coeffs| {
+            let config = StarkConfig { deterministic: true, store_poly_coeffs, ..StarkConfig::default() };
+            config_report(14, config).unwrap()
+        };
+        let (stored, dropped) = (report(true), report(false));
+
+        assert_eq!((stored.store_poly_coeffs, dropped.store_poly_coeffs), (true, false));
+        // The same trees either way, each PoB column holding 2^14 coefficients
+        assert_eq!(stored.coefficient_bytes, dropped.coefficient_bytes);
+        assert!(stored.coefficient_bytes >= 4 << 14);
+        assert_eq!(stored.proof_size_bytes, dropped.proof_size_bytes);
+    }
+
+    #[test]
+    fn test_invalid_trace_size_is_an_error() {
+        assert!(matches!(
+            config_report(2, StarkConfig::default()),
+            Err(AnalysisError::Prove(_))
+        ));
+    }
+}
@@ -0,0 +1,626 @@
+// Builders for circuit inputs
+// Each setter runs the checks that only depend on its own arguments immediately;
+// `build()` adds the cross-field checks and reports every problem at once
+
+use alloy_primitives::{Address, U256};
+
+use crate::constants::circuit_params::*;
+use crate::constants::{CircuitParams, MAINNET_CHAIN_ID};
+use crate::field::M31;
+use crate::secret::Secret;
+use crate::utils::header::STATE_ROOT_OFFSET;
+use crate::utils::header_chain::verify_descendants;
+use crate::utils::mpt::{layer_issues, LayerError};
+use crate::witness::balances::{resolve_balances, BalanceError};
+use super::proof_of_burn::{
+    check_expected_burn_address, ProofOfBurnError, ProofOfBurnInputs, SecurityLevel,
+};
+use super::spend::{check_expected_coin, check_trace_width, SpendError, SpendInputs};
+use super::validation::{validate_amount, MAX_AMOUNT};
+
+/// A single problem found while building circuit inputs
+#[derive(Debug, thiserror::Error)]
+pub enum InputIssue {
+    #[error("Missing required field: {field}")]
+    Missing { field: &'static str },
+
+    #[error("Invalid {field}: {reason}")]
+    Invalid { field: &'static str, reason: String },
+
+    #[error(transparent)]
+    ProofOfBurn(#[from] ProofOfBurnError),
+
+    #[error(transparent)]
+    Spend(#[from] SpendError),
+
+    #[error(transparent)]
+    Balance(#[from] BalanceError),
+
+    #[error(transparent)]
+    Layer(#[from] LayerError),
+}
+
+/// Every issue collected by a builder, in the order they were found
+#[derive(Debug, Default, thiserror::Error)]
+#[error("{} input validation issue(s): {}", .issues.len(), format_issues(.issues))]
+pub struct InputValidationReport {
+    pub issues: Vec<InputIssue>,
+}
+
+impl InputValidationReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+fn format_issues(issues: &[InputIssue]) -> String {
+    issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+/// Incremental builder for `ProofOfBurnInputs`
+#[derive(Debug, Default)]
+pub struct ProofOfBurnInputsBuilder {
+    burn_key: Option<Secret<M31>>,
+    balances: Option<(U256, Option<U256>)>,
+    reveal_amount: Option<U256>,
+    burn_extra_commitment: M31,
+    layers: Option<Vec<Vec<u8>>>,
+    block_header: Option<Vec<u8>>,
+    header_chain: Vec<Vec<u8>>,
+    chain_id: Option<u64>,
+    valid_until_block: Option<u64>,
+    leaf: Option<(u8, u8)>,
+    proof_extra_commitment: M31,
+    expected_burn_address: Option<Address>,
+    report: InputValidationReport,
+}
+
+impl ProofOfBurnInputsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Secret burn key; canonical, as every `M31` is
+    pub fn burn_key(mut self, key: M31) -> Self {
+        self.burn_key = Some(Secret::new(key));
+        self
+    }
+
+    /// Actual and intended balance of the burn address
+    pub fn balances(mut self, actual: U256, intended: U256) -> Self {
+        if actual > U256::from(MAX_ACTUAL_BALANCE) {
+            self.report.issues.push(
+                ProofOfBurnError::ActualBalanceTooHigh { value: actual, max: MAX_ACTUAL_BALANCE }.into(),
+            );
+        }
+        if intended > U256::from(MAX_INTENDED_BALANCE) {
+            self.report.issues.push(
+                ProofOfBurnError::IntendedBalanceTooHigh { value: intended, max: MAX_INTENDED_BALANCE }
+                    .into(),
+            );
+        }
+        if intended > actual {
+            self.report
+                .issues
+                .push(ProofOfBurnError::IntendedGreaterThanActual { intended, actual }.into());
+        }
+        self.balances = Some((actual, Some(intended)));
+        self
+    }
+
+    /// Actual balance only; the intended balance defaults to min(actual, maxIntendedBalance)
+    pub fn actual_balance(mut self, actual: U256) -> Self {
+        if actual > U256::from(MAX_ACTUAL_BALANCE) {
+            self.report.issues.push(
+                ProofOfBurnError::ActualBalanceTooHigh { value: actual, max: MAX_ACTUAL_BALANCE }.into(),
+            );
+        }
+        self.balances = Some((actual, None));
+        self
+    }
+
+    /// Amount revealed immediately upon proof submission
+    pub fn reveal(mut self, amount: U256) -> Self {
+        if amount > MAX_AMOUNT {
+            self.report.issues.push(InputIssue::Invalid {
+                field: "reveal_amount",
+                reason: format!("{} does not fit in {} bytes", amount, AMOUNT_BYTES),
+            });
+        }
+        self.reveal_amount = Some(amount);
+        self
+    }
+
+    pub fn burn_extra_commitment(mut self, commitment: M31) -> Self {
+        self.burn_extra_commitment = commitment;
+        self
+    }
+
+    pub fn proof_extra_commitment(mut self, commitment: M31) -> Self {
+        self.proof_extra_commitment = commitment;
+        self
+    }
+
+    /// Merkle-Patricia-Trie proof layers, root first; see `mpt::layer_issues` for the shape checks
+    pub fn mpt_layers(mut self, layers: Vec<Vec<u8>>) -> Self {
+        self.report.issues.extend(layer_issues(&layers).into_iter().map(InputIssue::from));
+        self.layers = Some(layers);
+        self
+    }
+
+    /// RLP-encoded block header containing the state root
+    pub fn block_header(mut self, header: Vec<u8>) -> Self {
+        if header.len() > MAX_HEADER_BLOCKS * 136 {
+            self.report.issues.push(
+                ProofOfBurnError::HeaderTooLarge { size: header.len(), max: MAX_HEADER_BLOCKS * 136 }
+                    .into(),
+            );
+        }
+        if header.len() < STATE_ROOT_OFFSET + 32 {
+            self.report.issues.push(
+                ProofOfBurnError::InvalidBlockHeader {
+                    reason: "Header too short to contain state root".to_string(),
+                }
+                .into(),
+            );
+        }
+        self.block_header = Some(header);
+        self
+    }
+
+    /// Headers of the blocks after `block_header`'s, oldest first; the last one's hash is bound
+    pub fn header_chain(mut self, headers: Vec<Vec<u8>>) -> Self {
+        self.header_chain = headers;
+        self
+    }
+
+    /// Chain the burn is submitted on; mainnet unless set
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Last block number at which the proof may be submitted
+    pub fn valid_until_block(mut self, block: u64) -> Self {
+        self.valid_until_block = Some(block);
+        self
+    }
+
+    /// Leaf address-hash nibble count and the PoW security relaxation it is checked against
+    pub fn leaf_nibbles(mut self, num_leaf_address_nibbles: u8, byte_security_relax: u8) -> Self {
+        let required = SecurityLevel::from_relax(byte_security_relax).min_leaf_address_nibbles;
+        if num_leaf_address_nibbles < required {
+            self.report.issues.push(
+                ProofOfBurnError::InsufficientNibbles {
+                    provided: num_leaf_address_nibbles,
+                    required,
+                }
+                .into(),
+            );
+        }
+        self.leaf = Some((num_leaf_address_nibbles, byte_security_relax));
+        self
+    }
+
+    /// Address the burn was sent to; checked against the key, reveal and commitment in `build`
+    pub fn expected_burn_address(mut self, address: Address) -> Self {
+        self.expected_burn_address = Some(address);
+        self
+    }
+
+    /// Issues found so far by the setters
+    pub fn issues(&self) -> &[InputIssue] {
+        &self.report.issues
+    }
+
+    /// Run the cross-field checks and produce the inputs
+    pub fn build(self) -> Result<ProofOfBurnInputs, InputValidationReport> {
+        let mut report = self.report;
+
+        // Setter checks already cover the balance bounds; only report what they could not see
+        let balances_reported = report.issues.iter().any(|issue| {
+            matches!(
+                issue,
+                InputIssue::ProofOfBurn(
+                    ProofOfBurnError::ActualBalanceTooHigh { .. }
+                        | ProofOfBurnError::IntendedBalanceTooHigh { .. }
+                        | ProofOfBurnError::IntendedGreaterThanActual { .. }
+                )
+            )
+        });
+
+        let mut resolved = None;
+        if let (Some((actual, intended)), Some(reveal)) = (self.balances, self.reveal_amount) {
+            match resolve_balances(actual, intended, reveal, &CircuitParams::default()) {
+                Ok(balances) => resolved = Some(balances),
+                Err(e) if !balances_reported => report.issues.push(e.into()),
+                Err(_) => {}
+            }
+        }
+
+        // The chain must descend from the header, so it is checked once both are set
+        if let (Some(block_header), false) = (&self.block_header, self.header_chain.is_empty()) {
+            if let Err(e) = verify_descendants(block_header, &self.header_chain) {
+                report.issues.push(ProofOfBurnError::HeaderChain(e).into());
+            }
+        }
+
+        let missing = [
+            ("burn_key", self.burn_key.is_none()),
+            ("balances", self.balances.is_none()),
+            ("reveal_amount", self.reveal_amount.is_none()),
+            ("layers", self.layers.is_none()),
+            ("block_header", self.block_header.is_none()),
+            ("leaf_nibbles", self.leaf.is_none()),
+        ];
+        for (field, is_missing) in missing {
+            if is_missing {
+                report.issues.push(InputIssue::Missing { field });
+            }
+        }
+
+        match (self.burn_key, resolved, self.reveal_amount, self.layers, self.block_header, self.leaf) {
+            (
+                Some(burn_key),
+                Some((actual_balance, intended_balance)),
+                Some(reveal_amount),
+                Some(layers),
+                Some(block_header),
+                Some((num_leaf_address_nibbles, byte_security_relax)),
+            ) if report.is_empty() => {
+                let inputs = ProofOfBurnInputs {
+                    burn_key,
+                    actual_balance,
+                    intended_balance,
+                    reveal_amount,
+                    burn_extra_commitment: self.burn_extra_commitment,
+                    layers,
+                    block_header,
+                    header_chain: self.header_chain,
+                    chain_id: self.chain_id.unwrap_or(MAINNET_CHAIN_ID),
+                    valid_until_block: self.valid_until_block,
+                    num_leaf_address_nibbles,
+                    byte_security_relax,
+                    proof_extra_commitment: self.proof_extra_commitment,
+                    expected_burn_address: self.expected_burn_address,
+                };
+                // Needs the key, reveal and commitment together, so it cannot run in a setter
+                match check_expected_burn_address(&inputs) {
+                    Ok(()) => Ok(inputs),
+                    Err(e) => {
+                        report.issues.push(e.into());
+                        Err(report)
+                    }
+                }
+            }
+            _ => Err(report),
+        }
+    }
+}
+
+/// Incremental builder for `SpendInputs`
+#[derive(Debug, Default)]
+pub struct SpendInputsBuilder {
+    burn_key: Option<Secret<M31>>,
+    balances: Option<(U256, U256)>,
+    extra_commitment: M31,
+    recipient: Option<Address>,
+    chain_id: Option<u64>,
+    expected_coin: Option<M31>,
+    report: InputValidationReport,
+}
+
+impl SpendInputsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Secret burn key; canonical, as every `M31` is
+    pub fn burn_key(mut self, key: M31) -> Self {
+        self.burn_key = Some(Secret::new(key));
+        self
+    }
+
+    /// Coin balance and the amount withdrawn from it
+    pub fn balances(mut self, balance: U256, withdrawn: U256) -> Self {
+        for (value, what) in [(balance, "balance"), (withdrawn, "withdrawn_balance")] {
+            if let Err(e) = validate_amount(value, what) {
+                self.report.issues.push(SpendError::from(e).into());
+            } else if let Err(e) = check_trace_width(value, what) {
+                self.report.issues.push(e.into());
+            }
+        }
+        if withdrawn > balance {
+            self.report
+                .issues
+                .push(SpendError::InsufficientBalance { balance, withdrawn }.into());
+        }
+        self.balances = Some((balance, withdrawn));
+        self
+    }
+
+    pub fn extra_commitment(mut self, commitment: M31) -> Self {
+        self.extra_commitment = commitment;
+        self
+    }
+
+    /// Address the withdrawal is bound to; without one the proof is front-runnable
+    pub fn recipient(mut self, recipient: Address) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    /// Chain the coin is spent on; mainnet unless set
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Coin the wallet holds; checked against the burn key and balance in `build`
+    pub fn expected_coin(mut self, coin: M31) -> Self {
+        self.expected_coin = Some(coin);
+        self
+    }
+
+    /// Issues found so far by the setters
+    pub fn issues(&self) -> &[InputIssue] {
+        &self.report.issues
+    }
+
+    pub fn build(self) -> Result<SpendInputs, InputValidationReport> {
+        let mut report = self.report;
+
+        if self.burn_key.is_none() {
+            report.issues.push(InputIssue::Missing { field: "burn_key" });
+        }
+        if self.balances.is_none() {
+            report.issues.push(InputIssue::Missing { field: "balances" });
+        }
+
+        match (self.burn_key, self.balances) {
+            (Some(burn_key), Some((balance, withdrawn_balance))) if report.is_empty() => {
+                let inputs = SpendInputs {
+                    burn_key,
+                    balance,
+                    withdrawn_balance,
+                    extra_commitment: self.extra_commitment,
+                    recipient: self.recipient,
+                    chain_id: self.chain_id.unwrap_or(MAINNET_CHAIN_ID),
+                    expected_coin: self.expected_coin,
+                };
+                // Needs both the key and the balance, so it cannot run in a setter
+                match check_expected_coin(&inputs) {
+                    Ok(()) => Ok(inputs),
+                    Err(e) => {
+                        report.issues.push(e.into());
+                        Err(report)
+                    }
+                }
+            }
+            _ => Err(report),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mock_account_proof;
+    use crate::utils::burn_address::compute_burn_address;
+
+    fn eth(n: u64) -> U256 {
+        U256::from(n) * U256::from(1_000_000_000_000_000_000u64)
+    }
+
+    fn valid_pob_builder() -> ProofOfBurnInputsBuilder {
+        ProofOfBurnInputsBuilder::new()
+            .burn_key(M31::from(12345))
+            .balances(eth(1), eth(1))
+            .reveal(eth(1) / U256::from(2))
+            .burn_extra_commitment(M31::from(100))
+            .mpt_layers(mock_account_proof(&[0x11; 32], eth(1), 2).layers)
+            .block_header(vec![0u8; 643])
+            .leaf_nibbles(50, 0)
+            .proof_extra_commitment(M31::from(200))
+    }
+
+    #[test]
+    fn test_builder_debug_redacts_burn_key() {
+        let debug = format!("{:?}", valid_pob_builder());
+        assert!(debug.contains("burn_key: Some(Secret([REDACTED]))"));
+        assert!(!debug.contains("12345"));
+    }
+
+    #[test]
+    fn test_pob_valid_build() {
+        let inputs = valid_pob_builder().build().unwrap();
+        assert_eq!(*inputs.burn_key.expose_secret(), M31::from(12345));
+        assert_eq!(inputs.reveal_amount, eth(1) / U256::from(2));
+        assert_eq!(inputs.layers.len(), 2);
+        assert_eq!(inputs.proof_extra_commitment, M31::from(200));
+    }
+
+    #[test]
+    fn test_pob_build_checks_expected_burn_address() {
+        let funded = compute_burn_address(M31::from(12345), eth(1) / U256::from(2), M31::from(100));
+        let inputs = valid_pob_builder().expected_burn_address(funded).build().unwrap();
+        assert_eq!(inputs.expected_burn_address, Some(funded));
+
+        // Funded for the whole balance, proven with half of it revealed
+        let funded = compute_burn_address(M31::from(12345), eth(1), M31::from(100));
+        let report = valid_pob_builder().expected_burn_address(funded).build().unwrap_err();
+        assert!(matches!(
+            report.issues[..],
+            [InputIssue::ProofOfBurn(ProofOfBurnError::BurnAddressMismatch { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_pob_burn_key_not_canonical() {
+        // A non-canonical key cannot reach the builder: reading rejects it and `new` reduces it
+        assert!(serde_json::from_str::<M31>(&M31::PRIME.to_string()).is_err());
+        let builder = ProofOfBurnInputsBuilder::new().burn_key(M31::new(M31::PRIME));
+        assert!(builder.issues().is_empty());
+    }
+
+    #[test]
+    fn test_pob_balances_setter_failures() {
+        let builder = ProofOfBurnInputsBuilder::new().balances(eth(101), eth(1));
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::ProofOfBurn(ProofOfBurnError::ActualBalanceTooHigh { .. })]
+        ));
+
+        let builder = ProofOfBurnInputsBuilder::new().balances(eth(20), eth(11));
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::ProofOfBurn(ProofOfBurnError::IntendedBalanceTooHigh { .. })]
+        ));
+
+        let builder = ProofOfBurnInputsBuilder::new().balances(eth(1), eth(2));
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::ProofOfBurn(ProofOfBurnError::IntendedGreaterThanActual { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_pob_reveal_too_wide() {
+        let builder = ProofOfBurnInputsBuilder::new().reveal(U256::MAX);
+        assert!(matches!(builder.issues(), [InputIssue::Invalid { field: "reveal_amount", .. }]));
+    }
+
+    #[test]
+    fn test_pob_layers_setter_failures() {
+        let builder = ProofOfBurnInputsBuilder::new().mpt_layers(vec![]);
+        assert!(matches!(builder.issues(), [InputIssue::Layer(LayerError::NoLayers)]));
+
+        let leaf = mock_account_proof(&[0x11; 32], eth(1), 1).layers;
+        let builder = ProofOfBurnInputsBuilder::new().mpt_layers(vec![leaf[0].clone(); MAX_NUM_LAYERS + 1]);
+        assert!(matches!(builder.issues(), [InputIssue::Layer(LayerError::TooManyLayers { .. })]));
+
+        let builder = ProofOfBurnInputsBuilder::new().mpt_layers(vec![vec![0u8; MAX_NODE_LEN + 1], leaf[0].clone()]);
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::Layer(LayerError::LayerTooLarge { index: 0, size, .. })] if *size == MAX_NODE_LEN + 1
+        ));
+
+        // Every bad layer is reported, each with its index
+        let builder = ProofOfBurnInputsBuilder::new().mpt_layers(vec![leaf[0].clone(), vec![], vec![0u8; 80]]);
+        assert!(matches!(
+            builder.issues(),
+            [
+                InputIssue::Layer(LayerError::EmptyLayer { index: 1 }),
+                InputIssue::Layer(LayerError::NotALeaf { index: 2, size: 80, .. }),
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_pob_header_setter_failures() {
+        let builder = ProofOfBurnInputsBuilder::new().block_header(vec![0u8; MAX_HEADER_BLOCKS * 136 + 1]);
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::ProofOfBurn(ProofOfBurnError::HeaderTooLarge { .. })]
+        ));
+
+        let builder = ProofOfBurnInputsBuilder::new().block_header(vec![0u8; 100]);
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::ProofOfBurn(ProofOfBurnError::InvalidBlockHeader { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_pob_leaf_nibbles_too_few() {
+        let builder = ProofOfBurnInputsBuilder::new().leaf_nibbles(40, 0);
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::ProofOfBurn(ProofOfBurnError::InsufficientNibbles { provided: 40, required: 50 })]
+        ));
+
+        // Each relaxed byte lowers the requirement by two nibbles
+        assert!(ProofOfBurnInputsBuilder::new().leaf_nibbles(46, 2).issues().is_empty());
+    }
+
+    #[test]
+    fn test_pob_build_cross_field_reveal() {
+        let report = valid_pob_builder().reveal(eth(2)).build().unwrap_err();
+        assert!(matches!(
+            report.issues.as_slice(),
+            [InputIssue::Balance(BalanceError::RevealAboveIntended { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_pob_intended_defaults_for_dusted_account() {
+        // 50 ETH including dust: intended is capped at maxIntendedBalance (10 ETH)
+        let inputs = valid_pob_builder().actual_balance(eth(50)).build().unwrap();
+        assert_eq!(inputs.actual_balance, eth(50));
+        assert_eq!(inputs.intended_balance, eth(10));
+
+        // Explicit intended below a dusted actual balance is accepted
+        let dust = U256::from(1000);
+        let inputs = valid_pob_builder().balances(eth(1) + dust, eth(1)).build().unwrap();
+        assert_eq!(inputs.actual_balance, eth(1) + dust);
+        assert_eq!(inputs.intended_balance, eth(1));
+    }
+
+    #[test]
+    fn test_pob_build_reports_missing_fields() {
+        let report = ProofOfBurnInputsBuilder::new().burn_key(M31::from(1)).build().unwrap_err();
+        assert_eq!(report.issues.len(), 5);
+        assert!(report.issues.iter().all(|issue| matches!(issue, InputIssue::Missing { .. })));
+    }
+
+    #[test]
+    fn test_spend_valid_build() {
+        let inputs = SpendInputsBuilder::new()
+            .burn_key(M31::from(12345))
+            .balances(U256::from(1000), U256::from(400))
+            .extra_commitment(M31::from(100))
+            .build()
+            .unwrap();
+        assert_eq!(inputs.balance, U256::from(1000));
+        assert_eq!(inputs.withdrawn_balance, U256::from(400));
+        assert_eq!(inputs.extra_commitment, M31::from(100));
+    }
+
+    #[test]
+    fn test_spend_setter_failures() {
+        let builder = SpendInputsBuilder::new().balances(U256::from(400), U256::from(1000));
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::Spend(SpendError::InsufficientBalance { .. })]
+        ));
+
+        let builder = SpendInputsBuilder::new().balances(U256::MAX, U256::from(1));
+        assert!(matches!(builder.issues(), [InputIssue::Spend(SpendError::AmountTooLarge { .. })]));
+
+        let builder = SpendInputsBuilder::new().balances(U256::from(1) << 64, U256::from(1));
+        assert!(matches!(
+            builder.issues(),
+            [InputIssue::Spend(SpendError::AmountBeyondTrace { field: "balance", .. })]
+        ));
+    }
+
+    #[test]
+    fn test_spend_build_reports_missing_fields() {
+        let report = SpendInputsBuilder::new().build().unwrap_err();
+        assert_eq!(report.issues.len(), 2);
+    }
+
+    #[test]
+    fn test_spend_build_checks_expected_coin() {
+        let builder = || {
+            SpendInputsBuilder::new()
+                .burn_key(M31::from(12345))
+                .balances(U256::from(1000), U256::from(400))
+        };
+        let coin = crate::coins::coin(M31::from(12345), U256::from(1000));
+        assert_eq!(builder().expected_coin(coin).build().unwrap().expected_coin, Some(coin));
+
+        let report = builder().expected_coin(coin + M31::from(1)).build().unwrap_err();
+        assert!(matches!(report.issues[..], [InputIssue::Spend(SpendError::CoinMismatch { .. })]));
+    }
+}
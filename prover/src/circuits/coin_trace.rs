@@ -0,0 +1,46 @@
+// Coin and amount trace values shared by the AIRs
+// Every AIR holding a coin lays out the coin's Poseidon2 input state and its 64-bit amounts the
+// same way; the PoB, Spend, Split, Merge and Transfer traces all build them here, next to the
+// range-check gadgets that constrain the chunks (range_check_air.rs)
+
+use stwo_prover::core::fields::m31::BaseField;
+use zeroize::Zeroize;
+
+use crate::circuits::range_check_air::BALANCE_CHUNKS;
+use crate::coins;
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon2_stwo::{
+    basefield_to_custom_m31, custom_m31_to_basefield, poseidon2_permutation, N_STATE,
+};
+use alloy_primitives::U256;
+
+/// Low 64 bits of an amount, or an error naming it if it does not fit the four chunks
+pub(crate) fn chunked_amount(name: &str, value: U256) -> Result<u64, String> {
+    u64::try_from(value).map_err(|_| {
+        format!("{} {} does not fit in {} 16-bit chunks", name, value, BALANCE_CHUNKS)
+    })
+}
+
+/// Low and high 32-bit limbs of a chunked amount, as trace cells
+pub(crate) fn amount_limbs(value: u64) -> [BaseField; 2] {
+    [BaseField::from((value & 0xFFFFFFFF) as u32), BaseField::from((value >> 32) as u32)]
+}
+
+/// Poseidon2 input state of the coin holding `amount`: `coins::coin_preimage`, zero-padded
+pub(crate) fn coin_state(burn_key: BaseField, amount: u64) -> [BaseField; N_STATE] {
+    let mut preimage = coins::coin_preimage(basefield_to_custom_m31(burn_key), U256::from(amount));
+    let mut state = [BaseField::from_u32_unchecked(0); N_STATE];
+    for (cell, value) in state.iter_mut().zip(&preimage) {
+        *cell = custom_m31_to_basefield(*value);
+    }
+    preimage.zeroize();
+    state
+}
+
+/// Poseidon2 output of `coin_state(burn_key, amount)`, whose first element is the coin
+pub(crate) fn coin_permutation(burn_key: BaseField, amount: u64) -> [BaseField; N_STATE] {
+    let mut state = coin_state(burn_key, amount);
+    let output = poseidon2_permutation(state);
+    zeroize_base_fields(&mut state);
+    output
+}
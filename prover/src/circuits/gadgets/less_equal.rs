@@ -0,0 +1,155 @@
+// Less-or-equal gadget
+// Proves `a <= b` for 64-bit values held as little-endian 16-bit chunks. The trace carries the
+// difference `b - a` as chunks and the borrow out of every chunk but the top one; the gadget
+// constrains the borrows to be boolean and the subtraction chunk by chunk. With every chunk of
+// `a`, `b` and the difference range-checked, each chunk equation stays below 2^17 in absolute
+// value, so it holds over the integers, and no borrow out of the top chunk means the difference
+// did not wrap modulo 2^64.
+//
+// Range-checking the chunks is left to the caller: its range-check claims share logup columns
+// in pairs across the whole row (`write_range_check_claims`), so they are added in one batch.
+// Split and Merge, whose difference is an input rather than a witness column, call
+// `constrain_chunked_sub` directly.
+
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_constraint_framework::EvalAtRow;
+
+use crate::circuits::range_check_air::{
+    balance_chunks, chunked_sub_borrows, constrain_chunked_sub, BALANCE_CHUNKS,
+};
+
+/// Constrain `a <= b`, given `diff == b - a` and the borrows out of its chunks
+///
+/// Adds `BALANCE_CHUNKS - 1` constraints, one per borrow being boolean, then one per chunk
+/// equation, least significant first. `a`, `b` and `diff` must be range-checked by the caller.
+pub fn constrain_less_equal<E: EvalAtRow>(
+    eval: &mut E,
+    a: &[E::F; BALANCE_CHUNKS],
+    b: &[E::F; BALANCE_CHUNKS],
+    diff: &[E::F; BALANCE_CHUNKS],
+    borrows: &[E::F; BALANCE_CHUNKS - 1],
+) {
+    constrain_chunked_sub(eval, b, a, diff, borrows);
+}
+
+/// Columns `constrain_less_equal` reads besides `a` and `b`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LessEqualWitness {
+    /// `b - a` as 16-bit chunks
+    pub diff: [BaseField; BALANCE_CHUNKS],
+    /// Borrow out of each chunk of `b - a` but the top one
+    pub borrows: [BaseField; BALANCE_CHUNKS - 1],
+}
+
+/// The difference and borrows proving `a <= b`; `None` when `a > b`
+pub fn less_equal_witness(a: u64, b: u64) -> Option<LessEqualWitness> {
+    let borrows = chunked_sub_borrows(b, a)?;
+    Some(LessEqualWitness { diff: balance_chunks(b - a), borrows })
+}
+
+#[cfg(test)]
+mod tests {
+    use stwo_prover::core::poly::circle::CanonicCoset;
+    use stwo_prover::prover::backend::simd::column::BaseColumn;
+    use stwo_prover::prover::backend::simd::SimdBackend;
+    use stwo_prover::prover::backend::Column;
+    use stwo_prover::prover::poly::circle::CircleEvaluation;
+    use stwo_prover::prover::poly::BitReversedOrder;
+    use stwo_constraint_framework::FrameworkEval;
+
+    use super::*;
+    use crate::circuits::range_check_air::RANGE_CHECK_BITS;
+    use crate::debug::{check_constraints, ConstraintViolation};
+
+    const LOG_SIZE: u32 = 4;
+
+    /// A component holding nothing but one `a <= b` check per row: columns `a`, `b`, `diff`, then
+    /// the borrows
+    struct LessEqualEval;
+
+    impl FrameworkEval for LessEqualEval {
+        fn log_size(&self) -> u32 {
+            LOG_SIZE
+        }
+
+        fn max_constraint_log_degree_bound(&self) -> u32 {
+            LOG_SIZE + 1
+        }
+
+        fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+            let a = std::array::from_fn(|_| eval.next_trace_mask());
+            let b = std::array::from_fn(|_| eval.next_trace_mask());
+            let diff = std::array::from_fn(|_| eval.next_trace_mask());
+            let borrows = std::array::from_fn(|_| eval.next_trace_mask());
+            constrain_less_equal(&mut eval, &a, &b, &diff, &borrows);
+            eval
+        }
+    }
+
+    /// The trace of `LessEqualEval` with every row holding `a`, `b` and `witness`
+    fn trace(
+        a: u64,
+        b: u64,
+        witness: LessEqualWitness,
+    ) -> Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        [&balance_chunks(a)[..], &balance_chunks(b)[..], &witness.diff[..], &witness.borrows[..]]
+            .concat()
+            .into_iter()
+            .map(|value| {
+                let mut column = BaseColumn::zeros(1 << LOG_SIZE);
+                for row in 0..1 << LOG_SIZE {
+                    column.set(row, value);
+                }
+                CircleEvaluation::new(domain, column)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_witness() {
+        assert_eq!(less_equal_witness(6, 5), None);
+        let borrows = |a, b| less_equal_witness(a, b).unwrap().borrows.map(|borrow| borrow.0);
+        assert_eq!(borrows(1, 0x1_0000), [1, 0, 0]);
+        assert_eq!(borrows(1, 1 << 48), [1, 1, 1]);
+        assert_eq!(borrows(u64::MAX, u64::MAX), [0, 0, 0]);
+        assert_eq!(less_equal_witness(1, 1 << 48).unwrap().diff, balance_chunks((1 << 48) - 1));
+    }
+
+    #[test]
+    fn test_passing_limb_sets() {
+        let pairs = [(0, 0), (5, 5), (1, 0x1_0000), (1, 1 << 48), (0xFFFF, 0x1_0000), (0, u64::MAX)];
+        for (a, b) in pairs {
+            let witness = less_equal_witness(a, b).unwrap();
+            check_constraints(&LessEqualEval, &trace(a, b, witness))
+                .unwrap_or_else(|e| panic!("{a} <= {b}: {e:?}"));
+        }
+    }
+
+    #[test]
+    fn test_failing_limb_sets() {
+        // a > b with the difference taken modulo 2^64: every chunk is in range, and only the
+        // borrow out of the top chunk, which has no column, is missing
+        for (a, b) in [(1, 0), (1 << 48, 1), (u64::MAX, 0)] {
+            let borrows = std::array::from_fn(|i| {
+                let mask = (1u64 << (RANGE_CHECK_BITS as usize * (i + 1))) - 1;
+                BaseField::from(((b & mask) < (a & mask)) as u32)
+            });
+            let witness = LessEqualWitness { diff: balance_chunks(b.wrapping_sub(a)), borrows };
+            match check_constraints(&LessEqualEval, &trace(a, b, witness)) {
+                Err(ConstraintViolation::Constraint { constraint, .. }) => {
+                    assert_eq!(constraint, 2 * BALANCE_CHUNKS - 2, "{a} <= {b}")
+                }
+                other => panic!("{a} <= {b}: expected the top chunk equation to fail, got {other:?}"),
+            }
+        }
+
+        // A borrow of 2 would let the chunk equations absorb a wrong difference
+        let mut witness = less_equal_witness(1, 0x1_0000).unwrap();
+        witness.borrows[0] = BaseField::from(2);
+        assert!(matches!(
+            check_constraints(&LessEqualEval, &trace(1, 0x1_0000, witness)),
+            Err(ConstraintViolation::Constraint { constraint: 0, .. })
+        ));
+    }
+}
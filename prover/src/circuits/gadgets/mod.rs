@@ -0,0 +1,8 @@
+// Constraint gadgets shared by the AIRs
+// Each gadget constrains one relation between trace columns the caller has already read, and
+// comes with the witness-side function filling the columns it introduces, so an eval and its
+// trace generator cannot disagree on how the relation is laid out.
+
+pub mod less_equal;
+
+pub use less_equal::{constrain_less_equal, less_equal_witness, LessEqualWitness};
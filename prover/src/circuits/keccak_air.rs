@@ -0,0 +1,637 @@
+// Keccak AIR for Stwo
+// Proves keccak256(block_header) == block_root, one Keccak-f[1600] round per row with every
+// intermediate state bit committed as a column
+// Rounds are chained through a lookup relation instead of by row adjacency, so the trace needs no
+// transition constraints and the rows of a header can sit anywhere in the domain
+
+// Alias for macro compatibility (relation! macro expects 'stwo' crate name)
+extern crate stwo_prover as stwo;
+
+use std::ops::{Add, Mul, Range};
+
+use itertools::Itertools;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use stwo_prover::prover::backend::simd::qm31::PackedSecureField;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{Col, Column};
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{
+    relation, EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator, Relation,
+    RelationEntry,
+};
+
+use crate::circuits::layout::{
+    next_mask_vec, next_masks, BLOCK_ROOT_CHUNKS, KECCAK_LAYOUT, KECCAK_RATE_BITS, KECCAK_ROUNDS,
+    KECCAK_STATE_BITS,
+};
+use crate::constants::circuit_params::MAX_HEADER_BLOCKS;
+use crate::field::ops::simd::add_columns_in_place;
+
+/// Keccak sponge rate in bytes
+pub const KECCAK_RATE_BYTES: usize = KECCAK_RATE_BITS / 8;
+
+const LANE_BITS: usize = 64;
+const RATE_LANES: usize = KECCAK_RATE_BITS / LANE_BITS;
+const ROOT_BITS: usize = 256;
+
+/// Bits per lookup value; 16-bit chunks of boolean columns cannot wrap in M31
+const CHUNK_BITS: usize = 16;
+const STATE_CHUNKS: usize = KECCAK_STATE_BITS / CHUNK_BITS;
+
+/// Width of a round link: block index, round number and the state as 16-bit chunks
+const KECCAK_LINK_SIZE: usize = 2 + STATE_CHUNKS;
+
+/// Links each round's output state to the next round's input state
+relation!(KeccakStateElements, KECCAK_LINK_SIZE);
+/// Carries the final block root, as 16-bit chunks, to the Proof of Burn component
+relation!(BlockRootElements, BLOCK_ROOT_CHUNKS);
+
+/// Iota round constants
+const ROUND_CONSTANTS: [u64; KECCAK_ROUNDS] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000,
+    0x000000000000808B, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
+    0x000000008000808B, 0x800000000000008B, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800A, 0x800000008000000A,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+/// Rho rotation offsets, indexed `[x][y]`
+const RHO_OFFSETS: [[u32; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// Number of 136-byte sponge blocks keccak256 absorbs for a header of `header_len` bytes
+pub fn keccak_block_count(header_len: usize) -> usize {
+    header_len / KECCAK_RATE_BYTES + 1
+}
+
+/// Smallest trace size holding every round of a `header_len`-byte header
+pub fn keccak_log_size(header_len: usize) -> u32 {
+    (keccak_block_count(header_len) * KECCAK_ROUNDS)
+        .next_power_of_two()
+        .ilog2()
+        .max(LOG_N_LANES)
+}
+
+/// A 32-byte root as the little-endian 16-bit chunks the lookups carry
+pub fn block_root_chunks(root: &[u8; 32]) -> [BaseField; BLOCK_ROOT_CHUNKS] {
+    std::array::from_fn(|i| BaseField::from(u16::from_le_bytes([root[2 * i], root[2 * i + 1]]) as u32))
+}
+
+pub type KeccakComponent = FrameworkComponent<KeccakEval>;
+
+/// Keccak constraint evaluator
+#[derive(Clone)]
+pub struct KeccakEval {
+    /// Log2 of the number of rows in the trace
+    pub log_n_rows: u32,
+    pub keccak_state_lookup: KeccakStateElements,
+    pub block_root_lookup: BlockRootElements,
+}
+
+impl FrameworkEval for KeccakEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        // Degree bound: LOG_EXPAND for interpolation (matching stwo examples)
+        self.log_n_rows + 2
+    }
+
+    /// Constrain one Keccak-f round per row and chain the rounds through lookups
+    ///
+    /// Constraints, in order: round selectors, the first/last/root flags, absorption, the zero
+    /// initial state, theta, then chi with iota. Each row consumes `(block, round, state_in)` and
+    /// produces `(block', round + 1, round_out)`; the first row of the sponge consumes nothing and
+    /// the last produces only the root. The header bytes, including their padding, are witness:
+    /// the component proves the root is their Keccak hash, not what the header contains.
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let layout = KECCAK_LAYOUT;
+        let one = E::F::from(BaseField::from(1));
+        let two = E::F::from(BaseField::from(2));
+        let boolean = |x: &E::F| x.clone() * (x.clone() - one.clone());
+        let xor = |a: E::F, b: E::F| a.clone() + b.clone() - two.clone() * a * b;
+
+        let selectors: [E::F; KECCAK_ROUNDS] = next_masks(&mut eval, layout.round_selectors());
+        let [block_index] = next_masks::<E, 1>(&mut eval, layout.block_index());
+        let [first] = next_masks::<E, 1>(&mut eval, layout.first());
+        let [last] = next_masks::<E, 1>(&mut eval, layout.last());
+        let [root_multiplicity] = next_masks::<E, 1>(&mut eval, layout.root_multiplicity());
+        let state_in = next_mask_vec(&mut eval, layout.state_in());
+        let message = next_mask_vec(&mut eval, layout.message());
+        let absorbed = next_mask_vec(&mut eval, layout.absorbed());
+        let theta_parity = next_mask_vec(&mut eval, layout.theta_parity());
+        let theta_out = next_mask_vec(&mut eval, layout.theta_out());
+        let round_out = next_mask_vec(&mut eval, layout.round_out());
+
+        // Selectors are one-hot on round rows and all zero on padding rows
+        let active = weighted_sum(&selectors, |_| 1);
+        for selector in &selectors {
+            eval.add_constraint(boolean(selector));
+        }
+        eval.add_constraint(boolean(&active));
+
+        // `first` and `last` may only mark round 0 and round 23; only `last` may answer root lookups
+        eval.add_constraint(boolean(&first));
+        eval.add_constraint(first.clone() * (one.clone() - selectors[0].clone()));
+        eval.add_constraint(boolean(&last));
+        eval.add_constraint(last.clone() * (one.clone() - selectors[KECCAK_ROUNDS - 1].clone()));
+        eval.add_constraint(root_multiplicity.clone() * (one.clone() - last.clone()));
+
+        // Absorption: message bits only in round 0, xored into the rate
+        for i in 0..KECCAK_RATE_BITS {
+            eval.add_constraint(boolean(&message[i]));
+            eval.add_constraint(message[i].clone() * (one.clone() - selectors[0].clone()));
+            eval.add_constraint(boolean(&absorbed[i]));
+            let sum = state_in[i].clone() + message[i].clone() - absorbed[i].clone();
+            eval.add_constraint(sum.clone() * (sum - two.clone()));
+        }
+
+        // The sponge starts from the zero state; linked states must be bits for the chunks to bind
+        for bit in &state_in {
+            eval.add_constraint(boolean(bit));
+            eval.add_constraint(first.clone() * bit.clone());
+        }
+
+        // Theta: C[x][z] is the parity of column (x, z), A'[x][y][z] = A ^ C[x-1][z] ^ C[x+1][z-1]
+        let pre_theta = |i: usize| if i < KECCAK_RATE_BITS { absorbed[i].clone() } else { state_in[i].clone() };
+        for x in 0..5 {
+            for z in 0..LANE_BITS {
+                let parity = theta_parity[LANE_BITS * x + z].clone();
+                let sum = (0..5)
+                    .map(|y| pre_theta(bit_index(x, y, z)))
+                    .reduce(|a, b| a + b)
+                    .unwrap();
+                let diff = sum - parity.clone();
+                eval.add_constraint(boolean(&parity));
+                eval.add_constraint(
+                    diff.clone() * (diff.clone() - two.clone()) * (diff - E::F::from(BaseField::from(4))),
+                );
+            }
+        }
+        for y in 0..5 {
+            for x in 0..5 {
+                for z in 0..LANE_BITS {
+                    let i = bit_index(x, y, z);
+                    let sum = pre_theta(i)
+                        + theta_parity[LANE_BITS * ((x + 4) % 5) + z].clone()
+                        + theta_parity[LANE_BITS * ((x + 1) % 5) + (z + LANE_BITS - 1) % LANE_BITS].clone();
+                    let diff = sum - theta_out[i].clone();
+                    eval.add_constraint(boolean(&theta_out[i]));
+                    eval.add_constraint(diff.clone() * (diff - two.clone()));
+                }
+            }
+        }
+
+        // Rho and pi only move bits; chi and iota are checked against round_out
+        let b = rho_pi_sources().iter().map(|&source| theta_out[source].clone()).collect_vec();
+        for y in 0..5 {
+            for x in 0..5 {
+                for z in 0..LANE_BITS {
+                    let i = bit_index(x, y, z);
+                    let not_next = one.clone() - b[bit_index((x + 1) % 5, y, z)].clone();
+                    let mut expected = xor(b[i].clone(), not_next * b[bit_index((x + 2) % 5, y, z)].clone());
+                    if i < LANE_BITS {
+                        if let Some(round_constant) = round_constant_bit(&selectors, z) {
+                            expected = xor(expected, round_constant);
+                        }
+                    }
+                    eval.add_constraint(round_out[i].clone() - expected);
+                }
+            }
+        }
+
+        // Round links and the block root
+        let round = weighted_sum(&selectors, |round| round as u32);
+        let next_round = weighted_sum(&selectors[..KECCAK_ROUNDS - 1], |round| round as u32 + 1);
+        let consumed = [vec![block_index.clone(), round], pack_chunks(&state_in)].concat();
+        let produced = [
+            vec![block_index + selectors[KECCAK_ROUNDS - 1].clone(), next_round],
+            pack_chunks(&round_out),
+        ]
+        .concat();
+        eval.add_to_relation(RelationEntry::new(
+            &self.keccak_state_lookup,
+            -E::EF::from(active.clone() - first),
+            &consumed,
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.keccak_state_lookup,
+            E::EF::from(active - last),
+            &produced,
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.block_root_lookup,
+            E::EF::from(root_multiplicity),
+            &pack_chunks(&round_out[..ROOT_BITS]),
+        ));
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Trace column of bit `z` of lane `(x, y)`
+const fn bit_index(x: usize, y: usize, z: usize) -> usize {
+    LANE_BITS * (x + 5 * y) + z
+}
+
+/// For each bit of the rho-pi output, the theta output bit it is taken from
+fn rho_pi_sources() -> [usize; KECCAK_STATE_BITS] {
+    let mut sources = [0; KECCAK_STATE_BITS];
+    for x in 0..5 {
+        for y in 0..5 {
+            let offset = RHO_OFFSETS[x][y] as usize;
+            for z in 0..LANE_BITS {
+                sources[bit_index(y, (2 * x + 3 * y) % 5, z)] = bit_index(x, y, (z + LANE_BITS - offset) % LANE_BITS);
+            }
+        }
+    }
+    sources
+}
+
+/// Bit `z` of the current round's iota constant, or `None` if no round sets it
+fn round_constant_bit<F>(selectors: &[F; KECCAK_ROUNDS], z: usize) -> Option<F>
+where
+    F: Clone + Add<Output = F>,
+{
+    (0..KECCAK_ROUNDS)
+        .filter(|&round| (ROUND_CONSTANTS[round] >> z) & 1 == 1)
+        .map(|round| selectors[round].clone())
+        .reduce(|a, b| a + b)
+}
+
+/// `sum(weight(i) * values[i])`
+fn weighted_sum<F>(values: &[F], weight: impl Fn(usize) -> u32) -> F
+where
+    F: Clone + Add<Output = F> + Mul<BaseField, Output = F>,
+{
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value.clone() * BaseField::from(weight(i)))
+        .reduce(|a, b| a + b)
+        .expect("weighted sum of no values")
+}
+
+/// Pack bits into little-endian 16-bit chunks
+fn pack_chunks<F>(bits: &[F]) -> Vec<F>
+where
+    F: Clone + Add<Output = F> + Mul<BaseField, Output = F>,
+{
+    bits.chunks(CHUNK_BITS)
+        .map(|chunk| weighted_sum(chunk, |k| 1 << k))
+        .collect()
+}
+
+/// Keccak padding (pad10*1 with the 0x01 domain byte) up to whole sponge blocks
+fn pad_header(header: &[u8]) -> Vec<u8> {
+    let mut padded = header.to_vec();
+    padded.push(0x01);
+    padded.resize(padded.len().next_multiple_of(KECCAK_RATE_BYTES), 0);
+    *padded.last_mut().expect("padding is never empty") |= 0x80;
+    padded
+}
+
+/// Intermediate states of one Keccak-f round, as committed in the trace
+struct RoundSteps {
+    parity: [u64; 5],
+    theta_out: [u64; 25],
+    out: [u64; 25],
+}
+
+/// Apply round `round` of Keccak-f[1600] to `state` (lanes indexed `x + 5 * y`)
+fn keccak_round(state: [u64; 25], round: usize) -> RoundSteps {
+    let parity: [u64; 5] = std::array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+    let theta_out: [u64; 25] = std::array::from_fn(|i| {
+        let x = i % 5;
+        state[i] ^ parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1)
+    });
+
+    let mut b = [0u64; 25];
+    for x in 0..5 {
+        for y in 0..5 {
+            b[y + 5 * ((2 * x + 3 * y) % 5)] = theta_out[x + 5 * y].rotate_left(RHO_OFFSETS[x][y]);
+        }
+    }
+
+    let mut out: [u64; 25] = std::array::from_fn(|i| {
+        let (x, y) = (i % 5, i / 5);
+        b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y])
+    });
+    out[0] ^= ROUND_CONSTANTS[round];
+
+    RoundSteps { parity, theta_out, out }
+}
+
+/// Set the bits of `lanes` in the columns of `range` at `row`
+fn set_bits(trace: &mut [BaseColumn], range: Range<usize>, row: usize, lanes: &[u64]) {
+    assert_eq!(range.len(), lanes.len() * LANE_BITS, "trace layout range {:?} filled with {} lanes", range, lanes.len());
+    for (i, col) in range.enumerate() {
+        if (lanes[i / LANE_BITS] >> (i % LANE_BITS)) & 1 == 1 {
+            trace[col].set(row, BaseField::from(1));
+        }
+    }
+}
+
+/// Keccak trace columns needed to build the interaction trace
+pub struct KeccakLookupData {
+    /// Every trace column, in `KECCAK_LAYOUT` order
+    pub columns: Vec<BaseColumn>,
+}
+
+/// Generate the Keccak trace for `keccak256(header)`
+///
+/// Rows `24 * block + round` hold the sponge's rounds; the rest are all-zero padding rows, which
+/// satisfy every constraint and take part in no lookup. The final round answers
+/// `root_multiplicity` block root lookups.
+#[tracing::instrument(level = "debug", skip_all, fields(log_size, header_len = header.len()))]
+pub fn generate_keccak_trace(
+    log_size: u32,
+    header: &[u8],
+    root_multiplicity: u32,
+) -> Result<(
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    KeccakLookupData,
+), String> {
+    let padded = pad_header(header);
+    let n_blocks = padded.len() / KECCAK_RATE_BYTES;
+    if n_blocks > MAX_HEADER_BLOCKS {
+        return Err(format!(
+            "Block header of {} bytes needs {} Keccak blocks, maximum is {}",
+            header.len(),
+            n_blocks,
+            MAX_HEADER_BLOCKS
+        ));
+    }
+    let size = 1usize << log_size;
+    if n_blocks * KECCAK_ROUNDS > size {
+        return Err(format!(
+            "{} Keccak rounds do not fit in 2^{} rows",
+            n_blocks * KECCAK_ROUNDS,
+            log_size
+        ));
+    }
+
+    let layout = KECCAK_LAYOUT;
+    let mut trace = (0..layout.total_columns())
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(size))
+        .collect_vec();
+
+    let mut lanes = [0u64; 25];
+    for (block, bytes) in padded.chunks(KECCAK_RATE_BYTES).enumerate() {
+        let mut message = [0u64; RATE_LANES];
+        for (lane, word) in message.iter_mut().zip(bytes.chunks(8)) {
+            *lane = u64::from_le_bytes(word.try_into().expect("rate is a whole number of lanes"));
+        }
+
+        for round in 0..KECCAK_ROUNDS {
+            let row = block * KECCAK_ROUNDS + round;
+            let is_first = block == 0 && round == 0;
+            let is_last = block == n_blocks - 1 && round == KECCAK_ROUNDS - 1;
+            trace[layout.round_selectors().start + round].set(row, BaseField::from(1));
+            trace[layout.block_index().start].set(row, BaseField::from(block as u32));
+            trace[layout.first().start].set(row, BaseField::from(is_first as u32));
+            trace[layout.last().start].set(row, BaseField::from(is_last as u32));
+            if is_last {
+                trace[layout.root_multiplicity().start].set(row, BaseField::from(root_multiplicity));
+            }
+
+            set_bits(&mut trace, layout.state_in(), row, &lanes);
+            let mut absorbed = lanes;
+            if round == 0 {
+                set_bits(&mut trace, layout.message(), row, &message);
+                for (lane, word) in absorbed.iter_mut().zip(message) {
+                    *lane ^= word;
+                }
+            }
+            set_bits(&mut trace, layout.absorbed(), row, &absorbed[..RATE_LANES]);
+
+            let steps = keccak_round(absorbed, round);
+            set_bits(&mut trace, layout.theta_parity(), row, &steps.parity);
+            set_bits(&mut trace, layout.theta_out(), row, &steps.theta_out);
+            set_bits(&mut trace, layout.round_out(), row, &steps.out);
+            lanes = steps.out;
+        }
+    }
+
+    let lookup_data = KeccakLookupData { columns: trace.clone() };
+
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let trace_evals = trace
+        .into_iter()
+        .map(|col| CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(domain, col))
+        .collect_vec();
+
+    Ok((trace_evals, lookup_data))
+}
+
+/// Generate the Keccak interaction trace
+///
+/// Mirrors `KeccakEval::evaluate`: the consumed and produced round links share one logup column,
+/// the block root gets its own.
+pub fn gen_keccak_interaction_trace(
+    log_size: u32,
+    lookup_data: &KeccakLookupData,
+    keccak_state_lookup: &KeccakStateElements,
+    block_root_lookup: &BlockRootElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let layout = KECCAK_LAYOUT;
+    let columns = &lookup_data.columns;
+    let bits = |range: Range<usize>, vec_row: usize| -> Vec<PackedBaseField> {
+        columns[range].iter().map(|col| col.data[vec_row]).collect()
+    };
+    let column = |range: Range<usize>, vec_row: usize| columns[range.start].data[vec_row];
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+
+    // A row is a round row iff one of its round selectors is set
+    let mut active = BaseColumn::zeros(1 << log_size);
+    for selector in &columns[layout.round_selectors()] {
+        add_columns_in_place(&mut active, selector).expect("trace columns share the trace size");
+    }
+
+    // Consumed + produced round links
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let selectors = bits(layout.round_selectors(), vec_row);
+        let active = active.data[vec_row];
+        let block_index = column(layout.block_index(), vec_row);
+        let consumed = [
+            vec![block_index, weighted_sum(&selectors, |round| round as u32)],
+            pack_chunks(&bits(layout.state_in(), vec_row)),
+        ]
+        .concat();
+        let produced = [
+            vec![
+                block_index + selectors[KECCAK_ROUNDS - 1],
+                weighted_sum(&selectors[..KECCAK_ROUNDS - 1], |round| round as u32 + 1),
+            ],
+            pack_chunks(&bits(layout.round_out(), vec_row)),
+        ]
+        .concat();
+
+        let p0 = -PackedSecureField::from(active - column(layout.first(), vec_row));
+        let p1 = PackedSecureField::from(active - column(layout.last(), vec_row));
+        let q0: PackedSecureField = keccak_state_lookup.combine(&consumed);
+        let q1: PackedSecureField = keccak_state_lookup.combine(&produced);
+        col_gen.write_frac(vec_row, p0 * q1 + p1 * q0, q0 * q1);
+    }
+    col_gen.finalize_col();
+
+    // Block root
+    let root_bits = layout.round_out().start..layout.round_out().start + ROOT_BITS;
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let p = PackedSecureField::from(column(layout.root_multiplicity(), vec_row));
+        let q: PackedSecureField = block_root_lookup.combine(&pack_chunks(&bits(root_bits.clone(), vec_row)));
+        col_gen.write_frac(vec_row, p, q);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Zero;
+
+    use super::*;
+    use crate::debug::{check_constraints, ConstraintViolation};
+    use crate::utils::keccak::keccak256;
+
+    fn header() -> Vec<u8> {
+        (0..643u32).map(|i| (i * 31 + 7) as u8).collect()
+    }
+
+    fn keccak_eval(log_n_rows: u32) -> KeccakEval {
+        KeccakEval {
+            log_n_rows,
+            keccak_state_lookup: KeccakStateElements::dummy(),
+            block_root_lookup: BlockRootElements::dummy(),
+        }
+    }
+
+    fn claimed_sum(lookup_data: &KeccakLookupData, log_size: u32) -> SecureField {
+        let (_, sum) = gen_keccak_interaction_trace(
+            log_size,
+            lookup_data,
+            &KeccakStateElements::dummy(),
+            &BlockRootElements::dummy(),
+        );
+        sum
+    }
+
+    #[test]
+    fn test_round_function_matches_keccak256() {
+        for len in [0, 1, KECCAK_RATE_BYTES - 1, KECCAK_RATE_BYTES, 643] {
+            let input = vec![0xabu8; len];
+            let mut lanes = [0u64; 25];
+            for block in pad_header(&input).chunks(KECCAK_RATE_BYTES) {
+                for (lane, word) in lanes.iter_mut().zip(block.chunks(8)) {
+                    *lane ^= u64::from_le_bytes(word.try_into().unwrap());
+                }
+                for round in 0..KECCAK_ROUNDS {
+                    lanes = keccak_round(lanes, round).out;
+                }
+            }
+            let root: Vec<u8> = lanes[..4].iter().flat_map(|lane| lane.to_le_bytes()).collect();
+            assert_eq!(root, keccak256(&input), "{} byte input", len);
+        }
+    }
+
+    #[test]
+    fn test_keccak_trace_satisfies_constraints() {
+        let header = header();
+        let log_size = keccak_log_size(header.len());
+        let (trace, _) = generate_keccak_trace(log_size, &header, 1).unwrap();
+        assert_eq!(trace.len(), KECCAK_LAYOUT.total_columns());
+        check_constraints(&keccak_eval(log_size), &trace).unwrap();
+    }
+
+    #[test]
+    fn test_last_round_holds_block_root() {
+        let header = header();
+        let log_size = keccak_log_size(header.len());
+        let (trace, _) = generate_keccak_trace(log_size, &header, 1).unwrap();
+
+        let last_row = keccak_block_count(header.len()) * KECCAK_ROUNDS - 1;
+        let root_bits = trace[KECCAK_LAYOUT.round_out()]
+            .iter()
+            .take(ROOT_BITS)
+            .map(|col| col.values.at(last_row))
+            .collect_vec();
+        assert_eq!(pack_chunks(&root_bits), block_root_chunks(&keccak256(&header)));
+    }
+
+    #[test]
+    fn test_tampered_round_output_violates_constraint() {
+        let header = header();
+        let log_size = keccak_log_size(header.len());
+        let (mut trace, _) = generate_keccak_trace(log_size, &header, 1).unwrap();
+
+        let column = KECCAK_LAYOUT.round_out().start + 100;
+        let bit = trace[column].values.at(5);
+        trace[column].values.set(5, BaseField::from(1) - bit);
+        match check_constraints(&keccak_eval(log_size), &trace) {
+            Err(ConstraintViolation::Constraint { row, .. }) => assert_eq!(row, 5),
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_round_links_cancel() {
+        let header = header();
+        let log_size = keccak_log_size(header.len());
+
+        // With no root lookups to answer, the consumed and produced links cancel exactly
+        let (_, lookup_data) = generate_keccak_trace(log_size, &header, 0).unwrap();
+        assert_eq!(claimed_sum(&lookup_data, log_size), SecureField::zero());
+
+        let (_, lookup_data) = generate_keccak_trace(log_size, &header, 1).unwrap();
+        assert_ne!(claimed_sum(&lookup_data, log_size), SecureField::zero());
+    }
+
+    #[test]
+    fn test_skipped_round_breaks_links() {
+        let header = header();
+        let log_size = keccak_log_size(header.len());
+        let (_, mut lookup_data) = generate_keccak_trace(log_size, &header, 0).unwrap();
+
+        // Changing a linked input bit leaves the previous round's output unconsumed
+        let column = KECCAK_LAYOUT.state_in().start + 3;
+        let row = 7;
+        let bit = lookup_data.columns[column].at(row);
+        lookup_data.columns[column].set(row, BaseField::from(1) - bit);
+        assert_ne!(claimed_sum(&lookup_data, log_size), SecureField::zero());
+    }
+
+    #[test]
+    fn test_keccak_log_size() {
+        assert_eq!(keccak_block_count(0), 1);
+        assert_eq!(keccak_block_count(KECCAK_RATE_BYTES), 2);
+        assert_eq!(keccak_log_size(0), 5);
+        assert_eq!(keccak_log_size(643), 7);
+    }
+
+    #[test]
+    fn test_oversized_header_rejected() {
+        let header = vec![0u8; MAX_HEADER_BLOCKS * KECCAK_RATE_BYTES];
+        assert!(generate_keccak_trace(8, &header, 1).is_err());
+        assert!(generate_keccak_trace(4, &[0u8; 10], 1).is_err());
+    }
+}
@@ -0,0 +1,771 @@
+// Trace column layouts
+// Named column ranges shared by trace generation and the AIR evaluators, so a layout change
+// is made in one place instead of recounting offsets in both
+
+use std::ops::Range;
+
+use stwo_constraint_framework::EvalAtRow;
+
+use crate::circuits::range_check_air::{BALANCE_CHUNKS, LIMB_PIECE_COLUMNS};
+use crate::constants::OUTPUT_LIMBS;
+use crate::utils::poseidon::U256_M31_LIMBS;
+
+/// Poseidon2 state width used by the AIR traces
+const N_STATE: usize = 16;
+
+/// 16-bit chunks of a 256-bit block root
+pub const BLOCK_ROOT_CHUNKS: usize = 16;
+
+/// Keccak-f[1600] rounds, state width and sponge rate (136 bytes) in bits
+pub const KECCAK_ROUNDS: usize = 24;
+pub const KECCAK_STATE_BITS: usize = 1600;
+pub const KECCAK_RATE_BITS: usize = 1088;
+
+/// Column ranges of the Proof of Burn trace
+///
+/// | Range                       | Columns | Contents                                     |
+/// |-----------------------------|---------|----------------------------------------------|
+/// | `inputs`                    | 10      | burn_key .. byte_security_relax              |
+/// | `nullifier_initial`         | 16      | Poseidon2 input state                        |
+/// | `nullifier_out`             | 8       | Nullifier limbs                              |
+/// | `remaining_coin_initial`    | 16      |                                              |
+/// | `remaining_coin_out`        | 1       | Remaining coin                               |
+/// | `commitment_initial`        | 16      |                                              |
+/// | `commitment_out`            | 8       | Commitment limbs (public output)             |
+/// | `burn_address_initial`      | 16      | Burn address preimage                        |
+/// | `burn_address_out`          | 8       | Burn address digest (public output)          |
+/// | `block_root`                | 16      | keccak256(header) as 16-bit chunks           |
+/// | `block_root_digest`         | 1       | Poseidon2 of `block_root`, in commitment     |
+/// | `intended_balance_chunks`   | 4       | intended_balance as 16-bit chunks            |
+/// | `reveal_amount_chunks`      | 4       | reveal_amount as 16-bit chunks               |
+/// | `remaining_balance_chunks`  | 4       | intended_balance - reveal_amount, chunked    |
+/// | `actual_balance_chunks`     | 4       | actual_balance as 16-bit chunks              |
+/// | `excess_balance_chunks`     | 4       | actual_balance - intended_balance, chunked   |
+/// | `reveal_amount_limb_pieces` | 8       | Reveal chunks 1 and 3 split at limb edges    |
+/// | `remaining_limb_pieces`     | 8       | Remaining chunks 1 and 3 split at limb edges |
+/// | `remaining_balance_borrows` | 3       | Borrows of the remaining-balance subtraction |
+/// | `excess_balance_borrows`    | 3       | Borrows of the excess-balance subtraction    |
+/// | `enabled`                   | 1       | 1 on witness rows, 0 on padding rows         |
+///
+/// The permutations themselves are proven by the shared Poseidon2 table component; each
+/// `(initial, out)` pair is a lookup into it. `block_root` is looked up from the Keccak component
+/// and every `*_chunks` and limb piece column from the range-check component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PobTraceLayout;
+
+/// The Proof of Burn trace layout
+pub const POB_LAYOUT: PobTraceLayout = PobTraceLayout;
+
+/// Number of input columns at the start of the PoB trace
+pub const POB_INPUT_COLUMNS: usize = 10;
+
+impl PobTraceLayout {
+    pub const fn inputs(&self) -> Range<usize> {
+        0..POB_INPUT_COLUMNS
+    }
+
+    pub const fn nullifier_initial(&self) -> Range<usize> {
+        after(self.inputs(), N_STATE)
+    }
+
+    pub const fn nullifier_out(&self) -> Range<usize> {
+        after(self.nullifier_initial(), OUTPUT_LIMBS)
+    }
+
+    pub const fn remaining_coin_initial(&self) -> Range<usize> {
+        after(self.nullifier_out(), N_STATE)
+    }
+
+    pub const fn remaining_coin_out(&self) -> Range<usize> {
+        after(self.remaining_coin_initial(), 1)
+    }
+
+    pub const fn commitment_initial(&self) -> Range<usize> {
+        after(self.remaining_coin_out(), N_STATE)
+    }
+
+    pub const fn commitment_out(&self) -> Range<usize> {
+        after(self.commitment_initial(), OUTPUT_LIMBS)
+    }
+
+    pub const fn burn_address_initial(&self) -> Range<usize> {
+        after(self.commitment_out(), N_STATE)
+    }
+
+    pub const fn burn_address_out(&self) -> Range<usize> {
+        after(self.burn_address_initial(), OUTPUT_LIMBS)
+    }
+
+    pub const fn block_root(&self) -> Range<usize> {
+        after(self.burn_address_out(), BLOCK_ROOT_CHUNKS)
+    }
+
+    pub const fn block_root_digest(&self) -> Range<usize> {
+        after(self.block_root(), 1)
+    }
+
+    pub const fn intended_balance_chunks(&self) -> Range<usize> {
+        after(self.block_root_digest(), BALANCE_CHUNKS)
+    }
+
+    pub const fn reveal_amount_chunks(&self) -> Range<usize> {
+        after(self.intended_balance_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn remaining_balance_chunks(&self) -> Range<usize> {
+        after(self.reveal_amount_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn actual_balance_chunks(&self) -> Range<usize> {
+        after(self.remaining_balance_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn excess_balance_chunks(&self) -> Range<usize> {
+        after(self.actual_balance_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn reveal_amount_limb_pieces(&self) -> Range<usize> {
+        after(self.excess_balance_chunks(), LIMB_PIECE_COLUMNS)
+    }
+
+    pub const fn remaining_limb_pieces(&self) -> Range<usize> {
+        after(self.reveal_amount_limb_pieces(), LIMB_PIECE_COLUMNS)
+    }
+
+    pub const fn remaining_balance_borrows(&self) -> Range<usize> {
+        after(self.remaining_limb_pieces(), BALANCE_CHUNKS - 1)
+    }
+
+    pub const fn excess_balance_borrows(&self) -> Range<usize> {
+        after(self.remaining_balance_borrows(), BALANCE_CHUNKS - 1)
+    }
+
+    pub const fn enabled(&self) -> Range<usize> {
+        after(self.excess_balance_borrows(), 1)
+    }
+
+    /// The five balance chunk ranges and the limb pieces, claimed in the range-check table
+    pub const fn range_checked(&self) -> Range<usize> {
+        self.intended_balance_chunks().start..self.remaining_limb_pieces().end
+    }
+
+    pub const fn total_columns(&self) -> usize {
+        self.enabled().end
+    }
+
+    /// Every range with its name, in trace order
+    pub fn ranges(&self) -> [(&'static str, Range<usize>); 21] {
+        [
+            ("inputs", self.inputs()),
+            ("nullifier_initial", self.nullifier_initial()),
+            ("nullifier_out", self.nullifier_out()),
+            ("remaining_coin_initial", self.remaining_coin_initial()),
+            ("remaining_coin_out", self.remaining_coin_out()),
+            ("commitment_initial", self.commitment_initial()),
+            ("commitment_out", self.commitment_out()),
+            ("burn_address_initial", self.burn_address_initial()),
+            ("burn_address_out", self.burn_address_out()),
+            ("block_root", self.block_root()),
+            ("block_root_digest", self.block_root_digest()),
+            ("intended_balance_chunks", self.intended_balance_chunks()),
+            ("reveal_amount_chunks", self.reveal_amount_chunks()),
+            ("remaining_balance_chunks", self.remaining_balance_chunks()),
+            ("actual_balance_chunks", self.actual_balance_chunks()),
+            ("excess_balance_chunks", self.excess_balance_chunks()),
+            ("reveal_amount_limb_pieces", self.reveal_amount_limb_pieces()),
+            ("remaining_limb_pieces", self.remaining_limb_pieces()),
+            ("remaining_balance_borrows", self.remaining_balance_borrows()),
+            ("excess_balance_borrows", self.excess_balance_borrows()),
+            ("enabled", self.enabled()),
+        ]
+    }
+}
+
+/// Column ranges of the Spend trace
+///
+/// | Range                       | Columns | Contents                              |
+/// |-----------------------------|---------|---------------------------------------|
+/// | `inputs`                    | 6       | burn_key .. extra_commitment          |
+/// | `coin`                      | 1       | Coin being spent                      |
+/// | `remaining_coin`            | 1       | Coin holding the remaining balance    |
+/// | `commitment_out`            | 8       | Commitment limbs (public output)      |
+/// | `coin_state`                | 2       | coin Poseidon2 output[1..3]           |
+/// | `remaining_coin_state`      | 2       | remaining coin Poseidon2 output[1..3] |
+/// | `withdrawn_balance_limbs`   | 9       | withdrawn_balance as 30-bit limbs     |
+/// | `balance_chunks`            | 4       | balance as 16-bit chunks              |
+/// | `withdrawn_balance_chunks`  | 4       | withdrawn_balance as 16-bit chunks    |
+/// | `remaining_balance_chunks`  | 4       | balance - withdrawn_balance, chunked  |
+/// | `withdrawn_limb_pieces`     | 8       | Chunks 1 and 3 split at limb edges    |
+/// | `remaining_balance_borrows` | 3       | Borrows of the chunked subtraction    |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpendTraceLayout;
+
+/// The Spend trace layout
+pub const SPEND_LAYOUT: SpendTraceLayout = SpendTraceLayout;
+
+impl SpendTraceLayout {
+    pub const fn inputs(&self) -> Range<usize> {
+        0..6
+    }
+
+    pub const fn coin(&self) -> Range<usize> {
+        after(self.inputs(), 1)
+    }
+
+    pub const fn remaining_coin(&self) -> Range<usize> {
+        after(self.coin(), 1)
+    }
+
+    pub const fn commitment_out(&self) -> Range<usize> {
+        after(self.remaining_coin(), OUTPUT_LIMBS)
+    }
+
+    pub const fn coin_state(&self) -> Range<usize> {
+        after(self.commitment_out(), 2)
+    }
+
+    pub const fn remaining_coin_state(&self) -> Range<usize> {
+        after(self.coin_state(), 2)
+    }
+
+    pub const fn withdrawn_balance_limbs(&self) -> Range<usize> {
+        after(self.remaining_coin_state(), U256_M31_LIMBS)
+    }
+
+    pub const fn balance_chunks(&self) -> Range<usize> {
+        after(self.withdrawn_balance_limbs(), BALANCE_CHUNKS)
+    }
+
+    pub const fn withdrawn_balance_chunks(&self) -> Range<usize> {
+        after(self.balance_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn remaining_balance_chunks(&self) -> Range<usize> {
+        after(self.withdrawn_balance_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn withdrawn_limb_pieces(&self) -> Range<usize> {
+        after(self.remaining_balance_chunks(), LIMB_PIECE_COLUMNS)
+    }
+
+    pub const fn remaining_balance_borrows(&self) -> Range<usize> {
+        after(self.withdrawn_limb_pieces(), BALANCE_CHUNKS - 1)
+    }
+
+    /// The three balance chunk ranges and the limb pieces, claimed in the range-check table
+    pub const fn range_checked(&self) -> Range<usize> {
+        self.balance_chunks().start..self.withdrawn_limb_pieces().end
+    }
+
+    pub const fn total_columns(&self) -> usize {
+        self.remaining_balance_borrows().end
+    }
+
+    /// Every range with its name, in trace order
+    pub fn ranges(&self) -> [(&'static str, Range<usize>); 12] {
+        [
+            ("inputs", self.inputs()),
+            ("coin", self.coin()),
+            ("remaining_coin", self.remaining_coin()),
+            ("commitment_out", self.commitment_out()),
+            ("coin_state", self.coin_state()),
+            ("remaining_coin_state", self.remaining_coin_state()),
+            ("withdrawn_balance_limbs", self.withdrawn_balance_limbs()),
+            ("balance_chunks", self.balance_chunks()),
+            ("withdrawn_balance_chunks", self.withdrawn_balance_chunks()),
+            ("remaining_balance_chunks", self.remaining_balance_chunks()),
+            ("withdrawn_limb_pieces", self.withdrawn_limb_pieces()),
+            ("remaining_balance_borrows", self.remaining_balance_borrows()),
+        ]
+    }
+}
+
+/// Column ranges of the Split trace
+///
+/// | Range                   | Columns | Contents                                      |
+/// |-------------------------|---------|-----------------------------------------------|
+/// | `inputs`                | 8       | burn_key .. extra_commitment                  |
+/// | `coin`                  | 1       | Coin being split (public output)              |
+/// | `child_coin_a`          | 1       | Child coin holding amount_a (public output)   |
+/// | `child_coin_b`          | 1       | Child coin holding amount_b (public output)   |
+/// | `commitment_out`        | 8       | Commitment limbs (public output)              |
+/// | `coin_initial`          | 16      | coin Poseidon2 input state                    |
+/// | `child_coin_a_initial`  | 16      | child coin A Poseidon2 input state            |
+/// | `child_coin_b_initial`  | 16      | child coin B Poseidon2 input state            |
+/// | `commitment_initial`    | 16      | commitment Poseidon2 input state              |
+/// | `balance_chunks`        | 4       | balance as 16-bit chunks                      |
+/// | `amount_a_chunks`       | 4       | amount_a as 16-bit chunks                     |
+/// | `amount_b_chunks`       | 4       | amount_b = balance - amount_a, chunked        |
+/// | `balance_limb_pieces`   | 8       | Balance chunks 1 and 3 split at limb edges    |
+/// | `amount_a_limb_pieces`  | 8       | amount_a chunks 1 and 3 split at limb edges   |
+/// | `amount_b_limb_pieces`  | 8       | amount_b chunks 1 and 3 split at limb edges   |
+/// | `conservation_borrows`  | 3       | Borrows of the chunked subtraction            |
+/// | `enabled`               | 1       | 1 on witness rows, 0 on padding rows          |
+///
+/// As in the PoB trace, each `(initial, out)` pair is a lookup into the Poseidon2 table
+/// component, and every chunk and limb piece column is looked up in the range-check component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitTraceLayout;
+
+/// The Split trace layout
+pub const SPLIT_LAYOUT: SplitTraceLayout = SplitTraceLayout;
+
+impl SplitTraceLayout {
+    pub const fn inputs(&self) -> Range<usize> {
+        0..8
+    }
+
+    pub const fn coin(&self) -> Range<usize> {
+        after(self.inputs(), 1)
+    }
+
+    pub const fn child_coin_a(&self) -> Range<usize> {
+        after(self.coin(), 1)
+    }
+
+    pub const fn child_coin_b(&self) -> Range<usize> {
+        after(self.child_coin_a(), 1)
+    }
+
+    pub const fn commitment_out(&self) -> Range<usize> {
+        after(self.child_coin_b(), OUTPUT_LIMBS)
+    }
+
+    pub const fn coin_initial(&self) -> Range<usize> {
+        after(self.commitment_out(), N_STATE)
+    }
+
+    pub const fn child_coin_a_initial(&self) -> Range<usize> {
+        after(self.coin_initial(), N_STATE)
+    }
+
+    pub const fn child_coin_b_initial(&self) -> Range<usize> {
+        after(self.child_coin_a_initial(), N_STATE)
+    }
+
+    pub const fn commitment_initial(&self) -> Range<usize> {
+        after(self.child_coin_b_initial(), N_STATE)
+    }
+
+    pub const fn balance_chunks(&self) -> Range<usize> {
+        after(self.commitment_initial(), BALANCE_CHUNKS)
+    }
+
+    pub const fn amount_a_chunks(&self) -> Range<usize> {
+        after(self.balance_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn amount_b_chunks(&self) -> Range<usize> {
+        after(self.amount_a_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn balance_limb_pieces(&self) -> Range<usize> {
+        after(self.amount_b_chunks(), LIMB_PIECE_COLUMNS)
+    }
+
+    pub const fn amount_a_limb_pieces(&self) -> Range<usize> {
+        after(self.balance_limb_pieces(), LIMB_PIECE_COLUMNS)
+    }
+
+    pub const fn amount_b_limb_pieces(&self) -> Range<usize> {
+        after(self.amount_a_limb_pieces(), LIMB_PIECE_COLUMNS)
+    }
+
+    pub const fn conservation_borrows(&self) -> Range<usize> {
+        after(self.amount_b_limb_pieces(), BALANCE_CHUNKS - 1)
+    }
+
+    pub const fn enabled(&self) -> Range<usize> {
+        after(self.conservation_borrows(), 1)
+    }
+
+    /// The three amount chunk ranges and their limb pieces, claimed in the range-check table
+    pub const fn range_checked(&self) -> Range<usize> {
+        self.balance_chunks().start..self.amount_b_limb_pieces().end
+    }
+
+    /// The public outputs the verifier answers: coin, child coins, then the commitment limbs
+    pub const fn public_output(&self) -> Range<usize> {
+        self.coin().start..self.commitment_out().end
+    }
+
+    pub const fn total_columns(&self) -> usize {
+        self.enabled().end
+    }
+
+    /// Every range with its name, in trace order
+    pub fn ranges(&self) -> [(&'static str, Range<usize>); 17] {
+        [
+            ("inputs", self.inputs()),
+            ("coin", self.coin()),
+            ("child_coin_a", self.child_coin_a()),
+            ("child_coin_b", self.child_coin_b()),
+            ("commitment_out", self.commitment_out()),
+            ("coin_initial", self.coin_initial()),
+            ("child_coin_a_initial", self.child_coin_a_initial()),
+            ("child_coin_b_initial", self.child_coin_b_initial()),
+            ("commitment_initial", self.commitment_initial()),
+            ("balance_chunks", self.balance_chunks()),
+            ("amount_a_chunks", self.amount_a_chunks()),
+            ("amount_b_chunks", self.amount_b_chunks()),
+            ("balance_limb_pieces", self.balance_limb_pieces()),
+            ("amount_a_limb_pieces", self.amount_a_limb_pieces()),
+            ("amount_b_limb_pieces", self.amount_b_limb_pieces()),
+            ("conservation_borrows", self.conservation_borrows()),
+            ("enabled", self.enabled()),
+        ]
+    }
+}
+
+/// Column ranges of the Merge trace
+///
+/// | Range                   | Columns | Contents                                   |
+/// |-------------------------|---------|--------------------------------------------|
+/// | `inputs`                | 8       | burn_key .. extra_commitment               |
+/// | `coin_a`                | 1       | First consumed coin                        |
+/// | `coin_b`                | 1       | Second consumed coin                       |
+/// | `merged_coin`           | 1       | Coin holding the merged balance            |
+/// | `nullifier_a`           | 8       | Nullifier limbs of coin A (public output)  |
+/// | `nullifier_b`           | 8       | Nullifier limbs of coin B (public output)  |
+/// | `commitment_out`        | 8       | Commitment limbs (public output)           |
+/// | `coin_a_state`          | 2       | coin A Poseidon2 output[1..3]              |
+/// | `coin_b_state`          | 2       | coin B Poseidon2 output[1..3]              |
+/// | `merged_coin_state`     | 2       | merged coin Poseidon2 output[1..3]         |
+/// | `balance_a_chunks`      | 4       | balance_a as 16-bit chunks                 |
+/// | `balance_b_chunks`      | 4       | balance_b as 16-bit chunks                 |
+/// | `merged_balance_chunks` | 4       | merged_balance as 16-bit chunks            |
+/// | `merge_borrows`         | 3       | Borrows of merged_balance - balance_a      |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeTraceLayout;
+
+/// The Merge trace layout
+pub const MERGE_LAYOUT: MergeTraceLayout = MergeTraceLayout;
+
+impl MergeTraceLayout {
+    pub const fn inputs(&self) -> Range<usize> {
+        0..8
+    }
+
+    pub const fn coin_a(&self) -> Range<usize> {
+        after(self.inputs(), 1)
+    }
+
+    pub const fn coin_b(&self) -> Range<usize> {
+        after(self.coin_a(), 1)
+    }
+
+    pub const fn merged_coin(&self) -> Range<usize> {
+        after(self.coin_b(), 1)
+    }
+
+    pub const fn nullifier_a(&self) -> Range<usize> {
+        after(self.merged_coin(), OUTPUT_LIMBS)
+    }
+
+    pub const fn nullifier_b(&self) -> Range<usize> {
+        after(self.nullifier_a(), OUTPUT_LIMBS)
+    }
+
+    pub const fn commitment_out(&self) -> Range<usize> {
+        after(self.nullifier_b(), OUTPUT_LIMBS)
+    }
+
+    pub const fn coin_a_state(&self) -> Range<usize> {
+        after(self.commitment_out(), 2)
+    }
+
+    pub const fn coin_b_state(&self) -> Range<usize> {
+        after(self.coin_a_state(), 2)
+    }
+
+    pub const fn merged_coin_state(&self) -> Range<usize> {
+        after(self.coin_b_state(), 2)
+    }
+
+    pub const fn balance_a_chunks(&self) -> Range<usize> {
+        after(self.merged_coin_state(), BALANCE_CHUNKS)
+    }
+
+    pub const fn balance_b_chunks(&self) -> Range<usize> {
+        after(self.balance_a_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn merged_balance_chunks(&self) -> Range<usize> {
+        after(self.balance_b_chunks(), BALANCE_CHUNKS)
+    }
+
+    pub const fn merge_borrows(&self) -> Range<usize> {
+        after(self.merged_balance_chunks(), BALANCE_CHUNKS - 1)
+    }
+
+    pub const fn total_columns(&self) -> usize {
+        self.merge_borrows().end
+    }
+
+    /// Every range with its name, in trace order
+    pub fn ranges(&self) -> [(&'static str, Range<usize>); 14] {
+        [
+            ("inputs", self.inputs()),
+            ("coin_a", self.coin_a()),
+            ("coin_b", self.coin_b()),
+            ("merged_coin", self.merged_coin()),
+            ("nullifier_a", self.nullifier_a()),
+            ("nullifier_b", self.nullifier_b()),
+            ("commitment_out", self.commitment_out()),
+            ("coin_a_state", self.coin_a_state()),
+            ("coin_b_state", self.coin_b_state()),
+            ("merged_coin_state", self.merged_coin_state()),
+            ("balance_a_chunks", self.balance_a_chunks()),
+            ("balance_b_chunks", self.balance_b_chunks()),
+            ("merged_balance_chunks", self.merged_balance_chunks()),
+            ("merge_borrows", self.merge_borrows()),
+        ]
+    }
+}
+
+/// Column ranges of the Transfer trace
+///
+/// | Range                | Columns | Contents                                        |
+/// |----------------------|---------|-------------------------------------------------|
+/// | `inputs`             | 4       | sender_burn_key .. recipient_key_commitment     |
+/// | `new_balance`        | 2       | Balance limbs hashed into the new coin          |
+/// | `old_coin`           | 1       | Sender's coin                                   |
+/// | `new_coin`           | 1       | Recipient's coin                                |
+/// | `old_coin_nullifier` | 8       | Nullifier limbs of the old coin (public output) |
+/// | `commitment_out`     | 8       | Commitment limbs (public output)                |
+/// | `old_coin_state`     | 2       | old coin Poseidon2 output[1..3]                 |
+/// | `new_coin_state`     | 2       | new coin Poseidon2 output[1..3]                 |
+/// | `balance_chunks`     | 4       | balance as 16-bit chunks                        |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferTraceLayout;
+
+/// The Transfer trace layout
+pub const TRANSFER_LAYOUT: TransferTraceLayout = TransferTraceLayout;
+
+impl TransferTraceLayout {
+    pub const fn inputs(&self) -> Range<usize> {
+        0..4
+    }
+
+    pub const fn new_balance(&self) -> Range<usize> {
+        after(self.inputs(), 2)
+    }
+
+    pub const fn old_coin(&self) -> Range<usize> {
+        after(self.new_balance(), 1)
+    }
+
+    pub const fn new_coin(&self) -> Range<usize> {
+        after(self.old_coin(), 1)
+    }
+
+    pub const fn old_coin_nullifier(&self) -> Range<usize> {
+        after(self.new_coin(), OUTPUT_LIMBS)
+    }
+
+    pub const fn commitment_out(&self) -> Range<usize> {
+        after(self.old_coin_nullifier(), OUTPUT_LIMBS)
+    }
+
+    pub const fn old_coin_state(&self) -> Range<usize> {
+        after(self.commitment_out(), 2)
+    }
+
+    pub const fn new_coin_state(&self) -> Range<usize> {
+        after(self.old_coin_state(), 2)
+    }
+
+    pub const fn balance_chunks(&self) -> Range<usize> {
+        after(self.new_coin_state(), BALANCE_CHUNKS)
+    }
+
+    pub const fn total_columns(&self) -> usize {
+        self.balance_chunks().end
+    }
+
+    /// Every range with its name, in trace order
+    pub fn ranges(&self) -> [(&'static str, Range<usize>); 9] {
+        [
+            ("inputs", self.inputs()),
+            ("new_balance", self.new_balance()),
+            ("old_coin", self.old_coin()),
+            ("new_coin", self.new_coin()),
+            ("old_coin_nullifier", self.old_coin_nullifier()),
+            ("commitment_out", self.commitment_out()),
+            ("old_coin_state", self.old_coin_state()),
+            ("new_coin_state", self.new_coin_state()),
+            ("balance_chunks", self.balance_chunks()),
+        ]
+    }
+}
+
+/// Read the trace masks of `range`, which must be the next `N` columns the evaluator consumes
+pub(crate) fn next_masks<E: EvalAtRow, const N: usize>(eval: &mut E, range: Range<usize>) -> [E::F; N] {
+    assert_eq!(range.len(), N, "trace layout range {:?} read as {} masks", range, N);
+    std::array::from_fn(|_| eval.next_trace_mask())
+}
+
+/// Column ranges of the Keccak trace, one Keccak-f round per row
+///
+/// State bits are indexed `64 * (x + 5 * y) + z` for lane `(x, y)` and bit `z`.
+///
+/// | Range              | Columns | Contents                                         |
+/// |--------------------|---------|--------------------------------------------------|
+/// | `round_selectors`  | 24      | One-hot round number, all zero on padding rows   |
+/// | `block_index`      | 1       | Sponge block this round belongs to               |
+/// | `first`            | 1       | Round 0 of the first block                       |
+/// | `last`             | 1       | Round 23 of the last block                       |
+/// | `root_multiplicity`| 1       | Block root lookups answered by this row          |
+/// | `state_in`         | 1600    | State entering the round (before absorption)     |
+/// | `message`          | 1088    | Absorbed block bits, round 0 only                |
+/// | `absorbed`         | 1088    | Rate bits of `state_in` xor `message`            |
+/// | `theta_parity`     | 320     | Column parities C[x][z]                          |
+/// | `theta_out`        | 1600    | State after theta                                |
+/// | `round_out`        | 1600    | State after rho, pi, chi and iota                |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeccakTraceLayout;
+
+/// The Keccak trace layout
+pub const KECCAK_LAYOUT: KeccakTraceLayout = KeccakTraceLayout;
+
+impl KeccakTraceLayout {
+    pub const fn round_selectors(&self) -> Range<usize> {
+        0..KECCAK_ROUNDS
+    }
+
+    pub const fn block_index(&self) -> Range<usize> {
+        after(self.round_selectors(), 1)
+    }
+
+    pub const fn first(&self) -> Range<usize> {
+        after(self.block_index(), 1)
+    }
+
+    pub const fn last(&self) -> Range<usize> {
+        after(self.first(), 1)
+    }
+
+    pub const fn root_multiplicity(&self) -> Range<usize> {
+        after(self.last(), 1)
+    }
+
+    pub const fn state_in(&self) -> Range<usize> {
+        after(self.root_multiplicity(), KECCAK_STATE_BITS)
+    }
+
+    pub const fn message(&self) -> Range<usize> {
+        after(self.state_in(), KECCAK_RATE_BITS)
+    }
+
+    pub const fn absorbed(&self) -> Range<usize> {
+        after(self.message(), KECCAK_RATE_BITS)
+    }
+
+    pub const fn theta_parity(&self) -> Range<usize> {
+        after(self.absorbed(), 5 * 64)
+    }
+
+    pub const fn theta_out(&self) -> Range<usize> {
+        after(self.theta_parity(), KECCAK_STATE_BITS)
+    }
+
+    pub const fn round_out(&self) -> Range<usize> {
+        after(self.theta_out(), KECCAK_STATE_BITS)
+    }
+
+    pub const fn total_columns(&self) -> usize {
+        self.round_out().end
+    }
+
+    /// Every range with its name, in trace order
+    pub fn ranges(&self) -> [(&'static str, Range<usize>); 11] {
+        [
+            ("round_selectors", self.round_selectors()),
+            ("block_index", self.block_index()),
+            ("first", self.first()),
+            ("last", self.last()),
+            ("root_multiplicity", self.root_multiplicity()),
+            ("state_in", self.state_in()),
+            ("message", self.message()),
+            ("absorbed", self.absorbed()),
+            ("theta_parity", self.theta_parity()),
+            ("theta_out", self.theta_out()),
+            ("round_out", self.round_out()),
+        ]
+    }
+}
+
+/// Read the trace masks of `range` when it is too wide for a stack array
+pub(crate) fn next_mask_vec<E: EvalAtRow>(eval: &mut E, range: Range<usize>) -> Vec<E::F> {
+    range.map(|_| eval.next_trace_mask()).collect()
+}
+
+/// The `len` columns directly after `range`
+const fn after(range: Range<usize>, len: usize) -> Range<usize> {
+    range.end..range.end + len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_contiguous(ranges: &[(&'static str, Range<usize>)], total: usize) {
+        let mut next = 0;
+        for (name, range) in ranges {
+            assert_eq!(range.start, next, "{} does not start where the previous range ends", name);
+            assert!(!range.is_empty(), "{} is empty", name);
+            next = range.end;
+        }
+        assert_eq!(next, total);
+        assert_eq!(ranges.iter().map(|(_, range)| range.len()).sum::<usize>(), total);
+    }
+
+    #[test]
+    fn test_pob_layout_contiguous() {
+        assert_contiguous(&POB_LAYOUT.ranges(), POB_LAYOUT.total_columns());
+        assert_eq!(
+            POB_LAYOUT.total_columns(),
+            10 + 3 * (N_STATE + OUTPUT_LIMBS) + (N_STATE + 1) + (BLOCK_ROOT_CHUNKS + 1)
+                + (7 * BALANCE_CHUNKS - 2) + 2 * LIMB_PIECE_COLUMNS + 1
+        );
+    }
+
+    #[test]
+    fn test_keccak_layout_contiguous() {
+        assert_contiguous(&KECCAK_LAYOUT.ranges(), KECCAK_LAYOUT.total_columns());
+        assert_eq!(
+            KECCAK_LAYOUT.total_columns(),
+            KECCAK_ROUNDS + 4 + 3 * KECCAK_STATE_BITS + 2 * KECCAK_RATE_BITS + 5 * 64
+        );
+    }
+
+    #[test]
+    fn test_spend_layout_contiguous() {
+        assert_contiguous(&SPEND_LAYOUT.ranges(), SPEND_LAYOUT.total_columns());
+        assert_eq!(
+            SPEND_LAYOUT.total_columns(),
+            8 + OUTPUT_LIMBS + 4 + U256_M31_LIMBS + (4 * BALANCE_CHUNKS - 1) + LIMB_PIECE_COLUMNS
+        );
+    }
+
+    #[test]
+    fn test_split_layout_contiguous() {
+        assert_contiguous(&SPLIT_LAYOUT.ranges(), SPLIT_LAYOUT.total_columns());
+        assert_eq!(
+            SPLIT_LAYOUT.total_columns(),
+            11 + OUTPUT_LIMBS + 4 * N_STATE + (4 * BALANCE_CHUNKS - 1) + 3 * LIMB_PIECE_COLUMNS + 1
+        );
+    }
+
+    #[test]
+    fn test_merge_layout_contiguous() {
+        assert_contiguous(&MERGE_LAYOUT.ranges(), MERGE_LAYOUT.total_columns());
+        assert_eq!(MERGE_LAYOUT.total_columns(), 11 + 3 * OUTPUT_LIMBS + 6 + (4 * BALANCE_CHUNKS - 1));
+    }
+
+    #[test]
+    fn test_transfer_layout_contiguous() {
+        assert_contiguous(&TRANSFER_LAYOUT.ranges(), TRANSFER_LAYOUT.total_columns());
+        assert_eq!(TRANSFER_LAYOUT.total_columns(), 8 + 2 * OUTPUT_LIMBS + 4 + BALANCE_CHUNKS);
+    }
+}
@@ -0,0 +1,287 @@
+// Merge Circuit - Stwo AIR Implementation
+// Consolidates two coins under the same burn key into one coin holding both balances
+// Both consumed coins are nullified so neither can be spent, split or merged again
+
+use crate::circuits::validation::{validate_amount, AmountError, MAX_AMOUNT};
+use crate::coins;
+use crate::constants::OUTPUT_LIMBS;
+use crate::prover::packaging::limbs_to_u256;
+use crate::utils::poseidon::poseidon_wide;
+use alloy_primitives::U256;
+use crate::field::M31;
+use crate::secret::Secret;
+use serde::{Deserialize, Serialize};
+
+/// Inputs for the Merge circuit
+/// Proves that two coins are consumed into one coin holding the sum of their balances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeInputs {
+    /// Secret burn key shared by both input coins and the merged coin
+    pub burn_key: Secret<M31>,
+
+    /// Balance of the first coin being consumed
+    pub balance_a: U256,
+
+    /// Balance of the second coin being consumed
+    pub balance_b: U256,
+
+    /// Claimed balance of the merged coin, `balance_a + balance_b`
+    pub merged_balance: U256,
+
+    /// Extra commitment (e.g., receiver hint, fees)
+    pub extra_commitment: M31,
+}
+
+/// Public outputs from the Merge circuit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeOutputs {
+    /// Public commitment hash of all values, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
+
+    /// Nullifier of the first consumed coin
+    pub nullifier_a: [M31; OUTPUT_LIMBS],
+
+    /// Nullifier of the second consumed coin
+    pub nullifier_b: [M31; OUTPUT_LIMBS],
+
+    /// The new coin holding `merged_balance`
+    pub merged_coin: M31,
+}
+
+/// Packed as the contracts take them, in hex
+impl std::fmt::Display for MergeOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nullifier_a={:#x}, nullifier_b={:#x}, merged_coin={:#x}, commitment={:#x}",
+            limbs_to_u256(&self.nullifier_a),
+            limbs_to_u256(&self.nullifier_b),
+            self.merged_coin.value(),
+            limbs_to_u256(&self.commitment)
+        )
+    }
+}
+
+/// Merge circuit implementation
+///
+/// Constraints:
+/// 1. mergedBalance == balanceA + balanceB, within maxAmountBytes
+/// 2. coinX = Poseidon2([COIN_PREFIX, burnKey, balanceX])[0] for X in {A, B, merged}
+/// 3. nullifierX = Poseidon2([NULLIFIER_PREFIX, burnKey, coinX])[..8] for X in {A, B}
+/// 4. commitment = PublicCommitment([nullifierA, nullifierB, mergedCoin, extraCommitment])
+pub struct MergeCircuit {
+    inputs: MergeInputs,
+}
+
+impl MergeCircuit {
+    /// Create a new Merge circuit with given inputs
+    pub fn new(inputs: MergeInputs) -> Result<Self, MergeError> {
+        // Validation: amounts fit in maxAmountBytes (31 bytes = 248 bits)
+        validate_amount(inputs.balance_a, "balance_a")?;
+        validate_amount(inputs.balance_b, "balance_b")?;
+        validate_amount(inputs.merged_balance, "merged_balance")?;
+
+        // Validation: the sum must itself fit in maxAmountBytes
+        let sum = inputs
+            .balance_a
+            .checked_add(inputs.balance_b)
+            .filter(|sum| *sum <= MAX_AMOUNT)
+            .ok_or(MergeError::Overflow {
+                balance_a: inputs.balance_a,
+                balance_b: inputs.balance_b,
+            })?;
+
+        // Validation: amounts fit the 64 bits the Merge trace chunks
+        for (field, value) in [
+            ("balance_a", inputs.balance_a),
+            ("balance_b", inputs.balance_b),
+            ("merged_balance", inputs.merged_balance),
+        ] {
+            if value > U256::from(u64::MAX) {
+                return Err(MergeError::AmountTooLarge { field, value });
+            }
+        }
+
+        // Validation: mergedBalance == balanceA + balanceB
+        if sum != inputs.merged_balance {
+            return Err(MergeError::BalanceMismatch {
+                balance_a: inputs.balance_a,
+                balance_b: inputs.balance_b,
+                merged_balance: inputs.merged_balance,
+            });
+        }
+
+        Ok(Self { inputs })
+    }
+
+    /// Compute the circuit outputs
+    pub fn compute_outputs(&self) -> MergeOutputs {
+        let burn_key = *self.inputs.burn_key.expose_secret();
+        let coin_a = coins::coin(burn_key, self.inputs.balance_a);
+        let coin_b = coins::coin(burn_key, self.inputs.balance_b);
+        let merged_coin = coins::coin(burn_key, self.inputs.merged_balance);
+
+        let nullifier_a = coins::coin_nullifier(burn_key, coin_a);
+        let nullifier_b = coins::coin_nullifier(burn_key, coin_b);
+
+        let commitment = compute_merge_commitment(
+            &nullifier_a,
+            &nullifier_b,
+            merged_coin,
+            self.inputs.extra_commitment,
+        );
+
+        MergeOutputs {
+            commitment,
+            nullifier_a,
+            nullifier_b,
+            merged_coin,
+        }
+    }
+}
+
+/// Compute the public commitment for the Merge circuit
+///
+/// The consumed coins enter only through their nullifiers, so a merge reveals no balance.
+fn compute_merge_commitment(
+    nullifier_a: &[M31; OUTPUT_LIMBS],
+    nullifier_b: &[M31; OUTPUT_LIMBS],
+    merged_coin: M31,
+    extra_commitment: M31,
+) -> [M31; OUTPUT_LIMBS] {
+    let mut inputs = Vec::with_capacity(2 * OUTPUT_LIMBS + 2);
+    inputs.extend_from_slice(nullifier_a);
+    inputs.extend_from_slice(nullifier_b);
+    inputs.push(merged_coin);
+    inputs.push(extra_commitment);
+    poseidon_wide(&inputs)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MergeError {
+    #[error("Merged balance mismatch: balance_a={balance_a}, balance_b={balance_b}, merged_balance={merged_balance}")]
+    BalanceMismatch {
+        balance_a: U256,
+        balance_b: U256,
+        merged_balance: U256,
+    },
+
+    #[error("Merged balance overflows the amount limit: balance_a={balance_a}, balance_b={balance_b}")]
+    Overflow {
+        balance_a: U256,
+        balance_b: U256,
+    },
+
+    #[error(transparent)]
+    InvalidAmount(#[from] AmountError),
+
+    #[error("{field} {value} does not fit in the 64 bits the Merge trace holds")]
+    AmountTooLarge {
+        field: &'static str,
+        value: U256,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge_inputs(balance_a: u64, balance_b: u64, merged_balance: u64) -> MergeInputs {
+        MergeInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance_a: U256::from(balance_a),
+            balance_b: U256::from(balance_b),
+            merged_balance: U256::from(merged_balance),
+            extra_commitment: M31::from(100),
+        }
+    }
+
+    #[test]
+    fn test_merge_circuit_valid() {
+        let outputs = MergeCircuit::new(merge_inputs(300, 700, 1000)).unwrap().compute_outputs();
+
+        assert!(outputs.commitment.iter().all(|limb| limb.value() > 0));
+        assert_ne!(outputs.nullifier_a, outputs.nullifier_b);
+        assert_eq!(outputs.merged_coin, coins::coin(M31::from(12345), U256::from(1000)));
+    }
+
+    #[test]
+    fn test_merge_circuit_wrong_merged_balance() {
+        for merged_balance in [999, 1001, 0] {
+            let result = MergeCircuit::new(merge_inputs(300, 700, merged_balance));
+            assert!(matches!(result, Err(MergeError::BalanceMismatch { .. })));
+        }
+    }
+
+    #[test]
+    fn test_merge_circuit_overflow() {
+        let mut inputs = merge_inputs(0, 0, 0);
+        inputs.balance_a = MAX_AMOUNT;
+        inputs.balance_b = U256::from(1);
+        inputs.merged_balance = MAX_AMOUNT;
+        assert!(matches!(MergeCircuit::new(inputs), Err(MergeError::Overflow { .. })));
+    }
+
+    #[test]
+    fn test_merge_circuit_amount_too_large() {
+        let mut inputs = merge_inputs(0, 0, 0);
+        inputs.balance_a = MAX_AMOUNT + U256::from(1);
+        match MergeCircuit::new(inputs) {
+            Err(MergeError::InvalidAmount(e)) => assert_eq!(e.field, "balance_a"),
+            other => panic!("expected InvalidAmount, got {:?}", other.err()),
+        }
+
+        // Below the cap, the trace only holds 64-bit balances
+        let widest = U256::from(u64::MAX);
+        let mut inputs = merge_inputs(u64::MAX - 1, 1, 0);
+        inputs.merged_balance = widest;
+        assert!(MergeCircuit::new(inputs).is_ok());
+
+        let mut inputs = merge_inputs(u64::MAX, 1, 0);
+        inputs.merged_balance = widest + U256::from(1);
+        match MergeCircuit::new(inputs) {
+            Err(MergeError::AmountTooLarge { field, value }) => {
+                assert_eq!((field, value), ("merged_balance", U256::from(1) << 64));
+            }
+            other => panic!("expected AmountTooLarge, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_merge_undoes_split() {
+        use crate::circuits::split::{SplitCircuit, SplitInputs};
+
+        let split = SplitCircuit::new(SplitInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            amount_a: U256::from(300),
+            amount_b: U256::from(700),
+            extra_commitment: M31::from(100),
+        })
+        .unwrap()
+        .compute_outputs();
+        let merge = MergeCircuit::new(merge_inputs(300, 700, 1000)).unwrap().compute_outputs();
+
+        assert_eq!(merge.merged_coin, split.coin);
+        assert_eq!(merge.nullifier_a, coins::coin_nullifier(M31::from(12345), split.child_coin_a));
+    }
+
+    #[test]
+    fn test_merged_coin_is_spendable() {
+        use crate::circuits::spend::{SpendCircuit, SpendInputs};
+        use crate::constants::MAINNET_CHAIN_ID;
+
+        // A Spend checks its coin against the merged one before anything is proven
+        let merge = MergeCircuit::new(merge_inputs(300, 700, 1000)).unwrap().compute_outputs();
+        let spend = SpendCircuit::new(SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: Some(merge.merged_coin),
+        });
+        assert_eq!(spend.unwrap().compute_outputs().coin, merge.merged_coin);
+    }
+}
@@ -0,0 +1,468 @@
+// Merge AIR (Algebraic Intermediate Representation) for Stwo
+// Implements constraints for consolidating two coins into one
+// The merged balance is tied to the inputs by the chunked subtraction
+// merged_balance - balance_a == balance_b, whose 16-bit chunks are looked up in the range-check
+// component (range_check_air.rs); four range-checked chunks also bound the sum below 2^64
+
+use itertools::Itertools;
+use zeroize::Zeroize;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{Col, Column};
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator};
+
+use crate::circuits::coin_trace::{amount_limbs, chunked_amount, coin_permutation};
+use crate::circuits::layout::{next_masks, MERGE_LAYOUT};
+use crate::circuits::merge::MergeInputs;
+use crate::circuits::proof_of_burn_air::NULLIFIER_PREFIX;
+use crate::circuits::range_check_air::{
+    add_range_check_claims, balance_chunks, chunked_sub_borrows, constrain_chunked_sub,
+    constrain_limb_chunks, write_range_check_claims, RangeCheckElements, RangeCheckMultiplicities,
+    BALANCE_CHUNKS,
+};
+use crate::circuits::stats::{circuit_stats, CircuitStats};
+use crate::constants::{M31_PRIME, OUTPUT_LIMBS};
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon2_stwo::{poseidon2_permutation, N_STATE};
+
+/// Helper constant for zero field element
+const ZERO: BaseField = BaseField::from_u32_unchecked(0);
+
+/// Number of columns in the Merge trace
+///
+/// Trace structure (see `MergeTraceLayout` for the named ranges):
+/// 0. burn_key (private witness)
+/// 1-2. balance_a_low, balance_a_high
+/// 3-4. balance_b_low, balance_b_high
+/// 5-6. merged_balance_low, merged_balance_high
+/// 7. extra_commitment
+/// 8-10. coin_a, coin_b, merged_coin (computed)
+/// 11-26. nullifier_a and nullifier_b limbs (public output)
+/// 27-34. commitment limbs (public output)
+/// 35-40. intermediate_poseidon_state
+/// 41-55. chunked merged-balance subtraction
+pub const NUM_MERGE_COLUMNS: usize = MERGE_LAYOUT.total_columns();
+
+/// Human-readable name of every Merge trace column, in trace order (see `NUM_MERGE_COLUMNS`)
+pub fn merge_column_labels() -> Vec<String> {
+    let mut labels: Vec<String> = [
+        "burn_key",
+        "balance_a_low",
+        "balance_a_high",
+        "balance_b_low",
+        "balance_b_high",
+        "merged_balance_low",
+        "merged_balance_high",
+        "extra_commitment",
+    ]
+    .iter()
+    .map(|label| label.to_string())
+    .collect();
+    for (name, range) in &MERGE_LAYOUT.ranges()[1..] {
+        if range.len() == 1 {
+            labels.push(name.to_string());
+        } else {
+            labels.extend((0..range.len()).map(|i| format!("{}_{}", name, i)));
+        }
+    }
+
+    debug_assert_eq!(labels.len(), NUM_MERGE_COLUMNS);
+    labels
+}
+
+pub type MergeComponent = FrameworkComponent<MergeEval>;
+
+/// Merge constraint evaluator
+/// Defines the AIR constraints for merging two coins into one
+#[derive(Clone)]
+pub struct MergeEval {
+    /// Log2 of the number of rows in the trace
+    pub log_n_rows: u32,
+    /// Lookup elements for the balance chunks answered by the range-check component
+    pub range_check_lookup: RangeCheckElements,
+}
+
+impl MergeEval {
+    /// Trace, interaction and constraint counts of this AIR
+    pub fn statistics(&self) -> CircuitStats {
+        circuit_stats(self)
+    }
+}
+
+impl FrameworkEval for MergeEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        // Degree bound: LOG_EXPAND for interpolation (matching stwo examples)
+        self.log_n_rows + 2
+    }
+
+    /// Evaluate constraints at a single row
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let layout = MERGE_LAYOUT;
+
+        // Read trace columns
+        let [
+            burn_key,
+            balance_a_low,
+            balance_a_high,
+            balance_b_low,
+            balance_b_high,
+            merged_balance_low,
+            merged_balance_high,
+            _extra_commitment,
+        ] = next_masks::<E, 8>(&mut eval, layout.inputs());
+        let [_coin_a] = next_masks::<E, 1>(&mut eval, layout.coin_a());
+        let [_coin_b] = next_masks::<E, 1>(&mut eval, layout.coin_b());
+        let [_merged_coin] = next_masks::<E, 1>(&mut eval, layout.merged_coin());
+        let _nullifier_a: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.nullifier_a());
+        let _nullifier_b: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.nullifier_b());
+        let _commitment: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.commitment_out());
+
+        // Intermediate Poseidon state columns
+        let _coin_a_state: [E::F; 2] = next_masks(&mut eval, layout.coin_a_state());
+        let _coin_b_state: [E::F; 2] = next_masks(&mut eval, layout.coin_b_state());
+        let _merged_coin_state: [E::F; 2] = next_masks(&mut eval, layout.merged_coin_state());
+
+        // Chunked merged-balance subtraction
+        let balance_a_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.balance_a_chunks());
+        let balance_b_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.balance_b_chunks());
+        let merged_balance_chunks: [E::F; BALANCE_CHUNKS] =
+            next_masks(&mut eval, layout.merged_balance_chunks());
+        let merge_borrows: [E::F; BALANCE_CHUNKS - 1] = next_masks(&mut eval, layout.merge_borrows());
+
+        // === CONSTRAINT 1: Conservation and overflow ===
+        // merged_balance == balance_a + balance_b, written as merged_balance - balance_a ==
+        // balance_b with no borrow out of the top chunk; merged_balance being four range-checked
+        // chunks means the sum cannot wrap
+        // Constraint indices (used by `debug::check_merge_constraints` reports):
+        //   0      placeholder
+        //   1-6    balance_a, balance_b, merged_balance low/high limbs == their 16-bit chunks
+        //   7-9    merge_borrows are boolean
+        //   10-13  merged_balance_chunks - balance_a_chunks == balance_b_chunks
+        // Every chunk is range-checked on every row; padding rows are all zero, which is in range
+
+        // === CONSTRAINT 2: Coin computations ===
+        // coin_x = Poseidon2([COIN_PREFIX, burn_key, balance_x])[0] for coin_a, coin_b, merged_coin
+        //
+        // In production, these would be full Poseidon AIR constraints
+
+        // === CONSTRAINT 3: Nullifiers ===
+        // nullifier_x = Poseidon2([NULLIFIER_PREFIX, burn_key, coin_x]) for both consumed coins
+
+        // === CONSTRAINT 4: Commitment computation ===
+        // commitment = Hash(nullifier_a, nullifier_b, merged_coin, extra_commitment)
+
+        // === PLACEHOLDER CONSTRAINTS ===
+        // These ensure the trace compiles and columns are used
+        // TODO: Replace with actual cryptographic constraints
+        eval.add_constraint(burn_key.clone() - burn_key.clone());
+
+        let limbs = [
+            (balance_a_low, &balance_a_chunks[..2]),
+            (balance_a_high, &balance_a_chunks[2..]),
+            (balance_b_low, &balance_b_chunks[..2]),
+            (balance_b_high, &balance_b_chunks[2..]),
+            (merged_balance_low, &merged_balance_chunks[..2]),
+            (merged_balance_high, &merged_balance_chunks[2..]),
+        ];
+        for (limb, chunks) in limbs {
+            constrain_limb_chunks(&mut eval, limb, [chunks[0].clone(), chunks[1].clone()]);
+        }
+        constrain_chunked_sub(
+            &mut eval,
+            &merged_balance_chunks,
+            &balance_a_chunks,
+            &balance_b_chunks,
+            &merge_borrows,
+        );
+        add_range_check_claims(
+            &mut eval,
+            &self.range_check_lookup,
+            E::EF::from(E::F::from(BaseField::from(1))),
+            &[balance_a_chunks, balance_b_chunks, merged_balance_chunks].concat(),
+        );
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Nullifier limbs of a consumed coin: Poseidon2 of `[NULLIFIER_PREFIX, burn_key, coin, 0, ...]`
+pub(crate) fn nullifier_limbs(burn_key: BaseField, coin: BaseField) -> [BaseField; OUTPUT_LIMBS] {
+    let mut state = [ZERO; N_STATE];
+    state[..3].copy_from_slice(&[NULLIFIER_PREFIX, burn_key, coin]);
+    let output = poseidon2_permutation(state);
+    zeroize_base_fields(&mut state);
+    std::array::from_fn(|i| output[i])
+}
+
+/// Commitment limbs: a two-block sponge absorbing both nullifiers, then the merged coin and
+/// extra commitment
+fn commitment_limbs(
+    nullifier_a: &[BaseField; OUTPUT_LIMBS],
+    nullifier_b: &[BaseField; OUTPUT_LIMBS],
+    merged_coin: BaseField,
+    extra_commitment: BaseField,
+) -> [BaseField; OUTPUT_LIMBS] {
+    let mut state = [ZERO; N_STATE];
+    state[..OUTPUT_LIMBS].copy_from_slice(nullifier_a);
+    state[OUTPUT_LIMBS..].copy_from_slice(nullifier_b);
+    let mut state = poseidon2_permutation(state);
+    state[0] += merged_coin;
+    state[1] += extra_commitment;
+    let output = poseidon2_permutation(state);
+    std::array::from_fn(|i| output[i])
+}
+
+/// Generate the execution trace for Merge
+///
+/// Fails if the merged balance is not the sum of the two balances, or if any balance does not
+/// fit the chunked columns.
+#[tracing::instrument(level = "debug", skip_all, fields(log_size))]
+pub fn generate_merge_trace(
+    log_size: u32,
+    inputs: &MergeInputs,
+) -> Result<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>, String> {
+    let size = 1 << log_size;
+
+    // Validate M31 values are in correct range before conversion
+    // Error messages never include the burn key itself
+    let mut burn_key_val = inputs.burn_key.expose_secret().value();
+    if burn_key_val >= M31_PRIME {
+        return Err(format!("burn_key exceeds M31 prime {}", M31_PRIME));
+    }
+    let extra_commitment_val = inputs.extra_commitment.value();
+    if extra_commitment_val >= M31_PRIME {
+        return Err(format!(
+            "extra_commitment value {} exceeds M31 prime {}",
+            extra_commitment_val, M31_PRIME
+        ));
+    }
+
+    let balance_a = chunked_amount("balance_a", inputs.balance_a)?;
+    let balance_b = chunked_amount("balance_b", inputs.balance_b)?;
+    let merged_balance = chunked_amount("merged_balance", inputs.merged_balance)?;
+    let borrows = chunked_sub_borrows(merged_balance, balance_a)
+        .filter(|_| merged_balance - balance_a == balance_b)
+        .ok_or_else(|| {
+            format!(
+                "Merged balance mismatch: balance_a={}, balance_b={}, merged_balance={}",
+                balance_a, balance_b, merged_balance
+            )
+        })?;
+
+    let mut trace = (0..NUM_MERGE_COLUMNS)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(size))
+        .collect_vec();
+
+    let mut burn_key_field = BaseField::from_u32_unchecked(burn_key_val);
+    let [balance_a_low, balance_a_high] = amount_limbs(balance_a);
+    let [balance_b_low, balance_b_high] = amount_limbs(balance_b);
+    let [merged_balance_low, merged_balance_high] = amount_limbs(merged_balance);
+    let extra_commitment_field = BaseField::from_u32_unchecked(extra_commitment_val);
+
+    // Compute derived values using Poseidon2
+    let coin_a_output = coin_permutation(burn_key_field, balance_a);
+    let coin_b_output = coin_permutation(burn_key_field, balance_b);
+    let merged_coin_output = coin_permutation(burn_key_field, merged_balance);
+    let coin_a = coin_a_output[0];
+    let coin_b = coin_b_output[0];
+    let merged_coin = merged_coin_output[0];
+
+    let nullifier_a = nullifier_limbs(burn_key_field, coin_a);
+    let nullifier_b = nullifier_limbs(burn_key_field, coin_b);
+    let commitment = commitment_limbs(&nullifier_a, &nullifier_b, merged_coin, extra_commitment_field);
+
+    // Fill the trace
+    // For SIMD backend, we fill vec_index 0 (first SIMD lane)
+    let vec_index = 0;
+    let layout = MERGE_LAYOUT;
+    let mut fill = |range: std::ops::Range<usize>, values: &[BaseField]| {
+        assert_eq!(range.len(), values.len(), "trace layout range {:?} filled with {} values", range, values.len());
+        for (col, &value) in range.zip(values) {
+            trace[col].data[vec_index] = value.into();
+        }
+    };
+
+    fill(layout.inputs(), &[
+        burn_key_field,
+        balance_a_low,
+        balance_a_high,
+        balance_b_low,
+        balance_b_high,
+        merged_balance_low,
+        merged_balance_high,
+        extra_commitment_field,
+    ]);
+    fill(layout.coin_a(), &[coin_a]);
+    fill(layout.coin_b(), &[coin_b]);
+    fill(layout.merged_coin(), &[merged_coin]);
+    fill(layout.nullifier_a(), &nullifier_a);
+    fill(layout.nullifier_b(), &nullifier_b);
+    fill(layout.commitment_out(), &commitment);
+    fill(layout.coin_a_state(), &coin_a_output[1..3]);
+    fill(layout.coin_b_state(), &coin_b_output[1..3]);
+    fill(layout.merged_coin_state(), &merged_coin_output[1..3]);
+    fill(layout.balance_a_chunks(), &balance_chunks(balance_a));
+    fill(layout.balance_b_chunks(), &balance_chunks(balance_b));
+    fill(layout.merged_balance_chunks(), &balance_chunks(merged_balance));
+    fill(layout.merge_borrows(), &borrows);
+
+    // Wipe key-bearing temporaries; the trace columns now hold the only copy
+    burn_key_val.zeroize();
+    zeroize_base_fields(std::slice::from_mut(&mut burn_key_field));
+
+    // Convert to CircleEvaluations
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    Ok(trace
+        .into_iter()
+        .map(|col| CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(domain, col))
+        .collect_vec())
+}
+
+/// Balance chunk columns of a Merge trace, in the order `MergeEval` claims them
+fn range_checked_columns(
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> Vec<&BaseColumn> {
+    let layout = MERGE_LAYOUT;
+    trace[layout.balance_a_chunks().start..layout.merged_balance_chunks().end]
+        .iter()
+        .map(|col| &col.values)
+        .collect()
+}
+
+/// Every row claims its chunks once, padding rows included
+fn claim_multiplicity(log_size: u32) -> BaseColumn {
+    (0..1 << log_size).map(|_| BaseField::from(1)).collect()
+}
+
+/// Range-check table multiplicities answering the claims of a Merge trace
+pub fn merge_range_check_multiplicities(
+    log_size: u32,
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> Result<RangeCheckMultiplicities, String> {
+    let multiplicity = claim_multiplicity(log_size);
+    let mut multiplicities = RangeCheckMultiplicities::default();
+    for column in range_checked_columns(trace) {
+        multiplicities.add_column(column, &multiplicity)?;
+    }
+    Ok(multiplicities)
+}
+
+/// Generate the Merge interaction trace for the balance chunk range checks
+pub fn gen_merge_interaction_trace(
+    log_size: u32,
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    range_check_lookup: &RangeCheckElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    write_range_check_claims(
+        &mut logup_gen,
+        log_size,
+        range_check_lookup,
+        &claim_multiplicity(log_size),
+        &range_checked_columns(trace),
+    );
+    logup_gen.finalize_last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::M31;
+    use crate::secret::Secret;
+    use alloy_primitives::U256;
+
+    fn create_test_inputs() -> MergeInputs {
+        MergeInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance_a: U256::from(300),
+            balance_b: U256::from(700),
+            merged_balance: U256::from(1000),
+            extra_commitment: M31::from(100),
+        }
+    }
+
+    #[test]
+    fn test_generate_merge_trace() {
+        let log_size = 4;
+        let trace = generate_merge_trace(log_size, &create_test_inputs()).unwrap();
+
+        assert_eq!(trace.len(), NUM_MERGE_COLUMNS);
+        for col in &trace {
+            assert_eq!(col.len(), 1 << log_size);
+        }
+        // Nullifiers are per coin
+        let layout = MERGE_LAYOUT;
+        assert_ne!(
+            trace[layout.nullifier_a().start].values.at(0),
+            trace[layout.nullifier_b().start].values.at(0)
+        );
+    }
+
+    #[test]
+    fn test_trace_rejects_wrong_merged_balance() {
+        let mut inputs = create_test_inputs();
+        inputs.merged_balance = U256::from(1001);
+        assert!(generate_merge_trace(4, &inputs).is_err());
+    }
+
+    #[test]
+    fn test_trace_rejects_sum_beyond_chunks() {
+        let mut inputs = create_test_inputs();
+        inputs.balance_a = U256::from(u64::MAX);
+        inputs.balance_b = U256::from(1);
+        inputs.merged_balance = U256::from(u64::MAX) + U256::from(1);
+        assert!(generate_merge_trace(4, &inputs).is_err());
+    }
+
+    #[test]
+    fn test_column_labels_match_layout() {
+        let labels = merge_column_labels();
+        assert_eq!(labels.len(), NUM_MERGE_COLUMNS);
+        assert_eq!(labels.iter().collect::<std::collections::HashSet<_>>().len(), NUM_MERGE_COLUMNS);
+        assert_eq!(labels[MERGE_LAYOUT.merged_coin().start], "merged_coin");
+        assert_eq!(labels[MERGE_LAYOUT.nullifier_b().start], "nullifier_b_0");
+        assert_eq!(labels[NUM_MERGE_COLUMNS - 1], "merge_borrows_2");
+    }
+
+    #[test]
+    fn test_range_check_claims_answered() {
+        use crate::circuits::range_check_air::{gen_range_check_interaction_trace, generate_range_check_trace};
+        use num_traits::Zero;
+
+        let log_size = 4;
+        let trace = generate_merge_trace(log_size, &create_test_inputs()).unwrap();
+        let lookup_elements = RangeCheckElements::dummy();
+        let (_, claims_sum) = gen_merge_interaction_trace(log_size, &trace, &lookup_elements);
+
+        let multiplicities = merge_range_check_multiplicities(log_size, &trace).unwrap();
+        let (_, table) = generate_range_check_trace(&multiplicities);
+        let (_, table_sum) = gen_range_check_interaction_trace(&table, &lookup_elements);
+        assert_eq!(claims_sum + table_sum, SecureField::zero());
+    }
+
+    #[test]
+    fn test_statistics_match_traces() {
+        let log_size = 4;
+        let eval = crate::debug::merge_eval(log_size);
+        let stats = eval.statistics();
+        assert_eq!(stats.n_trace_columns, NUM_MERGE_COLUMNS);
+
+        let trace = generate_merge_trace(log_size, &create_test_inputs()).unwrap();
+        let (interaction_trace, _) = gen_merge_interaction_trace(log_size, &trace, &eval.range_check_lookup);
+        assert_eq!(stats.n_interaction_columns, interaction_trace.len());
+    }
+}
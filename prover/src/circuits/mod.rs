@@ -2,17 +2,76 @@
 
 pub mod proof_of_burn;
 pub mod spend;
+pub mod split;
+pub mod merge;
+pub mod transfer;
+pub mod builder;
+pub mod layout;
+pub mod trace_writer;
+pub mod stats;
+pub mod validation;
 
 // AIR (Algebraic Intermediate Representation) implementations for Stwo
 pub mod proof_of_burn_air;
+pub mod poseidon2_table_air;
+pub mod keccak_air;
+pub mod range_check_air;
+pub mod coin_trace;
+pub mod gadgets;
+pub mod preprocessed;
 pub mod spend_air;
+pub mod split_air;
+pub mod merge_air;
+pub mod transfer_air;
 
 // Re-export main types
-pub use proof_of_burn::{ProofOfBurnCircuit, ProofOfBurnInputs, ProofOfBurnOutputs, ProofOfBurnError};
+pub use proof_of_burn::{ProofOfBurnCircuit, ProofOfBurnInputs, ProofOfBurnOutputs, ProofOfBurnError, SecurityLevel};
 pub use spend::{SpendCircuit, SpendInputs, SpendOutputs, SpendError};
+pub use split::{SplitCircuit, SplitInputs, SplitOutputs, SplitError};
+pub use merge::{MergeCircuit, MergeInputs, MergeOutputs, MergeError};
+pub use transfer::{TransferCircuit, TransferInputs, TransferOutputs, TransferError};
+pub use builder::{InputIssue, InputValidationReport, ProofOfBurnInputsBuilder, SpendInputsBuilder};
 pub use proof_of_burn_air::{
     ProofOfBurnComponent, ProofOfBurnEval, LookupData, NullifierElements, RemainingCoinElements,
-    CommitmentElements, generate_pob_trace, gen_interaction_trace,
+    CommitmentElements, BlockRootDigestElements, BurnAddressElements, generate_pob_trace, gen_interaction_trace,
+    pob_column_labels, PobLookupColumns, PobWitness,
+};
+pub use stats::{circuit_stats, CircuitStats};
+pub use validation::{validate_amount, AmountError};
+pub use preprocessed::IsFirst;
+pub use layout::{
+    KeccakTraceLayout, MergeTraceLayout, PobTraceLayout, SpendTraceLayout, SplitTraceLayout,
+    TransferTraceLayout, KECCAK_LAYOUT, MERGE_LAYOUT, POB_LAYOUT, SPEND_LAYOUT, SPLIT_LAYOUT,
+    TRANSFER_LAYOUT,
+};
+pub use trace_writer::{TraceLayout, TraceWriter, TraceWriterError};
+pub use poseidon2_table_air::{
+    Poseidon2Relation, Poseidon2TableComponent, Poseidon2TableEntry, Poseidon2TableEval,
+    generate_poseidon2_table_trace, generate_poseidon2_table_trace_cached, gen_poseidon2_table_interaction_trace,
+    poseidon2_table_log_size,
+};
+pub use keccak_air::{
+    BlockRootElements, KeccakComponent, KeccakEval, KeccakLookupData, KeccakStateElements,
+    generate_keccak_trace, gen_keccak_interaction_trace, keccak_log_size,
+};
+pub use range_check_air::{
+    RangeCheckComponent, RangeCheckElements, RangeCheckEval, RangeCheckMultiplicities,
+    generate_range_check_trace, gen_range_check_interaction_trace, gen_range_check_preprocessed_trace,
+};
+pub use spend_air::{
+    SpendComponent, SpendEval, generate_spend_trace, gen_spend_interaction_trace, spend_column_labels,
+    spend_range_check_multiplicities, SpendLookupData,
+};
+pub use split_air::{
+    SplitComponent, SplitEval, SplitPublicOutputElements, generate_split_trace,
+    gen_split_interaction_trace, split_column_labels, split_outputs_from_trace,
+    split_range_check_multiplicities,
+};
+pub use merge_air::{
+    MergeComponent, MergeEval, generate_merge_trace, gen_merge_interaction_trace, merge_column_labels,
+    merge_range_check_multiplicities,
+};
+pub use transfer_air::{
+    TransferComponent, TransferEval, generate_transfer_trace, gen_transfer_interaction_trace,
+    transfer_column_labels, transfer_range_check_multiplicities,
 };
-pub use spend_air::{SpendComponent, SpendEval, generate_spend_trace};
-
@@ -0,0 +1,569 @@
+// Poseidon2 table AIR for Stwo
+// Proves a table of Poseidon2 permutations once, so circuits that hash only emit lookup claims
+// for their (input, output) pairs instead of re-proving each permutation inline
+// Architecture follows the stwo Poseidon example: one permutation per row, with every S-box
+// output committed as a column
+
+use itertools::Itertools;
+use num_traits::Zero;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES};
+use stwo_prover::prover::backend::simd::qm31::PackedSecureField;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{Col, Column};
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{
+    EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator, Relation, RelationEntry,
+};
+
+use crate::circuits::proof_of_burn_air::{
+    BlockRootDigestElements, BurnAddressElements, CommitmentElements, NullifierElements,
+    RemainingCoinElements,
+};
+use crate::constants::OUTPUT_LIMBS;
+use crate::utils::poseidon2_stwo::{
+    apply_external_round_matrix, apply_internal_round_matrix, pow5, PermutationCache,
+    EXTERNAL_ROUND_CONSTS, INTERNAL_ROUND_CONSTS, N_HALF_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_STATE,
+};
+
+/// Columns holding the permutation: input state, every full round's S-box outputs and every
+/// partial round's single S-box output. The last full round's columns are the output state.
+const N_PERMUTATION_COLUMNS: usize =
+    N_STATE + 2 * N_HALF_FULL_ROUNDS * N_STATE + N_PARTIAL_ROUNDS;
+
+/// Number of columns in the Poseidon2 table trace
+///
+/// Trace structure:
+/// - permutation columns (see `N_PERMUTATION_COLUMNS`)
+/// - one multiplicity column per relation: nullifier, remaining coin, commitment, block root
+///   digest, burn address
+pub const NUM_POSEIDON2_TABLE_COLUMNS: usize = N_PERMUTATION_COLUMNS + N_RELATIONS;
+
+/// Number of `Poseidon2Relation`s, one multiplicity column each
+const N_RELATIONS: usize = 5;
+
+/// Which lookup relation a table row answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Poseidon2Relation {
+    Nullifier,
+    RemainingCoin,
+    Commitment,
+    BlockRootDigest,
+    BurnAddress,
+}
+
+impl Poseidon2Relation {
+    /// Output limbs appended to the input state in this relation's lookups
+    pub fn output_limbs(self) -> usize {
+        match self {
+            Poseidon2Relation::Nullifier | Poseidon2Relation::Commitment | Poseidon2Relation::BurnAddress => {
+                OUTPUT_LIMBS
+            }
+            Poseidon2Relation::RemainingCoin | Poseidon2Relation::BlockRootDigest => 1,
+        }
+    }
+
+    fn multiplicity_column(self) -> usize {
+        N_PERMUTATION_COLUMNS + self as usize
+    }
+}
+
+/// A permutation the table proves, and how many lookups of `relation` it answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poseidon2TableEntry {
+    pub relation: Poseidon2Relation,
+    pub input: [BaseField; N_STATE],
+    pub multiplicity: u32,
+}
+
+/// Smallest table size holding `n_entries` rows (at least one SIMD vector)
+pub fn poseidon2_table_log_size(n_entries: usize) -> u32 {
+    n_entries.next_power_of_two().ilog2().max(LOG_N_LANES)
+}
+
+pub type Poseidon2TableComponent = FrameworkComponent<Poseidon2TableEval>;
+
+/// Poseidon2 table constraint evaluator
+#[derive(Clone)]
+pub struct Poseidon2TableEval {
+    /// Log2 of the number of rows in the table
+    pub log_n_rows: u32,
+    pub nullifier_lookup: NullifierElements,
+    pub remaining_coin_lookup: RemainingCoinElements,
+    pub commitment_lookup: CommitmentElements,
+    pub block_root_digest_lookup: BlockRootDigestElements,
+    pub burn_address_lookup: BurnAddressElements,
+}
+
+impl FrameworkEval for Poseidon2TableEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        // Degree bound: LOG_EXPAND for interpolation (matching stwo examples)
+        self.log_n_rows + 2
+    }
+
+    /// Constrain one permutation per row and answer its lookups
+    ///
+    /// Constraint indices (used by `debug::check_constraints` reports): one per S-box column, in
+    /// trace order, starting at 0 for the first full round.
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let input: [E::F; N_STATE] = std::array::from_fn(|_| eval.next_trace_mask());
+        let mut state = input.clone();
+
+        // Full rounds (first half)
+        for round in 0..N_HALF_FULL_ROUNDS {
+            full_round(&mut eval, &mut state, round);
+        }
+
+        // Partial rounds
+        for round in 0..N_PARTIAL_ROUNDS {
+            state[0] = state[0].clone() + INTERNAL_ROUND_CONSTS[round];
+            apply_internal_round_matrix(&mut state);
+            state[0] = sbox_column(&mut eval, state[0].clone());
+        }
+
+        // Full rounds (second half)
+        for round in 0..N_HALF_FULL_ROUNDS {
+            full_round(&mut eval, &mut state, round + N_HALF_FULL_ROUNDS);
+        }
+
+        // Answer lookups: the table side of each relation carries negative multiplicity
+        let [nullifier_mult, remaining_coin_mult, commitment_mult, block_root_digest_mult, burn_address_mult] =
+            std::array::from_fn(|_| eval.next_trace_mask());
+        let lookup_values = |relation: Poseidon2Relation| {
+            [&input[..], &state[..relation.output_limbs()]].concat()
+        };
+        eval.add_to_relation(RelationEntry::new(
+            &self.nullifier_lookup,
+            -E::EF::from(nullifier_mult),
+            &lookup_values(Poseidon2Relation::Nullifier),
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.remaining_coin_lookup,
+            -E::EF::from(remaining_coin_mult),
+            &lookup_values(Poseidon2Relation::RemainingCoin),
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.commitment_lookup,
+            -E::EF::from(commitment_mult),
+            &lookup_values(Poseidon2Relation::Commitment),
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.block_root_digest_lookup,
+            -E::EF::from(block_root_digest_mult),
+            &lookup_values(Poseidon2Relation::BlockRootDigest),
+        ));
+        eval.add_to_relation(RelationEntry::new(
+            &self.burn_address_lookup,
+            -E::EF::from(burn_address_mult),
+            &lookup_values(Poseidon2Relation::BurnAddress),
+        ));
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Add round constants, apply the external matrix and commit each S-box output
+fn full_round<E: EvalAtRow>(eval: &mut E, state: &mut [E::F; N_STATE], round: usize) {
+    for (s, round_const) in state.iter_mut().zip(EXTERNAL_ROUND_CONSTS[round]) {
+        *s = s.clone() + round_const;
+    }
+    apply_external_round_matrix(state);
+    for s in state.iter_mut() {
+        *s = sbox_column(eval, s.clone());
+    }
+}
+
+/// Read the next column and constrain it to `x^5`
+fn sbox_column<E: EvalAtRow>(eval: &mut E, x: E::F) -> E::F {
+    let x2 = x.clone() * x.clone();
+    let x5 = x2.clone() * x2 * x;
+    let column = eval.next_trace_mask();
+    eval.add_constraint(x5 - column.clone());
+    column
+}
+
+/// Every permutation column for `input`, in trace order
+fn permutation_columns(input: [BaseField; N_STATE]) -> Vec<BaseField> {
+    let mut columns = Vec::with_capacity(N_PERMUTATION_COLUMNS);
+    columns.extend_from_slice(&input);
+    let mut state = input;
+
+    let full_round_columns = |state: &mut [BaseField; N_STATE], round: usize, columns: &mut Vec<BaseField>| {
+        for i in 0..N_STATE {
+            state[i] += EXTERNAL_ROUND_CONSTS[round][i];
+        }
+        apply_external_round_matrix(state);
+        for s in state.iter_mut() {
+            *s = pow5(*s);
+        }
+        columns.extend_from_slice(state);
+    };
+
+    for round in 0..N_HALF_FULL_ROUNDS {
+        full_round_columns(&mut state, round, &mut columns);
+    }
+    for round in 0..N_PARTIAL_ROUNDS {
+        state[0] += INTERNAL_ROUND_CONSTS[round];
+        apply_internal_round_matrix(&mut state);
+        state[0] = pow5(state[0]);
+        columns.push(state[0]);
+    }
+    for round in 0..N_HALF_FULL_ROUNDS {
+        full_round_columns(&mut state, round + N_HALF_FULL_ROUNDS, &mut columns);
+    }
+
+    debug_assert_eq!(columns.len(), N_PERMUTATION_COLUMNS);
+    columns
+}
+
+/// Table columns needed to build the interaction trace
+pub struct Poseidon2TableLookupData {
+    pub input: [BaseColumn; N_STATE],
+    pub output: [BaseColumn; OUTPUT_LIMBS],
+    /// Nullifier, remaining coin, commitment, block root digest and burn address multiplicities
+    pub multiplicities: [BaseColumn; N_RELATIONS],
+}
+
+/// Generate the table trace: one row per entry, padding rows hold the permutation of the zero
+/// state with zero multiplicity so they satisfy the round constraints without answering lookups
+#[tracing::instrument(level = "debug", skip_all, fields(log_size, entries = entries.len()))]
+pub fn generate_poseidon2_table_trace(
+    log_size: u32,
+    entries: &[Poseidon2TableEntry],
+) -> Result<(
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    Poseidon2TableLookupData,
+), String> {
+    generate_table_trace(log_size, entries, None)
+}
+
+/// `generate_poseidon2_table_trace`, tracing each distinct entry input once while `cache` holds it
+///
+/// The trace is identical to the uncached one; `cache.stats()` counts what it saved.
+#[tracing::instrument(level = "debug", skip_all, fields(log_size, entries = entries.len()))]
+pub fn generate_poseidon2_table_trace_cached(
+    log_size: u32,
+    entries: &[Poseidon2TableEntry],
+    cache: &mut PermutationCache,
+) -> Result<(
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    Poseidon2TableLookupData,
+), String> {
+    generate_table_trace(log_size, entries, Some(cache))
+}
+
+fn generate_table_trace(
+    log_size: u32,
+    entries: &[Poseidon2TableEntry],
+    mut cache: Option<&mut PermutationCache>,
+) -> Result<(
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    Poseidon2TableLookupData,
+), String> {
+    let size = 1usize << log_size;
+    if entries.len() > size {
+        return Err(format!(
+            "{} Poseidon2 table entries do not fit in 2^{} rows",
+            entries.len(),
+            log_size
+        ));
+    }
+
+    let mut trace = (0..NUM_POSEIDON2_TABLE_COLUMNS)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(size))
+        .collect_vec();
+
+    let padding = permutation_columns([BaseField::zero(); N_STATE]);
+    for row in 0..size {
+        let entry = entries.get(row);
+        let traced;
+        let columns: &[BaseField] = match (entry, cache.as_deref_mut()) {
+            (Some(entry), Some(cache)) => cache.get_or_trace(entry.input, permutation_columns),
+            (Some(entry), None) => {
+                traced = permutation_columns(entry.input);
+                &traced
+            }
+            (None, _) => &padding,
+        };
+        for (col, &value) in columns.iter().enumerate() {
+            trace[col].set(row, value);
+        }
+        if let Some(entry) = entry {
+            trace[entry.relation.multiplicity_column()].set(row, BaseField::from(entry.multiplicity));
+        }
+    }
+
+    let output_start = N_PERMUTATION_COLUMNS - N_STATE;
+    let lookup_data = Poseidon2TableLookupData {
+        input: std::array::from_fn(|i| trace[i].clone()),
+        output: std::array::from_fn(|i| trace[output_start + i].clone()),
+        multiplicities: std::array::from_fn(|i| trace[N_PERMUTATION_COLUMNS + i].clone()),
+    };
+
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let trace_evals = trace
+        .into_iter()
+        .map(|col| CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(domain, col))
+        .collect_vec();
+
+    Ok((trace_evals, lookup_data))
+}
+
+/// Generate the table's interaction trace
+///
+/// Mirrors `Poseidon2TableEval::evaluate`: nullifier and remaining coin share one logup column,
+/// the commitment and block root digest another, and the burn address has the last to itself.
+pub fn gen_poseidon2_table_interaction_trace(
+    log_size: u32,
+    lookup_data: &Poseidon2TableLookupData,
+    nullifier_lookup: &NullifierElements,
+    remaining_coin_lookup: &RemainingCoinElements,
+    commitment_lookup: &CommitmentElements,
+    block_root_digest_lookup: &BlockRootDigestElements,
+    burn_address_lookup: &BurnAddressElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    let values = |relation: Poseidon2Relation, vec_row: usize| -> Vec<PackedBaseField> {
+        lookup_data
+            .input
+            .iter()
+            .chain(&lookup_data.output[..relation.output_limbs()])
+            .map(|col| col.data[vec_row])
+            .collect()
+    };
+    let multiplicity = |relation: Poseidon2Relation, vec_row: usize| {
+        -PackedSecureField::from(lookup_data.multiplicities[relation as usize].data[vec_row])
+    };
+
+    // Nullifier + remaining coin
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let p0 = multiplicity(Poseidon2Relation::Nullifier, vec_row);
+        let p1 = multiplicity(Poseidon2Relation::RemainingCoin, vec_row);
+        let q0: PackedSecureField = nullifier_lookup.combine(&values(Poseidon2Relation::Nullifier, vec_row));
+        let q1: PackedSecureField =
+            remaining_coin_lookup.combine(&values(Poseidon2Relation::RemainingCoin, vec_row));
+        col_gen.write_frac(vec_row, p0 * q1 + p1 * q0, q0 * q1);
+    }
+    col_gen.finalize_col();
+
+    // Commitment + block root digest
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let p0 = multiplicity(Poseidon2Relation::Commitment, vec_row);
+        let p1 = multiplicity(Poseidon2Relation::BlockRootDigest, vec_row);
+        let q0: PackedSecureField = commitment_lookup.combine(&values(Poseidon2Relation::Commitment, vec_row));
+        let q1: PackedSecureField =
+            block_root_digest_lookup.combine(&values(Poseidon2Relation::BlockRootDigest, vec_row));
+        col_gen.write_frac(vec_row, p0 * q1 + p1 * q0, q0 * q1);
+    }
+    col_gen.finalize_col();
+
+    // Burn address
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let p = multiplicity(Poseidon2Relation::BurnAddress, vec_row);
+        let q: PackedSecureField = burn_address_lookup.combine(&values(Poseidon2Relation::BurnAddress, vec_row));
+        col_gen.write_frac(vec_row, p, q);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::circuits::keccak_air::{
+        gen_keccak_interaction_trace, generate_keccak_trace, keccak_log_size, BlockRootElements,
+        KeccakStateElements,
+    };
+    use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+    use crate::circuits::proof_of_burn_air::{gen_interaction_trace, generate_pob_trace, PublicOutputElements};
+    use crate::circuits::range_check_air::{
+        gen_range_check_interaction_trace, generate_range_check_trace, RangeCheckElements,
+        RangeCheckMultiplicities,
+    };
+    use crate::debug::{check_constraints, ConstraintViolation};
+    use crate::field::M31;
+    use crate::prover::PobPublicValues;
+    use crate::secret::Secret;
+    use crate::utils::poseidon2_stwo::poseidon2_permutation;
+    use alloy_primitives::U256;
+    use stwo_prover::prover::backend::simd::m31::N_LANES;
+
+    fn pob_inputs() -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    fn table_eval(log_n_rows: u32) -> Poseidon2TableEval {
+        Poseidon2TableEval {
+            log_n_rows,
+            nullifier_lookup: NullifierElements::dummy(),
+            remaining_coin_lookup: RemainingCoinElements::dummy(),
+            commitment_lookup: CommitmentElements::dummy(),
+            block_root_digest_lookup: BlockRootDigestElements::dummy(),
+            burn_address_lookup: BurnAddressElements::dummy(),
+        }
+    }
+
+    /// PoB (plus Keccak, range check and public outputs) and table claimed sums, after letting
+    /// `tamper` edit the PoB lookup data
+    fn claimed_sums(tamper: impl FnOnce(&mut crate::circuits::LookupData)) -> (SecureField, SecureField) {
+        let log_n_rows = 5;
+        let (pob_trace, mut pob_lookup) = generate_pob_trace(log_n_rows, &pob_inputs()).unwrap();
+        let table_log_size = poseidon2_table_log_size(pob_lookup.table_entries.len());
+        let (_, table_lookup) =
+            generate_poseidon2_table_trace(table_log_size, &pob_lookup.table_entries).unwrap();
+        tamper(&mut pob_lookup);
+
+        let (nullifier, remaining_coin, commitment, block_root_digest, burn_address) = (
+            NullifierElements::dummy(),
+            RemainingCoinElements::dummy(),
+            CommitmentElements::dummy(),
+            BlockRootDigestElements::dummy(),
+            BurnAddressElements::dummy(),
+        );
+        let (_, table_sum) = gen_poseidon2_table_interaction_trace(
+            table_log_size,
+            &table_lookup,
+            &nullifier,
+            &remaining_coin,
+            &commitment,
+            &block_root_digest,
+            &burn_address,
+        );
+        // The block root lookups are answered by the Keccak component, not the table
+        let block_root = BlockRootElements::dummy();
+        let header = pob_inputs().block_header;
+        let keccak_log_size = keccak_log_size(header.len());
+        let (_, keccak_lookup) = generate_keccak_trace(keccak_log_size, &header, N_LANES as u32).unwrap();
+        let (_, keccak_sum) = gen_keccak_interaction_trace(
+            keccak_log_size,
+            &keccak_lookup,
+            &KeccakStateElements::dummy(),
+            &block_root,
+        );
+        // And the balance chunks by the range-check component
+        let range_check = RangeCheckElements::dummy();
+        let mut multiplicities = RangeCheckMultiplicities::default();
+        for column in &pob_lookup.range_checked {
+            multiplicities.add_column(column, &pob_lookup.enabled).unwrap();
+        }
+        let (_, range_check_column) = generate_range_check_trace(&multiplicities);
+        let (_, range_check_sum) = gen_range_check_interaction_trace(&range_check_column, &range_check);
+        // And the public outputs by the verifier
+        let public_output = PublicOutputElements::dummy();
+        let public_sum = PobPublicValues::from_trace(&pob_trace).logup_sum(&public_output);
+        let (_, pob_sum) = gen_interaction_trace(
+            log_n_rows,
+            pob_lookup,
+            &nullifier,
+            &remaining_coin,
+            &commitment,
+            &block_root_digest,
+            &burn_address,
+            &block_root,
+            &range_check,
+            &public_output,
+        );
+        (pob_sum + keccak_sum + range_check_sum - public_sum, table_sum)
+    }
+
+    #[test]
+    fn test_permutation_columns_end_in_output() {
+        let input: [BaseField; N_STATE] = std::array::from_fn(|i| BaseField::from(i as u32 + 1));
+        let columns = permutation_columns(input);
+        assert_eq!(columns.len(), N_PERMUTATION_COLUMNS);
+        assert_eq!(columns[N_PERMUTATION_COLUMNS - N_STATE..], poseidon2_permutation(input));
+    }
+
+    #[test]
+    fn test_table_trace_satisfies_round_constraints() {
+        let entries = [Poseidon2TableEntry {
+            relation: Poseidon2Relation::Nullifier,
+            input: std::array::from_fn(|i| BaseField::from(7 * i as u32)),
+            multiplicity: 1,
+        }];
+        let (trace, _) = generate_poseidon2_table_trace(4, &entries).unwrap();
+        assert_eq!(trace.len(), NUM_POSEIDON2_TABLE_COLUMNS);
+        check_constraints(&table_eval(4), &trace).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_table_trace_violates_round_constraint() {
+        let (_, pob_lookup) = generate_pob_trace(4, &pob_inputs()).unwrap();
+        let (mut trace, _) = generate_poseidon2_table_trace(4, &pob_lookup.table_entries).unwrap();
+
+        // First S-box output of the first full round
+        trace[N_STATE].values.set(1, BaseField::from(1234u32));
+        match check_constraints(&table_eval(4), &trace) {
+            Err(ConstraintViolation::Constraint { constraint, row, .. }) => {
+                assert_eq!(constraint, 0);
+                assert_eq!(row, 1);
+            }
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pob_lookups_cancel_against_table() {
+        let (pob_sum, table_sum) = claimed_sums(|_| {});
+        assert_ne!(pob_sum, SecureField::zero());
+        assert_eq!(pob_sum + table_sum, SecureField::zero());
+    }
+
+    #[test]
+    fn test_tampered_pob_output_breaks_lookup_balance() {
+        let (pob_sum, table_sum) = claimed_sums(|lookup| {
+            lookup.nullifier[0].data[0] += PackedBaseField::broadcast(BaseField::from(1));
+        });
+        assert_ne!(pob_sum + table_sum, SecureField::zero());
+    }
+
+    #[test]
+    fn test_table_log_size() {
+        assert_eq!(poseidon2_table_log_size(3), LOG_N_LANES);
+        assert_eq!(poseidon2_table_log_size(1 << LOG_N_LANES), LOG_N_LANES);
+        assert_eq!(poseidon2_table_log_size((1 << LOG_N_LANES) + 1), LOG_N_LANES + 1);
+    }
+
+    #[test]
+    fn test_oversized_table_rejected() {
+        let entry = Poseidon2TableEntry {
+            relation: Poseidon2Relation::Commitment,
+            input: [BaseField::zero(); N_STATE],
+            multiplicity: 1,
+        };
+        assert!(generate_poseidon2_table_trace(4, &vec![entry; 17]).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+// Preprocessed selector columns
+// Columns that depend only on the trace size, committed in the preprocessed tree next to the
+// range-check table. They are not witness data: the verifier regenerates the preprocessed tree
+// and rejects a proof whose preprocessed root differs, so a prover cannot commit other values.
+
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::Column;
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+
+/// Selector that is 1 on the first row of a `2^log_size` trace and 0 elsewhere
+///
+/// Boundary constraints multiply by it to hold on row 0 only, e.g. that the row the public
+/// outputs are read from is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsFirst {
+    pub log_size: u32,
+}
+
+impl IsFirst {
+    pub const fn new(log_size: u32) -> Self {
+        Self { log_size }
+    }
+
+    pub fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!("is_first_{}", self.log_size),
+        }
+    }
+
+    /// Value on `row`, in the trace's bit-reversed storage order
+    pub fn value(&self, row: usize) -> BaseField {
+        BaseField::from((row == 0) as u32)
+    }
+
+    /// Generate the column
+    pub fn gen_column(&self) -> CircleEvaluation<SimdBackend, BaseField, BitReversedOrder> {
+        let mut column = BaseColumn::zeros(1 << self.log_size);
+        column.set(0, BaseField::from(1));
+        CircleEvaluation::new(CanonicCoset::new(self.log_size).circle_domain(), column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_first_selects_row_zero() {
+        let is_first = IsFirst::new(5);
+        let column = is_first.gen_column();
+        assert_eq!(column.domain.log_size(), 5);
+        for row in 0..1 << 5 {
+            assert_eq!(column.values.at(row), is_first.value(row));
+        }
+        assert_eq!(column.values.at(0), BaseField::from(1));
+        assert_ne!(is_first.id().id, IsFirst::new(6).id().id);
+    }
+}
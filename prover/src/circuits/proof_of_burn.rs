@@ -2,46 +2,78 @@
 // Translates proof_of_burn.circom to Rust using Circle STARK proofs
 // Reference: proof-of-burn/circuits/proof_of_burn.circom
 
-use crate::constants::{
-    circuit_params::*,
-    poseidon_coin_prefix, poseidon_nullifier_prefix,
-};
+use crate::coins;
+use crate::constants::{circuit_params::*, MAINNET_CHAIN_ID, OUTPUT_LIMBS};
+use crate::circuits::keccak_air::block_root_chunks;
+use crate::circuits::validation::{validate_amount, AmountError};
+use crate::prover::packaging::limbs_to_u256;
 use crate::utils::{
-    burn_address::compute_burn_address_hash,
+    burn_address::{burn_address_digest_with, compute_burn_address, compute_burn_address_hash, format_checksummed},
+    header::{state_root_at_offset, BlockHeader},
+    header_chain::{verify_descendants, ChainError, ChainInfo},
     keccak::keccak256,
     mpt::verify_mpt_proof,
-    poseidon::{poseidon2, poseidon3, u256_to_m31},
+    poseidon2_stwo::{basefield_to_custom_m31, permute_padded, poseidon2_permutation, Permutation, N_STATE},
     pow::verify_pow,
 };
-use alloy_primitives::U256;
+use alloy_primitives::{Address, B256, U256};
 use serde::{Deserialize, Serialize};
 use crate::field::M31;
+use crate::secret::Secret;
 
 /// Inputs for the Proof of Burn circuit
 /// Private witness data that proves ETH was burned
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ProofOfBurnInputs {
     /// Secret burn key from which address and nullifier are derived
-    pub burn_key: M31,
+    pub burn_key: Secret<M31>,
     
     /// Actual balance in the burn address (may include dust)
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
     pub actual_balance: U256,
     
     /// Intended balance (without dust from attackers)
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
     pub intended_balance: U256,
     
     /// Amount to reveal immediately upon proof submission
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
     pub reveal_amount: U256,
     
     /// Extra commitment (receiver, fees, etc.)
     pub burn_extra_commitment: M31,
     
     /// Merkle-Patricia-Trie proof layers
+    #[serde(with = "crate::utils::hex_bytes::layers")]
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<crate::schema::ByteString>"))]
     pub layers: Vec<Vec<u8>>,
     
     /// Ethereum block header containing state root
+    #[serde(with = "crate::utils::hex_bytes")]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::ByteString"))]
     pub block_header: Vec<u8>,
     
+    /// Headers of the blocks after `block_header`'s, oldest first, ending at the block the burn
+    /// is submitted against
+    ///
+    /// Empty to submit against `block_header`'s own block. Otherwise the MPT proof is still
+    /// checked against `block_header`, and the statement binds the hash of the last header.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "crate::utils::hex_bytes::layers")]
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<crate::schema::ByteString>"))]
+    pub header_chain: Vec<Vec<u8>>,
+    
+    /// Chain id of the chain the burn is submitted on, mainnet if absent
+    ///
+    /// Bound into the proof's channel with `valid_until_block`, so the proof does not verify
+    /// for another chain.
+    #[serde(default = "mainnet_chain_id")]
+    pub chain_id: u64,
+    
+    /// Last block number at which the proof may be submitted; `None` for no expiry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until_block: Option<u64>,
+    
     /// Number of address-hash nibbles in the leaf node
     pub num_leaf_address_nibbles: u8,
     
@@ -50,19 +82,122 @@ pub struct ProofOfBurnInputs {
     
     /// Extra commitment for proof metadata (e.g., prover address)
     pub proof_extra_commitment: M31,
+    
+    /// Address the burn was sent to, if the caller knows it
+    ///
+    /// The MPT proof is looked up under the address the burn key, reveal amount and extra
+    /// commitment derive, so inputs whose reveal amount drifted from the one the funded address
+    /// was derived with point at another, empty account. With the funded address set, that is
+    /// rejected up front as a mismatch rather than as a failed trie walk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::schema::AddressHex>"))]
+    pub expected_burn_address: Option<Address>,
+}
+
+fn mainnet_chain_id() -> u64 {
+    MAINNET_CHAIN_ID
+}
+
+impl ProofOfBurnInputs {
+    /// The header whose hash is publicly bound: the last of `header_chain`, or `block_header`
+    pub fn bound_block_header(&self) -> &[u8] {
+        self.header_chain.last().unwrap_or(&self.block_header)
+    }
+
+    /// Check `block_header` and then `header_chain` form a linked chain of blocks
+    pub fn verify_header_chain(&self) -> Result<ChainInfo, ChainError> {
+        verify_descendants(&self.block_header, &self.header_chain)
+    }
 }
 
 /// Public outputs from the Proof of Burn circuit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ProofOfBurnOutputs {
-    /// Public commitment hash of all values
-    pub commitment: M31,
+    /// Public commitment hash of all values, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
     
-    /// Nullifier to prevent double-spending
-    pub nullifier: M31,
+    /// Nullifier to prevent double-spending, as `OUTPUT_LIMBS` limbs
+    pub nullifier: [M31; OUTPUT_LIMBS],
     
     /// Encrypted remaining balance
     pub remaining_coin: M31,
+
+    /// Poseidon2 digest the burn address is derived from, as `OUTPUT_LIMBS` limbs
+    pub burn_address_digest: [M31; OUTPUT_LIMBS],
+    
+    /// Security level the proof was generated under (bound into `commitment`)
+    pub security: SecurityLevel,
+}
+
+impl ProofOfBurnOutputs {
+    /// Nullifier packed as the contracts take it, 0x-prefixed hex
+    pub fn nullifier_hex(&self) -> String {
+        format!("{:#x}", limbs_to_u256(&self.nullifier))
+    }
+
+    /// Remaining coin, 0x-prefixed hex
+    pub fn remaining_coin_hex(&self) -> String {
+        format!("{:#x}", self.remaining_coin.value())
+    }
+
+    /// Commitment packed as the contracts take it, 0x-prefixed hex
+    pub fn commitment_hex(&self) -> String {
+        format!("{:#x}", limbs_to_u256(&self.commitment))
+    }
+
+    /// Burn address digest packed from its limbs, 0x-prefixed hex
+    pub fn burn_address_digest_hex(&self) -> String {
+        format!("{:#x}", limbs_to_u256(&self.burn_address_digest))
+    }
+}
+
+/// Packed as the contracts take them, in hex
+impl std::fmt::Display for ProofOfBurnOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nullifier={}, remaining_coin={}, commitment={}, {}",
+            self.nullifier_hex(),
+            self.remaining_coin_hex(),
+            self.commitment_hex(),
+            self.security
+        )
+    }
+}
+
+/// Security parameters implied by `byte_security_relax`
+///
+/// Each relaxed byte drops the leaf nibble requirement by 2 and raises the PoW requirement
+/// by one zero byte. `byte_security_relax` is bound into the commitment, and with it the two
+/// requirements it implies, so a verifier can see which trade-off a proof was made under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SecurityLevel {
+    pub byte_security_relax: u8,
+    pub pow_zero_bytes: u8,
+    pub min_leaf_address_nibbles: u8,
+}
+
+impl SecurityLevel {
+    /// Derive the effective requirements for a given relax value
+    pub fn from_relax(byte_security_relax: u8) -> Self {
+        Self {
+            byte_security_relax,
+            pow_zero_bytes: (POW_MINIMUM_ZERO_BYTES + byte_security_relax as usize) as u8,
+            min_leaf_address_nibbles: MIN_LEAF_ADDRESS_NIBBLES
+                .saturating_sub(byte_security_relax as usize * 2) as u8,
+        }
+    }
+}
+
+impl std::fmt::Display for SecurityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "byte_security_relax={} (PoW {} zero bytes, >= {} leaf nibbles)",
+            self.byte_security_relax, self.pow_zero_bytes, self.min_leaf_address_nibbles
+        )
+    }
 }
 
 /// Proof of Burn circuit implementation
@@ -84,6 +219,11 @@ impl ProofOfBurnCircuit {
     pub fn new(inputs: ProofOfBurnInputs) -> Result<Self, ProofOfBurnError> {
         // Constraint: Balance validations (lines 84-97)
         
+        // Every amount fits in maxAmountBytes, whatever the balance limits below
+        validate_amount(inputs.actual_balance, "actual_balance")?;
+        validate_amount(inputs.intended_balance, "intended_balance")?;
+        validate_amount(inputs.reveal_amount, "reveal_amount")?;
+        
         // intendedBalance <= maxIntendedBalance
         if inputs.intended_balance > U256::from(MAX_INTENDED_BALANCE) {
             return Err(ProofOfBurnError::IntendedBalanceTooHigh {
@@ -117,13 +257,12 @@ impl ProofOfBurnCircuit {
         }
         
         // Constraint: Security parameters (lines 90-91)
-        let min_nibbles = MIN_LEAF_ADDRESS_NIBBLES
-            .saturating_sub(inputs.byte_security_relax as usize * 2);
+        let min_nibbles = SecurityLevel::from_relax(inputs.byte_security_relax).min_leaf_address_nibbles;
         
-        if (inputs.num_leaf_address_nibbles as usize) < min_nibbles {
+        if inputs.num_leaf_address_nibbles < min_nibbles {
             return Err(ProofOfBurnError::InsufficientNibbles {
                 provided: inputs.num_leaf_address_nibbles,
-                required: min_nibbles as u8,
+                required: min_nibbles,
             });
         }
         
@@ -142,89 +281,86 @@ impl ProofOfBurnCircuit {
             });
         }
         
+        // Headers after `block_header` must descend from it
+        if !inputs.header_chain.is_empty() {
+            inputs.verify_header_chain()?;
+        }
+        
+        // The address proven must be the one the burn was sent to
+        check_expected_burn_address(&inputs)?;
+        
         Ok(Self { inputs })
     }
     
-    /// Compute the circuit outputs
-    pub fn compute_outputs(&self) -> Result<ProofOfBurnOutputs, ProofOfBurnError> {
-        // Constraint: Calculate encrypted-balance of remaining-coin (line 113)
-        let remaining_balance = self.inputs.intended_balance - self.inputs.reveal_amount;
-        let remaining_balance_m31 = u256_to_m31(remaining_balance);
-        
-        let remaining_coin = poseidon3([
-            poseidon_coin_prefix(),
-            self.inputs.burn_key,
-            remaining_balance_m31,
-        ]);
-        
-        // Constraint: Calculate nullifier (line 116)
-        let nullifier = poseidon2([
-            poseidon_nullifier_prefix(),
-            self.inputs.burn_key,
-        ]);
-        
+    /// The inputs with `actual_balance` taken from the account the MPT proof commits to
+    ///
+    /// This is what the trace is built from: its actual balance columns, which the AIR checks
+    /// cover the intended balance, then hold the proven leaf's balance rather than the input
+    /// file's. An input claiming another balance than the leaf holds is rejected.
+    pub fn proven_inputs(&self) -> Result<ProofOfBurnInputs, ProofOfBurnError> {
         // Constraint: Calculate keccak hash of burn-address (line 119)
         let address_hash = compute_burn_address_hash(
-            self.inputs.burn_key,
+            *self.inputs.burn_key.expose_secret(),
             self.inputs.reveal_amount,
             self.inputs.burn_extra_commitment,
         );
         
-        // Constraint: Calculate the block-root (line 122)
-        let block_root = keccak256(&self.inputs.block_header);
-        
         // Constraint: Fetch the stateRoot from the block-header (lines 125-129)
-        // State root starts at byte 91 of the block header
-        const STATE_ROOT_OFFSET: usize = 91;
-        
-        if self.inputs.block_header.len() < STATE_ROOT_OFFSET + 32 {
-            return Err(ProofOfBurnError::InvalidBlockHeader {
+        let state_root = state_root_at_offset(&self.inputs.block_header).ok_or_else(|| {
+            ProofOfBurnError::InvalidBlockHeader {
                 reason: "Header too short to contain state root".to_string(),
-            });
-        }
-        
-        let mut state_root = [0u8; 32];
-        state_root.copy_from_slice(&self.inputs.block_header[STATE_ROOT_OFFSET..STATE_ROOT_OFFSET + 32]);
+            }
+        })?;
         
         // Constraint: Verify MPT proof (lines 148-193)
-        verify_mpt_proof(
+        let proven = verify_mpt_proof(
             &self.inputs.layers,
             &state_root,
             &address_hash,
-            self.inputs.actual_balance,
         ).map_err(|e| ProofOfBurnError::MptVerificationFailed {
             reason: e.to_string(),
         })?;
         
+        // Constraint: Leaf account is a burn account holding actualBalance (lines 198-206)
+        if proven.balance != self.inputs.actual_balance {
+            return Err(ProofOfBurnError::ProvenBalanceMismatch {
+                claimed: self.inputs.actual_balance,
+                proven: proven.balance,
+            });
+        }
+        if !proven.is_burn_account() {
+            return Err(ProofOfBurnError::NotBurnAccount);
+        }
+        
+        // `new` checked intendedBalance <= actualBalance, now the proven balance
+        Ok(ProofOfBurnInputs { actual_balance: proven.balance, ..self.inputs.clone() })
+    }
+    
+    /// Compute the circuit outputs
+    #[tracing::instrument(level = "debug", name = "compute_pob_outputs", skip_all)]
+    pub fn compute_outputs(&self) -> Result<ProofOfBurnOutputs, ProofOfBurnError> {
+        let inputs = self.proven_inputs()?;
+        
         // Constraint: Verify PoW (line 211)
-        let pow_zero_bytes = POW_MINIMUM_ZERO_BYTES + self.inputs.byte_security_relax as usize;
+        let security = SecurityLevel::from_relax(inputs.byte_security_relax);
+        let pow_zero_bytes = security.pow_zero_bytes as usize;
 
-        if !verify_pow(
-            self.inputs.burn_key,
-            self.inputs.reveal_amount,
-            self.inputs.burn_extra_commitment,
-            pow_zero_bytes,
-        ) {
+        let pow_ok = tracing::debug_span!("check_pow", required_zeros = pow_zero_bytes).in_scope(|| {
+            verify_pow(
+                *inputs.burn_key.expose_secret(),
+                inputs.reveal_amount,
+                inputs.burn_extra_commitment,
+                pow_zero_bytes,
+            )
+        });
+        if !pow_ok {
             return Err(ProofOfBurnError::PowVerificationFailed {
                 required_zeros: pow_zero_bytes,
             });
         }
         
-        // Constraint: Calculate public commitment (lines 132-139)
-        let commitment = compute_pob_commitment(
-            &block_root,
-            nullifier,
-            remaining_coin,
-            self.inputs.reveal_amount,
-            self.inputs.burn_extra_commitment,
-            self.inputs.proof_extra_commitment,
-        );
-        
-        Ok(ProofOfBurnOutputs {
-            commitment,
-            nullifier,
-            remaining_coin,
-        })
+        // Constraints: remaining coin, nullifier and public commitment (lines 113-116, 132-139)
+        Ok(compute_pob_outputs(&inputs, poseidon2_permutation))
     }
     
     /// Verify all circuit constraints
@@ -233,54 +369,105 @@ impl ProofOfBurnCircuit {
     }
 }
 
-/// Compute the public commitment for Proof of Burn circuit
-/// Corresponds to PublicCommitment in proof-of-burn/circuits/utils/public_commitment.circom
-/// 
-/// commitment = Hash(blockRoot, nullifier, remainingCoin, revealAmount, burnExtraCommitment, proofExtraCommitment)
-fn compute_pob_commitment(
-    block_root: &[u8; 32],
-    nullifier: M31,
-    remaining_coin: M31,
-    reveal_amount: U256,
-    burn_extra_commitment: M31,
-    proof_extra_commitment: M31,
-) -> M31 {
-    // In the Circom version, this uses Keccak hash of all values
-    // For M31 compatibility, we'll use a simplified approach
-    
-    // Convert all values to M31 field and hash with Poseidon
-    let reveal_amount_m31 = u256_to_m31(reveal_amount);
-    
-    // Simple version: hash the first few bytes of block_root with other values
-    let block_root_m31 = M31::from(u32::from_be_bytes([
-        block_root[0],
-        block_root[1],
-        block_root[2],
-        block_root[3],
-    ]));
-    
-    // Combine all commitments using poseidon functions
-    // Since poseidon_hash only supports up to 4 inputs, we use a combination
-    use crate::utils::poseidon::{poseidon3, poseidon4};
-
-    // First hash 4 inputs
-    let hash1 = poseidon4([
-        block_root_m31,
-        nullifier,
+/// Check that `block_header` commits to the MPT proof whose first layer is `root_layer`
+///
+/// The circuit only ties the two together inside MPT verification, whose failure names neither
+/// side. A mismatch here carries the header's block number, since the usual cause is a proof
+/// fetched for a newer block than the header.
+pub fn check_header_matches_proof(block_header: &[u8], root_layer: &[u8]) -> Result<(), ProofOfBurnError> {
+    let invalid_header = |e: crate::utils::header::HeaderError| ProofOfBurnError::InvalidBlockHeader {
+        reason: e.to_string(),
+    };
+    let header = BlockHeader::decode(block_header).map_err(invalid_header)?;
+    let header_state_root = header.state_root().map_err(invalid_header)?;
+    let proof_root = keccak256(root_layer);
+    if header_state_root == proof_root {
+        return Ok(());
+    }
+    Err(ProofOfBurnError::HeaderProofMismatch {
+        header_state_root: B256::from(header_state_root),
+        proof_root: B256::from(proof_root),
+        block_number: header.number().map_err(invalid_header)?,
+    })
+}
+
+/// Require `inputs.expected_burn_address`, if set, to be the address their burn key, reveal
+/// amount and extra commitment derive
+pub(crate) fn check_expected_burn_address(inputs: &ProofOfBurnInputs) -> Result<(), ProofOfBurnError> {
+    let Some(expected) = inputs.expected_burn_address else { return Ok(()) };
+    let derived = compute_burn_address(
+        *inputs.burn_key.expose_secret(),
+        inputs.reveal_amount,
+        inputs.burn_extra_commitment,
+    );
+    if derived != expected {
+        return Err(ProofOfBurnError::BurnAddressMismatch { expected, derived });
+    }
+    Ok(())
+}
+
+/// Compute the nullifier, remaining coin and commitment exactly as the PoB trace hashes them
+///
+/// Each value is one call to `permutation` on a zero-padded state, the same states the Poseidon2
+/// table component proves, but built here from the protocol constants and `u256_to_m31_array`
+/// rather than from the trace code. `consistency::check_pob` holds the two paths equal.
+///
+/// nullifier = Poseidon2([NULLIFIER_PREFIX, burnKey])[..8]
+/// remainingCoin = Poseidon2([COIN_PREFIX, burnKey, (intendedBalance - revealAmount) limbs])[0]
+/// commitment = Poseidon2([nullifier, remainingCoin, revealAmountLow, revealAmountHigh,
+///                         burnExtraCommitment, proofExtraCommitment, byteSecurityRelax, 0,
+///                         blockRootDigest])[..8]
+///
+/// The reveal amount is absorbed as its two 32-bit limbs, which the trace range-checks, so
+/// reveal amounts that differ above bit 32 commit differently.
+/// blockRootDigest = Poseidon2(keccak256(blockHeader) as 16-bit chunks)[0]
+pub(crate) fn compute_pob_outputs(inputs: &ProofOfBurnInputs, permutation: Permutation) -> ProofOfBurnOutputs {
+    let hash = |preimage: &[M31]| permute_padded(permutation, preimage);
+    let burn_key = *inputs.burn_key.expose_secret();
+
+    let nullifier = coins::nullifier_with(permutation, burn_key);
+    let remaining_balance = inputs.intended_balance - inputs.reveal_amount;
+    let remaining_coin = coins::coin_with(permutation, burn_key, remaining_balance);
+
+    // Constraint: Calculate the block-root (line 122), digested into one limb
+    let block_root = block_root_chunks(&keccak256(&inputs.block_header));
+    let block_root_digest = basefield_to_custom_m31(permutation(block_root)[0]);
+
+    let security = SecurityLevel::from_relax(inputs.byte_security_relax);
+    let mut preimage = Vec::with_capacity(N_STATE);
+    preimage.extend_from_slice(&nullifier);
+    let reveal_amount = inputs.reveal_amount.as_limbs()[0];
+    preimage.extend_from_slice(&[
         remaining_coin,
-        reveal_amount_m31,
+        M31::from(reveal_amount as u32),
+        M31::from((reveal_amount >> 32) as u32),
+        inputs.burn_extra_commitment,
+        inputs.proof_extra_commitment,
+        M31::from(security.byte_security_relax as u32),
+        M31::zero(),
     ]);
+    preimage.push(block_root_digest);
+    let commitment_state = hash(&preimage);
 
-    // Then hash the result with the remaining 2 inputs
-    poseidon3([
-        hash1,
-        burn_extra_commitment,
-        proof_extra_commitment,
-    ])
+    ProofOfBurnOutputs {
+        commitment: std::array::from_fn(|i| commitment_state[i]),
+        nullifier,
+        remaining_coin,
+        burn_address_digest: burn_address_digest_with(
+            permutation,
+            burn_key,
+            inputs.reveal_amount,
+            inputs.burn_extra_commitment,
+        ),
+        security,
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ProofOfBurnError {
+    #[error(transparent)]
+    AmountTooLarge(#[from] AmountError),
+    
     #[error("Intended balance too high: {value}, max: {max}")]
     IntendedBalanceTooHigh { value: U256, max: u128 },
     
@@ -305,11 +492,26 @@ pub enum ProofOfBurnError {
     #[error("Invalid block header: {reason}")]
     InvalidBlockHeader { reason: String },
     
+    #[error("Header of block {block_number} has state root {header_state_root}, but the MPT proof is rooted at {proof_root} (fetched for another block?)")]
+    HeaderProofMismatch { header_state_root: B256, proof_root: B256, block_number: u64 },
+    
     #[error("MPT verification failed: {reason}")]
     MptVerificationFailed { reason: String },
     
+    #[error("Actual balance {claimed} does not match the proven account balance {proven}")]
+    ProvenBalanceMismatch { claimed: U256, proven: U256 },
+    
+    #[error("Proven account is not a burn account: it has a nonce, storage or code")]
+    NotBurnAccount,
+    
     #[error("PoW verification failed: requires {required_zeros} zero bytes")]
     PowVerificationFailed { required_zeros: usize },
+    
+    #[error("Invalid header chain: {0}")]
+    HeaderChain(#[from] ChainError),
+    
+    #[error("Burn address mismatch: expected {}, the burn key, reveal amount and extra commitment derive {}", format_checksummed(*expected), format_checksummed(*derived))]
+    BurnAddressMismatch { expected: Address, derived: Address },
 }
 
 #[cfg(test)]
@@ -318,19 +520,51 @@ mod tests {
     
     fn create_test_inputs() -> ProofOfBurnInputs {
         ProofOfBurnInputs {
-            burn_key: M31::from(12345),
+            burn_key: Secret::new(M31::from(12345)),
             actual_balance: U256::from(1000000000000000000u64), // 1 ETH
             intended_balance: U256::from(1000000000000000000u64),
             reveal_amount: U256::from(500000000000000000u64), // 0.5 ETH
             burn_extra_commitment: M31::from(100),
             layers: vec![vec![0u8; 100], vec![0u8; 80]], // Dummy layers
             block_header: vec![0u8; 643], // Typical header size
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
             num_leaf_address_nibbles: 50,
             byte_security_relax: 0,
             proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
         }
     }
     
+    #[test]
+    fn test_debug_redacts_burn_key() {
+        let debug = format!("{:?}", create_test_inputs());
+        assert!(debug.contains("burn_key: Secret([REDACTED])"));
+        assert!(!debug.contains("12345"));
+    }
+    
+    #[test]
+    fn test_header_chain_binds_last_header() {
+        use crate::test_utils::{mock_block_header, mock_descendants};
+
+        let mut inputs = create_test_inputs();
+        inputs.block_header = mock_block_header(&[0x11; 32]);
+        assert_eq!(inputs.bound_block_header(), inputs.block_header);
+
+        inputs.header_chain = mock_descendants(&inputs.block_header, 3);
+        assert_eq!(inputs.bound_block_header(), inputs.header_chain[2]);
+        assert_eq!(inputs.verify_header_chain().unwrap().len, 4);
+        assert!(ProofOfBurnCircuit::new(inputs.clone()).is_ok());
+
+        // The chain must start at the block the MPT proof is checked against
+        inputs.header_chain.remove(0);
+        assert!(matches!(
+            ProofOfBurnCircuit::new(inputs),
+            Err(ProofOfBurnError::HeaderChain(ChainError::BrokenLink { index: 1, .. }))
+        ));
+    }
+    
     #[test]
     fn test_proof_of_burn_circuit_creation() {
         let inputs = create_test_inputs();
@@ -347,6 +581,28 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[test]
+    fn test_amounts_capped_at_amount_bytes() {
+        let cap = U256::from(1) << 248;
+        for field in ["actual_balance", "intended_balance", "reveal_amount"] {
+            let with = |value: U256| {
+                let mut inputs = create_test_inputs();
+                match field {
+                    "actual_balance" => inputs.actual_balance = value,
+                    "intended_balance" => inputs.intended_balance = value,
+                    _ => inputs.reveal_amount = value,
+                }
+                ProofOfBurnCircuit::new(inputs).err()
+            };
+            // 2^248 - 1 passes the cap and fails a later check; 2^248 fails the cap itself
+            assert!(!matches!(with(cap - U256::from(1)), Some(ProofOfBurnError::AmountTooLarge(_))));
+            match with(cap) {
+                Some(ProofOfBurnError::AmountTooLarge(e)) => assert_eq!(e.field, field),
+                other => panic!("{field}: expected AmountTooLarge, got {:?}", other),
+            }
+        }
+    }
+    
     #[test]
     fn test_reveal_amount_too_high() {
         let mut inputs = create_test_inputs();
@@ -356,6 +612,47 @@ mod tests {
         assert!(result.is_err());
     }
     
+    fn funded_address(reveal_amount: U256) -> Address {
+        compute_burn_address(M31::from(12345), reveal_amount, M31::from(100))
+    }
+    
+    #[test]
+    fn test_expected_burn_address_matches() {
+        let mut inputs = create_test_inputs();
+        inputs.expected_burn_address = Some(funded_address(inputs.reveal_amount));
+        assert!(ProofOfBurnCircuit::new(inputs).is_ok());
+    }
+    
+    #[test]
+    fn test_expected_burn_address_mismatch() {
+        // Funded with the whole balance revealed, proven with half of it
+        let mut inputs = create_test_inputs();
+        let funded = funded_address(inputs.intended_balance);
+        inputs.expected_burn_address = Some(funded);
+        match ProofOfBurnCircuit::new(inputs) {
+            Err(ProofOfBurnError::BurnAddressMismatch { expected, derived }) => {
+                assert_eq!(expected, funded);
+                assert_eq!(derived, funded_address(U256::from(500000000000000000u64)));
+            }
+            other => panic!("expected BurnAddressMismatch, got {:?}", other.err()),
+        }
+    }
+    
+    #[test]
+    fn test_expected_burn_address_absent_from_older_inputs() {
+        // Written without the field, and read back from a file that lacks it
+        let mut json = serde_json::to_value(create_test_inputs()).unwrap();
+        assert!(json.get("expected_burn_address").is_none());
+        let inputs: ProofOfBurnInputs = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(inputs.expected_burn_address, None);
+        assert!(ProofOfBurnCircuit::new(inputs).is_ok());
+        
+        let funded = funded_address(U256::from(500000000000000000u64));
+        json["expected_burn_address"] = format_checksummed(funded).into();
+        let inputs: ProofOfBurnInputs = serde_json::from_value(json).unwrap();
+        assert_eq!(inputs.expected_burn_address, Some(funded));
+    }
+    
     #[test]
     fn test_intended_greater_than_actual() {
         let mut inputs = create_test_inputs();
@@ -378,5 +675,97 @@ mod tests {
         // With dummy test data, MPT verification should fail
         assert!(result.is_err());
     }
-}
+    
+    #[test]
+    fn test_actual_balance_must_match_proven_balance() {
+        use crate::test_utils::mock_account_proof;
+        
+        // The trie proves 1 ETH at the burn address; the inputs claim a little more
+        let mut inputs = create_test_inputs();
+        let address_hash = compute_burn_address_hash(
+            *inputs.burn_key.expose_secret(),
+            inputs.reveal_amount,
+            inputs.burn_extra_commitment,
+        );
+        let proof = mock_account_proof(&address_hash, inputs.actual_balance, 3);
+        inputs.layers = proof.layers;
+        inputs.block_header = proof.block_header;
+        
+        // The trace is built from the leaf's balance
+        let honest = ProofOfBurnCircuit::new(inputs.clone()).unwrap().proven_inputs().unwrap();
+        assert_eq!(honest.actual_balance, U256::from(1000000000000000000u64));
+        
+        inputs.actual_balance += U256::from(1);
+        let circuit = ProofOfBurnCircuit::new(inputs).unwrap();
+        let is_mismatch = |error: &ProofOfBurnError| {
+            matches!(
+                error,
+                ProofOfBurnError::ProvenBalanceMismatch { proven, .. }
+                    if *proven == U256::from(1000000000000000000u64)
+            )
+        };
+        assert!(is_mismatch(&circuit.proven_inputs().unwrap_err()));
+        assert!(is_mismatch(&circuit.compute_outputs().unwrap_err()));
+    }
+    
+    #[test]
+    fn test_commitment_binds_byte_security_relax() {
+        // Same burn, same block, only the relax value differs
+        let commit = |relax: u8| {
+            let mut inputs = create_test_inputs();
+            inputs.byte_security_relax = relax;
+            compute_pob_outputs(&inputs, poseidon2_permutation).commitment
+        };
+        
+        assert_ne!(commit(0), commit(1));
+        assert_eq!(commit(1), commit(1));
+    }
+
+    #[test]
+    fn test_commitment_binds_reveal_amount_high_limb() {
+        // Reveal amounts with the same low 32 bits, and the same remaining balance
+        let commit = |reveal_amount: u64| {
+            let mut inputs = create_test_inputs();
+            inputs.intended_balance = U256::from(reveal_amount + 1000);
+            inputs.reveal_amount = U256::from(reveal_amount);
+            compute_pob_outputs(&inputs, poseidon2_permutation)
+        };
+        let (low, high) = (commit(500000), commit(500000 + (1u64 << 32)));
+
+        assert_eq!(low.remaining_coin, high.remaining_coin);
+        assert_ne!(low.commitment, high.commitment);
+    }
+    
+    #[test]
+    fn test_outputs_dedup_in_hash_set() {
+        use std::collections::HashSet;
+
+        let outputs = |burn_key: u32, relax: u8| {
+            let mut inputs = create_test_inputs();
+            inputs.burn_key = Secret::new(M31::from(burn_key));
+            inputs.byte_security_relax = relax;
+            compute_pob_outputs(&inputs, poseidon2_permutation)
+        };
+        // Resubmitting a burn yields equal outputs; another key or security level does not
+        let submitted = [outputs(1, 0), outputs(2, 0), outputs(1, 0), outputs(1, 1), outputs(2, 0)];
+        let unique: HashSet<_> = submitted.iter().cloned().collect();
+        assert_eq!(unique.len(), 3);
+        assert_eq!(submitted[0], submitted[2]);
+        assert_ne!(submitted[0], submitted[3]);
+
+        let first = &submitted[0];
+        assert_eq!(first.nullifier_hex(), format!("{:#x}", limbs_to_u256(&first.nullifier)));
+        assert!(first.to_string().starts_with(&format!("nullifier={}, ", first.nullifier_hex())));
+    }
 
+    #[test]
+    fn test_security_level_from_relax() {
+        let strict = SecurityLevel::from_relax(0);
+        assert_eq!(strict.pow_zero_bytes as usize, POW_MINIMUM_ZERO_BYTES);
+        assert_eq!(strict.min_leaf_address_nibbles as usize, MIN_LEAF_ADDRESS_NIBBLES);
+        
+        let relaxed = SecurityLevel::from_relax(3);
+        assert_eq!(relaxed.pow_zero_bytes as usize, POW_MINIMUM_ZERO_BYTES + 3);
+        assert_eq!(relaxed.min_leaf_address_nibbles as usize, MIN_LEAF_ADDRESS_NIBBLES - 6);
+    }
+}
@@ -0,0 +1,396 @@
+// Range-check AIR for Stwo
+// A lookup table of every 16-bit value: other components claim that a column lies in [0, 2^16)
+// by adding it to `RangeCheckElements`, and this component answers each claim with the
+// multiplicity the prover counted for that value
+// M31 limbs of 32-bit values cannot be range-checked directly (2^32 > p), so values are
+// split into 16-bit chunks and the arithmetic on them is constrained chunk by chunk
+
+// Alias for macro compatibility (relation! macro expects 'stwo' crate name)
+extern crate stwo_prover as stwo;
+
+use itertools::Itertools;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::m31::LOG_N_LANES;
+use stwo_prover::prover::backend::simd::qm31::PackedSecureField;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::Column;
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    relation, EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator, Relation,
+    RelationEntry,
+};
+
+use crate::utils::poseidon::U256_M31_LIMBS;
+
+/// Bits covered by one range-check lookup
+pub const RANGE_CHECK_BITS: u32 = 16;
+
+/// The table holds one row per 16-bit value
+pub const RANGE_CHECK_LOG_SIZE: u32 = RANGE_CHECK_BITS;
+
+/// 16-bit chunks of a 64-bit balance, least significant first
+pub const BALANCE_CHUNKS: usize = 4;
+
+/// Claims that a value lies in [0, 2^16)
+relation!(RangeCheckElements, 1);
+
+/// Preprocessed column holding the value `i` at row `i`
+pub fn range_check_column_id() -> PreProcessedColumnId {
+    PreProcessedColumnId {
+        id: format!("range_check_{}", RANGE_CHECK_BITS),
+    }
+}
+
+pub type RangeCheckComponent = FrameworkComponent<RangeCheckEval>;
+
+/// Range-check table evaluator
+///
+/// One main column: how many claims each row's value answers.
+#[derive(Clone)]
+pub struct RangeCheckEval {
+    pub lookup_elements: RangeCheckElements,
+}
+
+impl FrameworkEval for RangeCheckEval {
+    fn log_size(&self) -> u32 {
+        RANGE_CHECK_LOG_SIZE
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        RANGE_CHECK_LOG_SIZE + 1
+    }
+
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let value = eval.get_preprocessed_column(range_check_column_id());
+        let multiplicity = eval.next_trace_mask();
+
+        eval.add_to_relation(RelationEntry::new(
+            &self.lookup_elements,
+            -E::EF::from(multiplicity),
+            &[value],
+        ));
+        eval.finalize_logup();
+
+        eval
+    }
+}
+
+/// Add a range-check claim for each of `values`
+///
+/// Call `write_range_check_claims` with the same columns, in the same order, when building the
+/// interaction trace.
+pub fn add_range_check_claims<E: EvalAtRow>(
+    eval: &mut E,
+    lookup_elements: &RangeCheckElements,
+    multiplicity: E::EF,
+    values: &[E::F],
+) {
+    for value in values {
+        eval.add_to_relation(RelationEntry::new(
+            lookup_elements,
+            multiplicity.clone(),
+            std::slice::from_ref(value),
+        ));
+    }
+}
+
+/// Constrain `limb == low + 2^16 * high`
+///
+/// Holds modulo p, so it ties the chunks to the limb's field value.
+pub fn constrain_limb_chunks<E: EvalAtRow>(eval: &mut E, limb: E::F, [low, high]: [E::F; 2]) {
+    eval.add_constraint(limb - (low + high * BaseField::from(1u32 << RANGE_CHECK_BITS)));
+}
+
+/// Bit widths of the pieces the 30-bit limbs of a 64-bit value cut its 16-bit chunks into:
+/// chunk 1 splits at bit 30 into 14 and 2 bits, chunk 3 at bit 60 into 12 and 4 bits
+const LIMB_PIECE_BITS: [u32; 4] = [14, 2, 12, 4];
+
+/// Columns `limb_pieces` fills: each piece, then each piece shifted to the top of 16 bits
+pub const LIMB_PIECE_COLUMNS: usize = 2 * LIMB_PIECE_BITS.len();
+
+/// Constrain `limbs` to be the `u256_to_m31_array` limbs of the 64-bit value chunked as `chunks`
+///
+/// `pieces` are the `limb_pieces` columns and must be range-checked along with the chunks. A
+/// piece and its shift both lying in [0, 2^16) bounds the piece to its width, so each limb is
+/// the integer its bits make rather than a value equal to it modulo p. Adds, in order: the four
+/// shifts, the two chunk splits, then one constraint per limb (3 + `U256_M31_LIMBS` in all).
+pub fn constrain_chunk_limbs<E: EvalAtRow>(
+    eval: &mut E,
+    chunks: &[E::F; BALANCE_CHUNKS],
+    pieces: &[E::F; LIMB_PIECE_COLUMNS],
+    limbs: [E::F; U256_M31_LIMBS],
+) {
+    let pow = |bits: u32| BaseField::from(1u32 << bits);
+    let (pieces, shifted) = pieces.split_at(LIMB_PIECE_BITS.len());
+    for ((piece, shifted), bits) in pieces.iter().zip(shifted).zip(LIMB_PIECE_BITS) {
+        eval.add_constraint(shifted.clone() - piece.clone() * pow(RANGE_CHECK_BITS - bits));
+    }
+    let [c1_low, c1_high, c3_low, c3_high] = std::array::from_fn(|i| pieces[i].clone());
+    let [low_bits, high_bits] = [LIMB_PIECE_BITS[0], LIMB_PIECE_BITS[2]];
+    eval.add_constraint(chunks[1].clone() - (c1_low.clone() + c1_high.clone() * pow(low_bits)));
+    eval.add_constraint(chunks[3].clone() - (c3_low.clone() + c3_high.clone() * pow(high_bits)));
+
+    // Bits 0..30, 30..60 and 60..64; every higher limb is zero
+    let expected = [
+        chunks[0].clone() + c1_low * pow(RANGE_CHECK_BITS),
+        c1_high + chunks[2].clone() * pow(2) + c3_low * pow(2 + RANGE_CHECK_BITS),
+        c3_high,
+    ];
+    for (i, limb) in limbs.into_iter().enumerate() {
+        match expected.get(i) {
+            Some(value) => eval.add_constraint(limb - value.clone()),
+            None => eval.add_constraint(limb),
+        }
+    }
+}
+
+/// Witness of `constrain_chunk_limbs` for `value`
+pub fn limb_pieces(value: u64) -> [BaseField; LIMB_PIECE_COLUMNS] {
+    let chunks = balance_chunks(value).map(|chunk| chunk.0);
+    let pieces = [
+        chunks[1] & ((1 << LIMB_PIECE_BITS[0]) - 1),
+        chunks[1] >> LIMB_PIECE_BITS[0],
+        chunks[3] & ((1 << LIMB_PIECE_BITS[2]) - 1),
+        chunks[3] >> LIMB_PIECE_BITS[2],
+    ];
+    std::array::from_fn(|i| match i.checked_sub(pieces.len()) {
+        None => BaseField::from(pieces[i]),
+        Some(j) => BaseField::from(pieces[j] << (RANGE_CHECK_BITS - LIMB_PIECE_BITS[j])),
+    })
+}
+
+/// Constrain `a - b == diff` over range-checked little-endian 16-bit chunks
+///
+/// `borrows[i]` is the borrow out of chunk `i`. Every chunk equation stays below 2^17 in absolute
+/// value, so it holds over the integers; with no borrow out of the top chunk, `diff` being
+/// range-checked proves `a >= b`.
+pub fn constrain_chunked_sub<E: EvalAtRow>(
+    eval: &mut E,
+    a: &[E::F; BALANCE_CHUNKS],
+    b: &[E::F; BALANCE_CHUNKS],
+    diff: &[E::F; BALANCE_CHUNKS],
+    borrows: &[E::F; BALANCE_CHUNKS - 1],
+) {
+    let one = E::F::from(BaseField::from(1));
+    for borrow in borrows {
+        eval.add_constraint(borrow.clone() * (borrow.clone() - one.clone()));
+    }
+    for i in 0..BALANCE_CHUNKS {
+        let mut chunk = a[i].clone() - b[i].clone() - diff[i].clone();
+        if i > 0 {
+            chunk = chunk - borrows[i - 1].clone();
+        }
+        if i < BALANCE_CHUNKS - 1 {
+            chunk = chunk + borrows[i].clone() * BaseField::from(1u32 << RANGE_CHECK_BITS);
+        }
+        eval.add_constraint(chunk);
+    }
+}
+
+/// Little-endian 16-bit chunks of a 64-bit value
+pub fn balance_chunks(value: u64) -> [BaseField; BALANCE_CHUNKS] {
+    std::array::from_fn(|i| BaseField::from(((value >> (RANGE_CHECK_BITS as usize * i)) & 0xFFFF) as u32))
+}
+
+/// Borrow out of each chunk but the top one when computing `a - b`, as `constrain_chunked_sub`
+/// expects; `None` when `a < b`
+pub fn chunked_sub_borrows(a: u64, b: u64) -> Option<[BaseField; BALANCE_CHUNKS - 1]> {
+    if a < b {
+        return None;
+    }
+    Some(std::array::from_fn(|i| {
+        let mask = (1u64 << (RANGE_CHECK_BITS as usize * (i + 1))) - 1;
+        BaseField::from(((a & mask) < (b & mask)) as u32)
+    }))
+}
+
+/// Counts how often each 16-bit value is claimed, for the table's multiplicity column
+#[derive(Debug, Clone)]
+pub struct RangeCheckMultiplicities {
+    counts: Vec<u32>,
+}
+
+impl Default for RangeCheckMultiplicities {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; 1 << RANGE_CHECK_LOG_SIZE],
+        }
+    }
+}
+
+impl RangeCheckMultiplicities {
+    /// Count the claims on `values`, row by row, made with `multiplicity`
+    pub fn add_column(&mut self, values: &BaseColumn, multiplicity: &BaseColumn) -> Result<(), String> {
+        for row in 0..values.len() {
+            let times = multiplicity.at(row).0;
+            if times == 0 {
+                continue;
+            }
+            let value = values.at(row).0;
+            let count = self.counts.get_mut(value as usize).ok_or_else(|| {
+                format!("Range-check claim {} at row {} is not a {}-bit value", value, row, RANGE_CHECK_BITS)
+            })?;
+            *count += times;
+        }
+        Ok(())
+    }
+}
+
+/// Generate the preprocessed trace: the single column of every 16-bit value
+pub fn gen_range_check_preprocessed_trace() -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+    let domain = CanonicCoset::new(RANGE_CHECK_LOG_SIZE).circle_domain();
+    vec![CircleEvaluation::new(domain, range_check_values())]
+}
+
+fn range_check_values() -> BaseColumn {
+    (0..1u32 << RANGE_CHECK_LOG_SIZE).map(BaseField::from).collect()
+}
+
+/// Generate the main trace: the multiplicity column
+pub fn generate_range_check_trace(
+    multiplicities: &RangeCheckMultiplicities,
+) -> (ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>, BaseColumn) {
+    let column: BaseColumn = multiplicities.counts.iter().map(|&count| BaseField::from(count)).collect();
+    let domain = CanonicCoset::new(RANGE_CHECK_LOG_SIZE).circle_domain();
+    (vec![CircleEvaluation::new(domain, column.clone())], column)
+}
+
+/// Generate the range-check table's interaction trace from its multiplicity column
+pub fn gen_range_check_interaction_trace(
+    multiplicities: &BaseColumn,
+    lookup_elements: &RangeCheckElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let values = range_check_values();
+    let mut logup_gen = LogupTraceGenerator::new(RANGE_CHECK_LOG_SIZE);
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (RANGE_CHECK_LOG_SIZE - LOG_N_LANES)) {
+        let q: PackedSecureField = lookup_elements.combine(&[values.data[vec_row]]);
+        col_gen.write_frac(vec_row, -PackedSecureField::from(multiplicities.data[vec_row]), q);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+/// Write the logup columns of claims made by `add_range_check_claims`
+///
+/// Consecutive claims share a column, matching `finalize_logup_in_pairs` when no other relation
+/// entry sits between them; an odd last claim gets a column of its own.
+pub fn write_range_check_claims(
+    logup_gen: &mut LogupTraceGenerator,
+    log_size: u32,
+    lookup_elements: &RangeCheckElements,
+    multiplicity: &BaseColumn,
+    values: &[&BaseColumn],
+) {
+    for pair in &values.iter().chunks(2) {
+        let pair = pair.collect_vec();
+        let mut col_gen = logup_gen.new_col();
+        for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+            let p = PackedSecureField::from(multiplicity.data[vec_row]);
+            let q0: PackedSecureField = lookup_elements.combine(&[pair[0].data[vec_row]]);
+            match pair.get(1) {
+                Some(second) => {
+                    let q1: PackedSecureField = lookup_elements.combine(&[second.data[vec_row]]);
+                    col_gen.write_frac(vec_row, p * (q0 + q1), q0 * q1);
+                }
+                None => col_gen.write_frac(vec_row, p, q0),
+            }
+        }
+        col_gen.finalize_col();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Zero;
+
+    const CLAIMS_LOG_SIZE: u32 = 4;
+
+    fn column(values: &[u32]) -> BaseColumn {
+        let mut column = BaseColumn::zeros(1 << CLAIMS_LOG_SIZE);
+        for (row, &value) in values.iter().enumerate() {
+            column.set(row, BaseField::from(value));
+        }
+        column
+    }
+
+    /// Claimed sums of a claiming component and the table answering it
+    fn claimed_sums(values: &[&BaseColumn], multiplicities: &RangeCheckMultiplicities) -> SecureField {
+        let lookup_elements = RangeCheckElements::dummy();
+        let ones = column(&[1; 1 << CLAIMS_LOG_SIZE]);
+        let mut logup_gen = LogupTraceGenerator::new(CLAIMS_LOG_SIZE);
+        write_range_check_claims(&mut logup_gen, CLAIMS_LOG_SIZE, &lookup_elements, &ones, values);
+        let (_, claims_sum) = logup_gen.finalize_last();
+
+        let (_, table) = generate_range_check_trace(multiplicities);
+        let (_, table_sum) = gen_range_check_interaction_trace(&table, &lookup_elements);
+        claims_sum + table_sum
+    }
+
+    #[test]
+    fn test_in_range_claims_balance() {
+        let low = column(&[0, 1, 0xFFFF, 1234]);
+        let high = column(&[7, 7, 42]);
+        let ones = column(&[1; 1 << CLAIMS_LOG_SIZE]);
+
+        let mut multiplicities = RangeCheckMultiplicities::default();
+        for values in [&low, &high, &low] {
+            multiplicities.add_column(values, &ones).unwrap();
+        }
+        assert_eq!(claimed_sums(&[&low, &high, &low], &multiplicities), SecureField::zero());
+    }
+
+    #[test]
+    fn test_out_of_range_claim_unanswered() {
+        let ones = column(&[1; 1 << CLAIMS_LOG_SIZE]);
+        let in_range = column(&[0xFFFF]);
+        let out_of_range = column(&[1u32 << RANGE_CHECK_BITS]);
+
+        let mut multiplicities = RangeCheckMultiplicities::default();
+        assert!(multiplicities.add_column(&out_of_range, &ones).is_err());
+
+        // Whatever the table counts, no row answers 2^16
+        multiplicities.add_column(&in_range, &ones).unwrap();
+        assert_ne!(claimed_sums(&[&out_of_range], &multiplicities), SecureField::zero());
+    }
+
+    #[test]
+    fn test_chunked_sub_borrows() {
+        assert_eq!(balance_chunks(0x0123_4567_89AB_CDEF), [0xCDEF, 0x89AB, 0x4567, 0x0123].map(BaseField::from));
+        assert_eq!(chunked_sub_borrows(5, 6), None);
+        assert_eq!(chunked_sub_borrows(0x1_0000, 1), Some([1, 0, 0].map(BaseField::from)));
+        assert_eq!(chunked_sub_borrows(1 << 48, 1), Some([1, 1, 1].map(BaseField::from)));
+        assert_eq!(chunked_sub_borrows(u64::MAX, u64::MAX), Some([0, 0, 0].map(BaseField::from)));
+    }
+
+    #[test]
+    fn test_limb_pieces_rebuild_the_limbs() {
+        use crate::utils::poseidon::u256_to_m31_array;
+
+        for value in [0, 1, 0x3FFF_FFFF, 1 << 30, (1 << 60) - 1, 1 << 60, 0x0123_4567_89AB_CDEF, u64::MAX] {
+            let chunks = balance_chunks(value).map(|chunk| chunk.0);
+            let [c1_low, c1_high, c3_low, c3_high, shifted @ ..] = limb_pieces(value).map(|piece| piece.0);
+            assert_eq!(c1_low + (c1_high << 14), chunks[1]);
+            assert_eq!(c3_low + (c3_high << 12), chunks[3]);
+            // Every shifted piece fills at most 16 bits
+            assert_eq!(shifted, [c1_low << 2, c1_high << 14, c3_low << 4, c3_high << 12]);
+            assert!(shifted.iter().all(|&piece| piece < 1 << RANGE_CHECK_BITS));
+
+            let limbs = [chunks[0] + (c1_low << 16), c1_high + (chunks[2] << 2) + (c3_low << 18), c3_high];
+            let expected = u256_to_m31_array(alloy_primitives::U256::from(value));
+            assert_eq!(limbs.to_vec(), expected[..3].iter().map(|limb| limb.value()).collect::<Vec<_>>());
+            assert!(expected[3..].iter().all(|limb| limb.value() == 0));
+        }
+    }
+}
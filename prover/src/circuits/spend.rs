@@ -2,34 +2,87 @@
 // Translates spend.circom to Rust using Circle STARK proofs
 // Reference: proof-of-burn/circuits/spend.circom
 
-use crate::constants::poseidon_coin_prefix;
-use crate::utils::poseidon::{poseidon3, u256_to_m31};
-use alloy_primitives::U256;
+use crate::circuits::validation::{validate_amount, AmountError};
+use crate::coins;
+use crate::constants::{MAINNET_CHAIN_ID, OUTPUT_LIMBS};
+use crate::prover::packaging::limbs_to_u256;
+use crate::utils::commitments::spend_extra;
+use crate::utils::poseidon::u256_to_m31_array;
+use crate::utils::poseidon2_stwo::{permute_padded, poseidon2_permutation, Permutation};
+use alloy_primitives::{Address, U256};
 use crate::field::M31;
+use crate::secret::Secret;
 use serde::{Deserialize, Serialize};
 
 /// Inputs for the Spend circuit
 /// Proves that a coin can be partially spent, creating a new coin with remaining balance
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SpendInputs {
     /// Secret burn key used to derive coins
-    pub burn_key: M31,
+    pub burn_key: Secret<M31>,
     
     /// Total balance in the coin being spent
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
     pub balance: U256,
     
     /// Amount being withdrawn/revealed from this coin
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
     pub withdrawn_balance: U256,
     
     /// Extra commitment (e.g., receiver address, fees)
     pub extra_commitment: M31,
+
+    /// Address the withdrawal is paid to, if the proof is bound to one
+    ///
+    /// With a recipient, the commitment takes `spend_extra(recipient, extra_commitment)` in place
+    /// of `extra_commitment`, so a copy of the proof submitted by someone else still pays this
+    /// address. Without one, whoever sees the proof in the mempool can submit it first and
+    /// direct the withdrawal to themselves: the proof is front-runnable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::schema::AddressHex>"))]
+    pub recipient: Option<Address>,
+    
+    /// Chain id of the chain the coin is spent on, mainnet if absent
+    ///
+    /// Bound into the proof's channel through the `SpendStatement`, so the proof does not
+    /// verify for another chain.
+    #[serde(default = "mainnet_chain_id")]
+    pub chain_id: u64,
+
+    /// Coin the wallet holds on-chain, if it knows it; hex or an integer in input files
+    ///
+    /// `SpendCircuit::new` rejects inputs whose burn key and balance do not hash to it, so a
+    /// balance that drifted from the coin is caught before proving rather than by the contract.
+    #[serde(default, with = "crate::utils::hex_m31::option", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::schema::M31Hex>"))]
+    pub expected_coin: Option<M31>,
+}
+
+fn mainnet_chain_id() -> u64 {
+    MAINNET_CHAIN_ID
+}
+
+impl SpendInputs {
+    /// The extra commitment the spend commits to, with the recipient folded in if there is one
+    pub fn bound_extra_commitment(&self) -> M31 {
+        bound_extra_commitment(self.extra_commitment, self.recipient)
+    }
+}
+
+/// `spend_extra(recipient, extra_commitment)` with a recipient, `extra_commitment` without
+pub(crate) fn bound_extra_commitment(extra_commitment: M31, recipient: Option<Address>) -> M31 {
+    match recipient {
+        Some(recipient) => spend_extra(recipient, extra_commitment),
+        None => extra_commitment,
+    }
 }
 
 /// Public outputs from the Spend circuit
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SpendOutputs {
-    /// Public commitment hash of all values
-    pub commitment: M31,
+    /// Public commitment hash of all values, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
     
     /// The original coin being spent
     pub coin: M31,
@@ -38,13 +91,45 @@ pub struct SpendOutputs {
     pub remaining_coin: M31,
 }
 
+impl SpendOutputs {
+    /// Coin being spent, 0x-prefixed hex
+    pub fn coin_hex(&self) -> String {
+        format!("{:#x}", self.coin.value())
+    }
+
+    /// Remaining coin, 0x-prefixed hex
+    pub fn remaining_coin_hex(&self) -> String {
+        format!("{:#x}", self.remaining_coin.value())
+    }
+
+    /// Commitment packed as the contracts take it, 0x-prefixed hex
+    pub fn commitment_hex(&self) -> String {
+        format!("{:#x}", limbs_to_u256(&self.commitment))
+    }
+}
+
+/// Packed as the contracts take them, in hex
+impl std::fmt::Display for SpendOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "coin={}, remaining_coin={}, commitment={}",
+            self.coin_hex(),
+            self.remaining_coin_hex(),
+            self.commitment_hex()
+        )
+    }
+}
+
 /// Spend circuit implementation
 /// 
 /// Constraints (from spend.circom lines 40-52):
 /// 1. balance >= withdrawnBalance
-/// 2. coin = Poseidon3(COIN_PREFIX, burnKey, balance)
-/// 3. remainingCoin = Poseidon3(COIN_PREFIX, burnKey, balance - withdrawnBalance)
-/// 4. commitment = PublicCommitment([coin, withdrawnBalance, remainingCoin, extraCommitment])
+/// 2. coin = Poseidon2([COIN_PREFIX, burnKey, balance])[0]
+/// 3. remainingCoin = Poseidon2([COIN_PREFIX, burnKey, balance - withdrawnBalance])[0]
+/// 4. commitment = PublicCommitment([coin, withdrawnBalance[0..9], remainingCoin, extraCommitment]),
+///    with the withdrawn balance as its `u256_to_m31_array` limbs and the recipient, if any,
+///    folded into extraCommitment (`SpendInputs::bound_extra_commitment`)
 pub struct SpendCircuit {
     inputs: SpendInputs,
 }
@@ -61,59 +146,22 @@ impl SpendCircuit {
         }
         
         // Validation: amounts fit in maxAmountBytes (31 bytes = 248 bits)
-        const MAX_AMOUNT: U256 = U256::from_limbs([
-            0xFFFFFFFFFFFFFFFF,
-            0xFFFFFFFFFFFFFFFF,
-            0xFFFFFFFFFFFFFFFF,
-            0x00FFFFFFFFFFFFFF,
-        ]);
-        
-        if inputs.balance > MAX_AMOUNT {
-            return Err(SpendError::AmountTooLarge { value: inputs.balance });
-        }
+        validate_amount(inputs.balance, "balance")?;
+        validate_amount(inputs.withdrawn_balance, "withdrawn_balance")?;
+
+        // The trace chunks the low 64 bits of each balance, so anything wider cannot be proven
+        check_trace_width(inputs.balance, "balance")?;
+        check_trace_width(inputs.withdrawn_balance, "withdrawn_balance")?;
         
-        if inputs.withdrawn_balance > MAX_AMOUNT {
-            return Err(SpendError::AmountTooLarge { value: inputs.withdrawn_balance });
-        }
+        // The coin spent must be the one the wallet holds
+        check_expected_coin(&inputs)?;
         
         Ok(Self { inputs })
     }
     
     /// Compute the circuit outputs
     pub fn compute_outputs(&self) -> SpendOutputs {
-        // Constraint: coin = Poseidon3(COIN_PREFIX, burnKey, balance)
-        // Line 43 of spend.circom
-        let balance_m31 = u256_to_m31(self.inputs.balance);
-        let coin = poseidon3([
-            poseidon_coin_prefix(),
-            self.inputs.burn_key,
-            balance_m31,
-        ]);
-        
-        // Constraint: remainingCoin = Poseidon3(COIN_PREFIX, burnKey, balance - withdrawnBalance)
-        // Line 44 of spend.circom
-        let remaining_balance = self.inputs.balance - self.inputs.withdrawn_balance;
-        let remaining_balance_m31 = u256_to_m31(remaining_balance);
-        let remaining_coin = poseidon3([
-            poseidon_coin_prefix(),
-            self.inputs.burn_key,
-            remaining_balance_m31,
-        ]);
-        
-        // Constraint: commitment = PublicCommitment(...)
-        // Lines 46-52 of spend.circom
-        let commitment = compute_spend_commitment(
-            coin,
-            self.inputs.withdrawn_balance,
-            remaining_coin,
-            self.inputs.extra_commitment,
-        );
-        
-        SpendOutputs {
-            commitment,
-            coin,
-            remaining_coin,
-        }
+        compute_spend_outputs(&self.inputs, poseidon2_permutation)
     }
     
     /// Verify the circuit constraints are satisfied
@@ -127,27 +175,70 @@ impl SpendCircuit {
     }
 }
 
-/// Compute the public commitment for Spend circuit
-/// Simplified version of PublicCommitment from proof-of-burn/circuits/utils/public_commitment.circom
-/// 
-/// In practice, this would be:
-/// commitment = Keccak256(coin || withdrawnBalance || remainingCoin || extraCommitment)
-/// 
-/// For M31 compatibility, we use Poseidon instead
-fn compute_spend_commitment(
+/// Require the amount in input field `field` to fit the 64 bits the Spend trace chunks
+pub(crate) fn check_trace_width(value: U256, field: &'static str) -> Result<(), SpendError> {
+    if value > U256::from(u64::MAX) {
+        return Err(SpendError::AmountBeyondTrace { field, value });
+    }
+    Ok(())
+}
+
+/// Require `inputs.expected_coin`, if set, to be the coin their burn key and balance hash to
+pub(crate) fn check_expected_coin(inputs: &SpendInputs) -> Result<(), SpendError> {
+    let Some(expected) = inputs.expected_coin else { return Ok(()) };
+    let computed = coins::coin(*inputs.burn_key.expose_secret(), inputs.balance);
+    if computed != expected {
+        return Err(SpendError::CoinMismatch { expected, computed });
+    }
+    Ok(())
+}
+
+/// Compute the coins and commitment exactly as the Spend trace hashes them
+///
+/// Each value is one call to `permutation` on a zero-padded state; `consistency::check_spend`
+/// holds this path equal to `generate_spend_trace`.
+pub(crate) fn compute_spend_outputs(inputs: &SpendInputs, permutation: Permutation) -> SpendOutputs {
+    let burn_key = *inputs.burn_key.expose_secret();
+
+    // Constraint: coin = Poseidon2([COIN_PREFIX, burnKey, balance])[0]
+    // Line 43 of spend.circom
+    let coin = coins::coin_with(permutation, burn_key, inputs.balance);
+
+    // Constraint: remainingCoin = Poseidon2([COIN_PREFIX, burnKey, balance - withdrawnBalance])[0]
+    // Line 44 of spend.circom
+    let remaining_balance = inputs.balance - inputs.withdrawn_balance;
+    let remaining_coin = coins::coin_with(permutation, burn_key, remaining_balance);
+
+    SpendOutputs {
+        commitment: spend_commitment_with(
+            permutation,
+            coin,
+            inputs.withdrawn_balance,
+            remaining_coin,
+            inputs.bound_extra_commitment(),
+        ),
+        coin,
+        remaining_coin,
+    }
+}
+
+/// Commitment of a spend of `coin`, as `compute_spend_outputs` and `SpendStatement` derive it
+pub(crate) fn spend_commitment_with(
+    permutation: Permutation,
     coin: M31,
     withdrawn_balance: U256,
     remaining_coin: M31,
     extra_commitment: M31,
-) -> M31 {
-    // Convert withdrawn_balance to M31
-    let withdrawn_m31 = u256_to_m31(withdrawn_balance);
-    
-    // Compute commitment using Poseidon hash
-    // This creates a single public value that commits to all circuit outputs
-    use crate::utils::poseidon::poseidon4;
-    
-    poseidon4([coin, withdrawn_m31, remaining_coin, extra_commitment])
+) -> [M31; OUTPUT_LIMBS] {
+    // Constraint: commitment = PublicCommitment(...)
+    // Lines 46-52 of spend.circom, with Poseidon2 in place of Keccak for M31 compatibility.
+    // The withdrawn balance goes in as all of its limbs, so the commitment binds every bit the
+    // contract sees rather than the low 32 bits
+    let mut preimage = vec![coin];
+    preimage.extend(u256_to_m31_array(withdrawn_balance));
+    preimage.extend([remaining_coin, extra_commitment]);
+    let commitment_state = permute_padded(permutation, &preimage);
+    std::array::from_fn(|i| commitment_state[i])
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -158,32 +249,75 @@ pub enum SpendError {
         withdrawn: U256,
     },
     
-    #[error("Amount too large: {value}")]
-    AmountTooLarge {
+    #[error(transparent)]
+    AmountTooLarge(#[from] AmountError),
+
+    #[error("{field} {value} does not fit in the 64 bits the Spend trace holds")]
+    AmountBeyondTrace {
+        field: &'static str,
         value: U256,
     },
+
+    #[error("Coin mismatch: expected {:#x}, the burn key and balance give {:#x}", expected.value(), computed.value())]
+    CoinMismatch {
+        expected: M31,
+        computed: M31,
+    },
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::poseidon::u256_to_m31;
+    
+    #[test]
+    fn test_debug_redacts_burn_key() {
+        let inputs = SpendInputs {
+            burn_key: Secret::new(M31::from(987654)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        };
+        let debug = format!("{:?}", inputs);
+        assert!(debug.contains("burn_key: Secret([REDACTED])"));
+        assert!(!debug.contains("987654"));
+        assert!(debug.contains("extra_commitment: M31(100)"));
+    }
+    
+    #[test]
+    fn test_display_is_hex() {
+        let mut commitment = [M31::zero(); OUTPUT_LIMBS];
+        commitment[0] = M31::from(1);
+        commitment[7] = M31::from(2);
+        let outputs = SpendOutputs { commitment, coin: M31::from(255), remaining_coin: M31::from(16) };
+        assert_eq!(
+            outputs.to_string(),
+            format!("coin=0xff, remaining_coin=0x10, commitment={:#x}", (U256::from(2) << 217) + U256::from(1))
+        );
+    }
     
     #[test]
     fn test_spend_circuit_valid() {
         let inputs = SpendInputs {
-            burn_key: M31::from(12345),
+            burn_key: Secret::new(M31::from(12345)),
             balance: U256::from(1000),
             withdrawn_balance: U256::from(400),
             extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         };
         
         let circuit = SpendCircuit::new(inputs).unwrap();
         let outputs = circuit.compute_outputs();
         
         // Verify coins are computed
-        assert!(outputs.coin.0 > 0);
-        assert!(outputs.remaining_coin.0 > 0);
-        assert!(outputs.commitment.0 > 0);
+        assert!(outputs.coin.value() > 0);
+        assert!(outputs.remaining_coin.value() > 0);
+        assert!(outputs.commitment.iter().all(|limb| limb.value() > 0));
         
         // Coins should be different (different balances)
         assert_ne!(outputs.coin, outputs.remaining_coin);
@@ -192,10 +326,13 @@ mod tests {
     #[test]
     fn test_spend_circuit_insufficient_balance() {
         let inputs = SpendInputs {
-            burn_key: M31::from(12345),
+            burn_key: Secret::new(M31::from(12345)),
             balance: U256::from(100),
             withdrawn_balance: U256::from(200), // More than balance!
             extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         };
         
         let result = SpendCircuit::new(inputs);
@@ -205,10 +342,13 @@ mod tests {
     #[test]
     fn test_spend_circuit_full_withdrawal() {
         let inputs = SpendInputs {
-            burn_key: M31::from(12345),
+            burn_key: Secret::new(M31::from(12345)),
             balance: U256::from(1000),
             withdrawn_balance: U256::from(1000), // Withdraw everything
             extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         };
         
         let circuit = SpendCircuit::new(inputs).unwrap();
@@ -216,7 +356,7 @@ mod tests {
         
         // Remaining coin should represent zero balance
         // (still a valid coin, just with zero balance)
-        assert!(outputs.remaining_coin.0 > 0);
+        assert!(outputs.remaining_coin.value() > 0);
     }
     
     #[test]
@@ -225,17 +365,23 @@ mod tests {
         let extra_commitment = M31::from(100);
         
         let circuit1 = SpendCircuit::new(SpendInputs {
-            burn_key,
+            burn_key: Secret::new(burn_key),
             balance: U256::from(1000),
             withdrawn_balance: U256::from(300),
             extra_commitment,
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         }).unwrap();
         
         let circuit2 = SpendCircuit::new(SpendInputs {
-            burn_key,
+            burn_key: Secret::new(burn_key),
             balance: U256::from(500),
             withdrawn_balance: U256::from(100),
             extra_commitment,
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         }).unwrap();
         
         let outputs1 = circuit1.compute_outputs();
@@ -243,19 +389,149 @@ mod tests {
         
         // Different balances should produce different coins
         assert_ne!(outputs1.coin, outputs2.coin);
+        assert_ne!(outputs1, outputs2);
+        assert_eq!(outputs1, circuit1.compute_outputs());
     }
     
     #[test]
     fn test_spend_verification() {
         let inputs = SpendInputs {
-            burn_key: M31::from(12345),
+            burn_key: Secret::new(M31::from(12345)),
             balance: U256::from(1000),
             withdrawn_balance: U256::from(400),
             extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         };
         
         let circuit = SpendCircuit::new(inputs).unwrap();
         assert!(circuit.verify().is_ok());
     }
+
+    #[test]
+    fn test_amount_cap() {
+        let spend = |balance: U256| {
+            SpendCircuit::new(SpendInputs {
+                burn_key: Secret::new(M31::from(12345)),
+                balance,
+                withdrawn_balance: U256::from(1),
+                extra_commitment: M31::from(100),
+                recipient: None,
+                chain_id: MAINNET_CHAIN_ID,
+                expected_coin: None,
+            })
+        };
+        let cap = U256::from(1) << 248;
+        match spend(cap) {
+            Err(SpendError::AmountTooLarge(e)) => assert_eq!(e.field, "balance"),
+            other => panic!("expected AmountTooLarge, got {:?}", other.err()),
+        }
+
+        // Below the cap, the trace only holds 64-bit balances
+        let widest = U256::from(u64::MAX);
+        assert!(spend(widest).is_ok());
+        for balance in [widest + U256::from(1), cap - U256::from(1)] {
+            match spend(balance) {
+                Err(SpendError::AmountBeyondTrace { field, value }) => {
+                    assert_eq!((field, value), ("balance", balance));
+                }
+                other => panic!("expected AmountBeyondTrace, got {:?}", other.err()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_commitment_binds_full_withdrawn_balance() {
+        // Same low 32 bits: one M31 of the withdrawn balance could not tell these apart
+        let spend = |withdrawn_balance: U256| {
+            SpendCircuit::new(SpendInputs {
+                burn_key: Secret::new(M31::from(12345)),
+                balance: U256::from(1u64 << 40),
+                withdrawn_balance,
+                extra_commitment: M31::from(100),
+                recipient: None,
+                chain_id: MAINNET_CHAIN_ID,
+                expected_coin: None,
+            })
+            .unwrap()
+            .compute_outputs()
+        };
+        let small = U256::from(1000);
+        let large = small + (U256::from(1) << 32);
+        assert_eq!(u256_to_m31(small), u256_to_m31(large));
+
+        let (a, b) = (spend(small), spend(large));
+        // The remaining balances are 2^32 apart, and the coins hash every limb of them
+        assert_eq!(a.coin, b.coin);
+        assert_ne!(a.remaining_coin, b.remaining_coin);
+        assert_ne!(a.commitment, b.commitment);
+    }
+
+    #[test]
+    fn test_commitment_binds_recipient() {
+        let spend = |recipient: Option<Address>| {
+            SpendCircuit::new(SpendInputs { recipient, ..inputs_expecting(None) })
+                .unwrap()
+                .compute_outputs()
+        };
+        let unbound = spend(None);
+        let (a, b) = (spend(Some(Address::repeat_byte(0x33))), spend(Some(Address::repeat_byte(0x44))));
+        // Only the commitment moves: the coins do not depend on who is paid
+        assert_eq!(a.coin, b.coin);
+        assert_eq!(a.remaining_coin, b.remaining_coin);
+        assert_ne!(a.commitment, b.commitment);
+        assert_ne!(a.commitment, unbound.commitment);
+        assert_ne!(b.commitment, unbound.commitment);
+    }
+
+    fn inputs_expecting(expected_coin: Option<M31>) -> SpendInputs {
+        SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin,
+        }
+    }
+
+    #[test]
+    fn test_expected_coin_matches() {
+        let coin = coins::coin(M31::from(12345), U256::from(1000));
+        let outputs = SpendCircuit::new(inputs_expecting(Some(coin))).unwrap().compute_outputs();
+        assert_eq!(outputs.coin, coin);
+    }
+
+    #[test]
+    fn test_expected_coin_mismatch() {
+        let computed = coins::coin(M31::from(12345), U256::from(1000));
+        // The coin of a balance that drifted from the one the wallet recorded
+        let expected = coins::coin(M31::from(12345), U256::from(999));
+        match SpendCircuit::new(inputs_expecting(Some(expected))) {
+            Err(SpendError::CoinMismatch { expected: e, computed: c }) => {
+                assert_eq!((e, c), (expected, computed));
+            }
+            other => panic!("expected CoinMismatch, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_expected_coin_absent_from_older_inputs() {
+        // An input file written before `expected_coin` existed
+        let json = r#"{"burn_key": 12345, "balance": "0x3e8", "withdrawn_balance": "0x190", "extra_commitment": 100}"#;
+        let inputs: SpendInputs = serde_json::from_str(json).unwrap();
+        assert_eq!(inputs.expected_coin, None);
+        assert!(SpendCircuit::new(inputs).is_ok());
+
+        // Written back without the field, and read in hex when present
+        let written = serde_json::to_value(inputs_expecting(None)).unwrap();
+        assert!(written.get("expected_coin").is_none());
+        let coin = coins::coin(M31::from(12345), U256::from(1000));
+        let json = json.replace('}', &format!(r#", "expected_coin": "{:#x}"}}"#, coin.value()));
+        let inputs: SpendInputs = serde_json::from_str(&json).unwrap();
+        assert_eq!(inputs.expected_coin, Some(coin));
+    }
 }
 
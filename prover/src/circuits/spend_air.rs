@@ -1,25 +1,42 @@
 // Spend AIR (Algebraic Intermediate Representation) for Stwo
 // Implements constraints for partial coin spending
+// The 16-bit balance chunks are looked up in the range-check component (range_check_air.rs)
 
 use itertools::Itertools;
+use zeroize::Zeroize;
 use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
 use stwo_prover::core::poly::circle::CanonicCoset;
 use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
 use stwo_prover::prover::backend::simd::SimdBackend;
 use stwo_prover::prover::backend::{Col, Column};
 use stwo_prover::prover::poly::circle::CircleEvaluation;
 use stwo_prover::prover::poly::BitReversedOrder;
-use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval};
+use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator};
 
+use crate::circuits::coin_trace::coin_permutation;
+use crate::circuits::gadgets::less_equal::{constrain_less_equal, less_equal_witness};
+use crate::circuits::layout::{next_masks, SPEND_LAYOUT};
+use crate::circuits::range_check_air::{
+    add_range_check_claims, balance_chunks, constrain_chunk_limbs, constrain_limb_chunks,
+    limb_pieces, write_range_check_claims, RangeCheckElements, RangeCheckMultiplicities,
+    BALANCE_CHUNKS, LIMB_PIECE_COLUMNS,
+};
 use crate::circuits::spend::SpendInputs;
-use crate::utils::poseidon2_stwo::poseidon2_permutation;
+use crate::circuits::stats::{circuit_stats, CircuitStats};
+use crate::circuits::trace_writer::TraceWriter;
+use crate::constants::OUTPUT_LIMBS;
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon::{u256_to_m31_array, U256_M31_LIMBS};
+use crate::utils::poseidon2_stwo::{custom_m31_to_basefield, poseidon2_permutation};
 
 /// Helper constant for zero field element
 const ZERO: BaseField = BaseField::from_u32_unchecked(0);
 
 /// Number of columns in the Spend trace
 /// 
-/// Trace structure:
+/// Trace structure (see `SpendTraceLayout` for the named ranges):
 /// 0. burn_key (private witness)
 /// 1. balance_low (lower 128 bits)
 /// 2. balance_high (upper 128 bits)
@@ -28,9 +45,42 @@ const ZERO: BaseField = BaseField::from_u32_unchecked(0);
 /// 5. extra_commitment
 /// 6. coin (computed)
 /// 7. remaining_coin (computed)
-/// 8. commitment (public output)
-/// 9-15. intermediate_poseidon_state
-pub const NUM_SPEND_COLUMNS: usize = 16;
+/// 8-15. commitment limbs (public output)
+/// 16-19. intermediate_poseidon_state
+/// 20-28. withdrawn_balance as 30-bit limbs, as the commitment absorbs it
+/// 29-40. balance, withdrawn and remaining balance chunks
+/// 41-48. pieces tying the withdrawn limbs to their chunks
+/// 49-51. remaining-balance borrows
+pub const NUM_SPEND_COLUMNS: usize = SPEND_LAYOUT.total_columns();
+
+/// Human-readable name of every Spend trace column, in trace order (see `NUM_SPEND_COLUMNS`)
+pub fn spend_column_labels() -> Vec<String> {
+    let mut labels: Vec<String> = [
+        "burn_key",
+        "balance_low",
+        "balance_high",
+        "withdrawn_balance_low",
+        "withdrawn_balance_high",
+        "extra_commitment",
+        "coin",
+        "remaining_coin",
+    ]
+    .iter()
+    .map(|label| label.to_string())
+    .collect();
+    labels.extend((0..OUTPUT_LIMBS).map(|i| format!("commitment_limb_{}", i)));
+    labels.extend(
+        ["coin_state_1", "coin_state_2", "remaining_coin_state_1", "remaining_coin_state_2"]
+            .iter()
+            .map(|label| label.to_string()),
+    );
+    for (name, range) in &SPEND_LAYOUT.ranges()[6..] {
+        labels.extend((0..range.len()).map(|i| format!("{}_{}", name, i)));
+    }
+
+    debug_assert_eq!(labels.len(), NUM_SPEND_COLUMNS);
+    labels
+}
 
 pub type SpendComponent = FrameworkComponent<SpendEval>;
 
@@ -40,6 +90,15 @@ pub type SpendComponent = FrameworkComponent<SpendEval>;
 pub struct SpendEval {
     /// Log2 of the number of rows in the trace
     pub log_n_rows: u32,
+    /// Lookup elements for the balance chunks answered by the range-check component
+    pub range_check_lookup: RangeCheckElements,
+}
+
+impl SpendEval {
+    /// Trace, interaction and constraint counts of this AIR
+    pub fn statistics(&self) -> CircuitStats {
+        circuit_stats(self)
+    }
 }
 
 impl FrameworkEval for SpendEval {
@@ -54,79 +113,158 @@ impl FrameworkEval for SpendEval {
     
     /// Evaluate constraints at a single row
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let layout = SPEND_LAYOUT;
+
         // Read trace columns
-        let burn_key = eval.next_trace_mask();
-        let balance_low = eval.next_trace_mask();
-        let balance_high = eval.next_trace_mask();
-        let withdrawn_balance_low = eval.next_trace_mask();
-        let withdrawn_balance_high = eval.next_trace_mask();
-        let extra_commitment = eval.next_trace_mask();
-        let coin = eval.next_trace_mask();
-        let remaining_coin = eval.next_trace_mask();
-        let commitment = eval.next_trace_mask();
+        let [
+            burn_key,
+            balance_low,
+            balance_high,
+            withdrawn_balance_low,
+            withdrawn_balance_high,
+            _extra_commitment,
+        ] = next_masks::<E, 6>(&mut eval, layout.inputs());
+        let [_coin] = next_masks::<E, 1>(&mut eval, layout.coin());
+        let [_remaining_coin] = next_masks::<E, 1>(&mut eval, layout.remaining_coin());
+        let _commitment: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.commitment_out());
         
         // Intermediate Poseidon state columns
-        let _poseidon_state_0 = eval.next_trace_mask();
-        let _poseidon_state_1 = eval.next_trace_mask();
-        let _poseidon_state_2 = eval.next_trace_mask();
-        let _poseidon_state_3 = eval.next_trace_mask();
-        let _poseidon_state_4 = eval.next_trace_mask();
-        let _poseidon_state_5 = eval.next_trace_mask();
-        let _poseidon_state_6 = eval.next_trace_mask();
+        let _coin_state: [E::F; 2] = next_masks(&mut eval, layout.coin_state());
+        let _remaining_coin_state: [E::F; 2] = next_masks(&mut eval, layout.remaining_coin_state());
+        let withdrawn_balance_limbs: [E::F; U256_M31_LIMBS] =
+            next_masks(&mut eval, layout.withdrawn_balance_limbs());
+
+        // Chunked remaining-balance subtraction
+        let balance_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.balance_chunks());
+        let withdrawn_balance_chunks: [E::F; BALANCE_CHUNKS] =
+            next_masks(&mut eval, layout.withdrawn_balance_chunks());
+        let remaining_balance_chunks: [E::F; BALANCE_CHUNKS] =
+            next_masks(&mut eval, layout.remaining_balance_chunks());
+        let withdrawn_limb_pieces: [E::F; LIMB_PIECE_COLUMNS] =
+            next_masks(&mut eval, layout.withdrawn_limb_pieces());
+        let remaining_balance_borrows: [E::F; BALANCE_CHUNKS - 1] =
+            next_masks(&mut eval, layout.remaining_balance_borrows());
         
         // === CONSTRAINT 1: Balance validation ===
         // withdrawn_balance <= balance
-        // This would need proper range checks in production
+        // Constraint indices (used by `debug::check_spend_constraints` reports):
+        //   0      placeholder
+        //   1-4    balance_low/high, withdrawn_balance_low/high == their 16-bit chunks
+        //   5-7    remaining_balance_borrows are boolean
+        //   8-11   balance_chunks - withdrawn_balance_chunks == remaining_balance_chunks
+        //   12-15  withdrawn_limb_pieces are their pieces shifted to the top of 16 bits
+        //   16-17  withdrawn_balance_chunks 1 and 3 == their pieces
+        //   18-26  withdrawn_balance_limbs == the limbs of withdrawn_balance_chunks
+        // Every chunk and piece is range-checked on every row; padding rows are all zero, which
+        // is in range
         
         // === CONSTRAINT 2: Coin computation ===
-        // coin = Poseidon3([COIN_PREFIX, burn_key, balance])
+        // coin = Poseidon2([COIN_PREFIX, burn_key, balance limbs])[0] (`coins::coin`)
         // 
         // In production, this would be a full Poseidon AIR constraint
         
         // === CONSTRAINT 3: Remaining coin computation ===
         // remaining_balance = balance - withdrawn_balance
-        // remaining_coin = Poseidon3([COIN_PREFIX, burn_key, remaining_balance])
-        // BaseField subtraction handles underflow correctly with modular arithmetic,
-        // but we validate in trace generation that withdrawn_balance <= balance
-        let _remaining_balance_low = balance_low.clone() - withdrawn_balance_low.clone();
-        let _remaining_balance_high = balance_high.clone() - withdrawn_balance_high.clone();
+        // remaining_coin = Poseidon2([COIN_PREFIX, burn_key, remaining_balance limbs])[0]
+        // The limbs are those of the remaining chunks, not of the 32-bit limb differences, which
+        // would be off by 2^32 whenever the subtraction borrows into the high limb
         
         // === CONSTRAINT 4: Commitment computation ===
-        // commitment = Hash(coin, withdrawn_balance, remaining_coin, extra_commitment)
+        // commitment = Hash(coin, withdrawn_balance_limbs, remaining_coin, extra_commitment)
+        // The limbs are rebuilt exactly from the range-checked withdrawn chunks (constraints 12-26)
         
         // === PLACEHOLDER CONSTRAINTS ===
         // These ensure the trace compiles and columns are used
         // TODO: Replace with actual cryptographic constraints
         eval.add_constraint(burn_key.clone() - burn_key.clone());
+
+        let limbs = [
+            (balance_low, &balance_chunks[..2]),
+            (balance_high, &balance_chunks[2..]),
+            (withdrawn_balance_low, &withdrawn_balance_chunks[..2]),
+            (withdrawn_balance_high, &withdrawn_balance_chunks[2..]),
+        ];
+        for (limb, chunks) in limbs {
+            constrain_limb_chunks(&mut eval, limb, [chunks[0].clone(), chunks[1].clone()]);
+        }
+        constrain_less_equal(
+            &mut eval,
+            &withdrawn_balance_chunks,
+            &balance_chunks,
+            &remaining_balance_chunks,
+            &remaining_balance_borrows,
+        );
+        constrain_chunk_limbs(
+            &mut eval,
+            &withdrawn_balance_chunks,
+            &withdrawn_limb_pieces,
+            withdrawn_balance_limbs,
+        );
+        let range_checked = [
+            &balance_chunks[..],
+            &withdrawn_balance_chunks,
+            &remaining_balance_chunks,
+            &withdrawn_limb_pieces,
+        ]
+        .concat();
+        add_range_check_claims(
+            &mut eval,
+            &self.range_check_lookup,
+            E::EF::from(E::F::from(BaseField::from(1))),
+            &range_checked,
+        );
+        eval.finalize_logup_in_pairs();
         
         eval
     }
 }
 
-/// Generate the execution trace for Spend
+/// Columns of a Spend trace the interaction trace is generated from
+#[derive(Debug, Clone)]
+pub struct SpendLookupData {
+    /// Balance chunk and limb piece columns claimed in the range-check table, in `SpendEval` order
+    pub range_checked: Vec<BaseColumn>,
+    /// How often each row claims its chunks: once, padding rows included
+    pub multiplicity: BaseColumn,
+}
+
+impl SpendLookupData {
+    /// Lookup data read back from a Spend trace, after any edits to it
+    pub fn from_trace(trace: &[CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>]) -> Self {
+        let layout = SPEND_LAYOUT;
+        let range_checked = trace[layout.range_checked()]
+            .iter()
+            .map(|col| col.values.clone())
+            .collect_vec();
+        let multiplicity = (0..trace[0].len()).map(|_| BaseField::from(1)).collect();
+        Self { range_checked, multiplicity }
+    }
+}
+
+/// Generate the execution trace for Spend and its lookup data
+#[tracing::instrument(level = "debug", skip_all, fields(log_size))]
 pub fn generate_spend_trace(
     log_size: u32,
     inputs: &SpendInputs,
-) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
+) -> (ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>, SpendLookupData) {
     let size = 1 << log_size;
     
-    // Create empty columns
-    let mut trace = (0..NUM_SPEND_COLUMNS)
-        .map(|_| Col::<SimdBackend, BaseField>::zeros(size))
-        .collect_vec();
-    
     // Validate M31 values are in correct range before conversion
     use crate::constants::M31_PRIME;
-    let burn_key_val = inputs.burn_key.value();
+    // Panic messages never include the burn key itself
+    let mut burn_key_val = inputs.burn_key.expose_secret().value();
     if burn_key_val >= M31_PRIME {
-        panic!("burn_key value {} exceeds M31 prime {}", burn_key_val, M31_PRIME);
+        panic!("burn_key exceeds M31 prime {}", M31_PRIME);
     }
-    let extra_commitment_val = inputs.extra_commitment.value();
+    // The recipient, if any, is folded in here: the AIR sees one extra commitment either way
+    let extra_commitment_val = inputs.bound_extra_commitment().value();
     if extra_commitment_val >= M31_PRIME {
         panic!("extra_commitment value {} exceeds M31 prime {}", extra_commitment_val, M31_PRIME);
     }
     
     // Extract balance parts and validate
+    let balance_u64 = inputs.balance.as_limbs()[0];
+    let withdrawn_balance_u64 = inputs.withdrawn_balance.as_limbs()[0];
     let balance_low_u32 = (inputs.balance.as_limbs()[0] & 0xFFFFFFFF) as u32;
     let balance_high_u32 = ((inputs.balance.as_limbs()[0] >> 32) & 0xFFFFFFFF) as u32;
     let withdrawn_balance_low_u32 = (inputs.withdrawn_balance.as_limbs()[0] & 0xFFFFFFFF) as u32;
@@ -146,7 +284,7 @@ pub fn generate_spend_trace(
     // Convert u32 values to BaseField
     // BaseField::from() automatically reduces modulo M31_PRIME, so values can be any u32
     // For M31 values that are already validated, we use from_u32_unchecked for efficiency
-    let burn_key_field = BaseField::from_u32_unchecked(burn_key_val);
+    let mut burn_key_field = BaseField::from_u32_unchecked(burn_key_val);
     let balance_low = BaseField::from(balance_low_u32);
     let balance_high = BaseField::from(balance_high_u32);
     let withdrawn_balance_low = BaseField::from(withdrawn_balance_low_u32);
@@ -155,82 +293,127 @@ pub fn generate_spend_trace(
     
     // Compute derived values using Poseidon2
     
-    // coin = Poseidon3([COIN_PREFIX, burn_key, balance])
-    let coin_state = [
-        BaseField::from_u32_unchecked(2), // COIN_PREFIX
-        burn_key_field,
-        balance_low,
-        ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO,
-    ];
-    let coin_output = poseidon2_permutation(coin_state);
+    // coin = Poseidon2([COIN_PREFIX, burn_key, balance limbs])[0]
+    let coin_output = coin_permutation(burn_key_field, balance_u64);
     let coin = coin_output[0];
     
-    // remaining_coin = Poseidon3([COIN_PREFIX, burn_key, remaining_balance])
+    // remaining_coin = Poseidon2([COIN_PREFIX, burn_key, remaining_balance limbs])[0]
     // Safe to subtract now - we validated withdrawn_balance <= balance above
-    // BaseField subtraction handles underflow correctly with modular arithmetic
-    let remaining_balance_low = balance_low - withdrawn_balance_low;
-    let remaining_balance_high = balance_high - withdrawn_balance_high;
-    
-    let remaining_coin_state = [
-        BaseField::from_u32_unchecked(2), // COIN_PREFIX
-        burn_key_field,
-        remaining_balance_low,
-        ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO,
-    ];
-    let remaining_coin_output = poseidon2_permutation(remaining_coin_state);
+    let remaining_balance_u64 = balance_u64 - withdrawn_balance_u64;
+    let remaining_coin_output = coin_permutation(burn_key_field, remaining_balance_u64);
     let remaining_coin = remaining_coin_output[0];
     
-    // commitment = Hash(coin, withdrawn_balance, remaining_coin, extra_commitment)
-    let commitment_state = [
-        coin,
-        withdrawn_balance_low,
-        remaining_coin,
-        extra_commitment_field,
-        ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO,
-    ];
+    // commitment = Hash(coin, withdrawn_balance_limbs, remaining_coin, extra_commitment)
+    let withdrawn_balance_limbs: Vec<BaseField> =
+        u256_to_m31_array(inputs.withdrawn_balance).into_iter().map(custom_m31_to_basefield).collect();
+    let mut commitment_state = [ZERO; 16];
+    commitment_state[0] = coin;
+    commitment_state[1..=U256_M31_LIMBS].copy_from_slice(&withdrawn_balance_limbs);
+    commitment_state[U256_M31_LIMBS + 1] = remaining_coin;
+    commitment_state[U256_M31_LIMBS + 2] = extra_commitment_field;
     let commitment_output = poseidon2_permutation(commitment_state);
-    let commitment = commitment_output[0];
     
-    // Fill the trace
-    // For SIMD backend, we fill vec_index 0 (first SIMD lane)
-    let vec_index = 0;
-    trace[0].data[vec_index] = burn_key_field.into();
-    trace[1].data[vec_index] = balance_low.into();
-    trace[2].data[vec_index] = balance_high.into();
-    trace[3].data[vec_index] = withdrawn_balance_low.into();
-    trace[4].data[vec_index] = withdrawn_balance_high.into();
-    trace[5].data[vec_index] = extra_commitment_field.into();
-    trace[6].data[vec_index] = coin.into();
-    trace[7].data[vec_index] = remaining_coin.into();
-    trace[8].data[vec_index] = commitment.into();
-    trace[9].data[vec_index] = coin_output[1].into();
-    trace[10].data[vec_index] = coin_output[2].into();
-    trace[11].data[vec_index] = remaining_coin_output[1].into();
-    trace[12].data[vec_index] = remaining_coin_output[2].into();
-    trace[13].data[vec_index] = commitment_output[1].into();
-    trace[14].data[vec_index] = commitment_output[2].into();
-    trace[15].data[vec_index] = ZERO.into();
+    // Fill the witness row range by range
+    let layout = SPEND_LAYOUT;
+    let mut writer = TraceWriter::new(layout, 1);
+    writer.set(layout.inputs(), 0, &[
+        burn_key_field,
+        balance_low,
+        balance_high,
+        withdrawn_balance_low,
+        withdrawn_balance_high,
+        extra_commitment_field,
+    ]);
+    writer.set(layout.coin(), 0, &[coin]);
+    writer.set(layout.remaining_coin(), 0, &[remaining_coin]);
+    writer.set(layout.commitment_out(), 0, &commitment_output[..OUTPUT_LIMBS]);
+    writer.set(layout.coin_state(), 0, &coin_output[1..3]);
+    writer.set(layout.remaining_coin_state(), 0, &remaining_coin_output[1..3]);
+    writer.set(layout.withdrawn_balance_limbs(), 0, &withdrawn_balance_limbs);
+    writer.set(layout.balance_chunks(), 0, &balance_chunks(balance_u64));
+    writer.set(layout.withdrawn_balance_chunks(), 0, &balance_chunks(withdrawn_balance_u64));
+    let remaining_balance = less_equal_witness(withdrawn_balance_u64, balance_u64)
+        .expect("withdrawn_balance <= balance was checked above");
+    writer.set(layout.remaining_balance_chunks(), 0, &remaining_balance.diff);
+    writer.set(layout.withdrawn_limb_pieces(), 0, &limb_pieces(withdrawn_balance_u64));
+    writer.set(layout.remaining_balance_borrows(), 0, &remaining_balance.borrows);
+    let mut columns = writer.finalize().unwrap_or_else(|e| panic!("Spend trace: {}", e));
+
+    // For SIMD backend, vec_index 0 broadcasts the witness row to the first N_LANES rows
+    let trace = columns
+        .iter()
+        .map(|values| {
+            let mut col = Col::<SimdBackend, BaseField>::zeros(size);
+            col.data[0] = values[0].into();
+            col
+        })
+        .collect_vec();
+    debug_assert_eq!(trace.len(), NUM_SPEND_COLUMNS);
+    for column in &mut columns {
+        zeroize_base_fields(column);
+    }
+
+    // Wipe key-bearing temporaries; the trace columns now hold the only copy
+    burn_key_val.zeroize();
+    zeroize_base_fields(std::slice::from_mut(&mut burn_key_field));
     
     // Convert to CircleEvaluations
     let domain = CanonicCoset::new(log_size).circle_domain();
-    trace
+    let trace = trace
         .into_iter()
         .map(|col| CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(domain, col))
-        .collect_vec()
+        .collect_vec();
+    let lookup_data = SpendLookupData::from_trace(&trace);
+    (trace, lookup_data)
+}
+
+/// Range-check table multiplicities answering the claims of a Spend trace
+pub fn spend_range_check_multiplicities(lookup_data: &SpendLookupData) -> Result<RangeCheckMultiplicities, String> {
+    let mut multiplicities = RangeCheckMultiplicities::default();
+    for column in &lookup_data.range_checked {
+        multiplicities.add_column(column, &lookup_data.multiplicity)?;
+    }
+    Ok(multiplicities)
+}
+
+/// Generate the Spend interaction trace for the balance chunk range checks
+pub fn gen_spend_interaction_trace(
+    log_size: u32,
+    lookup_data: &SpendLookupData,
+    range_check_lookup: &RangeCheckElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    write_range_check_claims(
+        &mut logup_gen,
+        log_size,
+        range_check_lookup,
+        &lookup_data.multiplicity,
+        &lookup_data.range_checked.iter().collect_vec(),
+    );
+    logup_gen.finalize_last()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::circuits::range_check_air::RANGE_CHECK_BITS;
+    use crate::constants::MAINNET_CHAIN_ID;
     use crate::field::M31;
+    use crate::secret::Secret;
     use alloy_primitives::U256;
     
     fn create_test_inputs() -> SpendInputs {
         SpendInputs {
-            burn_key: M31::from(12345),
+            burn_key: Secret::new(M31::from(12345)),
             balance: U256::from(1000),
             withdrawn_balance: U256::from(400),
             extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
         }
     }
     
@@ -239,7 +422,7 @@ mod tests {
         let inputs = create_test_inputs();
         let log_size = 4; // 16 rows
         
-        let trace = generate_spend_trace(log_size, &inputs);
+        let (trace, lookup_data) = generate_spend_trace(log_size, &inputs);
         
         // Verify we have the correct number of columns
         assert_eq!(trace.len(), NUM_SPEND_COLUMNS);
@@ -248,11 +431,75 @@ mod tests {
         for col in &trace {
             assert_eq!(col.len(), 1 << log_size);
         }
+        assert_eq!(lookup_data.range_checked.len(), 3 * BALANCE_CHUNKS + LIMB_PIECE_COLUMNS);
+        assert_eq!(lookup_data.range_checked[0].to_cpu(), trace[SPEND_LAYOUT.balance_chunks().start].values.to_cpu());
     }
     
+    #[test]
+    fn test_column_labels_match_layout() {
+        let labels = spend_column_labels();
+        assert_eq!(labels.len(), NUM_SPEND_COLUMNS);
+        assert_eq!(labels.iter().collect::<std::collections::HashSet<_>>().len(), NUM_SPEND_COLUMNS);
+        assert_eq!(labels[8], "commitment_limb_0");
+        assert_eq!(labels[SPEND_LAYOUT.balance_chunks().start], "balance_chunks_0");
+        assert_eq!(labels[NUM_SPEND_COLUMNS - 1], "remaining_balance_borrows_2");
+    }
+
+    #[test]
+    fn test_range_check_claims_answered() {
+        use crate::circuits::range_check_air::{gen_range_check_interaction_trace, generate_range_check_trace};
+        use num_traits::Zero;
+
+        let log_size = 4;
+        let (_, lookup_data) = generate_spend_trace(log_size, &create_test_inputs());
+        let lookup_elements = RangeCheckElements::dummy();
+        let (_, claims_sum) = gen_spend_interaction_trace(log_size, &lookup_data, &lookup_elements);
+
+        let multiplicities = spend_range_check_multiplicities(&lookup_data).unwrap();
+        let (_, table) = generate_range_check_trace(&multiplicities);
+        let (_, table_sum) = gen_range_check_interaction_trace(&table, &lookup_elements);
+        assert_eq!(claims_sum + table_sum, SecureField::zero());
+    }
+
+    #[test]
+    fn test_corrupted_lookup_data_rejected() {
+        use crate::circuits::range_check_air::{gen_range_check_interaction_trace, generate_range_check_trace};
+        use num_traits::Zero;
+
+        let log_size = 4;
+        let (_, lookup_data) = generate_spend_trace(log_size, &create_test_inputs());
+        let lookup_elements = RangeCheckElements::dummy();
+        let multiplicities = spend_range_check_multiplicities(&lookup_data).unwrap();
+        let (_, table) = generate_range_check_trace(&multiplicities);
+        let (_, table_sum) = gen_range_check_interaction_trace(&table, &lookup_elements);
+
+        // A claim on another in-range chunk no longer cancels against the trace's table
+        let mut corrupted = lookup_data.clone();
+        let chunk = corrupted.range_checked[0].at(0);
+        corrupted.range_checked[0].set(0, chunk + BaseField::from(1));
+        let (_, claims_sum) = gen_spend_interaction_trace(log_size, &corrupted, &lookup_elements);
+        assert_ne!(claims_sum + table_sum, SecureField::zero());
+
+        // A chunk past 16 bits has no table entry at all
+        corrupted.range_checked[0].set(0, BaseField::from(1 << RANGE_CHECK_BITS));
+        assert!(spend_range_check_multiplicities(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_statistics_match_traces() {
+        let log_size = 4;
+        let eval = crate::debug::spend_eval(log_size);
+        let stats = eval.statistics();
+        assert_eq!(stats.n_trace_columns, NUM_SPEND_COLUMNS);
+
+        let (_, lookup_data) = generate_spend_trace(log_size, &create_test_inputs());
+        let (interaction_trace, _) = gen_spend_interaction_trace(log_size, &lookup_data, &eval.range_check_lookup);
+        assert_eq!(stats.n_interaction_columns, interaction_trace.len());
+    }
+
     #[test]
     fn test_spend_eval_structure() {
-        let eval = SpendEval { log_n_rows: 4 };
+        let eval = SpendEval { log_n_rows: 4, range_check_lookup: RangeCheckElements::dummy() };
         
         assert_eq!(eval.log_size(), 4);
         assert_eq!(eval.max_constraint_log_degree_bound(), 6); // log_n_rows + LOG_EXPAND (4 + 2)
@@ -0,0 +1,230 @@
+// Split Circuit - Stwo AIR Implementation
+// Splits one coin into two private child coins without revealing any amount
+// Follows the coin construction of spend.circom; nothing is withdrawn publicly
+
+use crate::coins;
+use crate::constants::OUTPUT_LIMBS;
+use crate::prover::packaging::limbs_to_u256;
+use crate::utils::poseidon2_stwo::{permute_padded, poseidon2_permutation};
+use alloy_primitives::U256;
+use crate::field::M31;
+use crate::secret::Secret;
+use serde::{Deserialize, Serialize};
+
+/// Inputs for the Split circuit
+/// Proves that a coin is split into two child coins whose balances sum to the coin's balance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitInputs {
+    /// Secret burn key used to derive the coin and both children
+    pub burn_key: Secret<M31>,
+
+    /// Total balance in the coin being split
+    pub balance: U256,
+
+    /// Balance of the first child coin
+    pub amount_a: U256,
+
+    /// Balance of the second child coin, `balance - amount_a`
+    pub amount_b: U256,
+
+    /// Extra commitment (e.g., receiver hint, fees)
+    pub extra_commitment: M31,
+}
+
+/// Public outputs from the Split circuit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitOutputs {
+    /// Public commitment hash of all values, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
+
+    /// The original coin being split
+    pub coin: M31,
+
+    /// The child coin holding `amount_a`
+    pub child_coin_a: M31,
+
+    /// The child coin holding `amount_b`
+    pub child_coin_b: M31,
+}
+
+/// Packed as the contracts take them, in hex
+impl std::fmt::Display for SplitOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "coin={:#x}, child_coin_a={:#x}, child_coin_b={:#x}, commitment={:#x}",
+            self.coin.value(),
+            self.child_coin_a.value(),
+            self.child_coin_b.value(),
+            limbs_to_u256(&self.commitment)
+        )
+    }
+}
+
+/// Split circuit implementation
+///
+/// Constraints:
+/// 1. amountA + amountB == balance, with amountA <= balance
+/// 2. coin = Poseidon2([COIN_PREFIX, burnKey, balance])[0]
+/// 3. childCoinA = Poseidon2([COIN_PREFIX, burnKey, amountA])[0]
+/// 4. childCoinB = Poseidon2([COIN_PREFIX, burnKey, amountB])[0]
+/// 5. commitment = Poseidon2([coin, childCoinA, childCoinB, extraCommitment])[..8]
+pub struct SplitCircuit {
+    inputs: SplitInputs,
+}
+
+impl SplitCircuit {
+    /// Create a new Split circuit with given inputs
+    pub fn new(inputs: SplitInputs) -> Result<Self, SplitError> {
+        // Validation: amounts fit the 64 bits the Split trace chunks
+        for value in [inputs.balance, inputs.amount_a, inputs.amount_b] {
+            if value > U256::from(u64::MAX) {
+                return Err(SplitError::AmountTooLarge { value });
+            }
+        }
+
+        // Validation: amountA + amountB == balance (no value created or destroyed)
+        if inputs.amount_a > inputs.balance || inputs.balance - inputs.amount_a != inputs.amount_b {
+            return Err(SplitError::AmountMismatch {
+                balance: inputs.balance,
+                amount_a: inputs.amount_a,
+                amount_b: inputs.amount_b,
+            });
+        }
+
+        Ok(Self { inputs })
+    }
+
+    /// Compute the circuit outputs
+    pub fn compute_outputs(&self) -> SplitOutputs {
+        let burn_key = *self.inputs.burn_key.expose_secret();
+        let coin = coins::coin(burn_key, self.inputs.balance);
+        let child_coin_a = coins::coin(burn_key, self.inputs.amount_a);
+        let child_coin_b = coins::coin(burn_key, self.inputs.amount_b);
+
+        let commitment = compute_split_commitment(
+            coin,
+            child_coin_a,
+            child_coin_b,
+            self.inputs.extra_commitment,
+        );
+
+        SplitOutputs {
+            commitment,
+            coin,
+            child_coin_a,
+            child_coin_b,
+        }
+    }
+}
+
+/// Compute the public commitment for the Split circuit, as the Split trace does
+///
+/// Neither child amount is part of the commitment, so a split reveals no balance.
+fn compute_split_commitment(
+    coin: M31,
+    child_coin_a: M31,
+    child_coin_b: M31,
+    extra_commitment: M31,
+) -> [M31; OUTPUT_LIMBS] {
+    let commitment_state = permute_padded(
+        poseidon2_permutation,
+        &[coin, child_coin_a, child_coin_b, extra_commitment],
+    );
+    std::array::from_fn(|i| commitment_state[i])
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SplitError {
+    #[error("Split amounts do not add up: balance={balance}, amount_a={amount_a}, amount_b={amount_b}")]
+    AmountMismatch {
+        balance: U256,
+        amount_a: U256,
+        amount_b: U256,
+    },
+
+    #[error("Amount {value} does not fit in the 64 bits the Split trace holds")]
+    AmountTooLarge {
+        value: U256,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+
+    fn split_inputs(balance: u64, amount_a: u64, amount_b: u64) -> SplitInputs {
+        SplitInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(balance),
+            amount_a: U256::from(amount_a),
+            amount_b: U256::from(amount_b),
+            extra_commitment: M31::from(100),
+        }
+    }
+
+    #[test]
+    fn test_split_circuit_valid() {
+        let outputs = SplitCircuit::new(split_inputs(1000, 300, 700)).unwrap().compute_outputs();
+
+        assert!(outputs.commitment.iter().all(|limb| limb.value() > 0));
+        assert_ne!(outputs.coin, outputs.child_coin_a);
+        assert_ne!(outputs.coin, outputs.child_coin_b);
+        assert_ne!(outputs.child_coin_a, outputs.child_coin_b);
+    }
+
+    #[test]
+    fn test_children_match_spend_coins() {
+        use crate::circuits::spend::{SpendCircuit, SpendInputs};
+
+        // A child holding `amount` is the coin a Spend of `balance - amount` would leave
+        let outputs = SplitCircuit::new(split_inputs(1000, 300, 700)).unwrap().compute_outputs();
+        let spend = SpendCircuit::new(SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(700),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        })
+        .unwrap()
+        .compute_outputs();
+
+        assert_eq!(outputs.coin, spend.coin);
+        assert_eq!(outputs.child_coin_a, spend.remaining_coin);
+    }
+
+    #[test]
+    fn test_split_circuit_rejects_mismatched_amounts() {
+        for (balance, amount_a, amount_b) in [(1000, 300, 600), (1000, 300, 800), (1000, 1200, 0)] {
+            let result = SplitCircuit::new(split_inputs(balance, amount_a, amount_b));
+            assert!(matches!(result, Err(SplitError::AmountMismatch { .. })));
+        }
+    }
+
+    #[test]
+    fn test_split_circuit_empty_child() {
+        let outputs = SplitCircuit::new(split_inputs(1000, 1000, 0)).unwrap().compute_outputs();
+        assert!(outputs.child_coin_b.value() > 0);
+    }
+
+    #[test]
+    fn test_split_circuit_amount_too_large() {
+        let mut inputs = split_inputs(0, 0, 0);
+        inputs.balance = U256::MAX;
+        inputs.amount_a = U256::MAX;
+        assert!(matches!(SplitCircuit::new(inputs), Err(SplitError::AmountTooLarge { .. })));
+
+        // A balance one past u64::MAX is beyond the trace even though the amounts add up
+        let mut inputs = split_inputs(0, 1, u64::MAX);
+        inputs.balance = U256::from(u64::MAX) + U256::from(1);
+        let Err(SplitError::AmountTooLarge { value }) = SplitCircuit::new(inputs) else {
+            panic!("a 65-bit balance must be rejected");
+        };
+        assert_eq!(value, U256::from(1) << 64);
+
+        assert!(SplitCircuit::new(split_inputs(u64::MAX, u64::MAX, 0)).is_ok());
+    }
+}
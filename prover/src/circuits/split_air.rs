@@ -0,0 +1,693 @@
+// Split AIR (Algebraic Intermediate Representation) for Stwo
+// Implements constraints for splitting one coin into two child coins
+// Conservation is a chunked subtraction balance - amount_a == amount_b whose 16-bit chunks are
+// looked up in the range-check component (range_check_air.rs), exactly as Spend's remaining balance
+// The three coin permutations and the commitment permutation are looked up in the Poseidon2 table
+// component (poseidon2_table_air.rs) through the relations the PoB AIR uses, and the coins and
+// commitment are claimed as public outputs the verifier answers
+
+// Alias for macro compatibility (relation! macro expects 'stwo' crate name)
+extern crate stwo_prover as stwo;
+
+use std::ops::Range;
+
+use itertools::Itertools;
+use zeroize::Zeroize;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::m31::{PackedBaseField, LOG_N_LANES, N_LANES};
+use stwo_prover::prover::backend::simd::qm31::PackedSecureField;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{Col, Column};
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{
+    relation, EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator, Relation,
+    RelationEntry,
+};
+
+use crate::circuits::coin_trace::{amount_limbs, chunked_amount, coin_state};
+use crate::circuits::layout::{next_masks, SPLIT_LAYOUT};
+use crate::circuits::poseidon2_table_air::{Poseidon2Relation, Poseidon2TableEntry};
+use crate::circuits::proof_of_burn_air::{CommitmentElements, RemainingCoinElements, COIN_PREFIX};
+use crate::circuits::range_check_air::{
+    add_range_check_claims, balance_chunks, chunked_sub_borrows, constrain_chunk_limbs,
+    constrain_chunked_sub, constrain_limb_chunks, limb_pieces, write_range_check_claims,
+    RangeCheckElements, RangeCheckMultiplicities, BALANCE_CHUNKS, LIMB_PIECE_COLUMNS,
+};
+use crate::circuits::split::{SplitInputs, SplitOutputs};
+use crate::circuits::stats::{circuit_stats, CircuitStats};
+use crate::constants::{M31_PRIME, OUTPUT_LIMBS};
+use crate::field::M31;
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon::U256_M31_LIMBS;
+use crate::utils::poseidon2_stwo::{poseidon2_permutation, N_STATE};
+
+/// Helper constant for zero field element
+const ZERO: BaseField = BaseField::from_u32_unchecked(0);
+
+/// Width of the public output claim: coin, child coin A, child coin B, commitment limbs
+pub const SPLIT_PUBLIC_OUTPUT_SIZE: usize = 3 + OUTPUT_LIMBS;
+
+/// Binds the Split public output cells to the outputs the verifier mixes into the channel
+///
+/// Every enabled row claims its coins and commitment here; the verifier answers the claims with
+/// the outputs it was given, as `PublicOutputElements` does for PoB.
+relation!(SplitPublicOutputElements, SPLIT_PUBLIC_OUTPUT_SIZE);
+
+/// Number of columns in the Split trace
+///
+/// Trace structure (see `SplitTraceLayout` for the named ranges):
+/// 0. burn_key (private witness)
+/// 1-2. balance_low, balance_high
+/// 3-4. amount_a_low, amount_a_high
+/// 5-6. amount_b_low, amount_b_high
+/// 7. extra_commitment
+/// 8-10. coin, child_coin_a, child_coin_b (public output)
+/// 11-18. commitment limbs (public output)
+/// 19-82. Poseidon2 input states of the three coins and the commitment
+/// 83-121. chunked conservation subtraction and limb pieces
+/// 122. enabled
+pub const NUM_SPLIT_COLUMNS: usize = SPLIT_LAYOUT.total_columns();
+
+/// Human-readable name of every Split trace column, in trace order (see `NUM_SPLIT_COLUMNS`)
+pub fn split_column_labels() -> Vec<String> {
+    let mut labels: Vec<String> = [
+        "burn_key",
+        "balance_low",
+        "balance_high",
+        "amount_a_low",
+        "amount_a_high",
+        "amount_b_low",
+        "amount_b_high",
+        "extra_commitment",
+        "coin",
+        "child_coin_a",
+        "child_coin_b",
+    ]
+    .iter()
+    .map(|label| label.to_string())
+    .collect();
+    labels.extend((0..OUTPUT_LIMBS).map(|i| format!("commitment_limb_{}", i)));
+    for (name, range) in &SPLIT_LAYOUT.ranges()[5..] {
+        match range.len() {
+            1 => labels.push(name.to_string()),
+            len => labels.extend((0..len).map(|i| format!("{}_{}", name, i))),
+        }
+    }
+
+    debug_assert_eq!(labels.len(), NUM_SPLIT_COLUMNS);
+    labels
+}
+
+pub type SplitComponent = FrameworkComponent<SplitEval>;
+
+/// Split constraint evaluator
+/// Defines the AIR constraints for splitting a coin into two child coins
+#[derive(Clone)]
+pub struct SplitEval {
+    /// Log2 of the number of rows in the trace
+    pub log_n_rows: u32,
+    /// Lookup elements for the coin permutations answered by the Poseidon2 table component
+    pub remaining_coin_lookup: RemainingCoinElements,
+    /// Lookup elements for the commitment permutation answered by the Poseidon2 table component
+    pub commitment_lookup: CommitmentElements,
+    /// Lookup elements for the amount chunks answered by the range-check component
+    pub range_check_lookup: RangeCheckElements,
+    /// Lookup elements for the public outputs answered by the verifier
+    pub public_output_lookup: SplitPublicOutputElements,
+}
+
+impl SplitEval {
+    /// Trace, interaction and constraint counts of this AIR
+    pub fn statistics(&self) -> CircuitStats {
+        circuit_stats(self)
+    }
+}
+
+impl FrameworkEval for SplitEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        // Degree bound: LOG_EXPAND for interpolation (matching stwo examples)
+        self.log_n_rows + 2
+    }
+
+    /// Evaluate constraints at a single row
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let layout = SPLIT_LAYOUT;
+
+        // Read trace columns
+        let [
+            burn_key,
+            balance_low,
+            balance_high,
+            amount_a_low,
+            amount_a_high,
+            amount_b_low,
+            amount_b_high,
+            extra_commitment,
+        ] = next_masks::<E, 8>(&mut eval, layout.inputs());
+        let [coin] = next_masks::<E, 1>(&mut eval, layout.coin());
+        let [child_coin_a] = next_masks::<E, 1>(&mut eval, layout.child_coin_a());
+        let [child_coin_b] = next_masks::<E, 1>(&mut eval, layout.child_coin_b());
+        let commitment: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.commitment_out());
+
+        // Poseidon2 input states, in layout order
+        let coin_initial: [E::F; N_STATE] = next_masks(&mut eval, layout.coin_initial());
+        let child_coin_a_initial: [E::F; N_STATE] =
+            next_masks(&mut eval, layout.child_coin_a_initial());
+        let child_coin_b_initial: [E::F; N_STATE] =
+            next_masks(&mut eval, layout.child_coin_b_initial());
+        let commitment_initial: [E::F; N_STATE] =
+            next_masks(&mut eval, layout.commitment_initial());
+
+        // Chunked conservation subtraction and the limb pieces of every amount
+        let balance_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.balance_chunks());
+        let amount_a_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.amount_a_chunks());
+        let amount_b_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.amount_b_chunks());
+        let balance_limb_pieces: [E::F; LIMB_PIECE_COLUMNS] =
+            next_masks(&mut eval, layout.balance_limb_pieces());
+        let amount_a_limb_pieces: [E::F; LIMB_PIECE_COLUMNS] =
+            next_masks(&mut eval, layout.amount_a_limb_pieces());
+        let amount_b_limb_pieces: [E::F; LIMB_PIECE_COLUMNS] =
+            next_masks(&mut eval, layout.amount_b_limb_pieces());
+        let conservation_borrows: [E::F; BALANCE_CHUNKS - 1] =
+            next_masks(&mut eval, layout.conservation_borrows());
+        let [enabled] = next_masks::<E, 1>(&mut eval, layout.enabled());
+
+        // Constraint indices (used by `debug::check_split_constraints` reports):
+        //   0      enabled is boolean
+        //   1-6    balance, amount_a, amount_b low/high limbs == their 16-bit chunks
+        //   7-9    conservation_borrows are boolean
+        //   10-13  balance_chunks - amount_a_chunks == amount_b_chunks
+        //   14-35  coin_initial is the coin preimage of balance_chunks
+        //   36-57  child_coin_a_initial is the coin preimage of amount_a_chunks
+        //   58-79  child_coin_b_initial is the coin preimage of amount_b_chunks
+        //   80-83  commitment_initial[0..4] == coin, child_coin_a, child_coin_b, extra_commitment
+        //   84-95  commitment_initial[4..16] == zero
+        // Every constraint is homogeneous in the main trace but the prefixes, which are gated on
+        // `enabled`, so the all-zero padding rows satisfy them.
+        eval.add_constraint(enabled.clone() * (enabled.clone() - E::F::from(BaseField::from(1))));
+
+        // === CONSTRAINT 1: Conservation ===
+        // amount_a + amount_b == balance with amount_a <= balance, i.e. balance - amount_a is
+        // amount_b without a borrow out of the top chunk
+        let limbs = [
+            (balance_low, &balance_chunks[..2]),
+            (balance_high, &balance_chunks[2..]),
+            (amount_a_low, &amount_a_chunks[..2]),
+            (amount_a_high, &amount_a_chunks[2..]),
+            (amount_b_low, &amount_b_chunks[..2]),
+            (amount_b_high, &amount_b_chunks[2..]),
+        ];
+        for (limb, chunks) in limbs {
+            constrain_limb_chunks(&mut eval, limb, [chunks[0].clone(), chunks[1].clone()]);
+        }
+        constrain_chunked_sub(
+            &mut eval,
+            &balance_chunks,
+            &amount_a_chunks,
+            &amount_b_chunks,
+            &conservation_borrows,
+        );
+
+        // === CONSTRAINT 2: Coin preimages ===
+        // coin = Poseidon2([COIN_PREFIX, burn_key, balance limbs])[0], children likewise with their
+        // amounts (`coins::coin`). Each state holds the prefix, the key and the limbs rebuilt
+        // exactly from the range-checked chunks, as the PoB remaining coin's does; the lookups
+        // below prove each coin is its state's permutation
+        let coin_states = [
+            (&coin_initial, &balance_chunks, &balance_limb_pieces),
+            (&child_coin_a_initial, &amount_a_chunks, &amount_a_limb_pieces),
+            (&child_coin_b_initial, &amount_b_chunks, &amount_b_limb_pieces),
+        ];
+        for (state, chunks, pieces) in coin_states {
+            eval.add_constraint(enabled.clone() * (state[0].clone() - E::F::from(COIN_PREFIX)));
+            eval.add_constraint(state[1].clone() - burn_key.clone());
+            let limbs = std::array::from_fn(|i| state[2 + i].clone());
+            constrain_chunk_limbs(&mut eval, chunks, pieces, limbs);
+            for cell in &state[2 + U256_M31_LIMBS..] {
+                eval.add_constraint(cell.clone());
+            }
+        }
+
+        // === CONSTRAINT 3: Commitment preimage ===
+        // commitment = Poseidon2([coin, child_coin_a, child_coin_b, extra_commitment])[..8]
+        // (`SplitCircuit::compute_outputs`)
+        let preimage = [coin.clone(), child_coin_a.clone(), child_coin_b.clone(), extra_commitment];
+        for (state, value) in commitment_initial.iter().zip(preimage) {
+            eval.add_constraint(state.clone() - value);
+        }
+        for state in &commitment_initial[4..] {
+            eval.add_constraint(state.clone());
+        }
+
+        // === CONSTRAINT 4: Poseidon2, range-check and public output lookups ===
+        // Every enabled row claims its four Poseidon2 (input, output) pairs, its chunks and limb
+        // pieces; the table and range-check components answer them. The public outputs are
+        // answered by the verifier, which subtracts their claims from the sums
+        let multiplicity = E::EF::from(enabled);
+        for (state, output) in [
+            (&coin_initial, &coin),
+            (&child_coin_a_initial, &child_coin_a),
+            (&child_coin_b_initial, &child_coin_b),
+        ] {
+            eval.add_to_relation(RelationEntry::new(
+                &self.remaining_coin_lookup,
+                multiplicity.clone(),
+                &[&state[..], std::slice::from_ref(output)].concat(),
+            ));
+        }
+        eval.add_to_relation(RelationEntry::new(
+            &self.commitment_lookup,
+            multiplicity.clone(),
+            &[&commitment_initial[..], &commitment[..]].concat(),
+        ));
+        add_range_check_claims(
+            &mut eval,
+            &self.range_check_lookup,
+            multiplicity.clone(),
+            &[
+                &balance_chunks[..],
+                &amount_a_chunks,
+                &amount_b_chunks,
+                &balance_limb_pieces,
+                &amount_a_limb_pieces,
+                &amount_b_limb_pieces,
+            ]
+            .concat(),
+        );
+        eval.add_to_relation(RelationEntry::new(
+            &self.public_output_lookup,
+            multiplicity,
+            &[&[coin, child_coin_a, child_coin_b][..], &commitment[..]].concat(),
+        ));
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generate the execution trace for Split and the Poseidon2 table entries answering its lookups
+///
+/// Fails if the amounts do not add up to the balance or do not fit the chunked columns.
+#[tracing::instrument(level = "debug", skip_all, fields(log_size))]
+pub fn generate_split_trace(
+    log_size: u32,
+    inputs: &SplitInputs,
+) -> Result<(
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    Vec<Poseidon2TableEntry>,
+), String> {
+    let size = 1 << log_size;
+
+    // Validate M31 values are in correct range before conversion
+    // Error messages never include the burn key itself
+    let mut burn_key_val = inputs.burn_key.expose_secret().value();
+    if burn_key_val >= M31_PRIME {
+        return Err(format!("burn_key exceeds M31 prime {}", M31_PRIME));
+    }
+    let extra_commitment_val = inputs.extra_commitment.value();
+    if extra_commitment_val >= M31_PRIME {
+        return Err(format!(
+            "extra_commitment value {} exceeds M31 prime {}",
+            extra_commitment_val, M31_PRIME
+        ));
+    }
+
+    let balance = chunked_amount("balance", inputs.balance)?;
+    let amount_a = chunked_amount("amount_a", inputs.amount_a)?;
+    let amount_b = chunked_amount("amount_b", inputs.amount_b)?;
+    let borrows = chunked_sub_borrows(balance, amount_a)
+        .filter(|_| balance - amount_a == amount_b)
+        .ok_or_else(|| {
+            format!(
+                "Split amounts do not add up: balance={}, amount_a={}, amount_b={}",
+                balance, amount_a, amount_b
+            )
+        })?;
+
+    let mut trace = (0..NUM_SPLIT_COLUMNS)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(size))
+        .collect_vec();
+
+    let mut burn_key_field = BaseField::from_u32_unchecked(burn_key_val);
+    let [balance_low, balance_high] = amount_limbs(balance);
+    let [amount_a_low, amount_a_high] = amount_limbs(amount_a);
+    let [amount_b_low, amount_b_high] = amount_limbs(amount_b);
+    let extra_commitment_field = BaseField::from_u32_unchecked(extra_commitment_val);
+
+    // Compute derived values using Poseidon2, one coin permutation per coin
+    let mut coin_initial = coin_state(burn_key_field, balance);
+    let mut child_coin_a_initial = coin_state(burn_key_field, amount_a);
+    let mut child_coin_b_initial = coin_state(burn_key_field, amount_b);
+    let coin = poseidon2_permutation(coin_initial)[0];
+    let child_coin_a = poseidon2_permutation(child_coin_a_initial)[0];
+    let child_coin_b = poseidon2_permutation(child_coin_b_initial)[0];
+
+    // commitment = Hash(coin, child_coin_a, child_coin_b, extra_commitment)
+    let commitment_initial = [
+        coin,
+        child_coin_a,
+        child_coin_b,
+        extra_commitment_field,
+        ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO, ZERO,
+    ];
+    let commitment_output = poseidon2_permutation(commitment_initial);
+
+    // Fill the trace
+    // For SIMD backend, vec_index 0 broadcasts the witness row to the first N_LANES rows
+    let vec_index = 0;
+    let layout = SPLIT_LAYOUT;
+    let mut fill = |range: Range<usize>, values: &[BaseField]| {
+        assert_eq!(range.len(), values.len(), "trace layout range {:?} filled with {} values", range, values.len());
+        for (col, &value) in range.zip(values) {
+            trace[col].data[vec_index] = value.into();
+        }
+    };
+
+    fill(layout.inputs(), &[
+        burn_key_field,
+        balance_low,
+        balance_high,
+        amount_a_low,
+        amount_a_high,
+        amount_b_low,
+        amount_b_high,
+        extra_commitment_field,
+    ]);
+    fill(layout.coin(), &[coin]);
+    fill(layout.child_coin_a(), &[child_coin_a]);
+    fill(layout.child_coin_b(), &[child_coin_b]);
+    fill(layout.commitment_out(), &commitment_output[..OUTPUT_LIMBS]);
+    fill(layout.coin_initial(), &coin_initial);
+    fill(layout.child_coin_a_initial(), &child_coin_a_initial);
+    fill(layout.child_coin_b_initial(), &child_coin_b_initial);
+    fill(layout.commitment_initial(), &commitment_initial);
+    fill(layout.balance_chunks(), &balance_chunks(balance));
+    fill(layout.amount_a_chunks(), &balance_chunks(amount_a));
+    fill(layout.amount_b_chunks(), &balance_chunks(amount_b));
+    fill(layout.balance_limb_pieces(), &limb_pieces(balance));
+    fill(layout.amount_a_limb_pieces(), &limb_pieces(amount_a));
+    fill(layout.amount_b_limb_pieces(), &limb_pieces(amount_b));
+    fill(layout.conservation_borrows(), &borrows);
+    fill(layout.enabled(), &[BaseField::from(1)]);
+
+    // Each permutation is looked up once per enabled row
+    let table_entry = |relation, input| Poseidon2TableEntry {
+        relation,
+        input,
+        multiplicity: N_LANES as u32,
+    };
+    let table_entries = vec![
+        table_entry(Poseidon2Relation::RemainingCoin, coin_initial),
+        table_entry(Poseidon2Relation::RemainingCoin, child_coin_a_initial),
+        table_entry(Poseidon2Relation::RemainingCoin, child_coin_b_initial),
+        table_entry(Poseidon2Relation::Commitment, commitment_initial),
+    ];
+
+    // Wipe key-bearing temporaries; the trace columns and table entries now hold the only copies
+    burn_key_val.zeroize();
+    zeroize_base_fields(std::slice::from_mut(&mut burn_key_field));
+    for state in [&mut coin_initial, &mut child_coin_a_initial, &mut child_coin_b_initial] {
+        zeroize_base_fields(state);
+    }
+
+    // Convert to CircleEvaluations
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    let trace = trace
+        .into_iter()
+        .map(|col| CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(domain, col))
+        .collect_vec();
+    Ok((trace, table_entries))
+}
+
+/// The public output cells of `outputs`, in `SplitPublicOutputElements` order
+pub fn split_public_output(outputs: &SplitOutputs) -> [BaseField; SPLIT_PUBLIC_OUTPUT_SIZE] {
+    let values = [outputs.coin, outputs.child_coin_a, outputs.child_coin_b]
+        .into_iter()
+        .chain(outputs.commitment);
+    let mut cells = [ZERO; SPLIT_PUBLIC_OUTPUT_SIZE];
+    for (cell, value) in cells.iter_mut().zip(values) {
+        *cell = BaseField::from_u32_unchecked(value.value());
+    }
+    cells
+}
+
+/// Read the public outputs from the first row of a Split main trace
+pub fn split_outputs_from_trace(
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> SplitOutputs {
+    let layout = SPLIT_LAYOUT;
+    let cell = |column: usize| M31::new(trace[column].values.at(0).0);
+    SplitOutputs {
+        commitment: std::array::from_fn(|i| cell(layout.commitment_out().start + i)),
+        coin: cell(layout.coin().start),
+        child_coin_a: cell(layout.child_coin_a().start),
+        child_coin_b: cell(layout.child_coin_b().start),
+    }
+}
+
+/// Chunk and limb piece columns of a Split trace, in the order `SplitEval` claims them
+fn range_checked_columns(
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> Vec<&BaseColumn> {
+    trace[SPLIT_LAYOUT.range_checked()].iter().map(|col| &col.values).collect()
+}
+
+/// Every enabled row claims its chunks once
+fn claim_multiplicity(
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> &BaseColumn {
+    &trace[SPLIT_LAYOUT.enabled().start].values
+}
+
+/// Range-check table multiplicities answering the claims of a Split trace
+pub fn split_range_check_multiplicities(
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> Result<RangeCheckMultiplicities, String> {
+    let multiplicity = claim_multiplicity(trace);
+    let mut multiplicities = RangeCheckMultiplicities::default();
+    for column in range_checked_columns(trace) {
+        multiplicities.add_column(column, multiplicity)?;
+    }
+    Ok(multiplicities)
+}
+
+/// Generate the Split interaction trace for the Poseidon2, range-check and public output lookups
+///
+/// Columns pair the relation entries as `SplitEval` adds them: coin + child coin A, child coin
+/// B + commitment, the range-check claims, then the public outputs on their own.
+pub fn gen_split_interaction_trace(
+    log_size: u32,
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    remaining_coin_lookup: &RemainingCoinElements,
+    commitment_lookup: &CommitmentElements,
+    range_check_lookup: &RangeCheckElements,
+    public_output_lookup: &SplitPublicOutputElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let layout = SPLIT_LAYOUT;
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    let values = |ranges: &[Range<usize>], vec_row: usize| -> Vec<PackedBaseField> {
+        ranges
+            .iter()
+            .cloned()
+            .flatten()
+            .map(|column| trace[column].values.data[vec_row])
+            .collect()
+    };
+    let enabled = |vec_row: usize| PackedSecureField::from(claim_multiplicity(trace).data[vec_row]);
+
+    // Coin + child coin A
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let q0: PackedSecureField = remaining_coin_lookup
+            .combine(&values(&[layout.coin_initial(), layout.coin()], vec_row));
+        let q1: PackedSecureField = remaining_coin_lookup
+            .combine(&values(&[layout.child_coin_a_initial(), layout.child_coin_a()], vec_row));
+        col_gen.write_frac(vec_row, enabled(vec_row) * (q0 + q1), q0 * q1);
+    }
+    col_gen.finalize_col();
+
+    // Child coin B + commitment
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let q0: PackedSecureField = remaining_coin_lookup
+            .combine(&values(&[layout.child_coin_b_initial(), layout.child_coin_b()], vec_row));
+        let q1: PackedSecureField = commitment_lookup
+            .combine(&values(&[layout.commitment_initial(), layout.commitment_out()], vec_row));
+        col_gen.write_frac(vec_row, enabled(vec_row) * (q0 + q1), q0 * q1);
+    }
+    col_gen.finalize_col();
+
+    write_range_check_claims(
+        &mut logup_gen,
+        log_size,
+        range_check_lookup,
+        claim_multiplicity(trace),
+        &range_checked_columns(trace),
+    );
+
+    // Public outputs, answered by the verifier
+    let mut col_gen = logup_gen.new_col();
+    for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+        let q: PackedSecureField =
+            public_output_lookup.combine(&values(&[layout.public_output()], vec_row));
+        col_gen.write_frac(vec_row, enabled(vec_row), q);
+    }
+    col_gen.finalize_col();
+
+    logup_gen.finalize_last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::split::SplitCircuit;
+    use crate::secret::Secret;
+    use alloy_primitives::U256;
+
+    fn create_test_inputs() -> SplitInputs {
+        SplitInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            amount_a: U256::from(300),
+            amount_b: U256::from(700),
+            extra_commitment: M31::from(100),
+        }
+    }
+
+    #[test]
+    fn test_generate_split_trace() {
+        let log_size = 4;
+        let (trace, table_entries) = generate_split_trace(log_size, &create_test_inputs()).unwrap();
+
+        assert_eq!(trace.len(), NUM_SPLIT_COLUMNS);
+        for col in &trace {
+            assert_eq!(col.len(), 1 << log_size);
+        }
+        assert_eq!(table_entries.len(), 4);
+    }
+
+    #[test]
+    fn test_trace_outputs_match_circuit() {
+        let inputs = create_test_inputs();
+        let (trace, _) = generate_split_trace(4, &inputs).unwrap();
+        let expected = SplitCircuit::new(inputs).unwrap().compute_outputs();
+        let outputs = split_outputs_from_trace(&trace);
+
+        assert_eq!(outputs.coin, expected.coin);
+        assert_eq!(outputs.child_coin_a, expected.child_coin_a);
+        assert_eq!(outputs.child_coin_b, expected.child_coin_b);
+        assert_eq!(outputs.commitment, expected.commitment);
+    }
+
+    #[test]
+    fn test_trace_rejects_unbalanced_amounts() {
+        let mut inputs = create_test_inputs();
+        inputs.amount_b = U256::from(701);
+        assert!(generate_split_trace(4, &inputs).is_err());
+
+        // amount_a above the balance would need a borrow out of the top chunk
+        inputs.amount_a = U256::from(1300);
+        inputs.amount_b = U256::ZERO;
+        assert!(generate_split_trace(4, &inputs).is_err());
+    }
+
+    #[test]
+    fn test_trace_rejects_amounts_beyond_chunks() {
+        let mut inputs = create_test_inputs();
+        inputs.balance = U256::from(u64::MAX) + U256::from(1);
+        inputs.amount_a = U256::from(1);
+        inputs.amount_b = U256::from(u64::MAX);
+        assert!(generate_split_trace(4, &inputs).is_err());
+    }
+
+    #[test]
+    fn test_column_labels_match_layout() {
+        let labels = split_column_labels();
+        assert_eq!(labels.len(), NUM_SPLIT_COLUMNS);
+        assert_eq!(labels.iter().collect::<std::collections::HashSet<_>>().len(), NUM_SPLIT_COLUMNS);
+        assert_eq!(labels[SPLIT_LAYOUT.commitment_out().start], "commitment_limb_0");
+        assert_eq!(labels[SPLIT_LAYOUT.amount_b_chunks().start], "amount_b_chunks_0");
+        assert_eq!(labels[SPLIT_LAYOUT.child_coin_b_initial().start + 2], "child_coin_b_initial_2");
+        assert_eq!(labels[SPLIT_LAYOUT.conservation_borrows().end - 1], "conservation_borrows_2");
+        assert_eq!(labels[NUM_SPLIT_COLUMNS - 1], "enabled");
+    }
+
+    #[test]
+    fn test_lookups_answered() {
+        use crate::circuits::poseidon2_table_air::{
+            gen_poseidon2_table_interaction_trace, generate_poseidon2_table_trace,
+            poseidon2_table_log_size,
+        };
+        use crate::circuits::proof_of_burn_air::{
+            BlockRootDigestElements, BurnAddressElements, NullifierElements,
+        };
+        use crate::circuits::range_check_air::{gen_range_check_interaction_trace, generate_range_check_trace};
+        use stwo_prover::core::fields::FieldExpOps;
+
+        let log_size = 4;
+        let (trace, table_entries) = generate_split_trace(log_size, &create_test_inputs()).unwrap();
+        let eval = crate::debug::split_eval(log_size);
+        let (_, claims_sum) = gen_split_interaction_trace(
+            log_size,
+            &trace,
+            &eval.remaining_coin_lookup,
+            &eval.commitment_lookup,
+            &eval.range_check_lookup,
+            &eval.public_output_lookup,
+        );
+
+        let table_log_size = poseidon2_table_log_size(table_entries.len());
+        let (_, table_data) =
+            generate_poseidon2_table_trace(table_log_size, &table_entries).unwrap();
+        let (_, table_sum) = gen_poseidon2_table_interaction_trace(
+            table_log_size,
+            &table_data,
+            &NullifierElements::dummy(),
+            &eval.remaining_coin_lookup,
+            &eval.commitment_lookup,
+            &BlockRootDigestElements::dummy(),
+            &BurnAddressElements::dummy(),
+        );
+        let multiplicities = split_range_check_multiplicities(&trace).unwrap();
+        let (_, range_check) = generate_range_check_trace(&multiplicities);
+        let (_, range_check_sum) =
+            gen_range_check_interaction_trace(&range_check, &eval.range_check_lookup);
+
+        // Only the public output claims are left, one per enabled row
+        let outputs = split_public_output(&split_outputs_from_trace(&trace));
+        let public: SecureField = eval.public_output_lookup.combine(&outputs);
+        let public_sum = SecureField::from(BaseField::from(N_LANES as u32)) * public.inverse();
+        assert_eq!(claims_sum + table_sum + range_check_sum, public_sum);
+    }
+
+    #[test]
+    fn test_statistics_match_traces() {
+        let log_size = 4;
+        let eval = crate::debug::split_eval(log_size);
+        let stats = eval.statistics();
+        assert_eq!(stats.n_trace_columns, NUM_SPLIT_COLUMNS);
+
+        let (trace, _) = generate_split_trace(log_size, &create_test_inputs()).unwrap();
+        let (interaction_trace, _) = gen_split_interaction_trace(
+            log_size,
+            &trace,
+            &eval.remaining_coin_lookup,
+            &eval.commitment_lookup,
+            &eval.range_check_lookup,
+            &eval.public_output_lookup,
+        );
+        assert_eq!(stats.n_interaction_columns, interaction_trace.len());
+    }
+}
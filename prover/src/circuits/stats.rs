@@ -0,0 +1,103 @@
+// Circuit statistics
+// Column and constraint counts read off an AIR evaluator instead of kept by hand, so they cannot
+// drift from what the evaluator actually does
+
+use std::ops::Mul;
+
+use itertools::Itertools;
+use num_traits::Zero;
+use serde::Serialize;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_constraint_framework::{
+    Batching, EvalAtRow, FrameworkEval, Relation, RelationEntry, ORIGINAL_TRACE_IDX,
+};
+
+/// Size of an AIR, as counted by running its evaluator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CircuitStats {
+    /// Main trace columns
+    pub n_trace_columns: usize,
+    /// Interaction trace base columns, 4 per logup column
+    pub n_interaction_columns: usize,
+    /// Constraints, including the one each logup column adds
+    pub n_constraints: usize,
+    /// `FrameworkEval::max_constraint_log_degree_bound`
+    pub max_degree_bound: u32,
+}
+
+/// Count the columns and constraints `eval` uses
+pub fn circuit_stats<E: FrameworkEval>(eval: &E) -> CircuitStats {
+    let counter = eval.evaluate(CountingEvaluator::default());
+    CircuitStats {
+        n_trace_columns: counter.trace_columns,
+        n_interaction_columns: counter.logup_columns * SECURE_EXTENSION_DEGREE,
+        n_constraints: counter.constraints + counter.logup_columns,
+        max_degree_bound: eval.max_constraint_log_degree_bound(),
+    }
+}
+
+/// `EvalAtRow` that only counts what the evaluator reads and emits
+///
+/// Every mask reads as zero; no evaluator branches on trace values. Only main trace reads are
+/// counted: the logup columns are counted when the evaluator finalizes its relation entries.
+#[derive(Default)]
+struct CountingEvaluator {
+    trace_columns: usize,
+    constraints: usize,
+    pending_entries: usize,
+    logup_columns: usize,
+}
+
+impl CountingEvaluator {
+    fn finalize(&mut self, columns: usize) {
+        self.logup_columns += columns;
+        self.pending_entries = 0;
+    }
+}
+
+impl EvalAtRow for CountingEvaluator {
+    type F = SecureField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        _offsets: [isize; N],
+    ) -> [Self::F; N] {
+        if interaction == ORIGINAL_TRACE_IDX {
+            self.trace_columns += 1;
+        }
+        [SecureField::zero(); N]
+    }
+
+    fn add_constraint<G>(&mut self, _constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        self.constraints += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_partial_evals(values)
+    }
+
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        _entry: RelationEntry<'_, Self::F, Self::EF, R>,
+    ) {
+        self.pending_entries += 1;
+    }
+
+    fn finalize_logup_batched(&mut self, batching: &Batching) {
+        self.finalize(batching.iter().unique().count());
+    }
+
+    fn finalize_logup(&mut self) {
+        self.finalize(self.pending_entries);
+    }
+
+    fn finalize_logup_in_pairs(&mut self) {
+        self.finalize(self.pending_entries.div_ceil(2));
+    }
+}
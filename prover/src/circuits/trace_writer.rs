@@ -0,0 +1,184 @@
+// Trace writer
+// Trace generators fill their rows region by region through a `TraceWriter`, which knows the
+// layout's named ranges and counts the writes to every cell. `finalize` refuses a trace with a
+// cell written twice or never written, so a miscounted or forgotten region fails when the trace
+// is generated instead of surfacing later as an unsatisfied constraint on a shifted column.
+
+use std::ops::Range;
+
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::circuits::layout::{PobTraceLayout, SpendTraceLayout};
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon2_stwo::N_STATE;
+
+/// A trace layout a `TraceWriter` checks writes against
+pub trait TraceLayout {
+    /// Number of columns of the trace
+    fn n_columns(&self) -> usize;
+
+    /// Every range with its name, in trace order
+    fn regions(&self) -> Vec<(&'static str, Range<usize>)>;
+}
+
+impl TraceLayout for PobTraceLayout {
+    fn n_columns(&self) -> usize {
+        self.total_columns()
+    }
+
+    fn regions(&self) -> Vec<(&'static str, Range<usize>)> {
+        self.ranges().to_vec()
+    }
+}
+
+impl TraceLayout for SpendTraceLayout {
+    fn n_columns(&self) -> usize {
+        self.total_columns()
+    }
+
+    fn regions(&self) -> Vec<(&'static str, Range<usize>)> {
+        self.ranges().to_vec()
+    }
+}
+
+/// A cell `finalize` rejected
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TraceWriterError {
+    #[error("trace column {column} ({region}) written {writes} times on row {row}")]
+    WrittenTwice { region: &'static str, column: usize, row: usize, writes: u32 },
+
+    #[error("trace column {column} ({region}) never written on row {row}")]
+    Unwritten { region: &'static str, column: usize, row: usize },
+}
+
+/// Witness rows of a trace, filled one layout range at a time
+///
+/// Rows are the writer's own: a generator that broadcasts one witness over the SIMD lanes
+/// writes a single row and spreads it when it builds the columns.
+pub struct TraceWriter<L: TraceLayout> {
+    layout: L,
+    n_rows: usize,
+    /// Cell values, column-major
+    cells: Vec<BaseField>,
+    /// Writes to each cell, in the order of `cells`
+    writes: Vec<u32>,
+}
+
+impl<L: TraceLayout> TraceWriter<L> {
+    /// An unwritten trace of `n_rows` rows over `layout`
+    pub fn new(layout: L, n_rows: usize) -> Self {
+        let n_cells = layout.n_columns() * n_rows;
+        Self { layout, n_rows, cells: vec![BaseField::from(0); n_cells], writes: vec![0; n_cells] }
+    }
+
+    /// Write `values` into the columns of `range` on `row`
+    ///
+    /// Panics when `values` does not fill `range` exactly or `row` is out of bounds; writing a
+    /// cell twice is reported by `finalize`.
+    pub fn set(&mut self, range: Range<usize>, row: usize, values: &[BaseField]) {
+        assert_eq!(
+            range.len(),
+            values.len(),
+            "trace layout range {:?} filled with {} values",
+            range,
+            values.len()
+        );
+        assert!(row < self.n_rows, "trace row {} out of {} rows", row, self.n_rows);
+        for (column, &value) in range.zip(values) {
+            let cell = column * self.n_rows + row;
+            self.cells[cell] = value;
+            self.writes[cell] += 1;
+        }
+    }
+
+    /// Write a Poseidon2 state into the 16 columns of `range` on `row`
+    pub fn set_state(&mut self, range: Range<usize>, row: usize, state: &[BaseField; N_STATE]) {
+        self.set(range, row, state);
+    }
+
+    /// The columns, each `n_rows` long, once every cell was written exactly once
+    ///
+    /// The values may include the burn key; the caller zeroizes them once the trace is built.
+    pub fn finalize(self) -> Result<Vec<Vec<BaseField>>, TraceWriterError> {
+        if let Some(cell) = self.writes.iter().position(|&writes| writes != 1) {
+            let (column, row) = (cell / self.n_rows, cell % self.n_rows);
+            let region = self
+                .layout
+                .regions()
+                .into_iter()
+                .find(|(_, range)| range.contains(&column))
+                .map_or("outside the layout", |(name, _)| name);
+            return Err(match self.writes[cell] {
+                0 => TraceWriterError::Unwritten { region, column, row },
+                writes => TraceWriterError::WrittenTwice { region, column, row, writes },
+            });
+        }
+        Ok(self.cells.chunks(self.n_rows).map(<[BaseField]>::to_vec).collect())
+    }
+}
+
+impl<L: TraceLayout> Drop for TraceWriter<L> {
+    fn drop(&mut self) {
+        zeroize_base_fields(&mut self.cells);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::layout::{POB_LAYOUT, SPEND_LAYOUT};
+    use crate::constants::OUTPUT_LIMBS;
+
+    fn cell_value(column: usize, row: usize) -> BaseField {
+        BaseField::from((column * 2 + row) as u32)
+    }
+
+    /// A Spend writer with every region written on both rows, except `skip`
+    fn spend_writer(skip: Option<&str>) -> TraceWriter<SpendTraceLayout> {
+        let mut writer = TraceWriter::new(SPEND_LAYOUT, 2);
+        for (name, range) in SPEND_LAYOUT.ranges() {
+            if Some(name) == skip {
+                continue;
+            }
+            for row in 0..2 {
+                let values: Vec<_> = range.clone().map(|column| cell_value(column, row)).collect();
+                writer.set(range.clone(), row, &values);
+            }
+        }
+        writer
+    }
+
+    #[test]
+    fn test_finalize_fills_every_column() {
+        let columns = spend_writer(None).finalize().unwrap();
+        assert_eq!(columns.len(), SPEND_LAYOUT.total_columns());
+        for (column, values) in columns.iter().enumerate() {
+            assert_eq!(values, &[cell_value(column, 0), cell_value(column, 1)]);
+        }
+    }
+
+    #[test]
+    fn test_finalize_rejects_skipped_column() {
+        let error = spend_writer(Some("remaining_coin")).finalize().unwrap_err();
+        let column = SPEND_LAYOUT.remaining_coin().start;
+        assert_eq!(error, TraceWriterError::Unwritten { region: "remaining_coin", column, row: 0 });
+    }
+
+    #[test]
+    fn test_finalize_rejects_double_write() {
+        let mut writer = TraceWriter::new(POB_LAYOUT, 1);
+        for (_, range) in POB_LAYOUT.ranges() {
+            writer.set(range.clone(), 0, &vec![BaseField::from(1); range.len()]);
+        }
+        // A region filled one column too far overlaps the next
+        let shifted = POB_LAYOUT.nullifier_out().start + 1..POB_LAYOUT.nullifier_out().end + 1;
+        writer.set(shifted, 0, &[BaseField::from(2); OUTPUT_LIMBS]);
+
+        let error = writer.finalize().unwrap_err();
+        let column = POB_LAYOUT.nullifier_out().start + 1;
+        assert_eq!(
+            error,
+            TraceWriterError::WrittenTwice { region: "nullifier_out", column, row: 0, writes: 2 }
+        );
+    }
+}
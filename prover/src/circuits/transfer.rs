@@ -0,0 +1,188 @@
+// Transfer Circuit - Stwo AIR Implementation
+// Hands a coin to another owner without revealing its balance: the sender's coin is nullified
+// and a coin with the same balance is created under the recipient's key commitment
+
+use crate::circuits::validation::{validate_amount, AmountError};
+use crate::coins;
+use crate::constants::OUTPUT_LIMBS;
+use crate::prover::packaging::limbs_to_u256;
+use crate::utils::poseidon::poseidon_wide;
+use alloy_primitives::U256;
+use crate::field::M31;
+use crate::secret::Secret;
+use serde::{Deserialize, Serialize};
+
+/// Inputs for the Transfer circuit
+/// Proves that a coin is re-issued with the same balance under the recipient's key commitment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferInputs {
+    /// Secret burn key of the sender, which owns the coin being transferred
+    pub sender_burn_key: Secret<M31>,
+
+    /// Balance of the coin, carried unchanged into the new coin
+    pub balance: U256,
+
+    /// Blinding commitment to the recipient's key, supplied by the recipient
+    pub recipient_key_commitment: M31,
+}
+
+/// Public outputs from the Transfer circuit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferOutputs {
+    /// Public commitment hash of all values, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
+
+    /// The sender's coin being transferred
+    pub old_coin: M31,
+
+    /// Nullifier of the sender's coin
+    pub old_coin_nullifier: [M31; OUTPUT_LIMBS],
+
+    /// The recipient's new coin
+    pub new_coin: M31,
+}
+
+/// Packed as the contracts take them, in hex
+impl std::fmt::Display for TransferOutputs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "old_coin={:#x}, old_coin_nullifier={:#x}, new_coin={:#x}, commitment={:#x}",
+            self.old_coin.value(),
+            limbs_to_u256(&self.old_coin_nullifier),
+            self.new_coin.value(),
+            limbs_to_u256(&self.commitment)
+        )
+    }
+}
+
+/// Transfer circuit implementation
+///
+/// Constraints:
+/// 1. oldCoin = Poseidon2([COIN_PREFIX, senderBurnKey, balance])[0]
+/// 2. oldCoinNullifier = Poseidon2([NULLIFIER_PREFIX, senderBurnKey, oldCoin])[..8]
+/// 3. newCoin = Poseidon2([COIN_PREFIX, recipientKeyCommitment, balance])[0], with the same balance
+/// 4. commitment = PublicCommitment([oldCoin, oldCoinNullifier, newCoin])
+pub struct TransferCircuit {
+    inputs: TransferInputs,
+}
+
+impl TransferCircuit {
+    /// Create a new Transfer circuit with given inputs
+    pub fn new(inputs: TransferInputs) -> Result<Self, TransferError> {
+        // Validation: amount fits in maxAmountBytes (31 bytes = 248 bits)
+        validate_amount(inputs.balance, "balance")?;
+
+        // Validation: amount fits the 64 bits the Transfer trace chunks
+        if inputs.balance > U256::from(u64::MAX) {
+            return Err(TransferError::AmountTooLarge { value: inputs.balance });
+        }
+
+        // A coin under the sender's own key would only re-issue the coin to the sender
+        if inputs.recipient_key_commitment == *inputs.sender_burn_key.expose_secret() {
+            return Err(TransferError::SelfTransfer);
+        }
+
+        Ok(Self { inputs })
+    }
+
+    /// Compute the circuit outputs
+    pub fn compute_outputs(&self) -> TransferOutputs {
+        let sender_burn_key = *self.inputs.sender_burn_key.expose_secret();
+        let old_coin = coins::coin(sender_burn_key, self.inputs.balance);
+        let old_coin_nullifier = coins::coin_nullifier(sender_burn_key, old_coin);
+        let new_coin = coins::coin(self.inputs.recipient_key_commitment, self.inputs.balance);
+
+        let commitment = compute_transfer_commitment(old_coin, &old_coin_nullifier, new_coin);
+
+        TransferOutputs {
+            commitment,
+            old_coin,
+            old_coin_nullifier,
+            new_coin,
+        }
+    }
+}
+
+/// Compute the public commitment for the Transfer circuit
+fn compute_transfer_commitment(
+    old_coin: M31,
+    old_coin_nullifier: &[M31; OUTPUT_LIMBS],
+    new_coin: M31,
+) -> [M31; OUTPUT_LIMBS] {
+    let mut inputs = Vec::with_capacity(OUTPUT_LIMBS + 2);
+    inputs.push(old_coin);
+    inputs.extend_from_slice(old_coin_nullifier);
+    inputs.push(new_coin);
+    poseidon_wide(&inputs)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransferError {
+    #[error(transparent)]
+    InvalidAmount(#[from] AmountError),
+
+    #[error("Balance {value} does not fit in the 64 bits the Transfer trace holds")]
+    AmountTooLarge {
+        value: U256,
+    },
+
+    #[error("Recipient key commitment equals the sender's burn key")]
+    SelfTransfer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer_inputs(balance: u64) -> TransferInputs {
+        TransferInputs {
+            sender_burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(balance),
+            recipient_key_commitment: M31::from(67890),
+        }
+    }
+
+    #[test]
+    fn test_transfer_circuit_valid() {
+        let outputs = TransferCircuit::new(transfer_inputs(1000)).unwrap().compute_outputs();
+
+        assert!(outputs.commitment.iter().all(|limb| limb.value() > 0));
+        assert_ne!(outputs.old_coin, outputs.new_coin);
+        assert_eq!(outputs.old_coin, coins::coin(M31::from(12345), U256::from(1000)));
+    }
+
+    #[test]
+    fn test_new_coin_is_recipient_coin_of_same_balance() {
+        // The recipient can later spend the coin as if it had been burned under their key commitment
+        let outputs = TransferCircuit::new(transfer_inputs(1000)).unwrap().compute_outputs();
+        assert_eq!(outputs.new_coin, coins::coin(M31::from(67890), U256::from(1000)));
+        assert_ne!(outputs.new_coin, coins::coin(M31::from(67890), U256::from(1001)));
+    }
+
+    #[test]
+    fn test_transfer_circuit_rejects_self_transfer() {
+        let mut inputs = transfer_inputs(1000);
+        inputs.recipient_key_commitment = M31::from(12345);
+        assert!(matches!(TransferCircuit::new(inputs), Err(TransferError::SelfTransfer)));
+    }
+
+    #[test]
+    fn test_transfer_circuit_amount_too_large() {
+        let mut inputs = transfer_inputs(0);
+        inputs.balance = U256::MAX;
+        match TransferCircuit::new(inputs) {
+            Err(TransferError::InvalidAmount(e)) => assert_eq!(e.field, "balance"),
+            other => panic!("expected InvalidAmount, got {:?}", other.err()),
+        }
+
+        // Below the cap, the trace only holds 64-bit balances
+        assert!(TransferCircuit::new(transfer_inputs(u64::MAX)).is_ok());
+        let mut inputs = transfer_inputs(0);
+        inputs.balance = U256::from(1) << 64;
+        match TransferCircuit::new(inputs) {
+            Err(TransferError::AmountTooLarge { value }) => assert_eq!(value, U256::from(1) << 64),
+            other => panic!("expected AmountTooLarge, got {:?}", other.err()),
+        }
+    }
+}
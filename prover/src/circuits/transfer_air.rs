@@ -0,0 +1,375 @@
+// Transfer AIR (Algebraic Intermediate Representation) for Stwo
+// Implements constraints for re-issuing a coin under the recipient's key commitment
+// The new coin hashes its own copy of the balance limbs, which is constrained equal to the
+// range-checked balance of the old coin
+
+use itertools::Itertools;
+use zeroize::Zeroize;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{Col, Column};
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::{EvalAtRow, FrameworkComponent, FrameworkEval, LogupTraceGenerator};
+
+use crate::circuits::coin_trace::{amount_limbs, chunked_amount, coin_permutation};
+use crate::circuits::layout::{next_masks, TRANSFER_LAYOUT};
+use crate::circuits::merge_air::nullifier_limbs;
+use crate::circuits::range_check_air::{
+    add_range_check_claims, balance_chunks, constrain_limb_chunks, write_range_check_claims,
+    RangeCheckElements, RangeCheckMultiplicities, BALANCE_CHUNKS,
+};
+use crate::circuits::stats::{circuit_stats, CircuitStats};
+use crate::circuits::transfer::TransferInputs;
+use crate::constants::{M31_PRIME, OUTPUT_LIMBS};
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon2_stwo::{poseidon2_permutation, N_STATE};
+
+/// Helper constant for zero field element
+const ZERO: BaseField = BaseField::from_u32_unchecked(0);
+
+/// Number of columns in the Transfer trace
+///
+/// Trace structure (see `TransferTraceLayout` for the named ranges):
+/// 0. sender_burn_key (private witness)
+/// 1-2. balance_low, balance_high
+/// 3. recipient_key_commitment
+/// 4-5. new_balance_low, new_balance_high
+/// 6-7. old_coin, new_coin (computed)
+/// 8-15. old coin nullifier limbs (public output)
+/// 16-23. commitment limbs (public output)
+/// 24-27. intermediate_poseidon_state
+/// 28-31. balance chunks
+pub const NUM_TRANSFER_COLUMNS: usize = TRANSFER_LAYOUT.total_columns();
+
+/// Human-readable name of every Transfer trace column, in trace order (see `NUM_TRANSFER_COLUMNS`)
+pub fn transfer_column_labels() -> Vec<String> {
+    let mut labels: Vec<String> = [
+        "sender_burn_key",
+        "balance_low",
+        "balance_high",
+        "recipient_key_commitment",
+        "new_balance_low",
+        "new_balance_high",
+        "old_coin",
+        "new_coin",
+    ]
+    .iter()
+    .map(|label| label.to_string())
+    .collect();
+    for (name, range) in &TRANSFER_LAYOUT.ranges()[4..] {
+        labels.extend((0..range.len()).map(|i| format!("{}_{}", name, i)));
+    }
+
+    debug_assert_eq!(labels.len(), NUM_TRANSFER_COLUMNS);
+    labels
+}
+
+pub type TransferComponent = FrameworkComponent<TransferEval>;
+
+/// Transfer constraint evaluator
+/// Defines the AIR constraints for handing a coin to a new owner
+#[derive(Clone)]
+pub struct TransferEval {
+    /// Log2 of the number of rows in the trace
+    pub log_n_rows: u32,
+    /// Lookup elements for the balance chunks answered by the range-check component
+    pub range_check_lookup: RangeCheckElements,
+}
+
+impl TransferEval {
+    /// Trace, interaction and constraint counts of this AIR
+    pub fn statistics(&self) -> CircuitStats {
+        circuit_stats(self)
+    }
+}
+
+impl FrameworkEval for TransferEval {
+    fn log_size(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    fn max_constraint_log_degree_bound(&self) -> u32 {
+        // Degree bound: LOG_EXPAND for interpolation (matching stwo examples)
+        self.log_n_rows + 2
+    }
+
+    /// Evaluate constraints at a single row
+    fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
+        let layout = TRANSFER_LAYOUT;
+
+        // Read trace columns
+        let [
+            sender_burn_key,
+            balance_low,
+            balance_high,
+            _recipient_key_commitment,
+        ] = next_masks::<E, 4>(&mut eval, layout.inputs());
+        let [new_balance_low, new_balance_high] = next_masks::<E, 2>(&mut eval, layout.new_balance());
+        let [_old_coin] = next_masks::<E, 1>(&mut eval, layout.old_coin());
+        let [_new_coin] = next_masks::<E, 1>(&mut eval, layout.new_coin());
+        let _old_coin_nullifier: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.old_coin_nullifier());
+        let _commitment: [E::F; OUTPUT_LIMBS] = next_masks(&mut eval, layout.commitment_out());
+
+        // Intermediate Poseidon state columns
+        let _old_coin_state: [E::F; 2] = next_masks(&mut eval, layout.old_coin_state());
+        let _new_coin_state: [E::F; 2] = next_masks(&mut eval, layout.new_coin_state());
+
+        let balance_chunks: [E::F; BALANCE_CHUNKS] = next_masks(&mut eval, layout.balance_chunks());
+
+        // === CONSTRAINT 1: Balance preservation ===
+        // The new coin's balance limbs equal the old coin's, which are range-checked
+        // Constraint indices (used by `debug::check_transfer_constraints` reports):
+        //   0      placeholder
+        //   1-2    balance_low/high == their 16-bit chunks
+        //   3-4    new_balance_low/high == balance_low/high
+        // Every chunk is range-checked on every row; padding rows are all zero, which is in range
+
+        // === CONSTRAINT 2: Coin computations ===
+        // old_coin = Poseidon2([COIN_PREFIX, sender_burn_key, balance])[0]
+        // new_coin = Poseidon2([COIN_PREFIX, recipient_key_commitment, new_balance])[0]
+        //
+        // In production, these would be full Poseidon AIR constraints
+
+        // === CONSTRAINT 3: Nullifier ===
+        // old_coin_nullifier = Poseidon2([NULLIFIER_PREFIX, sender_burn_key, old_coin])
+
+        // === CONSTRAINT 4: Commitment computation ===
+        // commitment = Hash(old_coin, old_coin_nullifier, new_coin)
+
+        // === PLACEHOLDER CONSTRAINTS ===
+        // These ensure the trace compiles and columns are used
+        // TODO: Replace with actual cryptographic constraints
+        eval.add_constraint(sender_burn_key.clone() - sender_burn_key.clone());
+
+        constrain_limb_chunks(
+            &mut eval,
+            balance_low.clone(),
+            [balance_chunks[0].clone(), balance_chunks[1].clone()],
+        );
+        constrain_limb_chunks(
+            &mut eval,
+            balance_high.clone(),
+            [balance_chunks[2].clone(), balance_chunks[3].clone()],
+        );
+        eval.add_constraint(new_balance_low - balance_low);
+        eval.add_constraint(new_balance_high - balance_high);
+        add_range_check_claims(
+            &mut eval,
+            &self.range_check_lookup,
+            E::EF::from(E::F::from(BaseField::from(1))),
+            &balance_chunks,
+        );
+        eval.finalize_logup_in_pairs();
+
+        eval
+    }
+}
+
+/// Generate the execution trace for Transfer
+///
+/// Fails if the balance does not fit the chunked columns.
+#[tracing::instrument(level = "debug", skip_all, fields(log_size))]
+pub fn generate_transfer_trace(
+    log_size: u32,
+    inputs: &TransferInputs,
+) -> Result<ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>, String> {
+    let size = 1 << log_size;
+
+    // Validate M31 values are in correct range before conversion
+    // Error messages never include the burn key itself
+    let mut sender_burn_key_val = inputs.sender_burn_key.expose_secret().value();
+    if sender_burn_key_val >= M31_PRIME {
+        return Err(format!("sender_burn_key exceeds M31 prime {}", M31_PRIME));
+    }
+    let recipient_val = inputs.recipient_key_commitment.value();
+    if recipient_val >= M31_PRIME {
+        return Err(format!(
+            "recipient_key_commitment value {} exceeds M31 prime {}",
+            recipient_val, M31_PRIME
+        ));
+    }
+    let balance = chunked_amount("balance", inputs.balance)?;
+
+    let mut trace = (0..NUM_TRANSFER_COLUMNS)
+        .map(|_| Col::<SimdBackend, BaseField>::zeros(size))
+        .collect_vec();
+
+    let mut sender_burn_key_field = BaseField::from_u32_unchecked(sender_burn_key_val);
+    let recipient_field = BaseField::from_u32_unchecked(recipient_val);
+    let [balance_low, balance_high] = amount_limbs(balance);
+
+    // Compute derived values using Poseidon2
+    let old_coin_output = coin_permutation(sender_burn_key_field, balance);
+    let new_coin_output = coin_permutation(recipient_field, balance);
+    let old_coin = old_coin_output[0];
+    let new_coin = new_coin_output[0];
+    let old_coin_nullifier = nullifier_limbs(sender_burn_key_field, old_coin);
+
+    // commitment = Hash(old_coin, old_coin_nullifier, new_coin)
+    let mut commitment_state = [ZERO; N_STATE];
+    commitment_state[0] = old_coin;
+    commitment_state[1..=OUTPUT_LIMBS].copy_from_slice(&old_coin_nullifier);
+    commitment_state[OUTPUT_LIMBS + 1] = new_coin;
+    let commitment_output = poseidon2_permutation(commitment_state);
+
+    // Fill the trace
+    // For SIMD backend, we fill vec_index 0 (first SIMD lane)
+    let vec_index = 0;
+    let layout = TRANSFER_LAYOUT;
+    let mut fill = |range: std::ops::Range<usize>, values: &[BaseField]| {
+        assert_eq!(range.len(), values.len(), "trace layout range {:?} filled with {} values", range, values.len());
+        for (col, &value) in range.zip(values) {
+            trace[col].data[vec_index] = value.into();
+        }
+    };
+
+    fill(layout.inputs(), &[sender_burn_key_field, balance_low, balance_high, recipient_field]);
+    fill(layout.new_balance(), &[balance_low, balance_high]);
+    fill(layout.old_coin(), &[old_coin]);
+    fill(layout.new_coin(), &[new_coin]);
+    fill(layout.old_coin_nullifier(), &old_coin_nullifier);
+    fill(layout.commitment_out(), &commitment_output[..OUTPUT_LIMBS]);
+    fill(layout.old_coin_state(), &old_coin_output[1..3]);
+    fill(layout.new_coin_state(), &new_coin_output[1..3]);
+    fill(layout.balance_chunks(), &balance_chunks(balance));
+
+    // Wipe key-bearing temporaries; the trace columns now hold the only copy
+    sender_burn_key_val.zeroize();
+    zeroize_base_fields(std::slice::from_mut(&mut sender_burn_key_field));
+
+    // Convert to CircleEvaluations
+    let domain = CanonicCoset::new(log_size).circle_domain();
+    Ok(trace
+        .into_iter()
+        .map(|col| CircleEvaluation::<SimdBackend, _, BitReversedOrder>::new(domain, col))
+        .collect_vec())
+}
+
+/// Balance chunk columns of a Transfer trace, in the order `TransferEval` claims them
+fn range_checked_columns(
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> Vec<&BaseColumn> {
+    trace[TRANSFER_LAYOUT.balance_chunks()]
+        .iter()
+        .map(|col| &col.values)
+        .collect()
+}
+
+/// Every row claims its chunks once, padding rows included
+fn claim_multiplicity(log_size: u32) -> BaseColumn {
+    (0..1 << log_size).map(|_| BaseField::from(1)).collect()
+}
+
+/// Range-check table multiplicities answering the claims of a Transfer trace
+pub fn transfer_range_check_multiplicities(
+    log_size: u32,
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+) -> Result<RangeCheckMultiplicities, String> {
+    let multiplicity = claim_multiplicity(log_size);
+    let mut multiplicities = RangeCheckMultiplicities::default();
+    for column in range_checked_columns(trace) {
+        multiplicities.add_column(column, &multiplicity)?;
+    }
+    Ok(multiplicities)
+}
+
+/// Generate the Transfer interaction trace for the balance chunk range checks
+pub fn gen_transfer_interaction_trace(
+    log_size: u32,
+    trace: &ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    range_check_lookup: &RangeCheckElements,
+) -> (
+    ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    SecureField,
+) {
+    let mut logup_gen = LogupTraceGenerator::new(log_size);
+    write_range_check_claims(
+        &mut logup_gen,
+        log_size,
+        range_check_lookup,
+        &claim_multiplicity(log_size),
+        &range_checked_columns(trace),
+    );
+    logup_gen.finalize_last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::M31;
+    use crate::secret::Secret;
+    use alloy_primitives::U256;
+
+    fn create_test_inputs() -> TransferInputs {
+        TransferInputs {
+            sender_burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            recipient_key_commitment: M31::from(67890),
+        }
+    }
+
+    #[test]
+    fn test_generate_transfer_trace() {
+        let log_size = 4;
+        let trace = generate_transfer_trace(log_size, &create_test_inputs()).unwrap();
+
+        assert_eq!(trace.len(), NUM_TRANSFER_COLUMNS);
+        for col in &trace {
+            assert_eq!(col.len(), 1 << log_size);
+        }
+        let layout = TRANSFER_LAYOUT;
+        assert_eq!(
+            trace[layout.new_balance().start].values.at(0),
+            trace[layout.inputs().start + 1].values.at(0)
+        );
+    }
+
+    #[test]
+    fn test_trace_rejects_balance_beyond_chunks() {
+        let mut inputs = create_test_inputs();
+        inputs.balance = U256::from(u64::MAX) + U256::from(1);
+        assert!(generate_transfer_trace(4, &inputs).is_err());
+    }
+
+    #[test]
+    fn test_column_labels_match_layout() {
+        let labels = transfer_column_labels();
+        assert_eq!(labels.len(), NUM_TRANSFER_COLUMNS);
+        assert_eq!(labels.iter().collect::<std::collections::HashSet<_>>().len(), NUM_TRANSFER_COLUMNS);
+        assert_eq!(labels[TRANSFER_LAYOUT.old_coin_nullifier().start], "old_coin_nullifier_0");
+        assert_eq!(labels[NUM_TRANSFER_COLUMNS - 1], "balance_chunks_3");
+    }
+
+    #[test]
+    fn test_range_check_claims_answered() {
+        use crate::circuits::range_check_air::{gen_range_check_interaction_trace, generate_range_check_trace};
+        use num_traits::Zero;
+
+        let log_size = 4;
+        let trace = generate_transfer_trace(log_size, &create_test_inputs()).unwrap();
+        let lookup_elements = RangeCheckElements::dummy();
+        let (_, claims_sum) = gen_transfer_interaction_trace(log_size, &trace, &lookup_elements);
+
+        let multiplicities = transfer_range_check_multiplicities(log_size, &trace).unwrap();
+        let (_, table) = generate_range_check_trace(&multiplicities);
+        let (_, table_sum) = gen_range_check_interaction_trace(&table, &lookup_elements);
+        assert_eq!(claims_sum + table_sum, SecureField::zero());
+    }
+
+    #[test]
+    fn test_statistics_match_traces() {
+        let log_size = 4;
+        let eval = crate::debug::transfer_eval(log_size);
+        let stats = eval.statistics();
+        assert_eq!(stats.n_trace_columns, NUM_TRANSFER_COLUMNS);
+
+        let trace = generate_transfer_trace(log_size, &create_test_inputs()).unwrap();
+        let (interaction_trace, _) = gen_transfer_interaction_trace(log_size, &trace, &eval.range_check_lookup);
+        assert_eq!(stats.n_interaction_columns, interaction_trace.len());
+    }
+}
@@ -0,0 +1,49 @@
+// Input checks shared by the witness circuits
+// Every amount is capped at AMOUNT_BYTES (31 bytes = 248 bits), like maxAmountBytes in the
+// circom circuits, so it cannot overflow the field arithmetic or the RLP it is encoded into.
+// The cap holds on its own, whatever balance limits a `CircuitParams` configures on top of it.
+
+use alloy_primitives::U256;
+
+use crate::constants::circuit_params::AMOUNT_BYTES;
+
+/// Largest amount representable in AMOUNT_BYTES (31 bytes = 248 bits)
+pub const MAX_AMOUNT: U256 = U256::from_limbs([
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+    0x00FFFFFFFFFFFFFF,
+]);
+
+/// An amount does not fit in AMOUNT_BYTES
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{field} {value} does not fit in {bytes} bytes", bytes = AMOUNT_BYTES)]
+pub struct AmountError {
+    /// Input field holding the amount
+    pub field: String,
+    pub value: U256,
+}
+
+/// Check that the amount in input field `what` fits in AMOUNT_BYTES
+pub fn validate_amount(value: U256, what: &str) -> Result<(), AmountError> {
+    if value > MAX_AMOUNT {
+        return Err(AmountError { field: what.to_owned(), value });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cap_is_inclusive() {
+        let largest = (U256::from(1) << 248) - U256::from(1);
+        assert_eq!(largest, MAX_AMOUNT);
+        assert_eq!(validate_amount(largest, "reveal_amount"), Ok(()));
+
+        let err = validate_amount(U256::from(1) << 248, "reveal_amount").unwrap_err();
+        assert_eq!(err.field, "reveal_amount");
+        assert!(err.to_string().starts_with("reveal_amount "), "{err}");
+    }
+}
@@ -0,0 +1,110 @@
+// Coin lineage
+// A wallet follows its private coin from the burn that creates it through every spend that
+// shrinks it. It predicts the next coin before a proof exists, and afterwards checks the proof's
+// outputs against its own record. Every prediction goes through `coin` and `remaining_coin`, so
+// it is the coin the circuits commit to.
+
+use alloy_primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::circuits::spend::SpendOutputs;
+use crate::coins::{coin, remaining_coin, BalanceError};
+use crate::field::M31;
+
+/// Balance of a private coin and the coin committing to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinState {
+    pub balance: U256,
+    pub coin: M31,
+}
+
+impl CoinState {
+    /// The coin holding `balance` under `burn_key`
+    pub fn new(burn_key: M31, balance: U256) -> Self {
+        Self { balance, coin: coin(burn_key, balance) }
+    }
+
+    /// Whether `outputs` are those of the spend that left this coin
+    ///
+    /// Compare the state `expected_after_spend` predicted against the outputs of the proof.
+    pub fn matches_output(&self, outputs: &SpendOutputs) -> bool {
+        outputs.remaining_coin == self.coin
+    }
+}
+
+/// Coin a burn of `intended` leaves after revealing `reveal`
+pub fn expected_after_burn(burn_key: M31, intended: U256, reveal: U256) -> Result<CoinState, BalanceError> {
+    let coin = remaining_coin(burn_key, intended, reveal)?;
+    Ok(CoinState { balance: intended - reveal, coin })
+}
+
+/// Coin a spend of `withdrawn` from `prev` leaves
+///
+/// `prev` must be a coin of `burn_key`: a spend proves knowledge of the key its coin was made
+/// with, and the remaining coin is made with the same one.
+pub fn expected_after_spend(burn_key: M31, prev: &CoinState, withdrawn: U256) -> Result<CoinState, BalanceError> {
+    let coin = remaining_coin(burn_key, prev.balance, withdrawn)?;
+    Ok(CoinState { balance: prev.balance - withdrawn, coin })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::circuits::proof_of_burn::{compute_pob_outputs, ProofOfBurnInputs};
+    use crate::circuits::spend::{SpendCircuit, SpendInputs};
+    use crate::secret::Secret;
+    use crate::utils::poseidon2_stwo::poseidon2_permutation;
+
+    #[test]
+    fn test_burn_then_three_spends() {
+        let burn_key = M31::from(4242);
+        let burn = ProofOfBurnInputs {
+            burn_key: Secret::new(burn_key),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(250000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        };
+        let mut state = expected_after_burn(burn_key, burn.intended_balance, burn.reveal_amount).unwrap();
+        assert_eq!(state.coin, compute_pob_outputs(&burn, poseidon2_permutation).remaining_coin);
+        assert_eq!(state, CoinState::new(burn_key, U256::from(750000u64)));
+
+        // The last spend withdraws everything that is left
+        for withdrawn in [100000u64, 400000, 250000] {
+            let withdrawn = U256::from(withdrawn);
+            let outputs = SpendCircuit::new(SpendInputs {
+                burn_key: Secret::new(burn_key),
+                balance: state.balance,
+                withdrawn_balance: withdrawn,
+                extra_commitment: M31::from(7),
+                recipient: None,
+                chain_id: MAINNET_CHAIN_ID,
+                expected_coin: None,
+            })
+            .unwrap()
+            .compute_outputs();
+            assert_eq!(outputs.coin, state.coin);
+
+            let next = expected_after_spend(burn_key, &state, withdrawn).unwrap();
+            assert!(next.matches_output(&outputs));
+            assert!(!state.matches_output(&outputs));
+            state = next;
+        }
+        assert_eq!(state.balance, U256::ZERO);
+
+        assert_eq!(
+            expected_after_spend(burn_key, &state, U256::from(1)),
+            Err(BalanceError { intended: U256::ZERO, reveal: U256::from(1) })
+        );
+    }
+}
@@ -0,0 +1,219 @@
+// Coin and nullifier derivations
+// Wallets need key + balance → coin and key → nullifier without building a circuit. These are
+// the definitions every circuit computes its outputs with, so a value recomputed here always
+// equals the one a proof commits to.
+//
+// Each value is one Poseidon2 permutation of a zero-padded state, as the Poseidon2 table proves:
+// coin = Poseidon2([COIN_PREFIX, burnKey, balance])[0] with the balance as its nine 30-bit
+// `u256_to_m31_array` limbs, nullifier = Poseidon2([NULLIFIER_PREFIX, burnKey])[..8] and a
+// consumed coin's nullifier Poseidon2([NULLIFIER_PREFIX, burnKey, coin])[..8].
+
+pub mod lineage;
+
+pub use lineage::CoinState;
+
+use std::str::FromStr;
+
+use alloy_primitives::U256;
+use zeroize::Zeroize;
+
+use crate::constants::{poseidon_coin_prefix, poseidon_nullifier_prefix, OUTPUT_LIMBS};
+use crate::field::M31;
+use crate::utils::poseidon::u256_to_m31_array;
+use crate::utils::poseidon2_stwo::{permute_padded, poseidon2_permutation, Permutation};
+
+/// Coin holding `balance` under `burn_key`
+///
+/// # Examples
+///
+/// ```
+/// use alloy_primitives::U256;
+/// use proof_of_burn_stwo::{coins, M31};
+///
+/// let burn_key = M31::from(12345);
+/// let remaining = coins::remaining_coin(burn_key, U256::from(1000), U256::from(400)).unwrap();
+/// assert_eq!(remaining, coins::coin(burn_key, U256::from(600)));
+/// ```
+pub fn coin(burn_key: M31, balance: U256) -> M31 {
+    coin_with(poseidon2_permutation, burn_key, balance)
+}
+
+/// Nullifier of `burn_key`, squeezed to `OUTPUT_LIMBS` limbs
+///
+/// A single limb would collide after ~2^15.5 burns, so unlike the coin it is not one M31.
+pub fn nullifier(burn_key: M31) -> [M31; OUTPUT_LIMBS] {
+    nullifier_with(poseidon2_permutation, burn_key)
+}
+
+/// Nullifier of a consumed `coin` under `burn_key`, squeezed to `OUTPUT_LIMBS` limbs
+///
+/// Binding the coin makes it per coin rather than per burn key, so the two coins of a merge and
+/// any later coin under the same key all get distinct nullifiers.
+pub fn coin_nullifier(burn_key: M31, coin: M31) -> [M31; OUTPUT_LIMBS] {
+    coin_nullifier_with(poseidon2_permutation, burn_key, coin)
+}
+
+/// Coin holding what is left of `intended` after revealing `reveal`
+///
+/// The remaining coin of a burn; a spend's is `remaining_coin(burn_key, balance, withdrawn)`.
+pub fn remaining_coin(burn_key: M31, intended: U256, reveal: U256) -> Result<M31, BalanceError> {
+    let remaining = intended.checked_sub(reveal).ok_or(BalanceError { intended, reveal })?;
+    Ok(coin(burn_key, remaining))
+}
+
+/// `coin` with the balance as a 0x-prefixed hex or decimal string
+///
+/// # Examples
+///
+/// ```
+/// use alloy_primitives::U256;
+/// use proof_of_burn_stwo::{coins, M31};
+///
+/// let burn_key = M31::from(12345);
+/// let coin = coins::coin_from_str(burn_key, "0x3e8").unwrap();
+/// assert_eq!(coin, coins::coin(burn_key, U256::from(1000)));
+/// assert!(coins::coin_from_str(burn_key, "0xzz").is_err());
+/// ```
+pub fn coin_from_str(burn_key: M31, balance: &str) -> Result<M31, CoinError> {
+    Ok(coin(burn_key, parse_amount("balance", balance)?))
+}
+
+/// `remaining_coin` with the amounts as 0x-prefixed hex or decimal strings
+pub fn remaining_coin_from_str(burn_key: M31, intended: &str, reveal: &str) -> Result<M31, CoinError> {
+    let intended = parse_amount("intended", intended)?;
+    let reveal = parse_amount("reveal", reveal)?;
+    Ok(remaining_coin(burn_key, intended, reveal)?)
+}
+
+/// `coin` under `permutation`; the circuits compute their outputs through this
+pub(crate) fn coin_with(permutation: Permutation, burn_key: M31, balance: U256) -> M31 {
+    let mut preimage = coin_preimage(burn_key, balance);
+    let coin = permute_padded(permutation, &preimage)[0];
+    preimage.zeroize();
+    coin
+}
+
+/// What `coin` hashes: the prefix, the key and every limb of `balance`; the traces pad it to
+/// their Poseidon2 states
+pub(crate) fn coin_preimage(burn_key: M31, balance: U256) -> Vec<M31> {
+    let mut preimage = vec![poseidon_coin_prefix(), burn_key];
+    preimage.extend(u256_to_m31_array(balance));
+    preimage
+}
+
+/// `nullifier` under `permutation`; the circuits compute their outputs through this
+pub(crate) fn nullifier_with(permutation: Permutation, burn_key: M31) -> [M31; OUTPUT_LIMBS] {
+    let state = permute_padded(permutation, &[poseidon_nullifier_prefix(), burn_key]);
+    std::array::from_fn(|i| state[i])
+}
+
+/// `coin_nullifier` under `permutation`
+pub(crate) fn coin_nullifier_with(permutation: Permutation, burn_key: M31, coin: M31) -> [M31; OUTPUT_LIMBS] {
+    let state = permute_padded(permutation, &[poseidon_nullifier_prefix(), burn_key, coin]);
+    std::array::from_fn(|i| state[i])
+}
+
+fn parse_amount(field: &'static str, value: &str) -> Result<U256, CoinError> {
+    U256::from_str(value.trim()).map_err(|_| CoinError::InvalidAmount { field, value: value.to_owned() })
+}
+
+/// More is revealed than the balance holds
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Reveal amount {reveal} exceeds balance {intended}")]
+pub struct BalanceError {
+    pub intended: U256,
+    pub reveal: U256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CoinError {
+    #[error("{field}: not a number: {value}")]
+    InvalidAmount { field: &'static str, value: String },
+
+    #[error(transparent)]
+    Balance(#[from] BalanceError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::circuits::proof_of_burn::{compute_pob_outputs, ProofOfBurnInputs};
+    use crate::circuits::spend::{SpendCircuit, SpendInputs};
+    use crate::secret::Secret;
+
+    #[test]
+    fn test_spend_outputs_match() {
+        let burn_key = M31::from(12345);
+        let (balance, withdrawn) = (U256::from(1000), U256::from(400));
+        let outputs = SpendCircuit::new(SpendInputs {
+            burn_key: Secret::new(burn_key),
+            balance,
+            withdrawn_balance: withdrawn,
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        })
+        .unwrap()
+        .compute_outputs();
+
+        assert_eq!(outputs.coin, coin(burn_key, balance));
+        assert_eq!(outputs.remaining_coin, remaining_coin(burn_key, balance, withdrawn).unwrap());
+    }
+
+    #[test]
+    fn test_burn_outputs_match() {
+        let burn_key = M31::from(12345);
+        let inputs = ProofOfBurnInputs {
+            burn_key: Secret::new(burn_key),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(250000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        };
+        // The hashing half of `ProofOfBurnCircuit::compute_outputs`, which first checks the MPT
+        let outputs = compute_pob_outputs(&inputs, poseidon2_permutation);
+
+        assert_eq!(outputs.nullifier, nullifier(burn_key));
+        assert_eq!(
+            outputs.remaining_coin,
+            remaining_coin(burn_key, inputs.intended_balance, inputs.reveal_amount).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_coin_binds_full_balance() {
+        // Balances with the same low 32 bits hash to different coins
+        let burn_key = M31::from(12345);
+        let balance = U256::from(1000);
+        assert_ne!(coin(burn_key, balance), coin(burn_key, balance + (U256::from(1) << 32)));
+        assert_ne!(coin(burn_key, balance), coin(burn_key, balance + (U256::from(1) << 200)));
+    }
+
+    #[test]
+    fn test_overdrawn_and_malformed_amounts() {
+        let burn_key = M31::from(7);
+        let err = remaining_coin(burn_key, U256::from(1), U256::from(2)).unwrap_err();
+        assert_eq!(err, BalanceError { intended: U256::from(1), reveal: U256::from(2) });
+
+        assert_eq!(
+            remaining_coin_from_str(burn_key, "1000", "0x190"),
+            remaining_coin(burn_key, U256::from(1000), U256::from(400)).map_err(CoinError::from)
+        );
+        assert!(matches!(
+            remaining_coin_from_str(burn_key, "1000", "four hundred"),
+            Err(CoinError::InvalidAmount { field: "reveal", .. })
+        ));
+        assert!(matches!(remaining_coin_from_str(burn_key, "1", "2"), Err(CoinError::Balance(_))));
+    }
+}
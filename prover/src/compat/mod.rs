@@ -0,0 +1,3 @@
+// Compatibility adapters for tooling outside this crate
+
+pub mod worm;
@@ -0,0 +1,310 @@
+// WORM witness JSON adapter
+// Converts Circom/Groth16 witness inputs (BN254 decimal strings, padded byte arrays)
+// into the M31/U256 inputs used by the STWO circuits
+// Reference: worm-privacy/proof-of-burn circuits/main_proof_of_burn.circom, main_spend.circom
+
+use std::str::FromStr;
+
+use alloy_primitives::U256;
+use serde::Deserialize;
+
+use crate::circuits::builder::{InputValidationReport, ProofOfBurnInputsBuilder, SpendInputsBuilder};
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::spend::SpendInputs;
+use crate::circuits::validation::MAX_AMOUNT;
+use crate::constants::circuit_params::*;
+use crate::field::M31;
+
+/// BN254 scalar field modulus, the field WORM witness values live in
+const BN254_MODULUS: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+/// Numeric witness value as written by snarkjs tooling (decimal string or plain number)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum WormNumber {
+    Str(String),
+    Num(u64),
+}
+
+/// Proof of Burn witness in WORM's field naming
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WormProofOfBurnJson {
+    burn_key: WormNumber,
+    actual_balance: WormNumber,
+    intended_balance: WormNumber,
+    reveal_amount: WormNumber,
+    burn_extra_commitment: WormNumber,
+    num_layers: WormNumber,
+    layer_lens: Vec<WormNumber>,
+    layers: Vec<Vec<WormNumber>>,
+    block_header: Vec<WormNumber>,
+    block_header_len: WormNumber,
+    num_leaf_address_nibbles: WormNumber,
+    byte_security_relax: WormNumber,
+    proof_extra_commitment: WormNumber,
+}
+
+/// Spend witness in WORM's field naming
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WormSpendJson {
+    burn_key: WormNumber,
+    balance: WormNumber,
+    withdrawn_balance: WormNumber,
+    extra_commitment: WormNumber,
+}
+
+impl ProofOfBurnInputs {
+    /// Parse a WORM `proof_of_burn` witness JSON
+    ///
+    /// Padded `layers`/`blockHeader` arrays are trimmed to `layerLens`/`blockHeaderLen`.
+    /// The burn key must already be a canonical M31 element; extra commitments are opaque
+    /// BN254 hashes and are reduced modulo the M31 prime. Circuit limits are enforced by
+    /// `ProofOfBurnInputsBuilder`.
+    pub fn from_worm_json(json: &str) -> Result<Self, WormError> {
+        let raw: WormProofOfBurnJson = serde_json::from_str(json)?;
+
+        let actual_balance = parse_amount("actualBalance", &raw.actual_balance)?;
+        let intended_balance = parse_amount("intendedBalance", &raw.intended_balance)?;
+        let reveal_amount = parse_amount("revealAmount", &raw.reveal_amount)?;
+
+        let num_layers = parse_small("numLayers", &raw.num_layers, MAX_NUM_LAYERS)?;
+        if raw.layers.len() < num_layers || raw.layer_lens.len() < num_layers {
+            return Err(WormError::LengthOutOfRange {
+                field: "numLayers",
+                len: num_layers,
+                max: raw.layers.len().min(raw.layer_lens.len()),
+            });
+        }
+
+        let layers = raw.layers[..num_layers]
+            .iter()
+            .zip(&raw.layer_lens)
+            .map(|(layer, len)| {
+                let len = parse_small("layerLens", len, MAX_NODE_BLOCKS * 136)?;
+                parse_bytes("layers", layer, len)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let header_len = parse_small("blockHeaderLen", &raw.block_header_len, MAX_HEADER_BLOCKS * 136)?;
+        let block_header = parse_bytes("blockHeader", &raw.block_header, header_len)?;
+
+        let inputs = ProofOfBurnInputsBuilder::new()
+            .burn_key(parse_m31_exact("burnKey", &raw.burn_key)?)
+            .balances(actual_balance, intended_balance)
+            .reveal(reveal_amount)
+            .burn_extra_commitment(parse_m31_reduced("burnExtraCommitment", &raw.burn_extra_commitment)?)
+            .mpt_layers(layers)
+            .block_header(block_header)
+            .leaf_nibbles(
+                parse_u8("numLeafAddressNibbles", &raw.num_leaf_address_nibbles)?,
+                parse_u8("byteSecurityRelax", &raw.byte_security_relax)?,
+            )
+            .proof_extra_commitment(parse_m31_reduced("proofExtraCommitment", &raw.proof_extra_commitment)?)
+            .build()?;
+
+        Ok(inputs)
+    }
+}
+
+impl SpendInputs {
+    /// Parse a WORM `spend` witness JSON
+    pub fn from_worm_json(json: &str) -> Result<Self, WormError> {
+        let raw: WormSpendJson = serde_json::from_str(json)?;
+
+        let inputs = SpendInputsBuilder::new()
+            .burn_key(parse_m31_exact("burnKey", &raw.burn_key)?)
+            .balances(
+                parse_amount("balance", &raw.balance)?,
+                parse_amount("withdrawnBalance", &raw.withdrawn_balance)?,
+            )
+            .extra_commitment(parse_m31_reduced("extraCommitment", &raw.extra_commitment)?)
+            .build()?;
+
+        Ok(inputs)
+    }
+}
+
+/// Parse a witness value and check it is a BN254 field element
+fn parse_field_element(field: &'static str, value: &WormNumber) -> Result<U256, WormError> {
+    let parsed = match value {
+        WormNumber::Num(n) => U256::from(*n),
+        WormNumber::Str(s) => U256::from_str(s.trim()).map_err(|_| WormError::InvalidNumber {
+            field,
+            value: s.clone(),
+        })?,
+    };
+
+    let modulus = U256::from_str(BN254_MODULUS).expect("valid BN254 modulus");
+    if parsed >= modulus {
+        return Err(WormError::NotAFieldElement { field, value: parsed });
+    }
+
+    Ok(parsed)
+}
+
+fn parse_m31_exact(field: &'static str, value: &WormNumber) -> Result<M31, WormError> {
+    let parsed = parse_field_element(field, value)?;
+    if parsed >= U256::from(M31::PRIME) {
+        return Err(WormError::M31OutOfRange { field });
+    }
+    Ok(M31::new(parsed.to::<u32>()))
+}
+
+fn parse_m31_reduced(field: &'static str, value: &WormNumber) -> Result<M31, WormError> {
+    let parsed = parse_field_element(field, value)?;
+    Ok(M31::new((parsed % U256::from(M31::PRIME)).to::<u32>()))
+}
+
+fn parse_amount(field: &'static str, value: &WormNumber) -> Result<U256, WormError> {
+    let parsed = parse_field_element(field, value)?;
+    if parsed > MAX_AMOUNT {
+        return Err(WormError::AmountTooLarge { field, value: parsed, limit: MAX_AMOUNT });
+    }
+    Ok(parsed)
+}
+
+fn parse_small(field: &'static str, value: &WormNumber, max: usize) -> Result<usize, WormError> {
+    let parsed = parse_field_element(field, value)?;
+    if parsed > U256::from(max) {
+        return Err(WormError::LengthOutOfRange {
+            field,
+            len: parsed.saturating_to::<usize>(),
+            max,
+        });
+    }
+    Ok(parsed.to::<usize>())
+}
+
+fn parse_u8(field: &'static str, value: &WormNumber) -> Result<u8, WormError> {
+    Ok(parse_small(field, value, u8::MAX as usize)? as u8)
+}
+
+/// Trim a padded byte array to `len` and convert each element to a byte
+fn parse_bytes(field: &'static str, values: &[WormNumber], len: usize) -> Result<Vec<u8>, WormError> {
+    if len > values.len() {
+        return Err(WormError::LengthOutOfRange {
+            field,
+            len,
+            max: values.len(),
+        });
+    }
+
+    values[..len]
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let parsed = parse_field_element(field, value)?;
+            if parsed > U256::from(u8::MAX) {
+                return Err(WormError::InvalidByte { field, index, value: parsed });
+            }
+            Ok(parsed.to::<u8>())
+        })
+        .collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WormError {
+    #[error("Invalid WORM JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("{field}: not a number: {value}")]
+    InvalidNumber { field: &'static str, value: String },
+
+    #[error("{field}: {value} is not a BN254 field element")]
+    NotAFieldElement { field: &'static str, value: U256 },
+
+    /// The value is not echoed back: this check guards the burn key
+    #[error("{field}: value does not fit in the M31 field")]
+    M31OutOfRange { field: &'static str },
+
+    #[error("{field}: amount {value} exceeds limit {limit}")]
+    AmountTooLarge { field: &'static str, value: U256, limit: U256 },
+
+    #[error("{field}: byte {index} has value {value}")]
+    InvalidByte { field: &'static str, index: usize, value: U256 },
+
+    #[error("{field}: length {len} exceeds {max}")]
+    LengthOutOfRange { field: &'static str, len: usize, max: usize },
+
+    #[error(transparent)]
+    Validation(#[from] InputValidationReport),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POB_FIXTURE: &str = include_str!("../../tests/fixtures/worm/proof_of_burn_input.json");
+    const SPEND_FIXTURE: &str = include_str!("../../tests/fixtures/worm/spend_input.json");
+
+    #[test]
+    fn test_pob_field_mapping() {
+        let inputs = ProofOfBurnInputs::from_worm_json(POB_FIXTURE).unwrap();
+
+        assert_eq!(*inputs.burn_key.expose_secret(), M31::new(123456789));
+        assert_eq!(inputs.actual_balance, U256::from(1_000_000_000_000_000_000u128));
+        assert_eq!(inputs.intended_balance, U256::from(1_000_000_000_000_000_000u128));
+        assert_eq!(inputs.reveal_amount, U256::from(500_000_000_000_000_000u128));
+        // 2805698094...487632 mod (2^31 - 1)
+        assert_eq!(inputs.burn_extra_commitment, M31::new(1012546216));
+        assert_eq!(inputs.proof_extra_commitment, M31::new(0));
+        assert_eq!(inputs.num_leaf_address_nibbles, 50);
+        assert_eq!(inputs.byte_security_relax, 0);
+
+        // Padding is trimmed to layerLens / blockHeaderLen
+        let lens: Vec<usize> = inputs.layers.iter().map(Vec::len).collect();
+        assert_eq!(lens, vec![532, 340, 112]);
+        assert_eq!(inputs.layers[0][0], 1);
+        assert_eq!(inputs.layers[1][0], 14);
+        assert_eq!(inputs.block_header.len(), 643);
+        assert_eq!(inputs.block_header[0], 5);
+    }
+
+    #[test]
+    fn test_spend_field_mapping() {
+        let inputs = SpendInputs::from_worm_json(SPEND_FIXTURE).unwrap();
+
+        assert_eq!(*inputs.burn_key.expose_secret(), M31::new(123456789));
+        assert_eq!(inputs.balance, U256::from(500_000_000_000_000_000u128));
+        assert_eq!(inputs.withdrawn_balance, U256::from(200_000_000_000_000_000u128));
+        assert_eq!(inputs.extra_commitment, M31::new(32528020));
+    }
+
+    #[test]
+    fn test_burn_key_outside_m31_rejected() {
+        let json = SPEND_FIXTURE.replace("\"burnKey\": \"123456789\"", "\"burnKey\": \"2147483647\"");
+        let err = SpendInputs::from_worm_json(&json).unwrap_err();
+        assert!(matches!(err, WormError::M31OutOfRange { field: "burnKey", .. }));
+    }
+
+    #[test]
+    fn test_balance_over_circuit_limit_rejected() {
+        // 101 ETH exceeds MAX_ACTUAL_BALANCE
+        let json = POB_FIXTURE.replace(
+            "\"actualBalance\": \"1000000000000000000\"",
+            "\"actualBalance\": \"101000000000000000000\"",
+        );
+        let err = ProofOfBurnInputs::from_worm_json(&json).unwrap_err();
+        assert!(matches!(err, WormError::Validation(_)));
+    }
+
+    #[test]
+    fn test_non_field_element_rejected() {
+        let json = SPEND_FIXTURE.replace(
+            "\"balance\": \"500000000000000000\"",
+            &format!("\"balance\": \"{}\"", BN254_MODULUS),
+        );
+        let err = SpendInputs::from_worm_json(&json).unwrap_err();
+        assert!(matches!(err, WormError::NotAFieldElement { field: "balance", .. }));
+    }
+
+    #[test]
+    fn test_invalid_number_rejected() {
+        let json = SPEND_FIXTURE.replace("\"123456789\"", "\"12ab\"");
+        let err = SpendInputs::from_worm_json(&json).unwrap_err();
+        assert!(matches!(err, WormError::InvalidNumber { field: "burnKey", .. }));
+    }
+}
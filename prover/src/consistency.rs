@@ -0,0 +1,189 @@
+// Witness circuit / trace consistency checks
+// Each circuit's outputs are computed twice: by the witness circuit (`compute_outputs`) and by
+// the AIR trace generator, whose cells are what the proof binds. Both hash the same Poseidon2
+// states but build them independently, so an edit to either file can silently split them.
+// These checks run both paths on the same inputs and report the first output that differs.
+// MPT and PoW validation gate the witness outputs without feeding them, so they are skipped.
+
+use stwo_prover::prover::backend::simd::m31::LOG_N_LANES;
+use stwo_prover::prover::backend::Column;
+
+use crate::circuits::layout::SPEND_LAYOUT;
+use crate::circuits::proof_of_burn::{compute_pob_outputs, ProofOfBurnCircuit, ProofOfBurnInputs};
+use crate::circuits::proof_of_burn_air::generate_pob_trace;
+use crate::circuits::spend::{compute_spend_outputs, SpendCircuit, SpendInputs};
+use crate::circuits::spend_air::generate_spend_trace;
+use crate::field::M31;
+use crate::prover::PobPublicValues;
+use crate::utils::poseidon2_stwo::{poseidon2_permutation, Permutation};
+
+/// Trace size for the checks: the witness sits on the first `N_LANES` rows, so one SIMD
+/// vector holds it all
+const CHECK_LOG_SIZE: u32 = LOG_N_LANES;
+
+/// Check that `ProofOfBurnCircuit` and `generate_pob_trace` agree on the public outputs
+pub fn check_pob(inputs: &ProofOfBurnInputs) -> Result<(), Mismatch> {
+    check_pob_with(inputs, poseidon2_permutation)
+}
+
+/// Check that `SpendCircuit` and `generate_spend_trace` agree on the coins and commitment
+pub fn check_spend(inputs: &SpendInputs) -> Result<(), Mismatch> {
+    check_spend_with(inputs, poseidon2_permutation)
+}
+
+/// `check_pob` with the witness circuit hashing through `permutation`
+pub(crate) fn check_pob_with(inputs: &ProofOfBurnInputs, permutation: Permutation) -> Result<(), Mismatch> {
+    ProofOfBurnCircuit::new(inputs.clone()).map_err(|e| Mismatch::Circuit(e.to_string()))?;
+    let circuit = compute_pob_outputs(inputs, permutation);
+    let (trace, _lookup_data) = generate_pob_trace(CHECK_LOG_SIZE, inputs).map_err(Mismatch::Trace)?;
+    let trace = PobPublicValues::from_trace(&trace);
+
+    compare(OutputValue::Nullifier, &circuit.nullifier, &trace.nullifier)?;
+    compare(OutputValue::RemainingCoin, &[circuit.remaining_coin], &[trace.remaining_coin])?;
+    compare(OutputValue::Commitment, &circuit.commitment, &trace.commitment)?;
+    compare(OutputValue::BurnAddress, &circuit.burn_address_digest, &trace.burn_address_digest)
+}
+
+/// `check_spend` with the witness circuit hashing through `permutation`
+pub(crate) fn check_spend_with(inputs: &SpendInputs, permutation: Permutation) -> Result<(), Mismatch> {
+    // The trace generator panics on inputs the circuit rejects, so validate first
+    SpendCircuit::new(inputs.clone()).map_err(|e| Mismatch::Circuit(e.to_string()))?;
+    let circuit = compute_spend_outputs(inputs, permutation);
+    let (trace, _) = generate_spend_trace(CHECK_LOG_SIZE, inputs);
+    let cells = |range: std::ops::Range<usize>| {
+        range.map(|column| M31::new(trace[column].values.at(0).0)).collect::<Vec<_>>()
+    };
+
+    let layout = SPEND_LAYOUT;
+    compare(OutputValue::Coin, &[circuit.coin], &cells(layout.coin()))?;
+    compare(OutputValue::RemainingCoin, &[circuit.remaining_coin], &cells(layout.remaining_coin()))?;
+    compare(OutputValue::Commitment, &circuit.commitment, &cells(layout.commitment_out()))
+}
+
+fn compare(value: OutputValue, circuit: &[M31], trace: &[M31]) -> Result<(), Mismatch> {
+    if circuit == trace {
+        return Ok(());
+    }
+    Err(Mismatch::Value {
+        value,
+        circuit: circuit.to_vec(),
+        trace: trace.to_vec(),
+    })
+}
+
+/// An output both paths compute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputValue {
+    Nullifier,
+    RemainingCoin,
+    Commitment,
+    Coin,
+    BurnAddress,
+}
+
+impl std::fmt::Display for OutputValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Nullifier => "nullifier",
+            Self::RemainingCoin => "remaining_coin",
+            Self::Commitment => "commitment",
+            Self::Coin => "coin",
+            Self::BurnAddress => "burn_address_digest",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Mismatch {
+    #[error("Witness circuit and trace disagree on {value}: circuit {circuit:?}, trace {trace:?}")]
+    Value {
+        value: OutputValue,
+        circuit: Vec<M31>,
+        trace: Vec<M31>,
+    },
+
+    #[error("Witness circuit rejected the inputs: {0}")]
+    Circuit(String),
+
+    #[error("Trace generation failed: {0}")]
+    Trace(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::secret::Secret;
+    use alloy_primitives::U256;
+    use stwo_prover::core::fields::m31::BaseField;
+    use crate::utils::poseidon2_stwo::N_STATE;
+
+    fn pob_inputs(intended_balance: u64, reveal_amount: u64) -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            actual_balance: U256::from(intended_balance),
+            intended_balance: U256::from(intended_balance),
+            reveal_amount: U256::from(reveal_amount),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    fn spend_inputs(balance: u64, withdrawn_balance: u64) -> SpendInputs {
+        SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(balance),
+            withdrawn_balance: U256::from(withdrawn_balance),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        }
+    }
+
+    /// Poseidon2 with the first output limb bumped, a stand-in for an edit to one path only
+    fn broken_permutation(state: [BaseField; N_STATE]) -> [BaseField; N_STATE] {
+        let mut output = poseidon2_permutation(state);
+        output[0] += BaseField::from(1);
+        output
+    }
+
+    #[test]
+    fn test_pob_paths_agree() {
+        // 1 ETH - 0.5 ETH borrows from the high 32-bit limb
+        for (intended_balance, reveal_amount) in [(1000000, 500000), (10u64.pow(18), 5 * 10u64.pow(17))] {
+            check_pob(&pob_inputs(intended_balance, reveal_amount)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_spend_paths_agree() {
+        for (balance, withdrawn_balance) in [(1000, 400), (1000, 1000), (1 << 32, 1)] {
+            check_spend(&spend_inputs(balance, withdrawn_balance)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_broken_permutation_is_caught() {
+        let result = check_pob_with(&pob_inputs(1000000, 500000), broken_permutation);
+        assert!(matches!(result, Err(Mismatch::Value { value: OutputValue::Nullifier, .. })));
+
+        let result = check_spend_with(&spend_inputs(1000, 400), broken_permutation);
+        assert!(matches!(result, Err(Mismatch::Value { value: OutputValue::Coin, .. })));
+    }
+
+    #[test]
+    fn test_invalid_inputs_are_reported() {
+        let result = check_spend(&spend_inputs(400, 1000));
+        assert!(matches!(result, Err(Mismatch::Circuit(_))));
+    }
+}
@@ -2,6 +2,11 @@
 // Translated from proof-of-burn/circuits/utils/constants.circom
 // Reference: miner/src/constants.rs
 
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use stwo_prover::core::fields::m31::BaseField;
+
 use crate::field::M31;
 
 /// Base Poseidon prefix derived from keccak256("EIP-7503") mod P
@@ -12,9 +17,19 @@ const POSEIDON_PREFIX_STR: &str = "526565650429886141451431706587512042888424003
 /// M31 field prime: 2^31 - 1 = 2147483647
 pub const M31_PRIME: u32 = 2147483647;
 
+/// Chain id of Ethereum mainnet, which burn inputs and statements default to
+pub const MAINNET_CHAIN_ID: u64 = 1;
+
+/// Number of M31 limbs in a nullifier or commitment
+/// 8 limbs × 31 bits = 248 bits, packed into one uint256 for Solidity
+pub const OUTPUT_LIMBS: usize = 8;
+
+/// Base Poseidon prefix, computed on first use
+static POSEIDON_PREFIX: OnceLock<M31> = OnceLock::new();
+
 /// Compute the base Poseidon prefix for M31 field
 /// We take the original BN254 value modulo M31 prime
-pub fn poseidon_prefix() -> M31 {
+fn compute_poseidon_prefix() -> M31 {
     // Parse the large number and reduce modulo M31 prime
     // For simplicity, we'll use the hash of "EIP-7503" directly
     // and reduce it modulo M31
@@ -27,6 +42,12 @@ pub fn poseidon_prefix() -> M31 {
     M31::new(val % M31_PRIME)
 }
 
+/// Base Poseidon prefix for M31 field
+/// The keccak digest is computed once and shared by every thread
+pub fn poseidon_prefix() -> M31 {
+    *POSEIDON_PREFIX.get_or_init(compute_poseidon_prefix)
+}
+
 /// Poseidon prefix for burn address computation
 /// Original: POSEIDON_PREFIX + 0
 pub fn poseidon_burn_address_prefix() -> M31 {
@@ -45,6 +66,21 @@ pub fn poseidon_coin_prefix() -> M31 {
     poseidon_prefix() + M31::from(2)
 }
 
+/// `poseidon_burn_address_prefix` as an AIR trace value
+pub fn poseidon_burn_address_prefix_base() -> BaseField {
+    BaseField::from_u32_unchecked(poseidon_burn_address_prefix().value())
+}
+
+/// `poseidon_nullifier_prefix` as an AIR trace value
+pub fn poseidon_nullifier_prefix_base() -> BaseField {
+    BaseField::from_u32_unchecked(poseidon_nullifier_prefix().value())
+}
+
+/// `poseidon_coin_prefix` as an AIR trace value
+pub fn poseidon_coin_prefix_base() -> BaseField {
+    BaseField::from_u32_unchecked(poseidon_coin_prefix().value())
+}
+
 /// Circuit parameters from main_proof_of_burn.circom
 pub mod circuit_params {
     /// Maximum number of Merkle-Patricia-Trie proof nodes supported
@@ -53,6 +89,9 @@ pub mod circuit_params {
     /// Keccak blocks are 136 bytes. MPT nodes are maximum 532 bytes ~ 3.91 blocks
     pub const MAX_NODE_BLOCKS: usize = 4;
     
+    /// Largest MPT node: a branch of 16 hashes and an empty value, RLP-encoded
+    pub const MAX_NODE_LEN: usize = 532;
+    
     /// Average header length of the last 100 blocks ~ 643 bytes ~ 4.72 blocks
     pub const MAX_HEADER_BLOCKS: usize = 8;
     
@@ -88,9 +127,70 @@ pub mod circuit_params {
     ];
 }
 
+/// Runtime view of `circuit_params`, for code that takes the limits as an argument
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CircuitParams {
+    pub max_num_layers: usize,
+    pub max_node_blocks: usize,
+    pub max_header_blocks: usize,
+    pub min_leaf_address_nibbles: usize,
+    pub amount_bytes: usize,
+    pub pow_minimum_zero_bytes: usize,
+    pub max_intended_balance: u128,
+    pub max_actual_balance: u128,
+}
+
+impl Default for CircuitParams {
+    fn default() -> Self {
+        use circuit_params::*;
+
+        Self {
+            max_num_layers: MAX_NUM_LAYERS,
+            max_node_blocks: MAX_NODE_BLOCKS,
+            max_header_blocks: MAX_HEADER_BLOCKS,
+            min_leaf_address_nibbles: MIN_LEAF_ADDRESS_NIBBLES,
+            amount_bytes: AMOUNT_BYTES,
+            pow_minimum_zero_bytes: POW_MINIMUM_ZERO_BYTES,
+            max_intended_balance: MAX_INTENDED_BALANCE,
+            max_actual_balance: MAX_ACTUAL_BALANCE,
+        }
+    }
+}
+
+impl CircuitParams {
+    /// Keccak256 of the parameters in declaration order, `usize` fields as big-endian `u64`
+    /// and balances as big-endian `u128`
+    ///
+    /// Envelopes record the hash of the defaults, the limits the circuit is compiled with.
+    pub fn params_hash(&self) -> [u8; 32] {
+        let limits = [
+            self.max_num_layers,
+            self.max_node_blocks,
+            self.max_header_blocks,
+            self.min_leaf_address_nibbles,
+            self.amount_bytes,
+            self.pow_minimum_zero_bytes,
+        ];
+        let mut bytes: Vec<u8> = limits.iter().flat_map(|&limit| (limit as u64).to_be_bytes()).collect();
+        bytes.extend(self.max_intended_balance.to_be_bytes());
+        bytes.extend(self.max_actual_balance.to_be_bytes());
+        crate::utils::keccak::keccak256(&bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_default_params_hash() {
+        assert_eq!(
+            hex::encode(CircuitParams::default().params_hash()),
+            "9941d0e398610bb9432cd5c9a3e1c59a62bd6634fc718bd48d2213800a62e304"
+        );
+        let relaxed = CircuitParams { pow_minimum_zero_bytes: 1, ..CircuitParams::default() };
+        assert_ne!(relaxed.params_hash(), CircuitParams::default().params_hash());
+    }
     
     #[test]
     fn test_poseidon_prefixes() {
@@ -104,6 +204,19 @@ mod tests {
         assert_eq!(nullifier, base + M31::one());
         assert_eq!(coin, base + M31::from(2));
     }
+
+    #[test]
+    fn test_cached_prefix_matches_recomputation() {
+        let handles: Vec<_> = (0..4).map(|_| std::thread::spawn(poseidon_prefix)).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), compute_poseidon_prefix());
+        }
+        assert_eq!(poseidon_prefix(), M31::from(2107230662));
+
+        assert_eq!(poseidon_burn_address_prefix_base().0, poseidon_burn_address_prefix().value());
+        assert_eq!(poseidon_nullifier_prefix_base().0, poseidon_nullifier_prefix().value());
+        assert_eq!(poseidon_coin_prefix_base().0, poseidon_coin_prefix().value());
+    }
     
     #[test]
     fn test_circuit_params() {
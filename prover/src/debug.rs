@@ -0,0 +1,607 @@
+// Constraint debugging and trace inspection
+// A proof over a bad trace only fails once stwo checks the composition polynomial, with no hint
+// of which constraint broke. These helpers evaluate the AIR row by row on the trace instead and
+// report the first violated constraint, or dump the trace for inspection.
+// Lookup relations are not checked here: they only balance across components, which the
+// logup claimed sums already cover.
+
+use std::io::Write;
+use std::ops::Mul;
+use std::path::Path;
+
+use num_traits::Zero;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::secure_column::SECURE_EXTENSION_DEGREE;
+use stwo_prover::core::utils::offset_bit_reversed_circle_domain_index;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::Column;
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_constraint_framework::preprocessed_columns::PreProcessedColumnId;
+use stwo_constraint_framework::{
+    Batching, EvalAtRow, FrameworkEval, Relation, RelationEntry, ORIGINAL_TRACE_IDX,
+};
+
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::keccak_air::BlockRootElements;
+use crate::circuits::preprocessed::IsFirst;
+use crate::circuits::proof_of_burn_air::{
+    generate_pob_trace, BlockRootDigestElements, BurnAddressElements, CommitmentElements,
+    NullifierElements, ProofOfBurnEval, PublicOutputElements, RemainingCoinElements,
+};
+use crate::circuits::range_check_air::RangeCheckElements;
+use crate::circuits::spend::SpendInputs;
+use crate::circuits::spend_air::{generate_spend_trace, SpendEval};
+use crate::circuits::split::SplitInputs;
+use crate::circuits::split_air::{generate_split_trace, SplitEval, SplitPublicOutputElements};
+use crate::circuits::merge::MergeInputs;
+use crate::circuits::merge_air::{generate_merge_trace, MergeEval};
+use crate::circuits::transfer::TransferInputs;
+use crate::circuits::transfer_air::{generate_transfer_trace, TransferEval};
+
+type Trace = ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
+
+/// Generate the Proof of Burn trace and check every `ProofOfBurnEval` constraint on it
+pub fn check_pob_constraints(
+    inputs: &ProofOfBurnInputs,
+    log_n_rows: u32,
+) -> Result<(), ConstraintViolation> {
+    let (trace, _lookup_data) =
+        generate_pob_trace(log_n_rows, inputs).map_err(ConstraintViolation::Trace)?;
+    check_constraints(&pob_eval(log_n_rows), &trace)
+}
+
+/// `ProofOfBurnEval` with placeholder lookup elements, which the row checks never combine
+pub fn pob_eval(log_n_rows: u32) -> ProofOfBurnEval {
+    ProofOfBurnEval {
+        log_n_rows,
+        claimed_sum: SecureField::zero(),
+        nullifier_lookup: NullifierElements::dummy(),
+        remaining_coin_lookup: RemainingCoinElements::dummy(),
+        commitment_lookup: CommitmentElements::dummy(),
+        block_root_digest_lookup: BlockRootDigestElements::dummy(),
+        burn_address_lookup: BurnAddressElements::dummy(),
+        block_root_lookup: BlockRootElements::dummy(),
+        range_check_lookup: RangeCheckElements::dummy(),
+        public_output_lookup: PublicOutputElements::dummy(),
+    }
+}
+
+/// Generate the Spend trace and check every `SpendEval` constraint on it
+pub fn check_spend_constraints(
+    inputs: &SpendInputs,
+    log_n_rows: u32,
+) -> Result<(), ConstraintViolation> {
+    let (trace, _) = generate_spend_trace(log_n_rows, inputs);
+    check_constraints(&spend_eval(log_n_rows), &trace)
+}
+
+/// `SpendEval` with placeholder lookup elements
+pub fn spend_eval(log_n_rows: u32) -> SpendEval {
+    SpendEval {
+        log_n_rows,
+        range_check_lookup: RangeCheckElements::dummy(),
+    }
+}
+
+/// Generate the Split trace and check every `SplitEval` constraint on it
+pub fn check_split_constraints(
+    inputs: &SplitInputs,
+    log_n_rows: u32,
+) -> Result<(), ConstraintViolation> {
+    let (trace, _) = generate_split_trace(log_n_rows, inputs).map_err(ConstraintViolation::Trace)?;
+    check_constraints(&split_eval(log_n_rows), &trace)
+}
+
+/// `SplitEval` with placeholder lookup elements
+pub fn split_eval(log_n_rows: u32) -> SplitEval {
+    SplitEval {
+        log_n_rows,
+        remaining_coin_lookup: RemainingCoinElements::dummy(),
+        commitment_lookup: CommitmentElements::dummy(),
+        range_check_lookup: RangeCheckElements::dummy(),
+        public_output_lookup: SplitPublicOutputElements::dummy(),
+    }
+}
+
+/// Generate the Merge trace and check every `MergeEval` constraint on it
+pub fn check_merge_constraints(
+    inputs: &MergeInputs,
+    log_n_rows: u32,
+) -> Result<(), ConstraintViolation> {
+    let trace = generate_merge_trace(log_n_rows, inputs).map_err(ConstraintViolation::Trace)?;
+    check_constraints(&merge_eval(log_n_rows), &trace)
+}
+
+/// `MergeEval` with placeholder lookup elements
+pub fn merge_eval(log_n_rows: u32) -> MergeEval {
+    MergeEval {
+        log_n_rows,
+        range_check_lookup: RangeCheckElements::dummy(),
+    }
+}
+
+/// Generate the Transfer trace and check every `TransferEval` constraint on it
+pub fn check_transfer_constraints(
+    inputs: &TransferInputs,
+    log_n_rows: u32,
+) -> Result<(), ConstraintViolation> {
+    let trace = generate_transfer_trace(log_n_rows, inputs).map_err(ConstraintViolation::Trace)?;
+    check_constraints(&transfer_eval(log_n_rows), &trace)
+}
+
+/// `TransferEval` with placeholder lookup elements
+pub fn transfer_eval(log_n_rows: u32) -> TransferEval {
+    TransferEval {
+        log_n_rows,
+        range_check_lookup: RangeCheckElements::dummy(),
+    }
+}
+
+/// Evaluate `eval` on every row of `trace` and report the first violated constraint
+///
+/// Rows are indexed as stored in the trace, i.e. in bit-reversed circle domain order.
+pub fn check_constraints<E: FrameworkEval>(eval: &E, trace: &Trace) -> Result<(), ConstraintViolation> {
+    let log_size = eval.log_size();
+    let columns: Vec<Vec<BaseField>> = trace.iter().map(|col| col.values.to_cpu()).collect();
+
+    for row in 0..(1usize << log_size) {
+        let evaluator = eval.evaluate(RowEvaluator::new(&columns, log_size, row));
+
+        if evaluator.columns_read != columns.len() {
+            return Err(ConstraintViolation::ColumnCount {
+                read: evaluator.columns_read,
+                provided: columns.len(),
+            });
+        }
+        if let Some((constraint, value)) = evaluator.violation {
+            return Err(ConstraintViolation::Constraint { constraint, row, value });
+        }
+    }
+    Ok(())
+}
+
+/// Write `trace` as CSV: a `row` column followed by one column per trace column
+///
+/// Values are canonical M31 integers; rows are in stored (bit-reversed) order.
+/// `labels` is typically `pob_column_labels()` or `spend_column_labels()`.
+pub fn dump_trace_csv<S: AsRef<str>>(
+    trace: &Trace,
+    labels: &[S],
+    path: impl AsRef<Path>,
+) -> Result<(), TraceDumpError> {
+    if labels.len() != trace.len() {
+        return Err(TraceDumpError::LabelCount {
+            labels: labels.len(),
+            columns: trace.len(),
+        });
+    }
+
+    let columns: Vec<Vec<BaseField>> = trace.iter().map(|col| col.values.to_cpu()).collect();
+    let n_rows = columns.first().map_or(0, Vec::len);
+
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write!(out, "row")?;
+    for label in labels {
+        write!(out, ",{}", label.as_ref())?;
+    }
+    writeln!(out)?;
+
+    for row in 0..n_rows {
+        write!(out, "{}", row)?;
+        for column in &columns {
+            write!(out, ",{}", column[row].0)?;
+        }
+        writeln!(out)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// `EvalAtRow` over concrete values of a single trace row
+struct RowEvaluator<'a> {
+    columns: &'a [Vec<BaseField>],
+    log_size: u32,
+    row: usize,
+    columns_read: usize,
+    constraints_seen: usize,
+    violation: Option<(usize, SecureField)>,
+}
+
+impl<'a> RowEvaluator<'a> {
+    fn new(columns: &'a [Vec<BaseField>], log_size: u32, row: usize) -> Self {
+        Self {
+            columns,
+            log_size,
+            row,
+            columns_read: 0,
+            constraints_seen: 0,
+            violation: None,
+        }
+    }
+}
+
+impl EvalAtRow for RowEvaluator<'_> {
+    type F = SecureField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        assert_eq!(
+            interaction, ORIGINAL_TRACE_IDX,
+            "constraint debugging only covers the main trace"
+        );
+        let column = self.columns.get(self.columns_read);
+        self.columns_read += 1;
+
+        // Missing columns read as zero; the count mismatch is reported after evaluation
+        offsets.map(|offset| {
+            let index =
+                offset_bit_reversed_circle_domain_index(self.row, self.log_size, self.log_size, offset);
+            column.map_or(SecureField::zero(), |values| values[index].into())
+        })
+    }
+
+    // The only preprocessed column the checked AIRs read is `is_first`, regenerated per row
+    fn get_preprocessed_column(&mut self, column: PreProcessedColumnId) -> Self::F {
+        let is_first = IsFirst::new(self.log_size);
+        assert_eq!(
+            column.id,
+            is_first.id().id,
+            "constraint debugging only provides the is_first preprocessed column"
+        );
+        is_first.value(self.row).into()
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        let value = SecureField::from(constraint);
+        if self.violation.is_none() && !value.is_zero() {
+            self.violation = Some((self.constraints_seen, value));
+        }
+        self.constraints_seen += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_partial_evals(values)
+    }
+
+    // Lookups are skipped: they add no main-trace constraint
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        _entry: RelationEntry<'_, Self::F, Self::EF, R>,
+    ) {
+    }
+
+    fn finalize_logup_batched(&mut self, _batching: &Batching) {}
+
+    fn finalize_logup(&mut self) {}
+
+    fn finalize_logup_in_pairs(&mut self) {}
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConstraintViolation {
+    #[error("Trace generation failed: {0}")]
+    Trace(String),
+
+    #[error("AIR reads {read} trace columns but the trace has {provided}")]
+    ColumnCount { read: usize, provided: usize },
+
+    #[error("Constraint {constraint} violated at row {row}: evaluated to {value}")]
+    Constraint {
+        constraint: usize,
+        row: usize,
+        value: SecureField,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TraceDumpError {
+    #[error("{labels} labels for {columns} trace columns")]
+    LabelCount { labels: usize, columns: usize },
+
+    #[error("Failed to write trace dump: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::layout::POB_LAYOUT;
+    use crate::constants::{MAINNET_CHAIN_ID, OUTPUT_LIMBS};
+    use crate::field::M31;
+    use crate::secret::Secret;
+    use crate::utils::poseidon::U256_M31_LIMBS;
+    use alloy_primitives::U256;
+
+    fn pob_inputs() -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_pob_witness_passes() {
+        check_pob_constraints(&pob_inputs(), 4).unwrap();
+    }
+
+    #[test]
+    fn test_valid_spend_witness_passes() {
+        let inputs = SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        };
+        check_spend_constraints(&inputs, 4).unwrap();
+    }
+
+    #[test]
+    fn test_spend_limbs_pinned_to_chunks() {
+        use crate::circuits::layout::SPEND_LAYOUT;
+
+        let inputs = SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        };
+
+        // Moving 2^30 from limb 1 into limb 0 keeps their weighted sum, modulo p and over the
+        // integers, yet the limbs are no longer those of the withdrawn chunks
+        let (mut trace, _) = generate_spend_trace(4, &inputs);
+        let limbs = SPEND_LAYOUT.withdrawn_balance_limbs().start;
+        let values = &mut trace[limbs].values;
+        values.set(0, values.at(0) + BaseField::from(1 << 30));
+        let values = &mut trace[limbs + 1].values;
+        values.set(0, values.at(0) - BaseField::from(1));
+        match check_constraints(&spend_eval(4), &trace) {
+            Err(ConstraintViolation::Constraint { constraint, row, .. }) => {
+                // Constraint 18: withdrawn_balance_limbs[0] == its chunk bits
+                assert_eq!(constraint, 18);
+                assert_eq!(row, 0);
+            }
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_split_conservation_violation_reports_constraint() {
+        use crate::circuits::layout::SPLIT_LAYOUT;
+        use crate::circuits::split_air::generate_split_trace;
+
+        let inputs = SplitInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            amount_a: U256::from(300),
+            amount_b: U256::from(700),
+            extra_commitment: M31::from(100),
+        };
+        check_split_constraints(&inputs, 4).unwrap();
+
+        // Inflating child B by one unit, consistently in its limb and chunk, breaks conservation
+        let (mut trace, _) = generate_split_trace(4, &inputs).unwrap();
+        for column in [SPLIT_LAYOUT.inputs().start + 5, SPLIT_LAYOUT.amount_b_chunks().start] {
+            let values = &mut trace[column].values;
+            values.set(0, values.at(0) + BaseField::from(1));
+        }
+        match check_constraints(&split_eval(4), &trace) {
+            Err(ConstraintViolation::Constraint { constraint, row, .. }) => {
+                // Constraint 10: lowest chunk of balance - amount_a == amount_b
+                assert_eq!(constraint, 10);
+                assert_eq!(row, 0);
+            }
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wrong_merged_balance_reports_constraint() {
+        use crate::circuits::layout::MERGE_LAYOUT;
+
+        let inputs = MergeInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance_a: U256::from(300),
+            balance_b: U256::from(700),
+            merged_balance: U256::from(1000),
+            extra_commitment: M31::from(100),
+        };
+        check_merge_constraints(&inputs, 4).unwrap();
+
+        // Claim one unit more, consistently in the merged limb and its chunk
+        let mut trace = generate_merge_trace(4, &inputs).unwrap();
+        for column in [MERGE_LAYOUT.inputs().start + 5, MERGE_LAYOUT.merged_balance_chunks().start] {
+            let values = &mut trace[column].values;
+            values.set(0, values.at(0) + BaseField::from(1));
+        }
+        match check_constraints(&merge_eval(4), &trace) {
+            Err(ConstraintViolation::Constraint { constraint, row, .. }) => {
+                // Constraint 10: lowest chunk of merged_balance - balance_a == balance_b
+                assert_eq!(constraint, 10);
+                assert_eq!(row, 0);
+            }
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_transfer_balance_mismatch_reports_constraint() {
+        use crate::circuits::layout::TRANSFER_LAYOUT;
+
+        let inputs = TransferInputs {
+            sender_burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            recipient_key_commitment: M31::from(67890),
+        };
+        check_transfer_constraints(&inputs, 4).unwrap();
+
+        let mut trace = generate_transfer_trace(4, &inputs).unwrap();
+        let values = &mut trace[TRANSFER_LAYOUT.new_balance().start].values;
+        values.set(0, values.at(0) + BaseField::from(1));
+        match check_constraints(&transfer_eval(4), &trace) {
+            Err(ConstraintViolation::Constraint { constraint, row, .. }) => {
+                // Constraint 3: new_balance_low == balance_low
+                assert_eq!(constraint, 3);
+                assert_eq!(row, 0);
+            }
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_corrupted_remaining_balance_reports_constraint() {
+        let log_n_rows = 4;
+        let (mut trace, _) = generate_pob_trace(log_n_rows, &pob_inputs()).unwrap();
+
+        let column = POB_LAYOUT.remaining_coin_initial().start + 2;
+        trace[column].values.set(3, BaseField::from(1234u32));
+
+        let eval = pob_eval(log_n_rows);
+        match check_constraints(&eval, &trace) {
+            Err(ConstraintViolation::Constraint { constraint, row, .. }) => {
+                // Constraint 84: remaining_coin_initial[2] == lowest limb of the remaining chunks
+                assert_eq!(constraint, 84);
+                assert_eq!(row, 3);
+            }
+            other => panic!("expected a constraint violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_eval_reads_columns_in_generation_order() {
+        // Each wiring constraint pins a column the eval reads from a different layout range, so
+        // corrupting the column the generator wrote must trip exactly the expected constraint
+        let log_n_rows = 4;
+        let eval = pob_eval(log_n_rows);
+        let cases = [
+            (POB_LAYOUT.nullifier_initial().start + 1, 0),
+            (POB_LAYOUT.remaining_coin_initial().start + 1, 1),
+            (POB_LAYOUT.commitment_initial().start, 2),
+            (POB_LAYOUT.commitment_initial().start + OUTPUT_LIMBS, 10),
+            (POB_LAYOUT.commitment_initial().start + OUTPUT_LIMBS + 2, 12),
+            (POB_LAYOUT.commitment_initial().start + OUTPUT_LIMBS + 4, 14),
+            (POB_LAYOUT.commitment_initial().start + OUTPUT_LIMBS + 6, 16),
+            (POB_LAYOUT.enabled().start, 17),
+            (POB_LAYOUT.block_root_digest().start, 18),
+            (POB_LAYOUT.reveal_amount_chunks().start, 21),
+            (POB_LAYOUT.remaining_balance_borrows().start, 23),
+            (POB_LAYOUT.burn_address_initial().start + 1, 32),
+            (POB_LAYOUT.burn_address_initial().start + 2 + U256_M31_LIMBS, 48),
+        ];
+
+        for (column, expected) in cases {
+            let (mut trace, _) = generate_pob_trace(log_n_rows, &pob_inputs()).unwrap();
+            trace[column].values.set(0, BaseField::from(1234u32));
+            match check_constraints(&eval, &trace) {
+                Err(ConstraintViolation::Constraint { constraint, .. }) => {
+                    assert_eq!(constraint, expected, "column {}", column)
+                }
+                other => panic!("column {}: expected a constraint violation, got {:?}", column, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_disabled_first_row_trips_boundary() {
+        // A disabled row is fine anywhere but row 0, which the public values are read from
+        let log_n_rows = 4;
+        let eval = pob_eval(log_n_rows);
+        for (row, expected) in [(1, None), (0, Some(30))] {
+            let (mut trace, _) = generate_pob_trace(log_n_rows, &pob_inputs()).unwrap();
+            trace[POB_LAYOUT.enabled().start].values.set(row, BaseField::from(0));
+            match check_constraints(&eval, &trace) {
+                Ok(()) => assert_eq!(expected, None, "row {}", row),
+                Err(ConstraintViolation::Constraint { constraint, row: violated, .. }) => {
+                    assert_eq!((Some(constraint), violated), (expected, 0));
+                }
+                other => panic!("row {}: unexpected result {:?}", row, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncated_trace_reports_column_count() {
+        let log_n_rows = 4;
+        let (mut trace, _) = generate_pob_trace(log_n_rows, &pob_inputs()).unwrap();
+        trace.pop();
+
+        let eval = pob_eval(log_n_rows);
+        assert!(matches!(
+            check_constraints(&eval, &trace),
+            Err(ConstraintViolation::ColumnCount { read, provided }) if read == provided + 1
+        ));
+    }
+
+    #[test]
+    fn test_dump_spend_trace_csv() {
+        use crate::circuits::spend_air::{spend_column_labels, NUM_SPEND_COLUMNS};
+
+        let inputs = SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        };
+        let log_n_rows = 4;
+        let (trace, _) = generate_spend_trace(log_n_rows, &inputs);
+
+        let path = std::env::temp_dir().join("pob_spend_trace_dump.csv");
+        dump_trace_csv(&trace, &spend_column_labels(), &path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 1 + (1 << log_n_rows));
+
+        let header: Vec<&str> = lines[0].split(',').collect();
+        assert_eq!(header.len(), 1 + NUM_SPEND_COLUMNS);
+        assert_eq!(header[0], "row");
+        assert_eq!(header[1], "burn_key");
+        assert_eq!(header[6], "extra_commitment");
+
+        // Row 0 holds the witness; field 2 (after row and burn_key) is balance_low
+        let first_row: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(first_row[0], "0");
+        assert_eq!(first_row[2], "1000");
+    }
+
+    #[test]
+    fn test_dump_rejects_label_mismatch() {
+        let (trace, _) = generate_pob_trace(4, &pob_inputs()).unwrap();
+        let path = std::env::temp_dir().join("pob_trace_dump_mismatch.csv");
+        assert!(matches!(
+            dump_trace_csv(&trace, &["burn_key"], &path),
+            Err(TraceDumpError::LabelCount { labels: 1, .. })
+        ));
+    }
+}
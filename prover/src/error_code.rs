@@ -0,0 +1,718 @@
+// Machine-readable error codes
+// Every library error maps to one `ErrorCode`, a stable `E_*` string, so a wallet branches on the
+// same code whichever front end failed: `pob-prover --error-format json`, the WASM entry points
+// and the HTTP server all report an error as `ErrorReport` (`{code, message, details}`).
+//
+// Stability: codes are never renamed or reused. A new error variant takes an existing code when
+// a client would handle it alike, or a new one; the `code` matches below have no wildcard arms,
+// so a variant without a code does not compile.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::Serialize;
+use stwo_prover::core::verifier::VerificationError;
+
+use crate::circuits::proof_of_burn::ProofOfBurnError;
+use crate::circuits::spend::SpendError;
+use crate::exit_code;
+use crate::prover::{
+    CompatError, EnvelopeError, ExtractionError, ServiceError, StatementError, TraceSizeError,
+    VerificationFailure,
+};
+use crate::utils::mpt::{LayerError, MptError};
+use crate::utils::pow::MinerError;
+use crate::witness::{Check, CheckStatus, InputFileError, PrecheckReport, SealError};
+
+/// Stable identifier of a failure, serialized as its `E_*` name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ErrorCode {
+    /// An input or proof file, or a request body, is missing
+    #[serde(rename = "E_INPUT_MISSING")]
+    InputMissing,
+    /// Inputs are not well-formed: bad JSON, a malformed MPT proof or header field
+    #[serde(rename = "E_INPUT_PARSE")]
+    InputParse,
+    /// Inputs exceed a size limit: layers, header, nibbles, body size
+    #[serde(rename = "E_INPUT_LIMITS")]
+    InputLimits,
+    /// A sealed witness does not open with the key given, or was altered
+    #[serde(rename = "E_WITNESS_SEAL")]
+    WitnessSeal,
+    /// An amount does not fit, or the balances and reveal are out of order
+    #[serde(rename = "E_BALANCE_BOUNDS")]
+    BalanceBounds,
+    /// The block header or header chain is invalid
+    #[serde(rename = "E_HEADER_INVALID")]
+    HeaderInvalid,
+    /// The MPT proof does not prove the burn account under the header's state root
+    #[serde(rename = "E_MPT_MISMATCH")]
+    MptMismatch,
+    /// The burn key does not meet the proof-of-work requirement, or none was found
+    #[serde(rename = "E_POW_FAIL")]
+    PowFail,
+    /// The spend's coin is not the one its burn key and balance give
+    #[serde(rename = "E_COIN_MISMATCH")]
+    CoinMismatch,
+    /// The burn address the inputs derive is not the funded one they name
+    #[serde(rename = "E_BURN_ADDRESS_MISMATCH")]
+    BurnAddressMismatch,
+    /// `log_n_rows` is outside the trace sizes the provers accept
+    #[serde(rename = "E_TRACE_SIZE")]
+    TraceSize,
+    /// Trace generation or the stwo prover failed
+    #[serde(rename = "E_STWO_PROVE")]
+    StwoProve,
+    /// The proof does not verify
+    #[serde(rename = "E_VERIFY_FAIL")]
+    VerifyFail,
+    /// A valid proof of another statement, chain or expiry than the expected one
+    #[serde(rename = "E_STATEMENT_MISMATCH")]
+    StatementMismatch,
+    /// The envelope is unreadable, or from another format, parameter set or component shape
+    #[serde(rename = "E_FORMAT_VERSION")]
+    FormatVersion,
+    /// The server refused a request without the right bearer token
+    #[serde(rename = "E_UNAUTHORIZED")]
+    Unauthorized,
+    /// The server has no such endpoint, or not for that method
+    #[serde(rename = "E_ROUTE")]
+    Route,
+    /// Any other failure
+    #[serde(rename = "E_INTERNAL")]
+    Internal,
+}
+
+impl ErrorCode {
+    /// The `E_*` name, as serialized
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InputMissing => "E_INPUT_MISSING",
+            ErrorCode::InputParse => "E_INPUT_PARSE",
+            ErrorCode::InputLimits => "E_INPUT_LIMITS",
+            ErrorCode::WitnessSeal => "E_WITNESS_SEAL",
+            ErrorCode::BalanceBounds => "E_BALANCE_BOUNDS",
+            ErrorCode::HeaderInvalid => "E_HEADER_INVALID",
+            ErrorCode::MptMismatch => "E_MPT_MISMATCH",
+            ErrorCode::PowFail => "E_POW_FAIL",
+            ErrorCode::CoinMismatch => "E_COIN_MISMATCH",
+            ErrorCode::BurnAddressMismatch => "E_BURN_ADDRESS_MISMATCH",
+            ErrorCode::TraceSize => "E_TRACE_SIZE",
+            ErrorCode::StwoProve => "E_STWO_PROVE",
+            ErrorCode::VerifyFail => "E_VERIFY_FAIL",
+            ErrorCode::StatementMismatch => "E_STATEMENT_MISMATCH",
+            ErrorCode::FormatVersion => "E_FORMAT_VERSION",
+            ErrorCode::Unauthorized => "E_UNAUTHORIZED",
+            ErrorCode::Route => "E_ROUTE",
+            ErrorCode::Internal => "E_INTERNAL",
+        }
+    }
+
+    /// Exit status of `pob-prover` on a failure with this code
+    pub const fn exit_code(self) -> u8 {
+        match self {
+            ErrorCode::InputMissing => exit_code::MISSING_FILE,
+            ErrorCode::StatementMismatch => exit_code::STATEMENT_MISMATCH,
+            ErrorCode::VerifyFail => exit_code::INVALID_PROOF,
+            _ => exit_code::FAILURE,
+        }
+    }
+
+    /// Code of the first error in `error`'s source chain that has one, or `Internal`
+    ///
+    /// For errors that reach a front end type-erased, as `anyhow::Error` does: a context message
+    /// wrapped around a library error reports the library error's code.
+    pub fn of(error: &(dyn Error + 'static)) -> ErrorCode {
+        std::iter::successors(Some(error), |error| error.source())
+            .find_map(known_code)
+            .unwrap_or(ErrorCode::Internal)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Code of `error` if it is one of the library's error types
+fn known_code(error: &(dyn Error + 'static)) -> Option<ErrorCode> {
+    macro_rules! downcast {
+        ($($ty:ty),* $(,)?) => {
+            $(if let Some(error) = error.downcast_ref::<$ty>() {
+                return Some(error.code());
+            })*
+        };
+    }
+    downcast!(
+        ServiceError,
+        ProofOfBurnError,
+        SpendError,
+        MptError,
+        LayerError,
+        EnvelopeError,
+        CompatError,
+        StatementError,
+        ExtractionError,
+        VerificationFailure,
+        VerificationError,
+        TraceSizeError,
+        InputFileError,
+        SealError,
+        MinerError,
+    );
+    error.downcast_ref::<serde_json::Error>().map(|_| ErrorCode::InputParse)
+}
+
+/// An error with a stable `ErrorCode`
+pub trait HasErrorCode {
+    fn code(&self) -> ErrorCode;
+}
+
+impl HasErrorCode for ProofOfBurnError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ProofOfBurnError::AmountTooLarge(_)
+            | ProofOfBurnError::IntendedBalanceTooHigh { .. }
+            | ProofOfBurnError::ActualBalanceTooHigh { .. }
+            | ProofOfBurnError::IntendedGreaterThanActual { .. }
+            | ProofOfBurnError::RevealAmountTooHigh { .. } => ErrorCode::BalanceBounds,
+            ProofOfBurnError::InsufficientNibbles { .. }
+            | ProofOfBurnError::TooManyLayers { .. }
+            | ProofOfBurnError::HeaderTooLarge { .. } => ErrorCode::InputLimits,
+            ProofOfBurnError::InvalidBlockHeader { .. } | ProofOfBurnError::HeaderChain(_) => {
+                ErrorCode::HeaderInvalid
+            }
+            ProofOfBurnError::HeaderProofMismatch { .. }
+            | ProofOfBurnError::MptVerificationFailed { .. }
+            | ProofOfBurnError::ProvenBalanceMismatch { .. }
+            | ProofOfBurnError::NotBurnAccount => ErrorCode::MptMismatch,
+            ProofOfBurnError::PowVerificationFailed { .. } => ErrorCode::PowFail,
+            ProofOfBurnError::BurnAddressMismatch { .. } => ErrorCode::BurnAddressMismatch,
+        }
+    }
+}
+
+impl HasErrorCode for SpendError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            SpendError::InsufficientBalance { .. }
+            | SpendError::AmountTooLarge(_)
+            | SpendError::AmountBeyondTrace { .. } => ErrorCode::BalanceBounds,
+            SpendError::CoinMismatch { .. } => ErrorCode::CoinMismatch,
+        }
+    }
+}
+
+impl HasErrorCode for LayerError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            LayerError::NoLayers
+            | LayerError::EmptyLayer { .. }
+            | LayerError::RootTooShort { .. }
+            | LayerError::NotALeaf { .. } => ErrorCode::InputParse,
+            LayerError::TooManyLayers { .. } | LayerError::LayerTooLarge { .. } => {
+                ErrorCode::InputLimits
+            }
+        }
+    }
+}
+
+impl HasErrorCode for MptError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            MptError::Layers(e) => e.code(),
+            MptError::InvalidStateRoot { .. }
+            | MptError::HashNotInParent { .. }
+            | MptError::InvalidLeaf { .. }
+            | MptError::LeafKeyMismatch
+            | MptError::NonCanonicalRlp { .. } => ErrorCode::MptMismatch,
+            MptError::LayerTooLarge { .. } | MptError::ScanLimitExceeded { .. } => {
+                ErrorCode::InputLimits
+            }
+        }
+    }
+}
+
+impl HasErrorCode for TraceSizeError {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::TraceSize
+    }
+}
+
+impl HasErrorCode for VerificationError {
+    fn code(&self) -> ErrorCode {
+        ErrorCode::VerifyFail
+    }
+}
+
+impl HasErrorCode for VerificationFailure {
+    fn code(&self) -> ErrorCode {
+        self.source.code()
+    }
+}
+
+impl HasErrorCode for ExtractionError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ExtractionError::Verification(e) => e.code(),
+        }
+    }
+}
+
+impl HasErrorCode for StatementError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            StatementError::Length { .. }
+            | StatementError::Version { .. }
+            | StatementError::NonCanonicalLimb { .. }
+            | StatementError::ReservedByte { .. }
+            | StatementError::NonCanonicalExpiry { .. } => ErrorCode::FormatVersion,
+            StatementError::PublicValuesMismatch | StatementError::ReplayScopeMismatch => {
+                ErrorCode::VerifyFail
+            }
+            StatementError::Extraction(e) => e.code(),
+            StatementError::Verification(e) => e.code(),
+        }
+    }
+}
+
+impl HasErrorCode for EnvelopeError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            EnvelopeError::Serialization(_)
+            | EnvelopeError::Layout(_)
+            | EnvelopeError::LayoutMismatch
+            | EnvelopeError::HasherMismatch { .. }
+            | EnvelopeError::InstanceCountMismatch { .. }
+            | EnvelopeError::UnknownFormat
+            | EnvelopeError::UnknownCompression { .. }
+            | EnvelopeError::Decompression(_)
+            | EnvelopeError::TooLarge => ErrorCode::FormatVersion,
+            EnvelopeError::MissingStatement | EnvelopeError::PublicInputsMismatch => {
+                ErrorCode::VerifyFail
+            }
+            EnvelopeError::Statement(e) => e.code(),
+            EnvelopeError::StatementMismatch { .. }
+            | EnvelopeError::WrongChain { .. }
+            | EnvelopeError::Expired { .. } => ErrorCode::StatementMismatch,
+            EnvelopeError::Compression(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+impl HasErrorCode for CompatError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            CompatError::ParameterMismatch { .. }
+            | CompatError::CircuitMismatch { .. }
+            | CompatError::TraceSizeMismatch { .. }
+            | CompatError::TreeCountMismatch { .. }
+            | CompatError::ColumnCountMismatch { .. }
+            | CompatError::FriConfigMismatch { .. } => ErrorCode::FormatVersion,
+            CompatError::Envelope(e) => e.code(),
+        }
+    }
+}
+
+impl HasErrorCode for ServiceError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ServiceError::BurnInputs(e) => e.code(),
+            ServiceError::SpendInputs(e) => e.code(),
+            // Trace generation and stwo report through anyhow; only the size check is typed
+            ServiceError::Prove(e) => match e.downcast_ref::<TraceSizeError>() {
+                Some(e) => e.code(),
+                None => ErrorCode::StwoProve,
+            },
+            ServiceError::Envelope(e) => e.code(),
+            ServiceError::Compat(e) => e.code(),
+            ServiceError::Verification(e) => e.code(),
+            ServiceError::CircuitMismatch { .. } => ErrorCode::FormatVersion,
+        }
+    }
+}
+
+impl HasErrorCode for InputFileError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            InputFileError::TooLarge { .. } => ErrorCode::InputLimits,
+            InputFileError::Io(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                ErrorCode::InputMissing
+            }
+            InputFileError::Io(_) | InputFileError::Json(_) => ErrorCode::InputParse,
+        }
+    }
+}
+
+impl HasErrorCode for SealError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            SealError::Decrypt => ErrorCode::WitnessSeal,
+            SealError::Version { .. } => ErrorCode::FormatVersion,
+            SealError::Json(_) => ErrorCode::InputParse,
+        }
+    }
+}
+
+impl HasErrorCode for MinerError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            MinerError::Exhausted { .. } => ErrorCode::PowFail,
+            MinerError::InvalidDifficulty { .. } => ErrorCode::InputLimits,
+            MinerError::Cancelled => ErrorCode::Internal,
+        }
+    }
+}
+
+impl HasErrorCode for Check {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Check::Balances => ErrorCode::BalanceBounds,
+            Check::FundedAddress => ErrorCode::BurnAddressMismatch,
+            Check::ProofOfWork => ErrorCode::PowFail,
+            Check::Header | Check::HeaderChain => ErrorCode::HeaderInvalid,
+            Check::StateRoot | Check::Mpt | Check::BurnAddress => ErrorCode::MptMismatch,
+        }
+    }
+}
+
+/// Code of the first check that failed; checks skipped after it add nothing
+impl HasErrorCode for PrecheckReport {
+    fn code(&self) -> ErrorCode {
+        self.outcomes
+            .iter()
+            .find(|outcome| outcome.status == CheckStatus::Failed)
+            .map_or(ErrorCode::Internal, |outcome| outcome.check.code())
+    }
+}
+
+/// An error as every front end reports it: `{"code": "E_...", "message": ..., "details": ...}`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorReport {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Structured detail, such as a pre-check or verification report
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl ErrorReport {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into(), details: None }
+    }
+
+    /// Report `error` with its code and message
+    pub fn from_error<E: HasErrorCode + fmt::Display>(error: &E) -> Self {
+        Self::new(error.code(), error.to_string())
+    }
+
+    pub fn with_details(mut self, details: &impl Serialize) -> Self {
+        self.details = serde_json::to_value(details).ok();
+        self
+    }
+
+    /// The report as one line of JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("error reports serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, B256, U256};
+
+    use super::*;
+    use crate::circuits::validation::AmountError;
+    use crate::prover::{
+        CircuitKind, LayoutError, MerkleHasherKind, ParameterFingerprints, TreeRole,
+        VerificationPhase,
+    };
+    use crate::utils::header_chain::ChainError;
+    use crate::M31;
+
+    fn io_error(kind: std::io::ErrorKind) -> std::io::Error {
+        std::io::Error::new(kind, "io")
+    }
+
+    fn json_error() -> serde_json::Error {
+        serde_json::from_str::<u32>("{").unwrap_err()
+    }
+
+    fn amount_error() -> AmountError {
+        AmountError { field: "reveal_amount".to_string(), value: U256::MAX }
+    }
+
+    fn verification_error() -> VerificationError {
+        VerificationError::InvalidStructure("test".to_string())
+    }
+
+    fn verification_failure() -> VerificationFailure {
+        VerificationFailure::new(VerificationPhase::Verify, verification_error())
+    }
+
+    /// One error of every variant of every library error type, with the code it reports
+    fn table() -> Vec<(Box<dyn Error + Send + Sync>, ErrorCode)> {
+        use ErrorCode::*;
+
+        let pob: Vec<(ProofOfBurnError, ErrorCode)> = vec![
+            (ProofOfBurnError::AmountTooLarge(amount_error()), BalanceBounds),
+            (ProofOfBurnError::IntendedBalanceTooHigh { value: U256::MAX, max: 1 }, BalanceBounds),
+            (ProofOfBurnError::ActualBalanceTooHigh { value: U256::MAX, max: 1 }, BalanceBounds),
+            (
+                ProofOfBurnError::IntendedGreaterThanActual {
+                    intended: U256::from(2),
+                    actual: U256::from(1),
+                },
+                BalanceBounds,
+            ),
+            (
+                ProofOfBurnError::RevealAmountTooHigh {
+                    reveal: U256::from(2),
+                    intended: U256::from(1),
+                },
+                BalanceBounds,
+            ),
+            (ProofOfBurnError::InsufficientNibbles { provided: 1, required: 50 }, InputLimits),
+            (ProofOfBurnError::TooManyLayers { provided: 20, max: 16 }, InputLimits),
+            (ProofOfBurnError::HeaderTooLarge { size: 1000, max: 643 }, InputLimits),
+            (ProofOfBurnError::InvalidBlockHeader { reason: String::new() }, HeaderInvalid),
+            (
+                ProofOfBurnError::HeaderProofMismatch {
+                    header_state_root: B256::ZERO,
+                    proof_root: B256::ZERO,
+                    block_number: 0,
+                },
+                MptMismatch,
+            ),
+            (ProofOfBurnError::MptVerificationFailed { reason: String::new() }, MptMismatch),
+            (
+                ProofOfBurnError::ProvenBalanceMismatch {
+                    claimed: U256::from(1),
+                    proven: U256::ZERO,
+                },
+                MptMismatch,
+            ),
+            (ProofOfBurnError::NotBurnAccount, MptMismatch),
+            (ProofOfBurnError::PowVerificationFailed { required_zeros: 2 }, PowFail),
+            (ProofOfBurnError::HeaderChain(ChainError::Empty), HeaderInvalid),
+            (
+                ProofOfBurnError::BurnAddressMismatch {
+                    expected: Address::ZERO,
+                    derived: Address::repeat_byte(1),
+                },
+                BurnAddressMismatch,
+            ),
+        ];
+        let spend: Vec<(SpendError, ErrorCode)> = vec![
+            (
+                SpendError::InsufficientBalance {
+                    balance: U256::from(1),
+                    withdrawn: U256::from(2),
+                },
+                BalanceBounds,
+            ),
+            (SpendError::AmountTooLarge(amount_error()), BalanceBounds),
+            (
+                SpendError::AmountBeyondTrace { field: "balance", value: U256::from(1) << 64 },
+                BalanceBounds,
+            ),
+            (
+                SpendError::CoinMismatch { expected: M31::from(1), computed: M31::from(2) },
+                CoinMismatch,
+            ),
+        ];
+        let layers: Vec<(LayerError, ErrorCode)> = vec![
+            (LayerError::NoLayers, InputParse),
+            (LayerError::TooManyLayers { count: 20, max: 16 }, InputLimits),
+            (LayerError::EmptyLayer { index: 0 }, InputParse),
+            (LayerError::LayerTooLarge { index: 0, size: 1000, max: 532 }, InputLimits),
+            (LayerError::RootTooShort { size: 1, min: 32 }, InputParse),
+            (LayerError::NotALeaf { index: 1, size: 600, reason: String::new() }, InputParse),
+        ];
+        let mpt: Vec<(MptError, ErrorCode)> = vec![
+            (MptError::Layers(LayerError::NoLayers), InputParse),
+            (MptError::InvalidStateRoot { expected: [0; 32], computed: [1; 32] }, MptMismatch),
+            (MptError::HashNotInParent { layer: 1, hash: [0; 32] }, MptMismatch),
+            (MptError::InvalidLeaf { reason: String::new() }, MptMismatch),
+            (MptError::LeafKeyMismatch, MptMismatch),
+            (MptError::NonCanonicalRlp { layer: 0, reason: String::new() }, MptMismatch),
+            (MptError::LayerTooLarge { size: 1000, max: 532 }, InputLimits),
+            (MptError::ScanLimitExceeded { scanned: 1000, max: 10 }, InputLimits),
+        ];
+        let statement: Vec<(StatementError, ErrorCode)> = vec![
+            (StatementError::Length { expected: 1, found: 2 }, FormatVersion),
+            (StatementError::Version { expected: 1, found: 2 }, FormatVersion),
+            (StatementError::NonCanonicalLimb { index: 0 }, FormatVersion),
+            (StatementError::ReservedByte { found: 1 }, FormatVersion),
+            (StatementError::NonCanonicalExpiry { flag: 2 }, FormatVersion),
+            (StatementError::PublicValuesMismatch, VerifyFail),
+            (StatementError::ReplayScopeMismatch, VerifyFail),
+            (
+                StatementError::Extraction(ExtractionError::Verification(verification_failure())),
+                VerifyFail,
+            ),
+            (StatementError::Verification(verification_failure()), VerifyFail),
+        ];
+        let hashers = (MerkleHasherKind::Blake2s, MerkleHasherKind::Poseidon252);
+        let envelope: Vec<(EnvelopeError, ErrorCode)> = vec![
+            (EnvelopeError::Serialization(json_error()), FormatVersion),
+            (EnvelopeError::MissingStatement, VerifyFail),
+            (EnvelopeError::PublicInputsMismatch, VerifyFail),
+            (EnvelopeError::Statement(StatementError::PublicValuesMismatch), VerifyFail),
+            (EnvelopeError::StatementMismatch { mismatches: Vec::new() }, StatementMismatch),
+            (EnvelopeError::Layout(LayoutError::Count { layout: 3, proof: 2 }), FormatVersion),
+            (EnvelopeError::LayoutMismatch, FormatVersion),
+            (
+                EnvelopeError::HasherMismatch { envelope: hashers.0, expected: hashers.1 },
+                FormatVersion,
+            ),
+            (EnvelopeError::InstanceCountMismatch { envelope: 1, components: 2 }, FormatVersion),
+            (EnvelopeError::WrongChain { expected: 1, found: 2 }, StatementMismatch),
+            (EnvelopeError::Expired { valid_until_block: 1, current_block: 2 }, StatementMismatch),
+            (EnvelopeError::UnknownFormat, FormatVersion),
+            (EnvelopeError::UnknownCompression { flag: 9 }, FormatVersion),
+            (EnvelopeError::Compression(io_error(std::io::ErrorKind::Other)), Internal),
+            (
+                EnvelopeError::Decompression(io_error(std::io::ErrorKind::InvalidData)),
+                FormatVersion,
+            ),
+            (EnvelopeError::TooLarge, FormatVersion),
+        ];
+        let fingerprints = ParameterFingerprints::current();
+        let compat: Vec<(CompatError, ErrorCode)> = vec![
+            (
+                CompatError::ParameterMismatch { expected: fingerprints, found: fingerprints },
+                FormatVersion,
+            ),
+            (
+                CompatError::CircuitMismatch {
+                    envelope: CircuitKind::Spend,
+                    components: CircuitKind::Burn,
+                },
+                FormatVersion,
+            ),
+            (CompatError::TraceSizeMismatch { expected: 5, found: 6 }, FormatVersion),
+            (CompatError::TreeCountMismatch { expected: 3, found: 2 }, FormatVersion),
+            (
+                CompatError::ColumnCountMismatch { tree: TreeRole::Trace, expected: 1, found: 2 },
+                FormatVersion,
+            ),
+            (
+                CompatError::FriConfigMismatch { log_last_layer_degree_bound: 9, min_log_size: 4 },
+                FormatVersion,
+            ),
+            (CompatError::Envelope(EnvelopeError::UnknownFormat), FormatVersion),
+        ];
+        let service: Vec<(ServiceError, ErrorCode)> = vec![
+            (ServiceError::BurnInputs(ProofOfBurnError::NotBurnAccount), MptMismatch),
+            (ServiceError::SpendInputs(SpendError::AmountTooLarge(amount_error())), BalanceBounds),
+            (ServiceError::Prove(anyhow::anyhow!("stwo failed")), StwoProve),
+            (ServiceError::Prove(TraceSizeError { log_n_rows: 30 }.into()), TraceSize),
+            (ServiceError::Envelope(EnvelopeError::MissingStatement), VerifyFail),
+            (
+                ServiceError::Compat(CompatError::TreeCountMismatch { expected: 3, found: 2 }),
+                FormatVersion,
+            ),
+            (ServiceError::Verification(verification_failure()), VerifyFail),
+            (ServiceError::CircuitMismatch { circuit: CircuitKind::Spend }, FormatVersion),
+        ];
+        let input_file: Vec<(InputFileError, ErrorCode)> = vec![
+            (InputFileError::TooLarge { limit: 1 }, InputLimits),
+            (InputFileError::Io(io_error(std::io::ErrorKind::NotFound)), InputMissing),
+            (InputFileError::Io(io_error(std::io::ErrorKind::PermissionDenied)), InputParse),
+            (InputFileError::Json(json_error()), InputParse),
+        ];
+        let seal: Vec<(SealError, ErrorCode)> = vec![
+            (SealError::Version { found: 2 }, FormatVersion),
+            (SealError::Decrypt, WitnessSeal),
+            (SealError::Json(json_error()), InputParse),
+        ];
+        let miner: Vec<(MinerError, ErrorCode)> = vec![
+            (MinerError::Exhausted { attempts: 1 }, PowFail),
+            (MinerError::InvalidDifficulty { zero_bytes: 33 }, InputLimits),
+            (MinerError::Cancelled, Internal),
+        ];
+
+        fn boxed<E: HasErrorCode + Error + Send + Sync + 'static>(
+            rows: Vec<(E, ErrorCode)>,
+        ) -> Vec<(Box<dyn Error + Send + Sync>, ErrorCode)> {
+            rows.into_iter()
+                .map(|(error, code)| {
+                    assert_eq!(error.code(), code, "{error:?}");
+                    (Box::new(error) as Box<dyn Error + Send + Sync>, code)
+                })
+                .collect()
+        }
+        let mut table = boxed(pob);
+        table.extend(boxed(spend));
+        table.extend(boxed(layers));
+        table.extend(boxed(mpt));
+        table.extend(boxed(statement));
+        table.extend(boxed(envelope));
+        table.extend(boxed(compat));
+        table.extend(boxed(service));
+        table.extend(boxed(input_file));
+        table.extend(boxed(seal));
+        table.extend(boxed(miner));
+        table.extend(boxed(vec![(TraceSizeError { log_n_rows: 2 }, TraceSize)]));
+        let extraction = ExtractionError::Verification(verification_failure());
+        table.extend(boxed(vec![(extraction, VerifyFail)]));
+        table.extend(boxed(vec![(verification_failure(), VerifyFail)]));
+        table.extend(boxed(vec![(verification_error(), VerifyFail)]));
+        table
+    }
+
+    #[test]
+    fn test_every_variant_maps_to_its_code() {
+        // `boxed` checks `code()`; type-erased, `ErrorCode::of` finds the same code
+        for (error, code) in table() {
+            assert_eq!(ErrorCode::of(&*error), code, "{error:?}");
+        }
+    }
+
+    #[test]
+    fn test_precheck_reports_first_failed_check() {
+        use crate::witness::CheckOutcome;
+
+        let outcome = |check, status| CheckOutcome { check, status, detail: String::new() };
+        let report = PrecheckReport {
+            outcomes: vec![
+                outcome(Check::Balances, CheckStatus::Passed),
+                outcome(Check::Header, CheckStatus::Skipped),
+                outcome(Check::StateRoot, CheckStatus::Failed),
+                outcome(Check::BurnAddress, CheckStatus::Failed),
+            ],
+        };
+        assert_eq!(report.code(), ErrorCode::MptMismatch);
+    }
+
+    #[test]
+    fn test_of_finds_wrapped_errors() {
+        let error = anyhow::Error::from(ProofOfBurnError::NotBurnAccount).context("Reading inputs");
+        assert_eq!(ErrorCode::of(error.as_ref()), ErrorCode::MptMismatch);
+        let plain = anyhow::anyhow!("no library error");
+        assert_eq!(ErrorCode::of(plain.as_ref()), ErrorCode::Internal);
+    }
+
+    #[test]
+    fn test_codes_serialize_as_names() {
+        use ErrorCode::*;
+        let codes = [
+            InputMissing, InputParse, InputLimits, WitnessSeal, BalanceBounds, HeaderInvalid,
+            MptMismatch, PowFail, CoinMismatch, BurnAddressMismatch, TraceSize, StwoProve,
+            VerifyFail, StatementMismatch, FormatVersion, Unauthorized, Route, Internal,
+        ];
+        let mut names = std::collections::HashSet::new();
+        for code in codes {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+            assert!(names.insert(code.as_str()), "{code} named twice");
+        }
+
+        let report = ErrorReport::from_error(&SpendError::AmountTooLarge(amount_error()));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["code"], "E_BALANCE_BOUNDS");
+        assert_eq!(json["message"], report.message);
+        assert!(json.get("details").is_none());
+    }
+}
@@ -0,0 +1,15 @@
+// Exit statuses of pob-prover
+// Scripts branch on them; `ErrorCode::exit_code` gives the status of each machine-readable error
+// code. Other errors exit with 1.
+
+/// Any failure without a status of its own
+pub const FAILURE: u8 = 1;
+
+/// An input or proof file does not exist, as for clap's usage errors
+pub const MISSING_FILE: u8 = 2;
+
+/// `verify` found a valid proof of another statement than the expected one
+pub const STATEMENT_MISMATCH: u8 = 3;
+
+/// `verify` read a proof that is invalid
+pub const INVALID_PROOF: u8 = 4;
@@ -2,13 +2,33 @@
 // This is a standalone implementation that doesn't depend on stwo
 // Once stwo is stable, we'll use their implementation
 
+pub mod ops;
+
+use std::fmt;
 use std::ops::{Add, Mul, Sub};
-use serde::{Serialize, Deserialize};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
 
 /// M31 field element: elements of the field F_{2^31 - 1}
 /// This is the Mersenne prime field used by Circle STARKs
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct M31(pub u32);
+///
+/// Always canonical, below `PRIME`: the value is private, so elements are made by `new` (or the
+/// `From` impls), which reduce, and read back with `value`.
+///
+/// ```compile_fail
+/// let element = proof_of_burn_stwo::field::M31(5);
+/// ```
+///
+/// ```compile_fail
+/// let raw: u32 = proof_of_burn_stwo::field::M31::new(5).0;
+/// ```
+///
+/// Serialized as a JSON number, on every platform and whatever the in-memory layout. A
+/// 0x-prefixed hex string of at most 8 digits is read as well, the spelling wallets display; a
+/// value of `PRIME` or above is rejected in either form rather than reduced to another element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct M31(u32);
 
 impl M31 {
     pub const PRIME: u32 = 2147483647; // 2^31 - 1
@@ -39,6 +59,68 @@ impl M31 {
     }
 }
 
+impl Serialize for M31 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for M31 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Binary formats cannot tell a number from a string; they get the number
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(M31Visitor)
+        } else {
+            deserializer.deserialize_u32(M31Visitor)
+        }
+    }
+}
+
+struct M31Visitor;
+
+impl M31Visitor {
+    fn canonical<E: de::Error>(value: u64) -> Result<M31, E> {
+        if value >= u64::from(M31::PRIME) {
+            return Err(E::custom(format!("{value:#x} is not a canonical M31 element")));
+        }
+        Ok(M31(value as u32))
+    }
+}
+
+impl<'de> Visitor<'de> for M31Visitor {
+    type Value = M31;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an M31 element as an integer or 0x-prefixed hex")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<M31, E> {
+        let digits = value
+            .strip_prefix("0x")
+            .ok_or_else(|| E::custom(format!("hex M31 without 0x prefix: {value:.16}")))?;
+        if digits.is_empty() || digits.len() > 8 {
+            return Err(E::custom(format!("hex M31 must have 1 to 8 digits: {value:.16}")));
+        }
+        let value = u64::from_str_radix(digits, 16).map_err(|e| E::custom(format!("invalid hex M31: {e}")))?;
+        Self::canonical(value)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<M31, E> {
+        Self::canonical(value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<M31, E> {
+        let value = u64::try_from(value).map_err(|_| E::custom(format!("{value} is not a canonical M31 element")))?;
+        Self::canonical(value)
+    }
+}
+
+impl Zeroize for M31 {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl From<u32> for M31 {
     fn from(value: u32) -> Self {
         M31::new(value)
@@ -51,7 +133,8 @@ impl From<i32> for M31 {
             M31::new(value as u32)
         } else {
             // Handle negative by wrapping: -x = P - x
-            M31::new(M31::PRIME - ((-value) as u32 % M31::PRIME))
+            // `unsigned_abs` because negating i32::MIN overflows
+            M31::new(M31::PRIME - (value.unsigned_abs() % M31::PRIME))
         }
     }
 }
@@ -125,6 +208,12 @@ mod tests {
         assert_eq!(c.value(), 30);
     }
 
+    #[test]
+    fn test_from_i32_min() {
+        // i32::MIN = -2^31 = -(P + 1)
+        assert_eq!(M31::from(i32::MIN), M31::zero() - M31::one());
+    }
+
     #[test]
     fn test_m31_modulo() {
         let large = M31::from(M31::PRIME + 5);
@@ -174,5 +263,34 @@ mod tests {
         // Test commutativity of multiplication
         assert_eq!(a * b, b * a);
     }
+
+    #[test]
+    fn test_serde_reads_numbers_and_hex() {
+        let element = M31::new(M31::PRIME - 1);
+        for json in ["2147483646", r#""0x7ffffffe""#, r#""0x7FFFFFFE""#] {
+            assert_eq!(serde_json::from_str::<M31>(json).unwrap(), element, "{json}");
+        }
+        // Always written back as a number
+        assert_eq!(serde_json::to_string(&element).unwrap(), "2147483646");
+        let roundtrip: M31 = serde_json::from_str(&serde_json::to_string(&M31::new(7)).unwrap()).unwrap();
+        assert_eq!(roundtrip, M31::new(7));
+    }
+
+    #[test]
+    fn test_serde_rejects_non_canonical() {
+        let rejected = [
+            "2147483647",
+            r#""0x7fffffff""#,
+            "4294967296",
+            "-1",
+            r#""7f""#,
+            r#""0x""#,
+            r#""0x000000001""#,
+            "1.5",
+        ];
+        for json in rejected {
+            assert!(serde_json::from_str::<M31>(json).is_err(), "{json} was accepted");
+        }
+    }
 }
 
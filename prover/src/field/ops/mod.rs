@@ -0,0 +1,93 @@
+// Elementwise M31 arithmetic over slices
+// Sums, scalar products and dot products for trace assembly, in place of hand-rolled loops.
+// Operations on two slices return `LengthMismatch` instead of panicking or truncating to the
+// shorter one. `simd` has the same operations on packed `BaseColumn`s.
+
+pub mod simd;
+
+use super::M31;
+
+/// The two operands of an elementwise operation have different lengths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Operands have different lengths: {left} and {right}")]
+pub struct LengthMismatch {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl LengthMismatch {
+    /// `Ok` if `left == right`
+    pub fn check(left: usize, right: usize) -> Result<(), Self> {
+        if left == right {
+            Ok(())
+        } else {
+            Err(Self { left, right })
+        }
+    }
+}
+
+/// `a[i] + b[i]`
+pub fn add_slices(a: &[M31], b: &[M31]) -> Result<Vec<M31>, LengthMismatch> {
+    LengthMismatch::check(a.len(), b.len())?;
+    Ok(a.iter().zip(b).map(|(&a, &b)| a + b).collect())
+}
+
+/// `a[i] += b[i]`
+pub fn add_slices_in_place(a: &mut [M31], b: &[M31]) -> Result<(), LengthMismatch> {
+    LengthMismatch::check(a.len(), b.len())?;
+    for (a, &b) in a.iter_mut().zip(b) {
+        *a = *a + b;
+    }
+    Ok(())
+}
+
+/// `a[i] * scalar`
+pub fn mul_scalar(a: &[M31], scalar: M31) -> Vec<M31> {
+    a.iter().map(|&a| a * scalar).collect()
+}
+
+/// `a[i] *= scalar`
+pub fn mul_scalar_in_place(a: &mut [M31], scalar: M31) {
+    for a in a.iter_mut() {
+        *a = *a * scalar;
+    }
+}
+
+/// `sum(a[i] * b[i])`
+pub fn dot(a: &[M31], b: &[M31]) -> Result<M31, LengthMismatch> {
+    LengthMismatch::check(a.len(), b.len())?;
+    // Each product is below 2^62, so the sum is reduced once at the end
+    let sum: u128 = a.iter().zip(b).map(|(a, b)| a.value() as u128 * b.value() as u128).sum();
+    Ok(M31((sum % M31::PRIME as u128) as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m31s(values: &[u32]) -> Vec<M31> {
+        values.iter().map(|&value| M31::from(value)).collect()
+    }
+
+    #[test]
+    fn test_length_mismatch_is_an_error() {
+        let (a, b) = (m31s(&[1, 2, 3]), m31s(&[4, 5]));
+        let mismatch = LengthMismatch { left: 3, right: 2 };
+        assert_eq!(add_slices(&a, &b), Err(mismatch));
+        assert_eq!(dot(&a, &b), Err(mismatch));
+
+        let mut sum = a.clone();
+        assert_eq!(add_slices_in_place(&mut sum, &b), Err(mismatch));
+        assert_eq!(sum, a, "a failed in-place operation leaves its operand untouched");
+    }
+
+    #[test]
+    fn test_wraps_at_the_prime() {
+        let minus_one = M31::zero() - M31::one();
+        let a = vec![minus_one; 3];
+        assert_eq!(add_slices(&a, &m31s(&[1, 2, 3])).unwrap(), m31s(&[0, 1, 2]));
+        assert_eq!(mul_scalar(&a, minus_one), m31s(&[1, 1, 1]));
+        assert_eq!(dot(&a, &a).unwrap(), M31::from(3u32));
+        assert_eq!(dot(&[], &[]).unwrap(), M31::zero());
+    }
+}
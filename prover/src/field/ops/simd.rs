@@ -0,0 +1,82 @@
+// The slice operations on packed `BaseColumn`s
+// Each step works on `N_LANES` values at once; `dot_columns` reduces the lanes once at the end.
+
+use num_traits::Zero;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::prover::backend::simd::column::BaseColumn;
+use stwo_prover::prover::backend::simd::m31::{PackedBaseField, N_LANES};
+use stwo_prover::prover::backend::Column;
+
+use super::LengthMismatch;
+
+/// `a[i] + b[i]`
+pub fn add_columns(a: &BaseColumn, b: &BaseColumn) -> Result<BaseColumn, LengthMismatch> {
+    LengthMismatch::check(a.len(), b.len())?;
+    let data = a.data.iter().zip(&b.data).map(|(&a, &b)| a + b).collect();
+    Ok(BaseColumn { data, length: a.len() })
+}
+
+/// `a[i] += b[i]`
+pub fn add_columns_in_place(a: &mut BaseColumn, b: &BaseColumn) -> Result<(), LengthMismatch> {
+    LengthMismatch::check(a.len(), b.len())?;
+    for (a, &b) in a.data.iter_mut().zip(&b.data) {
+        *a += b;
+    }
+    Ok(())
+}
+
+/// `a[i] * scalar`
+pub fn mul_scalar_column(a: &BaseColumn, scalar: BaseField) -> BaseColumn {
+    let scalar = PackedBaseField::broadcast(scalar);
+    BaseColumn { data: a.data.iter().map(|&a| a * scalar).collect(), length: a.len() }
+}
+
+/// `a[i] *= scalar`
+pub fn mul_scalar_column_in_place(a: &mut BaseColumn, scalar: BaseField) {
+    let scalar = PackedBaseField::broadcast(scalar);
+    for a in a.data.iter_mut() {
+        *a *= scalar;
+    }
+}
+
+/// `sum(a[i] * b[i])`
+///
+/// Lanes past `len()` in a partial last vector are not read.
+pub fn dot_columns(a: &BaseColumn, b: &BaseColumn) -> Result<BaseField, LengthMismatch> {
+    LengthMismatch::check(a.len(), b.len())?;
+    let full = a.len() / N_LANES;
+    let packed = a.data[..full]
+        .iter()
+        .zip(&b.data[..full])
+        .fold(PackedBaseField::zero(), |sum, (&a, &b)| sum + a * b);
+    let tail = (full * N_LANES..a.len()).fold(BaseField::zero(), |sum, i| sum + a.at(i) * b.at(i));
+    Ok(packed.to_array().into_iter().fold(tail, |sum, lane| sum + lane))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: impl IntoIterator<Item = u32>) -> BaseColumn {
+        values.into_iter().map(BaseField::from).collect()
+    }
+
+    #[test]
+    fn test_partial_last_vector() {
+        // 20 values: one full vector and 4 lanes of a second
+        let a = column(1..=20);
+        let b = column(std::iter::repeat(2).take(20));
+        assert_eq!(dot_columns(&a, &b).unwrap(), BaseField::from(420));
+        assert_eq!(add_columns(&a, &b).unwrap().to_cpu(), column(3..=22).to_cpu());
+        assert_eq!(mul_scalar_column(&a, BaseField::from(2)).to_cpu(), column((1..=20).map(|v| 2 * v)).to_cpu());
+    }
+
+    #[test]
+    fn test_length_mismatch_is_an_error() {
+        let (a, b) = (column(0..32), column(0..16));
+        let mismatch = LengthMismatch { left: 32, right: 16 };
+        assert_eq!(add_columns(&a, &b).err(), Some(mismatch));
+        assert_eq!(dot_columns(&a, &b).err(), Some(mismatch));
+        assert_eq!(add_columns_in_place(&mut a.clone(), &b), Err(mismatch));
+    }
+}
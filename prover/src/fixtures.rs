@@ -0,0 +1,171 @@
+// Known-good circuit inputs and outputs for downstream integration tests
+// `small_burn` is a 1 ETH burn into a two-level mock trie revealing 0.5 ETH; `small_spend`
+// withdraws 0.2 ETH of the coin it leaves. Both are read from prover/fixtures/*.json, which ship
+// with the crate, so wallets and contract tests in other languages can load the same files.
+//
+// Stability: the inputs and outputs do not change across minor versions. A change to a hash
+// construction behind them (the burn address, Poseidon2's parameters, the coin, nullifier or
+// public commitment) regenerates both files and bumps `FIXTURES_VERSION`, which each file
+// carries as `version`. The tests below rebuild the fixtures and fail when they drift from the
+// files; `UPDATE_FIXTURES=1 cargo test --lib fixtures` rewrites them.
+
+use alloy_primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::circuits::proof_of_burn::{ProofOfBurnCircuit, ProofOfBurnInputs, ProofOfBurnOutputs};
+use crate::circuits::spend::{SpendCircuit, SpendInputs, SpendOutputs};
+use crate::constants::MAINNET_CHAIN_ID;
+use crate::secret::Secret;
+use crate::test_utils::mock_account_proof;
+use crate::utils::burn_address::compute_burn_address_hash;
+use crate::M31;
+
+/// Version of the fixture files, bumped when a hash construction changes their outputs
+pub const FIXTURES_VERSION: u32 = 1;
+
+const SMALL_BURN_JSON: &str = include_str!("../fixtures/small_burn.json");
+const SMALL_SPEND_JSON: &str = include_str!("../fixtures/small_spend.json");
+
+/// Smallest burn key meeting `POW_MINIMUM_ZERO_BYTES` for the small burn's reveal and extra
+const SMALL_BURN_KEY: u32 = 29405;
+
+/// 1 ETH in wei
+const ONE_ETH: u64 = 1_000_000_000_000_000_000;
+
+/// Branch levels of the small burn's mock trie
+const SMALL_BURN_LAYERS: usize = 2;
+
+/// One fixture file: inputs, the outputs they produce and the file's version
+#[derive(Serialize, Deserialize)]
+struct Fixture<I, O> {
+    version: u32,
+    inputs: I,
+    outputs: O,
+}
+
+fn parse<I, O>(json: &str) -> (I, O)
+where
+    I: for<'de> Deserialize<'de>,
+    O: for<'de> Deserialize<'de>,
+{
+    let fixture: Fixture<I, O> = serde_json::from_str(json).expect("shipped fixture parses");
+    assert_eq!(fixture.version, FIXTURES_VERSION, "shipped fixture has a stale version");
+    (fixture.inputs, fixture.outputs)
+}
+
+/// A 1 ETH burn revealing 0.5 ETH, with the outputs the circuit computes for it
+///
+/// The account sits in a two-level mock trie whose root the block header commits to, so the
+/// inputs pass `ProofOfBurnCircuit::new` and prove as they are.
+pub fn small_burn() -> (ProofOfBurnInputs, ProofOfBurnOutputs) {
+    parse(SMALL_BURN_JSON)
+}
+
+/// A spend of `small_burn`'s remaining coin withdrawing 0.2 ETH, with its outputs
+///
+/// `expected_coin` is set to the burn's `remaining_coin`, so the two fixtures chain.
+pub fn small_spend() -> (SpendInputs, SpendOutputs) {
+    parse(SMALL_SPEND_JSON)
+}
+
+fn build_small_burn() -> (ProofOfBurnInputs, ProofOfBurnOutputs) {
+    let burn_key = M31::from(SMALL_BURN_KEY);
+    let (balance, reveal_amount) = (U256::from(ONE_ETH), U256::from(ONE_ETH / 2));
+    let burn_extra_commitment = M31::from(100u32);
+    let address_hash = compute_burn_address_hash(burn_key, reveal_amount, burn_extra_commitment);
+    let proof = mock_account_proof(&address_hash, balance, SMALL_BURN_LAYERS);
+    let inputs = ProofOfBurnInputs {
+        burn_key: Secret::new(burn_key),
+        actual_balance: balance,
+        intended_balance: balance,
+        reveal_amount,
+        burn_extra_commitment,
+        layers: proof.layers,
+        block_header: proof.block_header,
+        header_chain: Vec::new(),
+        chain_id: MAINNET_CHAIN_ID,
+        valid_until_block: None,
+        num_leaf_address_nibbles: proof.num_leaf_address_nibbles,
+        byte_security_relax: 0,
+        proof_extra_commitment: M31::from(200u32),
+        expected_burn_address: None,
+    };
+    let outputs = ProofOfBurnCircuit::new(inputs.clone())
+        .and_then(|circuit| circuit.compute_outputs())
+        .expect("small burn is valid");
+    (inputs, outputs)
+}
+
+fn build_small_spend() -> (SpendInputs, SpendOutputs) {
+    let (burn, burn_outputs) = build_small_burn();
+    let inputs = SpendInputs {
+        burn_key: burn.burn_key,
+        balance: burn.intended_balance - burn.reveal_amount,
+        withdrawn_balance: U256::from(ONE_ETH / 5),
+        extra_commitment: M31::from(300u32),
+        recipient: None,
+        chain_id: burn.chain_id,
+        expected_coin: Some(burn_outputs.remaining_coin),
+    };
+    let circuit = SpendCircuit::new(inputs.clone()).expect("small spend is valid");
+    let outputs = circuit.compute_outputs();
+    (inputs, outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::constants::circuit_params::POW_MINIMUM_ZERO_BYTES;
+    use crate::utils::pow::verify_pow;
+
+    /// Compare a rebuilt fixture against its shipped file, or rewrite the file with UPDATE_FIXTURES
+    fn check_in_sync<I: Serialize, O: Serialize>(name: &str, shipped: &str, inputs: I, outputs: O) {
+        let fixture = Fixture { version: FIXTURES_VERSION, inputs, outputs };
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name);
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            let json = serde_json::to_string_pretty(&fixture).unwrap();
+            std::fs::write(&path, json + "\n").unwrap();
+            return;
+        }
+        let built = serde_json::to_value(&fixture).unwrap();
+        let shipped: serde_json::Value = serde_json::from_str(shipped).unwrap();
+        assert_eq!(
+            built,
+            shipped,
+            "{} differs from the rebuilt fixture; bump FIXTURES_VERSION and rerun with \
+             UPDATE_FIXTURES=1 if a hash construction changed",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn test_small_burn_in_sync() {
+        let (inputs, outputs) = build_small_burn();
+        check_in_sync("small_burn.json", SMALL_BURN_JSON, inputs, outputs);
+    }
+
+    #[test]
+    fn test_small_spend_in_sync() {
+        let (inputs, outputs) = build_small_spend();
+        check_in_sync("small_spend.json", SMALL_SPEND_JSON, inputs, outputs);
+    }
+
+    #[test]
+    fn test_shipped_outputs_recompute() {
+        let (burn, burn_outputs) = small_burn();
+        assert!(verify_pow(
+            *burn.burn_key.expose_secret(),
+            burn.reveal_amount,
+            burn.burn_extra_commitment,
+            POW_MINIMUM_ZERO_BYTES
+        ));
+        let computed = ProofOfBurnCircuit::new(burn).unwrap().compute_outputs().unwrap();
+        assert_eq!(computed, burn_outputs);
+
+        let (spend, spend_outputs) = small_spend();
+        assert_eq!(spend.expected_coin, Some(burn_outputs.remaining_coin));
+        assert_eq!(SpendCircuit::new(spend).unwrap().compute_outputs(), spend_outputs);
+    }
+}
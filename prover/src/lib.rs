@@ -3,9 +3,40 @@
 
 pub mod field;
 pub mod constants;
+pub mod secret;
+pub mod platform;
 pub mod utils;
+pub mod coins;
 pub mod circuits;
 pub mod prover;
+pub mod compat;
+pub mod witness;
+pub mod debug;
+pub mod consistency;
+pub mod analysis;
+pub mod vectors;
+pub mod exit_code;
+pub mod error_code;
+
+// JSON Schemas of the input and envelope files (`schema` feature)
+#[cfg(feature = "schema")]
+pub mod schema;
+
+// HTTP proving API behind `pob-prover serve` (`server` feature)
+#[cfg(feature = "server")]
+pub mod server;
+
+// Mock tries and headers for tests and benchmarks (`test-utils` feature)
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+// Known-good burn and spend inputs with their outputs (`test-utils` feature)
+#[cfg(any(test, feature = "test-utils"))]
+pub mod fixtures;
+
+// Browser entry points (wasm32 + `wasm` feature only)
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub mod wasm;
 
 // Re-export commonly used types
 pub use field::M31;
@@ -13,8 +44,15 @@ pub use constants::*;
 
 // Re-export prover functions
 pub use prover::{
-    prove_proof_of_burn, verify_proof_of_burn,
-    prove_spend, verify_spend,
-    StarkConfig,
+    prove_proof_of_burn, verify_proof_of_burn, ProofOfBurnComponents, PobPublicValues, ExtractionError,
+    BurnStatement, StatementError,
+    prove_spend, verify_spend, verify_spend_against, SpendComponents, SpendStatement,
+    prove_split, verify_split, SplitComponents,
+    prove_merge, verify_merge, MergeComponents,
+    prove_transfer, verify_transfer, TransferComponents,
+    StarkConfig, fri_params_for, MerkleHasherKind,
+    prove_proof_of_burn_with_hasher, prove_spend_with_hasher,
+    PobProver, ServiceError, verify_many, VerificationFailure, VerificationPhase,
+    max_batch_size, BatchLayout, BatchError,
 };
 
@@ -1,20 +1,60 @@
 //! Proof of Burn STWO Prover
 //!
 //! Zero-knowledge proof generator for Proof of Burn protocol using Circle STARKs.
-//! Designed for WebAssembly deployment in browsers for maximum privacy.
+//! Native command-line front end; the browser entry points live in the
+//! library's `wasm` module.
 
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256, U256};
 use anyhow::Context;
 use clap::{Parser, Subcommand};
 use proof_of_burn_stwo::{
+    analysis::{config_report, ConfigReport},
     circuits::{
+        builder::{InputIssue, InputValidationReport},
         proof_of_burn::{ProofOfBurnCircuit, ProofOfBurnInputs},
+        proof_of_burn_air::{generate_pob_trace, pob_column_labels},
         spend::{SpendCircuit, SpendInputs},
+        stats::CircuitStats,
+        split::{SplitCircuit, SplitInputs},
+        transfer::{TransferCircuit, TransferInputs},
+    },
+    debug::{
+        check_pob_constraints, dump_trace_csv, merge_eval, pob_eval, spend_eval, split_eval, transfer_eval,
+    },
+    error_code::{ErrorCode, ErrorReport},
+    exit_code,
+    field::M31,
+    prover::{
+        packaging::ProofId,
+        envelope::{CircuitKind, EnvelopeError, CLAIMS_VERSION, ENVELOPE_MAGIC},
+        prove_proof_of_burn,
+        solidity::{encode_proof_calldata, export_foundry_fixture},
+        BurnStatement, CommitmentLayout, EnvelopeSummary, PobPublicValues, ProofCommitments, ProofEnvelope,
+        ProvingTimings, SpendStatement, StarkConfig,
+    },
+    constants::{circuit_params::POW_MINIMUM_ZERO_BYTES, CircuitParams},
+    schema::SchemaType,
+    utils::{
+        burn_address::{compute_burn_address, format_checksummed, parse_address},
+        commitments,
+        header_chain::verify_descendants,
+        keccak::keccak256,
+        mpt::layer_issues,
+        pow::{mine_burn_key, MinerError, DEFAULT_MAX_ATTEMPTS},
+    },
+    vectors,
+    witness::{
+        precheck, read_json_file, seal::generate_secret, seal_with_policy, unseal, InputFileError,
+        SealPolicy, SealedWitness, DEFAULT_MAX_INPUT_BYTES,
     },
-    prover::prove_proof_of_burn,
 };
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::OnceLock;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
 
 /// Simplified proof structure containing only accessible commitment data.
 /// This replaces the complex SolidityStarkProof with placeholders.
@@ -25,7 +65,10 @@ pub struct SimpleProof {
     /// Composition commitment from STWO proof
     pub composition_commitment: B256,
     /// Unique proof identifier
-    pub proof_id: B256,
+    pub proof_id: ProofId,
+    /// Wall-clock time of each proving phase
+    #[serde(default)]
+    pub timings: ProvingTimings,
 }
 
 #[derive(Parser)]
@@ -43,6 +86,18 @@ The prover uses transparent Circle STARKs (STWO) without trusted setup,
 providing post-quantum security and universal composability."#
 )]
 struct Cli {
+    /// Log debug-level progress (sizes, per-phase spans); `RUST_LOG` takes precedence when set
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Refuse input files larger than this many bytes
+    #[arg(long, value_name = "BYTES", global = true, default_value_t = DEFAULT_MAX_INPUT_BYTES)]
+    max_input_size: u64,
+
+    /// Report a failure on stderr as text or as one JSON object `{code, message, details}`
+    #[arg(long, value_enum, value_name = "FORMAT", global = true, default_value = "text")]
+    error_format: OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -56,7 +111,7 @@ enum Commands {
 
 The proof demonstrates that:
 - The burn address exists in the specified Ethereum block
-- The balance meets the intended amount criteria
+- The intended balance does not exceed the burn address's actual balance
 - The proof-of-work requirement is satisfied
 - All cryptographic commitments are valid
 
@@ -69,8 +124,62 @@ Output: STWO proof file suitable for on-chain verification"#
         input: PathBuf,
 
         /// Path where the generated proof will be saved
+        #[arg(short, long, value_name = "FILE", required_unless_present = "check_only")]
+        output: Option<PathBuf>,
+
+        /// Only evaluate the AIR constraints on the trace and report the first violation
+        #[arg(long)]
+        check_only: bool,
+
+        /// Write the execution trace as labelled CSV to this file before proving
+        #[arg(long, value_name = "FILE")]
+        dump_trace: Option<PathBuf>,
+
+        /// Also write the ABI-encoded verifier calldata (0x-prefixed hex) to this file
+        #[arg(long, value_name = "FILE")]
+        emit_calldata: Option<PathBuf>,
+
+        /// Also write a Foundry test fixture (JSON, readable with vm.parseJson) to this file
+        #[arg(long, value_name = "FILE")]
+        emit_foundry_fixture: Option<PathBuf>,
+
+        /// Also write the public values extracted from the verified proof (JSON) to this file
+        #[arg(long, value_name = "FILE")]
+        emit_public_values: Option<PathBuf>,
+
+        /// Also write the proof envelope (full STARK proof and statement) to this file
+        #[arg(long, value_name = "FILE")]
+        emit_envelope: Option<PathBuf>,
+
+        /// DEFLATE-compress the envelope written by --emit-envelope
+        #[arg(long, requires = "emit_envelope")]
+        compress: bool,
+
+        /// Prove even if the witness pre-check fails (its report is still logged)
+        #[arg(long)]
+        force: bool,
+
+        #[command(flatten)]
+        burn_args: BurnInputArgs,
+    },
+
+    /// Check burn inputs without proving
+    #[command(
+        about = "Run every witness check that needs no STARK trace",
+        long_about = r#"Check burn inputs in milliseconds, before committing to a proving run.
+
+Runs the balance bounds, proof-of-work, header parsing, state-root match,
+MPT walk and burn-address checks, and reports each one with details.
+Exits with an error if any check fails. generate-burn runs the same checks
+first and stops on a failure unless --force is given."#
+    )]
+    Precheck {
+        /// Path to JSON input file containing burn proof parameters
         #[arg(short, long, value_name = "FILE")]
-        output: PathBuf,
+        input: PathBuf,
+
+        #[command(flatten)]
+        burn_args: BurnInputArgs,
     },
 
     /// Generate proof for token spending operation
@@ -94,6 +203,62 @@ Output: STWO proof file for spend verification"#
         /// Path where the generated proof will be saved
         #[arg(short, long, value_name = "FILE")]
         output: PathBuf,
+
+        /// Bind the proof to the address the withdrawal is paid to; without one, the proof can
+        /// be front-run by whoever sees it first
+        #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+        recipient: Option<Address>,
+    },
+
+    /// Generate proof for splitting a coin into two child coins
+    #[command(
+        about = "Create a proof that splits one coin into two private child coins",
+        long_about = r#"Generate a zero-knowledge proof for a coin split.
+
+The proof demonstrates that:
+- The spending key is valid for the coin
+- The two child amounts add up to the coin's balance
+- Both child coin commitments are properly formed
+
+No amount is revealed: the public commitment covers only the three coins
+and the extra commitment.
+
+Input: JSON file with split parameters
+Output: STWO proof file for split verification"#
+    )]
+    GenerateSplit {
+        /// Path to JSON input file containing split parameters
+        #[arg(short, long, value_name = "FILE")]
+        input: PathBuf,
+
+        /// Path where the generated proof will be saved
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Generate proof for handing a coin to another owner
+    #[command(
+        about = "Create a proof that transfers a coin to a recipient's key commitment",
+        long_about = r#"Generate a zero-knowledge proof for a coin transfer.
+
+The proof demonstrates that:
+- The sender's key is valid for the old coin, which is nullified
+- The new coin holds exactly the old coin's balance
+- The new coin is bound to the recipient's key commitment
+
+The balance itself is never revealed.
+
+Input: JSON file with transfer parameters
+Output: STWO proof file for transfer verification"#
+    )]
+    GenerateTransfer {
+        /// Path to JSON input file containing transfer parameters
+        #[arg(short, long, value_name = "FILE")]
+        input: PathBuf,
+
+        /// Path where the generated proof will be saved
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
     },
 
     /// Verify proof locally (for testing)
@@ -104,6 +269,21 @@ Output: STWO proof file for spend verification"#
 This command is primarily for testing and development. In production,
 proof verification occurs on-chain through the smart contract.
 
+With --statement, an envelope must also prove the given statement (JSON): a
+BurnStatement for burn envelopes, a SpendStatement for spend envelopes.
+Every differing field is reported.
+
+A batched burn envelope is reported instance by instance; with --statement,
+one of its instances must prove the given statement.
+
+Envelopes since v14 carry the claims their proof is verified against, and
+are invalid without them. Older envelopes and the other proof files are
+only checked for their structure, and reported as unverified.
+
+Exit status: 0 if the proof is valid (and proves the statement), 1 if it
+is unreadable, 2 if the file does not exist, 3 if it is valid but proves
+another statement, 4 if it is invalid.
+
 Note: This verification uses the same cryptographic algorithms as
 the on-chain verifier but runs locally for development purposes."#
     )]
@@ -112,9 +292,37 @@ the on-chain verifier but runs locally for development purposes."#
         #[arg(short, long, value_name = "FILE")]
         proof: PathBuf,
 
-        /// Type of proof to verify ("burn" or "spend")
+        /// Type of proof to verify ("burn", "spend", "split" or "transfer")
         #[arg(short = 't', long, value_name = "TYPE")]
         proof_type: String,
+
+        /// Expected statement (BurnStatement or SpendStatement JSON, after the envelope's circuit)
+        /// the envelope must prove
+        #[arg(long, value_name = "FILE")]
+        statement: Option<PathBuf>,
+    },
+
+    /// Show what a proof envelope records, without verifying it
+    #[command(
+        about = "Print the metadata of a proof envelope (not verified)",
+        long_about = r#"Print what a proof envelope records without verifying anything.
+
+Shows the format version, circuit, trace size, Merkle hasher, the FRI
+configuration of the embedded proof, the named public inputs, the burn
+statement (block hash, reveal amount, ...), the proof size and the recorded
+commitment layout with each root.
+
+Reads compressed, uncompressed and bare JSON envelopes, including older
+format versions. The output is metadata only: use verify to check a proof."#
+    )]
+    Inspect {
+        /// Path to the proof envelope (written by --emit-envelope)
+        #[arg(short, long, value_name = "FILE")]
+        proof: PathBuf,
+
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Display circuit parameters and system information
@@ -124,171 +332,756 @@ the on-chain verifier but runs locally for development purposes."#
 
 This includes:
 - Circuit size limits and constraints
+- Trace column, interaction column and constraint counts of each AIR
 - Cryptographic parameter details
 - Security level information
 - Estimated gas costs for verification
 
+With --analyze, also proves a dummy burn to measure proof size and verifier work
+(Merkle decommitments, queried field elements, hash operations) for one configuration.
+
+--output-format json prints the circuit parameters, AIR sizes and the analysis
+as one JSON object on stdout; logs stay on stderr.
+
 Useful for understanding system capabilities and planning deployments."#
     )]
-    Info,
+    Info {
+        /// Measure proof size and verification work on a dry-run burn proof
+        #[arg(long)]
+        analyze: bool,
+
+        /// Log2 of the trace rows to analyze
+        #[arg(long, value_name = "N", default_value_t = BURN_LOG_N_ROWS, requires = "analyze")]
+        log_n_rows: u32,
+
+        /// Target security bits (FRI parameters from `fri_params_for`); the default config if omitted
+        #[arg(long, value_name = "BITS", requires = "analyze")]
+        security: Option<u32>,
+
+        /// Print a human-readable report ("text") or a JSON object ("json")
+        #[arg(long, value_enum, value_name = "FORMAT", default_value = "text")]
+        output_format: OutputFormat,
+    },
+
+    /// Print the JSON Schema of an input or envelope file
+    #[command(
+        about = "Print the JSON Schema of an input or output file format",
+        long_about = r#"Print the JSON Schema (draft 07) of a file format to stdout.
+
+Types:
+- burn-inputs:  generate-burn input (ProofOfBurnInputs)
+- spend-inputs: generate-spend input (SpendInputs)
+- envelope:     proof envelope written by --emit-envelope (ProofEnvelope)
+
+256-bit integers, hashes and byte strings are hex strings; the schemas
+give their patterns."#
+    )]
+    Schema {
+        /// File format ("burn-inputs", "spend-inputs" or "envelope")
+        #[arg(short = 't', long = "type", value_name = "TYPE")]
+        schema_type: SchemaType,
+    },
+
+    /// Derive the burn address of a key and its commitments
+    #[command(
+        about = "Print the EIP-55 checksummed burn address to send ETH to",
+        long_about = r#"Derive the burn address from a burn key, reveal amount and burn extra
+commitment, and print it EIP-55 checksummed with its trie key.
+
+The extra commitment is taken from --burn-extra-commitment, or derived from
+--receiver, --fee and --salt as generate-burn does. With --expect, exits with
+an error unless the derived address is the given one; a mixed-case --expect
+must carry a valid checksum."#
+    )]
+    DeriveAddress {
+        /// Burn key (an M31 value)
+        #[arg(long, value_name = "KEY")]
+        burn_key: u32,
+
+        /// Amount to reveal, in wei (decimal or 0x-prefixed hex)
+        #[arg(long, value_name = "WEI")]
+        reveal_amount: U256,
+
+        /// burn_extra_commitment as an M31 value
+        #[arg(long, value_name = "M31", default_value_t = 0, conflicts_with = "receiver")]
+        burn_extra_commitment: u32,
+
+        /// Derive burn_extra_commitment as `commitments::burn_extra(receiver, fee, salt)`
+        #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+        receiver: Option<Address>,
+
+        /// Relayer fee in wei bound into burn_extra_commitment
+        #[arg(long, value_name = "WEI", default_value = "0", requires = "receiver")]
+        fee: U256,
+
+        /// Salt bound into burn_extra_commitment
+        #[arg(long, value_name = "SALT", default_value_t = 0, requires = "receiver")]
+        salt: u32,
+
+        /// Address the derived one must equal, e.g. the one the ETH was sent to
+        #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+        expect: Option<Address>,
+    },
+
+    /// Mine a burn key meeting the PoW requirement
+    #[command(
+        about = "Search for a burn key whose PoW hash has enough leading zero bytes",
+        long_about = r#"Search for a burn key whose PoW hash (keccak256 of the key, reveal amount,
+burn extra commitment and "EIP-7503") starts with --zero-bytes zero bytes.
+
+The search starts at a random key and gives up after --max-attempts
+candidates; each zero byte multiplies the expected work by 256. The key is
+printed to stdout: keep it secret, it controls the burned ETH."#
+    )]
+    MineKey {
+        /// Amount to reveal, in wei (decimal or 0x-prefixed hex)
+        #[arg(long, value_name = "WEI")]
+        reveal_amount: U256,
+
+        /// burn_extra_commitment as an M31 value
+        #[arg(long, value_name = "M31", default_value_t = 0)]
+        burn_extra_commitment: u32,
+
+        /// Leading zero bytes the PoW hash must have
+        #[arg(long, value_name = "BYTES", default_value_t = POW_MINIMUM_ZERO_BYTES)]
+        zero_bytes: usize,
+
+        /// Candidates to try before giving up
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_ATTEMPTS)]
+        max_attempts: u64,
+    },
+
+    /// Create a key pair for receiving sealed witnesses
+    #[command(
+        about = "Write an X25519 secret key for opening sealed witnesses",
+        long_about = r#"Write a fresh X25519 secret key (hex) to --output and print its public key.
+
+Run this on the proving box: hand the public key to whoever seals witnesses
+for it (seal-witness --seal-to), and keep the key file for
+generate-burn --unseal-key."#
+    )]
+    WitnessKey {
+        /// Path to write the secret key to; must not exist yet
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Encrypt burn inputs for a prover that must not learn the burn key
+    #[command(
+        about = "Seal burn inputs to a proving box's public key",
+        long_about = r#"Encrypt a burn input file to an X25519 public key (from witness-key), so it
+can be stored or sent to that prover without exposing the burn key.
+
+The burn key is always encrypted. With --key-only the other fields stay
+readable, for provers allowed to see balances and MPT data; they are still
+authenticated, so altering them makes the witness fail to open."#
+    )]
+    SealWitness {
+        /// Path to JSON input file containing burn proof parameters
+        #[arg(short, long, value_name = "FILE")]
+        input: PathBuf,
+
+        /// Path where the sealed witness will be saved
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+
+        /// Public key of the prover to seal to (32 bytes, 0x-prefixed hex)
+        #[arg(long, value_name = "PUBKEY")]
+        seal_to: B256,
+
+        /// Encrypt only the burn key, leaving the other fields readable
+        #[arg(long)]
+        key_only: bool,
+    },
+
+    /// Write test vectors for other implementations of the protocol
+    #[command(
+        about = "Write test vectors for cross-implementation checks",
+        long_about = r#"Write canonical test vectors as JSON, to stdout or --output.
+
+Covers the Poseidon2 permutation, burn addresses, PoW hashes, coins,
+nullifiers, public commitments and proof ids for a fixed set of inputs,
+including the zero and largest M31 burn key and the zero and largest
+reveal amount. The file opens with a description of every section.
+
+tests/fixtures/test_vectors.json is this output; the test suite fails
+when the two drift apart."#
+    )]
+    GenVectors {
+        /// Path to write the vectors to; stdout if omitted
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Prove and verify for remote clients over HTTP
+    #[cfg(feature = "server")]
+    #[command(
+        about = "Serve burn and spend proofs over an HTTP JSON API",
+        long_about = r#"Run the prover as an HTTP server, so a thin wallet can hand proving to a
+machine with the cores for it.
+
+Endpoints:
+  POST /prove/burn    burn inputs, as the generate-burn input file
+  POST /prove/spend   spend inputs, as the generate-spend input file
+  POST /verify        a proof envelope file, compressed or not
+  GET  /info          the prover's configuration
+
+Prove requests sent with `Accept: text/event-stream` get progress events
+before the result. Errors are JSON with a `code` equal to the exit status
+of the matching CLI failure. Burn keys travel in request bodies: keep the
+default localhost address or serve behind a TLS proxy, and set --token."#
+    )]
+    Serve {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8645")]
+        listen: String,
+
+        /// Require `Authorization: Bearer <TOKEN>` on every request
+        #[arg(long, value_name = "TOKEN", env = "POB_SERVER_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// Requests handled at once
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        workers: usize,
+
+        /// Log2 of the trace rows of each proof
+        #[arg(long, value_name = "LOG_N_ROWS", default_value_t = BURN_LOG_N_ROWS)]
+        log_n_rows: u32,
+    },
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn main() -> anyhow::Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    init_tracing(cli.verbose);
+    MAX_INPUT_BYTES.set(cli.max_input_size).expect("set once at startup");
+    ERROR_FORMAT.set(cli.error_format).expect("set once at startup");
+
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(e) => {
+            let missing_file = e.downcast_ref::<MissingFile>().is_some();
+            let reported = json_error(|| {
+                let code = if missing_file { ErrorCode::InputMissing } else { ErrorCode::of(e.as_ref()) };
+                let causes: Vec<String> = e.chain().skip(1).map(ToString::to_string).collect();
+                let report = ErrorReport::new(code, e.to_string());
+                if causes.is_empty() {
+                    report
+                } else {
+                    report.with_details(&serde_json::json!({ "causes": causes }))
+                }
+            });
+            if !reported {
+                eprintln!("Error: {e:?}");
+            }
+            if missing_file {
+                ExitCode::from(EXIT_MISSING_FILE)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
 
-    match cli.command {
-        Commands::GenerateBurn { input, output } => {
-            generate_burn_proof(input, output)?;
+/// `--error-format`, set once at startup
+static ERROR_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// With `--error-format json`, print the report to stderr as one line and return true
+fn json_error(report: impl FnOnce() -> ErrorReport) -> bool {
+    let json = matches!(ERROR_FORMAT.get(), Some(OutputFormat::Json));
+    if json {
+        eprintln!("{}", report().to_json());
+    }
+    json
+}
+
+fn run(command: Commands) -> anyhow::Result<ExitCode> {
+    match command {
+        Commands::GenerateBurn {
+            input,
+            output,
+            check_only,
+            dump_trace,
+            emit_calldata,
+            emit_foundry_fixture,
+            emit_public_values,
+            emit_envelope,
+            compress,
+            force,
+            burn_args,
+        } => {
+            if let Some(dump_path) = dump_trace {
+                dump_burn_trace(&input, &burn_args, &dump_path)?;
+            }
+            if check_only {
+                check_burn_constraints(input, &burn_args)?;
+            } else {
+                let output = output.expect("clap requires --output unless --check-only");
+                precheck_burn_inputs(&input, &burn_args, force)?;
+                generate_burn_proof(
+                    input,
+                    &burn_args,
+                    output,
+                    emit_calldata,
+                    emit_foundry_fixture,
+                    emit_public_values,
+                    emit_envelope.map(|path| (path, compress)),
+                )?;
+            }
+        }
+        Commands::Precheck { input, burn_args } => {
+            let inputs = read_burn_inputs(&input, &burn_args)?;
+            run_precheck(&inputs)?;
         }
-        Commands::GenerateSpend { input, output } => {
-            generate_spend_proof(input, output)?;
+        Commands::GenerateSpend { input, output, recipient } => {
+            generate_spend_proof(input, output, recipient)?;
         }
-        Commands::Verify { proof, proof_type } => {
-            verify_proof(proof, proof_type)?;
+        Commands::GenerateSplit { input, output } => {
+            generate_split_proof(input, output)?;
         }
-        Commands::Info => {
-            show_system_info();
+        Commands::GenerateTransfer { input, output } => {
+            generate_transfer_proof(input, output)?;
+        }
+        Commands::Verify { proof, proof_type, statement } => {
+            return verify_proof(proof, proof_type, statement);
+        }
+        Commands::Inspect { proof, json } => {
+            inspect_envelope(&proof, json)?;
+        }
+        Commands::Schema { schema_type } => {
+            println!("{}", schema_type.to_json());
+        }
+        Commands::DeriveAddress { burn_key, reveal_amount, burn_extra_commitment, receiver, fee, salt, expect } => {
+            let extra = match receiver {
+                Some(receiver) => commitments::burn_extra(receiver, fee, M31::from(salt)),
+                None => M31::from(burn_extra_commitment),
+            };
+            derive_burn_address(M31::from(burn_key), reveal_amount, extra, expect)?;
+        }
+        Commands::MineKey { reveal_amount, burn_extra_commitment, zero_bytes, max_attempts } => {
+            mine_key(reveal_amount, M31::from(burn_extra_commitment), zero_bytes, max_attempts)?;
+        }
+        Commands::WitnessKey { output } => {
+            write_witness_key(&output)?;
+        }
+        Commands::SealWitness { input, output, seal_to, key_only } => {
+            let policy = if key_only { SealPolicy::KeyOnly } else { SealPolicy::All };
+            seal_witness(&input, &output, seal_to, policy)?;
+        }
+        Commands::GenVectors { output } => {
+            let json = vectors::generate().to_json();
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, json + "\n")
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("Test vectors written to {}", path.display());
+                }
+                None => println!("{json}"),
+            }
+        }
+        #[cfg(feature = "server")]
+        Commands::Serve { listen, token, workers, log_n_rows } => {
+            serve(&listen, token, workers, log_n_rows)?;
+        }
+        Commands::Info { analyze, log_n_rows, security, output_format } => {
+            let analysis = analyze.then(|| analyze_config(log_n_rows, security)).transpose()?;
+            match output_format {
+                OutputFormat::Text => {
+                    show_system_info();
+                    if let Some(report) = analysis {
+                        show_config_report(&report);
+                    }
+                }
+                OutputFormat::Json => {
+                    let report = InfoReport::new(analysis);
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+            }
         }
     }
 
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Log progress to stderr, filtered by `RUST_LOG` or else by `--verbose`
+fn init_tracing(verbose: bool) {
+    let default_level = if verbose { "debug" } else { "info" };
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(default_level));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .init();
+}
+
+/// Exit status when an input or proof file does not exist, as for clap's usage errors
+///
+/// Other errors exit with 1.
+const EXIT_MISSING_FILE: u8 = exit_code::MISSING_FILE;
+
+/// `verify` exit status for a valid proof of another statement than `--statement`
+const EXIT_STATEMENT_MISMATCH: u8 = exit_code::STATEMENT_MISMATCH;
+
+/// `verify` exit status for a readable proof that is invalid
+const EXIT_INVALID_PROOF: u8 = exit_code::INVALID_PROOF;
+
+/// A file named on the command line does not exist
+#[derive(Debug, thiserror::Error)]
+#[error("{kind} file does not exist: {}", path.display())]
+struct MissingFile {
+    kind: &'static str,
+    path: PathBuf,
+}
+
+impl MissingFile {
+    /// Fail unless `path` exists
+    fn check(kind: &'static str, path: &Path) -> Result<(), Self> {
+        if path.exists() {
+            Ok(())
+        } else {
+            Err(Self { kind, path: path.to_path_buf() })
+        }
+    }
+}
+
+/// How `info` prints its report, and `--error-format` a failure
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Log2 of the trace rows used by `generate-burn`
+const BURN_LOG_N_ROWS: u32 = 16;
+
+/// Burn input overrides shared by the commands that read burn inputs
+#[derive(clap::Args)]
+struct BurnInputArgs {
+    /// The input file is a sealed witness (seal-witness); open it with the secret key in this file
+    #[arg(long, value_name = "FILE", env = "POB_UNSEAL_KEY")]
+    unseal_key: Option<PathBuf>,
+
+    /// JSON array of the headers after the input's block_header, oldest first; sets header_chain
+    #[arg(long, value_name = "FILE", env = "POB_HEADER_CHAIN")]
+    header_chain: Option<PathBuf>,
+
+    #[command(flatten)]
+    extra_commitments: ExtraCommitmentArgs,
+}
+
+/// A header chain file: hex byte strings or integer arrays, as the input file's layers
+#[derive(Deserialize)]
+#[serde(transparent)]
+struct HeaderChainFile(#[serde(with = "proof_of_burn_stwo::utils::hex_bytes::layers")] Vec<Vec<u8>>);
+
+impl BurnInputArgs {
+    fn apply(&self, inputs: &mut ProofOfBurnInputs) -> anyhow::Result<()> {
+        if let Some(path) = &self.header_chain {
+            let HeaderChainFile(chain) = read_input_file(path)?;
+            let info = verify_descendants(&inputs.block_header, &chain)
+                .with_context(|| format!("Header chain {} does not extend the input's block header", path.display()))?;
+            tracing::info!("Header chain verified: block {} to {}", info.first_number, info.last_number);
+            inputs.header_chain = chain;
+        }
+        self.extra_commitments.apply(inputs);
+        Ok(())
+    }
+}
+
+/// Extra commitments derived with `utils::commitments` instead of read from the input file
+///
+/// The burn extra commitment is part of the burn address, so `--receiver`, `--fee` and `--salt`
+/// must match the values the burn address was derived with.
+#[derive(clap::Args)]
+struct ExtraCommitmentArgs {
+    /// Set burn_extra_commitment to `commitments::burn_extra(receiver, fee, salt)`
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+    receiver: Option<Address>,
+
+    /// Relayer fee in wei bound into burn_extra_commitment
+    #[arg(long, value_name = "WEI", default_value = "0", requires = "receiver")]
+    fee: U256,
+
+    /// Salt bound into burn_extra_commitment
+    #[arg(long, value_name = "SALT", default_value_t = 0, requires = "receiver")]
+    salt: u32,
+
+    /// Set proof_extra_commitment to `commitments::proof_extra(prover_address)`
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+    prover_address: Option<Address>,
+}
+
+impl ExtraCommitmentArgs {
+    fn apply(&self, inputs: &mut ProofOfBurnInputs) {
+        if let Some(receiver) = self.receiver {
+            inputs.burn_extra_commitment = commitments::burn_extra(receiver, self.fee, M31::from(self.salt));
+            tracing::info!("burn_extra_commitment derived for receiver {receiver}: {:?}", inputs.burn_extra_commitment);
+        }
+        if let Some(prover) = self.prover_address {
+            inputs.proof_extra_commitment = commitments::proof_extra(prover);
+            tracing::info!("proof_extra_commitment derived for prover {prover}: {:?}", inputs.proof_extra_commitment);
+        }
+    }
+}
+
+/// `--max-input-size`, set once at startup
+static MAX_INPUT_BYTES: OnceLock<u64> = OnceLock::new();
+
+/// Parse a JSON input file, streamed from disk and refused past `--max-input-size`
+fn read_input_file<T: DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let max_bytes = MAX_INPUT_BYTES.get().copied().unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+    read_json_file(path, max_bytes).map_err(|e| {
+        let context = match e {
+            InputFileError::TooLarge { .. } => {
+                format!("Refusing {}; raise --max-input-size if the file is intended", path.display())
+            }
+            _ => format!("Failed to read input file: {}", path.display()),
+        };
+        anyhow::Error::new(e).context(context)
+    })
+}
+
+fn read_burn_inputs(input_path: &Path, burn_args: &BurnInputArgs) -> anyhow::Result<ProofOfBurnInputs> {
+    tracing::info!("Reading burn proof inputs from: {}", input_path.display());
+
+    MissingFile::check("Input", input_path)?;
+
+    let mut inputs: ProofOfBurnInputs = match &burn_args.unseal_key {
+        Some(key_path) => {
+            let sealed: SealedWitness = read_input_file(input_path)?;
+            unseal(&sealed, &read_witness_key(key_path)?)
+                .with_context(|| format!("Failed to unseal {}", input_path.display()))?
+        }
+        None => read_input_file(input_path)?,
+    };
+
+    // Reject malformed MPT layers before anything hashes or scans them
+    let issues: Vec<InputIssue> = layer_issues(&inputs.layers).into_iter().map(InputIssue::from).collect();
+    if !issues.is_empty() {
+        return Err(InputValidationReport { issues }).with_context(|| "Invalid MPT layers in input JSON");
+    }
+
+    burn_args.apply(&mut inputs)?;
+    Ok(inputs)
+}
+
+/// Print the witness pre-check report; an error if any check did not pass
+fn run_precheck(inputs: &ProofOfBurnInputs) -> anyhow::Result<()> {
+    let report = precheck(inputs, &CircuitParams::default());
+    println!("Witness pre-check:");
+    print!("{}", report);
+    if !report.passed() {
+        anyhow::bail!("Witness pre-check failed: {} check(s) did not pass", report.failures().count());
+    }
+    Ok(())
+}
+
+fn derive_burn_address(burn_key: M31, reveal_amount: U256, extra: M31, expect: Option<Address>) -> anyhow::Result<()> {
+    let address = compute_burn_address(burn_key, reveal_amount, extra);
+    println!("Burn address:          {}", format_checksummed(address));
+    println!("Address hash:          {}", B256::from(keccak256(address.as_slice())));
+    println!("Burn extra commitment: {}", extra.value());
+    if let Some(expected) = expect {
+        if address != expected {
+            anyhow::bail!("Derived burn address {} is not {}", format_checksummed(address), format_checksummed(expected));
+        }
+        println!("Matches the expected address");
+    }
+    Ok(())
+}
+
+fn mine_key(reveal_amount: U256, extra: M31, zero_bytes: usize, max_attempts: u64) -> anyhow::Result<()> {
+    let never = std::sync::atomic::AtomicBool::new(false);
+    let mined = match mine_burn_key(reveal_amount, extra, zero_bytes, max_attempts, &never) {
+        Ok(mined) => mined,
+        Err(e @ MinerError::Exhausted { .. }) => {
+            return Err(anyhow::Error::new(e).context("Raise --max-attempts or lower --zero-bytes"));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    println!("Burn key:  {}", mined.burn_key.value());
+    println!("PoW hash:  {}", B256::from(mined.pow_hash));
+    println!("Attempts:  {} in {:.2?}", mined.attempts, mined.elapsed);
     Ok(())
 }
 
-// WASM entry point for browser usage
-#[cfg(target_arch = "wasm32")]
-use wasm_bindgen::prelude::*;
+fn write_witness_key(path: &Path) -> anyhow::Result<()> {
+    let secret = generate_secret();
+    let mut digits = Zeroizing::new([0u8; 64]);
+    hex::encode_to_slice(secret.as_bytes(), &mut *digits).expect("64 digits for 32 bytes");
+
+    // Never overwrite a key: witnesses sealed to it would no longer open
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    [b"0x".as_slice(), digits.as_slice(), b"\n"]
+        .into_iter()
+        .try_for_each(|part| std::io::Write::write_all(&mut file, part))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Secret key written to {}", path.display());
+    println!("Public key: {}", B256::from(PublicKey::from(&secret).to_bytes()));
+    Ok(())
+}
 
-#[cfg(target_arch = "wasm32")]
-#[wasm_bindgen(start)]
-pub fn main() {
-    // Initialize console logging for WASM
-    console_error_panic_hook::set_once();
-    console_log::init_with_level(log::Level::Info).expect("Failed to initialize logger");
+/// Read a secret key written by witness-key
+fn read_witness_key(path: &Path) -> anyhow::Result<StaticSecret> {
+    MissingFile::check("Unseal key", path)?;
+    let text = Zeroizing::new(
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?,
+    );
+    let digits = text.trim();
+    let mut bytes = Zeroizing::new([0u8; 32]);
+    hex::decode_to_slice(digits.strip_prefix("0x").unwrap_or(digits), &mut *bytes)
+        .with_context(|| format!("{} does not hold a 32-byte hex key", path.display()))?;
+    Ok(StaticSecret::from(*bytes))
 }
 
-#[cfg(target_arch = "wasm32")]
-#[wasm_bindgen]
-pub fn generate_burn_proof_wasm(input_json: &str) -> Result<String, JsValue> {
-    // Parse input JSON and generate proof
-    // Return proof as JSON string
-    unimplemented!("WASM implementation pending")
+fn seal_witness(
+    input_path: &Path,
+    output_path: &Path,
+    seal_to: B256,
+    policy: SealPolicy,
+) -> anyhow::Result<()> {
+    MissingFile::check("Input", input_path)?;
+    let inputs: ProofOfBurnInputs = read_input_file(input_path)?;
+    let sealed = seal_with_policy(&inputs, &PublicKey::from(seal_to.0), policy);
+    let json = serde_json::to_string_pretty(&sealed)?;
+    std::fs::write(output_path, json + "\n")
+        .with_context(|| format!("Failed to write {}", output_path.display()))?;
+    println!("Sealed witness written to {}", output_path.display());
+    Ok(())
 }
 
-#[cfg(target_arch = "wasm32")]
-#[wasm_bindgen]
-pub fn generate_spend_proof_wasm(input_json: &str) -> Result<String, JsValue> {
-    // Parse input JSON and generate proof
-    // Return proof as JSON string
-    unimplemented!("WASM implementation pending")
+/// Pre-check the inputs before proving, so a bad witness fails in milliseconds; a failure only
+/// stops the run without `force`
+fn precheck_burn_inputs(input_path: &Path, burn_args: &BurnInputArgs, force: bool) -> anyhow::Result<()> {
+    let inputs = read_burn_inputs(input_path, burn_args)?;
+    if let Err(e) = run_precheck(&inputs) {
+        if !force {
+            return Err(e.context("Pass --force to prove anyway"));
+        }
+        tracing::warn!("Witness pre-check failed, proving anyway (--force)");
+    }
+    Ok(())
+}
+
+fn dump_burn_trace(input_path: &Path, burn_args: &BurnInputArgs, dump_path: &Path) -> anyhow::Result<()> {
+    let inputs = read_burn_inputs(input_path, burn_args)?;
+
+    let (trace, _lookup_data) = generate_pob_trace(BURN_LOG_N_ROWS, &inputs)
+        .map_err(|e| anyhow::anyhow!("Trace generation failed: {}", e))?;
+    dump_trace_csv(&trace, &pob_column_labels(), dump_path)
+        .with_context(|| format!("Failed to dump trace: {}", dump_path.display()))?;
+
+    tracing::info!("Trace ({} columns) dumped to: {}", trace.len(), dump_path.display());
+    Ok(())
+}
+
+fn check_burn_constraints(input_path: PathBuf, burn_args: &BurnInputArgs) -> anyhow::Result<()> {
+    let inputs = read_burn_inputs(&input_path, burn_args)?;
+
+    tracing::info!("Checking Proof of Burn constraints...");
+    check_pob_constraints(&inputs, BURN_LOG_N_ROWS)
+        .with_context(|| "Constraint check failed")?;
+
+    tracing::info!("All constraints satisfied");
+    Ok(())
 }
 
 /// Convert STWO StarkProof to SimpleProof using only accessible data.
 /// This function extracts only the commitment data that STWO exposes publicly.
-/// The proof_id is calculated to match the Solidity contract expectation.
+/// The proof_id is the one the Solidity contract records for the statement.
 fn convert_stark_proof_to_simple(
     proof: &stwo_prover::core::proof::StarkProof<stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher>,
-    public_commitment: alloy_primitives::U256,
-    nullifier: alloy_primitives::U256,
-    commitment: alloy_primitives::U256,
+    layout: &CommitmentLayout,
+    timings: ProvingTimings,
+    proof_id: ProofId,
 ) -> anyhow::Result<SimpleProof> {
-    // TODO: Verify that proof.commitments[0] is trace_commitment and [1] is composition_commitment
-    let trace_commitment = B256::from(proof.commitments[0].0);
-    let composition_commitment = B256::from(proof.commitments[1].0);
-
-    // Calculate proof_id to match Solidity contract: keccak256(abi.encodePacked(publicCommitment, nullifier, commitment))
-    let mut packed_data = Vec::new();
-    packed_data.extend_from_slice(&public_commitment.to_be_bytes::<32>());
-    packed_data.extend_from_slice(&nullifier.to_be_bytes::<32>());
-    packed_data.extend_from_slice(&commitment.to_be_bytes::<32>());
-    let proof_id = alloy_primitives::keccak256(&packed_data);
+    // By role, as the contract reads them (`CONTRACT_TREE_ORDER`), whatever their position
+    let commitments = ProofCommitments::from_layout(proof, layout)?;
+    let trace_commitment = B256::from(commitments.trace.0);
+    let composition_commitment = B256::from(commitments.composition.0);
 
     let simple_proof = SimpleProof {
         trace_commitment,
         composition_commitment,
-        proof_id: B256::from(proof_id),
+        proof_id,
+        timings,
     };
 
     Ok(simple_proof)
 }
 
-fn generate_burn_proof(input_path: PathBuf, output_path: PathBuf) -> anyhow::Result<()> {
-    println!("Reading burn proof inputs from: {}", input_path.display());
-
-    // Validate input file exists
-    if !input_path.exists() {
-        anyhow::bail!("Input file does not exist: {}", input_path.display());
-    }
-
-    // Read and parse input
-    let input_data = std::fs::read_to_string(&input_path)
-        .with_context(|| format!("Failed to read input file: {}", input_path.display()))?;
-
-    let inputs: ProofOfBurnInputs = serde_json::from_str(&input_data)
-        .with_context(|| "Failed to parse input JSON")?;
+fn generate_burn_proof(
+    input_path: PathBuf,
+    burn_args: &BurnInputArgs,
+    output_path: PathBuf,
+    calldata_path: Option<PathBuf>,
+    fixture_path: Option<PathBuf>,
+    public_values_path: Option<PathBuf>,
+    envelope_path: Option<(PathBuf, bool)>,
+) -> anyhow::Result<()> {
+    let inputs = read_burn_inputs(&input_path, burn_args)?;
+
+    // Validate the witness and take the actual balance the trace carries from the proven leaf
+    let circuit = proof_of_burn_stwo::circuits::proof_of_burn::ProofOfBurnCircuit::new(inputs.clone())
+        .with_context(|| "Failed to create ProofOfBurnCircuit instance")?;
+    let outputs = circuit.compute_outputs()
+        .with_context(|| "Failed to compute circuit outputs")?;
+    let inputs = circuit.proven_inputs().with_context(|| "Failed to read the proven account")?;
 
-    println!("Generating complete STWO proof for Proof of Burn...");
+    tracing::info!("Generating complete STWO proof for Proof of Burn...");
 
     // Generate full STWO proof using the prover
     // TODO: Use proper log_n_rows calculation instead of hardcoded 16
     // TODO: Use proper ProverConfig instead of Default::default()
-    let (_component, stark_proof) = prove_proof_of_burn(&inputs, 16, Default::default())
+    let log_n_rows = BURN_LOG_N_ROWS;
+    let (components, stark_proof) = prove_proof_of_burn(&inputs, log_n_rows, Default::default())
         .with_context(|| "Failed to generate STWO proof")?;
 
-    println!("STWO proof generation successful");
+    tracing::info!("STWO proof generation successful");
 
-    // Create circuit instance and get real outputs
-    let circuit = proof_of_burn_stwo::circuits::proof_of_burn::ProofOfBurnCircuit::new(inputs.clone())
-        .with_context(|| "Failed to create ProofOfBurnCircuit instance")?;
+    // Read the public values back from the proof; verification consumes the proof, so extract
+    // from a copy read back from its serialized form
+    let proof_copy: stwo_prover::core::proof::StarkProof<stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher> =
+        serde_json::from_slice(&serde_json::to_vec(&stark_proof)?)?;
+    let public_values = PobPublicValues::extract(&components, proof_copy)
+        .with_context(|| "Generated proof does not verify against its public values")?;
 
-    let outputs = circuit.compute_outputs()
-        .with_context(|| "Failed to compute circuit outputs")?;
+    println!("Public values extracted:");
+    println!("  {public_values}");
+    println!("  Security Level: {}", outputs.security);
 
-    println!("Circuit outputs computed:");
-    println!("  Commitment: {:?}", outputs.commitment);
-    println!("  Nullifier: {:?}", outputs.nullifier);
-    println!("  Remaining Coin: {:?}", outputs.remaining_coin);
-
-    // Convert outputs to U256 for contract compatibility
-    // Validate M31 values are in correct range before accessing
-    use proof_of_burn_stwo::constants::M31_PRIME;
-    let nullifier_val = outputs.nullifier.value();
-    let commitment_val = outputs.commitment.value();
-    
-    if nullifier_val >= M31_PRIME {
-        anyhow::bail!("nullifier value {} exceeds M31 prime {}", nullifier_val, M31_PRIME);
-    }
-    if commitment_val >= M31_PRIME {
-        anyhow::bail!("commitment value {} exceeds M31 prime {}", commitment_val, M31_PRIME);
-    }
-    
-    // Safe to convert to u64 now
-    let nullifier = alloy_primitives::U256::from(nullifier_val as u64);
-    let commitment = alloy_primitives::U256::from(commitment_val as u64);
-
-    // Calculate block hash from block header (this is what Commitments.sol uses as blockHash)
-    let block_hash = alloy_primitives::keccak256(&inputs.block_header);
-
-    // Calculate publicCommitment as per Commitments.sol:
-    // keccak256(abi.encodePacked(blockHash, nullifier, commitment, revealAmount)) >> 8
-    let mut packed_data = Vec::new();
-    packed_data.extend_from_slice(block_hash.as_slice());
-    packed_data.extend_from_slice(&nullifier.to_be_bytes::<32>());
-    packed_data.extend_from_slice(&commitment.to_be_bytes::<32>());
-    packed_data.extend_from_slice(&inputs.reveal_amount.to_be_bytes::<32>());
-    let public_commitment_bytes = alloy_primitives::keccak256(&packed_data);
-    let public_commitment = alloy_primitives::U256::from_be_bytes(public_commitment_bytes.into()) >> alloy_primitives::U256::from(8);
+    // Everything the verifier checks, with the block hash Commitments.sol uses and the public
+    // inputs [publicCommitment, nullifier, commitment, securityLevel] derived from it
+    let statement = BurnStatement::new(&inputs, public_values);
+    let (public_commitment, nullifier, commitment, _security_level) = statement.to_solidity_tuple();
 
     // Convert to SimpleProof using commitment data and calculated proof_id
-    let simple_proof = convert_stark_proof_to_simple(&stark_proof, public_commitment, nullifier, commitment)
-        .with_context(|| "Failed to convert STWO proof to SimpleProof")?;
+    let simple_proof = convert_stark_proof_to_simple(
+        &stark_proof,
+        &components.layout,
+        components.timings,
+        statement.proof_id(),
+    )
+    .with_context(|| "Failed to convert STWO proof to SimpleProof")?;
 
     println!("Converted to SimpleProof:");
     println!("  Trace commitment: {:?}", simple_proof.trace_commitment);
     println!("  Composition commitment: {:?}", simple_proof.composition_commitment);
-    println!("  Proof ID: {:?}", simple_proof.proof_id);
+    println!("  Proof ID: {}", simple_proof.proof_id);
     println!("  Public inputs: commitment={:?}, nullifier={:?}, commitment={:?}", public_commitment, nullifier, commitment);
 
+    let timings = &simple_proof.timings;
+    println!("Proving time: {:.1} ms", timings.total_ms);
+    println!("  Trace generation: {:.1} ms", timings.trace_gen_ms);
+    println!("  Twiddles: {:.1} ms", timings.twiddles_ms);
+    println!("  Commitments: {:.1} ms", timings.commits_ms);
+    println!("  Interaction: {:.1} ms", timings.interaction_ms);
+    println!("  Proof: {:.1} ms", timings.proof_ms);
+
     // Create output directory if it doesn't exist
     if let Some(parent) = output_path.parent() {
         std::fs::create_dir_all(parent)
@@ -300,37 +1093,148 @@ fn generate_burn_proof(input_path: PathBuf, output_path: PathBuf) -> anyhow::Res
     std::fs::write(&output_path, output_data)
         .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
 
-    println!("SimpleProof saved to: {}", output_path.display());
-    println!("Note: This generates commitments-only proof data suitable for on-chain verification.");
+    tracing::info!("SimpleProof saved to: {}", output_path.display());
+    tracing::warn!("This generates commitments-only proof data suitable for on-chain verification.");
+
+    if let Some(public_values_path) = public_values_path {
+        std::fs::write(&public_values_path, serde_json::to_string_pretty(&public_values)?)
+            .with_context(|| format!("Failed to write public values file: {}", public_values_path.display()))?;
+
+        tracing::info!("Public values saved to: {}", public_values_path.display());
+    }
+
+    if calldata_path.is_none() && fixture_path.is_none() && envelope_path.is_none() {
+        return Ok(());
+    }
+
+    let envelope = ProofEnvelope::from_burn_proof(log_n_rows, &stark_proof, components.layout.clone(), statement)
+        .with_context(|| "Failed to build proof envelope")?
+        .with_claims(&components);
+
+    if let Some(calldata_path) = calldata_path {
+        let calldata = encode_proof_calldata(&envelope);
+
+        std::fs::write(&calldata_path, format!("0x{}", hex::encode(&calldata)))
+            .with_context(|| format!("Failed to write calldata file: {}", calldata_path.display()))?;
+
+        tracing::info!("Verifier calldata ({} bytes) saved to: {}", calldata.len(), calldata_path.display());
+    }
+
+    if let Some(fixture_path) = fixture_path {
+        export_foundry_fixture(&envelope, &fixture_path)
+            .with_context(|| format!("Failed to write Foundry fixture: {}", fixture_path.display()))?;
+
+        tracing::info!("Foundry fixture saved to: {}", fixture_path.display());
+    }
+
+    if let Some((envelope_path, compress)) = envelope_path {
+        let uncompressed = envelope.to_bytes().with_context(|| "Failed to encode proof envelope")?;
+        let bytes = if compress {
+            let compressed = envelope.to_bytes_compressed().with_context(|| "Failed to compress proof envelope")?;
+            println!("Envelope: {} bytes compressed, {} bytes uncompressed", compressed.len(), uncompressed.len());
+            compressed
+        } else {
+            println!("Envelope: {} bytes uncompressed", uncompressed.len());
+            uncompressed
+        };
+
+        std::fs::write(&envelope_path, bytes)
+            .with_context(|| format!("Failed to write envelope file: {}", envelope_path.display()))?;
+
+        tracing::info!("Proof envelope saved to: {}", envelope_path.display());
+    }
 
     Ok(())
 }
 
-fn generate_spend_proof(input_path: PathBuf, output_path: PathBuf) -> anyhow::Result<()> {
-    println!("Reading spend proof inputs from: {}", input_path.display());
+fn generate_spend_proof(input_path: PathBuf, output_path: PathBuf, recipient: Option<Address>) -> anyhow::Result<()> {
+    tracing::info!("Reading spend proof inputs from: {}", input_path.display());
+
+    MissingFile::check("Input", &input_path)?;
 
-    // Validate input file exists
-    if !input_path.exists() {
-        anyhow::bail!("Input file does not exist: {}", input_path.display());
+    let mut inputs: SpendInputs = read_input_file(&input_path)?;
+    if let Some(recipient) = recipient {
+        inputs.recipient = Some(recipient);
+    }
+    match inputs.recipient {
+        Some(recipient) => tracing::info!("Spend bound to recipient {recipient}"),
+        None => tracing::warn!("Spend not bound to a recipient: the proof can be front-run"),
     }
 
-    // Read and parse input
-    let input_data = std::fs::read_to_string(&input_path)
-        .with_context(|| format!("Failed to read input file: {}", input_path.display()))?;
+    tracing::info!("Creating Spend circuit...");
+    let circuit = SpendCircuit::new(inputs)?;
 
-    let inputs: SpendInputs = serde_json::from_str(&input_data)
-        .with_context(|| "Failed to parse input JSON")?;
+    tracing::info!("Computing circuit witness...");
+    let outputs = circuit.compute_outputs();
 
-    println!("Creating Spend circuit...");
-    let circuit = SpendCircuit::new(inputs)?;
+    tracing::info!("Circuit computation successful");
+    println!("  {outputs}");
+
+    // Create output directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory: {}", parent.display()))?;
+    }
+
+    // Save outputs
+    let output_data = serde_json::to_string_pretty(&outputs)?;
+    std::fs::write(&output_path, output_data)
+        .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
 
-    println!("Computing circuit witness...");
+    tracing::info!("Proof outputs saved to: {}", output_path.display());
+    tracing::warn!("This generates circuit outputs only. Full STWO proof generation requires additional implementation.");
+
+    Ok(())
+}
+
+fn generate_split_proof(input_path: PathBuf, output_path: PathBuf) -> anyhow::Result<()> {
+    tracing::info!("Reading split proof inputs from: {}", input_path.display());
+
+    MissingFile::check("Input", &input_path)?;
+
+    let inputs: SplitInputs = read_input_file(&input_path)?;
+
+    tracing::info!("Creating Split circuit...");
+    let circuit = SplitCircuit::new(inputs)?;
+
+    tracing::info!("Computing circuit witness...");
+    let outputs = circuit.compute_outputs();
+
+    tracing::info!("Circuit computation successful");
+    println!("  {outputs}");
+
+    // Create output directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory: {}", parent.display()))?;
+    }
+
+    // Save outputs
+    let output_data = serde_json::to_string_pretty(&outputs)?;
+    std::fs::write(&output_path, output_data)
+        .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
+
+    tracing::info!("Proof outputs saved to: {}", output_path.display());
+    tracing::warn!("This generates circuit outputs only. Full STWO proof generation requires additional implementation.");
+
+    Ok(())
+}
+
+fn generate_transfer_proof(input_path: PathBuf, output_path: PathBuf) -> anyhow::Result<()> {
+    tracing::info!("Reading transfer proof inputs from: {}", input_path.display());
+
+    MissingFile::check("Input", &input_path)?;
+
+    let inputs: TransferInputs = read_input_file(&input_path)?;
+
+    tracing::info!("Creating Transfer circuit...");
+    let circuit = TransferCircuit::new(inputs)?;
+
+    tracing::info!("Computing circuit witness...");
     let outputs = circuit.compute_outputs();
 
-    println!("Circuit computation successful");
-    println!("  Coin: {:?}", outputs.coin);
-    println!("  Remaining Coin: {:?}", outputs.remaining_coin);
-    println!("  Commitment: {:?}", outputs.commitment);
+    tracing::info!("Circuit computation successful");
+    println!("  {outputs}");
 
     // Create output directory if it doesn't exist
     if let Some(parent) = output_path.parent() {
@@ -343,52 +1247,245 @@ fn generate_spend_proof(input_path: PathBuf, output_path: PathBuf) -> anyhow::Re
     std::fs::write(&output_path, output_data)
         .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
 
-    println!("Proof outputs saved to: {}", output_path.display());
-    println!("Note: This generates circuit outputs only. Full STWO proof generation requires additional implementation.");
+    tracing::info!("Proof outputs saved to: {}", output_path.display());
+    tracing::warn!("This generates circuit outputs only. Full STWO proof generation requires additional implementation.");
 
     Ok(())
 }
 
-fn verify_proof(proof_path: PathBuf, proof_type: String) -> anyhow::Result<()> {
-    println!("Verifying {} proof from: {}", proof_type, proof_path.display());
+/// Serve proofs over HTTP until the process is stopped
+#[cfg(feature = "server")]
+fn serve(listen: &str, token: Option<String>, workers: usize, log_n_rows: u32) -> anyhow::Result<()> {
+    use proof_of_burn_stwo::prover::PobProver;
+    use proof_of_burn_stwo::server::ProofServer;
+
+    let prover = PobProver::new(StarkConfig::default(), CircuitParams::default()).with_log_n_rows(log_n_rows);
+    let max_body_bytes = MAX_INPUT_BYTES.get().copied().unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+    let mut server = ProofServer::bind(listen, prover)
+        .with_context(|| format!("Failed to listen on {listen}"))?
+        .with_max_body_bytes(max_body_bytes);
+    match token {
+        Some(token) => server = server.with_token(token),
+        None => tracing::warn!("No --token set: any client reaching {listen} can use the prover"),
+    }
+    // Scripts read the bound address, port 0 included, from this line
+    println!("Listening on http://{}", server.local_addr()?);
+    server.run(workers).context("Server failed")
+}
 
-    // Validate proof file exists
-    if !proof_path.exists() {
-        anyhow::bail!("Proof file does not exist: {}", proof_path.display());
+/// Print an envelope's metadata; nothing about the proof is checked
+fn inspect_envelope(proof_path: &Path, json: bool) -> anyhow::Result<()> {
+    let bytes = std::fs::read(proof_path)
+        .with_context(|| format!("Failed to read proof file: {}", proof_path.display()))?;
+    let summary = EnvelopeSummary::from_bytes(&bytes)
+        .with_context(|| format!("{} is not a readable proof envelope", proof_path.display()))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        print!("{summary}");
     }
+    Ok(())
+}
+
+fn verify_proof(
+    proof_path: PathBuf,
+    proof_type: String,
+    statement_path: Option<PathBuf>,
+) -> anyhow::Result<ExitCode> {
+    tracing::info!("Verifying {} proof from: {}", proof_type, proof_path.display());
+
+    MissingFile::check("Proof", &proof_path)?;
 
     // Read and parse proof data
-    let proof_data = std::fs::read_to_string(&proof_path)
+    let proof_bytes = std::fs::read(&proof_path)
         .with_context(|| format!("Failed to read proof file: {}", proof_path.display()))?;
 
+    // Written by `--emit-envelope`, compressed or not
+    if proof_bytes.starts_with(&ENVELOPE_MAGIC) {
+        let envelope = ProofEnvelope::from_bytes_auto(&proof_bytes)
+            .with_context(|| "Failed to decode proof envelope")?;
+        envelope.pcs_config().with_context(|| "Failed to parse the envelope's STARK proof")?;
+        if let Err(e) = envelope.layout.check(envelope.commitments.len()) {
+            return Ok(invalid_proof(
+                anyhow::Error::new(e).context("Envelope's commitment layout does not match its commitments"),
+            ));
+        }
+
+        // Envelopes since v14 carry the claims to rebuild their components from; without them
+        // only the structure of an older envelope can be checked
+        let verified = envelope.claims.is_some();
+        if !verified && envelope.version >= CLAIMS_VERSION {
+            return Ok(invalid_proof(anyhow::anyhow!(
+                "v{} envelope carries no component claims",
+                envelope.version
+            )));
+        }
+        let status = if verified { "valid" } else { "unverified" };
+
+        println!("Proof envelope structure is {status}");
+        println!("  Version: {}", envelope.version);
+        println!("  Circuit: {:?}", envelope.circuit);
+        println!("  Merkle Hasher: {}", envelope.merkle_hasher);
+        println!("  Commitment Layout: {:?}", envelope.layout.roles());
+        println!("  Public Inputs: {:?}", envelope.public_inputs);
+        if let Some(statement) = envelope.statement {
+            println!("  Statement Hash: {}", statement.statement_hash());
+        }
+        if let Some(statement) = envelope.spend_statement {
+            println!("  Statement Hash: {}", statement.statement_hash());
+        }
+        if let Some(proof_id) = envelope.proof_id() {
+            println!("  Proof ID: {proof_id}");
+        }
+        if verified {
+            let report = envelope.verify_offline();
+            println!("Offline verification ({:.2} ms):", report.total_ms());
+            print!("{report}");
+            if let Some((check, reason)) = report.failed_check() {
+                return Ok(invalid_proof(anyhow::anyhow!("{check} check failed: {reason}")));
+            }
+        }
+        if !envelope.instances.is_empty() {
+            let expected: Option<BurnStatement> = statement_path.as_deref().map(read_input_file).transpose()?;
+            return Ok(verify_batch_structure(&envelope, expected.as_ref(), verified));
+        }
+        // The public inputs must be the ones the embedded statement derives
+        let consistent = match envelope.circuit {
+            CircuitKind::Burn if envelope.statement.is_some() => envelope.burn_statement().map(drop),
+            CircuitKind::Spend if envelope.spend_statement.is_some() => envelope.spend_statement().map(drop),
+            _ => Ok(()),
+        };
+        if let Err(e) = consistent {
+            return Ok(invalid_proof(e.into()));
+        }
+        if let Some(path) = statement_path.as_deref() {
+            // The statement file is read as the statement type of the envelope's circuit
+            let checked = match envelope.circuit {
+                CircuitKind::Burn => envelope.check_burn_statement(&read_input_file::<BurnStatement>(path)?),
+                CircuitKind::Spend => envelope.check_spend_statement(&read_input_file::<SpendStatement>(path)?),
+            };
+            match checked {
+                Ok(()) => println!("  Statement: matches the expected statement"),
+                Err(EnvelopeError::StatementMismatch { mismatches }) => {
+                    let mismatches: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+                    let message = format!("Proof is {status} but proves another statement");
+                    json_error(|| {
+                        ErrorReport::new(ErrorCode::StatementMismatch, &message)
+                            .with_details(&serde_json::json!({ "mismatches": mismatches }))
+                    });
+                    println!("{message}:");
+                    for mismatch in mismatches {
+                        println!("  {mismatch}");
+                    }
+                    return Ok(ExitCode::from(EXIT_STATEMENT_MISMATCH));
+                }
+                Err(err) => return Ok(invalid_proof(err.into())),
+            }
+        }
+        if !verified {
+            tracing::warn!("Envelope carries no component claims (before v14): this verifies its structure only.");
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    if statement_path.is_some() {
+        anyhow::bail!("--statement requires a proof envelope (generate-burn --emit-envelope)");
+    }
+    let proof_data = String::from_utf8(proof_bytes).with_context(|| "Proof file is not UTF-8 JSON")?;
+
     match proof_type.as_str() {
         "burn" => {
-            let outputs: proof_of_burn_stwo::circuits::proof_of_burn::ProofOfBurnOutputs =
-                serde_json::from_str(&proof_data)
-                    .with_context(|| "Failed to parse burn proof JSON")?;
+            // Written by `generate-burn --emit-public-values`, from `PobPublicValues::extract`
+            let public_values: PobPublicValues = serde_json::from_str(&proof_data)
+                .with_context(|| "Failed to parse burn public values JSON")?;
 
-            println!("Burn proof structure is valid");
-            println!("  Nullifier: {:?}", outputs.nullifier);
-            println!("  Commitment: {:?}", outputs.commitment);
+            println!("Burn public values are unverified");
+            println!("  {public_values}");
         }
         "spend" => {
             let outputs: proof_of_burn_stwo::circuits::spend::SpendOutputs =
                 serde_json::from_str(&proof_data)
                     .with_context(|| "Failed to parse spend proof JSON")?;
 
-            println!("Spend proof structure is valid");
-            println!("  Coin: {:?}", outputs.coin);
-            println!("  Remaining Coin: {:?}", outputs.remaining_coin);
-            println!("  Commitment: {:?}", outputs.commitment);
+            println!("Spend proof structure is unverified");
+            println!("  {outputs}");
+        }
+        "split" => {
+            let outputs: proof_of_burn_stwo::circuits::split::SplitOutputs =
+                serde_json::from_str(&proof_data)
+                    .with_context(|| "Failed to parse split proof JSON")?;
+
+            println!("Split proof structure is unverified");
+            println!("  {outputs}");
+        }
+        "transfer" => {
+            let outputs: proof_of_burn_stwo::circuits::transfer::TransferOutputs =
+                serde_json::from_str(&proof_data)
+                    .with_context(|| "Failed to parse transfer proof JSON")?;
+
+            println!("Transfer proof structure is unverified");
+            println!("  {outputs}");
         }
         _ => {
-            anyhow::bail!("Unsupported proof type: {}. Supported types: 'burn', 'spend'", proof_type);
+            anyhow::bail!(
+                "Unsupported proof type: {}. Supported types: 'burn', 'spend', 'split', 'transfer'",
+                proof_type
+            );
         }
     }
 
-    println!("Note: This verifies proof structure only. Full cryptographic verification requires STWO implementation.");
+    tracing::warn!("This verifies proof structure only. Full cryptographic verification requires STWO implementation.");
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Report why a readable proof is invalid, on stderr, and exit with `EXIT_INVALID_PROOF`
+fn invalid_proof(reason: anyhow::Error) -> ExitCode {
+    let message = format!("Proof is invalid: {reason:#}");
+    if !json_error(|| ErrorReport::new(ErrorCode::VerifyFail, &message)) {
+        eprintln!("{message}");
+    }
+    ExitCode::from(EXIT_INVALID_PROOF)
+}
+
+/// Report each instance of a batched burn envelope; see `verify_proof` for the exit status
+///
+/// The public inputs are checked against each statement; the proof itself only if `verified`
+/// offline already, which every envelope since v14 is. With `expected`, some instance must
+/// prove it.
+fn verify_batch_structure(envelope: &ProofEnvelope, expected: Option<&BurnStatement>, verified: bool) -> ExitCode {
+    println!("  Instances: {}", envelope.instances.len());
+    let mut valid = true;
+    for (index, statement) in envelope.instances.iter().enumerate() {
+        let (public_commitment, nullifier, commitment, security_level) = statement.to_solidity_tuple();
+        let recorded = envelope.public_inputs.get(4 * index..4 * (index + 1));
+        let status = if recorded == Some(&[public_commitment, nullifier, commitment, security_level][..]) {
+            "ok"
+        } else {
+            valid = false;
+            "public inputs do not match the statement"
+        };
+        println!("    [{index}] {} {status}", statement.statement_hash());
+    }
+    if !valid || envelope.public_inputs.len() != 4 * envelope.instances.len() {
+        return invalid_proof(anyhow::anyhow!("public inputs do not match the instance statements"));
+    }
+    if let Some(expected) = expected {
+        match envelope.instances.iter().position(|statement| statement.mismatches(expected).is_empty()) {
+            Some(index) => println!("  Statement: instance {index} matches the expected statement"),
+            None => {
+                let status = if verified { "valid" } else { "unverified" };
+                let message =
+                    format!("Proof is {status} but no instance proves the expected statement");
+                json_error(|| ErrorReport::new(ErrorCode::StatementMismatch, &message));
+                println!("{message}");
+                return ExitCode::from(EXIT_STATEMENT_MISMATCH);
+            }
+        }
+    }
+    if !verified {
+        tracing::warn!("Envelope carries no component claims (before v14): this verifies its structure only.");
+    }
+    ExitCode::SUCCESS
 }
 
 fn show_system_info() {
@@ -407,6 +1504,16 @@ fn show_system_info() {
     println!("  PoW Min Zero Bytes:       {}", POW_MINIMUM_ZERO_BYTES);
     println!();
 
+    println!("AIR Size (2^{} rows):", BURN_LOG_N_ROWS);
+    for (name, stats) in air_sizes() {
+        println!("  {}:", name);
+        println!("    Trace Columns:          {}", stats.n_trace_columns);
+        println!("    Interaction Columns:    {}", stats.n_interaction_columns);
+        println!("    Constraints:            {}", stats.n_constraints);
+        println!("    Log Degree Bound:       {}", stats.max_degree_bound);
+    }
+    println!();
+
     println!("Balance Limits:");
     println!("  Max Intended Balance:     {} wei ({:.2} ETH)",
              MAX_INTENDED_BALANCE,
@@ -454,42 +1561,68 @@ fn show_system_info() {
     println!("  Production Ready:         Requires full STWO proof generation");
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloy_primitives::U256;
-    use std::str::FromStr;
-
-    #[test]
-    fn test_proof_id_calculation_consistency() {
-        // Test values that match the JavaScript verification script
-        let block_hash = alloy_primitives::keccak256([0xabu8; 32]);
-        let nullifier = U256::from_str("0x1212121212121212121212121212121212121212121212121212121212121212").unwrap();
-        let commitment = U256::from_str("0x3434343434343434343434343434343434343434343434343434343434343434").unwrap();
-        let reveal_amount = U256::from(500000000000000000u64); // 0.5 ETH
-
-        // Calculate publicCommitment as per Commitments.sol
-        let mut packed_data = Vec::new();
-        packed_data.extend_from_slice(block_hash.as_slice());
-        packed_data.extend_from_slice(&nullifier.to_be_bytes::<32>());
-        packed_data.extend_from_slice(&commitment.to_be_bytes::<32>());
-        packed_data.extend_from_slice(&reveal_amount.to_be_bytes::<32>());
-        let public_commitment_bytes = alloy_primitives::keccak256(&packed_data);
-        let public_commitment = alloy_primitives::U256::from_be_bytes(public_commitment_bytes.into()) >> alloy_primitives::U256::from(8);
-
-        // Calculate proof_id as per STWOProofOfBurnVerifier.sol
-        let mut proof_id_data = Vec::new();
-        proof_id_data.extend_from_slice(&public_commitment.to_be_bytes::<32>());
-        proof_id_data.extend_from_slice(&nullifier.to_be_bytes::<32>());
-        proof_id_data.extend_from_slice(&commitment.to_be_bytes::<32>());
-        let proof_id = alloy_primitives::keccak256(&proof_id_data);
-
-        // Expected values from JavaScript verification (UPDATED with correct blockHash)
-        let expected_public_commitment = U256::from_str("0x7f3efa11a3601ff4488fca730751aefabbd29bb9651349c4658aa67a64c550").unwrap();
-        let expected_proof_id_bytes = hex::decode("af19dffbe9939dedd30df03d7100b38fe1ef8eccf4544889a2ca1fcd907beeac").unwrap();
-        let expected_proof_id = alloy_primitives::B256::from_slice(&expected_proof_id_bytes);
-
-        assert_eq!(public_commitment, expected_public_commitment, "publicCommitment calculation mismatch");
-        assert_eq!(B256::from(proof_id), expected_proof_id, "proof_id calculation mismatch");
+/// Size of each AIR at `BURN_LOG_N_ROWS` rows
+fn air_sizes() -> [(&'static str, CircuitStats); 5] {
+    [
+        ("Proof of Burn", pob_eval(BURN_LOG_N_ROWS).statistics()),
+        ("Spend", spend_eval(BURN_LOG_N_ROWS).statistics()),
+        ("Split", split_eval(BURN_LOG_N_ROWS).statistics()),
+        ("Merge", merge_eval(BURN_LOG_N_ROWS).statistics()),
+        ("Transfer", transfer_eval(BURN_LOG_N_ROWS).statistics()),
+    ]
+}
+
+/// `info --output-format json`
+#[derive(Serialize)]
+struct InfoReport {
+    circuit_params: CircuitParams,
+    /// `CircuitParams::params_hash`, as envelopes record it
+    circuit_params_hash: B256,
+    /// Log2 of the rows `air` is counted at
+    air_log_n_rows: u32,
+    air: std::collections::BTreeMap<&'static str, CircuitStats>,
+    /// With --analyze
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analysis: Option<ConfigReport>,
+}
+
+impl InfoReport {
+    fn new(analysis: Option<ConfigReport>) -> Self {
+        let circuit_params = CircuitParams::default();
+        Self {
+            circuit_params_hash: B256::from(circuit_params.params_hash()),
+            circuit_params,
+            air_log_n_rows: BURN_LOG_N_ROWS,
+            air: air_sizes().into_iter().collect(),
+            analysis,
+        }
     }
 }
+
+fn analyze_config(log_n_rows: u32, security: Option<u32>) -> anyhow::Result<ConfigReport> {
+    let config = match security {
+        Some(bits) => StarkConfig::for_trace(log_n_rows, bits),
+        None => StarkConfig::default(),
+    };
+    tracing::info!(log_n_rows, "Proving a dummy burn to measure the configuration");
+    config_report(log_n_rows, config).context("Configuration analysis failed")
+}
+
+fn show_config_report(report: &ConfigReport) {
+    println!();
+    println!("Configuration Analysis (2^{} rows):", report.log_n_rows);
+    println!("  Security Bits:            {}", report.security_bits);
+    println!("  FRI Queries:              {}", report.n_queries);
+    println!("  Log Blowup Factor:        {}", report.log_blowup_factor);
+    println!("  PoW Bits:                 {}", report.pow_bits);
+    println!("  Proof Size:               {} bytes", report.proof_size_bytes);
+    println!("  Merkle Decommitments:     {}", report.merkle_decommitments);
+    println!("  Witness Hashes:           {}", report.witness_hashes);
+    println!("  Queried Field Elements:   {}", report.queried_field_elements);
+    println!("  Hash Operations (approx): {}", report.hash_operations);
+    println!(
+        "  Coefficient Memory:       {} bytes ({})",
+        report.coefficient_bytes,
+        if report.store_poly_coeffs { "stored" } else { "not stored" }
+    );
+}
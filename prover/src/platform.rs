@@ -0,0 +1,106 @@
+// Entropy and clocks that work natively and in the browser
+// wasm32-unknown-unknown has neither in std: `Instant::now` panics there, and `getrandom` only
+// reaches `crypto.getRandomValues` with its `js` feature, which the `wasm` feature turns on.
+// Everything in the crate that needs randomness or wall-clock time goes through this module, so
+// the two targets differ only here.
+
+/// Eight bytes from the operating system's (or the browser's) secure random source
+///
+/// Panics if no entropy source is available, which `getrandom` only reports on platforms that
+/// cannot produce a burn key safely anyway.
+pub fn secure_random_u64() -> u64 {
+    u64::from_le_bytes(secure_random_bytes())
+}
+
+/// `N` bytes from the same source as `secure_random_u64`, which panics as it does
+pub fn secure_random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).expect("no secure random source available");
+    bytes
+}
+
+/// Milliseconds on a monotonic clock with an arbitrary origin
+#[cfg(not(all(target_arch = "wasm32", feature = "wasm")))]
+fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    ORIGIN.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+/// Milliseconds since the page or worker started; zero where no `Performance` is reachable
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+fn now_ms() -> f64 {
+    use wasm_bindgen::JsCast;
+
+    let global = js_sys::global();
+    let performance = match global.dyn_ref::<web_sys::Window>() {
+        Some(window) => window.performance(),
+        None => global.dyn_ref::<web_sys::WorkerGlobalScope>().and_then(|worker| worker.performance()),
+    };
+    performance.map_or(0.0, |performance| performance.now())
+}
+
+/// Times consecutive phases of an operation, in milliseconds
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    start: f64,
+    lap_start: f64,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        let now = now_ms();
+        Self { start: now, lap_start: now }
+    }
+
+    /// Milliseconds since the previous lap, or since the start for the first
+    pub fn lap(&mut self) -> f64 {
+        let now = now_ms();
+        let elapsed = now - self.lap_start;
+        self.lap_start = now;
+        elapsed
+    }
+
+    /// Milliseconds since the start
+    pub fn total(&self) -> f64 {
+        now_ms() - self.start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stopwatch_measures_a_sleep() {
+        let stopwatch = Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(50));
+        let total = stopwatch.total();
+        // A sleep lasts at least as long as asked; a loaded machine may add to it
+        assert!((50.0..1000.0).contains(&total), "{total} ms");
+    }
+
+    #[test]
+    fn test_laps_add_up_to_the_total() {
+        let mut stopwatch = Stopwatch::start();
+        let laps: Vec<f64> = (0..3)
+            .map(|_| {
+                std::thread::sleep(Duration::from_millis(2));
+                stopwatch.lap()
+            })
+            .collect();
+        let total = stopwatch.total();
+        assert!(laps.iter().all(|&lap| lap >= 2.0), "{laps:?}");
+        // The laps telescope to the last lap's end; allow for float rounding
+        assert!(total + 1e-6 >= laps.iter().sum::<f64>(), "{total} < {laps:?}");
+    }
+
+    #[test]
+    fn test_secure_random_u64_varies() {
+        let draws: std::collections::HashSet<u64> = (0..8).map(|_| secure_random_u64()).collect();
+        assert!(draws.len() > 1);
+    }
+}
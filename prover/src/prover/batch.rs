@@ -0,0 +1,219 @@
+// Batch verification
+// Indexers receive proofs in bulk; `verify_many` checks a slice of them on the rayon thread pool
+// (sequentially without the `rayon` feature) and reports every outcome in input order.
+//
+// Verification has no per-configuration precomputation to share between proofs: stwo's verifier
+// needs no twiddles, and the components cannot be rebuilt from `log_n_rows` and the config
+// because envelopes do not carry their claimed sums. Each proof therefore comes with the
+// components it was made with, as for `PobProver::verify_envelope`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::prover::service::{verify_with_components, ProvedComponents, ServiceError, ServiceProof};
+use crate::prover::{InstanceResult, ProofEnvelope, ProvingTimings};
+
+/// One proof of a batch: its envelope and the components it was made with
+#[derive(Clone, Copy)]
+pub struct BatchItem<'a> {
+    pub envelope: &'a ProofEnvelope,
+    pub components: &'a ProvedComponents,
+}
+
+impl<'a> From<&'a ServiceProof> for BatchItem<'a> {
+    fn from(proof: &'a ServiceProof) -> Self {
+        Self { envelope: &proof.envelope, components: &proof.components }
+    }
+}
+
+/// Outcomes of a batch, in the order the proofs were given
+#[derive(Debug)]
+pub struct BatchReport {
+    /// `None` for a proof `fail_fast` skipped
+    pub results: Vec<Option<Result<(), ServiceError>>>,
+    /// Proving time of the batch, summed over the timings recorded on each item's components
+    pub proving_timings: ProvingTimings,
+}
+
+impl BatchReport {
+    /// Proofs that verified
+    pub fn verified(&self) -> usize {
+        self.results.iter().filter(|result| matches!(result, Some(Ok(())))).count()
+    }
+
+    /// Proofs that were rejected
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|result| matches!(result, Some(Err(_)))).count()
+    }
+
+    /// Proofs skipped after a failure
+    pub fn skipped(&self) -> usize {
+        self.results.iter().filter(|result| result.is_none()).count()
+    }
+
+    /// Index of the first rejected proof
+    pub fn first_failure(&self) -> Option<usize> {
+        self.results.iter().position(|result| matches!(result, Some(Err(_))))
+    }
+
+    /// Whether every proof was checked and verified
+    pub fn all_verified(&self) -> bool {
+        self.verified() == self.results.len()
+    }
+}
+
+/// Verify `items`, in parallel with the `rayon` feature
+///
+/// With `fail_fast`, proofs not yet started when one is rejected are skipped. Which ones that is
+/// depends on scheduling, so compare skipped entries only against sequential runs without it.
+/// A batched burn envelope is rejected with the error of its first failing instance.
+pub fn verify_many(items: &[BatchItem<'_>], fail_fast: bool) -> BatchReport {
+    let rejected = AtomicBool::new(false);
+    let verify = |item: &BatchItem<'_>| {
+        if fail_fast && rejected.load(Ordering::Relaxed) {
+            return None;
+        }
+        let result = verify_with_components(item.envelope, item.components)
+            .and_then(|instances| Ok(InstanceResult::all_passed(instances)?));
+        if result.is_err() {
+            rejected.store(true, Ordering::Relaxed);
+        }
+        Some(result)
+    };
+
+    #[cfg(feature = "rayon")]
+    let results = items.par_iter().map(verify).collect();
+    #[cfg(not(feature = "rayon"))]
+    let results = items.iter().map(verify).collect();
+    let proving_timings = items.iter().map(|item| *item.components.timings()).sum();
+    BatchReport { results, proving_timings }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Bytes, U256};
+
+    use super::*;
+    use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+    use crate::circuits::spend::SpendInputs;
+    use crate::constants::{CircuitParams, MAINNET_CHAIN_ID};
+    use crate::field::M31;
+    use crate::prover::{CircuitKind, EnvelopeError, PobProver, StarkConfig};
+    use crate::secret::Secret;
+
+    fn burn_inputs(burn_key: u32) -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    fn spend_inputs(burn_key: u32) -> SpendInputs {
+        SpendInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        }
+    }
+
+    #[test]
+    fn test_outcomes_are_reported_per_index() {
+        let prover = PobProver::new(StarkConfig::default(), CircuitParams::default()).with_log_n_rows(5);
+        let burn = prover.prove_burn(&burn_inputs(1)).unwrap();
+        let spend = prover.prove_spend(&spend_inputs(2)).unwrap();
+        let other_spend = prover.prove_spend(&spend_inputs(3)).unwrap();
+
+        let mut wrong_inputs = burn.envelope.clone();
+        wrong_inputs.public_inputs[1] += U256::from(1);
+        let mut truncated = burn.envelope.clone();
+        truncated.proof = Bytes::copy_from_slice(&burn.envelope.proof[..burn.envelope.proof.len() / 2]);
+        let mut swapped_proof = spend.envelope.clone();
+        swapped_proof.proof = other_spend.envelope.proof.clone();
+
+        let items = [
+            BatchItem::from(&burn),
+            BatchItem { envelope: &wrong_inputs, components: &burn.components },
+            BatchItem::from(&spend),
+            BatchItem { envelope: &spend.envelope, components: &burn.components },
+            BatchItem { envelope: &truncated, components: &burn.components },
+            BatchItem { envelope: &swapped_proof, components: &spend.components },
+            BatchItem::from(&other_spend),
+        ];
+        let report = verify_many(&items, false);
+
+        let results = &report.results;
+        assert!(matches!(results[0], Some(Ok(()))));
+        assert!(matches!(results[1], Some(Err(ServiceError::Envelope(EnvelopeError::PublicInputsMismatch)))));
+        assert!(matches!(results[2], Some(Ok(()))));
+        assert!(matches!(
+            results[3],
+            Some(Err(ServiceError::CircuitMismatch { circuit: CircuitKind::Spend }))
+        ));
+        assert!(matches!(results[4], Some(Err(ServiceError::Envelope(EnvelopeError::Serialization(_))))));
+        assert!(matches!(results[5], Some(Err(ServiceError::Verification(_)))));
+        assert!(matches!(results[6], Some(Ok(()))));
+        assert_eq!((report.verified(), report.failed(), report.skipped()), (3, 4, 0));
+        assert_eq!(report.first_failure(), Some(1));
+        assert!(!report.all_verified());
+
+        // Same outcomes as verifying one at a time
+        for (item, result) in items.iter().zip(results) {
+            let sequential = verify_with_components(item.envelope, item.components);
+            let outcome = result.as_ref().unwrap();
+            assert_eq!(outcome.is_ok(), sequential.is_ok());
+            assert_eq!(
+                outcome.as_ref().err().map(ToString::to_string),
+                sequential.err().map(|e| e.to_string())
+            );
+        }
+
+        // Fail-fast only skips; whatever it checks agrees with the full run
+        let fast = verify_many(&items, true);
+        assert!(fast.failed() >= 1);
+        assert_eq!(fast.verified() + fast.failed() + fast.skipped(), items.len());
+        for (fast, full) in fast.results.iter().zip(results) {
+            if let Some(fast) = fast {
+                assert_eq!(fast.is_ok(), full.as_ref().unwrap().is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_batch() {
+        let report = verify_many(&[], true);
+        assert!(report.all_verified());
+        assert_eq!(report.first_failure(), None);
+        assert_eq!(report.proving_timings, ProvingTimings::default());
+    }
+
+    #[test]
+    fn test_report_sums_proving_timings() {
+        let prover = PobProver::new(StarkConfig::default(), CircuitParams::default()).with_log_n_rows(5);
+        let burn = prover.prove_burn(&burn_inputs(1)).unwrap();
+        let spend = prover.prove_spend(&spend_inputs(2)).unwrap();
+
+        let report = verify_many(&[BatchItem::from(&burn), BatchItem::from(&spend)], false);
+        let (burn, spend) = (burn.components.timings(), spend.components.timings());
+        assert_eq!(report.proving_timings.total_ms, burn.total_ms + spend.total_ms);
+        assert_eq!(report.proving_timings.proof_ms, burn.proof_ms + spend.proof_ms);
+        assert!(report.proving_timings.total_ms > 0.0);
+    }
+}
@@ -0,0 +1,309 @@
+// Layout of batched Proof of Burn traces
+// A single proof fills the first SIMD vector of the PoB trace (rows 0..N_LANES) with its witness
+// row. A batch stacks instances one vector apart: instance i fills rows i * N_LANES..(i + 1) *
+// N_LANES and adds its five permutations to the shared Poseidon2 table. Every instance proves
+// against the same block header, so the Keccak component hashes it once and does not grow with
+// the batch; the range-check table has a fixed size.
+//
+// The batch size is therefore bounded by the PoB rows and the table entries an instance costs,
+// against the largest trace the provers accept. `BatchLayout` checks that bound up front and maps
+// instances to their rows, so a caller can find an instance's public values in the trace.
+
+use std::ops::Range;
+
+use itertools::Itertools;
+use stwo_prover::core::circle::M31_CIRCLE_LOG_ORDER;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::prover::backend::simd::m31::N_LANES;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{Col, Column};
+use stwo_prover::prover::poly::circle::CircleEvaluation;
+
+use crate::circuits::poseidon2_table_air::{
+    generate_poseidon2_table_trace, generate_poseidon2_table_trace_cached, poseidon2_table_log_size,
+    Poseidon2TableLookupData,
+};
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::proof_of_burn_air::{LookupData, PobWitness, NUM_POB_COLUMNS};
+use crate::platform::Stopwatch;
+use crate::prover::timings::ProvingTimings;
+use crate::prover::{PobPublicValues, ReplayScope, StarkConfig, TraceColumns, LOG_EXPAND, MAX_LOG_SIZE, MIN_LOG_SIZE};
+use crate::utils::poseidon2_stwo::PermutationCache;
+
+/// PoB trace rows an instance fills: one SIMD vector
+pub const ROWS_PER_INSTANCE: usize = N_LANES;
+
+/// Poseidon2 table entries an instance adds: nullifier, remaining coin, commitment, block root
+/// digest and burn address
+pub const TABLE_ENTRIES_PER_INSTANCE: usize = 5;
+
+/// Largest trace log size `config` can prove
+///
+/// The twiddles span the largest component's evaluation domain, `LOG_EXPAND` and the blowup
+/// above its trace, and a canonic coset must fit in the circle group.
+fn max_log_size(config: &StarkConfig) -> u32 {
+    let domain_headroom = LOG_EXPAND + config.fri_config.log_blowup_factor;
+    MAX_LOG_SIZE.min((M31_CIRCLE_LOG_ORDER - 1).saturating_sub(domain_headroom))
+}
+
+/// Largest number of instances one batched proof under `config` holds
+pub fn max_batch_size(config: &StarkConfig) -> usize {
+    let rows = 1usize << max_log_size(config);
+    (rows / ROWS_PER_INSTANCE).min(rows / TABLE_ENTRIES_PER_INSTANCE)
+}
+
+/// Where each instance of a batch sits in the PoB trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchLayout {
+    instances: usize,
+    log_n_rows: u32,
+}
+
+impl BatchLayout {
+    /// Layout of `instances` instances, in the smallest trace that holds them
+    pub fn new(instances: usize, config: &StarkConfig) -> Result<Self, BatchError> {
+        if instances == 0 {
+            return Err(BatchError::Empty);
+        }
+        let max = max_batch_size(config);
+        if instances > max {
+            return Err(BatchError::BatchTooLarge { given: instances, max });
+        }
+        let log_n_rows = (instances * ROWS_PER_INSTANCE).next_power_of_two().ilog2().max(MIN_LOG_SIZE);
+        Ok(Self { instances, log_n_rows })
+    }
+
+    pub fn instances(&self) -> usize {
+        self.instances
+    }
+
+    /// Log2 of the PoB trace rows
+    pub fn log_n_rows(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    /// Trace rows `instance` fills, `None` past the last instance
+    pub fn rows(&self, instance: usize) -> Option<Range<usize>> {
+        (instance < self.instances)
+            .then(|| instance * ROWS_PER_INSTANCE..(instance + 1) * ROWS_PER_INSTANCE)
+    }
+
+    /// Instance filling trace row `row`, `None` on padding rows
+    pub fn instance_at(&self, row: usize) -> Option<usize> {
+        let instance = row / ROWS_PER_INSTANCE;
+        (instance < self.instances).then_some(instance)
+    }
+
+    /// Public values of `instance`, read from its first row of a batched PoB trace
+    pub fn public_values(&self, trace: &TraceColumns, instance: usize) -> Option<PobPublicValues> {
+        self.rows(instance).map(|rows| PobPublicValues::from_trace_row(trace, rows.start))
+    }
+}
+
+/// Generate the PoB trace of a batch and its lookup data
+///
+/// The lookup data lists every instance's table entries, in instance order.
+pub fn generate_pob_batch_trace(
+    inputs: &[ProofOfBurnInputs],
+    config: &StarkConfig,
+) -> Result<(BatchLayout, TraceColumns, LookupData), BatchError> {
+    let layout = BatchLayout::new(inputs.len(), config)?;
+    if let Some(index) = inputs.iter().position(|instance| instance.block_header != inputs[0].block_header) {
+        return Err(BatchError::MixedBlocks { index });
+    }
+    let scope = ReplayScope::of(&inputs[0]);
+    if let Some(index) = inputs.iter().position(|instance| ReplayScope::of(instance) != scope) {
+        return Err(BatchError::MixedReplayScopes { index });
+    }
+    let witnesses: Vec<PobWitness> = inputs
+        .iter()
+        .enumerate()
+        .map(|(index, instance)| PobWitness::new(instance).map_err(|reason| BatchError::Witness { index, reason }))
+        .try_collect()?;
+
+    let domain = CanonicCoset::new(layout.log_n_rows).circle_domain();
+    let trace = (0..NUM_POB_COLUMNS)
+        .map(|column| {
+            let mut values = Col::<SimdBackend, BaseField>::zeros(1 << layout.log_n_rows);
+            // Vector i holds rows i * N_LANES..(i + 1) * N_LANES, instance i's rows
+            for (vec_row, witness) in witnesses.iter().enumerate() {
+                values.data[vec_row] = witness.value(column).into();
+            }
+            CircleEvaluation::new(domain, values)
+        })
+        .collect_vec();
+    let table_entries = witnesses.iter().flat_map(|witness| witness.table_entries()).cloned().collect_vec();
+    let lookup_data = LookupData::from_trace(&trace, &table_entries);
+    Ok((layout, trace, lookup_data))
+}
+
+/// Generate the Poseidon2 table trace answering a batch's lookups
+///
+/// With `cache_permutations`, each distinct input state is traced once, through a
+/// `PermutationCache`: every instance of a batch permutes the same block root, and instances
+/// sharing a burn key share their nullifier. The trace is the same either way. The returned
+/// timings hold the generation time and, when cached, the cache's hits and misses.
+pub fn generate_batch_table_trace(
+    lookup_data: &LookupData,
+    cache_permutations: bool,
+) -> Result<(TraceColumns, Poseidon2TableLookupData, ProvingTimings), BatchError> {
+    let stopwatch = Stopwatch::start();
+    let entries = &lookup_data.table_entries;
+    let log_size = poseidon2_table_log_size(entries.len());
+    let mut timings = ProvingTimings::default();
+    let (trace, table_lookup_data) = if cache_permutations {
+        let mut cache = PermutationCache::default();
+        let generated = generate_poseidon2_table_trace_cached(log_size, entries, &mut cache);
+        timings.permutation_cache = cache.stats();
+        generated
+    } else {
+        generate_poseidon2_table_trace(log_size, entries)
+    }
+    .map_err(BatchError::Table)?;
+    timings.trace_gen_ms = stopwatch.total();
+    timings.total_ms = timings.trace_gen_ms;
+    Ok((trace, table_lookup_data, timings))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BatchError {
+    #[error("A batch needs at least one instance")]
+    Empty,
+
+    #[error("Batch of {given} instances exceeds the maximum of {max}")]
+    BatchTooLarge { given: usize, max: usize },
+
+    #[error("Instance {index} proves against another block header than instance 0")]
+    MixedBlocks { index: usize },
+
+    #[error("Instance {index} is bound to another chain id or expiry than instance 0")]
+    MixedReplayScopes { index: usize },
+
+    #[error("Instance {index}: {reason}")]
+    Witness { index: usize, reason: String },
+
+    #[error("Poseidon2 table: {0}")]
+    Table(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::circuits::proof_of_burn_air::{
+        BURN_ADDRESS_PREFIX, COIN_PREFIX, NULLIFIER_PREFIX,
+    };
+    use crate::circuits::poseidon2_table_air::poseidon2_table_log_size;
+    use crate::circuits::layout::POB_LAYOUT;
+    use crate::coins;
+    use crate::field::M31;
+    use crate::secret::Secret;
+    use alloy_primitives::U256;
+
+    fn inputs(burn_key: u32) -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(250000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    #[test]
+    fn test_max_batch_size_is_inclusive() {
+        let config = StarkConfig::default();
+        let max = max_batch_size(&config);
+        assert_eq!(max, (1 << MAX_LOG_SIZE) / ROWS_PER_INSTANCE);
+
+        let layout = BatchLayout::new(max, &config).unwrap();
+        assert_eq!(layout.log_n_rows(), MAX_LOG_SIZE);
+        assert!(poseidon2_table_log_size(max * TABLE_ENTRIES_PER_INSTANCE) <= MAX_LOG_SIZE);
+
+        assert_eq!(BatchLayout::new(max + 1, &config), Err(BatchError::BatchTooLarge { given: max + 1, max }));
+        assert_eq!(BatchLayout::new(0, &config), Err(BatchError::Empty));
+        // A single instance takes the smallest trace, as a single proof does
+        assert_eq!(BatchLayout::new(1, &config).unwrap().log_n_rows(), MIN_LOG_SIZE);
+    }
+
+    #[test]
+    fn test_layout_locates_each_instance() {
+        let keys = [12345, 7, 99];
+        let batch: Vec<ProofOfBurnInputs> = keys.into_iter().map(inputs).collect();
+        let (layout, trace, lookup_data) = generate_pob_batch_trace(&batch, &StarkConfig::default()).unwrap();
+
+        assert_eq!(layout.log_n_rows(), 6);
+        assert_eq!(lookup_data.table_entries.len(), keys.len() * TABLE_ENTRIES_PER_INSTANCE);
+        for (instance, key) in keys.into_iter().enumerate() {
+            let rows = layout.rows(instance).unwrap();
+            assert_eq!(layout.instance_at(rows.end - 1), Some(instance));
+            let public_values = layout.public_values(&trace, instance).unwrap();
+            assert_eq!(public_values.nullifier, coins::nullifier(M31::from(key)));
+            // Every row of the instance carries the same witness
+            for row in rows {
+                assert_eq!(PobPublicValues::from_trace_row(&trace, row), public_values);
+            }
+        }
+
+        let padding = keys.len() * ROWS_PER_INSTANCE;
+        assert_eq!(layout.instance_at(padding), None);
+        assert_eq!(layout.rows(keys.len()), None);
+        assert_eq!(trace[POB_LAYOUT.enabled().start].values.at(padding), BaseField::from(0));
+        // The prefixes pin each instance's preimages, as for a single proof
+        let row = layout.rows(2).unwrap().start;
+        assert_eq!(trace[POB_LAYOUT.nullifier_initial().start].values.at(row), NULLIFIER_PREFIX);
+        assert_eq!(trace[POB_LAYOUT.remaining_coin_initial().start].values.at(row), COIN_PREFIX);
+        assert_eq!(trace[POB_LAYOUT.burn_address_initial().start].values.at(row), BURN_ADDRESS_PREFIX);
+    }
+
+    #[test]
+    fn test_cached_table_trace_matches_uncached() {
+        // Repeated instances repeat every permutation, and all instances share the block root
+        let batch: Vec<ProofOfBurnInputs> = [1, 2, 1, 2, 3].into_iter().map(inputs).collect();
+        let (_, _, lookup_data) = generate_pob_batch_trace(&batch, &StarkConfig::default()).unwrap();
+
+        let (uncached, uncached_lookup, timings) = generate_batch_table_trace(&lookup_data, false).unwrap();
+        assert_eq!(timings.permutation_cache.hit_rate(), None);
+        let (cached, cached_lookup, timings) = generate_batch_table_trace(&lookup_data, true).unwrap();
+
+        assert_eq!(cached.len(), uncached.len());
+        for (cached, uncached) in cached.iter().zip(&uncached) {
+            assert_eq!(cached.values.to_cpu(), uncached.values.to_cpu());
+        }
+        assert_eq!(cached_lookup.multiplicities[0].to_cpu(), uncached_lookup.multiplicities[0].to_cpu());
+        // Instances 2 and 3 repeat all five permutations of 0 and 1; 1 and 4 repeat the block root
+        let stats = timings.permutation_cache;
+        assert_eq!(stats.hits + stats.misses, lookup_data.table_entries.len() as u64);
+        assert_eq!(stats.hits, 2 * TABLE_ENTRIES_PER_INSTANCE as u64 + 2, "{stats:?}");
+    }
+
+    #[test]
+    fn test_instances_share_one_block() {
+        let mut batch = vec![inputs(1), inputs(2)];
+        batch[1].block_header[0] = 1;
+        assert_eq!(
+            generate_pob_batch_trace(&batch, &StarkConfig::default()).unwrap_err(),
+            BatchError::MixedBlocks { index: 1 }
+        );
+    }
+
+    #[test]
+    fn test_instances_share_one_replay_scope() {
+        let mut batch = vec![inputs(1), inputs(2), inputs(3)];
+        batch[2].valid_until_block = Some(100);
+        assert_eq!(
+            generate_pob_batch_trace(&batch, &StarkConfig::default()).unwrap_err(),
+            BatchError::MixedReplayScopes { index: 2 }
+        );
+    }
+}
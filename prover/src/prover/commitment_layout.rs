@@ -0,0 +1,224 @@
+// Commitment layout of a STARK proof
+// `CommitmentSchemeProver` appends one root to `StarkProof::commitments` per commit, in call
+// order, and stwo itself only reads the last one (the composition tree). Provers record the role
+// of each root as they commit it and check the count against the finished proof; verifiers and
+// envelope readers look roots up through the recorded layout instead of by position, so a tree
+// added or moved on the prover side cannot silently bind a root to the wrong tree.
+//
+// The verifier contract has no layout to read: it takes roots from fixed calldata slots,
+// `CONTRACT_TREE_ORDER`. tests/commitment_ordering.rs swaps the labeled roots of real proofs and
+// checks verification fails, which pins each slot to the tree stwo actually checks there.
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "test-utils")]
+use stwo_prover::core::proof::StarkProof;
+#[cfg(feature = "test-utils")]
+use stwo_prover::core::vcs::blake2_hash::Blake2sHash;
+#[cfg(feature = "test-utils")]
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+
+/// What a committed tree holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TreeRole {
+    /// Preprocessed columns, which the verifier regenerates
+    Preprocessed,
+    /// Main trace of every component
+    Trace,
+    /// Logup interaction trace of every component
+    Interaction,
+    /// Composition polynomial, committed by `prove` itself
+    Composition,
+    /// A tree committed outside the STARK transcript, skipped by the verifiers
+    Auxiliary,
+}
+
+/// Trees in the order the verifier contract reads their roots from calldata
+///
+/// The order stwo commits them in. A proof without an interaction tree leaves its slot out and
+/// the composition root moves up; auxiliary trees have no slot.
+pub const CONTRACT_TREE_ORDER: [TreeRole; 4] =
+    [TreeRole::Preprocessed, TreeRole::Trace, TreeRole::Interaction, TreeRole::Composition];
+
+impl TreeRole {
+    /// Rank of the tree in `CONTRACT_TREE_ORDER`; `None` for auxiliary trees
+    pub fn contract_slot(&self) -> Option<usize> {
+        CONTRACT_TREE_ORDER.iter().position(|role| role == self)
+    }
+
+    /// Index of the tree in the components' `trace_log_degree_bounds()`, for the trees the
+    /// verifier replays before calling `verify`
+    pub fn trace_tree_index(&self) -> Option<usize> {
+        match self {
+            TreeRole::Preprocessed => Some(0),
+            TreeRole::Trace => Some(1),
+            TreeRole::Interaction => Some(2),
+            TreeRole::Composition | TreeRole::Auxiliary => None,
+        }
+    }
+}
+
+/// Role of every root in `StarkProof::commitments`, in commitment order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct CommitmentLayout {
+    roles: Vec<TreeRole>,
+}
+
+impl CommitmentLayout {
+    /// Preprocessed, main and interaction trace (only when the AIR uses lookups), then composition
+    ///
+    /// What every prover in this crate commits; envelopes written before the layout was recorded
+    /// have it with the interaction tree.
+    pub fn standard(has_interaction: bool) -> Self {
+        let mut layout = Self::default();
+        layout.push(TreeRole::Preprocessed);
+        layout.push(TreeRole::Trace);
+        if has_interaction {
+            layout.push(TreeRole::Interaction);
+        }
+        layout.push(TreeRole::Composition);
+        layout
+    }
+
+    /// Record the next committed tree
+    pub(crate) fn push(&mut self, role: TreeRole) {
+        self.roles.push(role);
+    }
+
+    pub fn roles(&self) -> &[TreeRole] {
+        &self.roles
+    }
+
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    /// Position of the first tree with `role` in the proof's commitments
+    pub fn position(&self, role: TreeRole) -> Option<usize> {
+        self.roles.iter().position(|&r| r == role)
+    }
+
+    /// Trees the verifier replays before calling `verify`, as (position in the proof's
+    /// commitments, index in `trace_log_degree_bounds()`), in commitment order
+    pub fn trace_trees(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.roles
+            .iter()
+            .enumerate()
+            .filter_map(|(position, role)| Some((position, role.trace_tree_index()?)))
+    }
+
+    /// Check the layout describes a proof with `n_commitments` roots that stwo can verify
+    ///
+    /// Preprocessed, main trace and composition trees must appear once and the interaction tree
+    /// at most once; the trace trees must be committed in stwo's tree order and the composition
+    /// tree last, where stwo reads it.
+    pub fn check(&self, n_commitments: usize) -> Result<(), LayoutError> {
+        if self.roles.len() != n_commitments {
+            return Err(LayoutError::Count { layout: self.roles.len(), proof: n_commitments });
+        }
+        for (role, allowed) in [
+            (TreeRole::Preprocessed, 1..=1),
+            (TreeRole::Trace, 1..=1),
+            (TreeRole::Interaction, 0..=1),
+            (TreeRole::Composition, 1..=1),
+        ] {
+            let count = self.roles.iter().filter(|&&r| r == role).count();
+            if !allowed.contains(&count) {
+                return Err(LayoutError::RoleCount { role, count });
+            }
+        }
+        if self.roles.last() != Some(&TreeRole::Composition) {
+            return Err(LayoutError::CompositionNotLast);
+        }
+        let indices: Vec<usize> = self.trace_trees().map(|(_, index)| index).collect();
+        if indices.iter().enumerate().any(|(i, &index)| i != index) {
+            return Err(LayoutError::OutOfOrder);
+        }
+        Ok(())
+    }
+
+    /// Insert `root` as an auxiliary tree at `position` of both `proof` and this layout
+    ///
+    /// For tests only: every later root moves, and verifiers must follow it through the layout.
+    #[cfg(feature = "test-utils")]
+    pub fn insert_auxiliary_tree(
+        &mut self,
+        proof: &mut StarkProof<Blake2sMerkleHasher>,
+        position: usize,
+        root: Blake2sHash,
+    ) {
+        proof.0.commitments.insert(position, root);
+        self.roles.insert(position, TreeRole::Auxiliary);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LayoutError {
+    #[error("Commitment layout records {layout} trees, proof commits {proof}")]
+    Count { layout: usize, proof: usize },
+
+    #[error("Commitment layout has {count} {role:?} trees")]
+    RoleCount { role: TreeRole, count: usize },
+
+    #[error("Commitment layout does not end with the composition tree")]
+    CompositionNotLast,
+
+    #[error("Commitment layout commits the trace trees out of order")]
+    OutOfOrder,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_layout() {
+        let layout = CommitmentLayout::standard(true);
+        assert_eq!(layout.check(4), Ok(()));
+        assert_eq!(layout.trace_trees().collect::<Vec<_>>(), [(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(layout.position(TreeRole::Composition), Some(3));
+        assert_eq!(layout.check(3), Err(LayoutError::Count { layout: 4, proof: 3 }));
+
+        let without_interaction = CommitmentLayout::standard(false);
+        assert_eq!(without_interaction.check(3), Ok(()));
+        assert_eq!(without_interaction.position(TreeRole::Interaction), None);
+
+        // What the provers commit is what the contract reads
+        assert_eq!(layout.roles(), CONTRACT_TREE_ORDER);
+        assert_eq!(TreeRole::Auxiliary.contract_slot(), None);
+    }
+
+    #[test]
+    fn test_check_rejects_unverifiable_layouts() {
+        use TreeRole::*;
+        let layout = |roles: &[TreeRole]| CommitmentLayout { roles: roles.to_vec() };
+
+        assert_eq!(layout(&[Preprocessed, Auxiliary, Trace, Composition]).check(4), Ok(()));
+        assert_eq!(
+            layout(&[Preprocessed, Trace, Trace, Composition]).check(4),
+            Err(LayoutError::RoleCount { role: Trace, count: 2 })
+        );
+        assert_eq!(
+            layout(&[Preprocessed, Trace, Composition, Interaction]).check(4),
+            Err(LayoutError::CompositionNotLast)
+        );
+        assert_eq!(
+            layout(&[Trace, Preprocessed, Interaction, Composition]).check(4),
+            Err(LayoutError::OutOfOrder)
+        );
+    }
+
+    #[test]
+    fn test_json_is_a_list_of_roles() {
+        let json = serde_json::to_string(&CommitmentLayout::standard(true)).unwrap();
+        assert_eq!(json, r#"["preprocessed","trace","interaction","composition"]"#);
+        assert_eq!(serde_json::from_str::<CommitmentLayout>(&json).unwrap(), CommitmentLayout::standard(true));
+    }
+}
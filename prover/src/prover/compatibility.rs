@@ -0,0 +1,155 @@
+// Envelope and component compatibility, checked before verification
+// stwo reports a proof whose trees are not shaped as the verifying components expect as a generic
+// failure deep inside `verify`: a Merkle or OODS mismatch, or a panic on a sample count. Envelopes
+// do not carry the components (their lookup elements and claimed sums come from the prover), but
+// the components' `trace_log_degree_bounds()` say what the proof must look like. This compares
+// them against what the envelope records and its proof commits, and names the first tree that
+// differs, so the mismatch is reported as such before `verify` runs.
+//
+// Before any of that, the envelope's parameter fingerprints must be this build's: a proof made
+// with other Poseidon2 constants or circuit limits fails confusingly in `verify`, or not at all
+// where a constraint does not cover the difference.
+
+use alloy_primitives::B256;
+use stwo_prover::core::pcs::PcsConfig;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+
+use crate::constants::CircuitParams;
+use crate::prover::commitment_layout::TreeRole;
+use crate::prover::envelope::{CircuitKind, EnvelopeError, ProofEnvelope};
+use crate::prover::merkle_hasher::{MerkleHasherKind, ProofHasher};
+use crate::prover::service::ProvedComponents;
+use crate::utils::poseidon2_stwo::PARAMETERS_FINGERPRINT;
+
+/// Fingerprints of the parameters a proof depends on beyond its components
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParameterFingerprints {
+    /// `poseidon2_stwo::PARAMETERS_FINGERPRINT`
+    pub poseidon: B256,
+    /// `CircuitParams::params_hash` of the compiled-in limits
+    pub circuit: B256,
+}
+
+impl ParameterFingerprints {
+    /// The parameters this build proves and verifies with
+    pub fn current() -> Self {
+        Self {
+            poseidon: B256::from(PARAMETERS_FINGERPRINT),
+            circuit: B256::from(CircuitParams::default().params_hash()),
+        }
+    }
+}
+
+impl std::fmt::Display for ParameterFingerprints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "poseidon {}, circuit {}", self.poseidon, self.circuit)
+    }
+}
+
+/// What an embedded proof commits, read without verifying it
+struct ProofShape {
+    config: PcsConfig,
+    /// Columns sampled in each tree stwo verifies, in commitment order
+    columns: Vec<usize>,
+}
+
+impl ProofShape {
+    fn of<H: ProofHasher>(proof: &StarkProof<H>) -> Self {
+        Self { config: proof.config, columns: proof.sampled_values.iter().map(Vec::len).collect() }
+    }
+}
+
+impl ProofEnvelope {
+    /// The parameter fingerprints the envelope records
+    pub fn parameter_fingerprints(&self) -> ParameterFingerprints {
+        ParameterFingerprints { poseidon: self.poseidon_params_fingerprint, circuit: self.circuit_params_hash }
+    }
+
+    /// Require the envelope to be proven with this build's Poseidon2 and circuit parameters
+    ///
+    /// Envelopes before v12 record neither and always fail.
+    pub fn check_parameters(&self) -> Result<(), CompatError> {
+        let (expected, found) = (ParameterFingerprints::current(), self.parameter_fingerprints());
+        if found != expected {
+            return Err(CompatError::ParameterMismatch { expected, found });
+        }
+        Ok(())
+    }
+
+    /// Check the envelope and its proof have the shape `components` verify
+    ///
+    /// Runs `check_parameters` first. Then compares the circuit and trace size the envelope records against the components, then
+    /// every trace tree's column count against their `trace_log_degree_bounds()`, and requires the
+    /// proof's FRI configuration to fold the smallest column, as `StarkConfig::is_compatible`.
+    /// Passing says nothing about the proof's validity; it only rules out the mismatches stwo
+    /// would report without naming them.
+    pub fn compatibility_check(&self, components: &ProvedComponents) -> Result<(), CompatError> {
+        self.check_parameters()?;
+        if self.circuit != components.circuit() {
+            return Err(CompatError::CircuitMismatch { envelope: self.circuit, components: components.circuit() });
+        }
+        if self.log_n_rows != components.log_n_rows() {
+            return Err(CompatError::TraceSizeMismatch { expected: components.log_n_rows(), found: self.log_n_rows });
+        }
+        self.layout.check(self.commitments.len()).map_err(EnvelopeError::from)?;
+        let shape = match self.merkle_hasher {
+            MerkleHasherKind::Blake2s => ProofShape::of(&self.stark_proof::<Blake2sMerkleHasher>()?),
+            MerkleHasherKind::Poseidon252 => ProofShape::of(&self.stark_proof::<Poseidon252MerkleHasher>()?),
+        };
+
+        // Auxiliary trees are committed outside the transcript and never sampled
+        let sampled: Vec<TreeRole> =
+            self.layout.roles().iter().copied().filter(|&role| role != TreeRole::Auxiliary).collect();
+        if shape.columns.len() != sampled.len() {
+            return Err(CompatError::TreeCountMismatch { expected: sampled.len(), found: shape.columns.len() });
+        }
+        let sizes = components.trace_log_degree_bounds();
+        let trace_trees = sampled.iter().filter(|role| role.trace_tree_index().is_some()).count();
+        if sizes.len() != trace_trees {
+            return Err(CompatError::TreeCountMismatch { expected: sizes.len(), found: trace_trees });
+        }
+        for (role, &found) in sampled.iter().zip(&shape.columns) {
+            let Some(tree) = role.trace_tree_index() else { continue };
+            let expected = sizes[tree].len();
+            if found != expected {
+                return Err(CompatError::ColumnCountMismatch { tree: *role, expected, found });
+            }
+        }
+
+        let min_log_size = sizes.iter().flatten().copied().min().unwrap_or(0);
+        let log_last_layer_degree_bound = shape.config.fri_config.log_last_layer_degree_bound;
+        if log_last_layer_degree_bound + 2 > min_log_size {
+            return Err(CompatError::FriConfigMismatch { log_last_layer_degree_bound, min_log_size });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompatError {
+    #[error("Envelope was proven with other parameters ({found}) than this build's ({expected})")]
+    ParameterMismatch { expected: ParameterFingerprints, found: ParameterFingerprints },
+
+    #[error("Envelope holds a {envelope:?} proof, the components are {components:?} components")]
+    CircuitMismatch { envelope: CircuitKind, components: CircuitKind },
+
+    #[error("Envelope records 2^{found} trace rows, the components were proven with 2^{expected}")]
+    TraceSizeMismatch { expected: u32, found: u32 },
+
+    #[error("Expected {expected} committed trees, found {found}")]
+    TreeCountMismatch { expected: usize, found: usize },
+
+    #[error("{tree:?} tree: the components have {expected} columns, the proof samples {found}")]
+    ColumnCountMismatch { tree: TreeRole, expected: usize, found: usize },
+
+    #[error(
+        "Proof's FRI last layer bound 2^{log_last_layer_degree_bound} does not fit the smallest column \
+         (2^{min_log_size} rows)"
+    )]
+    FriConfigMismatch { log_last_layer_degree_bound: u32, min_log_size: u32 },
+
+    #[error(transparent)]
+    Envelope(#[from] EnvelopeError),
+}
@@ -0,0 +1,756 @@
+// Proof envelope: a self-describing container for a STWO proof
+// Carries everything a verifier (Rust or Solidity) needs alongside the raw proof
+//
+// Byte format (`to_bytes`, `to_bytes_compressed`):
+//
+//   magic        4    ENVELOPE_MAGIC
+//   compression  1    Compression
+//   body         ..   envelope JSON, raw or DEFLATE-compressed
+//
+// `from_bytes_auto` reads either, and bare envelope JSON as written before the header existed.
+
+use std::io::{Read, Write};
+
+use alloy_primitives::{Bytes, B256, U256};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::pcs::PcsConfig;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::BackendForChannel;
+
+use crate::prover::commitment_layout::{CommitmentLayout, LayoutError};
+use crate::prover::compatibility::ParameterFingerprints;
+use crate::prover::merkle_hasher::{MerkleHasherKind, ProofHasher};
+use crate::prover::offline::ComponentClaims;
+use crate::prover::packaging::ProofId;
+use crate::prover::public_values::PobPublicValues;
+use crate::prover::spend_statement::SpendStatement;
+use crate::prover::statement::{BurnStatement, FieldMismatch, StatementError};
+use crate::prover::{ProofOfBurnComponents, SpendComponents};
+
+/// Current envelope format version
+/// Bump whenever the serialized layout of `ProofEnvelope` changes; tests/envelope_snapshots.rs
+/// fails on a layout change until it is bumped and the snapshots are retaken
+///
+/// v2: nullifier and commitment are `OUTPUT_LIMBS` M31 limbs packed with `limbs_to_u256`
+/// v3: burn public inputs carry the packed security level
+/// v4: burn envelopes embed their `BurnStatement`
+/// v5: envelopes record the `CommitmentLayout` of `commitments`
+/// v6: spend commitments absorb the withdrawn balance as `u256_to_m31_array` limbs
+/// v7: burn commitments absorb both 32-bit limbs of the reveal amount
+/// v8: burn statements carry the burn address hash, bound through the proven address digest
+/// v9: envelopes record the `MerkleHasherKind` the proof was committed with
+/// v10: batched burn envelopes list each instance's `BurnStatement` in `instances`
+/// v11: burn statements carry the chain id and expiry (`ReplayScope`) the proof is bound to
+/// v12: envelopes record the Poseidon2 and circuit parameter fingerprints they were proven with
+/// v13: spend envelopes embed their `SpendStatement`, which the proof's channel is bound to
+/// v14: envelopes carry the `ComponentClaims` `verify_offline` rebuilds the components from
+pub const ENVELOPE_VERSION: u16 = 14;
+
+/// First version whose envelopes must carry `claims`; older ones only verify their structure
+pub const CLAIMS_VERSION: u16 = 14;
+
+/// Leading bytes of the envelope byte format
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"POBE";
+
+/// Upper bound on a decompressed envelope, so a crafted stream cannot exhaust memory
+pub const MAX_ENVELOPE_LEN: u64 = 64 << 20;
+
+/// Compression of the envelope body, the byte after `ENVELOPE_MAGIC`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Compression {
+    None = 0,
+    /// Raw DEFLATE (RFC 1951), pure Rust so it builds for wasm32
+    Deflate = 1,
+}
+
+impl TryFrom<u8> for Compression {
+    type Error = EnvelopeError;
+
+    fn try_from(flag: u8) -> Result<Self, Self::Error> {
+        match flag {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Deflate),
+            _ => Err(EnvelopeError::UnknownCompression { flag }),
+        }
+    }
+}
+
+/// Where and when an envelope is being verified, for `ProofEnvelope::check_replay`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Number of the chain's latest block; `None` skips the expiry check
+    pub current_block: Option<u64>,
+    /// Chain the proof must be bound to
+    pub expected_chain_id: u64,
+}
+
+impl VerifyOptions {
+    /// Accept proofs for `chain_id`, whatever their expiry
+    pub fn for_chain(chain_id: u64) -> Self {
+        Self { current_block: None, expected_chain_id: chain_id }
+    }
+
+    /// Also reject proofs that expired before `block`
+    pub fn at_block(self, block: u64) -> Self {
+        Self { current_block: Some(block), ..self }
+    }
+}
+
+/// Circuit that produced the proof inside an envelope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitKind {
+    Burn,
+    Spend,
+}
+
+/// Serialized STARK proof plus the public data needed to verify it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProofEnvelope {
+    /// Envelope format version (see `ENVELOPE_VERSION`)
+    pub version: u16,
+
+    /// Circuit that produced the proof
+    pub circuit: CircuitKind,
+
+    /// Log2 of the number of trace rows used when proving
+    pub log_n_rows: u32,
+
+    /// Merkle root of every committed tree, in commitment order, as 32 big-endian bytes
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<crate::schema::B256Hex>"))]
+    pub commitments: Vec<B256>,
+
+    /// Role of each root in `commitments`; envelopes before v5 have the standard layout
+    #[serde(default = "legacy_layout")]
+    pub layout: CommitmentLayout,
+
+    /// Hasher of the committed trees; envelopes before v9 are all Blake2s
+    #[serde(default)]
+    pub merkle_hasher: MerkleHasherKind,
+
+    /// `poseidon2_stwo::PARAMETERS_FINGERPRINT` of the prover; zero before v12
+    #[serde(default)]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::B256Hex"))]
+    pub poseidon_params_fingerprint: B256,
+
+    /// `CircuitParams::params_hash` of the prover's compiled-in limits; zero before v12
+    #[serde(default)]
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::B256Hex"))]
+    pub circuit_params_hash: B256,
+
+    /// Public inputs in the order the verifier contract expects them
+    ///
+    /// Burn: [publicCommitment, nullifier, commitment, securityLevel]
+    /// Spend: [commitment, coin, remainingCoin]
+    ///
+    /// Nullifiers and commitments are packed with `packaging::limbs_to_u256`,
+    /// the security level with `packaging::pack_security_level`.
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<crate::schema::U256Hex>"))]
+    pub public_inputs: Vec<U256>,
+
+    /// Public statement of a burn proof, from which `public_inputs` are derived; `None` for spend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement: Option<BurnStatement>,
+
+    /// Statement of every instance of a batched burn proof, in instance order; empty otherwise
+    ///
+    /// A batch has no `statement`; its public inputs are the four of each instance, concatenated
+    /// in this order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instances: Vec<BurnStatement>,
+
+    /// Public statement of a spend proof, from which `public_inputs` are derived; `None` for burn
+    /// and for spend envelopes before v13
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spend_statement: Option<SpendStatement>,
+
+    /// Sizes, logup sums and public values of the components the proof was made with; `None`
+    /// before v14, whose envelopes only verify against the components themselves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claims: Option<ComponentClaims>,
+
+    /// JSON-serialized `StarkProof`
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::BytesHex"))]
+    pub proof: Bytes,
+}
+
+impl ProofEnvelope {
+    /// Wrap a freshly generated STARK proof, committed as `layout` records
+    pub fn from_stark_proof<H: ProofHasher>(
+        circuit: CircuitKind,
+        log_n_rows: u32,
+        proof: &StarkProof<H>,
+        layout: CommitmentLayout,
+        public_inputs: Vec<U256>,
+    ) -> Result<Self, EnvelopeError> {
+        layout.check(proof.commitments.len())?;
+        let commitments = proof
+            .commitments
+            .iter()
+            .map(|commitment| B256::from(H::root_bytes(commitment)))
+            .collect();
+        let proof_bytes = serde_json::to_vec(proof)?;
+        let fingerprints = ParameterFingerprints::current();
+
+        Ok(Self {
+            version: ENVELOPE_VERSION,
+            circuit,
+            log_n_rows,
+            commitments,
+            layout,
+            merkle_hasher: H::KIND,
+            poseidon_params_fingerprint: fingerprints.poseidon,
+            circuit_params_hash: fingerprints.circuit,
+            public_inputs,
+            statement: None,
+            instances: Vec::new(),
+            spend_statement: None,
+            claims: None,
+            proof: Bytes::from(proof_bytes),
+        })
+    }
+
+    /// Wrap a burn proof, deriving the public inputs from its statement
+    pub fn from_burn_proof<H: ProofHasher>(
+        log_n_rows: u32,
+        proof: &StarkProof<H>,
+        layout: CommitmentLayout,
+        statement: BurnStatement,
+    ) -> Result<Self, EnvelopeError> {
+        let public_inputs = burn_public_inputs(&statement).to_vec();
+        Ok(Self {
+            statement: Some(statement),
+            ..Self::from_stark_proof(CircuitKind::Burn, log_n_rows, proof, layout, public_inputs)?
+        })
+    }
+
+    /// Wrap a batched burn proof, one statement per instance in instance order
+    pub fn from_burn_batch<H: ProofHasher>(
+        log_n_rows: u32,
+        proof: &StarkProof<H>,
+        layout: CommitmentLayout,
+        statements: Vec<BurnStatement>,
+    ) -> Result<Self, EnvelopeError> {
+        let public_inputs = statements.iter().flat_map(burn_public_inputs).collect();
+        Ok(Self {
+            instances: statements,
+            ..Self::from_stark_proof(CircuitKind::Burn, log_n_rows, proof, layout, public_inputs)?
+        })
+    }
+
+    /// Wrap a spend proof, deriving the public inputs from its statement
+    pub fn from_spend_proof<H: ProofHasher>(
+        log_n_rows: u32,
+        proof: &StarkProof<H>,
+        layout: CommitmentLayout,
+        statement: SpendStatement,
+    ) -> Result<Self, EnvelopeError> {
+        let public_inputs = statement.public_inputs().to_vec();
+        Ok(Self {
+            spend_statement: Some(statement),
+            ..Self::from_stark_proof(CircuitKind::Spend, log_n_rows, proof, layout, public_inputs)?
+        })
+    }
+
+    /// Record the claims of the components the proof was made with, for `verify_offline`
+    pub fn with_claims(self, claims: impl Into<ComponentClaims>) -> Self {
+        Self { claims: Some(claims.into()), ..self }
+    }
+
+    /// Verify a batched burn envelope: the proof once, then each instance's statement
+    ///
+    /// Errors concern the whole envelope: a layout, hasher or instance count other than the
+    /// components', or a proof that does not verify. Otherwise each instance passes if the proof
+    /// binds its statement's outputs and its public inputs are the ones the statement derives,
+    /// so a wrong statement fails only its own instance.
+    pub fn verify_burn_batch(&self, components: &ProofOfBurnComponents) -> Result<Vec<InstanceResult>, EnvelopeError> {
+        self.check_layout(&components.layout)?;
+        self.check_hasher(components.merkle_hasher)?;
+        let proven = components.instance_values();
+        if self.instances.len() != proven.len() {
+            return Err(EnvelopeError::InstanceCountMismatch {
+                envelope: self.instances.len(),
+                components: proven.len(),
+            });
+        }
+        if self.public_inputs.len() != 4 * self.instances.len() {
+            return Err(EnvelopeError::PublicInputsMismatch);
+        }
+        // The proof is verified once, against every instance's public values
+        match self.merkle_hasher {
+            MerkleHasherKind::Blake2s => {
+                PobPublicValues::extract(components, self.stark_proof::<Blake2sMerkleHasher>()?)
+            }
+            MerkleHasherKind::Poseidon252 => {
+                PobPublicValues::extract(components, self.stark_proof::<Poseidon252MerkleHasher>()?)
+            }
+        }
+        .map_err(StatementError::from)?;
+
+        let results = self
+            .instances
+            .iter()
+            .zip(proven)
+            .zip(self.public_inputs.chunks_exact(4))
+            .enumerate()
+            .map(|(index, ((statement, public_values), public_inputs))| {
+                let result = if !statement.binds(public_values) {
+                    Err(EnvelopeError::Statement(StatementError::PublicValuesMismatch))
+                } else if statement.replay_scope() != components.scope {
+                    Err(EnvelopeError::Statement(StatementError::ReplayScopeMismatch))
+                } else if public_inputs != burn_public_inputs(statement) {
+                    Err(EnvelopeError::PublicInputsMismatch)
+                } else {
+                    Ok(())
+                };
+                InstanceResult { index, result }
+            })
+            .collect();
+        Ok(results)
+    }
+
+    /// Verify a burn envelope's proof against `components` and its embedded statement
+    ///
+    /// Fails unless the public inputs are the ones the statement derives and the proof binds the
+    /// statement's outputs. The proof is read with the hasher the envelope records, which must be
+    /// the one `components` were proven with.
+    pub fn verify_burn(&self, components: &ProofOfBurnComponents) -> Result<(), EnvelopeError> {
+        self.check_layout(&components.layout)?;
+        self.check_hasher(components.merkle_hasher)?;
+        match self.merkle_hasher {
+            MerkleHasherKind::Blake2s => self.verify_burn_with::<Blake2sMerkleHasher>(components),
+            MerkleHasherKind::Poseidon252 => self.verify_burn_with::<Poseidon252MerkleHasher>(components),
+        }
+    }
+
+    fn verify_burn_with<H: ProofHasher>(&self, components: &ProofOfBurnComponents) -> Result<(), EnvelopeError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        self.burn_statement()?.verify(components, self.stark_proof::<H>()?)?;
+        Ok(())
+    }
+
+    /// `check_replay`, then `verify_burn`
+    pub fn verify_burn_with_options(
+        &self,
+        components: &ProofOfBurnComponents,
+        options: &VerifyOptions,
+    ) -> Result<(), EnvelopeError> {
+        self.check_replay(options)?;
+        self.verify_burn(components)
+    }
+
+    /// Reject an envelope whose statements are bound to another chain or have expired
+    ///
+    /// Every statement of a batch is checked. A proof stays valid through its `valid_until_block`;
+    /// spends do not expire. This reads the statements only; `verify_burn` and `verify_spend`
+    /// check the proof is bound to them.
+    pub fn check_replay(&self, options: &VerifyOptions) -> Result<(), EnvelopeError> {
+        if let Some(statement) = &self.spend_statement {
+            if statement.chain_id != options.expected_chain_id {
+                return Err(EnvelopeError::WrongChain {
+                    expected: options.expected_chain_id,
+                    found: statement.chain_id,
+                });
+            }
+            return Ok(());
+        }
+        let statements = match &self.statement {
+            Some(statement) => std::slice::from_ref(statement),
+            None if !self.instances.is_empty() => self.instances.as_slice(),
+            None => return Err(EnvelopeError::MissingStatement),
+        };
+        for statement in statements {
+            if statement.chain_id != options.expected_chain_id {
+                return Err(EnvelopeError::WrongChain {
+                    expected: options.expected_chain_id,
+                    found: statement.chain_id,
+                });
+            }
+            let expiry = statement.valid_until_block.zip(options.current_block);
+            if let Some((valid_until_block, current_block)) = expiry {
+                if current_block > valid_until_block {
+                    return Err(EnvelopeError::Expired { valid_until_block, current_block });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `verify_burn`, then require the embedded statement to be `expected`
+    ///
+    /// A proof that verifies but proves another statement fails with `StatementMismatch`, naming
+    /// every differing field.
+    pub fn verify_burn_against(
+        &self,
+        components: &ProofOfBurnComponents,
+        expected: &BurnStatement,
+    ) -> Result<(), EnvelopeError> {
+        self.verify_burn(components)?;
+        self.check_burn_statement(expected)
+    }
+
+    /// Require the embedded statement to be `expected`, without verifying the proof
+    pub fn check_burn_statement(&self, expected: &BurnStatement) -> Result<(), EnvelopeError> {
+        let mismatches = self.burn_statement()?.mismatches(expected);
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(EnvelopeError::StatementMismatch { mismatches })
+        }
+    }
+
+    /// The embedded statement, provided the public inputs are the ones it derives
+    pub fn burn_statement(&self) -> Result<&BurnStatement, EnvelopeError> {
+        let statement = self.statement.as_ref().ok_or(EnvelopeError::MissingStatement)?;
+        if self.public_inputs != burn_public_inputs(statement) {
+            return Err(EnvelopeError::PublicInputsMismatch);
+        }
+        Ok(statement)
+    }
+
+    /// Verify a spend envelope's proof against `components` and its embedded statement
+    ///
+    /// The statement is replayed into the channel in place of the components' own, so the proof
+    /// fails for any statement but the one it was made for; the public inputs must be the ones it
+    /// derives. Spend envelopes before v13 carry no statement and fail.
+    pub fn verify_spend(&self, components: &SpendComponents) -> Result<(), EnvelopeError> {
+        self.check_layout(&components.layout)?;
+        self.check_hasher(components.merkle_hasher)?;
+        match self.merkle_hasher {
+            MerkleHasherKind::Blake2s => self.verify_spend_with::<Blake2sMerkleHasher>(components),
+            MerkleHasherKind::Poseidon252 => self.verify_spend_with::<Poseidon252MerkleHasher>(components),
+        }
+    }
+
+    fn verify_spend_with<H: ProofHasher>(&self, components: &SpendComponents) -> Result<(), EnvelopeError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        self.spend_statement()?.verify(components, self.stark_proof::<H>()?)?;
+        Ok(())
+    }
+
+    /// `verify_spend`, then require the embedded statement to be `expected`
+    pub fn verify_spend_against(
+        &self,
+        components: &SpendComponents,
+        expected: &SpendStatement,
+    ) -> Result<(), EnvelopeError> {
+        self.verify_spend(components)?;
+        self.check_spend_statement(expected)
+    }
+
+    /// Require the embedded spend statement to be `expected`, without verifying the proof
+    pub fn check_spend_statement(&self, expected: &SpendStatement) -> Result<(), EnvelopeError> {
+        let mismatches = self.spend_statement()?.mismatches(expected);
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(EnvelopeError::StatementMismatch { mismatches })
+        }
+    }
+
+    /// The embedded spend statement, provided the public inputs are the ones it derives
+    pub fn spend_statement(&self) -> Result<&SpendStatement, EnvelopeError> {
+        let statement = self.spend_statement.as_ref().ok_or(EnvelopeError::MissingStatement)?;
+        if self.public_inputs != statement.public_inputs() {
+            return Err(EnvelopeError::PublicInputsMismatch);
+        }
+        Ok(statement)
+    }
+
+    /// The proof id of a single-instance burn envelope, computed from its public inputs
+    ///
+    /// `None` for spend envelopes, batches and burn envelopes without the four public inputs.
+    pub fn proof_id(&self) -> Option<ProofId> {
+        match (self.circuit, self.public_inputs.as_slice()) {
+            (CircuitKind::Burn, [public_commitment, nullifier, commitment, _security_level]) => {
+                Some(ProofId::compute(*public_commitment, *nullifier, *commitment))
+            }
+            _ => None,
+        }
+    }
+
+    /// Require the recorded layout to be `layout`, the one the verifying components were proven with
+    pub fn check_layout(&self, layout: &CommitmentLayout) -> Result<(), EnvelopeError> {
+        if self.layout != *layout {
+            return Err(EnvelopeError::LayoutMismatch);
+        }
+        Ok(())
+    }
+
+    /// Require the recorded hasher to be `merkle_hasher`, the one a verifier expects
+    pub fn check_hasher(&self, merkle_hasher: MerkleHasherKind) -> Result<(), EnvelopeError> {
+        if self.merkle_hasher != merkle_hasher {
+            return Err(EnvelopeError::HasherMismatch { envelope: self.merkle_hasher, expected: merkle_hasher });
+        }
+        Ok(())
+    }
+
+    /// Deserialize the embedded STARK proof, which must be committed with `H`
+    pub fn stark_proof<H: ProofHasher>(&self) -> Result<StarkProof<H>, EnvelopeError> {
+        self.check_hasher(H::KIND)?;
+        Ok(serde_json::from_slice(&self.proof)?)
+    }
+
+    /// Deserialize the embedded STARK proof with the recorded hasher and return its configuration
+    pub fn pcs_config(&self) -> Result<PcsConfig, EnvelopeError> {
+        Ok(match self.merkle_hasher {
+            MerkleHasherKind::Blake2s => self.stark_proof::<Blake2sMerkleHasher>()?.config,
+            MerkleHasherKind::Poseidon252 => self.stark_proof::<Poseidon252MerkleHasher>()?.config,
+        })
+    }
+
+    /// Uncompressed byte encoding, see the module comment for the layout
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EnvelopeError> {
+        let mut bytes = header(Compression::None);
+        serde_json::to_writer(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// DEFLATE-compressed byte encoding, see the module comment for the layout
+    pub fn to_bytes_compressed(&self) -> Result<Vec<u8>, EnvelopeError> {
+        let mut encoder = DeflateEncoder::new(header(Compression::Deflate), flate2::Compression::best());
+        encoder.write_all(&serde_json::to_vec(self)?).map_err(EnvelopeError::Compression)?;
+        encoder.finish().map_err(EnvelopeError::Compression)
+    }
+
+    /// Decode `to_bytes` or `to_bytes_compressed` output, or bare envelope JSON
+    pub fn from_bytes_auto(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        if bytes.first() == Some(&b'{') {
+            return Ok(serde_json::from_slice(bytes)?);
+        }
+        let body = bytes
+            .strip_prefix(&ENVELOPE_MAGIC)
+            .ok_or(EnvelopeError::UnknownFormat)?;
+        let (&flag, body) = body.split_first().ok_or(EnvelopeError::UnknownFormat)?;
+
+        match Compression::try_from(flag)? {
+            Compression::None => Ok(serde_json::from_slice(body)?),
+            Compression::Deflate => {
+                let mut json = Vec::new();
+                DeflateDecoder::new(body)
+                    .take(MAX_ENVELOPE_LEN + 1)
+                    .read_to_end(&mut json)
+                    .map_err(EnvelopeError::Decompression)?;
+                if json.len() as u64 > MAX_ENVELOPE_LEN {
+                    return Err(EnvelopeError::TooLarge);
+                }
+                Ok(serde_json::from_slice(&json)?)
+            }
+        }
+    }
+}
+
+/// Outcome of one instance of a batched burn envelope
+#[derive(Debug)]
+pub struct InstanceResult {
+    /// Position of the instance in the batch
+    pub index: usize,
+    pub result: Result<(), EnvelopeError>,
+}
+
+impl InstanceResult {
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// `Ok` if every instance passed, else the error of the first that did not
+    pub fn all_passed(results: Vec<InstanceResult>) -> Result<(), EnvelopeError> {
+        results.into_iter().try_for_each(|instance| instance.result)
+    }
+}
+
+/// The burn public inputs `statement` derives, in envelope order
+fn burn_public_inputs(statement: &BurnStatement) -> [U256; 4] {
+    let (public_commitment, nullifier, commitment, security_level) = statement.to_solidity_tuple();
+    [public_commitment, nullifier, commitment, security_level]
+}
+
+fn header(compression: Compression) -> Vec<u8> {
+    let mut bytes = ENVELOPE_MAGIC.to_vec();
+    bytes.push(compression as u8);
+    bytes
+}
+
+/// Layout of envelopes written before it was recorded
+fn legacy_layout() -> CommitmentLayout {
+    CommitmentLayout::standard(true)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EnvelopeError {
+    #[error("Proof serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Envelope carries no statement")]
+    MissingStatement,
+
+    #[error("Public inputs do not match the envelope's statement")]
+    PublicInputsMismatch,
+
+    #[error("Proof does not match the envelope's statement: {0}")]
+    Statement(#[from] StatementError),
+
+    #[error(
+        "Envelope proves another statement than expected: {}",
+        mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+    )]
+    StatementMismatch { mismatches: Vec<FieldMismatch> },
+
+    #[error("Invalid commitment layout: {0}")]
+    Layout(#[from] LayoutError),
+
+    #[error("Envelope records another commitment layout than the verifying components")]
+    LayoutMismatch,
+
+    #[error("Envelope proof is committed with {envelope}, expected {expected}")]
+    HasherMismatch { envelope: MerkleHasherKind, expected: MerkleHasherKind },
+
+    #[error("Envelope lists {envelope} instances, the components were proven with {components}")]
+    InstanceCountMismatch { envelope: usize, components: usize },
+
+    #[error("Proof is bound to chain {found}, expected chain {expected}")]
+    WrongChain { expected: u64, found: u64 },
+
+    #[error("Proof expired after block {valid_until_block}, the chain is at block {current_block}")]
+    Expired { valid_until_block: u64, current_block: u64 },
+
+    #[error("Not a proof envelope: missing magic bytes")]
+    UnknownFormat,
+
+    #[error("Unknown envelope compression flag {flag}")]
+    UnknownCompression { flag: u8 },
+
+    #[error("Envelope compression failed: {0}")]
+    Compression(#[source] std::io::Error),
+
+    #[error("Corrupted compressed envelope: {0}")]
+    Decompression(#[source] std::io::Error),
+
+    #[error("Decompressed envelope exceeds {} bytes", MAX_ENVELOPE_LEN)]
+    TooLarge,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> ProofEnvelope {
+        ProofEnvelope {
+            version: ENVELOPE_VERSION,
+            circuit: CircuitKind::Spend,
+            log_n_rows: 6,
+            commitments: vec![B256::repeat_byte(0x11); 4],
+            layout: CommitmentLayout::standard(true),
+            merkle_hasher: MerkleHasherKind::Blake2s,
+            poseidon_params_fingerprint: B256::ZERO,
+            circuit_params_hash: B256::ZERO,
+            public_inputs: vec![U256::from(1), U256::from(2), U256::from(3)],
+            statement: None,
+            instances: Vec::new(),
+            spend_statement: None,
+            claims: None,
+            proof: Bytes::from(vec![0x5a; 4096]),
+        }
+    }
+
+    fn assert_same(decoded: &ProofEnvelope, envelope: &ProofEnvelope) {
+        assert_eq!(decoded.version, envelope.version);
+        assert_eq!(decoded.circuit, envelope.circuit);
+        assert_eq!(decoded.commitments, envelope.commitments);
+        assert_eq!(decoded.public_inputs, envelope.public_inputs);
+        assert_eq!(decoded.proof, envelope.proof);
+    }
+
+    #[test]
+    fn test_byte_roundtrip() {
+        let envelope = envelope();
+        let plain = envelope.to_bytes().unwrap();
+        let compressed = envelope.to_bytes_compressed().unwrap();
+        assert_eq!(plain[..5], *b"POBE\x00");
+        assert_eq!(compressed[..5], *b"POBE\x01");
+        assert!(compressed.len() < plain.len());
+
+        for bytes in [plain, compressed, serde_json::to_vec(&envelope).unwrap()] {
+            assert_same(&ProofEnvelope::from_bytes_auto(&bytes).unwrap(), &envelope);
+        }
+    }
+
+    #[test]
+    fn test_corrupted_stream_is_rejected() {
+        let mut compressed = envelope().to_bytes_compressed().unwrap();
+        // 0b11 in the first block header is a reserved DEFLATE block type
+        compressed[5] |= 0b110;
+        assert!(matches!(
+            ProofEnvelope::from_bytes_auto(&compressed),
+            Err(EnvelopeError::Decompression(_))
+        ));
+
+        let mut truncated = envelope().to_bytes_compressed().unwrap();
+        truncated.truncate(truncated.len() / 2);
+        assert!(ProofEnvelope::from_bytes_auto(&truncated).is_err());
+
+        assert!(matches!(
+            ProofEnvelope::from_bytes_auto(b"POBE\x07"),
+            Err(EnvelopeError::UnknownCompression { flag: 7 })
+        ));
+        assert!(matches!(ProofEnvelope::from_bytes_auto(b"PK\x03\x04"), Err(EnvelopeError::UnknownFormat)));
+    }
+
+    #[test]
+    fn test_envelope_json_roundtrip() {
+        let envelope = ProofEnvelope {
+            version: ENVELOPE_VERSION,
+            circuit: CircuitKind::Burn,
+            log_n_rows: 6,
+            commitments: vec![B256::repeat_byte(0x11), B256::repeat_byte(0x22)],
+            layout: CommitmentLayout::standard(false),
+            merkle_hasher: MerkleHasherKind::Poseidon252,
+            poseidon_params_fingerprint: B256::repeat_byte(0x33),
+            circuit_params_hash: B256::repeat_byte(0x44),
+            public_inputs: vec![U256::from(1), U256::from(2), U256::from(3)],
+            statement: None,
+            instances: Vec::new(),
+            spend_statement: None,
+            claims: None,
+            proof: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        };
+
+        let json = serde_json::to_string(&envelope).unwrap();
+        let decoded: ProofEnvelope = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.version, envelope.version);
+        assert_eq!(decoded.circuit, CircuitKind::Burn);
+        // Three public inputs are not a burn tuple the contract can id
+        assert_eq!(decoded.proof_id(), None);
+        assert_eq!(decoded.commitments, envelope.commitments);
+        assert_eq!(decoded.layout, envelope.layout);
+        assert_eq!(decoded.merkle_hasher, MerkleHasherKind::Poseidon252);
+        assert_eq!(decoded.parameter_fingerprints(), envelope.parameter_fingerprints());
+        assert_eq!(decoded.public_inputs, envelope.public_inputs);
+        assert_eq!(decoded.proof, envelope.proof);
+
+        // v4 envelopes carry no layout and were all committed in the standard order, envelopes
+        // before v9 all with Blake2s, and envelopes before v12 record no fingerprints
+        let mut legacy: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for field in ["layout", "merkle_hasher", "poseidon_params_fingerprint", "circuit_params_hash"] {
+            legacy.as_object_mut().unwrap().remove(field);
+        }
+        let decoded: ProofEnvelope = serde_json::from_value(legacy).unwrap();
+        assert_eq!(decoded.layout, CommitmentLayout::standard(true));
+        assert_eq!(decoded.merkle_hasher, MerkleHasherKind::Blake2s);
+        assert_eq!(decoded.poseidon_params_fingerprint, B256::ZERO);
+        assert!(decoded.check_parameters().is_err());
+    }
+}
@@ -0,0 +1,282 @@
+// Envelope inspection
+// Support needs to see what a proof file holds without the components to verify it. This reads
+// what an envelope records: its format, circuit, trace size, the configuration of the embedded
+// proof, its public values and commitments. None of it is verified, and the summary says so.
+// Envelopes of older format versions are read with the defaults `from_bytes_auto` fills in, and
+// a proof that does not parse only leaves the configuration out.
+
+use alloy_primitives::{B256, U256};
+use serde::Serialize;
+use stwo_prover::core::pcs::PcsConfig;
+
+use crate::prover::commitment_layout::TreeRole;
+use crate::prover::envelope::{CircuitKind, EnvelopeError, ProofEnvelope, ENVELOPE_MAGIC, ENVELOPE_VERSION};
+use crate::prover::merkle_hasher::MerkleHasherKind;
+use crate::prover::spend_statement::SpendStatement;
+use crate::prover::statement::BurnStatement;
+
+/// Names of the burn public inputs, in envelope order
+pub const BURN_PUBLIC_INPUTS: [&str; 4] = ["publicCommitment", "nullifier", "commitment", "securityLevel"];
+
+/// Names of the spend public inputs, in envelope order
+pub const SPEND_PUBLIC_INPUTS: [&str; 3] = ["commitment", "coin", "remainingCoin"];
+
+/// What an envelope records, read without verifying anything
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvelopeSummary {
+    /// Always `false`: a reminder that this is metadata only
+    pub verified: bool,
+    /// Format version the envelope was written with
+    pub version: u16,
+    /// Format version of this crate, which may be newer
+    pub current_version: u16,
+    /// Version of the crate that read the envelope; envelopes do not record their writer's
+    pub crate_version: &'static str,
+    /// Whether the file was DEFLATE-compressed, `None` for bare envelope JSON
+    pub compressed: Option<bool>,
+    pub circuit: CircuitKind,
+    pub log_n_rows: u32,
+    pub merkle_hasher: MerkleHasherKind,
+    /// Configuration of the embedded proof, `None` if it does not parse
+    pub config: Option<ConfigSummary>,
+    /// Public inputs, named in the order the verifier contract takes them
+    pub public_inputs: Vec<NamedValue>,
+    /// Embedded statement of a burn envelope
+    pub statement: Option<BurnStatement>,
+    /// Embedded statement of a spend envelope
+    pub spend_statement: Option<SpendStatement>,
+    /// Recorded layout, with the root committed for each tree
+    pub commitments: Vec<CommitmentSummary>,
+    /// Size of the serialized `StarkProof`
+    pub proof_bytes: usize,
+    /// Size of the file or buffer the envelope was read from
+    pub envelope_bytes: usize,
+}
+
+/// FRI and proof-of-work parameters of the embedded proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ConfigSummary {
+    pub pow_bits: u32,
+    pub log_blowup_factor: u32,
+    pub log_last_layer_degree_bound: u32,
+    pub n_queries: usize,
+    /// `pow_bits + log_blowup_factor * n_queries`
+    pub security_bits: u32,
+}
+
+impl From<PcsConfig> for ConfigSummary {
+    fn from(config: PcsConfig) -> Self {
+        Self {
+            pow_bits: config.pow_bits,
+            log_blowup_factor: config.fri_config.log_blowup_factor,
+            log_last_layer_degree_bound: config.fri_config.log_last_layer_degree_bound,
+            n_queries: config.fri_config.n_queries,
+            security_bits: config.pow_bits
+                + config.fri_config.log_blowup_factor * config.fri_config.n_queries as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NamedValue {
+    pub name: String,
+    pub value: U256,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CommitmentSummary {
+    pub role: TreeRole,
+    pub root: B256,
+}
+
+impl EnvelopeSummary {
+    /// Decode an envelope as `ProofEnvelope::from_bytes_auto` does and summarize it
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let envelope = ProofEnvelope::from_bytes_auto(bytes)?;
+        let compressed = bytes.strip_prefix(&ENVELOPE_MAGIC).map(|body| body.first() == Some(&1));
+        Ok(Self { compressed, envelope_bytes: bytes.len(), ..Self::of(&envelope) })
+    }
+
+    /// Summarize a decoded envelope; `compressed` and `envelope_bytes` are unknown here
+    pub fn of(envelope: &ProofEnvelope) -> Self {
+        let names: &[&str] = match envelope.circuit {
+            CircuitKind::Burn => &BURN_PUBLIC_INPUTS,
+            CircuitKind::Spend => &SPEND_PUBLIC_INPUTS,
+        };
+        // A batch repeats the names for each instance; inputs past the names of the current
+        // format keep their position as name
+        let batched = !envelope.instances.is_empty();
+        let public_inputs = envelope
+            .public_inputs
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| NamedValue {
+                name: match names.get(i % names.len()) {
+                    Some(name) if batched => format!("{name}[{}]", i / names.len()),
+                    _ => names.get(i).map_or_else(|| format!("input{i}"), |name| name.to_string()),
+                },
+                value,
+            })
+            .collect();
+        // A layout that does not fit the roots is shown as far as it goes
+        let commitments = envelope
+            .layout
+            .roles()
+            .iter()
+            .zip(&envelope.commitments)
+            .map(|(&role, &root)| CommitmentSummary { role, root })
+            .collect();
+
+        Self {
+            verified: false,
+            version: envelope.version,
+            current_version: ENVELOPE_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION"),
+            compressed: None,
+            circuit: envelope.circuit,
+            log_n_rows: envelope.log_n_rows,
+            merkle_hasher: envelope.merkle_hasher,
+            config: envelope.pcs_config().ok().map(ConfigSummary::from),
+            public_inputs,
+            statement: envelope.statement,
+            spend_statement: envelope.spend_statement,
+            commitments,
+            proof_bytes: envelope.proof.len(),
+            envelope_bytes: 0,
+        }
+    }
+}
+
+impl std::fmt::Display for EnvelopeSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Proof envelope (metadata only, not verified)")?;
+        let compression = match self.compressed {
+            Some(true) => "deflate",
+            Some(false) => "none",
+            None => "bare JSON",
+        };
+        writeln!(f, "  Format version:  {} (current {}, {compression})", self.version, self.current_version)?;
+        writeln!(f, "  Read by:         proof-of-burn-stwo {}", self.crate_version)?;
+        writeln!(f, "  Circuit:         {:?}", self.circuit)?;
+        writeln!(f, "  Trace rows:      2^{}", self.log_n_rows)?;
+        writeln!(f, "  Merkle hasher:   {}", self.merkle_hasher)?;
+        match &self.config {
+            Some(c) => writeln!(
+                f,
+                "  Config:          {} security bits (pow_bits={}, log_blowup_factor={}, \
+                 log_last_layer_degree_bound={}, n_queries={})",
+                c.security_bits, c.pow_bits, c.log_blowup_factor, c.log_last_layer_degree_bound, c.n_queries
+            )?,
+            None => writeln!(f, "  Config:          unreadable, the embedded proof does not parse")?,
+        }
+        writeln!(f, "  Proof size:      {} bytes (envelope {} bytes)", self.proof_bytes, self.envelope_bytes)?;
+        writeln!(f, "  Public inputs:")?;
+        for input in &self.public_inputs {
+            writeln!(f, "    {:<17} {:#x}", input.name, input.value)?;
+        }
+        if let Some(statement) = &self.statement {
+            writeln!(f, "  Statement:")?;
+            writeln!(f, "    block_hash        {}", statement.block_hash)?;
+            writeln!(f, "    burn_address_hash {}", statement.burn_address_hash)?;
+            writeln!(f, "    reveal_amount     {}", statement.reveal_amount)?;
+            writeln!(f, "    remaining_coin    {:#x}", statement.remaining_coin.value())?;
+            writeln!(f, "    security          relax {}", statement.security.byte_security_relax)?;
+            writeln!(f, "    chain_id          {}", statement.chain_id)?;
+            match statement.valid_until_block {
+                Some(block) => writeln!(f, "    valid_until_block {block}")?,
+                None => writeln!(f, "    valid_until_block none")?,
+            }
+            writeln!(f, "    statement_hash    {}", statement.statement_hash())?;
+        }
+        if let Some(statement) = &self.spend_statement {
+            writeln!(f, "  Statement:")?;
+            writeln!(f, "    coin              {:#x}", statement.coin.value())?;
+            writeln!(f, "    remaining_coin    {:#x}", statement.remaining_coin.value())?;
+            writeln!(f, "    withdrawn_balance {}", statement.withdrawn_balance)?;
+            writeln!(f, "    extra_commitment  {:#x}", statement.extra_commitment.value())?;
+            writeln!(f, "    chain_id          {}", statement.chain_id)?;
+            match statement.recipient {
+                Some(recipient) => writeln!(f, "    recipient         {recipient}")?,
+                None => writeln!(f, "    recipient         none (front-runnable)")?,
+            }
+            writeln!(f, "    statement_hash    {}", statement.statement_hash())?;
+        }
+        writeln!(f, "  Commitments:")?;
+        for commitment in &self.commitments {
+            writeln!(f, "    {:<17} {}", format!("{:?}", commitment.role), commitment.root)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::CircuitParams;
+    use crate::prover::{PobProver, StarkConfig};
+    use crate::test_utils::{gen_pob_inputs, gen_spend_inputs, InputProfile};
+
+    fn prover() -> PobProver {
+        PobProver::new(StarkConfig::default(), CircuitParams::default()).with_log_n_rows(5)
+    }
+
+    #[test]
+    fn test_burn_and_spend_summaries() {
+        let burn = prover().prove_burn(&gen_pob_inputs(1, InputProfile::TinyBalances)).unwrap().envelope;
+        let bytes = burn.to_bytes_compressed().unwrap();
+        let summary = EnvelopeSummary::from_bytes(&bytes).unwrap();
+        assert!(!summary.verified);
+        assert_eq!(summary.version, ENVELOPE_VERSION);
+        assert_eq!((summary.compressed, summary.envelope_bytes), (Some(true), bytes.len()));
+        assert_eq!(summary.statement, burn.statement);
+        assert_eq!(summary.proof_bytes, burn.proof.len());
+        assert_eq!(summary.config, Some(burn.pcs_config().unwrap().into()));
+        let names: Vec<&str> = summary.public_inputs.iter().map(|input| input.name.as_str()).collect();
+        assert_eq!(names, BURN_PUBLIC_INPUTS);
+        assert_eq!(summary.commitments.len(), burn.commitments.len());
+        assert!(summary.to_string().contains("metadata only, not verified"));
+
+        let spend = prover().prove_spend(&gen_spend_inputs(1, InputProfile::TinyBalances)).unwrap().envelope;
+        let summary = EnvelopeSummary::from_bytes(&spend.to_bytes().unwrap()).unwrap();
+        assert_eq!((summary.circuit, summary.compressed, summary.statement), (CircuitKind::Spend, Some(false), None));
+        let values: Vec<U256> = summary.public_inputs.iter().map(|input| input.value).collect();
+        assert_eq!(values, spend.public_inputs);
+        assert_eq!(summary.public_inputs[1].name, "coin");
+        assert_eq!(summary.spend_statement, spend.spend_statement);
+        assert!(summary.to_string().contains("withdrawn_balance"));
+    }
+
+    #[test]
+    fn test_older_envelope_summarized() {
+        let burn = prover().prove_burn(&gen_pob_inputs(2, InputProfile::TinyBalances)).unwrap().envelope;
+        // A v3 envelope: no statement, layout or hasher recorded
+        let mut json = serde_json::to_value(&burn).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        fields.insert("version".into(), 3.into());
+        for field in ["statement", "layout", "merkle_hasher"] {
+            fields.remove(field);
+        }
+        let summary = EnvelopeSummary::from_bytes(&serde_json::to_vec(&json).unwrap()).unwrap();
+        assert_eq!((summary.version, summary.compressed, summary.statement), (3, None, None));
+        assert_eq!(summary.merkle_hasher, MerkleHasherKind::Blake2s);
+        assert_eq!(summary.commitments[0].role, TreeRole::Preprocessed);
+        assert!(summary.config.is_some());
+    }
+
+    #[test]
+    fn test_truncated_envelope_is_an_error() {
+        let spend = prover().prove_spend(&gen_spend_inputs(3, InputProfile::TinyBalances)).unwrap().envelope;
+        for bytes in [spend.to_bytes().unwrap(), spend.to_bytes_compressed().unwrap()] {
+            let truncated = &bytes[..bytes.len() / 2];
+            assert!(EnvelopeSummary::from_bytes(truncated).is_err());
+        }
+        assert!(matches!(EnvelopeSummary::from_bytes(&ENVELOPE_MAGIC), Err(EnvelopeError::UnknownFormat)));
+
+        // An envelope whose proof is cut short still summarizes, without a configuration
+        let mut cut = spend.clone();
+        cut.proof = cut.proof.slice(..cut.proof.len() / 2);
+        let summary = EnvelopeSummary::of(&cut);
+        assert_eq!(summary.config, None);
+        assert!(summary.to_string().contains("unreadable"));
+    }
+}
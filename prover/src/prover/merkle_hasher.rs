@@ -0,0 +1,97 @@
+// Merkle hasher of the commitment trees
+// Blake2s is the cheapest hasher to prove and verify natively, and the one the Solidity verifier
+// reads. A verifier running inside another STARK (recursion, or a Starknet contract) pays far less
+// for Poseidon252, whose hash is a single field element, so the PoB and Spend provers can commit
+// with either. The hasher also drives the Fiat-Shamir channel: a proof only verifies under the
+// hasher it was made with.
+//
+// `StarkConfig::merkle_hasher` selects the hasher at runtime and envelopes record it; the
+// `ProofHasher` trait ties each `MerkleHasherKind` to stwo's hasher and channel types.
+
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::channel::MerkleChannel;
+use stwo_prover::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+use stwo_prover::core::vcs::ops::MerkleHasher;
+use stwo_prover::core::vcs::poseidon252_merkle::{Poseidon252MerkleChannel, Poseidon252MerkleHasher};
+
+/// Hasher a proof commits its trees and draws its challenges with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleHasherKind {
+    /// Blake2s-256, verified by the Solidity contract
+    #[default]
+    Blake2s,
+    /// Poseidon over the Stark252 field, for recursive and Starknet verifiers
+    Poseidon252,
+}
+
+impl std::fmt::Display for MerkleHasherKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MerkleHasherKind::Blake2s => "blake2s",
+            MerkleHasherKind::Poseidon252 => "poseidon252",
+        })
+    }
+}
+
+impl std::str::FromStr for MerkleHasherKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "blake2s" => Ok(MerkleHasherKind::Blake2s),
+            "poseidon252" => Ok(MerkleHasherKind::Poseidon252),
+            _ => Err(format!("unknown Merkle hasher {name:?}, expected blake2s or poseidon252")),
+        }
+    }
+}
+
+/// A stwo Merkle hasher the provers in this crate can commit with
+///
+/// Implemented on the hasher rather than the channel so the hasher is inferred from a
+/// `StarkProof<H>`.
+pub trait ProofHasher: MerkleHasher {
+    /// Channel whose challenges are drawn by mixing in roots of this hasher
+    type Channel: MerkleChannel<H = Self>;
+
+    const KIND: MerkleHasherKind;
+
+    /// A root as the 32 big-endian bytes envelopes and calldata carry
+    fn root_bytes(root: &Self::Hash) -> [u8; 32];
+}
+
+impl ProofHasher for Blake2sMerkleHasher {
+    type Channel = Blake2sMerkleChannel;
+
+    const KIND: MerkleHasherKind = MerkleHasherKind::Blake2s;
+
+    fn root_bytes(root: &Self::Hash) -> [u8; 32] {
+        root.0
+    }
+}
+
+impl ProofHasher for Poseidon252MerkleHasher {
+    type Channel = Poseidon252MerkleChannel;
+
+    const KIND: MerkleHasherKind = MerkleHasherKind::Poseidon252;
+
+    fn root_bytes(root: &Self::Hash) -> [u8; 32] {
+        root.to_bytes_be()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_roundtrip() {
+        for kind in [MerkleHasherKind::Blake2s, MerkleHasherKind::Poseidon252] {
+            assert_eq!(kind.to_string().parse::<MerkleHasherKind>(), Ok(kind));
+            assert_eq!(serde_json::to_string(&kind).unwrap(), format!("\"{kind}\""));
+        }
+        assert!("sha256".parse::<MerkleHasherKind>().is_err());
+        assert_eq!(MerkleHasherKind::default(), Blake2sMerkleHasher::KIND);
+    }
+}
@@ -0,0 +1,2635 @@
+// Stwo Prover and Verifier for Proof of Burn circuits
+// Implements the full Circle STARK proving protocol
+
+pub mod batch;
+pub mod batch_layout;
+pub mod commitment_layout;
+pub mod compatibility;
+pub mod envelope;
+pub mod inspect;
+pub mod merkle_hasher;
+pub mod offline;
+pub mod packaging;
+pub mod public_values;
+pub mod service;
+pub mod solidity;
+pub mod spend_statement;
+pub mod statement;
+pub mod timings;
+pub mod verification_failure;
+
+pub use envelope::{CircuitKind, EnvelopeError, InstanceResult, ProofEnvelope, VerifyOptions, ENVELOPE_VERSION};
+pub use inspect::EnvelopeSummary;
+pub use public_values::{ExtractionError, PobPublicValues};
+pub use batch::{verify_many, BatchItem, BatchReport};
+pub use batch_layout::{
+    generate_batch_table_trace, generate_pob_batch_trace, max_batch_size, BatchError, BatchLayout,
+    ROWS_PER_INSTANCE,
+};
+pub use commitment_layout::{CommitmentLayout, LayoutError, TreeRole, CONTRACT_TREE_ORDER};
+pub use compatibility::{CompatError, ParameterFingerprints};
+pub use merkle_hasher::{MerkleHasherKind, ProofHasher};
+pub use offline::{Check, CheckOutcome, CheckResult, ComponentClaims, VerificationReport};
+pub use packaging::ProofId;
+pub use service::{PobProver, ProvedComponents, ServiceError, ServiceProof};
+pub use spend_statement::SpendStatement;
+pub use statement::{BurnStatement, FieldMismatch, ReplayScope, StatementError};
+pub use timings::ProvingTimings;
+pub use verification_failure::{VerificationFailure, VerificationPhase};
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use num_traits::Zero;
+
+use stwo_prover::core::air::Component;
+use stwo_prover::core::channel::{Blake2sChannel, Channel, MerkleChannel};
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::FieldExpOps;
+use stwo_prover::core::fri::FriConfig;
+use stwo_prover::core::pcs::{CommitmentSchemeVerifier, PcsConfig, TreeVec};
+use stwo_prover::core::poly::circle::CanonicCoset;
+use stwo_prover::core::ColumnVec;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher};
+use stwo_prover::core::vcs::ops::MerkleHasher;
+use stwo_prover::core::verifier::{verify, VerificationError};
+use stwo_prover::prover::backend::simd::m31::N_LANES;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::BackendForChannel;
+use stwo_prover::prover::poly::circle::{CircleEvaluation, PolyOps};
+use stwo_prover::prover::poly::twiddles::TwiddleTree;
+use stwo_prover::prover::poly::BitReversedOrder;
+use stwo_prover::prover::{prove, CommitmentSchemeProver, ComponentProver};
+use stwo_constraint_framework::{Relation, TraceLocationAllocator};
+
+use crate::circuits::layout::POB_LAYOUT;
+use crate::circuits::preprocessed::IsFirst;
+use crate::circuits::keccak_air::{
+    gen_keccak_interaction_trace, generate_keccak_trace, keccak_log_size, BlockRootElements,
+    KeccakComponent, KeccakEval, KeccakStateElements,
+};
+use crate::circuits::poseidon2_table_air::{
+    gen_poseidon2_table_interaction_trace, generate_poseidon2_table_trace, poseidon2_table_log_size,
+    Poseidon2TableComponent, Poseidon2TableEval,
+};
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::proof_of_burn_air::{
+    gen_interaction_trace, BlockRootDigestElements, BurnAddressElements, CommitmentElements, LookupData,
+    NullifierElements, PobLookupColumns, PobWitness, ProofOfBurnComponent, ProofOfBurnEval,
+    PublicOutputElements, RemainingCoinElements, NUM_POB_COLUMNS,
+};
+use crate::circuits::range_check_air::{
+    gen_range_check_interaction_trace, gen_range_check_preprocessed_trace, generate_range_check_trace,
+    RangeCheckComponent, RangeCheckElements, RangeCheckEval, RangeCheckMultiplicities,
+    RANGE_CHECK_LOG_SIZE,
+};
+use crate::circuits::spend::{compute_spend_outputs, SpendInputs};
+use crate::circuits::spend_air::{
+    gen_spend_interaction_trace, generate_spend_trace, spend_range_check_multiplicities,
+    SpendComponent, SpendEval, SpendLookupData,
+};
+use crate::circuits::merge::MergeInputs;
+use crate::circuits::merge_air::{
+    gen_merge_interaction_trace, generate_merge_trace, merge_range_check_multiplicities,
+    MergeComponent, MergeEval,
+};
+use crate::circuits::split::{SplitInputs, SplitOutputs};
+use crate::circuits::transfer::TransferInputs;
+use crate::circuits::transfer_air::{
+    gen_transfer_interaction_trace, generate_transfer_trace, transfer_range_check_multiplicities,
+    TransferComponent, TransferEval,
+};
+use crate::circuits::split_air::{
+    gen_split_interaction_trace, generate_split_trace, split_outputs_from_trace,
+    split_public_output, split_range_check_multiplicities, SplitComponent, SplitEval,
+    SplitPublicOutputElements,
+};
+use crate::platform::Stopwatch;
+use crate::secret::zeroize_base_fields;
+use crate::utils::poseidon2_stwo::poseidon2_permutation;
+use verification_failure::check_sampled_columns;
+
+/// Main trace columns of one component, as generated and committed
+pub type TraceColumns = ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
+
+/// Twiddle tables kept across proofs, grown to the largest domain requested so far
+///
+/// A table for a canonic domain also serves every smaller one, so one table covers every proof
+/// up to its size. Readers share it; `get` only takes the write lock to grow it.
+#[derive(Default)]
+pub(crate) struct TwiddleCache(RwLock<Option<(u32, Arc<TwiddleTree<SimdBackend>>)>>);
+
+impl TwiddleCache {
+    /// Twiddles for domains of log size up to `log_size`, computed if none cached are that large
+    pub(crate) fn get(&self, log_size: u32) -> Arc<TwiddleTree<SimdBackend>> {
+        if let Some(twiddles) = Self::covering(&self.0.read().unwrap_or_else(PoisonError::into_inner), log_size) {
+            return twiddles;
+        }
+        let mut cached = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have grown the table while this one waited for the lock
+        if let Some(twiddles) = Self::covering(&cached, log_size) {
+            return twiddles;
+        }
+        let twiddles = tracing::debug_span!("precompute_twiddles", log_size).in_scope(|| {
+            Arc::new(SimdBackend::precompute_twiddles(CanonicCoset::new(log_size).circle_domain().half_coset))
+        });
+        *cached = Some((log_size, Arc::clone(&twiddles)));
+        twiddles
+    }
+
+    fn covering(
+        cached: &Option<(u32, Arc<TwiddleTree<SimdBackend>>)>,
+        log_size: u32,
+    ) -> Option<Arc<TwiddleTree<SimdBackend>>> {
+        cached
+            .as_ref()
+            .filter(|(cached_log_size, _)| *cached_log_size >= log_size)
+            .map(|(_, twiddles)| Arc::clone(twiddles))
+    }
+}
+
+/// Whether a prover given `config` must first move onto `on_one_thread`
+#[cfg(feature = "rayon")]
+fn needs_one_thread(config: &StarkConfig) -> bool {
+    config.deterministic && rayon::current_num_threads() > 1
+}
+
+/// Run `prove` on a rayon pool of one thread, so every parallel iterator it reaches runs in order
+///
+/// The caller's tracing subscriber and span follow it onto that thread.
+#[cfg(feature = "rayon")]
+fn on_one_thread<R: Send>(prove: impl FnOnce() -> R + Send) -> Result<R, anyhow::Error> {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build()?;
+    let dispatch = tracing::dispatcher::get_default(Clone::clone);
+    let span = tracing::Span::current();
+    Ok(pool.install(move || tracing::dispatcher::with_default(&dispatch, || span.in_scope(prove))))
+}
+
+/// Log expansion factor for constraints
+/// Used for interpolation degree bound in proofs
+const LOG_EXPAND: u32 = 2;
+
+/// Smallest trace the provers accept: one SIMD vector of rows
+pub const MIN_LOG_SIZE: u32 = 4;
+
+/// Largest trace the provers accept (~1M rows)
+pub const MAX_LOG_SIZE: u32 = 20;
+
+/// A trace size outside `MIN_LOG_SIZE..=MAX_LOG_SIZE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("log_n_rows must be between {} and {}, got {log_n_rows}", MIN_LOG_SIZE, MAX_LOG_SIZE)]
+pub struct TraceSizeError {
+    pub log_n_rows: u32,
+}
+
+/// Fail unless the provers accept a trace of `2^log_n_rows` rows
+fn check_log_n_rows(log_n_rows: u32) -> Result<(), TraceSizeError> {
+    if (MIN_LOG_SIZE..=MAX_LOG_SIZE).contains(&log_n_rows) {
+        Ok(())
+    } else {
+        Err(TraceSizeError { log_n_rows })
+    }
+}
+
+/// Configuration for STARK proofs
+#[derive(Clone)]
+pub struct StarkConfig {
+    /// Number of proof-of-work bits for security
+    pub pow_bits: u32,
+    
+    /// FRI configuration
+    pub fri_config: FriConfig,
+
+    /// Require the same inputs to produce byte-identical proofs
+    ///
+    /// With the `rayon` feature the provers then run on a one-thread pool (`on_one_thread`), so
+    /// trace generation and any parallel iterator below it, stwo's grinding included, run in a
+    /// fixed order. Grinding returns the smallest valid nonce.
+    pub deterministic: bool,
+
+    /// Hasher of the commitment trees and the Fiat-Shamir channel
+    ///
+    /// `prove_proof_of_burn` and `prove_spend` commit with Blake2s; other hashers go through
+    /// their `_with_hasher` variants or `PobProver`, which select the hasher from this field.
+    pub merkle_hasher: MerkleHasherKind,
+
+    /// Keep each committed column's polynomial coefficients next to its evaluations
+    ///
+    /// Not needed for correct proofs: without them stwo interpolates a tree's columns again when
+    /// the prover evaluates them at the out-of-domain point, and the proof is the same. Keeping
+    /// them skips that interpolation at the cost of one M31 per row of every column
+    /// (`ConfigReport::coefficient_bytes`). No configuration of the pinned stwo revision
+    /// requires it.
+    pub store_poly_coeffs: bool,
+}
+
+impl Default for StarkConfig {
+    fn default() -> Self {
+        Self {
+            pow_bits: 10, // ~1024 iterations required
+            fri_config: FriConfig::new(
+                2,  // log_last_layer_degree_bound (fri_params_for gives 2 for the smallest 16-row traces)
+                1,  // log_blowup_factor (2x blowup)
+                64, // n_queries (security parameter)
+            ),
+            deterministic: false,
+            merkle_hasher: MerkleHasherKind::Blake2s,
+            store_poly_coeffs: false,
+        }
+    }
+}
+
+/// Proof-of-work bits `StarkConfig::for_trace` grinds
+const DEFAULT_POW_BITS: u32 = 10;
+
+/// Largest last-layer degree bound stwo accepts (`LOG_MAX_LAST_LAYER_DEGREE_BOUND`)
+const MAX_LOG_LAST_LAYER_DEGREE_BOUND: u32 = 10;
+
+/// Targets up to this many bits use a 2x blowup, larger ones 4x
+const MAX_SECURITY_BITS_AT_BLOWUP_1: u32 = 96;
+
+/// FRI parameters reaching `target_security_bits` for a proof whose smallest component has
+/// `2^log_n_rows` rows
+///
+/// With `DEFAULT_POW_BITS` of grinding:
+/// - `log_last_layer_degree_bound = min(log_n_rows - 2, 10)`: FRI first folds each column into
+///   a line polynomial of log degree `log_n_rows - 1` and only folds in columns strictly above
+///   the last layer, so a larger bound leaves the smallest columns unconsumed and stwo panics
+/// - `log_blowup_factor = 1` up to 96 bits, `2` above
+/// - `n_queries = ceil((target_security_bits - DEFAULT_POW_BITS) / log_blowup_factor)`, at least
+///   1, as each query contributes `log_blowup_factor` bits
+pub fn fri_params_for(log_n_rows: u32, target_security_bits: u32) -> FriConfig {
+    let log_last_layer_degree_bound = log_n_rows.saturating_sub(2).min(MAX_LOG_LAST_LAYER_DEGREE_BOUND);
+    let log_blowup_factor = if target_security_bits <= MAX_SECURITY_BITS_AT_BLOWUP_1 { 1 } else { 2 };
+    let query_bits = target_security_bits.saturating_sub(DEFAULT_POW_BITS);
+    let n_queries = query_bits.div_ceil(log_blowup_factor).max(1);
+    FriConfig::new(log_last_layer_degree_bound, log_blowup_factor, n_queries as usize)
+}
+
+impl StarkConfig {
+    /// Configuration reaching `security_bits` for a proof whose smallest component has
+    /// `2^log_n_rows` rows, see `fri_params_for`
+    pub fn for_trace(log_n_rows: u32, security_bits: u32) -> Self {
+        Self {
+            pow_bits: DEFAULT_POW_BITS,
+            fri_config: fri_params_for(log_n_rows, security_bits),
+            deterministic: false,
+            merkle_hasher: MerkleHasherKind::Blake2s,
+            store_poly_coeffs: false,
+        }
+    }
+
+    /// Conjectured security: proof-of-work bits plus `log_blowup_factor` bits per query
+    pub fn security_bits(&self) -> u32 {
+        self.pow_bits + self.fri_config.log_blowup_factor * self.fri_config.n_queries as u32
+    }
+
+    /// Whether stwo can prove with this configuration when the smallest component has
+    /// `2^min_log_size` rows
+    pub fn is_compatible(&self, min_log_size: u32) -> bool {
+        self.fri_config.log_last_layer_degree_bound + 2 <= min_log_size
+    }
+
+    /// This configuration, or `for_trace` at the same security if stwo would panic on it
+    fn fit_to_trace(self, min_log_size: u32) -> Self {
+        if self.is_compatible(min_log_size) {
+            return self;
+        }
+        let security_bits = self.security_bits();
+        tracing::warn!(
+            log_last_layer_degree_bound = self.fri_config.log_last_layer_degree_bound,
+            min_log_size,
+            security_bits,
+            "FRI configuration incompatible with the trace size, falling back to fri_params_for"
+        );
+        Self {
+            deterministic: self.deterministic,
+            merkle_hasher: self.merkle_hasher,
+            store_poly_coeffs: self.store_poly_coeffs,
+            ..Self::for_trace(min_log_size, security_bits)
+        }
+    }
+
+    /// Fail unless this configuration selects `H`, the hasher a prover was instantiated for
+    fn check_hasher<H: ProofHasher>(&self) -> Result<(), anyhow::Error> {
+        if self.merkle_hasher != H::KIND {
+            anyhow::bail!(
+                "StarkConfig selects the {} Merkle hasher, the prover commits with {}",
+                self.merkle_hasher,
+                H::KIND
+            );
+        }
+        Ok(())
+    }
+}
+
+impl From<StarkConfig> for PcsConfig {
+    fn from(config: StarkConfig) -> Self {
+        PcsConfig {
+            pow_bits: config.pow_bits,
+            fri_config: config.fri_config,
+        }
+    }
+}
+
+/// Merkle roots of a proof, addressed by tree instead of by position
+///
+/// `CommitmentSchemeProver` appends one root per `tree_builder().commit()` call, in call order,
+/// and `prove` commits the composition tree last. Which root is which tree is read from the
+/// proof's `CommitmentLayout`, as recorded by the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofCommitments<H: MerkleHasher = Blake2sMerkleHasher> {
+    pub preprocessed: H::Hash,
+    pub trace: H::Hash,
+    pub interaction: Option<H::Hash>,
+    pub composition: H::Hash,
+}
+
+impl<H: MerkleHasher> ProofCommitments<H> {
+    /// Split `proof.commitments` into named trees by `layout`
+    pub fn from_layout(
+        proof: &StarkProof<H>,
+        layout: &CommitmentLayout,
+    ) -> Result<Self, VerificationError> {
+        layout
+            .check(proof.commitments.len())
+            .map_err(|e| VerificationError::InvalidStructure(e.to_string()))?;
+        let root = |role| layout.position(role).map(|position| proof.commitments[position]);
+        let required = |role| root(role).expect("checked layouts have one tree of each required role");
+
+        Ok(Self {
+            preprocessed: required(TreeRole::Preprocessed),
+            trace: required(TreeRole::Trace),
+            interaction: root(TreeRole::Interaction),
+            composition: required(TreeRole::Composition),
+        })
+    }
+
+    /// Split `proof.commitments` into named trees by `CommitmentLayout::standard`
+    pub fn from_proof(
+        proof: &StarkProof<H>,
+        has_interaction: bool,
+    ) -> Result<Self, VerificationError> {
+        Self::from_layout(proof, &CommitmentLayout::standard(has_interaction))
+    }
+}
+
+/// The four components of a Proof of Burn proof
+///
+/// `pob` emits a lookup claim for each of its five Poseidon2 permutations, its balance chunks and
+/// the block root; `poseidon2` proves the table answering the permutations, `keccak` proves
+/// `keccak256(block_header)`, answering the block root, and `range_check` answers the chunks.
+/// All four share every commitment tree.
+pub struct ProofOfBurnComponents {
+    pub pob: ProofOfBurnComponent,
+    pub poseidon2: Poseidon2TableComponent,
+    pub keccak: KeccakComponent,
+    pub range_check: RangeCheckComponent,
+    /// Logup sums of `pob`, `poseidon2`, `keccak` and `range_check`, which add up to the public
+    /// values' `logup_sum` when every lookup is answered
+    pub claimed_sums: [SecureField; 4],
+    /// Public outputs the proof is bound to; read them through `PobPublicValues::extract`
+    ///
+    /// Those of the first instance for a batched proof.
+    pub public_values: PobPublicValues,
+    /// Public outputs of every instance of a batched proof, in instance order; empty for a single
+    /// proof
+    pub instances: Vec<PobPublicValues>,
+    /// Chain and expiry the proof is bound to, shared by every instance of a batch
+    pub scope: ReplayScope,
+    /// Role of every root in the proof's commitments, as recorded while proving
+    pub layout: CommitmentLayout,
+    /// Hasher the proof was committed with
+    pub merkle_hasher: MerkleHasherKind,
+    /// Wall-clock time of each proving phase
+    pub timings: ProvingTimings,
+}
+
+impl ProofOfBurnComponents {
+    fn provers(&self) -> [&dyn ComponentProver<SimdBackend>; 4] {
+        [&self.pob, &self.poseidon2, &self.keccak, &self.range_check]
+    }
+
+    fn verifiers(&self) -> [&dyn Component; 4] {
+        [&self.pob, &self.poseidon2, &self.keccak, &self.range_check]
+    }
+
+    /// Column sizes of every component, per tree
+    pub fn trace_log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(self.verifiers().into_iter().map(|c| c.trace_log_degree_bounds()))
+    }
+
+    /// Public outputs the proof claims: every instance's of a batch, else `public_values`
+    pub fn instance_values(&self) -> &[PobPublicValues] {
+        if self.instances.is_empty() {
+            std::slice::from_ref(&self.public_values)
+        } else {
+            &self.instances
+        }
+    }
+
+    /// Preprocessed columns the proof must commit to
+    pub fn preprocessed_trace(&self) -> TraceColumns {
+        pob_preprocessed_trace(self.pob.log_n_rows)
+    }
+
+    /// Log2 of the rows of `pob`, `poseidon2` and `keccak`; `range_check` has a fixed size
+    pub fn log_sizes(&self) -> [u32; 3] {
+        [self.pob.log_n_rows, self.poseidon2.log_n_rows, self.keccak.log_n_rows]
+    }
+
+    /// Components with the row counts `log_sizes` returns, drawing on `elements`, with no timings
+    ///
+    /// The trace locations are allocated in the order the prover commits the columns.
+    #[allow(clippy::too_many_arguments)]
+    fn assemble(
+        log_sizes: [u32; 3],
+        elements: PobLookupElements,
+        claimed_sums: [SecureField; 4],
+        public_values: PobPublicValues,
+        instances: Vec<PobPublicValues>,
+        scope: ReplayScope,
+        layout: CommitmentLayout,
+        merkle_hasher: MerkleHasherKind,
+    ) -> Self {
+        let [log_n_rows, table_log_size, keccak_log_size] = log_sizes;
+        let [pob_claimed_sum, table_claimed_sum, keccak_claimed_sum, range_check_claimed_sum] = claimed_sums;
+        let location_allocator = &mut TraceLocationAllocator::default();
+        Self {
+            pob: ProofOfBurnComponent::new(
+                location_allocator,
+                ProofOfBurnEval {
+                    log_n_rows,
+                    claimed_sum: pob_claimed_sum,
+                    nullifier_lookup: elements.nullifier.clone(),
+                    remaining_coin_lookup: elements.remaining_coin.clone(),
+                    commitment_lookup: elements.commitment.clone(),
+                    block_root_digest_lookup: elements.block_root_digest.clone(),
+                    burn_address_lookup: elements.burn_address.clone(),
+                    block_root_lookup: elements.block_root.clone(),
+                    range_check_lookup: elements.range_check.clone(),
+                    public_output_lookup: elements.public_output,
+                },
+                pob_claimed_sum,
+            ),
+            poseidon2: Poseidon2TableComponent::new(
+                location_allocator,
+                Poseidon2TableEval {
+                    log_n_rows: table_log_size,
+                    nullifier_lookup: elements.nullifier,
+                    remaining_coin_lookup: elements.remaining_coin,
+                    commitment_lookup: elements.commitment,
+                    block_root_digest_lookup: elements.block_root_digest,
+                    burn_address_lookup: elements.burn_address,
+                },
+                table_claimed_sum,
+            ),
+            keccak: KeccakComponent::new(
+                location_allocator,
+                KeccakEval {
+                    log_n_rows: keccak_log_size,
+                    keccak_state_lookup: elements.keccak_state,
+                    block_root_lookup: elements.block_root,
+                },
+                keccak_claimed_sum,
+            ),
+            range_check: RangeCheckComponent::new(
+                location_allocator,
+                RangeCheckEval {
+                    lookup_elements: elements.range_check,
+                },
+                range_check_claimed_sum,
+            ),
+            claimed_sums,
+            public_values,
+            instances,
+            scope,
+            layout,
+            merkle_hasher,
+            timings: ProvingTimings::default(),
+        }
+    }
+}
+
+/// Preprocessed columns of a Proof of Burn proof, in the order the components first read them
+///
+/// `pob` reads `is_first` and `range_check` the table's values; `TraceLocationAllocator` assigns
+/// preprocessed columns in that order, so the tree is committed in it too.
+fn pob_preprocessed_trace(log_n_rows: u32) -> TraceColumns {
+    let mut columns = vec![IsFirst::new(log_n_rows).gen_column()];
+    columns.extend(gen_range_check_preprocessed_trace());
+    columns
+}
+
+/// Lookup elements of every Proof of Burn relation, drawn after the main trace commitment
+///
+/// Prover and verifier both draw through `draw`, so the channel sees the same order on both sides.
+/// The replay scope and the public values are mixed in first, so every element depends on them; a
+/// batch mixes every instance's public values in instance order.
+struct PobLookupElements {
+    nullifier: NullifierElements,
+    remaining_coin: RemainingCoinElements,
+    commitment: CommitmentElements,
+    block_root_digest: BlockRootDigestElements,
+    burn_address: BurnAddressElements,
+    block_root: BlockRootElements,
+    keccak_state: KeccakStateElements,
+    range_check: RangeCheckElements,
+    public_output: PublicOutputElements,
+}
+
+impl PobLookupElements {
+    fn draw(channel: &mut impl Channel, scope: &ReplayScope, instances: &[PobPublicValues]) -> Self {
+        scope.mix_into(channel);
+        for public_values in instances {
+            public_values.mix_into(channel);
+        }
+        Self {
+            nullifier: NullifierElements::draw(channel),
+            remaining_coin: RemainingCoinElements::draw(channel),
+            commitment: CommitmentElements::draw(channel),
+            block_root_digest: BlockRootDigestElements::draw(channel),
+            burn_address: BurnAddressElements::draw(channel),
+            block_root: BlockRootElements::draw(channel),
+            keccak_state: KeccakStateElements::draw(channel),
+            range_check: RangeCheckElements::draw(channel),
+            public_output: PublicOutputElements::draw(channel),
+        }
+    }
+
+    /// Placeholder elements, for components whose column sizes are needed before the real ones
+    /// can be drawn
+    fn dummy() -> Self {
+        Self {
+            nullifier: NullifierElements::dummy(),
+            remaining_coin: RemainingCoinElements::dummy(),
+            commitment: CommitmentElements::dummy(),
+            block_root_digest: BlockRootDigestElements::dummy(),
+            burn_address: BurnAddressElements::dummy(),
+            block_root: BlockRootElements::dummy(),
+            keccak_state: KeccakStateElements::dummy(),
+            range_check: RangeCheckElements::dummy(),
+            public_output: PublicOutputElements::dummy(),
+        }
+    }
+}
+
+/// Prove a Proof of Burn statement using Circle STARKs
+/// 
+/// # Arguments
+/// * `inputs` - The witness data for the proof
+/// * `log_n_rows` - Log2 of the number of rows in the execution trace
+/// * `config` - STARK configuration parameters
+/// 
+/// # Returns
+/// * STARK proof and the components used for verification
+///
+/// Commits with Blake2s; `config.merkle_hasher` must select it.
+pub fn prove_proof_of_burn(
+    inputs: &ProofOfBurnInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(ProofOfBurnComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_proof_of_burn_with_hasher(inputs, log_n_rows, config)
+}
+
+/// `prove_proof_of_burn` committing with `H`, which `config.merkle_hasher` must select
+pub fn prove_proof_of_burn_with_hasher<H: ProofHasher>(
+    inputs: &ProofOfBurnInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(ProofOfBurnComponents, StarkProof<H>), anyhow::Error>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    let no_hook = None::<fn(&mut TraceColumns)>;
+    prove_proof_of_burn_impl(inputs, log_n_rows, config, &TwiddleCache::default(), no_hook)
+}
+
+/// `prove_proof_of_burn` with `trace_hook` applied to the PoB main trace before it is committed
+///
+/// For soundness tests only: whatever the hook changes, the prover or the verifier must reject.
+/// The hook needs the whole trace, so this path holds it and its lookup data (as
+/// `generate_pob_trace` returns them) where `prove_proof_of_burn` builds columns on demand; the
+/// proofs are otherwise identical.
+#[cfg(feature = "test-utils")]
+pub fn prove_proof_of_burn_with_trace_hook(
+    inputs: &ProofOfBurnInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(ProofOfBurnComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_proof_of_burn_impl(inputs, log_n_rows, config, &TwiddleCache::default(), Some(trace_hook))
+}
+
+#[tracing::instrument(name = "prove_proof_of_burn", level = "info", skip_all, fields(log_n_rows))]
+pub(crate) fn prove_proof_of_burn_impl<H: ProofHasher>(
+    inputs: &ProofOfBurnInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    twiddle_cache: &TwiddleCache,
+    trace_hook: Option<impl FnOnce(&mut TraceColumns) + Send>,
+) -> Result<(ProofOfBurnComponents, StarkProof<H>), anyhow::Error>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    #[cfg(feature = "rayon")]
+    if needs_one_thread(&config) {
+        return on_one_thread(move || {
+            prove_proof_of_burn_impl::<H>(inputs, log_n_rows, config, twiddle_cache, trace_hook)
+        })?;
+    }
+    config.check_hasher::<H>()?;
+    let mut stopwatch = Stopwatch::start();
+    let mut timings = ProvingTimings::default();
+
+    check_log_n_rows(log_n_rows)?;
+    
+    #[cfg(feature = "paranoid-checks")]
+    crate::consistency::check_pob(inputs)?;
+
+    // === Phase 1: Generate main execution traces (PoB, Poseidon2 table, Keccak, range check) ===
+    // Without a trace hook the PoB trace is never materialized: its columns are built from the
+    // witness row as they are committed, and read back from it for the interaction trace. A
+    // hooked trace is committed as edited and its lookups are read from it, as the other provers do
+    let witness = PobWitness::new(inputs)
+        .map_err(|e| anyhow::anyhow!("Trace generation failed: {}", e))?;
+    let (hooked_trace, held_lookup_data) = trace_hook
+        .map(|hook| {
+            let mut trace: TraceColumns = witness.trace_columns(log_n_rows, 0..NUM_POB_COLUMNS).collect();
+            hook(&mut trace);
+            let lookup_data = LookupData::from_trace(&trace, witness.table_entries());
+            (trace, lookup_data)
+        })
+        .unzip();
+    let lookup_columns: &dyn PobLookupColumns = match &held_lookup_data {
+        Some(lookup_data) => lookup_data,
+        None => &witness,
+    };
+    let public_values = match &hooked_trace {
+        Some(trace) => PobPublicValues::from_trace(trace),
+        None => PobPublicValues::from_witness(&witness),
+    };
+    let table_log_size = poseidon2_table_log_size(witness.table_entries().len());
+    let (table_trace, table_lookup_data) =
+        generate_poseidon2_table_trace(table_log_size, witness.table_entries())
+            .map_err(|e| anyhow::anyhow!("Poseidon2 table generation failed: {}", e))?;
+    // Every enabled PoB row looks up the block root once
+    let keccak_log_size = keccak_log_size(inputs.block_header.len());
+    let (keccak_trace, keccak_lookup_data) =
+        generate_keccak_trace(keccak_log_size, &inputs.block_header, N_LANES as u32)
+            .map_err(|e| anyhow::anyhow!("Keccak trace generation failed: {}", e))?;
+    let mut range_check_multiplicities = RangeCheckMultiplicities::default();
+    {
+        let enabled = lookup_columns.full_column(log_n_rows, POB_LAYOUT.enabled().start);
+        for column in POB_LAYOUT.range_checked() {
+            range_check_multiplicities
+                .add_column(&lookup_columns.full_column(log_n_rows, column), &enabled)
+                .map_err(|e| anyhow::anyhow!("Range-check trace generation failed: {}", e))?;
+        }
+    }
+    let (range_check_trace, range_check_column) = generate_range_check_trace(&range_check_multiplicities);
+    tracing::debug!(
+        pob_columns = NUM_POB_COLUMNS,
+        table_log_size,
+        table_entries = witness.table_entries().len(),
+        keccak_log_size,
+        keccak_columns = keccak_trace.len(),
+        "main traces generated"
+    );
+    timings.trace_gen_ms = stopwatch.lap();
+    
+    let min_log_size = log_n_rows
+        .min(table_log_size)
+        .min(keccak_log_size)
+        .min(RANGE_CHECK_LOG_SIZE);
+    let store_poly_coeffs = config.store_poly_coeffs;
+    let pcs_config: PcsConfig = config.fit_to_trace(min_log_size).into();
+    
+    // === Phase 2: Twiddles covering the largest component ===
+    let max_log_size = log_n_rows
+        .max(table_log_size)
+        .max(keccak_log_size)
+        .max(RANGE_CHECK_LOG_SIZE);
+    let twiddle_log_size = max_log_size + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor;
+    let twiddles = twiddle_cache.get(twiddle_log_size);
+    timings.twiddles_ms = stopwatch.lap();
+    
+    // === Phase 3: Setup Fiat-Shamir channel ===
+    let channel = &mut <H::Channel as MerkleChannel>::C::default();
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, H::Channel>::new(pcs_config, &twiddles);
+    if store_poly_coeffs {
+        commitment_scheme.set_store_polynomials_coefficients();
+    }
+    
+    // === Phase 4: Commit preprocessed trace (PoB's is_first, the range-check table's values) ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(pob_preprocessed_trace(log_n_rows));
+    tree_builder.commit(channel);
+    let mut layout = CommitmentLayout::default();
+    layout.push(TreeRole::Preprocessed);
+    
+    // === Phase 5: Commit main execution traces ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    match hooked_trace {
+        Some(trace) => {
+            tree_builder.extend_evals(trace);
+        }
+        // One layout range at a time: each column is generated just before it is interpolated
+        None => {
+            for (_, columns) in POB_LAYOUT.ranges() {
+                tree_builder.extend_evals(witness.trace_columns(log_n_rows, columns));
+            }
+        }
+    }
+    tree_builder.extend_evals(table_trace);
+    tree_builder.extend_evals(keccak_trace);
+    tree_builder.extend_evals(range_check_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Trace);
+    timings.commits_ms = stopwatch.lap();
+    
+    // === Phase 6: Mix the replay scope and public values and draw lookup elements ===
+    let scope = ReplayScope::of(inputs);
+    let elements = PobLookupElements::draw(channel, &scope, std::slice::from_ref(&public_values));
+    
+    // === Phase 7: Generate and commit interaction traces ===
+    let (interaction_trace, pob_claimed_sum) = gen_interaction_trace(
+        log_n_rows,
+        lookup_columns,
+        &elements.nullifier,
+        &elements.remaining_coin,
+        &elements.commitment,
+        &elements.block_root_digest,
+        &elements.burn_address,
+        &elements.block_root,
+        &elements.range_check,
+        &elements.public_output,
+    );
+    drop(held_lookup_data);
+    let (table_interaction_trace, table_claimed_sum) = gen_poseidon2_table_interaction_trace(
+        table_log_size,
+        &table_lookup_data,
+        &elements.nullifier,
+        &elements.remaining_coin,
+        &elements.commitment,
+        &elements.block_root_digest,
+        &elements.burn_address,
+    );
+    let (keccak_interaction_trace, keccak_claimed_sum) = gen_keccak_interaction_trace(
+        keccak_log_size,
+        &keccak_lookup_data,
+        &elements.keccak_state,
+        &elements.block_root,
+    );
+    let (range_check_interaction_trace, range_check_claimed_sum) =
+        gen_range_check_interaction_trace(&range_check_column, &elements.range_check);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(interaction_trace);
+    tree_builder.extend_evals(table_interaction_trace);
+    tree_builder.extend_evals(keccak_interaction_trace);
+    tree_builder.extend_evals(range_check_interaction_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Interaction);
+    // `prove` commits the composition tree last
+    layout.push(TreeRole::Composition);
+    let claimed_sums = [pob_claimed_sum, table_claimed_sum, keccak_claimed_sum, range_check_claimed_sum];
+    channel.mix_felts(&claimed_sums);
+    timings.interaction_ms = stopwatch.lap();
+    
+    // === Phase 8: Create components AFTER commits ===
+    let mut components = ProofOfBurnComponents::assemble(
+        [log_n_rows, table_log_size, keccak_log_size],
+        elements,
+        claimed_sums,
+        public_values,
+        Vec::new(),
+        scope,
+        layout,
+        H::KIND,
+    );
+    components.timings = timings;
+    
+    // === Phase 9: Generate the STARK proof ===
+    let stark_proof = tracing::info_span!("stark_prove")
+        .in_scope(|| prove(&components.provers(), channel, commitment_scheme))?;
+    components.layout.check(stark_proof.commitments.len())?;
+    components.timings.proof_ms = stopwatch.lap();
+    components.timings.total_ms = stopwatch.total();
+    
+    Ok((components, stark_proof))
+}
+
+/// Prove every instance of `inputs` in one Proof of Burn proof, committed with Blake2s
+///
+/// Instances are laid out by `BatchLayout` and must share a block header and a replay scope. The proof verifies
+/// with `verify_proof_of_burn` like a single one; the returned components list each instance's
+/// public values in `instances`, which the verifier mixes and sums in instance order.
+#[tracing::instrument(level = "info", skip_all, fields(instances = inputs.len()))]
+pub fn prove_proof_of_burn_batch(
+    inputs: &[ProofOfBurnInputs],
+    config: StarkConfig,
+) -> Result<(BatchLayout, ProofOfBurnComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    #[cfg(feature = "rayon")]
+    if needs_one_thread(&config) {
+        return on_one_thread(move || prove_proof_of_burn_batch(inputs, config))?;
+    }
+    config.check_hasher::<Blake2sMerkleHasher>()?;
+    let mut stopwatch = Stopwatch::start();
+    let mut timings = ProvingTimings::default();
+
+    #[cfg(feature = "paranoid-checks")]
+    for instance in inputs {
+        crate::consistency::check_pob(instance)?;
+    }
+
+    // === Phase 1: Generate main execution traces, one SIMD vector of PoB rows per instance ===
+    let (batch, trace, lookup_data) = generate_pob_batch_trace(inputs, &config)?;
+    let log_n_rows = batch.log_n_rows();
+    let instances: Vec<PobPublicValues> = (0..batch.instances())
+        .map(|instance| batch.public_values(&trace, instance).expect("instance is within the batch"))
+        .collect();
+    let (table_trace, table_lookup_data, table_timings) = generate_batch_table_trace(&lookup_data, true)?;
+    timings.permutation_cache = table_timings.permutation_cache;
+    let table_log_size = poseidon2_table_log_size(lookup_data.table_entries.len());
+    // Every enabled PoB row of every instance looks up the shared block root once
+    let block_header = &inputs[0].block_header;
+    let keccak_log_size = keccak_log_size(block_header.len());
+    let (keccak_trace, keccak_lookup_data) =
+        generate_keccak_trace(keccak_log_size, block_header, (batch.instances() * ROWS_PER_INSTANCE) as u32)
+            .map_err(|e| anyhow::anyhow!("Keccak trace generation failed: {}", e))?;
+    let mut range_check_multiplicities = RangeCheckMultiplicities::default();
+    {
+        let enabled = lookup_data.full_column(log_n_rows, POB_LAYOUT.enabled().start);
+        for column in POB_LAYOUT.range_checked() {
+            range_check_multiplicities
+                .add_column(&lookup_data.full_column(log_n_rows, column), &enabled)
+                .map_err(|e| anyhow::anyhow!("Range-check trace generation failed: {}", e))?;
+        }
+    }
+    let (range_check_trace, range_check_column) = generate_range_check_trace(&range_check_multiplicities);
+    timings.trace_gen_ms = stopwatch.lap();
+
+    let min_log_size = log_n_rows
+        .min(table_log_size)
+        .min(keccak_log_size)
+        .min(RANGE_CHECK_LOG_SIZE);
+    let store_poly_coeffs = config.store_poly_coeffs;
+    let pcs_config: PcsConfig = config.fit_to_trace(min_log_size).into();
+
+    // === Phase 2: Twiddles covering the largest component ===
+    let max_log_size = log_n_rows
+        .max(table_log_size)
+        .max(keccak_log_size)
+        .max(RANGE_CHECK_LOG_SIZE);
+    let twiddle_log_size = max_log_size + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor;
+    let twiddles = TwiddleCache::default().get(twiddle_log_size);
+    timings.twiddles_ms = stopwatch.lap();
+
+    // === Phase 3: Commit preprocessed and main traces ===
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(pcs_config, &twiddles);
+    if store_poly_coeffs {
+        commitment_scheme.set_store_polynomials_coefficients();
+    }
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(pob_preprocessed_trace(log_n_rows));
+    tree_builder.commit(channel);
+    let mut layout = CommitmentLayout::default();
+    layout.push(TreeRole::Preprocessed);
+
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(trace);
+    tree_builder.extend_evals(table_trace);
+    tree_builder.extend_evals(keccak_trace);
+    tree_builder.extend_evals(range_check_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Trace);
+    timings.commits_ms = stopwatch.lap();
+
+    // === Phase 4: Mix the shared replay scope and every instance's public values, then draw ===
+    let scope = ReplayScope::of(&inputs[0]);
+    let elements = PobLookupElements::draw(channel, &scope, &instances);
+
+    // === Phase 5: Generate and commit interaction traces ===
+    let (interaction_trace, pob_claimed_sum) = gen_interaction_trace(
+        log_n_rows,
+        &lookup_data,
+        &elements.nullifier,
+        &elements.remaining_coin,
+        &elements.commitment,
+        &elements.block_root_digest,
+        &elements.burn_address,
+        &elements.block_root,
+        &elements.range_check,
+        &elements.public_output,
+    );
+    let (table_interaction_trace, table_claimed_sum) = gen_poseidon2_table_interaction_trace(
+        table_log_size,
+        &table_lookup_data,
+        &elements.nullifier,
+        &elements.remaining_coin,
+        &elements.commitment,
+        &elements.block_root_digest,
+        &elements.burn_address,
+    );
+    let (keccak_interaction_trace, keccak_claimed_sum) = gen_keccak_interaction_trace(
+        keccak_log_size,
+        &keccak_lookup_data,
+        &elements.keccak_state,
+        &elements.block_root,
+    );
+    let (range_check_interaction_trace, range_check_claimed_sum) =
+        gen_range_check_interaction_trace(&range_check_column, &elements.range_check);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(interaction_trace);
+    tree_builder.extend_evals(table_interaction_trace);
+    tree_builder.extend_evals(keccak_interaction_trace);
+    tree_builder.extend_evals(range_check_interaction_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Interaction);
+    layout.push(TreeRole::Composition);
+    let claimed_sums = [pob_claimed_sum, table_claimed_sum, keccak_claimed_sum, range_check_claimed_sum];
+    channel.mix_felts(&claimed_sums);
+    timings.interaction_ms = stopwatch.lap();
+
+    // === Phase 6: Create components and prove ===
+    let mut components = ProofOfBurnComponents::assemble(
+        [log_n_rows, table_log_size, keccak_log_size],
+        elements,
+        claimed_sums,
+        instances[0],
+        instances,
+        scope,
+        layout,
+        MerkleHasherKind::Blake2s,
+    );
+    components.timings = timings;
+
+    let stark_proof = tracing::info_span!("stark_prove")
+        .in_scope(|| prove(&components.provers(), channel, commitment_scheme))?;
+    components.layout.check(stark_proof.commitments.len())?;
+    components.timings.proof_ms = stopwatch.lap();
+    components.timings.total_ms = stopwatch.total();
+
+    Ok((batch, components, stark_proof))
+}
+
+/// Verify a Proof of Burn STARK proof
+/// 
+/// # Arguments
+/// * `components` - The PoB, Poseidon2 table, Keccak and range-check components used to generate
+///   the proof
+/// * `proof` - The STARK proof to verify
+/// 
+/// # Returns
+/// * Ok(()) if the proof verifies against `components.public_values`, otherwise the failure with
+///   the phase it was found in
+#[tracing::instrument(level = "info", skip_all)]
+pub fn verify_proof_of_burn<H: ProofHasher>(
+    components: &ProofOfBurnComponents,
+    proof: StarkProof<H>,
+) -> Result<(), VerificationFailure>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    check_proof_hasher::<H>(components.merkle_hasher)
+        .map_err(|e| VerificationFailure::new(VerificationPhase::Setup, e))?;
+
+    // Setup verifier channel
+    let channel = &mut <H::Channel as MerkleChannel>::C::default();
+    let mut commitment_scheme = CommitmentSchemeVerifier::<H::Channel>::new(proof.config);
+    
+    // Replay the commitment phase
+    // This must match the prover's commitment order exactly
+    let mut elements = None;
+    replay_trace_commitments(
+        &mut commitment_scheme,
+        channel,
+        &components.trace_log_degree_bounds(),
+        components.preprocessed_trace(),
+        &components.layout,
+        &proof,
+        |channel| {
+            elements = Some(PobLookupElements::draw(channel, &components.scope, components.instance_values()));
+        },
+    )?;
+    let elements = elements.ok_or_else(|| {
+        VerificationFailure::invalid_structure(
+            VerificationPhase::LookupElements,
+            "Proof of Burn proof has no interaction tree",
+        )
+    })?;
+
+    // Every lookup the PoB component claims must be answered by the table, Keccak and range-check
+    // components, except the public output claims, which each instance's public values answer
+    let [pob_claimed_sum, table_claimed_sum, keccak_claimed_sum, range_check_claimed_sum] =
+        components.claimed_sums;
+    let public_sum = components
+        .instance_values()
+        .iter()
+        .fold(SecureField::zero(), |sum, values| sum + values.logup_sum(&elements.public_output));
+    if pob_claimed_sum + table_claimed_sum + keccak_claimed_sum + range_check_claimed_sum != public_sum {
+        return Err(VerificationFailure::invalid_structure(
+            VerificationPhase::LookupElements,
+            "Proof of Burn lookup sums do not cancel",
+        ));
+    }
+    channel.mix_felts(&components.claimed_sums);
+    
+    // Verify the proof
+    let verifiers = components.verifiers();
+    check_sampled_columns(&verifiers, &components.trace_log_degree_bounds(), &proof)?;
+    verify(&verifiers, channel, &mut commitment_scheme, proof)
+        .map_err(|e| VerificationFailure::new(VerificationPhase::Verify, e))
+}
+
+/// Fail unless a proof committed with `H` was made by components proven with `proven_with`
+///
+/// Roots and challenges of one hasher mean nothing under another, so a mismatch is reported as
+/// such instead of as whichever check the replay happens to fail first.
+fn check_proof_hasher<H: ProofHasher>(proven_with: MerkleHasherKind) -> Result<(), VerificationError> {
+    if proven_with != H::KIND {
+        return Err(VerificationError::InvalidStructure(format!(
+            "proof is committed with {}, the components were proven with {}",
+            H::KIND,
+            proven_with
+        )));
+    }
+    Ok(())
+}
+
+/// Replay the prover's trace commitments on the verifier side
+///
+/// Preprocessed, main and interaction trees are each committed with their column sizes from
+/// `sizes`, the components' `trace_log_degree_bounds()`, so a column added to any tree is picked up
+/// without touching the verifier. `draw_lookup_elements` runs between the main and interaction
+/// commitments, as in the prover.
+///
+/// Roots are taken from the proof at the positions `layout` records, and trees it marks as
+/// auxiliary are skipped.
+///
+/// The preprocessed root must be the root of `preprocessed_trace`: stwo takes it from the proof
+/// as it does the other roots, and would accept any preprocessed values consistent with it. A
+/// root other than that fails the replay of the preprocessed tree.
+fn replay_trace_commitments<H: ProofHasher>(
+    commitment_scheme: &mut CommitmentSchemeVerifier<H::Channel>,
+    channel: &mut <H::Channel as MerkleChannel>::C,
+    sizes: &TreeVec<Vec<u32>>,
+    preprocessed_trace: TraceColumns,
+    layout: &CommitmentLayout,
+    proof: &StarkProof<H>,
+    draw_lookup_elements: impl FnOnce(&mut <H::Channel as MerkleChannel>::C),
+) -> Result<(), VerificationFailure>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    let commitments = ProofCommitments::from_layout(proof, layout)
+        .map_err(|e| VerificationFailure::new(VerificationPhase::Setup, e))?;
+    let trees: Vec<_> = layout.trace_trees().collect();
+    if sizes.len() != trees.len() {
+        return Err(VerificationFailure::invalid_structure(
+            VerificationPhase::Setup,
+            format!("components describe {} trace trees, proof commits {}", sizes.len(), trees.len()),
+        ));
+    }
+    if commitments.preprocessed != preprocessed_root::<H>(preprocessed_trace, proof.config) {
+        return Err(VerificationFailure::invalid_structure(
+            VerificationPhase::CommitmentReplay { tree: TreeRole::Preprocessed },
+            "preprocessed root does not commit to the expected preprocessed columns",
+        ));
+    }
+    let mut draw_lookup_elements = Some(draw_lookup_elements);
+    for (position, tree) in trees {
+        if tree == 2 {
+            draw_lookup_elements.take().unwrap()(channel);
+        }
+        commitment_scheme.commit(proof.commitments[position], &sizes[tree], channel);
+    }
+    Ok(())
+}
+
+/// Root of the preprocessed tree a prover commits for `columns` under `config` with `H`
+fn preprocessed_root<H: ProofHasher>(columns: TraceColumns, config: PcsConfig) -> H::Hash
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    let log_size = columns.iter().map(|column| column.domain.log_size()).max().unwrap_or(0);
+    let twiddles = SimdBackend::precompute_twiddles(
+        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor).circle_domain().half_coset,
+    );
+    let mut commitment_scheme = CommitmentSchemeProver::<_, H::Channel>::new(config, &twiddles);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(columns);
+    tree_builder.commit(&mut <H::Channel as MerkleChannel>::C::default());
+    commitment_scheme.roots()[0]
+}
+
+/// The two components of a Spend proof
+///
+/// `spend` emits a range-check claim for each of its balance chunks and `range_check` answers them.
+/// Both share every commitment tree.
+pub struct SpendComponents {
+    pub spend: SpendComponent,
+    pub range_check: RangeCheckComponent,
+    /// Logup sums of `spend` and `range_check`, which cancel when every claim is answered
+    pub claimed_sums: [SecureField; 2],
+    /// Statement mixed into the channel before the range-check elements were drawn
+    pub statement: SpendStatement,
+    /// Role of every root in the proof's commitments, as recorded while proving
+    pub layout: CommitmentLayout,
+    /// Hasher the proof was committed with
+    pub merkle_hasher: MerkleHasherKind,
+    /// Wall-clock time of each proving phase
+    pub timings: ProvingTimings,
+}
+
+impl SpendComponents {
+    fn provers(&self) -> [&dyn ComponentProver<SimdBackend>; 2] {
+        [&self.spend, &self.range_check]
+    }
+
+    fn verifiers(&self) -> [&dyn Component; 2] {
+        [&self.spend, &self.range_check]
+    }
+
+    /// Column sizes of every component, per tree
+    pub fn trace_log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(self.verifiers().into_iter().map(|c| c.trace_log_degree_bounds()))
+    }
+
+    /// Components of a `2^log_n_rows`-row spend drawing on `range_check_lookup`, with no timings
+    fn assemble(
+        log_n_rows: u32,
+        range_check_lookup: RangeCheckElements,
+        claimed_sums: [SecureField; 2],
+        statement: SpendStatement,
+        layout: CommitmentLayout,
+        merkle_hasher: MerkleHasherKind,
+    ) -> Self {
+        let [spend_claimed_sum, range_check_claimed_sum] = claimed_sums;
+        let location_allocator = &mut TraceLocationAllocator::default();
+        Self {
+            spend: SpendComponent::new(
+                location_allocator,
+                SpendEval {
+                    log_n_rows,
+                    range_check_lookup: range_check_lookup.clone(),
+                },
+                spend_claimed_sum,
+            ),
+            range_check: RangeCheckComponent::new(
+                location_allocator,
+                RangeCheckEval {
+                    lookup_elements: range_check_lookup,
+                },
+                range_check_claimed_sum,
+            ),
+            claimed_sums,
+            statement,
+            layout,
+            merkle_hasher,
+            timings: ProvingTimings::default(),
+        }
+    }
+}
+
+/// Prove a Spend statement using Circle STARKs
+///
+/// Commits with Blake2s; `config.merkle_hasher` must select it.
+pub fn prove_spend(
+    inputs: &SpendInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(SpendComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_spend_with_hasher(inputs, log_n_rows, config)
+}
+
+/// `prove_spend` committing with `H`, which `config.merkle_hasher` must select
+pub fn prove_spend_with_hasher<H: ProofHasher>(
+    inputs: &SpendInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(SpendComponents, StarkProof<H>), anyhow::Error>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    prove_spend_impl(inputs, log_n_rows, config, &TwiddleCache::default(), |_| {})
+}
+
+/// `prove_spend` with `trace_hook` applied to the Spend main trace before it is committed
+///
+/// For soundness tests only: whatever the hook changes, the prover or the verifier must reject.
+#[cfg(feature = "test-utils")]
+pub fn prove_spend_with_trace_hook(
+    inputs: &SpendInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(SpendComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_spend_impl(inputs, log_n_rows, config, &TwiddleCache::default(), trace_hook)
+}
+
+#[tracing::instrument(name = "prove_spend", level = "info", skip_all, fields(log_n_rows))]
+pub(crate) fn prove_spend_impl<H: ProofHasher>(
+    inputs: &SpendInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    twiddle_cache: &TwiddleCache,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(SpendComponents, StarkProof<H>), anyhow::Error>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    #[cfg(feature = "rayon")]
+    if needs_one_thread(&config) {
+        return on_one_thread(move || {
+            prove_spend_impl::<H>(inputs, log_n_rows, config, twiddle_cache, trace_hook)
+        })?;
+    }
+    config.check_hasher::<H>()?;
+    let mut stopwatch = Stopwatch::start();
+    let mut timings = ProvingTimings::default();
+
+    check_log_n_rows(log_n_rows)?;
+    
+    let store_poly_coeffs = config.store_poly_coeffs;
+    let pcs_config: PcsConfig = config.fit_to_trace(log_n_rows.min(RANGE_CHECK_LOG_SIZE)).into();
+    
+    #[cfg(feature = "paranoid-checks")]
+    crate::consistency::check_spend(inputs)?;
+
+    // === Phase 1: Generate main execution traces (Spend, range check) ===
+    let (mut trace, _) = generate_spend_trace(log_n_rows, inputs);
+    let statement = SpendStatement::new(inputs, &compute_spend_outputs(inputs, poseidon2_permutation));
+    trace_hook(&mut trace);
+    // Read the lookups back from the hooked trace, so the claims match the committed columns
+    let lookup_data = SpendLookupData::from_trace(&trace);
+    let range_check_multiplicities = spend_range_check_multiplicities(&lookup_data)
+        .map_err(|e| anyhow::anyhow!("Range-check trace generation failed: {}", e))?;
+    let (range_check_trace, range_check_column) = generate_range_check_trace(&range_check_multiplicities);
+    timings.trace_gen_ms = stopwatch.lap();
+    
+    // === Phase 2: Twiddles covering the largest component ===
+    let twiddle_log_size =
+        log_n_rows.max(RANGE_CHECK_LOG_SIZE) + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor;
+    let twiddles = twiddle_cache.get(twiddle_log_size);
+    timings.twiddles_ms = stopwatch.lap();
+    
+    // === Phase 3: Setup channel ===
+    let channel = &mut <H::Channel as MerkleChannel>::C::default();
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, H::Channel>::new(pcs_config, &twiddles);
+    if store_poly_coeffs {
+        commitment_scheme.set_store_polynomials_coefficients();
+    }
+    
+    // === Phase 4: Commit preprocessed trace (the range-check table's values) ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(gen_range_check_preprocessed_trace());
+    tree_builder.commit(channel);
+    let mut layout = CommitmentLayout::default();
+    layout.push(TreeRole::Preprocessed);
+    
+    // === Phase 5: Commit main traces ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(trace.clone());
+    tree_builder.extend_evals(range_check_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Trace);
+    timings.commits_ms = stopwatch.lap();
+    
+    // === Phase 6: Bind the statement, draw lookup elements, commit interaction traces ===
+    statement.mix_into(channel);
+    let range_check_lookup = RangeCheckElements::draw(channel);
+    let (interaction_trace, spend_claimed_sum) =
+        gen_spend_interaction_trace(log_n_rows, &lookup_data, &range_check_lookup);
+    let (range_check_interaction_trace, range_check_claimed_sum) =
+        gen_range_check_interaction_trace(&range_check_column, &range_check_lookup);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(interaction_trace);
+    tree_builder.extend_evals(range_check_interaction_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Interaction);
+    // `prove` commits the composition tree last
+    layout.push(TreeRole::Composition);
+    let claimed_sums = [spend_claimed_sum, range_check_claimed_sum];
+    channel.mix_felts(&claimed_sums);
+    timings.interaction_ms = stopwatch.lap();
+    
+    // === Phase 7: Create components AFTER commits ===
+    let mut components =
+        SpendComponents::assemble(log_n_rows, range_check_lookup, claimed_sums, statement, layout, H::KIND);
+    components.timings = timings;
+    
+    // === Phase 8: Generate proof ===
+    let stark_proof = tracing::info_span!("stark_prove")
+        .in_scope(|| prove(&components.provers(), channel, commitment_scheme))?;
+    components.layout.check(stark_proof.commitments.len())?;
+    components.timings.proof_ms = stopwatch.lap();
+    components.timings.total_ms = stopwatch.total();
+    
+    Ok((components, stark_proof))
+}
+
+/// Verify a Spend STARK proof against the statement the components were proven for
+pub fn verify_spend<H: ProofHasher>(
+    components: &SpendComponents,
+    proof: StarkProof<H>,
+) -> Result<(), VerificationFailure>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    verify_spend_against(components, &components.statement, proof)
+}
+
+/// Verify a Spend STARK proof against `statement`
+///
+/// `statement` is mixed into the channel where the prover mixed its own, so the proof only
+/// verifies against the statement it was made for.
+#[tracing::instrument(level = "info", skip_all)]
+pub fn verify_spend_against<H: ProofHasher>(
+    components: &SpendComponents,
+    statement: &SpendStatement,
+    proof: StarkProof<H>,
+) -> Result<(), VerificationFailure>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    check_proof_hasher::<H>(components.merkle_hasher)
+        .map_err(|e| VerificationFailure::new(VerificationPhase::Setup, e))?;
+
+    // Every chunk the Spend component claims must be answered by the range-check component
+    let [spend_claimed_sum, range_check_claimed_sum] = components.claimed_sums;
+    if spend_claimed_sum + range_check_claimed_sum != SecureField::from_u32_unchecked(0, 0, 0, 0) {
+        return Err(VerificationFailure::invalid_structure(
+            VerificationPhase::LookupElements,
+            "Spend range-check sums do not cancel",
+        ));
+    }
+    
+    let channel = &mut <H::Channel as MerkleChannel>::C::default();
+    let mut commitment_scheme = CommitmentSchemeVerifier::<H::Channel>::new(proof.config);
+    
+    replay_trace_commitments(
+        &mut commitment_scheme,
+        channel,
+        &components.trace_log_degree_bounds(),
+        gen_range_check_preprocessed_trace(),
+        &components.layout,
+        &proof,
+        |channel| {
+            statement.mix_into(channel);
+            RangeCheckElements::draw(channel);
+        },
+    )?;
+    channel.mix_felts(&components.claimed_sums);
+    
+    // Verify
+    let verifiers = components.verifiers();
+    check_sampled_columns(&verifiers, &components.trace_log_degree_bounds(), &proof)?;
+    verify(&verifiers, channel, &mut commitment_scheme, proof)
+        .map_err(|e| VerificationFailure::new(VerificationPhase::Verify, e))
+}
+
+/// The three components of a Split proof
+///
+/// `split` emits a lookup claim for each of its four Poseidon2 permutations and its amount chunks;
+/// `poseidon2` proves the table answering the permutations and `range_check` answers the chunks.
+/// The coins and commitment are claimed as public outputs, which the verifier answers from
+/// `outputs`. All three share every commitment tree.
+pub struct SplitComponents {
+    pub split: SplitComponent,
+    pub poseidon2: Poseidon2TableComponent,
+    pub range_check: RangeCheckComponent,
+    /// Logup sums of `split`, `poseidon2` and `range_check`, which add up to the outputs' logup
+    /// sum when every lookup is answered
+    pub claimed_sums: [SecureField; 3],
+    /// Public outputs the proof is bound to, read off the trace the proof commits
+    pub outputs: SplitOutputs,
+    /// Role of every root in the proof's commitments, as recorded while proving
+    pub layout: CommitmentLayout,
+    /// Wall-clock time of each proving phase
+    pub timings: ProvingTimings,
+}
+
+impl SplitComponents {
+    fn provers(&self) -> [&dyn ComponentProver<SimdBackend>; 3] {
+        [&self.split, &self.poseidon2, &self.range_check]
+    }
+
+    fn verifiers(&self) -> [&dyn Component; 3] {
+        [&self.split, &self.poseidon2, &self.range_check]
+    }
+
+    /// Column sizes of every component, per tree
+    pub fn trace_log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(self.verifiers().into_iter().map(|c| c.trace_log_degree_bounds()))
+    }
+
+    /// Components with `split` and `poseidon2` of `log_sizes` rows, drawing on `elements`
+    ///
+    /// The trace locations are allocated in the order the prover commits the columns.
+    fn assemble(
+        log_sizes: [u32; 2],
+        elements: SplitLookupElements,
+        claimed_sums: [SecureField; 3],
+        outputs: SplitOutputs,
+        layout: CommitmentLayout,
+    ) -> Self {
+        let [log_n_rows, table_log_size] = log_sizes;
+        let [split_claimed_sum, table_claimed_sum, range_check_claimed_sum] = claimed_sums;
+        let location_allocator = &mut TraceLocationAllocator::default();
+        Self {
+            split: SplitComponent::new(
+                location_allocator,
+                SplitEval {
+                    log_n_rows,
+                    remaining_coin_lookup: elements.remaining_coin.clone(),
+                    commitment_lookup: elements.commitment.clone(),
+                    range_check_lookup: elements.range_check.clone(),
+                    public_output_lookup: elements.public_output,
+                },
+                split_claimed_sum,
+            ),
+            poseidon2: Poseidon2TableComponent::new(
+                location_allocator,
+                Poseidon2TableEval {
+                    log_n_rows: table_log_size,
+                    nullifier_lookup: elements.nullifier,
+                    remaining_coin_lookup: elements.remaining_coin,
+                    commitment_lookup: elements.commitment,
+                    block_root_digest_lookup: elements.block_root_digest,
+                    burn_address_lookup: elements.burn_address,
+                },
+                table_claimed_sum,
+            ),
+            range_check: RangeCheckComponent::new(
+                location_allocator,
+                RangeCheckEval {
+                    lookup_elements: elements.range_check,
+                },
+                range_check_claimed_sum,
+            ),
+            claimed_sums,
+            outputs,
+            layout,
+            timings: ProvingTimings::default(),
+        }
+    }
+}
+
+/// Lookup elements of every Split relation, drawn after the main trace commitment
+///
+/// The outputs are mixed in first, so every element depends on them. The table component takes
+/// elements for all five Poseidon2 relations, though Split only claims coins and a commitment.
+struct SplitLookupElements {
+    nullifier: NullifierElements,
+    remaining_coin: RemainingCoinElements,
+    commitment: CommitmentElements,
+    block_root_digest: BlockRootDigestElements,
+    burn_address: BurnAddressElements,
+    range_check: RangeCheckElements,
+    public_output: SplitPublicOutputElements,
+}
+
+impl SplitLookupElements {
+    fn draw(channel: &mut impl Channel, outputs: &SplitOutputs) -> Self {
+        channel.mix_u32s(&split_public_output(outputs).map(|value| value.0));
+        Self {
+            nullifier: NullifierElements::draw(channel),
+            remaining_coin: RemainingCoinElements::draw(channel),
+            commitment: CommitmentElements::draw(channel),
+            block_root_digest: BlockRootDigestElements::draw(channel),
+            burn_address: BurnAddressElements::draw(channel),
+            range_check: RangeCheckElements::draw(channel),
+            public_output: SplitPublicOutputElements::draw(channel),
+        }
+    }
+
+    /// Logup sum of the claims the enabled Split rows make on `outputs`, once per witness row
+    fn public_sum(&self, outputs: &SplitOutputs) -> SecureField {
+        let denominator: SecureField = self.public_output.combine(&split_public_output(outputs));
+        SecureField::from(BaseField::from(N_LANES as u32)) * denominator.inverse()
+    }
+}
+
+/// Prove a Split statement using Circle STARKs
+pub fn prove_split(
+    inputs: &SplitInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(SplitComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_split_impl(inputs, log_n_rows, config, &TwiddleCache::default(), |_| {})
+}
+
+/// `prove_split` with `trace_hook` applied to the Split main trace before it is committed
+///
+/// For soundness tests only: whatever the hook changes, the prover or the verifier must reject.
+#[cfg(feature = "test-utils")]
+pub fn prove_split_with_trace_hook(
+    inputs: &SplitInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(SplitComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_split_impl(inputs, log_n_rows, config, &TwiddleCache::default(), trace_hook)
+}
+
+#[tracing::instrument(name = "prove_split", level = "info", skip_all, fields(log_n_rows))]
+fn prove_split_impl(
+    inputs: &SplitInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    twiddle_cache: &TwiddleCache,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(SplitComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    #[cfg(feature = "rayon")]
+    if needs_one_thread(&config) {
+        return on_one_thread(move || {
+            prove_split_impl(inputs, log_n_rows, config, twiddle_cache, trace_hook)
+        })?;
+    }
+    let mut stopwatch = Stopwatch::start();
+    let mut timings = ProvingTimings::default();
+
+    check_log_n_rows(log_n_rows)?;
+
+    // === Phase 1: Generate main execution traces (Split, Poseidon2 table, range check) ===
+    // The outputs are read off the trace as committed, so a hooked trace is bound to its own
+    let (mut trace, mut table_entries) = generate_split_trace(log_n_rows, inputs)
+        .map_err(|e| anyhow::anyhow!("Trace generation failed: {}", e))?;
+    trace_hook(&mut trace);
+    let outputs = split_outputs_from_trace(&trace);
+    let table_log_size = poseidon2_table_log_size(table_entries.len());
+    let (table_trace, table_lookup_data) =
+        generate_poseidon2_table_trace(table_log_size, &table_entries)
+            .map_err(|e| anyhow::anyhow!("Poseidon2 table generation failed: {}", e))?;
+    for entry in &mut table_entries {
+        zeroize_base_fields(&mut entry.input);
+    }
+    let range_check_multiplicities = split_range_check_multiplicities(&trace)
+        .map_err(|e| anyhow::anyhow!("Range-check trace generation failed: {}", e))?;
+    let (range_check_trace, range_check_column) = generate_range_check_trace(&range_check_multiplicities);
+    timings.trace_gen_ms = stopwatch.lap();
+
+    let store_poly_coeffs = config.store_poly_coeffs;
+    let min_log_size = log_n_rows.min(table_log_size).min(RANGE_CHECK_LOG_SIZE);
+    let pcs_config: PcsConfig = config.fit_to_trace(min_log_size).into();
+
+    // === Phase 2: Twiddles covering the largest component ===
+    let max_log_size = log_n_rows.max(table_log_size).max(RANGE_CHECK_LOG_SIZE);
+    let twiddle_log_size = max_log_size + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor;
+    let twiddles = twiddle_cache.get(twiddle_log_size);
+    timings.twiddles_ms = stopwatch.lap();
+
+    // === Phase 3: Setup channel ===
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(pcs_config, &twiddles);
+    if store_poly_coeffs {
+        commitment_scheme.set_store_polynomials_coefficients();
+    }
+
+    // === Phase 4: Commit preprocessed trace (the range-check table's values) ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(gen_range_check_preprocessed_trace());
+    tree_builder.commit(channel);
+    let mut layout = CommitmentLayout::default();
+    layout.push(TreeRole::Preprocessed);
+
+    // === Phase 5: Commit main traces ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(trace.clone());
+    tree_builder.extend_evals(table_trace);
+    tree_builder.extend_evals(range_check_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Trace);
+    timings.commits_ms = stopwatch.lap();
+
+    // === Phase 6: Mix the outputs, draw lookup elements, commit interaction traces ===
+    let elements = SplitLookupElements::draw(channel, &outputs);
+    let (interaction_trace, split_claimed_sum) = gen_split_interaction_trace(
+        log_n_rows,
+        &trace,
+        &elements.remaining_coin,
+        &elements.commitment,
+        &elements.range_check,
+        &elements.public_output,
+    );
+    let (table_interaction_trace, table_claimed_sum) = gen_poseidon2_table_interaction_trace(
+        table_log_size,
+        &table_lookup_data,
+        &elements.nullifier,
+        &elements.remaining_coin,
+        &elements.commitment,
+        &elements.block_root_digest,
+        &elements.burn_address,
+    );
+    let (range_check_interaction_trace, range_check_claimed_sum) =
+        gen_range_check_interaction_trace(&range_check_column, &elements.range_check);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(interaction_trace);
+    tree_builder.extend_evals(table_interaction_trace);
+    tree_builder.extend_evals(range_check_interaction_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Interaction);
+    // `prove` commits the composition tree last
+    layout.push(TreeRole::Composition);
+    let claimed_sums = [split_claimed_sum, table_claimed_sum, range_check_claimed_sum];
+    channel.mix_felts(&claimed_sums);
+    timings.interaction_ms = stopwatch.lap();
+
+    // === Phase 7: Create components AFTER commits ===
+    let log_sizes = [log_n_rows, table_log_size];
+    let mut components =
+        SplitComponents::assemble(log_sizes, elements, claimed_sums, outputs, layout);
+    components.timings = timings;
+
+    // === Phase 8: Generate proof ===
+    let stark_proof = tracing::info_span!("stark_prove")
+        .in_scope(|| prove(&components.provers(), channel, commitment_scheme))?;
+    components.layout.check(stark_proof.commitments.len())?;
+    components.timings.proof_ms = stopwatch.lap();
+    components.timings.total_ms = stopwatch.total();
+
+    Ok((components, stark_proof))
+}
+
+/// Verify a Split STARK proof against the outputs the components were proven for
+///
+/// The outputs are mixed into the channel and answer the Split AIR's public output claims, so the
+/// proof only verifies together with the coins and commitment it was made for.
+#[tracing::instrument(level = "info", skip_all)]
+pub fn verify_split(
+    components: &SplitComponents,
+    proof: StarkProof<Blake2sMerkleHasher>,
+) -> Result<(), VerificationError> {
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(proof.config);
+
+    let mut elements = None;
+    replay_trace_commitments(
+        &mut commitment_scheme,
+        channel,
+        &components.trace_log_degree_bounds(),
+        gen_range_check_preprocessed_trace(),
+        &components.layout,
+        &proof,
+        |channel| {
+            elements = Some(SplitLookupElements::draw(channel, &components.outputs));
+        },
+    )?;
+    let elements = elements.ok_or_else(|| {
+        VerificationError::InvalidStructure("Split proof has no interaction tree".to_string())
+    })?;
+
+    // Every lookup the Split component claims must be answered by the table and range-check
+    // components, except the public output claims, which the outputs answer
+    let [split_claimed_sum, table_claimed_sum, range_check_claimed_sum] = components.claimed_sums;
+    let claimed_sum = split_claimed_sum + table_claimed_sum + range_check_claimed_sum;
+    if claimed_sum != elements.public_sum(&components.outputs) {
+        return Err(VerificationError::InvalidStructure(
+            "Split lookup sums do not cancel".to_string(),
+        ));
+    }
+    channel.mix_felts(&components.claimed_sums);
+
+    verify(&components.verifiers(), channel, &mut commitment_scheme, proof)
+}
+
+/// The two components of a Merge proof
+///
+/// `merge` emits a range-check claim for each of its balance chunks and `range_check` answers them.
+/// Both share every commitment tree.
+pub struct MergeComponents {
+    pub merge: MergeComponent,
+    pub range_check: RangeCheckComponent,
+    /// Logup sums of `merge` and `range_check`, which cancel when every claim is answered
+    pub claimed_sums: [SecureField; 2],
+    /// Role of every root in the proof's commitments, as recorded while proving
+    pub layout: CommitmentLayout,
+    /// Wall-clock time of each proving phase
+    pub timings: ProvingTimings,
+}
+
+impl MergeComponents {
+    fn provers(&self) -> [&dyn ComponentProver<SimdBackend>; 2] {
+        [&self.merge, &self.range_check]
+    }
+
+    fn verifiers(&self) -> [&dyn Component; 2] {
+        [&self.merge, &self.range_check]
+    }
+
+    /// Column sizes of every component, per tree
+    pub fn trace_log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(self.verifiers().into_iter().map(|c| c.trace_log_degree_bounds()))
+    }
+}
+
+/// Prove a Merge statement using Circle STARKs
+pub fn prove_merge(
+    inputs: &MergeInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(MergeComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_merge_impl(inputs, log_n_rows, config, &TwiddleCache::default(), |_| {})
+}
+
+/// `prove_merge` with `trace_hook` applied to the Merge main trace before it is committed
+///
+/// For soundness tests only: whatever the hook changes, the prover or the verifier must reject.
+#[cfg(feature = "test-utils")]
+pub fn prove_merge_with_trace_hook(
+    inputs: &MergeInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(MergeComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_merge_impl(inputs, log_n_rows, config, &TwiddleCache::default(), trace_hook)
+}
+
+#[tracing::instrument(name = "prove_merge", level = "info", skip_all, fields(log_n_rows))]
+fn prove_merge_impl(
+    inputs: &MergeInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    twiddle_cache: &TwiddleCache,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(MergeComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    #[cfg(feature = "rayon")]
+    if needs_one_thread(&config) {
+        return on_one_thread(move || {
+            prove_merge_impl(inputs, log_n_rows, config, twiddle_cache, trace_hook)
+        })?;
+    }
+    let mut stopwatch = Stopwatch::start();
+    let mut timings = ProvingTimings::default();
+
+    check_log_n_rows(log_n_rows)?;
+
+    let store_poly_coeffs = config.store_poly_coeffs;
+    let pcs_config: PcsConfig = config.fit_to_trace(log_n_rows.min(RANGE_CHECK_LOG_SIZE)).into();
+
+    // === Phase 1: Generate main execution traces (Merge, range check) ===
+    let mut trace = generate_merge_trace(log_n_rows, inputs)
+        .map_err(|e| anyhow::anyhow!("Trace generation failed: {}", e))?;
+    trace_hook(&mut trace);
+    let range_check_multiplicities = merge_range_check_multiplicities(log_n_rows, &trace)
+        .map_err(|e| anyhow::anyhow!("Range-check trace generation failed: {}", e))?;
+    let (range_check_trace, range_check_column) = generate_range_check_trace(&range_check_multiplicities);
+    timings.trace_gen_ms = stopwatch.lap();
+
+    // === Phase 2: Twiddles covering the largest component ===
+    let twiddle_log_size =
+        log_n_rows.max(RANGE_CHECK_LOG_SIZE) + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor;
+    let twiddles = twiddle_cache.get(twiddle_log_size);
+    timings.twiddles_ms = stopwatch.lap();
+
+    // === Phase 3: Setup channel ===
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(pcs_config, &twiddles);
+    if store_poly_coeffs {
+        commitment_scheme.set_store_polynomials_coefficients();
+    }
+
+    // === Phase 4: Commit preprocessed trace (the range-check table's values) ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(gen_range_check_preprocessed_trace());
+    tree_builder.commit(channel);
+    let mut layout = CommitmentLayout::default();
+    layout.push(TreeRole::Preprocessed);
+
+    // === Phase 5: Commit main traces ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(trace.clone());
+    tree_builder.extend_evals(range_check_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Trace);
+    timings.commits_ms = stopwatch.lap();
+
+    // === Phase 6: Draw lookup elements, generate and commit interaction traces ===
+    let range_check_lookup = RangeCheckElements::draw(channel);
+    let (interaction_trace, merge_claimed_sum) =
+        gen_merge_interaction_trace(log_n_rows, &trace, &range_check_lookup);
+    let (range_check_interaction_trace, range_check_claimed_sum) =
+        gen_range_check_interaction_trace(&range_check_column, &range_check_lookup);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(interaction_trace);
+    tree_builder.extend_evals(range_check_interaction_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Interaction);
+    // `prove` commits the composition tree last
+    layout.push(TreeRole::Composition);
+    let claimed_sums = [merge_claimed_sum, range_check_claimed_sum];
+    channel.mix_felts(&claimed_sums);
+    timings.interaction_ms = stopwatch.lap();
+
+    // === Phase 7: Create components AFTER commits ===
+    let location_allocator = &mut TraceLocationAllocator::default();
+    let mut components = MergeComponents {
+        merge: MergeComponent::new(
+            location_allocator,
+            MergeEval {
+                log_n_rows,
+                range_check_lookup: range_check_lookup.clone(),
+            },
+            merge_claimed_sum,
+        ),
+        range_check: RangeCheckComponent::new(
+            location_allocator,
+            RangeCheckEval {
+                lookup_elements: range_check_lookup,
+            },
+            range_check_claimed_sum,
+        ),
+        claimed_sums,
+        layout,
+        timings,
+    };
+
+    // === Phase 8: Generate proof ===
+    let stark_proof = tracing::info_span!("stark_prove")
+        .in_scope(|| prove(&components.provers(), channel, commitment_scheme))?;
+    components.layout.check(stark_proof.commitments.len())?;
+    components.timings.proof_ms = stopwatch.lap();
+    components.timings.total_ms = stopwatch.total();
+
+    Ok((components, stark_proof))
+}
+
+/// Verify a Merge STARK proof
+#[tracing::instrument(level = "info", skip_all)]
+pub fn verify_merge(
+    components: &MergeComponents,
+    proof: StarkProof<Blake2sMerkleHasher>,
+) -> Result<(), VerificationError> {
+    // Every chunk the Merge component claims must be answered by the range-check component
+    let [merge_claimed_sum, range_check_claimed_sum] = components.claimed_sums;
+    if merge_claimed_sum + range_check_claimed_sum != SecureField::from_u32_unchecked(0, 0, 0, 0) {
+        return Err(VerificationError::InvalidStructure(
+            "Merge range-check sums do not cancel".to_string(),
+        ));
+    }
+
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(proof.config);
+
+    replay_trace_commitments(
+        &mut commitment_scheme,
+        channel,
+        &components.trace_log_degree_bounds(),
+        gen_range_check_preprocessed_trace(),
+        &components.layout,
+        &proof,
+        |channel| {
+            RangeCheckElements::draw(channel);
+        },
+    )?;
+    channel.mix_felts(&components.claimed_sums);
+
+    verify(&components.verifiers(), channel, &mut commitment_scheme, proof)
+}
+
+/// The two components of a Transfer proof
+///
+/// `transfer` emits a range-check claim for each of its balance chunks and `range_check` answers them.
+/// Both share every commitment tree.
+pub struct TransferComponents {
+    pub transfer: TransferComponent,
+    pub range_check: RangeCheckComponent,
+    /// Logup sums of `transfer` and `range_check`, which cancel when every claim is answered
+    pub claimed_sums: [SecureField; 2],
+    /// Role of every root in the proof's commitments, as recorded while proving
+    pub layout: CommitmentLayout,
+    /// Wall-clock time of each proving phase
+    pub timings: ProvingTimings,
+}
+
+impl TransferComponents {
+    fn provers(&self) -> [&dyn ComponentProver<SimdBackend>; 2] {
+        [&self.transfer, &self.range_check]
+    }
+
+    fn verifiers(&self) -> [&dyn Component; 2] {
+        [&self.transfer, &self.range_check]
+    }
+
+    /// Column sizes of every component, per tree
+    pub fn trace_log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(self.verifiers().into_iter().map(|c| c.trace_log_degree_bounds()))
+    }
+}
+
+/// Prove a Transfer statement using Circle STARKs
+pub fn prove_transfer(
+    inputs: &TransferInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+) -> Result<(TransferComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_transfer_impl(inputs, log_n_rows, config, &TwiddleCache::default(), |_| {})
+}
+
+/// `prove_transfer` with `trace_hook` applied to the Transfer main trace before it is committed
+///
+/// For soundness tests only: whatever the hook changes, the prover or the verifier must reject.
+#[cfg(feature = "test-utils")]
+pub fn prove_transfer_with_trace_hook(
+    inputs: &TransferInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(TransferComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    prove_transfer_impl(inputs, log_n_rows, config, &TwiddleCache::default(), trace_hook)
+}
+
+#[tracing::instrument(name = "prove_transfer", level = "info", skip_all, fields(log_n_rows))]
+fn prove_transfer_impl(
+    inputs: &TransferInputs,
+    log_n_rows: u32,
+    config: StarkConfig,
+    twiddle_cache: &TwiddleCache,
+    trace_hook: impl FnOnce(&mut TraceColumns) + Send,
+) -> Result<(TransferComponents, StarkProof<Blake2sMerkleHasher>), anyhow::Error> {
+    #[cfg(feature = "rayon")]
+    if needs_one_thread(&config) {
+        return on_one_thread(move || {
+            prove_transfer_impl(inputs, log_n_rows, config, twiddle_cache, trace_hook)
+        })?;
+    }
+    let mut stopwatch = Stopwatch::start();
+    let mut timings = ProvingTimings::default();
+
+    check_log_n_rows(log_n_rows)?;
+
+    let store_poly_coeffs = config.store_poly_coeffs;
+    let pcs_config: PcsConfig = config.fit_to_trace(log_n_rows.min(RANGE_CHECK_LOG_SIZE)).into();
+
+    // === Phase 1: Generate main execution traces (Transfer, range check) ===
+    let mut trace = generate_transfer_trace(log_n_rows, inputs)
+        .map_err(|e| anyhow::anyhow!("Trace generation failed: {}", e))?;
+    trace_hook(&mut trace);
+    let range_check_multiplicities = transfer_range_check_multiplicities(log_n_rows, &trace)
+        .map_err(|e| anyhow::anyhow!("Range-check trace generation failed: {}", e))?;
+    let (range_check_trace, range_check_column) = generate_range_check_trace(&range_check_multiplicities);
+    timings.trace_gen_ms = stopwatch.lap();
+
+    // === Phase 2: Twiddles covering the largest component ===
+    let twiddle_log_size =
+        log_n_rows.max(RANGE_CHECK_LOG_SIZE) + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor;
+    let twiddles = twiddle_cache.get(twiddle_log_size);
+    timings.twiddles_ms = stopwatch.lap();
+
+    // === Phase 3: Setup channel ===
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme =
+        CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(pcs_config, &twiddles);
+    if store_poly_coeffs {
+        commitment_scheme.set_store_polynomials_coefficients();
+    }
+
+    // === Phase 4: Commit preprocessed trace (the range-check table's values) ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(gen_range_check_preprocessed_trace());
+    tree_builder.commit(channel);
+    let mut layout = CommitmentLayout::default();
+    layout.push(TreeRole::Preprocessed);
+
+    // === Phase 5: Commit main traces ===
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(trace.clone());
+    tree_builder.extend_evals(range_check_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Trace);
+    timings.commits_ms = stopwatch.lap();
+
+    // === Phase 6: Draw lookup elements, generate and commit interaction traces ===
+    let range_check_lookup = RangeCheckElements::draw(channel);
+    let (interaction_trace, transfer_claimed_sum) =
+        gen_transfer_interaction_trace(log_n_rows, &trace, &range_check_lookup);
+    let (range_check_interaction_trace, range_check_claimed_sum) =
+        gen_range_check_interaction_trace(&range_check_column, &range_check_lookup);
+    let mut tree_builder = commitment_scheme.tree_builder();
+    tree_builder.extend_evals(interaction_trace);
+    tree_builder.extend_evals(range_check_interaction_trace);
+    tree_builder.commit(channel);
+    layout.push(TreeRole::Interaction);
+    // `prove` commits the composition tree last
+    layout.push(TreeRole::Composition);
+    let claimed_sums = [transfer_claimed_sum, range_check_claimed_sum];
+    channel.mix_felts(&claimed_sums);
+    timings.interaction_ms = stopwatch.lap();
+
+    // === Phase 7: Create components AFTER commits ===
+    let location_allocator = &mut TraceLocationAllocator::default();
+    let mut components = TransferComponents {
+        transfer: TransferComponent::new(
+            location_allocator,
+            TransferEval {
+                log_n_rows,
+                range_check_lookup: range_check_lookup.clone(),
+            },
+            transfer_claimed_sum,
+        ),
+        range_check: RangeCheckComponent::new(
+            location_allocator,
+            RangeCheckEval {
+                lookup_elements: range_check_lookup,
+            },
+            range_check_claimed_sum,
+        ),
+        claimed_sums,
+        layout,
+        timings,
+    };
+
+    // === Phase 8: Generate proof ===
+    let stark_proof = tracing::info_span!("stark_prove")
+        .in_scope(|| prove(&components.provers(), channel, commitment_scheme))?;
+    components.layout.check(stark_proof.commitments.len())?;
+    components.timings.proof_ms = stopwatch.lap();
+    components.timings.total_ms = stopwatch.total();
+
+    Ok((components, stark_proof))
+}
+
+/// Verify a Transfer STARK proof
+#[tracing::instrument(level = "info", skip_all)]
+pub fn verify_transfer(
+    components: &TransferComponents,
+    proof: StarkProof<Blake2sMerkleHasher>,
+) -> Result<(), VerificationError> {
+    // Every chunk the Transfer component claims must be answered by the range-check component
+    let [transfer_claimed_sum, range_check_claimed_sum] = components.claimed_sums;
+    if transfer_claimed_sum + range_check_claimed_sum != SecureField::from_u32_unchecked(0, 0, 0, 0) {
+        return Err(VerificationError::InvalidStructure(
+            "Transfer range-check sums do not cancel".to_string(),
+        ));
+    }
+
+    let channel = &mut Blake2sChannel::default();
+    let mut commitment_scheme = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(proof.config);
+
+    replay_trace_commitments(
+        &mut commitment_scheme,
+        channel,
+        &components.trace_log_degree_bounds(),
+        gen_range_check_preprocessed_trace(),
+        &components.layout,
+        &proof,
+        |channel| {
+            RangeCheckElements::draw(channel);
+        },
+    )?;
+    channel.mix_felts(&components.claimed_sums);
+
+    verify(&components.verifiers(), channel, &mut commitment_scheme, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::field::M31;
+    use crate::secret::Secret;
+    use alloy_primitives::U256;
+    
+    fn create_test_pob_inputs() -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            // Use smaller values that fit within M31 after conversion
+            actual_balance: U256::from(1000000u64),  // 1M instead of 1e18
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),     // 500K instead of 5e17
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+    
+    fn create_test_spend_inputs() -> SpendInputs {
+        SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        }
+    }
+    
+    #[test]
+    fn test_prove_and_verify_pob() {
+        let inputs = create_test_pob_inputs();
+        let log_n_rows = 6; // 64 rows - safe minimum for twiddles
+        let config = StarkConfig::default();
+        
+        // Generate proof
+        let (component, proof) = prove_proof_of_burn(&inputs, log_n_rows, config)
+            .expect("Failed to generate proof");
+        
+        // Verify proof
+        let result = verify_proof_of_burn(&component, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+    
+    #[test]
+    fn test_prove_and_verify_spend() {
+        let inputs = create_test_spend_inputs();
+        let log_n_rows = 6; // 64 rows - safe minimum for twiddles
+        let config = StarkConfig::default();
+        
+        // Generate proof
+        let (component, proof) = prove_spend(&inputs, log_n_rows, config)
+            .expect("Failed to generate proof");
+        
+        // Verify proof
+        let result = verify_spend(&component, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+    
+    #[test]
+    fn test_spend_preprocessed_tree_round_trips() {
+        // The range-check table is a preprocessed column of the Spend proof; the verifier must
+        // commit the preprocessed tree with its size rather than as an empty tree
+        let (components, proof) = prove_spend(&create_test_spend_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let sizes = components.trace_log_degree_bounds();
+        assert_eq!(sizes[0], vec![RANGE_CHECK_LOG_SIZE]);
+        
+        let result = verify_spend(&components, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+    
+    #[test]
+    fn test_pob_preprocessed_tree_is_pinned() {
+        use stwo_prover::prover::backend::Column;
+
+        let log_n_rows = 6;
+        let (components, proof) = prove_proof_of_burn(&create_test_pob_inputs(), log_n_rows, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert_eq!(components.trace_log_degree_bounds()[0], vec![log_n_rows, RANGE_CHECK_LOG_SIZE]);
+        assert_eq!(proof.commitments[0], preprocessed_root::<Blake2sMerkleHasher>(components.preprocessed_trace(), proof.config));
+
+        // An is_first selecting another row, and a tree without is_first
+        let mut moved = IsFirst::new(log_n_rows).gen_column();
+        moved.values.set(0, BaseField::from(0));
+        moved.values.set(1, BaseField::from(1));
+        let altered = [vec![moved], gen_range_check_preprocessed_trace()].concat();
+        let omitted = gen_range_check_preprocessed_trace();
+        for columns in [altered, omitted] {
+            let mut forged = proof.clone();
+            forged.0.commitments[0] = preprocessed_root::<Blake2sMerkleHasher>(columns, proof.config);
+            assert!(matches!(
+                verify_proof_of_burn(&components, forged),
+                Err(VerificationFailure {
+                    phase: VerificationPhase::CommitmentReplay { tree: TreeRole::Preprocessed },
+                    source: VerificationError::InvalidStructure(_),
+                    ..
+                })
+            ));
+        }
+
+        let result = verify_proof_of_burn(&components, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+
+    #[test]
+    fn test_spend_rejects_altered_range_check_table() {
+        use stwo_prover::prover::backend::Column;
+
+        let (components, mut proof) = prove_spend(&create_test_spend_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+
+        // A table with one value out of range would answer claims on it
+        let mut table = gen_range_check_preprocessed_trace();
+        table[0].values.set(1, BaseField::from(1 << RANGE_CHECK_LOG_SIZE));
+        proof.0.commitments[0] = preprocessed_root::<Blake2sMerkleHasher>(table, proof.config);
+        assert!(matches!(
+            verify_spend(&components, proof),
+            Err(VerificationFailure {
+                phase: VerificationPhase::CommitmentReplay { tree: TreeRole::Preprocessed },
+                source: VerificationError::InvalidStructure(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_stored_coefficients_do_not_change_the_proof() {
+        let inputs = create_test_pob_inputs();
+        let prove = |store_poly_coeffs| {
+            let config = StarkConfig { store_poly_coeffs, ..StarkConfig::default() };
+            prove_proof_of_burn(&inputs, 6, config).expect("Failed to generate proof")
+        };
+        let (components, without) = prove(false);
+        let (_, with) = prove(true);
+        assert_eq!(serde_json::to_vec(&without).unwrap(), serde_json::to_vec(&with).unwrap());
+        verify_proof_of_burn(&components, without).expect("Verification failed");
+
+        let config = StarkConfig { store_poly_coeffs: false, ..StarkConfig::default() };
+        let (components, proof) = prove_spend(&create_test_spend_inputs(), 6, config).expect("Failed to generate proof");
+        verify_spend(&components, proof).expect("Verification failed");
+    }
+
+    #[test]
+    fn test_deterministic_proofs_are_reproducible() {
+        let inputs = create_test_pob_inputs();
+        for deterministic in [true, false] {
+            let config = StarkConfig { deterministic, ..StarkConfig::default() };
+            let (components, proof) = prove_proof_of_burn(&inputs, 6, config.clone())
+                .expect("Failed to generate proof");
+            let (_, again) = prove_proof_of_burn(&inputs, 6, config).expect("Failed to generate proof");
+            if deterministic {
+                assert_eq!(serde_json::to_vec(&proof).unwrap(), serde_json::to_vec(&again).unwrap());
+            }
+            let result = verify_proof_of_burn(&components, proof);
+            assert!(result.is_ok(), "Verification failed: {:?}", result);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_deterministic_trace_generation_runs_on_one_thread() {
+        let config = StarkConfig { deterministic: true, ..StarkConfig::default() };
+        let threads = std::sync::Mutex::new(0);
+        let hook = |_: &mut TraceColumns| *threads.lock().unwrap() = rayon::current_num_threads();
+        prove_proof_of_burn_impl::<Blake2sMerkleHasher>(
+            &create_test_pob_inputs(),
+            6,
+            config,
+            &TwiddleCache::default(),
+            Some(hook),
+        )
+        .expect("Failed to generate proof");
+        assert_eq!(*threads.lock().unwrap(), 1);
+    }
+    
+    /// Records the name of every span opened while it is the default subscriber
+    #[derive(Clone, Default)]
+    struct SpanNames(std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>);
+    
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanNames {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.0.lock().unwrap().push(attrs.metadata().name());
+        }
+    }
+    
+    #[test]
+    fn test_proving_emits_phase_spans() {
+        use tracing_subscriber::layer::SubscriberExt;
+        
+        let names = SpanNames::default();
+        let subscriber = tracing_subscriber::registry().with(names.clone());
+        let inputs = create_test_pob_inputs();
+        tracing::subscriber::with_default(subscriber, || {
+            let (components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+                .expect("Failed to generate proof");
+            verify_proof_of_burn(&components, proof).expect("Verification failed");
+        });
+        
+        let names = names.0.lock().unwrap();
+        for span in [
+            "prove_proof_of_burn",
+            "generate_pob_trace",
+            "gen_interaction_trace",
+            "precompute_twiddles",
+            "stark_prove",
+            "verify_proof_of_burn",
+        ] {
+            assert!(names.contains(&span), "span {} not emitted, got {:?}", span, names);
+        }
+    }
+    
+    #[test]
+    fn test_named_commitments_replay() {
+        let inputs = create_test_pob_inputs();
+        let (component, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        
+        let commitments = ProofCommitments::from_proof(&proof, true).unwrap();
+        assert_eq!(commitments.preprocessed, proof.commitments[0]);
+        assert_eq!(commitments.trace, proof.commitments[1]);
+        assert_eq!(commitments.interaction, Some(proof.commitments[2]));
+        assert_eq!(commitments.composition, *proof.commitments.last().unwrap());
+        
+        // PoB commits an interaction trace for its Poseidon2 lookups, so a 3-tree layout must be rejected
+        assert!(ProofCommitments::from_proof(&proof, false).is_err());
+        
+        // Feed the named roots back to a verifier by hand
+        let channel = &mut Blake2sChannel::default();
+        let mut commitment_scheme = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(proof.config);
+        let sizes = component.trace_log_degree_bounds();
+        commitment_scheme.commit(commitments.preprocessed, &sizes[0], channel);
+        commitment_scheme.commit(commitments.trace, &sizes[1], channel);
+        PobLookupElements::draw(channel, &component.scope, component.instance_values());
+        commitment_scheme.commit(commitments.interaction.unwrap(), &sizes[2], channel);
+        channel.mix_felts(&component.claimed_sums);
+        
+        let result = verify(&component.verifiers(), channel, &mut commitment_scheme, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+    
+    #[test]
+    fn test_pob_rejects_unbalanced_lookups() {
+        let inputs = create_test_pob_inputs();
+        let (mut components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        
+        // A table that answers different lookups leaves the sums unbalanced
+        components.claimed_sums[1] += SecureField::from_u32_unchecked(1, 0, 0, 0);
+        assert!(verify_proof_of_burn(&components, proof).is_err());
+    }
+    
+    #[test]
+    fn test_pob_rejects_unanswered_block_root() {
+        let inputs = create_test_pob_inputs();
+        let (mut components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        
+        // Dropping the Keccak component's root answers leaves the PoB root lookups unbalanced
+        components.claimed_sums[2] = SecureField::from_u32_unchecked(0, 0, 0, 0);
+        assert!(verify_proof_of_burn(&components, proof).is_err());
+    }
+    
+    #[test]
+    fn test_pob_rejects_unanswered_range_checks() {
+        let inputs = create_test_pob_inputs();
+        let (mut components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        
+        // Without the range-check table's answers the balance chunks are unproven
+        components.claimed_sums[3] = SecureField::from_u32_unchecked(0, 0, 0, 0);
+        assert!(verify_proof_of_burn(&components, proof).is_err());
+    }
+    
+    fn create_test_split_inputs() -> SplitInputs {
+        SplitInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            amount_a: U256::from(300),
+            amount_b: U256::from(700),
+            extra_commitment: M31::from(100),
+        }
+    }
+
+    #[test]
+    fn test_prove_and_verify_split() {
+        let (components, proof) = prove_split(&create_test_split_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let result = verify_split(&components, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+
+    #[test]
+    fn test_split_rejects_unbalanced_amounts() {
+        let mut inputs = create_test_split_inputs();
+        inputs.amount_b = U256::from(800);
+        assert!(prove_split(&inputs, 6, StarkConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_split_rejects_unanswered_range_checks() {
+        let (mut components, proof) = prove_split(&create_test_split_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+
+        components.claimed_sums[2] = SecureField::from_u32_unchecked(0, 0, 0, 0);
+        assert!(verify_split(&components, proof).is_err());
+    }
+
+    #[test]
+    fn test_split_outputs_are_bound() {
+        use crate::circuits::split::SplitCircuit;
+
+        let inputs = create_test_split_inputs();
+        let (mut components, proof) = prove_split(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let expected = SplitCircuit::new(inputs).unwrap().compute_outputs();
+        assert_eq!(components.outputs.commitment, expected.commitment);
+        assert_eq!(components.outputs.child_coin_b, expected.child_coin_b);
+
+        // The same proof claimed for another child coin must not verify
+        components.outputs.child_coin_b = components.outputs.child_coin_a;
+        assert!(verify_split(&components, proof).is_err());
+    }
+
+    fn create_test_merge_inputs() -> MergeInputs {
+        MergeInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance_a: U256::from(300),
+            balance_b: U256::from(700),
+            merged_balance: U256::from(1000),
+            extra_commitment: M31::from(100),
+        }
+    }
+
+    #[test]
+    fn test_prove_and_verify_merge() {
+        let (components, proof) = prove_merge(&create_test_merge_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let result = verify_merge(&components, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+
+    #[test]
+    fn test_merge_rejects_wrong_merged_balance() {
+        let mut inputs = create_test_merge_inputs();
+        inputs.merged_balance = U256::from(1001);
+        assert!(prove_merge(&inputs, 6, StarkConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_merge_rejects_unanswered_range_checks() {
+        let (mut components, proof) = prove_merge(&create_test_merge_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+
+        components.claimed_sums[1] = SecureField::from_u32_unchecked(0, 0, 0, 0);
+        assert!(verify_merge(&components, proof).is_err());
+    }
+
+    fn create_test_transfer_inputs() -> TransferInputs {
+        TransferInputs {
+            sender_burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            recipient_key_commitment: M31::from(67890),
+        }
+    }
+
+    #[test]
+    fn test_prove_and_verify_transfer() {
+        let (components, proof) = prove_transfer(&create_test_transfer_inputs(), 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let result = verify_transfer(&components, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+
+    #[test]
+    fn test_transfer_rejects_mismatched_balance() {
+        use crate::circuits::layout::TRANSFER_LAYOUT;
+        use stwo_prover::prover::backend::Column;
+
+        // Re-issue the coin with one unit more than the sender's coin holds
+        let result = prove_transfer_impl(&create_test_transfer_inputs(), 6, StarkConfig::default(), &TwiddleCache::default(), |trace| {
+            let values = &mut trace[TRANSFER_LAYOUT.new_balance().start].values;
+            values.set(0, values.at(0) + BaseField::from(1));
+        });
+        if let Ok((components, proof)) = result {
+            assert!(verify_transfer(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_spend_rejects_unanswered_range_checks() {
+        let inputs = create_test_spend_inputs();
+        let (mut components, proof) = prove_spend(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        
+        components.claimed_sums[1] = SecureField::from_u32_unchecked(0, 0, 0, 0);
+        assert!(verify_spend(&components, proof).is_err());
+    }
+    
+    #[test]
+    fn test_prove_and_verify_keccak_component() {
+        let header = vec![0x5au8; 300];
+        let log_size = keccak_log_size(header.len());
+        let pcs_config: PcsConfig = StarkConfig::default().into();
+        let twiddles = SimdBackend::precompute_twiddles(
+            CanonicCoset::new(log_size + LOG_EXPAND + pcs_config.fri_config.log_blowup_factor)
+                .circle_domain()
+                .half_coset,
+        );
+        
+        // Prove the Keccak component alone; with no root lookups its round links cancel
+        let channel = &mut Blake2sChannel::default();
+        let mut commitment_scheme =
+            CommitmentSchemeProver::<_, Blake2sMerkleChannel>::new(pcs_config, &twiddles);
+        commitment_scheme.set_store_polynomials_coefficients();
+        commitment_scheme.tree_builder().commit(channel);
+        let (trace, lookup_data) = generate_keccak_trace(log_size, &header, 0).unwrap();
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(trace);
+        tree_builder.commit(channel);
+        let keccak_state_lookup = KeccakStateElements::draw(channel);
+        let block_root_lookup = BlockRootElements::draw(channel);
+        let (interaction_trace, claimed_sum) =
+            gen_keccak_interaction_trace(log_size, &lookup_data, &keccak_state_lookup, &block_root_lookup);
+        assert_eq!(claimed_sum, SecureField::from_u32_unchecked(0, 0, 0, 0));
+        let mut tree_builder = commitment_scheme.tree_builder();
+        tree_builder.extend_evals(interaction_trace);
+        tree_builder.commit(channel);
+        let component = KeccakComponent::new(
+            &mut TraceLocationAllocator::default(),
+            KeccakEval { log_n_rows: log_size, keccak_state_lookup, block_root_lookup },
+            claimed_sum,
+        );
+        let proof = prove(&[&component], channel, commitment_scheme).expect("Failed to generate proof");
+        
+        // Verify
+        let channel = &mut Blake2sChannel::default();
+        let mut commitment_scheme = CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(proof.config);
+        let sizes = component.trace_log_degree_bounds();
+        for (position, tree) in CommitmentLayout::standard(true).trace_trees() {
+            if tree == 2 {
+                KeccakStateElements::draw(channel);
+                BlockRootElements::draw(channel);
+            }
+            commitment_scheme.commit(proof.commitments[position], &sizes[tree], channel);
+        }
+        let result = verify(&[&component], channel, &mut commitment_scheme, proof);
+        assert!(result.is_ok(), "Verification failed: {:?}", result);
+    }
+    
+    #[test]
+    fn test_pob_rejects_foreign_table_component() {
+        let inputs = create_test_pob_inputs();
+        let mut other_inputs = create_test_pob_inputs();
+        other_inputs.burn_key = Secret::new(M31::from(54321));
+        
+        let (mut components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_proof_of_burn(&other_inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        
+        // The table trace committed in `proof` does not match the swapped-in component
+        components.poseidon2 = other_components.poseidon2;
+        components.claimed_sums[1] = other_components.claimed_sums[1];
+        assert!(verify_proof_of_burn(&components, proof).is_err());
+    }
+    
+    #[test]
+    fn test_fri_params_for_proves_every_trace_size() {
+        for security_bits in [80, 96, 128] {
+            for log_n_rows in 4..=16 {
+                let config = StarkConfig::for_trace(log_n_rows, security_bits);
+                assert!(config.security_bits() >= security_bits);
+                assert!(config.is_compatible(log_n_rows));
+
+                let (components, proof) = prove_spend(&create_test_spend_inputs(), log_n_rows, config)
+                    .unwrap_or_else(|e| panic!("log_n_rows {log_n_rows}, {security_bits} bits: {e}"));
+                assert_eq!(proof.config.fri_config, fri_params_for(log_n_rows, security_bits));
+                verify_spend(&components, proof).expect("Verification failed");
+            }
+        }
+    }
+
+    #[test]
+    fn test_incompatible_config_falls_back() {
+        // A last layer at the trace size would leave the trace columns unfolded
+        let config = StarkConfig {
+            fri_config: FriConfig::new(6, 1, 64),
+            ..StarkConfig::default()
+        };
+        assert!(!config.is_compatible(6));
+
+        let (components, proof) = prove_proof_of_burn(&create_test_pob_inputs(), 6, config.clone())
+            .expect("Prover should fall back instead of panicking");
+        let fallback = StarkConfig {
+            pow_bits: proof.config.pow_bits,
+            fri_config: proof.config.fri_config,
+            ..StarkConfig::default()
+        };
+        assert!(fallback.is_compatible(6));
+        assert!(fallback.security_bits() >= config.security_bits());
+        verify_proof_of_burn(&components, proof).expect("Verification failed");
+    }
+
+    #[test]
+    fn test_config_must_select_the_provers_hasher() {
+        let config = StarkConfig { merkle_hasher: MerkleHasherKind::Poseidon252, ..StarkConfig::default() };
+        let err = prove_proof_of_burn(&create_test_pob_inputs(), 6, config).err().expect("Blake2s prover");
+        assert!(err.to_string().contains("poseidon252"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_log_n_rows() {
+        let inputs = create_test_pob_inputs();
+        let config = StarkConfig::default();
+        
+        // Too small
+        let result = prove_proof_of_burn(&inputs, 2, config.clone());
+        assert!(result.is_err());
+        
+        // Too large
+        let result = prove_proof_of_burn(&inputs, 25, config);
+        assert!(result.is_err());
+    }
+}
+
@@ -0,0 +1,614 @@
+// Offline envelope verification
+// `ProofEnvelope::verify_offline` verifies an envelope from its bytes alone: no prover, no
+// components handed over by whoever proved it, no state outside the envelope. Since v14 an
+// envelope carries its `ComponentClaims`, the component sizes and logup sums the prover
+// committed to; the lookup elements are redrawn by replaying the proof's commitments, as the
+// verifier does, so the claims are the only extra trust-free data an envelope needs.
+//
+// Every check runs in order and is recorded in a `VerificationReport` with its outcome and
+// duration; once one fails the rest are skipped. Nothing here panics on a malformed envelope:
+// sizes are bounded before anything is allocated for them, and a panic from deeper down (stwo
+// on a proof it cannot read) is caught and reported as a failure of the check it happened in.
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::channel::MerkleChannel;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::pcs::CommitmentSchemeVerifier;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::BackendForChannel;
+
+use crate::circuits::range_check_air::{gen_range_check_preprocessed_trace, RangeCheckElements};
+use crate::constants::M31_PRIME;
+use crate::platform::Stopwatch;
+use crate::prover::envelope::{CircuitKind, ProofEnvelope, ENVELOPE_VERSION};
+use crate::prover::public_values::PobPublicValues;
+use crate::prover::service::{verify_with_components, ProvedComponents};
+use crate::prover::{
+    replay_trace_commitments, InstanceResult, MerkleHasherKind, PobLookupElements, ProofHasher,
+    ProofOfBurnComponents, SpendComponents, MAX_LOG_SIZE, MIN_LOG_SIZE,
+};
+
+/// First envelope version carrying `ComponentClaims`
+pub const CLAIMS_VERSION: u16 = 14;
+
+/// What the prover's components claim, recorded in the envelope so a verifier can rebuild them
+///
+/// The lookup elements are not recorded: they are drawn from the channel, so the verifier draws
+/// them again. A claim that differs from the prover's fails verification like a wrong proof does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ComponentClaims {
+    /// Log2 of the rows of each sized component: `pob`, `poseidon2` and `keccak` for burn, `spend`
+    /// for spend
+    pub log_sizes: Vec<u32>,
+
+    /// Logup sum of each component in commitment order, range check last, as the four M31
+    /// coordinates of its QM31
+    pub claimed_sums: Vec<[u32; 4]>,
+
+    /// Public outputs of every burn instance, in instance order; empty for spend
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub public_values: Vec<PobPublicValues>,
+}
+
+impl From<&ProofOfBurnComponents> for ComponentClaims {
+    fn from(components: &ProofOfBurnComponents) -> Self {
+        Self {
+            log_sizes: components.log_sizes().to_vec(),
+            claimed_sums: components.claimed_sums.iter().map(|sum| encode_sum(*sum)).collect(),
+            public_values: components.instance_values().to_vec(),
+        }
+    }
+}
+
+impl From<&SpendComponents> for ComponentClaims {
+    fn from(components: &SpendComponents) -> Self {
+        Self {
+            log_sizes: vec![components.spend.log_n_rows],
+            claimed_sums: components.claimed_sums.iter().map(|sum| encode_sum(*sum)).collect(),
+            public_values: Vec::new(),
+        }
+    }
+}
+
+fn encode_sum(sum: SecureField) -> [u32; 4] {
+    sum.to_m31_array().map(|coordinate| coordinate.0)
+}
+
+/// The QM31 of four coordinates, each of which must be a canonical M31
+fn decode_sum(coordinates: [u32; 4]) -> Option<SecureField> {
+    if coordinates.iter().any(|&coordinate| coordinate >= M31_PRIME) {
+        return None;
+    }
+    Some(SecureField::from_m31_array(coordinates.map(BaseField::from_u32_unchecked)))
+}
+
+/// One step of `verify_offline`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// The bytes decode to an envelope (`verify_offline_bytes` only)
+    Decode,
+    /// Version, layout, claims, statements and embedded proof are well formed
+    Format,
+    /// Poseidon2 and circuit parameter fingerprints are this build's
+    Parameters,
+    /// Public inputs, proof id and public commitment follow from the statements, which bind the
+    /// claimed public values
+    PublicValues,
+    /// Components rebuilt from the claims fit the envelope's proof
+    Components,
+    /// The STARK proof verifies against the rebuilt components
+    Proof,
+}
+
+impl Check {
+    /// Every check, in the order they run
+    pub const ALL: [Check; 6] =
+        [Check::Decode, Check::Format, Check::Parameters, Check::PublicValues, Check::Components, Check::Proof];
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Check::Decode => "decode",
+            Check::Format => "format",
+            Check::Parameters => "parameters",
+            Check::PublicValues => "public_values",
+            Check::Components => "components",
+            Check::Proof => "proof",
+        })
+    }
+}
+
+/// How a check ended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum CheckOutcome {
+    Passed,
+    Failed(String),
+    /// Not run, because an earlier check failed
+    Skipped,
+}
+
+/// Outcome of one check and how long it took
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckResult {
+    pub check: Check,
+    pub outcome: CheckOutcome,
+    /// Wall-clock time of the check; zero when skipped
+    pub ms: f64,
+}
+
+/// Every check `verify_offline` made, in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerificationReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl VerificationReport {
+    /// Whether every check ran and passed
+    pub fn passed(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|result| result.outcome == CheckOutcome::Passed)
+    }
+
+    /// The check that failed and why, if one did
+    pub fn failed_check(&self) -> Option<(Check, &str)> {
+        self.checks.iter().find_map(|result| match &result.outcome {
+            CheckOutcome::Failed(reason) => Some((result.check, reason.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Total time of every check
+    pub fn total_ms(&self) -> f64 {
+        self.checks.iter().map(|result| result.ms).sum()
+    }
+
+    /// Run `check` unless an earlier one failed, and record its outcome
+    ///
+    /// A panic inside `run` is recorded as the check's failure.
+    fn run<T>(&mut self, check: Check, run: impl FnOnce() -> Result<T, String>) -> Option<T> {
+        if self.failed_check().is_some() {
+            return None;
+        }
+        let stopwatch = Stopwatch::start();
+        let result = catch_unwind(AssertUnwindSafe(run)).unwrap_or_else(|panic| {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(format!("panicked: {message}"))
+        });
+        let ms = stopwatch.total();
+        let (outcome, value) = match result {
+            Ok(value) => (CheckOutcome::Passed, Some(value)),
+            Err(reason) => (CheckOutcome::Failed(reason), None),
+        };
+        self.checks.push(CheckResult { check, outcome, ms });
+        value
+    }
+
+    /// Record every check after the last one run as skipped
+    fn skip_rest(&mut self) {
+        let next = match self.checks.last() {
+            Some(last) => Check::ALL.iter().position(|&check| check == last.check).map_or(Check::ALL.len(), |i| i + 1),
+            None => 0,
+        };
+        for &check in &Check::ALL[next..] {
+            self.checks.push(CheckResult { check, outcome: CheckOutcome::Skipped, ms: 0.0 });
+        }
+    }
+}
+
+/// One line per check
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.checks {
+            match &result.outcome {
+                CheckOutcome::Passed => writeln!(f, "  [pass] {:<13} {:>9.2} ms", result.check, result.ms)?,
+                CheckOutcome::Failed(reason) => {
+                    writeln!(f, "  [FAIL] {:<13} {:>9.2} ms  {reason}", result.check, result.ms)?
+                }
+                CheckOutcome::Skipped => writeln!(f, "  [skip] {}", result.check)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ProofEnvelope {
+    /// Verify the envelope with nothing but what it carries
+    ///
+    /// Runs the format, parameter, public value, component and proof checks in that order and
+    /// reports each; the envelope is valid if `VerificationReport::passed`. Replay protection is
+    /// not checked, as it depends on the chain: call `check_replay` for it.
+    pub fn verify_offline(&self) -> VerificationReport {
+        let mut report = VerificationReport::default();
+        match self.merkle_hasher {
+            MerkleHasherKind::Blake2s => run_checks::<Blake2sMerkleHasher>(self, &mut report),
+            MerkleHasherKind::Poseidon252 => run_checks::<Poseidon252MerkleHasher>(self, &mut report),
+        }
+        report.skip_rest();
+        report
+    }
+
+    /// Decode `bytes` as `from_bytes_auto` does, then `verify_offline`
+    pub fn verify_offline_bytes(bytes: &[u8]) -> VerificationReport {
+        let mut report = VerificationReport::default();
+        match report.run(Check::Decode, || ProofEnvelope::from_bytes_auto(bytes).map_err(|e| e.to_string())) {
+            Some(envelope) => {
+                report.checks.extend(envelope.verify_offline().checks);
+            }
+            None => report.skip_rest(),
+        }
+        report
+    }
+}
+
+fn run_checks<H: ProofHasher>(envelope: &ProofEnvelope, report: &mut VerificationReport)
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    let Some((claims, proof)) = report.run(Check::Format, || check_format::<H>(envelope)) else { return };
+    let Some(()) = report.run(Check::Parameters, || envelope.check_parameters().map_err(|e| e.to_string())) else {
+        return;
+    };
+    let Some(()) = report.run(Check::PublicValues, || check_public_values(envelope, claims)) else { return };
+    let Some(components) = report.run(Check::Components, || rebuild_components(envelope, claims, &proof)) else {
+        return;
+    };
+    report.run(Check::Proof, || {
+        let results = verify_with_components(envelope, &components).map_err(|e| e.to_string())?;
+        InstanceResult::all_passed(results).map_err(|e| e.to_string())
+    });
+}
+
+/// The claims and the embedded proof, once everything they are read with is in bounds
+fn check_format<H: ProofHasher>(envelope: &ProofEnvelope) -> Result<(&ComponentClaims, StarkProof<H>), String> {
+    if !(CLAIMS_VERSION..=ENVELOPE_VERSION).contains(&envelope.version) {
+        return Err(format!(
+            "envelope version {} is not supported; offline verification needs v{CLAIMS_VERSION} to v{ENVELOPE_VERSION}",
+            envelope.version
+        ));
+    }
+    envelope.layout.check(envelope.commitments.len()).map_err(|e| e.to_string())?;
+    let claims = envelope.claims.as_ref().ok_or("envelope carries no component claims")?;
+
+    let (n_log_sizes, n_claimed_sums, n_public_values) = match envelope.circuit {
+        CircuitKind::Burn => {
+            let instances = match (&envelope.statement, envelope.instances.len()) {
+                (Some(_), 0) => 1,
+                (None, 0) => return Err("burn envelope carries no statement".to_string()),
+                (Some(_), _) => return Err("burn envelope carries both a statement and instances".to_string()),
+                (None, instances) => instances,
+            };
+            (3, 4, instances)
+        }
+        CircuitKind::Spend => {
+            if envelope.spend_statement.is_none() {
+                return Err("spend envelope carries no statement".to_string());
+            }
+            (1, 2, 0)
+        }
+    };
+    let counts = (claims.log_sizes.len(), claims.claimed_sums.len(), claims.public_values.len());
+    if counts != (n_log_sizes, n_claimed_sums, n_public_values) {
+        return Err(format!(
+            "{:?} claims need {n_log_sizes} log sizes, {n_claimed_sums} claimed sums and {n_public_values} \
+             public values, found {}, {} and {}",
+            envelope.circuit, counts.0, counts.1, counts.2
+        ));
+    }
+    if let Some(&log_size) = claims.log_sizes.iter().find(|&&log_size| !(MIN_LOG_SIZE..=MAX_LOG_SIZE).contains(&log_size)) {
+        return Err(format!("claimed log size {log_size} is outside {MIN_LOG_SIZE}..={MAX_LOG_SIZE}"));
+    }
+    if claims.log_sizes[0] != envelope.log_n_rows {
+        return Err(format!(
+            "envelope records 2^{} trace rows, its claims 2^{}",
+            envelope.log_n_rows, claims.log_sizes[0]
+        ));
+    }
+    if claims.claimed_sums.iter().any(|&sum| decode_sum(sum).is_none()) {
+        return Err("claimed sum coordinate is not a canonical M31".to_string());
+    }
+    let proof = envelope.stark_proof::<H>().map_err(|e| e.to_string())?;
+    Ok((claims, proof))
+}
+
+/// Require the public inputs, proof id and public commitment to follow from the statements, and
+/// the statements to bind the claimed public values
+fn check_public_values(envelope: &ProofEnvelope, claims: &ComponentClaims) -> Result<(), String> {
+    match envelope.circuit {
+        CircuitKind::Burn if envelope.instances.is_empty() => {
+            // `burn_statement` requires the public inputs, public commitment first, to be the
+            // statement's
+            let statement = envelope.burn_statement().map_err(|e| e.to_string())?;
+            if envelope.proof_id() != Some(statement.proof_id()) {
+                return Err("proof id does not match the statement's".to_string());
+            }
+            if !statement.binds(&claims.public_values[0]) {
+                return Err("statement does not bind the claimed public values".to_string());
+            }
+        }
+        CircuitKind::Burn => {
+            let derived: Vec<_> = envelope
+                .instances
+                .iter()
+                .flat_map(|statement| {
+                    let (public_commitment, nullifier, commitment, security_level) = statement.to_solidity_tuple();
+                    [public_commitment, nullifier, commitment, security_level]
+                })
+                .collect();
+            if envelope.public_inputs != derived {
+                return Err("public inputs do not match the instances' statements".to_string());
+            }
+            let scope = envelope.instances[0].replay_scope();
+            for (index, (statement, public_values)) in envelope.instances.iter().zip(&claims.public_values).enumerate() {
+                if !statement.binds(public_values) {
+                    return Err(format!("instance {index}: statement does not bind the claimed public values"));
+                }
+                if statement.replay_scope() != scope {
+                    return Err(format!("instance {index}: replay scope differs from instance 0's"));
+                }
+            }
+        }
+        CircuitKind::Spend => {
+            envelope.spend_statement().map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Components of the envelope's proof, with lookup elements redrawn by replaying its commitments
+///
+/// Components of the claimed sizes are built with placeholder elements first, for the column
+/// sizes the replay commits with; the real elements are drawn where the prover drew them.
+fn rebuild_components<H: ProofHasher>(
+    envelope: &ProofEnvelope,
+    claims: &ComponentClaims,
+    proof: &StarkProof<H>,
+) -> Result<ProvedComponents, String>
+where
+    SimdBackend: BackendForChannel<H::Channel>,
+{
+    let claimed_sums: Vec<SecureField> = claims.claimed_sums.iter().filter_map(|&sum| decode_sum(sum)).collect();
+    let channel = &mut <H::Channel as MerkleChannel>::C::default();
+    let mut commitment_scheme = CommitmentSchemeVerifier::<H::Channel>::new(proof.config);
+
+    let components = match envelope.circuit {
+        CircuitKind::Burn => {
+            let log_sizes: [u32; 3] = claims.log_sizes.as_slice().try_into().map_err(|_| "burn claims need 3 log sizes")?;
+            let claimed_sums: [SecureField; 4] =
+                claimed_sums.as_slice().try_into().map_err(|_| "burn claims need 4 claimed sums")?;
+            let (scope, instances) = match &envelope.statement {
+                Some(statement) => (statement.replay_scope(), Vec::new()),
+                None => (envelope.instances[0].replay_scope(), claims.public_values.clone()),
+            };
+            let assemble = |elements: PobLookupElements| {
+                ProofOfBurnComponents::assemble(
+                    log_sizes,
+                    elements,
+                    claimed_sums,
+                    claims.public_values[0],
+                    instances.clone(),
+                    scope,
+                    envelope.layout.clone(),
+                    H::KIND,
+                )
+            };
+            let sized = assemble(PobLookupElements::dummy());
+            let mut elements = None;
+            replay_trace_commitments(
+                &mut commitment_scheme,
+                channel,
+                &sized.trace_log_degree_bounds(),
+                sized.preprocessed_trace(),
+                &envelope.layout,
+                proof,
+                |channel| elements = Some(PobLookupElements::draw(channel, &scope, sized.instance_values())),
+            )
+            .map_err(|e| e.to_string())?;
+            ProvedComponents::Burn(assemble(elements.ok_or("proof has no interaction tree")?))
+        }
+        CircuitKind::Spend => {
+            let statement = envelope.spend_statement.ok_or("spend envelope carries no statement")?;
+            let claimed_sums: [SecureField; 2] =
+                claimed_sums.as_slice().try_into().map_err(|_| "spend claims need 2 claimed sums")?;
+            let assemble = |range_check_lookup: RangeCheckElements| {
+                SpendComponents::assemble(
+                    claims.log_sizes[0],
+                    range_check_lookup,
+                    claimed_sums,
+                    statement,
+                    envelope.layout.clone(),
+                    H::KIND,
+                )
+            };
+            let sized = assemble(RangeCheckElements::dummy());
+            let mut range_check_lookup = None;
+            replay_trace_commitments(
+                &mut commitment_scheme,
+                channel,
+                &sized.trace_log_degree_bounds(),
+                gen_range_check_preprocessed_trace(),
+                &envelope.layout,
+                proof,
+                |channel| {
+                    statement.mix_into(channel);
+                    range_check_lookup = Some(RangeCheckElements::draw(channel));
+                },
+            )
+            .map_err(|e| e.to_string())?;
+            ProvedComponents::Spend(assemble(range_check_lookup.ok_or("proof has no interaction tree")?))
+        }
+    };
+    envelope.compatibility_check(&components).map_err(|e| e.to_string())?;
+    Ok(components)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+
+    use super::*;
+    use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+    use crate::constants::CircuitParams;
+    use crate::field::M31;
+    use crate::prover::service::PobProver;
+    use crate::prover::StarkConfig;
+    use crate::secret::Secret;
+    use crate::test_utils::{gen_pob_inputs, gen_spend_inputs, InputProfile};
+
+    const LOG_N_ROWS: u32 = 5;
+
+    fn prover() -> PobProver {
+        PobProver::new(StarkConfig::default(), CircuitParams::default()).with_log_n_rows(LOG_N_ROWS)
+    }
+
+    fn burn_envelope() -> ProofEnvelope {
+        prover().prove_burn(&gen_pob_inputs(3, InputProfile::TinyBalances)).unwrap().envelope
+    }
+
+    fn spend_envelope(seed: u64) -> ProofEnvelope {
+        prover().prove_spend(&gen_spend_inputs(seed, InputProfile::TinyBalances)).unwrap().envelope
+    }
+
+    fn assert_fails_at(report: &VerificationReport, check: Check) {
+        assert_eq!(report.failed_check().map(|(failed, _)| failed), Some(check), "{report}");
+        assert!(!report.passed());
+        // Every check is listed, those after the failing one as skipped
+        let listed: Vec<Check> = report.checks.iter().map(|result| result.check).collect();
+        assert!(Check::ALL.ends_with(&listed), "{listed:?}");
+        let failed_at = listed.iter().position(|&listed| listed == check).unwrap();
+        assert!(report.checks[failed_at + 1..].iter().all(|result| result.outcome == CheckOutcome::Skipped));
+    }
+
+    #[test]
+    fn test_valid_envelopes_pass_every_check() {
+        for envelope in [burn_envelope(), spend_envelope(4)] {
+            let report = envelope.verify_offline();
+            assert!(report.passed(), "{report}");
+            assert_eq!(report.checks.len(), Check::ALL.len() - 1);
+
+            let report = ProofEnvelope::verify_offline_bytes(&envelope.to_bytes_compressed().unwrap());
+            assert!(report.passed(), "{report}");
+            let listed: Vec<Check> = report.checks.iter().map(|result| result.check).collect();
+            assert_eq!(listed, Check::ALL);
+        }
+    }
+
+    #[test]
+    fn test_valid_batch_passes() {
+        // The prover does not check the trie, so instances may share one header with other keys
+        let base = gen_pob_inputs(3, InputProfile::TinyBalances);
+        let inputs: Vec<ProofOfBurnInputs> = (1..=3)
+            .map(|burn_key| ProofOfBurnInputs { burn_key: Secret::new(M31::from(burn_key)), ..base.clone() })
+            .collect();
+        let envelope = prover().prove_burn_batch(&inputs).unwrap().envelope;
+        assert_eq!(envelope.claims.as_ref().unwrap().public_values.len(), 3);
+        let report = envelope.verify_offline();
+        assert!(report.passed(), "{report}");
+    }
+
+    #[test]
+    fn test_truncated_bytes_fail_to_decode() {
+        let bytes = burn_envelope().to_bytes().unwrap();
+        let report = ProofEnvelope::verify_offline_bytes(&bytes[..bytes.len() / 2]);
+        assert_fails_at(&report, Check::Decode);
+        assert_eq!(report.checks.len(), Check::ALL.len());
+    }
+
+    #[test]
+    fn test_wrong_types_fail_to_decode() {
+        let mut json = serde_json::to_value(burn_envelope()).unwrap();
+        json["log_n_rows"] = serde_json::json!("five");
+        assert_fails_at(&ProofEnvelope::verify_offline_bytes(&serde_json::to_vec(&json).unwrap()), Check::Decode);
+
+        let mut json = serde_json::to_value(burn_envelope()).unwrap();
+        json["claims"]["claimed_sums"] = serde_json::json!("0x00");
+        assert_fails_at(&ProofEnvelope::verify_offline_bytes(&serde_json::to_vec(&json).unwrap()), Check::Decode);
+
+        assert_fails_at(&ProofEnvelope::verify_offline_bytes(b"[1, 2, 3]"), Check::Decode);
+    }
+
+    #[test]
+    fn test_malformed_envelopes_fail_format() {
+        let mut no_claims = burn_envelope();
+        no_claims.claims = None;
+        assert_fails_at(&no_claims.verify_offline(), Check::Format);
+
+        // Rejected before a column of 2^40 rows is allocated for it
+        let mut huge = burn_envelope();
+        huge.claims.as_mut().unwrap().log_sizes[1] = 40;
+        let report = huge.verify_offline();
+        assert_fails_at(&report, Check::Format);
+        assert!(report.failed_check().unwrap().1.contains("log size 40"), "{report}");
+
+        let mut non_canonical = burn_envelope();
+        non_canonical.claims.as_mut().unwrap().claimed_sums[0][2] = M31_PRIME;
+        assert_fails_at(&non_canonical.verify_offline(), Check::Format);
+
+        let mut old = burn_envelope();
+        old.version = CLAIMS_VERSION - 1;
+        assert_fails_at(&old.verify_offline(), Check::Format);
+
+        let mut truncated_proof = burn_envelope();
+        truncated_proof.proof = truncated_proof.proof[..truncated_proof.proof.len() - 7].to_vec().into();
+        assert_fails_at(&truncated_proof.verify_offline(), Check::Format);
+    }
+
+    #[test]
+    fn test_foreign_parameters_fail() {
+        let mut envelope = burn_envelope();
+        envelope.circuit_params_hash = Default::default();
+        assert_fails_at(&envelope.verify_offline(), Check::Parameters);
+    }
+
+    #[test]
+    fn test_inconsistent_public_values_fail() {
+        let mut public_commitment = burn_envelope();
+        public_commitment.public_inputs[0] += U256::from(1);
+        assert_fails_at(&public_commitment.verify_offline(), Check::PublicValues);
+
+        let mut claimed = burn_envelope();
+        let public_values = &mut claimed.claims.as_mut().unwrap().public_values[0];
+        public_values.commitment = public_values.nullifier;
+        assert_fails_at(&claimed.verify_offline(), Check::PublicValues);
+
+        let mut spend = spend_envelope(4);
+        spend.public_inputs[2] += U256::from(1);
+        assert_fails_at(&spend.verify_offline(), Check::PublicValues);
+    }
+
+    #[test]
+    fn test_wrong_trace_size_fails_reconstruction() {
+        // Consistent with itself, but the proof commits the preprocessed columns of 2^5 rows
+        let mut envelope = burn_envelope();
+        envelope.log_n_rows += 1;
+        envelope.claims.as_mut().unwrap().log_sizes[0] += 1;
+        let report = envelope.verify_offline();
+        assert_fails_at(&report, Check::Components);
+        assert!(report.failed_check().unwrap().1.contains("preprocessed"), "{report}");
+    }
+
+    #[test]
+    fn test_corrupted_claims_and_proofs_fail_verification() {
+        let mut sums = burn_envelope();
+        sums.claims.as_mut().unwrap().claimed_sums[3] = [0; 4];
+        assert_fails_at(&sums.verify_offline(), Check::Proof);
+
+        // Another spend's proof of the same shape: it decodes and fits, but proves another statement
+        let spend = spend_envelope(4);
+        let swapped = ProofEnvelope { proof: spend_envelope(5).proof, ..spend };
+        assert_fails_at(&swapped.verify_offline(), Check::Proof);
+    }
+}
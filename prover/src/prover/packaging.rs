@@ -0,0 +1,289 @@
+// Public value packaging shared with the Solidity contracts
+// Keccak packings must match Commitments.sol and STWOProofOfBurnVerifier.sol byte for byte
+
+use alloy_primitives::{keccak256, B256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::circuits::proof_of_burn::SecurityLevel;
+use crate::constants::{M31_PRIME, OUTPUT_LIMBS};
+use crate::field::M31;
+
+/// Bits per limb when packing M31 limbs into a uint256
+const LIMB_BITS: usize = 31;
+
+/// Pack a multi-limb nullifier or commitment into the uint256 the contracts take
+///
+/// Limb `i` occupies bits `[31 * i, 31 * i + 31)`, so the value always fits in 248 bits.
+/// Solidity recovers limb `i` as `(value >> (31 * i)) & 0x7fffffff`.
+pub fn limbs_to_u256(limbs: &[M31; OUTPUT_LIMBS]) -> U256 {
+    limbs
+        .iter()
+        .enumerate()
+        .fold(U256::ZERO, |acc, (i, limb)| acc | (U256::from(limb.value()) << (LIMB_BITS * i)))
+}
+
+/// Inverse of `limbs_to_u256`, rejecting values that no limb vector packs to
+pub fn u256_to_limbs(value: U256) -> Result<[M31; OUTPUT_LIMBS], LimbPackingError> {
+    if value >> (LIMB_BITS * OUTPUT_LIMBS) != U256::ZERO {
+        return Err(LimbPackingError::TooWide { value });
+    }
+
+    let mask = U256::from(M31_PRIME);
+    let mut limbs = [M31::zero(); OUTPUT_LIMBS];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let raw = ((value >> (LIMB_BITS * i)) & mask).to::<u32>();
+        // 2^31 - 1 fits in 31 bits but is not a canonical M31 element
+        if raw == M31_PRIME {
+            return Err(LimbPackingError::NonCanonicalLimb { index: i });
+        }
+        *limb = M31::new(raw);
+    }
+    Ok(limbs)
+}
+
+/// Compute the publicCommitment as per Commitments.sol
+///
+/// publicCommitment = keccak256(abi.encodePacked(blockHash, nullifier, commitment, revealAmount)) >> 8
+///
+/// The shift keeps the value below the BN254 scalar field used by the contracts.
+pub fn compute_public_commitment(
+    block_hash: B256,
+    nullifier: U256,
+    commitment: U256,
+    reveal_amount: U256,
+) -> U256 {
+    let mut packed_data = Vec::with_capacity(4 * 32);
+    packed_data.extend_from_slice(block_hash.as_slice());
+    packed_data.extend_from_slice(&nullifier.to_be_bytes::<32>());
+    packed_data.extend_from_slice(&commitment.to_be_bytes::<32>());
+    packed_data.extend_from_slice(&reveal_amount.to_be_bytes::<32>());
+
+    let hash = keccak256(&packed_data);
+    U256::from_be_bytes(hash.0) >> 8
+}
+
+/// Identifier of a burn proof, as STWOProofOfBurnVerifier.sol derives it
+///
+/// Serializes and displays as 0x-prefixed hex, like the `bytes32` the contract emits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProofId(pub B256);
+
+impl ProofId {
+    /// proofId = keccak256(abi.encodePacked(publicCommitment, nullifier, commitment))
+    pub fn compute(public_commitment: U256, nullifier: U256, commitment: U256) -> Self {
+        let mut packed_data = Vec::with_capacity(3 * 32);
+        packed_data.extend_from_slice(&public_commitment.to_be_bytes::<32>());
+        packed_data.extend_from_slice(&nullifier.to_be_bytes::<32>());
+        packed_data.extend_from_slice(&commitment.to_be_bytes::<32>());
+
+        Self(keccak256(&packed_data))
+    }
+
+    pub fn as_b256(&self) -> &B256 {
+        &self.0
+    }
+}
+
+impl From<ProofId> for B256 {
+    fn from(id: ProofId) -> Self {
+        id.0
+    }
+}
+
+impl PartialEq<B256> for ProofId {
+    fn eq(&self, other: &B256) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<ProofId> for B256 {
+    fn eq(&self, other: &ProofId) -> bool {
+        *self == other.0
+    }
+}
+
+impl std::fmt::Display for ProofId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+/// Pack the security level a burn proof claims into one uint256 public input
+///
+/// securityLevel = byteSecurityRelax | powZeroBytes << 8 | minLeafAddressNibbles << 16
+pub fn pack_security_level(security: &SecurityLevel) -> U256 {
+    U256::from(security.byte_security_relax)
+        | (U256::from(security.pow_zero_bytes) << 8)
+        | (U256::from(security.min_leaf_address_nibbles) << 16)
+}
+
+/// Inverse of `pack_security_level`; `None` if the value is not a level `from_relax` produces
+pub fn unpack_security_level(value: U256) -> Option<SecurityLevel> {
+    if value >> 24 != U256::ZERO {
+        return None;
+    }
+    let byte_security_relax = (value & U256::from(0xff)).to::<u8>();
+    let security = SecurityLevel::from_relax(byte_security_relax);
+    (pack_security_level(&security) == value).then_some(security)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LimbPackingError {
+    #[error("Packed value {value} does not fit in {} limbs", OUTPUT_LIMBS)]
+    TooWide { value: U256 },
+
+    #[error("Limb {index} is 2^31 - 1, which is not a canonical M31 element")]
+    NonCanonicalLimb { index: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn test_block_hash() -> B256 {
+        keccak256([0xabu8; 32])
+    }
+
+    fn test_nullifier() -> U256 {
+        U256::from_str("0x1212121212121212121212121212121212121212121212121212121212121212").unwrap()
+    }
+
+    fn test_commitment() -> U256 {
+        U256::from_str("0x3434343434343434343434343434343434343434343434343434343434343434").unwrap()
+    }
+
+    #[test]
+    fn test_proof_id_calculation_consistency() {
+        // Test values that match the JavaScript verification script
+        let reveal_amount = U256::from(500000000000000000u64); // 0.5 ETH
+
+        let public_commitment = compute_public_commitment(
+            test_block_hash(),
+            test_nullifier(),
+            test_commitment(),
+            reveal_amount,
+        );
+        let proof_id = ProofId::compute(public_commitment, test_nullifier(), test_commitment());
+
+        // Expected values from JavaScript verification (UPDATED with correct blockHash)
+        let expected_public_commitment = U256::from_str("0x7f3efa11a3601ff4488fca730751aefabbd29bb9651349c4658aa67a64c550").unwrap();
+        let expected_proof_id = B256::from_str("0xaf19dffbe9939dedd30df03d7100b38fe1ef8eccf4544889a2ca1fcd907beeac").unwrap();
+
+        assert_eq!(public_commitment, expected_public_commitment, "publicCommitment calculation mismatch");
+        assert_eq!(proof_id, expected_proof_id, "proof_id calculation mismatch");
+        assert_eq!(proof_id.to_string(), "0xaf19dffbe9939dedd30df03d7100b38fe1ef8eccf4544889a2ca1fcd907beeac");
+        assert_eq!(
+            serde_json::to_string(&proof_id).unwrap(),
+            "\"0xaf19dffbe9939dedd30df03d7100b38fe1ef8eccf4544889a2ca1fcd907beeac\""
+        );
+        assert_eq!(serde_json::from_str::<ProofId>(&serde_json::to_string(&proof_id).unwrap()).unwrap(), proof_id);
+    }
+
+    #[test]
+    fn test_proof_id_extreme_inputs() {
+        let zero = ProofId::compute(U256::ZERO, U256::ZERO, U256::ZERO);
+        let expected_zero = B256::from_str("0x46700b4d40ac5c35af2c22dda2787a91eb567b06c924a8fb8ae9a05b20c08c21").unwrap();
+        assert_eq!(zero, expected_zero);
+
+        let max = ProofId::compute(U256::MAX, U256::MAX, U256::MAX);
+        let expected_max = B256::from_str("0x8590bbc3ea43e28e8624fb1a2d59aaca701a5517e08511c4a14d9037de6f6086").unwrap();
+        assert_eq!(max, expected_max);
+        assert_eq!(B256::from(max), expected_max);
+    }
+
+    #[test]
+    fn test_zero_reveal_amount_vector() {
+        let public_commitment = compute_public_commitment(
+            test_block_hash(),
+            test_nullifier(),
+            test_commitment(),
+            U256::ZERO,
+        );
+        let proof_id = ProofId::compute(public_commitment, test_nullifier(), test_commitment());
+
+        let expected_public_commitment = U256::from_str("0x1fa28178f6c632db809b01d332adf1c997b4ae23d0d244ead87b83c69c2a2").unwrap();
+        let expected_proof_id = B256::from_str("0xba1733009238c2e338bd487ae12a7732402487c6606985b02b720418f20acd02").unwrap();
+
+        assert_eq!(public_commitment, expected_public_commitment);
+        assert_eq!(proof_id, expected_proof_id);
+    }
+
+    #[test]
+    fn test_max_nullifier_vector() {
+        let reveal_amount = U256::from(500000000000000000u64);
+
+        let public_commitment = compute_public_commitment(
+            test_block_hash(),
+            U256::MAX,
+            test_commitment(),
+            reveal_amount,
+        );
+        let proof_id = ProofId::compute(public_commitment, U256::MAX, test_commitment());
+
+        let expected_public_commitment = U256::from_str("0x248c7bd2cf3b519a23ad3d40d85099fdeb6df36d6bf551798cee46b7bdd892").unwrap();
+        let expected_proof_id = B256::from_str("0xbfbf8a9f098f524dbc5a368e7711f55ab74a9158bbac9a04eb7916896a446c12").unwrap();
+
+        assert_eq!(public_commitment, expected_public_commitment);
+        assert_eq!(proof_id, expected_proof_id);
+    }
+
+    #[test]
+    fn test_public_commitment_fits_in_248_bits() {
+        let public_commitment = compute_public_commitment(
+            B256::repeat_byte(0xff),
+            U256::MAX,
+            U256::MAX,
+            U256::MAX,
+        );
+        assert!(public_commitment < (U256::from(1) << 248));
+    }
+
+    #[test]
+    fn test_limb_packing_roundtrip() {
+        let limbs = crate::utils::poseidon::poseidon_wide(&[M31::from(12345)]);
+        let packed = limbs_to_u256(&limbs);
+
+        assert!(packed < (U256::from(1) << 248));
+        assert_eq!(u256_to_limbs(packed).unwrap(), limbs);
+
+        // Survives the bytes32 round trip the contracts see
+        let bytes = B256::from(packed.to_be_bytes::<32>());
+        assert_eq!(u256_to_limbs(U256::from_be_bytes(bytes.0)).unwrap(), limbs);
+    }
+
+    #[test]
+    fn test_limb_packing_layout() {
+        let mut limbs = [M31::zero(); OUTPUT_LIMBS];
+        limbs[0] = M31::from(1);
+        limbs[OUTPUT_LIMBS - 1] = M31::from(M31_PRIME - 1);
+
+        let expected = U256::from(1) | (U256::from(M31_PRIME - 1) << (31 * (OUTPUT_LIMBS - 1)));
+        assert_eq!(limbs_to_u256(&limbs), expected);
+    }
+
+    #[test]
+    fn test_limb_unpacking_rejects_invalid_values() {
+        assert!(matches!(
+            u256_to_limbs(U256::from(1) << 248),
+            Err(LimbPackingError::TooWide { .. })
+        ));
+        assert!(matches!(
+            u256_to_limbs(U256::from(M31_PRIME) << 62),
+            Err(LimbPackingError::NonCanonicalLimb { index: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_security_level_packing_roundtrip() {
+        for relax in [0u8, 1, 5] {
+            let security = SecurityLevel::from_relax(relax);
+            assert_eq!(unpack_security_level(pack_security_level(&security)), Some(security));
+        }
+
+        // A pow/nibble pair that does not follow from the relax byte is rejected
+        let forged = U256::from(1) | (U256::from(2) << 8) | (U256::from(50) << 16);
+        assert_eq!(unpack_security_level(forged), None);
+    }
+}
@@ -0,0 +1,233 @@
+// Public outputs of a Proof of Burn proof, as bound by the proof itself
+// The prover mixes them into the channel before drawing lookup elements and the PoB AIR claims
+// them on every enabled row, so the verifier only accepts a proof together with its own outputs
+
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::channel::Channel;
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::fields::qm31::SecureField;
+use stwo_prover::core::fields::FieldExpOps;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::prover::backend::simd::m31::N_LANES;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::{BackendForChannel, Column};
+use stwo_constraint_framework::Relation;
+
+use crate::circuits::layout::POB_LAYOUT;
+use crate::circuits::proof_of_burn_air::{PobWitness, PublicOutputElements, PUBLIC_OUTPUT_SIZE};
+use crate::constants::OUTPUT_LIMBS;
+use crate::field::M31;
+use crate::prover::packaging::limbs_to_u256;
+use crate::prover::{
+    verify_proof_of_burn, ProofHasher, ProofOfBurnComponents, TraceColumns, VerificationFailure,
+};
+use crate::utils::burn_address::burn_address_from_digest;
+use crate::utils::keccak::keccak256;
+
+/// Public outputs of a Proof of Burn proof
+///
+/// These are the values in the PoB trace's `nullifier_out`, `remaining_coin_out`,
+/// `commitment_out` and `burn_address_out` cells, i.e. the Poseidon2 outputs the table component
+/// proves. Read them with `extract`, which only returns them once the proof verifies against them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PobPublicValues {
+    /// Nullifier, as `OUTPUT_LIMBS` limbs
+    pub nullifier: [M31; OUTPUT_LIMBS],
+
+    /// Remaining coin
+    pub remaining_coin: M31,
+
+    /// Commitment, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
+
+    /// Poseidon2 digest of the burn address preimage, as `OUTPUT_LIMBS` limbs
+    ///
+    /// The proof binds it to the burn key, reveal amount and burn extra commitment it hashes;
+    /// `burn_address` and `burn_address_hash` apply the keccak steps the proof does not cover.
+    pub burn_address_digest: [M31; OUTPUT_LIMBS],
+}
+
+/// Packed as the contracts take them, in hex
+impl std::fmt::Display for PobPublicValues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nullifier={:#x}, remaining_coin={:#x}, commitment={:#x}, burn_address={}",
+            self.nullifier_u256(),
+            self.remaining_coin.value(),
+            self.commitment_u256(),
+            self.burn_address()
+        )
+    }
+}
+
+impl PobPublicValues {
+    /// Verify `proof` against `components` and return the public outputs it is bound to
+    ///
+    /// This is the only trusted source of a proof's outputs: the values are the ones mixed into
+    /// the channel and claimed by the PoB AIR, so they cannot differ from the proven trace.
+    pub fn extract<H: ProofHasher>(
+        components: &ProofOfBurnComponents,
+        proof: StarkProof<H>,
+    ) -> Result<Self, ExtractionError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        verify_proof_of_burn(components, proof)?;
+        Ok(components.public_values)
+    }
+
+    /// Read the public cells from the first row of a PoB main trace
+    pub(crate) fn from_trace(trace: &TraceColumns) -> Self {
+        Self::from_trace_row(trace, 0)
+    }
+
+    /// Read the public cells from row `row` of a PoB main trace
+    pub(crate) fn from_trace_row(trace: &TraceColumns, row: usize) -> Self {
+        Self::from_cells(|column| trace[column].values.at(row))
+    }
+
+    /// Read the public cells from the row `witness` fills the trace with
+    pub(crate) fn from_witness(witness: &PobWitness) -> Self {
+        Self::from_cells(|column| witness.value(column))
+    }
+
+    fn from_cells(value: impl Fn(usize) -> BaseField) -> Self {
+        let cell = |column: usize| M31::new(value(column).0);
+        Self {
+            nullifier: std::array::from_fn(|i| cell(POB_LAYOUT.nullifier_out().start + i)),
+            remaining_coin: cell(POB_LAYOUT.remaining_coin_out().start),
+            commitment: std::array::from_fn(|i| cell(POB_LAYOUT.commitment_out().start + i)),
+            burn_address_digest: std::array::from_fn(|i| cell(POB_LAYOUT.burn_address_out().start + i)),
+        }
+    }
+
+    /// Nullifier packed into the uint256 the contracts take
+    pub fn nullifier_u256(&self) -> U256 {
+        limbs_to_u256(&self.nullifier)
+    }
+
+    /// Commitment packed into the uint256 the contracts take
+    pub fn commitment_u256(&self) -> U256 {
+        limbs_to_u256(&self.commitment)
+    }
+
+    /// Address the burn was made to, the keccak256 of `burn_address_digest`
+    pub fn burn_address(&self) -> Address {
+        burn_address_from_digest(&self.burn_address_digest)
+    }
+
+    /// keccak256 of `burn_address`, the account's key in the state trie
+    pub fn burn_address_hash(&self) -> B256 {
+        B256::from(keccak256(self.burn_address().as_slice()))
+    }
+
+    /// The values in `PublicOutputElements` order: nullifier, remaining coin, commitment, burn
+    /// address digest
+    fn to_base_fields(self) -> [BaseField; PUBLIC_OUTPUT_SIZE] {
+        let mut values = [BaseField::from_u32_unchecked(0); PUBLIC_OUTPUT_SIZE];
+        let limbs = self
+            .nullifier
+            .iter()
+            .chain(std::iter::once(&self.remaining_coin))
+            .chain(self.commitment.iter())
+            .chain(self.burn_address_digest.iter());
+        for (value, limb) in values.iter_mut().zip(limbs) {
+            *value = BaseField::from_u32_unchecked(limb.value());
+        }
+        values
+    }
+
+    /// Mix the values into the channel, before the lookup elements are drawn
+    pub(crate) fn mix_into(&self, channel: &mut impl Channel) {
+        channel.mix_u32s(&self.to_base_fields().map(|value| value.0));
+    }
+
+    /// Logup sum of the claims the enabled PoB rows make on these values
+    ///
+    /// Each of the `N_LANES` witness rows claims the outputs once, so the four component sums
+    /// add up to this instead of zero.
+    pub(crate) fn logup_sum(&self, elements: &PublicOutputElements) -> SecureField {
+        let denominator: SecureField = elements.combine(&self.to_base_fields());
+        SecureField::from(BaseField::from(N_LANES as u32)) * denominator.inverse()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExtractionError {
+    #[error("Proof does not verify against its public values: {0}")]
+    Verification(#[from] VerificationFailure),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+    use crate::circuits::proof_of_burn_air::generate_pob_trace;
+    use crate::prover::{prove_proof_of_burn, StarkConfig};
+    use crate::secret::Secret;
+    use crate::utils::burn_address::compute_burn_address_hash;
+
+    const LOG_N_ROWS: u32 = 6;
+
+    fn pob_inputs(burn_key: u32) -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    #[test]
+    fn test_extracted_values_match_witness() {
+        let inputs = pob_inputs(12345);
+        let (components, proof) = prove_proof_of_burn(&inputs, LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let extracted = PobPublicValues::extract(&components, proof).expect("Proof should verify");
+
+        // The witness path the prover commits to; `consistency::check_pob` holds the witness
+        // circuit's outputs equal to it
+        let (trace, lookup_data) = generate_pob_trace(LOG_N_ROWS, &inputs).unwrap();
+        assert_eq!(extracted, PobPublicValues::from_trace(&trace));
+        assert_eq!(extracted.remaining_coin.value(), lookup_data.remaining_coin.at(0).0);
+        assert_eq!(extracted.nullifier.map(|limb| limb.value()), lookup_data.nullifier.map(|col| col.at(0).0));
+        assert_eq!(extracted.commitment.map(|limb| limb.value()), lookup_data.commitment.map(|col| col.at(0).0));
+        assert_eq!(
+            extracted.burn_address_hash(),
+            B256::from(compute_burn_address_hash(
+                *inputs.burn_key.expose_secret(),
+                inputs.reveal_amount,
+                inputs.burn_extra_commitment,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_extract_rejects_swapped_public_values() {
+        let (mut components, proof) = prove_proof_of_burn(&pob_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_proof_of_burn(&pob_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert_ne!(components.public_values, other_components.public_values);
+
+        components.public_values = other_components.public_values;
+        assert!(matches!(
+            PobPublicValues::extract(&components, proof),
+            Err(ExtractionError::Verification(_))
+        ));
+    }
+}
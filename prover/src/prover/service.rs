@@ -0,0 +1,677 @@
+// Long-lived prover for servers
+// `PobProver` owns what every proof would otherwise rebuild: the STARK configuration, the circuit
+// limits inputs are checked against, and the twiddle tables. Share one instance behind an `Arc`;
+// every method takes `&self` and may run concurrently from any number of threads.
+//
+// Envelopes do not carry the components a proof was made with, only their claims, so verification
+// here takes them alongside the envelope, as `ProofEnvelope::verify_burn` does. Without them,
+// `ProofEnvelope::verify_offline` rebuilds them from the claims.
+
+use alloy_primitives::U256;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use stwo_prover::core::vcs::poseidon252_merkle::Poseidon252MerkleHasher;
+use stwo_prover::core::pcs::TreeVec;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::BackendForChannel;
+
+use crate::circuits::proof_of_burn::{ProofOfBurnError, ProofOfBurnInputs};
+use crate::circuits::spend::{SpendCircuit, SpendError, SpendInputs};
+use crate::constants::CircuitParams;
+use crate::prover::compatibility::CompatError;
+use crate::prover::{
+    prove_proof_of_burn_batch, prove_proof_of_burn_impl, prove_spend_impl, BurnStatement,
+    CircuitKind, EnvelopeError, ExtractionError, InstanceResult, MerkleHasherKind, ProofEnvelope,
+    ProofHasher, ProofOfBurnComponents, ProvingTimings, SpendComponents, StarkConfig, StatementError,
+    TraceColumns, TwiddleCache, VerificationFailure,
+};
+
+/// Trace rows `PobProver` proves with unless set by `with_log_n_rows`
+pub const DEFAULT_LOG_N_ROWS: u32 = 16;
+
+/// Shared, re-entrant prover holding warm state across proofs
+///
+/// Twiddles are computed on the first proof and grown only when a larger domain is needed, so
+/// later proofs skip that step. Proofs are identical to the ones the free `prove_*` functions
+/// produce for the same configuration.
+pub struct PobProver {
+    config: StarkConfig,
+    params: CircuitParams,
+    log_n_rows: u32,
+    twiddles: TwiddleCache,
+}
+
+/// Components a proof was made with, needed to verify its envelope
+pub enum ProvedComponents {
+    Burn(ProofOfBurnComponents),
+    Spend(SpendComponents),
+}
+
+impl ProvedComponents {
+    /// How long each phase of the proof took
+    pub fn timings(&self) -> &ProvingTimings {
+        match self {
+            ProvedComponents::Burn(components) => &components.timings,
+            ProvedComponents::Spend(components) => &components.timings,
+        }
+    }
+
+    /// Circuit of the proof these components verify
+    pub fn circuit(&self) -> CircuitKind {
+        match self {
+            ProvedComponents::Burn(_) => CircuitKind::Burn,
+            ProvedComponents::Spend(_) => CircuitKind::Spend,
+        }
+    }
+
+    /// Log2 of the rows of the burn or spend component, the size an envelope records
+    pub fn log_n_rows(&self) -> u32 {
+        match self {
+            ProvedComponents::Burn(components) => components.pob.log_n_rows,
+            ProvedComponents::Spend(components) => components.spend.log_n_rows,
+        }
+    }
+
+    /// Column sizes of every component, per tree
+    pub fn trace_log_degree_bounds(&self) -> TreeVec<Vec<u32>> {
+        match self {
+            ProvedComponents::Burn(components) => components.trace_log_degree_bounds(),
+            ProvedComponents::Spend(components) => components.trace_log_degree_bounds(),
+        }
+    }
+}
+
+/// Envelope of a proof made by `PobProver`, with the components to verify it
+pub struct ServiceProof {
+    pub envelope: ProofEnvelope,
+    pub components: ProvedComponents,
+}
+
+impl PobProver {
+    /// Prover for `config`, rejecting burn inputs outside `params`
+    pub fn new(config: StarkConfig, params: CircuitParams) -> Self {
+        Self {
+            config,
+            params,
+            log_n_rows: DEFAULT_LOG_N_ROWS,
+            twiddles: TwiddleCache::default(),
+        }
+    }
+
+    /// Prove with `2^log_n_rows` trace rows instead of `DEFAULT_LOG_N_ROWS`
+    pub fn with_log_n_rows(mut self, log_n_rows: u32) -> Self {
+        self.log_n_rows = log_n_rows;
+        self
+    }
+
+    /// The configuration proofs are made with
+    pub fn config(&self) -> &StarkConfig {
+        &self.config
+    }
+
+    /// The limits burn inputs are checked against
+    pub fn params(&self) -> &CircuitParams {
+        &self.params
+    }
+
+    /// Log2 of the trace rows single proofs are made with
+    pub fn log_n_rows(&self) -> u32 {
+        self.log_n_rows
+    }
+
+    /// Prove a burn and wrap it in an envelope carrying its statement
+    ///
+    /// The proof is committed with the configuration's `merkle_hasher`, as are spends.
+    pub fn prove_burn(&self, inputs: &ProofOfBurnInputs) -> Result<ServiceProof, ServiceError> {
+        self.check_burn_limits(inputs)?;
+        match self.config.merkle_hasher {
+            MerkleHasherKind::Blake2s => self.prove_burn_with::<Blake2sMerkleHasher>(inputs),
+            MerkleHasherKind::Poseidon252 => self.prove_burn_with::<Poseidon252MerkleHasher>(inputs),
+        }
+    }
+
+    fn prove_burn_with<H: ProofHasher>(&self, inputs: &ProofOfBurnInputs) -> Result<ServiceProof, ServiceError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        let (components, proof) = prove_proof_of_burn_impl::<H>(
+            inputs,
+            self.log_n_rows,
+            self.config.clone(),
+            &self.twiddles,
+            None::<fn(&mut TraceColumns)>,
+        )
+        .map_err(ServiceError::Prove)?;
+        let statement = BurnStatement::new(inputs, components.public_values);
+        let envelope = ProofEnvelope::from_burn_proof(self.log_n_rows, &proof, components.layout.clone(), statement)?
+            .with_claims(&components);
+        Ok(ServiceProof { envelope, components: ProvedComponents::Burn(components) })
+    }
+
+    /// Prove every instance of `inputs` in one proof, wrapped in an envelope listing their statements
+    ///
+    /// The instances must share a block header. The trace size follows from the batch size, not
+    /// `with_log_n_rows`, and batches are committed with Blake2s only.
+    pub fn prove_burn_batch(&self, inputs: &[ProofOfBurnInputs]) -> Result<ServiceProof, ServiceError> {
+        for instance in inputs {
+            self.check_burn_limits(instance)?;
+        }
+        let (batch, components, proof) =
+            prove_proof_of_burn_batch(inputs, self.config.clone()).map_err(ServiceError::Prove)?;
+        let statements = inputs
+            .iter()
+            .zip(&components.instances)
+            .map(|(instance, &public_values)| BurnStatement::new(instance, public_values))
+            .collect();
+        let envelope =
+            ProofEnvelope::from_burn_batch(batch.log_n_rows(), &proof, components.layout.clone(), statements)?
+                .with_claims(&components);
+        Ok(ServiceProof { envelope, components: ProvedComponents::Burn(components) })
+    }
+
+    /// Prove a spend and wrap it in an envelope carrying its statement
+    ///
+    /// The envelope's public inputs are [commitment, coin, remainingCoin], derived from the statement.
+    pub fn prove_spend(&self, inputs: &SpendInputs) -> Result<ServiceProof, ServiceError> {
+        match self.config.merkle_hasher {
+            MerkleHasherKind::Blake2s => self.prove_spend_with::<Blake2sMerkleHasher>(inputs),
+            MerkleHasherKind::Poseidon252 => self.prove_spend_with::<Poseidon252MerkleHasher>(inputs),
+        }
+    }
+
+    fn prove_spend_with<H: ProofHasher>(&self, inputs: &SpendInputs) -> Result<ServiceProof, ServiceError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        SpendCircuit::new(inputs.clone())?;
+        let (components, proof) =
+            prove_spend_impl::<H>(inputs, self.log_n_rows, self.config.clone(), &self.twiddles, |_| {})
+                .map_err(ServiceError::Prove)?;
+        let envelope = ProofEnvelope::from_spend_proof(
+            self.log_n_rows,
+            &proof,
+            components.layout.clone(),
+            components.statement,
+        )?
+        .with_claims(&components);
+        Ok(ServiceProof { envelope, components: ProvedComponents::Spend(components) })
+    }
+
+    /// Verify `envelope` against the components its proof was made with
+    ///
+    /// Envelopes committed with another hasher than the configuration's, or proven with other
+    /// Poseidon2 or circuit parameters than this build's, are rejected before the proof is read.
+    /// Returns the result of each instance, as `verify_with_components` does; a proof that does
+    /// not verify fails with `ServiceError::Verification`, naming the phase it failed in.
+    pub fn verify_envelope(
+        &self,
+        envelope: &ProofEnvelope,
+        components: &ProvedComponents,
+    ) -> Result<Vec<InstanceResult>, ServiceError> {
+        envelope.check_hasher(self.config.merkle_hasher)?;
+        verify_with_components(envelope, components)
+    }
+
+    fn check_burn_limits(&self, inputs: &ProofOfBurnInputs) -> Result<(), ProofOfBurnError> {
+        let params = &self.params;
+        if inputs.layers.len() > params.max_num_layers {
+            return Err(ProofOfBurnError::TooManyLayers {
+                provided: inputs.layers.len(),
+                max: params.max_num_layers,
+            });
+        }
+        if inputs.intended_balance > U256::from(params.max_intended_balance) {
+            return Err(ProofOfBurnError::IntendedBalanceTooHigh {
+                value: inputs.intended_balance,
+                max: params.max_intended_balance,
+            });
+        }
+        if inputs.actual_balance > U256::from(params.max_actual_balance) {
+            return Err(ProofOfBurnError::ActualBalanceTooHigh {
+                value: inputs.actual_balance,
+                max: params.max_actual_balance,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Verify `envelope` against the components its proof was made with
+///
+/// Needs no prover state; `PobProver::verify_envelope` and `batch::verify_many` both call it.
+/// Runs `ProofEnvelope::compatibility_check` first, so an envelope shaped for other components
+/// fails with the mismatch rather than inside stwo's `verify`.
+///
+/// A batched burn envelope yields one result per instance (`ProofEnvelope::verify_burn_batch`),
+/// any other envelope a single passing result; an error fails the whole envelope. A proof stwo
+/// rejects is reported as `ServiceError::Verification` and logged with its phase.
+pub fn verify_with_components(
+    envelope: &ProofEnvelope,
+    components: &ProvedComponents,
+) -> Result<Vec<InstanceResult>, ServiceError> {
+    if envelope.circuit != components.circuit() {
+        return Err(ServiceError::CircuitMismatch { circuit: envelope.circuit });
+    }
+    envelope.compatibility_check(components)?;
+    let passed = || vec![InstanceResult { index: 0, result: Ok(()) }];
+    let result = match components {
+        ProvedComponents::Burn(components) if envelope.circuit == CircuitKind::Burn => {
+            if envelope.instances.is_empty() {
+                envelope.verify_burn(components).map(|()| passed())
+            } else {
+                envelope.verify_burn_batch(components)
+            }
+        }
+        ProvedComponents::Spend(components) if envelope.circuit == CircuitKind::Spend => {
+            envelope.verify_spend(components).map(|()| passed())
+        }
+        _ => return Err(ServiceError::CircuitMismatch { circuit: envelope.circuit }),
+    };
+    result.map_err(|e| match e {
+        // A proof stwo rejects is a verification failure, whichever statement it was checked with
+        EnvelopeError::Statement(
+            StatementError::Verification(failure)
+            | StatementError::Extraction(ExtractionError::Verification(failure)),
+        ) => {
+            failure.log();
+            ServiceError::Verification(failure)
+        }
+        e => e.into(),
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("Burn inputs rejected: {0}")]
+    BurnInputs(#[from] ProofOfBurnError),
+
+    #[error("Spend inputs rejected: {0}")]
+    SpendInputs(#[from] SpendError),
+
+    #[error("Proof generation failed: {0}")]
+    Prove(anyhow::Error),
+
+    #[error("Envelope error: {0}")]
+    Envelope(#[from] EnvelopeError),
+
+    #[error("Envelope does not fit the components: {0}")]
+    Compat(CompatError),
+
+    #[error("Verification failed: {0}")]
+    Verification(#[from] VerificationFailure),
+
+    #[error("Components do not belong to a {circuit:?} envelope")]
+    CircuitMismatch { circuit: CircuitKind },
+}
+
+impl From<CompatError> for ServiceError {
+    fn from(e: CompatError) -> Self {
+        match e {
+            // Keep envelope errors where callers already match them
+            CompatError::Envelope(e) => ServiceError::Envelope(e),
+            e => ServiceError::Compat(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use stwo_prover::core::proof::StarkProof;
+
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::field::M31;
+    use crate::prover::{
+        ParameterFingerprints, ReplayScope, SpendStatement, TreeRole, VerificationPhase,
+        VerifyOptions,
+    };
+    use crate::secret::Secret;
+
+    const LOG_N_ROWS: u32 = 5;
+
+    fn burn_inputs(burn_key: u32) -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    fn spend_inputs(burn_key: u32) -> SpendInputs {
+        SpendInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        }
+    }
+
+    fn prover() -> PobProver {
+        PobProver::new(StarkConfig::default(), CircuitParams::default()).with_log_n_rows(LOG_N_ROWS)
+    }
+
+    #[test]
+    fn test_prover_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PobProver>();
+        assert_send_sync::<ServiceProof>();
+    }
+
+    #[test]
+    fn test_concurrent_proofs_through_one_instance() {
+        let prover = Arc::new(prover());
+        let handles: Vec<_> = (0..8u32)
+            .map(|i| {
+                let prover = Arc::clone(&prover);
+                std::thread::spawn(move || {
+                    if i % 2 == 0 {
+                        prover.prove_burn(&burn_inputs(1000 + i)).unwrap()
+                    } else {
+                        prover.prove_spend(&spend_inputs(1000 + i)).unwrap()
+                    }
+                })
+            })
+            .collect();
+        let proofs: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+
+        for proof in &proofs {
+            prover.verify_envelope(&proof.envelope, &proof.components).unwrap();
+        }
+        // Different burn keys, different public inputs
+        for (i, a) in proofs.iter().enumerate() {
+            for b in &proofs[i + 1..] {
+                assert_ne!(a.envelope.public_inputs, b.envelope.public_inputs);
+            }
+        }
+    }
+
+    #[test]
+    fn test_proofs_match_the_free_functions() {
+        let inputs = burn_inputs(12345);
+        let proof = prover().prove_burn(&inputs).unwrap();
+        let (_, expected) =
+            crate::prover::prove_proof_of_burn(&inputs, LOG_N_ROWS, StarkConfig::default()).unwrap();
+        assert_eq!(proof.envelope.proof, serde_json::to_vec(&expected).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_inputs_outside_params() {
+        let params = CircuitParams { max_num_layers: 0, ..CircuitParams::default() };
+        let prover = PobProver::new(StarkConfig::default(), params).with_log_n_rows(LOG_N_ROWS);
+        assert!(matches!(
+            prover.prove_burn(&burn_inputs(1)),
+            Err(ServiceError::BurnInputs(ProofOfBurnError::TooManyLayers { provided: 1, max: 0 }))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_components_of_another_circuit() {
+        let prover = prover();
+        let burn = prover.prove_burn(&burn_inputs(1)).unwrap();
+        let spend = prover.prove_spend(&spend_inputs(1)).unwrap();
+        assert!(matches!(
+            prover.verify_envelope(&burn.envelope, &spend.components),
+            Err(ServiceError::CircuitMismatch { circuit: CircuitKind::Burn })
+        ));
+    }
+
+    #[test]
+    fn test_mismatched_shapes_reported_before_verify() {
+        let prover = prover();
+        let burn = prover.prove_burn(&burn_inputs(1)).unwrap();
+        let spend = prover.prove_spend(&spend_inputs(1)).unwrap();
+        for proof in [&burn, &spend] {
+            proof.envelope.compatibility_check(&proof.components).unwrap();
+        }
+
+        let mut resized = burn.envelope.clone();
+        resized.log_n_rows = LOG_N_ROWS + 1;
+        assert!(matches!(
+            prover.verify_envelope(&resized, &burn.components),
+            Err(ServiceError::Compat(CompatError::TraceSizeMismatch { expected: LOG_N_ROWS, found }))
+                if found == LOG_N_ROWS + 1
+        ));
+
+        // A FRI configuration that cannot fold the smallest column, re-serialized into the envelope
+        let mut refolded = spend.envelope.clone();
+        let mut proof = refolded.stark_proof::<Blake2sMerkleHasher>().unwrap();
+        proof.0.config.fri_config.log_last_layer_degree_bound = LOG_N_ROWS;
+        refolded.proof = serde_json::to_vec(&proof).unwrap().into();
+        assert!(matches!(
+            prover.verify_envelope(&refolded, &spend.components),
+            Err(ServiceError::Compat(CompatError::FriConfigMismatch { min_log_size: LOG_N_ROWS, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_parameter_mismatch_reported_before_verify() {
+        let prover = prover();
+        let burn = prover.prove_burn(&burn_inputs(1)).unwrap();
+        let current = ParameterFingerprints::current();
+        assert_eq!(burn.envelope.parameter_fingerprints(), current);
+
+        // Proven with other Poseidon2 constants
+        let mut foreign = burn.envelope.clone();
+        foreign.poseidon_params_fingerprint.0[31] ^= 1;
+        match prover.verify_envelope(&foreign, &burn.components) {
+            Err(ServiceError::Compat(CompatError::ParameterMismatch { expected, found })) => {
+                assert_eq!(expected, current);
+                assert_eq!(found, foreign.parameter_fingerprints());
+            }
+            other => panic!("expected a parameter mismatch, got {other:?}"),
+        }
+
+        // Proven with other circuit limits
+        let spend = prover.prove_spend(&spend_inputs(1)).unwrap();
+        let mut foreign = spend.envelope.clone();
+        foreign.circuit_params_hash.0[0] ^= 0x80;
+        assert!(matches!(
+            prover.verify_envelope(&foreign, &spend.components),
+            Err(ServiceError::Compat(CompatError::ParameterMismatch { .. }))
+        ));
+    }
+
+    fn poseidon_prover() -> PobProver {
+        let config = StarkConfig { merkle_hasher: MerkleHasherKind::Poseidon252, ..StarkConfig::default() };
+        PobProver::new(config, CircuitParams::default()).with_log_n_rows(LOG_N_ROWS)
+    }
+
+    #[test]
+    fn test_proves_and_verifies_under_each_hasher() {
+        for prover in [prover(), poseidon_prover()] {
+            let burn = prover.prove_burn(&burn_inputs(1)).unwrap();
+            let spend = prover.prove_spend(&spend_inputs(1)).unwrap();
+            for proof in [&burn, &spend] {
+                assert_eq!(proof.envelope.merkle_hasher, prover.config().merkle_hasher);
+                prover.verify_envelope(&proof.envelope, &proof.components).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_proofs_of_another_hasher() {
+        let (blake, poseidon) = (prover(), poseidon_prover());
+        let blake_burn = blake.prove_burn(&burn_inputs(1)).unwrap();
+        let poseidon_burn = poseidon.prove_burn(&burn_inputs(1)).unwrap();
+
+        // A verifier configured for Blake2s, or holding Blake2s components, rejects the envelope
+        assert!(matches!(
+            blake.verify_envelope(&poseidon_burn.envelope, &poseidon_burn.components),
+            Err(ServiceError::Envelope(EnvelopeError::HasherMismatch {
+                envelope: MerkleHasherKind::Poseidon252,
+                expected: MerkleHasherKind::Blake2s,
+            }))
+        ));
+        assert!(matches!(
+            verify_with_components(&poseidon_burn.envelope, &blake_burn.components),
+            Err(ServiceError::Envelope(EnvelopeError::HasherMismatch { .. }))
+        ));
+
+        // Relabelling the envelope does not make its proof verify under the other hasher
+        let mut relabelled = poseidon_burn.envelope.clone();
+        relabelled.merkle_hasher = MerkleHasherKind::Blake2s;
+        assert!(verify_with_components(&relabelled, &blake_burn.components).is_err());
+
+        // Nor do the free verifiers accept it with the other hasher's components
+        let ProvedComponents::Burn(blake_components) = &blake_burn.components else { unreachable!() };
+        let proof = poseidon_burn.envelope.stark_proof::<Poseidon252MerkleHasher>().unwrap();
+        assert!(matches!(
+            crate::prover::verify_proof_of_burn(blake_components, proof),
+            Err(VerificationFailure { phase: VerificationPhase::Setup, .. })
+        ));
+    }
+
+    #[test]
+    fn test_batch_flags_only_the_corrupted_instance() {
+        let prover = prover();
+        let inputs: Vec<ProofOfBurnInputs> = (1..=4).map(burn_inputs).collect();
+        let mut batch = prover.prove_burn_batch(&inputs).unwrap();
+        assert_eq!((batch.envelope.instances.len(), batch.envelope.public_inputs.len()), (4, 16));
+        assert_eq!(batch.envelope.statement, None);
+        let results = prover.verify_envelope(&batch.envelope, &batch.components).unwrap();
+        assert!(results.iter().all(InstanceResult::passed));
+
+        // Instance 2 claims instance 0's nullifier; the proof still verifies for the others
+        batch.envelope.instances[2].nullifier = batch.envelope.instances[0].nullifier;
+        let results = prover.verify_envelope(&batch.envelope, &batch.components).unwrap();
+        let passed: Vec<bool> = results.iter().map(InstanceResult::passed).collect();
+        assert_eq!(passed, [true, true, false, true]);
+        assert_eq!(results[2].index, 2);
+        assert!(matches!(
+            results[2].result,
+            Err(EnvelopeError::Statement(crate::prover::StatementError::PublicValuesMismatch))
+        ));
+
+        // A statement dropped from the list fails the whole envelope
+        batch.envelope.instances.pop();
+        assert!(matches!(
+            prover.verify_envelope(&batch.envelope, &batch.components),
+            Err(ServiceError::Envelope(EnvelopeError::InstanceCountMismatch { envelope: 3, components: 4 }))
+        ));
+    }
+
+    #[test]
+    fn test_replay_scope_is_checked_and_bound() {
+        let inputs = ProofOfBurnInputs {
+            chain_id: 11155111,
+            valid_until_block: Some(1000),
+            ..burn_inputs(7)
+        };
+        let proof = prover().prove_burn(&inputs).unwrap();
+        let ProvedComponents::Burn(components) = &proof.components else { unreachable!() };
+        let sepolia = VerifyOptions::for_chain(11155111);
+
+        // Not yet expired, up to and including the last valid block
+        for block in [999, 1000] {
+            proof.envelope.verify_burn_with_options(components, &sepolia.at_block(block)).unwrap();
+        }
+        assert!(matches!(
+            proof.envelope.check_replay(&sepolia.at_block(1001)),
+            Err(EnvelopeError::Expired { valid_until_block: 1000, current_block: 1001 })
+        ));
+        assert!(matches!(
+            proof.envelope.check_replay(&VerifyOptions::for_chain(MAINNET_CHAIN_ID)),
+            Err(EnvelopeError::WrongChain { expected: MAINNET_CHAIN_ID, found: 11155111 })
+        ));
+
+        // Relabelling the statement for mainnet passes the replay check but not verification
+        let mut relabelled = proof.envelope.clone();
+        relabelled.statement.as_mut().unwrap().chain_id = MAINNET_CHAIN_ID;
+        relabelled.check_replay(&VerifyOptions::for_chain(MAINNET_CHAIN_ID)).unwrap();
+        assert!(matches!(
+            relabelled.verify_burn(components),
+            Err(EnvelopeError::Statement(crate::prover::StatementError::ReplayScopeMismatch))
+        ));
+
+        // Nor does the proof verify with components claiming another scope
+        let stark_proof = proof.envelope.stark_proof::<Blake2sMerkleHasher>().unwrap();
+        let ProvedComponents::Burn(mut rescoped) = proof.components else { unreachable!() };
+        rescoped.scope = ReplayScope::default();
+        assert!(crate::prover::verify_proof_of_burn(&rescoped, stark_proof).is_err());
+    }
+
+    #[test]
+    fn test_spend_envelope_bound_to_its_statement() {
+        let prover = prover();
+        let proof = prover.prove_spend(&spend_inputs(9)).unwrap();
+        let statement = *proof.envelope.spend_statement().unwrap();
+        prover.verify_envelope(&proof.envelope, &proof.components).unwrap();
+        proof.envelope.check_spend_statement(&statement).unwrap();
+        proof.envelope.check_replay(&VerifyOptions::for_chain(MAINNET_CHAIN_ID)).unwrap();
+
+        // Claiming a larger withdrawal, public inputs and all, leaves the proof untouched
+        let mut inflated = proof.envelope.clone();
+        let claimed = SpendStatement { withdrawn_balance: U256::from(900), ..statement };
+        inflated.public_inputs = claimed.public_inputs().to_vec();
+        inflated.spend_statement = Some(claimed);
+        assert_eq!(inflated.proof, proof.envelope.proof);
+        assert!(matches!(
+            prover.verify_envelope(&inflated, &proof.components),
+            Err(ServiceError::Verification(_))
+        ));
+        assert!(matches!(
+            proof.envelope.check_spend_statement(&claimed),
+            Err(EnvelopeError::StatementMismatch { mismatches }) if mismatches[0].field == "withdrawn_balance"
+        ));
+
+        // Public inputs must still be the ones the statement derives
+        let mut stale = proof.envelope.clone();
+        stale.spend_statement = Some(claimed);
+        assert!(matches!(
+            prover.verify_envelope(&stale, &proof.components),
+            Err(ServiceError::Envelope(EnvelopeError::PublicInputsMismatch))
+        ));
+    }
+
+    #[test]
+    fn test_verification_failures_name_their_phase() {
+        let prover = prover();
+        let proof = prover.prove_spend(&spend_inputs(7)).unwrap();
+        let phase = |tamper: fn(&mut StarkProof<Blake2sMerkleHasher>)| {
+            let mut stark_proof = proof.envelope.stark_proof::<Blake2sMerkleHasher>().unwrap();
+            tamper(&mut stark_proof);
+            let envelope = ProofEnvelope {
+                proof: serde_json::to_vec(&stark_proof).unwrap().into(),
+                ..proof.envelope.clone()
+            };
+            match prover.verify_envelope(&envelope, &proof.components) {
+                Err(ServiceError::Verification(failure)) => failure.phase,
+                other => panic!("expected a verification failure, got {other:?}"),
+            }
+        };
+
+        // A trace root in place of the preprocessed one fails its replay, before stwo runs
+        let commitment = phase(|p| p.0.commitments[0] = p.0.commitments[1]);
+        assert_eq!(commitment, VerificationPhase::CommitmentReplay { tree: TreeRole::Preprocessed });
+
+        // A corrupted FRI commitment gets past the replay and fails inside stwo's `verify`
+        let fri = phase(|p| p.0.fri_proof.first_layer.commitment = p.0.commitments[1]);
+        assert_eq!(fri, VerificationPhase::Verify);
+        assert_ne!(commitment, fri);
+    }
+
+    #[test]
+    fn test_twiddle_cache_grows_only_when_needed() {
+        let cache = TwiddleCache::default();
+        let first = cache.get(8);
+        assert!(Arc::ptr_eq(&first, &cache.get(6)));
+        let grown = cache.get(9);
+        assert!(!Arc::ptr_eq(&first, &grown));
+        assert!(Arc::ptr_eq(&grown, &cache.get(8)));
+    }
+}
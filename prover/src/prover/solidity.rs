@@ -0,0 +1,397 @@
+// Solidity calldata encoding for STWO proofs
+// Bridges `ProofEnvelope` and the on-chain verifier contract
+//
+// Calldata layout (standard `abi.encode`, no function selector):
+//
+//   abi.encode(bytes32[] commitments, uint256[] publicInputs, bytes proof)
+//
+//   word 0   offset of `commitments`
+//   word 1   offset of `publicInputs`
+//   word 2   offset of `proof`
+//   ...      `commitments`: length, then one bytes32 per tree in `CONTRACT_TREE_ORDER`
+//   ...      `publicInputs`: length, then one uint256 per public input (envelope order)
+//   ...      `proof`: length, then the serialized proof right-padded to 32 bytes
+//
+// The contract decodes with `abi.decode(data, (bytes32[], uint256[], bytes))`. It reads each root
+// from a fixed slot of `commitments`, so roots are placed by their role in the envelope's layout,
+// not copied in commitment order; auxiliary trees are left out.
+
+use std::path::Path;
+
+use alloy_primitives::{Bytes, B256, U256};
+use alloy_sol_types::SolValue;
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+
+use crate::prover::envelope::{CircuitKind, EnvelopeError, ProofEnvelope};
+use crate::prover::packaging::ProofId;
+
+/// ABI tuple mirrored by the verifier contract
+type CalldataTuple = (Vec<B256>, Vec<U256>, Bytes);
+
+/// Calldata contents decoded back into Rust types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedCalldata {
+    pub commitments: Vec<B256>,
+    pub public_inputs: Vec<U256>,
+    pub proof: Bytes,
+}
+
+/// Roots of `envelope` in the slots the verifier contract reads them from
+///
+/// Ranked by `TreeRole::contract_slot` of their role in `envelope.layout`; roots of auxiliary
+/// trees are dropped.
+pub fn contract_commitments(envelope: &ProofEnvelope) -> Vec<B256> {
+    let mut roots: Vec<(usize, B256)> = envelope
+        .layout
+        .roles()
+        .iter()
+        .zip(&envelope.commitments)
+        .filter_map(|(role, root)| Some((role.contract_slot()?, *root)))
+        .collect();
+    roots.sort_by_key(|&(slot, _)| slot);
+    roots.into_iter().map(|(_, root)| root).collect()
+}
+
+/// Encode a proof envelope as verifier calldata
+///
+/// The encoding is deterministic: the same envelope always yields the same bytes.
+pub fn encode_proof_calldata(envelope: &ProofEnvelope) -> Vec<u8> {
+    let tuple: CalldataTuple = (
+        contract_commitments(envelope),
+        envelope.public_inputs.clone(),
+        envelope.proof.clone(),
+    );
+    tuple.abi_encode_params()
+}
+
+/// Decode calldata produced by `encode_proof_calldata`
+pub fn decode_proof_calldata(data: &[u8]) -> Result<DecodedCalldata, CalldataError> {
+    let (commitments, public_inputs, proof) =
+        <CalldataTuple as SolValue>::abi_decode_params(data, true)?;
+
+    Ok(DecodedCalldata {
+        commitments,
+        public_inputs,
+        proof,
+    })
+}
+
+/// Proof fixture consumed by Foundry tests via `vm.parseJson`
+///
+/// Field names are part of the contract test interface and must stay stable.
+/// Keys are declared in alphabetical order so the JSON can be decoded straight
+/// into a Solidity struct (Foundry requires alphabetical struct members).
+///
+/// | Key            | Type        | Contents                                             |
+/// |----------------|-------------|------------------------------------------------------|
+/// | `calldata`     | `bytes`     | Output of `encode_proof_calldata`, 0x-prefixed hex   |
+/// | `circuit`      | `string`    | `"burn"` or `"spend"`                                |
+/// | `commitments`  | `bytes32[]` | Merkle roots, see `contract_commitments`             |
+/// | `config`       | object      | Prover parameters, see `FoundryConfig`               |
+/// | `proofId`      | `bytes32`   | keccak256(publicCommitment, nullifier, commitment); burn only, `null` for spend |
+/// | `publicInputs` | `uint256[]` | Each public input as a 0x-prefixed 32-byte hex word  |
+/// | `version`      | `uint256`   | Envelope format version                              |
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundryFixture {
+    pub calldata: Bytes,
+    pub circuit: CircuitKind,
+    pub commitments: Vec<B256>,
+    pub config: FoundryConfig,
+    pub proof_id: Option<ProofId>,
+    pub public_inputs: Vec<B256>,
+    pub version: u16,
+}
+
+/// Prover parameters recorded in a Foundry fixture (alphabetical keys)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundryConfig {
+    pub log_blowup_factor: u32,
+    pub log_last_layer_degree_bound: u32,
+    pub log_n_rows: u32,
+    pub n_queries: usize,
+    pub pow_bits: u32,
+}
+
+impl FoundryFixture {
+    /// Build the fixture for an envelope
+    ///
+    /// The verifier contract hashes with Blake2s, so envelopes committed with another hasher are
+    /// rejected.
+    pub fn from_envelope(envelope: &ProofEnvelope) -> Result<Self, FixtureError> {
+        let proof = envelope.stark_proof::<Blake2sMerkleHasher>()?;
+
+        let proof_id = match envelope.circuit {
+            CircuitKind::Burn => Some(
+                envelope
+                    .proof_id()
+                    .ok_or(FixtureError::UnexpectedPublicInputs { found: envelope.public_inputs.len() })?,
+            ),
+            CircuitKind::Spend => None,
+        };
+
+        Ok(Self {
+            calldata: Bytes::from(encode_proof_calldata(envelope)),
+            circuit: envelope.circuit,
+            commitments: contract_commitments(envelope),
+            config: FoundryConfig {
+                log_blowup_factor: proof.config.fri_config.log_blowup_factor,
+                log_last_layer_degree_bound: proof.config.fri_config.log_last_layer_degree_bound,
+                log_n_rows: envelope.log_n_rows,
+                n_queries: proof.config.fri_config.n_queries,
+                pow_bits: proof.config.pow_bits,
+            },
+            proof_id,
+            public_inputs: envelope
+                .public_inputs
+                .iter()
+                .map(|input| B256::from(input.to_be_bytes::<32>()))
+                .collect(),
+            version: envelope.version,
+        })
+    }
+}
+
+/// Write a Foundry-compatible JSON fixture for an envelope
+pub fn export_foundry_fixture(
+    envelope: &ProofEnvelope,
+    path: impl AsRef<Path>,
+) -> Result<(), FixtureError> {
+    let fixture = FoundryFixture::from_envelope(envelope)?;
+    let json = serde_json::to_string_pretty(&fixture)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CalldataError {
+    #[error("Invalid calldata: {0}")]
+    Abi(#[from] alloy_sol_types::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+    #[error("Invalid envelope: {0}")]
+    Envelope(#[from] EnvelopeError),
+
+    #[error("Burn envelope must carry 4 public inputs, found {found}")]
+    UnexpectedPublicInputs { found: usize },
+
+    #[error("Fixture serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Failed to write fixture: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::circuits::proof_of_burn::{ProofOfBurnInputs, SecurityLevel};
+    use crate::field::M31;
+    use crate::prover::packaging::{compute_public_commitment, pack_security_level};
+    use crate::prover::envelope::{CircuitKind, ENVELOPE_VERSION};
+    use crate::prover::statement::StatementError;
+    use crate::prover::{CommitmentLayout, MerkleHasherKind};
+    use crate::secret::Secret;
+
+    /// abi.encode([0x11..11, 0x22..22], [1, 2, 3], 0xdeadbeef)
+    const GOLDEN_CALLDATA: &str = concat!(
+        "0000000000000000000000000000000000000000000000000000000000000060",
+        "00000000000000000000000000000000000000000000000000000000000000c0",
+        "0000000000000000000000000000000000000000000000000000000000000140",
+        "0000000000000000000000000000000000000000000000000000000000000002",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "2222222222222222222222222222222222222222222222222222222222222222",
+        "0000000000000000000000000000000000000000000000000000000000000003",
+        "0000000000000000000000000000000000000000000000000000000000000001",
+        "0000000000000000000000000000000000000000000000000000000000000002",
+        "0000000000000000000000000000000000000000000000000000000000000003",
+        "0000000000000000000000000000000000000000000000000000000000000004",
+        "deadbeef00000000000000000000000000000000000000000000000000000000",
+    );
+
+    fn golden_envelope() -> ProofEnvelope {
+        ProofEnvelope {
+            version: ENVELOPE_VERSION,
+            circuit: CircuitKind::Burn,
+            log_n_rows: 6,
+            commitments: vec![B256::repeat_byte(0x11), B256::repeat_byte(0x22)],
+            layout: CommitmentLayout::standard(false),
+            merkle_hasher: MerkleHasherKind::Blake2s,
+            poseidon_params_fingerprint: B256::ZERO,
+            circuit_params_hash: B256::ZERO,
+            public_inputs: vec![U256::from(1), U256::from(2), U256::from(3)],
+            statement: None,
+            instances: Vec::new(),
+            spend_statement: None,
+            claims: None,
+            proof: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        }
+    }
+
+    #[test]
+    fn test_encode_matches_golden_vector() {
+        let calldata = encode_proof_calldata(&golden_envelope());
+        assert_eq!(hex::encode(&calldata), GOLDEN_CALLDATA);
+    }
+
+    #[test]
+    fn test_calldata_roundtrip() {
+        let envelope = golden_envelope();
+        let decoded = decode_proof_calldata(&encode_proof_calldata(&envelope)).unwrap();
+
+        assert_eq!(decoded.commitments, envelope.commitments);
+        assert_eq!(decoded.public_inputs, envelope.public_inputs);
+        assert_eq!(decoded.proof, envelope.proof);
+    }
+
+    fn pob_inputs() -> ProofOfBurnInputs {
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            actual_balance: U256::from(1000000u64),
+            intended_balance: U256::from(1000000u64),
+            reveal_amount: U256::from(500000u64),
+            burn_extra_commitment: M31::from(100),
+            layers: vec![vec![0u8; 100]],
+            block_header: vec![0u8; 643],
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: 50,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    #[test]
+    fn test_foundry_fixture_roundtrip() {
+        use crate::prover::{prove_proof_of_burn, StarkConfig};
+
+        let inputs = pob_inputs();
+        let log_n_rows = 6;
+        let (components, proof) = prove_proof_of_burn(&inputs, log_n_rows, StarkConfig::default())
+            .expect("Failed to generate proof");
+
+        let security_level = pack_security_level(&SecurityLevel::from_relax(0));
+        let public_inputs = vec![U256::from(7), U256::from(8), U256::from(9), security_level];
+        let envelope = ProofEnvelope::from_stark_proof(
+            CircuitKind::Burn,
+            log_n_rows,
+            &proof,
+            components.layout.clone(),
+            public_inputs.clone(),
+        )
+        .unwrap();
+
+        let path = std::env::temp_dir().join("pob_foundry_fixture_roundtrip.json");
+        export_foundry_fixture(&envelope, &path).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let fixture: FoundryFixture = serde_json::from_str(&json).unwrap();
+        let decoded = decode_proof_calldata(&fixture.calldata).unwrap();
+
+        assert_eq!(decoded.public_inputs, public_inputs);
+        assert_eq!(decoded.commitments, envelope.commitments);
+        assert_eq!(fixture.config.log_n_rows, log_n_rows);
+        assert_eq!(fixture.config.n_queries, StarkConfig::default().fri_config.n_queries);
+        assert_eq!(
+            fixture.proof_id,
+            Some(ProofId::compute(public_inputs[0], public_inputs[1], public_inputs[2]))
+        );
+        assert_eq!(fixture.public_inputs[0], B256::from(U256::from(7).to_be_bytes::<32>()));
+    }
+
+    #[test]
+    fn test_burn_envelope_public_inputs_come_from_statement() {
+        use crate::prover::{prove_proof_of_burn, BurnStatement, PobPublicValues, StarkConfig};
+
+        let inputs = pob_inputs();
+        let (components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let statement = BurnStatement::new(&inputs, components.public_values);
+        let envelope = ProofEnvelope::from_burn_proof(6, &proof, components.layout.clone(), statement).unwrap();
+
+        let [public_commitment, nullifier, commitment, security_level] = envelope.public_inputs[..] else {
+            panic!("expected 4 burn public inputs, got {}", envelope.public_inputs.len());
+        };
+        let public_values: PobPublicValues = components.public_values;
+        assert_eq!(nullifier, public_values.nullifier_u256());
+        assert_eq!(commitment, public_values.commitment_u256());
+        assert_eq!(
+            public_commitment,
+            compute_public_commitment(statement.block_hash, nullifier, commitment, inputs.reveal_amount)
+        );
+        assert_eq!(security_level, pack_security_level(&SecurityLevel::from_relax(inputs.byte_security_relax)));
+        envelope.verify_burn(&components).expect("Envelope should verify against its statement");
+
+        // A statement claiming other outputs is rejected even with matching public inputs
+        let mut forged = envelope.clone();
+        let mut forged_statement = statement;
+        forged_statement.remaining_coin = M31::from(forged_statement.remaining_coin.value() + 1);
+        forged.statement = Some(forged_statement);
+        forged.public_inputs = ProofEnvelope::from_burn_proof(6, &proof, components.layout.clone(), forged_statement)
+            .unwrap()
+            .public_inputs;
+        assert!(matches!(
+            forged.verify_burn(&components),
+            Err(EnvelopeError::Statement(_))
+        ));
+
+        // Likewise a statement claiming another burn address, whose public inputs do not cover it
+        let mut forged_statement = statement;
+        forged_statement.burn_address_hash = B256::repeat_byte(0x42);
+        let forged = ProofEnvelope::from_burn_proof(6, &proof, components.layout.clone(), forged_statement).unwrap();
+        assert_eq!(forged.public_inputs, envelope.public_inputs);
+        assert!(matches!(
+            forged.verify_burn(&components),
+            Err(EnvelopeError::Statement(StatementError::PublicValuesMismatch))
+        ));
+    }
+
+    #[test]
+    fn test_burn_envelope_against_expected_statement() {
+        use crate::prover::{prove_proof_of_burn, BurnStatement, StarkConfig};
+
+        let inputs = pob_inputs();
+        let (components, proof) = prove_proof_of_burn(&inputs, 6, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let statement = BurnStatement::new(&inputs, components.public_values);
+        let envelope = ProofEnvelope::from_burn_proof(6, &proof, components.layout.clone(), statement).unwrap();
+
+        // Valid proof of the expected statement
+        envelope.verify_burn_against(&components, &statement).expect("Envelope should match its statement");
+
+        // Valid proof of another statement
+        let mut expected = statement;
+        expected.block_hash = B256::repeat_byte(0x42);
+        expected.burn_extra_commitment = M31::from(101);
+        let Err(EnvelopeError::StatementMismatch { mismatches }) =
+            envelope.verify_burn_against(&components, &expected)
+        else {
+            panic!("expected a statement mismatch");
+        };
+        let fields: Vec<_> = mismatches.iter().map(|mismatch| mismatch.field).collect();
+        assert_eq!(fields, ["block_hash", "burn_extra_commitment"]);
+
+        // Invalid proof: rejected before the statement is compared
+        let mut tampered = envelope.clone();
+        tampered.public_inputs[1] += U256::from(1);
+        assert!(matches!(
+            tampered.verify_burn_against(&components, &statement),
+            Err(EnvelopeError::PublicInputsMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_calldata() {
+        let calldata = encode_proof_calldata(&golden_envelope());
+        let result = decode_proof_calldata(&calldata[..calldata.len() - 32]);
+        assert!(result.is_err());
+    }
+}
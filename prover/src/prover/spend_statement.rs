@@ -0,0 +1,402 @@
+// Public statement of a Spend proof
+// What a verifier learns from a spend proof: the coin spent, the coin left over, how much was
+// withdrawn, the extra commitment, the recipient if the spend is bound to one, and the chain. The
+// commitment and the verifier contract's public inputs are derived from it, so a statement cannot
+// disagree with them.
+//
+// Byte encoding (`to_bytes`, big-endian throughout, 53 bytes without a recipient):
+//
+//   version             1    SPEND_STATEMENT_VERSION, or SPEND_STATEMENT_RECIPIENT_VERSION
+//   coin                4
+//   remaining_coin      4
+//   withdrawn_balance  32
+//   extra_commitment    4
+//   chain_id            8
+//   recipient          20    only in SPEND_STATEMENT_RECIPIENT_VERSION
+//
+// A statement without a recipient keeps version 1, so proofs made before recipients existed
+// still verify against the statements they were made for.
+//
+// The spend circuit has no nullifier of its own: the contract marks the spent coin, so `coin`
+// doubles as the nullifier (`nullifier()`).
+//
+// The prover mixes `statement_hash` into the channel before the range-check elements are drawn,
+// and the verifier mixes the statement it is given, so a proof made for one statement does not
+// verify against another, whatever field differs. Unlike the burn trace, the Spend trace does not
+// expose its outputs as public values yet, so the binding is through the transcript only.
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::channel::Channel;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::BackendForChannel;
+
+use crate::circuits::spend::{
+    bound_extra_commitment, spend_commitment_with, SpendInputs, SpendOutputs,
+};
+use crate::constants::{M31_PRIME, OUTPUT_LIMBS};
+use crate::field::M31;
+use crate::prover::packaging::limbs_to_u256;
+use crate::prover::statement::{FieldMismatch, StatementError};
+use crate::prover::{verify_spend_against, ProofHasher, SpendComponents};
+use crate::utils::poseidon2_stwo::poseidon2_permutation;
+
+/// Version byte leading the `SpendStatement` byte encoding
+pub const SPEND_STATEMENT_VERSION: u8 = 1;
+
+/// Version byte of the encoding of a statement with a recipient
+pub const SPEND_STATEMENT_RECIPIENT_VERSION: u8 = 2;
+
+/// Length of the `SpendStatement` byte encoding
+pub const SPEND_STATEMENT_LEN: usize = 1 + 4 + 4 + 32 + 4 + 8;
+
+/// Length of the encoding of a statement with a recipient
+pub const SPEND_STATEMENT_RECIPIENT_LEN: usize = SPEND_STATEMENT_LEN + 20;
+
+/// All public parameters of a spend proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct SpendStatement {
+    /// Coin being spent, which the contract marks spent
+    pub coin: M31,
+
+    /// Coin holding the balance left after the withdrawal
+    pub remaining_coin: M31,
+
+    /// Amount withdrawn from `coin`
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
+    pub withdrawn_balance: U256,
+
+    /// Extra commitment (e.g., receiver address, fees)
+    pub extra_commitment: M31,
+
+    /// Chain the proof is valid on
+    pub chain_id: u64,
+
+    /// Address the withdrawal is bound to, folded into the commitment with `extra_commitment`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schema", schemars(with = "Option<crate::schema::AddressHex>"))]
+    pub recipient: Option<Address>,
+}
+
+impl SpendStatement {
+    /// The statement proven for `inputs`, with the coins `SpendCircuit::compute_outputs` gives
+    pub fn new(inputs: &SpendInputs, outputs: &SpendOutputs) -> Self {
+        Self {
+            coin: outputs.coin,
+            remaining_coin: outputs.remaining_coin,
+            withdrawn_balance: inputs.withdrawn_balance,
+            extra_commitment: inputs.extra_commitment,
+            chain_id: inputs.chain_id,
+            recipient: inputs.recipient,
+        }
+    }
+
+    /// The value the contract nullifies: the spent coin itself
+    pub fn nullifier(&self) -> M31 {
+        self.coin
+    }
+
+    /// Commitment to the spend, as the Spend trace computes it
+    pub fn commitment(&self) -> [M31; OUTPUT_LIMBS] {
+        spend_commitment_with(
+            poseidon2_permutation,
+            self.coin,
+            self.withdrawn_balance,
+            self.remaining_coin,
+            bound_extra_commitment(self.extra_commitment, self.recipient),
+        )
+    }
+
+    /// The verifier contract's public inputs: [commitment, coin, remainingCoin]
+    pub fn public_inputs(&self) -> [U256; 3] {
+        [
+            limbs_to_u256(&self.commitment()),
+            U256::from(self.coin.value()),
+            U256::from(self.remaining_coin.value()),
+        ]
+    }
+
+    /// Mix the statement hash into the channel, before the range-check elements are drawn
+    pub(crate) fn mix_into(&self, channel: &mut impl Channel) {
+        let words: Vec<u32> = self
+            .statement_hash()
+            .chunks_exact(4)
+            .map(|word| u32::from_be_bytes(word.try_into().expect("4-byte word")))
+            .collect();
+        channel.mix_u32s(&words);
+    }
+
+    /// Verify `proof` against `components` and this statement
+    ///
+    /// The statement is replayed into the channel in place of the one the components were proven
+    /// for, so this fails for any statement but the proof's own.
+    pub fn verify<H: ProofHasher>(
+        &self,
+        components: &SpendComponents,
+        proof: StarkProof<H>,
+    ) -> Result<(), StatementError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        verify_spend_against(components, self, proof)?;
+        Ok(())
+    }
+
+    /// Canonical byte encoding, see the module comment for the layout
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SPEND_STATEMENT_RECIPIENT_LEN);
+        bytes.push(match self.recipient {
+            Some(_) => SPEND_STATEMENT_RECIPIENT_VERSION,
+            None => SPEND_STATEMENT_VERSION,
+        });
+        bytes.extend_from_slice(&self.coin.value().to_be_bytes());
+        bytes.extend_from_slice(&self.remaining_coin.value().to_be_bytes());
+        bytes.extend_from_slice(&self.withdrawn_balance.to_be_bytes::<32>());
+        bytes.extend_from_slice(&self.extra_commitment.value().to_be_bytes());
+        bytes.extend_from_slice(&self.chain_id.to_be_bytes());
+        if let Some(recipient) = self.recipient {
+            bytes.extend_from_slice(recipient.as_slice());
+        }
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, rejecting any encoding `to_bytes` does not produce
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StatementError> {
+        let with_recipient = bytes.first() == Some(&SPEND_STATEMENT_RECIPIENT_VERSION);
+        let expected_len = if with_recipient { SPEND_STATEMENT_RECIPIENT_LEN } else { SPEND_STATEMENT_LEN };
+        if bytes.len() != expected_len {
+            return Err(StatementError::Length { expected: expected_len, found: bytes.len() });
+        }
+        if !with_recipient && bytes[0] != SPEND_STATEMENT_VERSION {
+            return Err(StatementError::Version { expected: SPEND_STATEMENT_VERSION, found: bytes[0] });
+        }
+
+        // Limbs are numbered in encoding order: coin, remaining_coin, extra_commitment
+        let limb = |index: usize, offset: usize| {
+            let value = u32::from_be_bytes(bytes[offset..offset + 4].try_into().expect("4-byte limb"));
+            // M31::new would silently reduce 2^31 - 1 and above
+            if value >= M31_PRIME {
+                return Err(StatementError::NonCanonicalLimb { index });
+            }
+            Ok(M31::new(value))
+        };
+
+        Ok(Self {
+            coin: limb(0, 1)?,
+            remaining_coin: limb(1, 5)?,
+            withdrawn_balance: U256::from_be_slice(&bytes[9..41]),
+            extra_commitment: limb(2, 41)?,
+            chain_id: u64::from_be_bytes(bytes[45..53].try_into().expect("8-byte chain id")),
+            recipient: with_recipient.then(|| Address::from_slice(&bytes[SPEND_STATEMENT_LEN..])),
+        })
+    }
+
+    /// Fields of this statement that differ from `expected`, in encoding order
+    pub fn mismatches(&self, expected: &SpendStatement) -> Vec<FieldMismatch> {
+        let mut mismatches = Vec::new();
+        let mut compare = |field: &'static str, found: String, expected: String| {
+            if found != expected {
+                mismatches.push(FieldMismatch { field, expected, found });
+            }
+        };
+        compare("coin", self.coin.value().to_string(), expected.coin.value().to_string());
+        compare("remaining_coin", self.remaining_coin.value().to_string(), expected.remaining_coin.value().to_string());
+        compare("withdrawn_balance", self.withdrawn_balance.to_string(), expected.withdrawn_balance.to_string());
+        compare(
+            "extra_commitment",
+            self.extra_commitment.value().to_string(),
+            expected.extra_commitment.value().to_string(),
+        );
+        compare("chain_id", self.chain_id.to_string(), expected.chain_id.to_string());
+        let recipient = |recipient: Option<Address>| recipient.map_or("none".to_string(), |r| r.to_string());
+        compare("recipient", recipient(self.recipient), recipient(expected.recipient));
+        mismatches
+    }
+
+    /// keccak256 of the byte encoding
+    pub fn statement_hash(&self) -> B256 {
+        keccak256(self.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::spend::SpendCircuit;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::prover::{prove_spend, StarkConfig};
+    use crate::secret::Secret;
+
+    fn golden_statement() -> SpendStatement {
+        SpendStatement {
+            coin: M31::from(0x1234),
+            remaining_coin: M31::from(0x5678),
+            withdrawn_balance: U256::from(400_000_000_000_000_000u64),
+            extra_commitment: M31::from(100),
+            chain_id: MAINNET_CHAIN_ID,
+            recipient: None,
+        }
+    }
+
+    /// `golden_statement().to_bytes()`
+    const GOLDEN_BYTES: &str = concat!(
+        "01",
+        "00001234",
+        "00005678",
+        "000000000000000000000000000000000000000000000000058d15e176280000",
+        "00000064",
+        "0000000000000001",
+    );
+
+    fn spend_inputs() -> SpendInputs {
+        SpendInputs {
+            burn_key: Secret::new(M31::from(12345)),
+            balance: U256::from(1000),
+            withdrawn_balance: U256::from(400),
+            extra_commitment: M31::from(100),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        }
+    }
+
+    #[test]
+    fn test_byte_encoding_matches_golden_vector() {
+        let bytes = golden_statement().to_bytes();
+        assert_eq!(bytes.len(), SPEND_STATEMENT_LEN);
+        assert_eq!(hex::encode(&bytes), GOLDEN_BYTES);
+        assert_eq!(
+            hex::encode(golden_statement().statement_hash()),
+            "ccf030ac8f35e12bc0ae40351eaef15938e0523b3d724762b279f3d8d2ddf374"
+        );
+    }
+
+    #[test]
+    fn test_byte_roundtrip() {
+        let statement = golden_statement();
+        assert_eq!(SpendStatement::from_bytes(&statement.to_bytes()).unwrap(), statement);
+
+        let sepolia = SpendStatement { chain_id: 11155111, ..statement };
+        assert_eq!(SpendStatement::from_bytes(&sepolia.to_bytes()).unwrap(), sepolia);
+        assert_ne!(sepolia.statement_hash(), statement.statement_hash());
+
+        // A recipient is appended under its own version; the rest of the encoding is unchanged
+        let bound = SpendStatement { recipient: Some(Address::repeat_byte(0x33)), ..statement };
+        let bytes = bound.to_bytes();
+        assert_eq!(bytes.len(), SPEND_STATEMENT_RECIPIENT_LEN);
+        assert_eq!(bytes[0], SPEND_STATEMENT_RECIPIENT_VERSION);
+        assert_eq!(bytes[1..SPEND_STATEMENT_LEN], statement.to_bytes()[1..]);
+        assert_eq!(SpendStatement::from_bytes(&bytes).unwrap(), bound);
+        assert!(matches!(
+            SpendStatement::from_bytes(&bytes[..SPEND_STATEMENT_LEN]),
+            Err(StatementError::Length { expected: SPEND_STATEMENT_RECIPIENT_LEN, .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_noncanonical_encodings() {
+        let bytes = golden_statement().to_bytes();
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = 3;
+        assert!(matches!(SpendStatement::from_bytes(&wrong_version), Err(StatementError::Version { found: 3, .. })));
+
+        // The extra commitment set to 2^31 - 1
+        let mut noncanonical = bytes.clone();
+        noncanonical[41..45].copy_from_slice(&M31_PRIME.to_be_bytes());
+        assert!(matches!(
+            SpendStatement::from_bytes(&noncanonical),
+            Err(StatementError::NonCanonicalLimb { index: 2 })
+        ));
+
+        assert!(matches!(
+            SpendStatement::from_bytes(&bytes[1..]),
+            Err(StatementError::Length { expected: SPEND_STATEMENT_LEN, .. })
+        ));
+    }
+
+    #[test]
+    fn test_json_roundtrip_is_canonical() {
+        let statement = golden_statement();
+        let json = serde_json::to_string(&statement).unwrap();
+        assert_eq!(serde_json::from_str::<SpendStatement>(&json).unwrap(), statement);
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["coin"] = M31_PRIME.into();
+        assert!(serde_json::from_value::<SpendStatement>(value.clone()).is_err());
+        value["coin"] = 0x1234.into();
+        value["nullifier"] = 0x1234.into();
+        assert!(serde_json::from_value::<SpendStatement>(value).is_err());
+    }
+
+    #[test]
+    fn test_public_inputs_match_the_circuit() {
+        let inputs = spend_inputs();
+        let outputs = SpendCircuit::new(inputs.clone()).unwrap().compute_outputs();
+        let statement = SpendStatement::new(&inputs, &outputs);
+        assert_eq!(statement.commitment(), outputs.commitment);
+        assert_eq!(statement.nullifier(), outputs.coin);
+        assert_eq!(
+            statement.public_inputs(),
+            [
+                limbs_to_u256(&outputs.commitment),
+                U256::from(outputs.coin.value()),
+                U256::from(outputs.remaining_coin.value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mismatches_name_the_differing_fields() {
+        let statement = golden_statement();
+        assert!(statement.mismatches(&statement).is_empty());
+
+        let expected = SpendStatement { withdrawn_balance: U256::from(1), chain_id: 10, ..statement };
+        let fields: Vec<_> = statement.mismatches(&expected).iter().map(|mismatch| mismatch.field).collect();
+        assert_eq!(fields, ["withdrawn_balance", "chain_id"]);
+
+        let bound = SpendStatement { recipient: Some(Address::repeat_byte(0x33)), ..statement };
+        let fields: Vec<_> = statement.mismatches(&bound).iter().map(|mismatch| mismatch.field).collect();
+        assert_eq!(fields, ["recipient"]);
+    }
+
+    #[test]
+    fn test_proof_rejected_against_another_withdrawn_balance() {
+        let inputs = spend_inputs();
+        let (components, proof) = prove_spend(&inputs, 6, StarkConfig::default()).unwrap();
+        let statement = components.statement;
+        let outputs = SpendCircuit::new(inputs.clone()).unwrap().compute_outputs();
+        assert_eq!(statement, SpendStatement::new(&inputs, &outputs));
+
+        // The proof is left as it is; only the claimed withdrawal changes
+        let inflated = SpendStatement { withdrawn_balance: statement.withdrawn_balance + U256::from(1), ..statement };
+        assert!(matches!(
+            inflated.verify(&components, proof.clone()),
+            Err(StatementError::Verification(_))
+        ));
+        statement.verify(&components, proof).unwrap();
+    }
+
+    #[test]
+    fn test_proof_rejected_against_another_recipient() {
+        let recipient = Address::repeat_byte(0x33);
+        let inputs = SpendInputs { recipient: Some(recipient), ..spend_inputs() };
+        let (components, proof) = prove_spend(&inputs, 6, StarkConfig::default()).unwrap();
+        let statement = components.statement;
+        assert_eq!(statement.recipient, Some(recipient));
+
+        // Whoever copies the proof cannot claim it pays them, nor that it pays no one
+        for other in [Some(Address::repeat_byte(0x44)), None] {
+            let redirected = SpendStatement { recipient: other, ..statement };
+            assert_ne!(redirected.commitment(), statement.commitment());
+            assert!(matches!(
+                redirected.verify(&components, proof.clone()),
+                Err(StatementError::Verification(_))
+            ));
+        }
+        statement.verify(&components, proof).unwrap();
+    }
+}
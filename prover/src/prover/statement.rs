@@ -0,0 +1,513 @@
+// Public statement of a Proof of Burn proof
+// Everything a verifier learns from a burn proof, in one place: the values the proof binds, the
+// values they are computed from, and the security level the commitment claims. The envelope,
+// the Solidity encoder and the verifier all read from this type.
+//
+// Byte encoding (`to_bytes`, version 3, big-endian throughout, 192 bytes):
+//
+//   version                 1    BURN_STATEMENT_VERSION
+//   block_hash             32
+//   burn_address_hash      32
+//   reveal_amount          32
+//   nullifier              32    OUTPUT_LIMBS limbs, 4 bytes each
+//   remaining_coin          4
+//   commitment             32    OUTPUT_LIMBS limbs, 4 bytes each
+//   burn_extra_commitment   4
+//   proof_extra_commitment  4
+//   chain_id                8
+//   has_expiry              1    1 if valid_until_block is set, else 0
+//   valid_until_block       8    0 without an expiry
+//   security                2    byte_security_relax, then a 0 byte reserved
+//
+// Only `byte_security_relax` is encoded; the other security fields are derived from it.
+//
+// The chain id and expiry are mixed into the channel before the lookup elements are drawn (see
+// `ReplayScope`), so a proof made for one chain or expiry does not verify for another. Whether an
+// expiry has passed is up to the verifier: `envelope::VerifyOptions` carries the current block.
+//
+// The proof binds the burn address through its Poseidon2 digest, a public output pinned to the
+// burn key, reveal amount and burn extra commitment in the trace. The keccak steps from that
+// digest to `burn_address_hash` are not proven yet: `verify` recomputes them from the digest the
+// proof binds, so a statement claiming another address hash does not verify.
+//
+// With a header chain, `block_hash` is the hash of its last header, while the trace hashes the
+// header the MPT proof is checked against. The links between them are checked when the inputs
+// are (`ProofOfBurnCircuit::new`, `header_chain::verify_chain`) but not proven yet.
+
+use alloy_primitives::{keccak256, B256, U256};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::channel::Channel;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::prover::backend::simd::SimdBackend;
+use stwo_prover::prover::backend::BackendForChannel;
+
+use crate::circuits::proof_of_burn::{ProofOfBurnInputs, SecurityLevel};
+use crate::constants::{M31_PRIME, MAINNET_CHAIN_ID, OUTPUT_LIMBS};
+use crate::field::M31;
+use crate::prover::packaging::{compute_public_commitment, limbs_to_u256, pack_security_level, ProofId};
+use crate::prover::public_values::{ExtractionError, PobPublicValues};
+use crate::prover::{ProofHasher, ProofOfBurnComponents, VerificationFailure};
+
+/// Version byte leading the `BurnStatement` byte encoding
+pub const BURN_STATEMENT_VERSION: u8 = 3;
+
+/// Length of the `BurnStatement` byte encoding
+pub const BURN_STATEMENT_LEN: usize = 1 + 32 + 32 + 32 + 4 * OUTPUT_LIMBS + 4 + 4 * OUTPUT_LIMBS + 4 + 4 + 8 + 1 + 8 + 2;
+
+/// Public inputs of the verifier contract: (publicCommitment, nullifier, commitment, securityLevel)
+pub type BurnSolidityTuple = (U256, U256, U256, U256);
+
+/// All public parameters of a burn proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BurnStatement {
+    /// keccak256 of the block header the burn is proven in, or of the last header of its header
+    /// chain
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::B256Hex"))]
+    pub block_hash: B256,
+
+    /// keccak256 of the burn address, its key in the state trie
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::B256Hex"))]
+    pub burn_address_hash: B256,
+
+    /// Amount revealed immediately upon proof submission
+    #[cfg_attr(feature = "schema", schemars(with = "crate::schema::U256Hex"))]
+    pub reveal_amount: U256,
+
+    /// Nullifier, as `OUTPUT_LIMBS` limbs
+    pub nullifier: [M31; OUTPUT_LIMBS],
+
+    /// Remaining coin
+    pub remaining_coin: M31,
+
+    /// Commitment, as `OUTPUT_LIMBS` limbs
+    pub commitment: [M31; OUTPUT_LIMBS],
+
+    /// Extra commitment of the burn address (receiver, fees, etc.)
+    pub burn_extra_commitment: M31,
+
+    /// Extra commitment for proof metadata (e.g., prover address)
+    pub proof_extra_commitment: M31,
+
+    /// Chain the proof is valid on; statements of envelopes before v11 are all mainnet
+    #[serde(default = "mainnet_chain_id")]
+    pub chain_id: u64,
+
+    /// Last block number at which the proof may be submitted; `None` for no expiry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until_block: Option<u64>,
+
+    /// Security level the commitment is bound to
+    pub security: SecurityLevel,
+}
+
+fn mainnet_chain_id() -> u64 {
+    MAINNET_CHAIN_ID
+}
+
+/// Chain and expiry a burn proof is bound to
+///
+/// The prover mixes it into the channel before drawing lookup elements and the verifier replays
+/// it from the components, so it is bound like the public values are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReplayScope {
+    pub chain_id: u64,
+    pub valid_until_block: Option<u64>,
+}
+
+impl ReplayScope {
+    /// The scope `inputs` are proven for
+    pub fn of(inputs: &ProofOfBurnInputs) -> Self {
+        Self { chain_id: inputs.chain_id, valid_until_block: inputs.valid_until_block }
+    }
+
+    /// Mix the scope into the channel, before the public values
+    pub(crate) fn mix_into(&self, channel: &mut impl Channel) {
+        let expiry = self.valid_until_block.unwrap_or(0);
+        channel.mix_u32s(&[
+            self.chain_id as u32,
+            (self.chain_id >> 32) as u32,
+            self.valid_until_block.is_some() as u32,
+            expiry as u32,
+            (expiry >> 32) as u32,
+        ]);
+    }
+}
+
+impl Default for ReplayScope {
+    fn default() -> Self {
+        Self { chain_id: MAINNET_CHAIN_ID, valid_until_block: None }
+    }
+}
+
+impl BurnStatement {
+    /// The statement proven for `inputs`, with the outputs read from the proof
+    ///
+    /// Take `public_values` from `PobPublicValues::extract` so they are the values the proof binds.
+    pub fn new(inputs: &ProofOfBurnInputs, public_values: PobPublicValues) -> Self {
+        Self {
+            block_hash: keccak256(inputs.bound_block_header()),
+            burn_address_hash: public_values.burn_address_hash(),
+            reveal_amount: inputs.reveal_amount,
+            nullifier: public_values.nullifier,
+            remaining_coin: public_values.remaining_coin,
+            commitment: public_values.commitment,
+            burn_extra_commitment: inputs.burn_extra_commitment,
+            proof_extra_commitment: inputs.proof_extra_commitment,
+            chain_id: inputs.chain_id,
+            valid_until_block: inputs.valid_until_block,
+            security: SecurityLevel::from_relax(inputs.byte_security_relax),
+        }
+    }
+
+    /// The chain and expiry this statement claims the proof is bound to
+    pub fn replay_scope(&self) -> ReplayScope {
+        ReplayScope { chain_id: self.chain_id, valid_until_block: self.valid_until_block }
+    }
+
+    /// Whether `public_values` are this statement's outputs
+    ///
+    /// The burn address is compared by hash: the statement holds `burn_address_hash`, the public
+    /// values the digest it is the double keccak of.
+    pub fn binds(&self, public_values: &PobPublicValues) -> bool {
+        public_values.nullifier == self.nullifier
+            && public_values.remaining_coin == self.remaining_coin
+            && public_values.commitment == self.commitment
+            && public_values.burn_address_hash() == self.burn_address_hash
+    }
+
+    /// Verify `proof` against `components`, requiring it to bind this statement's outputs
+    pub fn verify<H: ProofHasher>(
+        &self,
+        components: &ProofOfBurnComponents,
+        proof: StarkProof<H>,
+    ) -> Result<(), StatementError>
+    where
+        SimdBackend: BackendForChannel<H::Channel>,
+    {
+        if !self.binds(&components.public_values) {
+            return Err(StatementError::PublicValuesMismatch);
+        }
+        if self.replay_scope() != components.scope {
+            return Err(StatementError::ReplayScopeMismatch);
+        }
+        PobPublicValues::extract(components, proof)?;
+        Ok(())
+    }
+
+    /// Canonical byte encoding, see the module comment for the layout
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BURN_STATEMENT_LEN);
+        bytes.push(BURN_STATEMENT_VERSION);
+        bytes.extend_from_slice(self.block_hash.as_slice());
+        bytes.extend_from_slice(self.burn_address_hash.as_slice());
+        bytes.extend_from_slice(&self.reveal_amount.to_be_bytes::<32>());
+        let limbs = self
+            .nullifier
+            .iter()
+            .chain(std::iter::once(&self.remaining_coin))
+            .chain(self.commitment.iter())
+            .chain([&self.burn_extra_commitment, &self.proof_extra_commitment]);
+        for limb in limbs {
+            bytes.extend_from_slice(&limb.value().to_be_bytes());
+        }
+        bytes.extend_from_slice(&self.chain_id.to_be_bytes());
+        bytes.push(self.valid_until_block.is_some() as u8);
+        bytes.extend_from_slice(&self.valid_until_block.unwrap_or(0).to_be_bytes());
+        bytes.extend_from_slice(&[self.security.byte_security_relax, 0]);
+        bytes
+    }
+
+    /// Inverse of `to_bytes`, rejecting any encoding `to_bytes` does not produce
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StatementError> {
+        if bytes.len() != BURN_STATEMENT_LEN {
+            return Err(StatementError::Length { expected: BURN_STATEMENT_LEN, found: bytes.len() });
+        }
+        if bytes[0] != BURN_STATEMENT_VERSION {
+            return Err(StatementError::Version { expected: BURN_STATEMENT_VERSION, found: bytes[0] });
+        }
+
+        let (head, rest) = bytes[1..].split_at(96);
+        let (limb_bytes, tail) = rest.split_at(4 * (2 * OUTPUT_LIMBS + 3));
+        let mut limbs = limb_bytes
+            .chunks_exact(4)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let value = u32::from_be_bytes(chunk.try_into().expect("4-byte chunk"));
+                // M31::new would silently reduce 2^31 - 1 and above
+                if value >= M31_PRIME {
+                    return Err(StatementError::NonCanonicalLimb { index });
+                }
+                Ok(M31::new(value))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        let mut next_limbs = |n: usize| limbs.by_ref().take(n).collect::<Vec<_>>();
+
+        let nullifier = next_limbs(OUTPUT_LIMBS).try_into().expect("OUTPUT_LIMBS limbs");
+        let remaining_coin = next_limbs(1)[0];
+        let commitment = next_limbs(OUTPUT_LIMBS).try_into().expect("OUTPUT_LIMBS limbs");
+        let [burn_extra_commitment, proof_extra_commitment]: [M31; 2] =
+            next_limbs(2).try_into().expect("two extra commitments");
+
+        let chain_id = u64::from_be_bytes(tail[..8].try_into().expect("8-byte chain id"));
+        let expiry = u64::from_be_bytes(tail[9..17].try_into().expect("8-byte expiry"));
+        let valid_until_block = match (tail[8], expiry) {
+            (1, _) => Some(expiry),
+            (0, 0) => None,
+            (flag, _) => return Err(StatementError::NonCanonicalExpiry { flag }),
+        };
+
+        let (byte_security_relax, reserved) = (tail[17], tail[18]);
+        if reserved != 0 {
+            return Err(StatementError::ReservedByte { found: reserved });
+        }
+
+        Ok(Self {
+            block_hash: B256::from_slice(&head[..32]),
+            burn_address_hash: B256::from_slice(&head[32..64]),
+            reveal_amount: U256::from_be_slice(&head[64..]),
+            nullifier,
+            remaining_coin,
+            commitment,
+            burn_extra_commitment,
+            proof_extra_commitment,
+            chain_id,
+            valid_until_block,
+            security: SecurityLevel::from_relax(byte_security_relax),
+        })
+    }
+
+    /// Fields of this statement that differ from `expected`, in encoding order
+    pub fn mismatches(&self, expected: &BurnStatement) -> Vec<FieldMismatch> {
+        let mut mismatches = Vec::new();
+        let mut compare = |field: &'static str, found: String, expected: String| {
+            if found != expected {
+                mismatches.push(FieldMismatch { field, expected, found });
+            }
+        };
+        compare("block_hash", self.block_hash.to_string(), expected.block_hash.to_string());
+        compare("burn_address_hash", self.burn_address_hash.to_string(), expected.burn_address_hash.to_string());
+        compare("reveal_amount", self.reveal_amount.to_string(), expected.reveal_amount.to_string());
+        let limbs = |limbs: &[M31]| format!("{:?}", limbs.iter().map(M31::value).collect::<Vec<_>>());
+        compare("nullifier", limbs(&self.nullifier), limbs(&expected.nullifier));
+        compare("remaining_coin", self.remaining_coin.value().to_string(), expected.remaining_coin.value().to_string());
+        compare("commitment", limbs(&self.commitment), limbs(&expected.commitment));
+        compare(
+            "burn_extra_commitment",
+            self.burn_extra_commitment.value().to_string(),
+            expected.burn_extra_commitment.value().to_string(),
+        );
+        compare(
+            "proof_extra_commitment",
+            self.proof_extra_commitment.value().to_string(),
+            expected.proof_extra_commitment.value().to_string(),
+        );
+        compare("chain_id", self.chain_id.to_string(), expected.chain_id.to_string());
+        let expiry = |block: Option<u64>| block.map_or_else(|| "none".to_string(), |block| block.to_string());
+        compare("valid_until_block", expiry(self.valid_until_block), expiry(expected.valid_until_block));
+        compare(
+            "byte_security_relax",
+            self.security.byte_security_relax.to_string(),
+            expected.security.byte_security_relax.to_string(),
+        );
+        mismatches
+    }
+
+    /// keccak256 of the byte encoding
+    pub fn statement_hash(&self) -> B256 {
+        keccak256(self.to_bytes())
+    }
+
+    /// The verifier contract's public inputs
+    ///
+    /// publicCommitment is keccak256(abi.encodePacked(blockHash, nullifier, commitment,
+    /// revealAmount)) >> 8 as per Commitments.sol; nullifier and commitment are packed with
+    /// `limbs_to_u256`, the security level with `pack_security_level`.
+    pub fn to_solidity_tuple(&self) -> BurnSolidityTuple {
+        let nullifier = limbs_to_u256(&self.nullifier);
+        let commitment = limbs_to_u256(&self.commitment);
+        let public_commitment =
+            compute_public_commitment(self.block_hash, nullifier, commitment, self.reveal_amount);
+        (public_commitment, nullifier, commitment, pack_security_level(&self.security))
+    }
+
+    /// The id the verifier contract records for a proof of this statement
+    pub fn proof_id(&self) -> ProofId {
+        let (public_commitment, nullifier, commitment, _security_level) = self.to_solidity_tuple();
+        ProofId::compute(public_commitment, nullifier, commitment)
+    }
+}
+
+/// A statement field whose value differs from the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMismatch {
+    pub field: &'static str,
+    pub expected: String,
+    pub found: String,
+}
+
+impl std::fmt::Display for FieldMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: expected {}, found {}", self.field, self.expected, self.found)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatementError {
+    #[error("Statement encoding is {found} bytes, expected {expected}")]
+    Length { expected: usize, found: usize },
+
+    #[error("Unsupported statement version {found}, expected {expected}")]
+    Version { expected: u8, found: u8 },
+
+    #[error("Limb {index} of the statement is not a canonical M31 element")]
+    NonCanonicalLimb { index: usize },
+
+    #[error("Reserved statement byte is {found}, expected 0")]
+    ReservedByte { found: u8 },
+
+    #[error("Statement expiry flag {flag} is not 1, nor 0 with a zero block number")]
+    NonCanonicalExpiry { flag: u8 },
+
+    #[error("Proof components are bound to other public values than the statement")]
+    PublicValuesMismatch,
+
+    #[error("Proof components are bound to another chain id or expiry than the statement")]
+    ReplayScopeMismatch,
+
+    #[error(transparent)]
+    Extraction(#[from] ExtractionError),
+
+    #[error("Proof does not verify against the statement: {0}")]
+    Verification(#[from] VerificationFailure),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn golden_statement() -> BurnStatement {
+        BurnStatement {
+            block_hash: B256::repeat_byte(0xab),
+            burn_address_hash: B256::repeat_byte(0xcd),
+            reveal_amount: U256::from(500_000_000_000_000_000u64),
+            nullifier: std::array::from_fn(|i| M31::from(i as u32 + 1)),
+            remaining_coin: M31::from(0x1234),
+            commitment: std::array::from_fn(|i| M31::from(0x100 + i as u32)),
+            burn_extra_commitment: M31::from(100),
+            proof_extra_commitment: M31::from(200),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: Some(19_000_000),
+            security: SecurityLevel::from_relax(1),
+        }
+    }
+
+    /// `golden_statement().to_bytes()`
+    const GOLDEN_BYTES: &str = concat!(
+        "03",
+        "abababababababababababababababababababababababababababababababab",
+        "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+        "00000000000000000000000000000000000000000000000006f05b59d3b20000",
+        "0000000100000002000000030000000400000005000000060000000700000008",
+        "00001234",
+        "0000010000000101000001020000010300000104000001050000010600000107",
+        "00000064",
+        "000000c8",
+        "0000000000000001",
+        "01",
+        "000000000121eac0",
+        "0100",
+    );
+
+    #[test]
+    fn test_byte_encoding_matches_golden_vector() {
+        let bytes = golden_statement().to_bytes();
+        assert_eq!(bytes.len(), BURN_STATEMENT_LEN);
+        assert_eq!(hex::encode(&bytes), GOLDEN_BYTES);
+    }
+
+    #[test]
+    fn test_statement_hash_matches_golden_vector() {
+        assert_eq!(
+            hex::encode(golden_statement().statement_hash()),
+            "ed278ef59258a509697c884646e520b96f50d2f496dc742e1867fa8f90996a51"
+        );
+    }
+
+    #[test]
+    fn test_byte_roundtrip() {
+        let statement = golden_statement();
+        assert_eq!(BurnStatement::from_bytes(&statement.to_bytes()).unwrap(), statement);
+
+        let unbounded = BurnStatement { chain_id: 11155111, valid_until_block: None, ..statement };
+        assert_eq!(BurnStatement::from_bytes(&unbounded.to_bytes()).unwrap(), unbounded);
+        assert_ne!(unbounded.statement_hash(), statement.statement_hash());
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_noncanonical_encodings() {
+        let bytes = golden_statement().to_bytes();
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = 1;
+        assert!(matches!(BurnStatement::from_bytes(&wrong_version), Err(StatementError::Version { found: 1, .. })));
+
+        // The first nullifier limb set to 2^31 - 1
+        let mut noncanonical = bytes.clone();
+        noncanonical[97..101].copy_from_slice(&M31_PRIME.to_be_bytes());
+        assert!(matches!(
+            BurnStatement::from_bytes(&noncanonical),
+            Err(StatementError::NonCanonicalLimb { index: 0 })
+        ));
+
+        // The expiry flag cleared while the block number is left in place
+        let mut expiry = bytes.clone();
+        expiry[BURN_STATEMENT_LEN - 11] = 0;
+        assert!(matches!(BurnStatement::from_bytes(&expiry), Err(StatementError::NonCanonicalExpiry { flag: 0 })));
+        expiry[BURN_STATEMENT_LEN - 11] = 2;
+        assert!(matches!(BurnStatement::from_bytes(&expiry), Err(StatementError::NonCanonicalExpiry { flag: 2 })));
+
+        let mut reserved = bytes.clone();
+        *reserved.last_mut().unwrap() = 1;
+        assert!(matches!(BurnStatement::from_bytes(&reserved), Err(StatementError::ReservedByte { .. })));
+
+        assert!(matches!(BurnStatement::from_bytes(&bytes[1..]), Err(StatementError::Length { .. })));
+    }
+
+    #[test]
+    fn test_mismatches_name_the_differing_fields() {
+        let statement = golden_statement();
+        assert!(statement.mismatches(&statement).is_empty());
+
+        let mut expected = statement;
+        expected.reveal_amount = U256::from(1);
+        expected.proof_extra_commitment = M31::from(201);
+        let mismatches = statement.mismatches(&expected);
+        let fields: Vec<_> = mismatches.iter().map(|mismatch| mismatch.field).collect();
+        assert_eq!(fields, ["reveal_amount", "proof_extra_commitment"]);
+        assert_eq!(mismatches[0].expected, "1");
+        assert_eq!(mismatches[0].found, "500000000000000000");
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let statement = golden_statement();
+        let json = serde_json::to_string(&statement).unwrap();
+        assert_eq!(serde_json::from_str::<BurnStatement>(&json).unwrap(), statement);
+    }
+
+    #[test]
+    fn test_solidity_tuple() {
+        let statement = golden_statement();
+        let (public_commitment, nullifier, commitment, security_level) = statement.to_solidity_tuple();
+        assert_eq!(nullifier, limbs_to_u256(&statement.nullifier));
+        assert_eq!(commitment, limbs_to_u256(&statement.commitment));
+        assert_eq!(
+            public_commitment,
+            compute_public_commitment(statement.block_hash, nullifier, commitment, statement.reveal_amount)
+        );
+        assert_eq!(security_level, pack_security_level(&statement.security));
+        assert_eq!(statement.proof_id(), ProofId::compute(public_commitment, nullifier, commitment));
+    }
+}
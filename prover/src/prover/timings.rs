@@ -0,0 +1,86 @@
+// Wall-clock time of each proving phase
+// Every prover times its phases as it runs them and returns the breakdown on its components, so
+// a slow proof can be attributed without a profiler or a tracing subscriber.
+// The clock is `platform::Stopwatch`, which reads `performance.now()` in the `wasm` build.
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::poseidon2_stwo::PermutationCacheStats;
+
+/// Milliseconds spent in each phase of one proof
+///
+/// The phases are disjoint; `total_ms` also covers input validation and building the components,
+/// so it is at least the sum of the others.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProvingTimings {
+    /// Twiddle precomputation; near zero when the `TwiddleCache` already covers the domain
+    pub twiddles_ms: f64,
+    /// Main trace generation of every component
+    ///
+    /// Without a trace hook the PoB prover builds its own main trace column by column as it
+    /// commits it, so most of that work counts toward `commits_ms` instead.
+    pub trace_gen_ms: f64,
+    /// Committing the preprocessed and main trace trees
+    pub commits_ms: f64,
+    /// Drawing the lookup elements, then generating and committing the interaction trace
+    pub interaction_ms: f64,
+    /// stwo's `prove`: composition polynomial, FRI and openings
+    pub proof_ms: f64,
+    /// The whole prover call
+    pub total_ms: f64,
+    /// Poseidon2 table rows served from a `PermutationCache`, when trace generation used one
+    #[serde(default)]
+    pub permutation_cache: PermutationCacheStats,
+}
+
+impl ProvingTimings {
+    /// Sum of the individual phases
+    pub fn phases_ms(&self) -> f64 {
+        self.twiddles_ms + self.trace_gen_ms + self.commits_ms + self.interaction_ms + self.proof_ms
+    }
+}
+
+impl std::ops::Add for ProvingTimings {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            twiddles_ms: self.twiddles_ms + other.twiddles_ms,
+            trace_gen_ms: self.trace_gen_ms + other.trace_gen_ms,
+            commits_ms: self.commits_ms + other.commits_ms,
+            interaction_ms: self.interaction_ms + other.interaction_ms,
+            proof_ms: self.proof_ms + other.proof_ms,
+            total_ms: self.total_ms + other.total_ms,
+            permutation_cache: self.permutation_cache + other.permutation_cache,
+        }
+    }
+}
+
+impl std::iter::Sum for ProvingTimings {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |sum, timings| sum + timings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum_adds_field_by_field() {
+        let timings = ProvingTimings {
+            twiddles_ms: 1.0,
+            trace_gen_ms: 2.0,
+            commits_ms: 3.0,
+            interaction_ms: 4.0,
+            proof_ms: 5.0,
+            total_ms: 16.0,
+            permutation_cache: PermutationCacheStats { hits: 3, misses: 1 },
+        };
+        let sum: ProvingTimings = [timings, timings].into_iter().sum();
+        assert_eq!(sum.phases_ms(), 30.0);
+        assert_eq!(sum.total_ms, 32.0);
+        assert_eq!(sum.permutation_cache.hit_rate(), Some(0.75));
+        assert_eq!(std::iter::empty().sum::<ProvingTimings>(), ProvingTimings::default());
+    }
+}
@@ -0,0 +1,148 @@
+// Verification failures with their phase
+// stwo's `verify` says what failed (an OODS mismatch, a Merkle or FRI error) but not where. The
+// verifiers here run in phases: the setup checks, the replay of each trace tree's root into the
+// channel, drawing the lookup elements and checking the claimed sums cancel, then stwo's `verify`.
+// Each wraps its error in a `VerificationFailure` naming the phase, and the component when the
+// check concerns a single one. stwo's own checks span every component at once, so an OODS
+// mismatch names none; `debug::check_constraints` on the trace finds the failing constraint.
+
+use std::fmt;
+
+use stwo_prover::core::air::Component;
+use stwo_prover::core::pcs::TreeVec;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::verifier::VerificationError;
+
+use crate::prover::commitment_layout::TreeRole;
+use crate::prover::merkle_hasher::ProofHasher;
+
+/// Where in a verification a check failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationPhase {
+    /// Before the channel is touched: the hasher, the commitment layout and the tree count
+    Setup,
+    /// Replaying the root of `tree` into the channel
+    CommitmentReplay { tree: TreeRole },
+    /// Drawing the lookup elements and checking the components' claimed sums cancel
+    LookupElements,
+    /// stwo's `verify`: the sampled values, the OODS check, decommitments, FRI and proof of work
+    Verify,
+}
+
+impl fmt::Display for VerificationPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationPhase::Setup => f.write_str("setup"),
+            VerificationPhase::CommitmentReplay { tree } => write!(f, "{tree:?} commitment replay"),
+            VerificationPhase::LookupElements => f.write_str("lookup elements"),
+            VerificationPhase::Verify => f.write_str("verify"),
+        }
+    }
+}
+
+/// A `VerificationError` with the phase it was raised in
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "{phase}{} failed: {source}",
+    .component.map_or(String::new(), |i| format!(" of component {i}"))
+)]
+pub struct VerificationFailure {
+    pub phase: VerificationPhase,
+    /// Index of the component in the verifier's component list, if the check concerns only it
+    pub component: Option<usize>,
+    pub source: VerificationError,
+}
+
+impl VerificationFailure {
+    pub fn new(phase: VerificationPhase, source: VerificationError) -> Self {
+        Self { phase, component: None, source }
+    }
+
+    /// An `InvalidStructure` error raised in `phase`
+    pub(crate) fn invalid_structure(phase: VerificationPhase, reason: impl Into<String>) -> Self {
+        Self::new(phase, VerificationError::InvalidStructure(reason.into()))
+    }
+
+    /// Log the failure with its phase and component as fields
+    pub fn log(&self) {
+        tracing::warn!(
+            phase = %self.phase,
+            component = ?self.component,
+            "Proof verification failed: {}",
+            self.source
+        );
+    }
+}
+
+/// The bare stwo error, for callers that report no phase
+impl From<VerificationFailure> for VerificationError {
+    fn from(failure: VerificationFailure) -> Self {
+        failure.source
+    }
+}
+
+/// Require `proof` to sample every trace column of every component, naming the first component
+/// whose columns it stops short of
+///
+/// stwo checks the sample shape of all components at once, and a proof sampling fewer columns
+/// than they have fails without saying whose are missing.
+pub(crate) fn check_sampled_columns<H: ProofHasher>(
+    components: &[&dyn Component],
+    sizes: &TreeVec<Vec<u32>>,
+    proof: &StarkProof<H>,
+) -> Result<(), VerificationFailure> {
+    let found = &proof.sampled_values;
+    if found.len() <= sizes.len() {
+        return Err(VerificationFailure::invalid_structure(
+            VerificationPhase::Verify,
+            format!(
+                "proof samples {} trees, the components commit {} and the composition",
+                found.len(),
+                sizes.len()
+            ),
+        ));
+    }
+    for (tree, expected) in sizes.iter().enumerate() {
+        let sampled = found[tree].len();
+        if sampled == expected.len() {
+            continue;
+        }
+        // Components own consecutive columns of every tree, in the order `concat_cols` put them
+        let mut end = 0;
+        let component = components.iter().position(|component| {
+            end += component.trace_log_degree_bounds().get(tree).map_or(0, Vec::len);
+            sampled < end
+        });
+        return Err(VerificationFailure {
+            phase: VerificationPhase::Verify,
+            component,
+            source: VerificationError::InvalidStructure(format!(
+                "tree {tree}: proof samples {sampled} columns, the components have {}",
+                expected.len()
+            )),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_names_phase_and_component() {
+        let oods = VerificationError::OodsNotMatching;
+        let expected = format!("verify failed: {oods}");
+        let failure = VerificationFailure::new(VerificationPhase::Verify, oods);
+        assert_eq!(failure.to_string(), expected);
+
+        let replay = VerificationPhase::CommitmentReplay { tree: TreeRole::Preprocessed };
+        let failure = VerificationFailure {
+            component: Some(3),
+            ..VerificationFailure::invalid_structure(replay, "root")
+        };
+        let message = failure.to_string();
+        assert!(message.starts_with("Preprocessed commitment replay of component 3 failed"), "{message}");
+        assert!(matches!(VerificationError::from(failure), VerificationError::InvalidStructure(_)));
+    }
+}
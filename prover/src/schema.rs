@@ -0,0 +1,238 @@
+// JSON Schemas of the input and output files (`schema` feature)
+// Integrators outside Rust validate against these instead of reading the struct definitions.
+// The alloy types serialize as 0x-prefixed hex strings, which schemars cannot see through their
+// hand-written serde impls; fields of those types take their schema from the stand-ins below.
+//
+// tests/schema_snapshots.rs compares every schema against tests/fixtures/schema/, so a change to
+// the serde format of these types shows up in review as a snapshot diff.
+
+use std::fmt;
+use std::str::FromStr;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{
+    InstanceType, Metadata, RootSchema, Schema, SchemaObject, StringValidation, SubschemaValidation,
+};
+use schemars::{schema_for, JsonSchema};
+
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::spend::SpendInputs;
+use crate::field::M31;
+use crate::prover::ProofEnvelope;
+
+/// Schema of a string matching `pattern`
+fn hex_string(description: &str, pattern: &str) -> Schema {
+    SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_owned()),
+            ..Default::default()
+        })),
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(pattern.to_owned()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Schema stand-in for `alloy_primitives::U256`
+pub struct U256Hex;
+
+impl JsonSchema for U256Hex {
+    fn schema_name() -> String {
+        "U256".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        // Written as hex; decimal strings are read as well
+        hex_string(
+            "256-bit unsigned integer, 0x-prefixed hex or decimal",
+            "^(0x[0-9a-fA-F]{1,64}|[0-9]{1,78})$",
+        )
+    }
+}
+
+/// Schema stand-in for `alloy_primitives::B256`
+pub struct B256Hex;
+
+impl JsonSchema for B256Hex {
+    fn schema_name() -> String {
+        "B256".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        hex_string("32 bytes, 0x-prefixed hex", "^0x[0-9a-fA-F]{64}$")
+    }
+}
+
+/// Schema stand-in for `alloy_primitives::Address`
+pub struct AddressHex;
+
+impl JsonSchema for AddressHex {
+    fn schema_name() -> String {
+        "Address".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        hex_string("20 bytes, 0x-prefixed hex", "^0x[0-9a-fA-F]{40}$")
+    }
+}
+
+/// Schema stand-in for `alloy_primitives::Bytes`
+pub struct BytesHex;
+
+impl JsonSchema for BytesHex {
+    fn schema_name() -> String {
+        "Bytes".to_owned()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        hex_string("Byte string, 0x-prefixed hex", "^0x([0-9a-fA-F]{2})*$")
+    }
+}
+
+/// Schema stand-in for the byte strings of input files (`utils::hex_bytes`)
+pub struct ByteString;
+
+impl JsonSchema for ByteString {
+    fn schema_name() -> String {
+        "ByteString".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // Written as hex; integer arrays, the earlier format, are read as well
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some("Byte string, 0x-prefixed hex or an array of bytes".to_owned()),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    hex_string("0x-prefixed hex", "^0x([0-9a-fA-F]{2})*$"),
+                    gen.subschema_for::<Vec<u8>>(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+// `M31` serializes through a hand-written impl as well, so its schema is written out here
+impl JsonSchema for M31 {
+    fn schema_name() -> String {
+        "M31".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // Written as a number; hex strings are read as well
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "M31 field element below 2^31 - 1, an integer or 0x-prefixed hex".to_owned(),
+                ),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    gen.subschema_for::<u32>(),
+                    hex_string("0x-prefixed hex", "^0x[0-9a-fA-F]{1,8}$"),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Schema stand-in for the M31 values of input files (`utils::hex_m31`)
+pub struct M31Hex;
+
+impl JsonSchema for M31Hex {
+    fn schema_name() -> String {
+        "M31Hex".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        // Written as hex; integers are read as well
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some("M31 field element, 0x-prefixed hex or an integer".to_owned()),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    hex_string("0x-prefixed hex", "^0x[0-9a-fA-F]{1,8}$"),
+                    gen.subschema_for::<u32>(),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A file format with a published schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaType {
+    /// `ProofOfBurnInputs`, the `generate-burn` input file
+    BurnInputs,
+    /// `SpendInputs`, the `generate-spend` input file
+    SpendInputs,
+    /// `ProofEnvelope`, written by `--emit-envelope`
+    Envelope,
+}
+
+impl SchemaType {
+    pub const ALL: [SchemaType; 3] = [SchemaType::BurnInputs, SchemaType::SpendInputs, SchemaType::Envelope];
+
+    /// Name on the command line and of the snapshot file
+    pub fn name(&self) -> &'static str {
+        match self {
+            SchemaType::BurnInputs => "burn-inputs",
+            SchemaType::SpendInputs => "spend-inputs",
+            SchemaType::Envelope => "envelope",
+        }
+    }
+
+    pub fn schema(&self) -> RootSchema {
+        match self {
+            SchemaType::BurnInputs => schema_for!(ProofOfBurnInputs),
+            SchemaType::SpendInputs => schema_for!(SpendInputs),
+            SchemaType::Envelope => schema_for!(ProofEnvelope),
+        }
+    }
+
+    /// The schema as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.schema()).expect("schemas serialize to JSON")
+    }
+}
+
+impl fmt::Display for SchemaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SchemaType {
+    type Err = UnknownSchemaType;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|schema_type| schema_type.name() == name)
+            .ok_or_else(|| UnknownSchemaType { name: name.to_owned() })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown schema type '{name}', expected one of: burn-inputs, spend-inputs, envelope")]
+pub struct UnknownSchemaType {
+    pub name: String,
+}
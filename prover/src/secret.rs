@@ -0,0 +1,110 @@
+// Wrapper for secret witness values (burn keys)
+// Redacts the value from Debug output and wipes it from memory on drop
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use stwo_prover::core::fields::m31::BaseField;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Secret value that is zeroized when dropped
+///
+/// Deliberately not `Copy`: every copy would be a stray plaintext the wrapper cannot wipe.
+#[derive(Clone)]
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Borrow the secret value
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize> ZeroizeOnDrop for Secret<T> {}
+
+impl<T: Zeroize> std::fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+impl<T: Zeroize + Serialize> Serialize for Secret<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Zeroize + Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+// Same JSON shape as the wrapped value
+#[cfg(feature = "schema")]
+impl<T: Zeroize + schemars::JsonSchema> schemars::JsonSchema for Secret<T> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        T::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        gen.subschema_for::<T>()
+    }
+}
+
+/// Wipe temporary stwo field elements that held secret-derived values
+pub fn zeroize_base_fields(values: &mut [BaseField]) {
+    for value in values.iter_mut() {
+        value.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::M31;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = Secret::new(M31::from(123456));
+        let debug = format!("{:?}", secret);
+        assert_eq!(debug, "Secret([REDACTED])");
+        assert!(!debug.contains("123456"));
+    }
+
+    #[test]
+    fn test_drop_zeroizes() {
+        let mut secret = std::mem::ManuallyDrop::new(Secret::new(M31::from(123456)));
+        // Run the destructor in place so the storage can still be inspected
+        unsafe { std::mem::ManuallyDrop::drop(&mut secret) };
+        assert_eq!(secret.0, M31::zero());
+    }
+
+    #[test]
+    fn test_serde_is_transparent() {
+        let secret = Secret::new(M31::from(42));
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, serde_json::to_string(&M31::from(42)).unwrap());
+
+        let decoded: Secret<M31> = serde_json::from_str(&json).unwrap();
+        assert_eq!(*decoded.expose_secret(), M31::from(42));
+    }
+}
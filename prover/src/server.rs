@@ -0,0 +1,574 @@
+// HTTP proving server (`server` feature)
+// Runs the prover on a machine with the cores for it while the wallet stays thin. Endpoints:
+//
+//   POST /prove/burn   burn inputs, the JSON of a generate-burn input file
+//   POST /prove/spend  spend inputs, the JSON of a generate-spend input file
+//   POST /verify       an envelope file, compressed or not, verified with `verify_offline`
+//   GET  /info         the prover's configuration and limits
+//
+// One `PobProver` serves every request, so twiddles stay warm across proofs. Inputs are checked
+// before anything is proven (the burn witness pre-check, `SpendCircuit::new`), and a failure is
+// answered with a JSON `ApiError`, `{code, message, details}` with the `E_*` code the CLI and the
+// WASM entry points report for the same failure (`error_code`).
+//
+// A prove request sent with `Accept: text/event-stream` is answered with server-sent events:
+// `progress` events (`{"phase": ...}`) while it runs, then a single `result` event carrying the
+// `ProveResponse` or an `error` event carrying the `ApiError`.
+//
+// HTTP/1.1 is spoken directly on std's `TcpListener`: one request per connection and request
+// bodies sized by Content-Length. Burn keys travel in request bodies, so bind to localhost or
+// serve behind a TLS proxy; with a token set, every request must carry
+// `Authorization: Bearer <token>`.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_primitives::{B256, U256};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::circuits::builder::{InputIssue, InputValidationReport};
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::spend::{SpendCircuit, SpendInputs};
+use crate::constants::CircuitParams;
+use crate::error_code::{ErrorCode, HasErrorCode};
+use crate::prover::{
+    CircuitKind, MerkleHasherKind, PobProver, ProofEnvelope, ProofId, ProvingTimings, ServiceError,
+    ServiceProof, VerificationReport, ENVELOPE_VERSION,
+};
+use crate::utils::mpt::layer_issues;
+use crate::witness::{precheck, read_json, InputFileError, DEFAULT_MAX_INPUT_BYTES};
+
+/// Largest request line and headers accepted: 16 KiB
+const MAX_HEAD_BYTES: u64 = 16 << 10;
+
+/// How long a client may pause while sending its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `GET /info`
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerInfo {
+    /// Version of this crate
+    pub version: &'static str,
+    /// Envelope format version of the proofs made
+    pub envelope_version: u16,
+    /// Log2 of the trace rows proofs are made with
+    pub log_n_rows: u32,
+    pub merkle_hasher: MerkleHasherKind,
+    /// Limits burn inputs are checked against
+    pub circuit_params: CircuitParams,
+    /// `CircuitParams::params_hash`, as envelopes record it
+    pub circuit_params_hash: B256,
+    /// Largest request body accepted, in bytes
+    pub max_body_bytes: u64,
+}
+
+/// Answer to `POST /prove/burn` and `POST /prove/spend`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProveResponse {
+    pub circuit: CircuitKind,
+    /// The envelope, uncompressed, as 0x-prefixed hex; the bytes `--emit-envelope` writes
+    pub envelope: String,
+    /// Public inputs of the verifier contract, as the envelope records them
+    pub public_inputs: Vec<U256>,
+    pub proof_id: Option<ProofId>,
+    pub timings: ProvingTimings,
+}
+
+impl ProveResponse {
+    fn new(proof: &ServiceProof) -> Result<Self, ApiError> {
+        let envelope = &proof.envelope;
+        let bytes = envelope.to_bytes().map_err(|e| {
+            ApiError::new(500, e.code(), format!("Failed to encode proof envelope: {e}"))
+        })?;
+        Ok(Self {
+            circuit: envelope.circuit,
+            envelope: format!("0x{}", hex::encode(bytes)),
+            public_inputs: envelope.public_inputs.clone(),
+            proof_id: envelope.proof_id(),
+            timings: *proof.components.timings(),
+        })
+    }
+}
+
+/// Answer to `POST /verify` for a valid envelope
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyResponse {
+    pub circuit: CircuitKind,
+    pub proof_id: Option<ProofId>,
+    /// Every check `verify_offline` made
+    pub report: VerificationReport,
+}
+
+/// An error answer: the HTTP status and the JSON body, an `ErrorReport`
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[error("{message}")]
+pub struct ApiError {
+    /// HTTP status of the response
+    #[serde(skip)]
+    pub status: u16,
+    pub code: ErrorCode,
+    pub message: String,
+    /// Structured detail, such as the pre-check or verification report
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    fn new(status: u16, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self { status, code, message: message.into(), details: None }
+    }
+
+    /// A request that is not well-formed
+    fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(400, ErrorCode::InputParse, message)
+    }
+
+    fn with_details(mut self, details: &impl Serialize) -> Self {
+        self.details = serde_json::to_value(details).ok();
+        self
+    }
+}
+
+impl From<ServiceError> for ApiError {
+    fn from(e: ServiceError) -> Self {
+        let status = match e {
+            ServiceError::BurnInputs(_) | ServiceError::SpendInputs(_) => 400,
+            _ => 500,
+        };
+        Self::new(status, e.code(), e.to_string())
+    }
+}
+
+/// A proving server bound to an address, not yet serving
+pub struct ProofServer {
+    listener: TcpListener,
+    handler: Handler,
+}
+
+impl ProofServer {
+    /// Bind to `addr`; port 0 picks a free port, see `local_addr`
+    pub fn bind(addr: impl ToSocketAddrs, prover: PobProver) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            handler: Handler { prover, token: None, max_body_bytes: DEFAULT_MAX_INPUT_BYTES },
+        })
+    }
+
+    /// Require `Authorization: Bearer <token>` on every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.handler.token = Some(token.into());
+        self
+    }
+
+    /// Refuse request bodies larger than `max_body_bytes` instead of `DEFAULT_MAX_INPUT_BYTES`
+    pub fn with_max_body_bytes(mut self, max_body_bytes: u64) -> Self {
+        self.handler.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// The address the server listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve requests on `workers` threads, at most that many at once; runs until the process
+    /// exits
+    pub fn run(self, workers: usize) -> io::Result<()> {
+        let handler = Arc::new(self.handler);
+        let threads = (0..workers.max(1))
+            .map(|_| {
+                let listener = self.listener.try_clone()?;
+                let handler = Arc::clone(&handler);
+                Ok(std::thread::spawn(move || handler.accept_loop(listener)))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        for thread in threads {
+            let _ = thread.join();
+        }
+        Ok(())
+    }
+}
+
+/// What every worker thread shares
+struct Handler {
+    prover: PobProver,
+    token: Option<String>,
+    max_body_bytes: u64,
+}
+
+impl Handler {
+    fn accept_loop(&self, listener: TcpListener) {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    // A panic fails the request, not the worker
+                    if catch_unwind(AssertUnwindSafe(|| self.handle(&stream))).is_err() {
+                        tracing::error!("Request handler panicked");
+                        let message = "The server failed while handling the request";
+                        let error = ApiError::new(500, ErrorCode::Internal, message);
+                        write_json(&mut &stream, 500, &error);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to accept a connection: {e}"),
+            }
+        }
+    }
+
+    fn handle(&self, stream: &TcpStream) {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let mut reader = BufReader::new(stream);
+        let mut writer = stream;
+        let result = read_head(&mut reader).and_then(|request| {
+            tracing::info!("{} {}", request.method, request.path);
+            self.authorize(&request)?;
+            self.route(&request, &mut reader, &mut writer)
+        });
+        if let Err(e) = result {
+            tracing::info!("Request failed ({}): {}", e.status, e.message);
+            write_json(&mut writer, e.status, &e);
+        }
+    }
+
+    fn route(
+        &self,
+        request: &Request,
+        reader: &mut impl Read,
+        writer: &mut impl Write,
+    ) -> Result<(), ApiError> {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/info") => {
+                write_json(writer, 200, &self.info());
+                Ok(())
+            }
+            ("POST", "/prove/burn") => {
+                let inputs = self.read_burn_inputs(request, reader)?;
+                self.run_proof(request, writer, || self.prover.prove_burn(&inputs))
+            }
+            ("POST", "/prove/spend") => {
+                let inputs: SpendInputs = self.read_body_json(request, reader)?;
+                SpendCircuit::new(inputs.clone()).map_err(|e| {
+                    ApiError::new(400, e.code(), format!("Spend inputs rejected: {e}"))
+                })?;
+                self.run_proof(request, writer, || self.prover.prove_spend(&inputs))
+            }
+            ("POST", "/verify") => self.verify(request, reader, writer),
+            (_, "/info" | "/prove/burn" | "/prove/spend" | "/verify") => Err(ApiError::new(
+                405,
+                ErrorCode::Route,
+                format!("{} is not allowed on {}", request.method, request.path),
+            )),
+            _ => {
+                let message = format!("No endpoint at {}", request.path);
+                Err(ApiError::new(404, ErrorCode::Route, message))
+            }
+        }
+    }
+
+    fn info(&self) -> ServerInfo {
+        let circuit_params = self.prover.params().clone();
+        ServerInfo {
+            version: env!("CARGO_PKG_VERSION"),
+            envelope_version: ENVELOPE_VERSION,
+            log_n_rows: self.prover.log_n_rows(),
+            merkle_hasher: self.prover.config().merkle_hasher,
+            circuit_params_hash: B256::from(circuit_params.params_hash()),
+            circuit_params,
+            max_body_bytes: self.max_body_bytes,
+        }
+    }
+
+    fn authorize(&self, request: &Request) -> Result<(), ApiError> {
+        let Some(token) = &self.token else {
+            return Ok(());
+        };
+        let presented = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if constant_time_eq(presented.trim().as_bytes(), token.as_bytes()) {
+            Ok(())
+        } else {
+            Err(ApiError::new(401, ErrorCode::Unauthorized, "Missing or wrong bearer token"))
+        }
+    }
+
+    /// Burn inputs of the request body, with malformed layers and failed pre-checks refused
+    fn read_burn_inputs(
+        &self,
+        request: &Request,
+        reader: &mut impl Read,
+    ) -> Result<ProofOfBurnInputs, ApiError> {
+        let inputs: ProofOfBurnInputs = self.read_body_json(request, reader)?;
+
+        // Reject malformed MPT layers before anything hashes or scans them
+        let layer_errors = layer_issues(&inputs.layers);
+        if let Some(first) = layer_errors.first() {
+            let code = first.code();
+            let issues = layer_errors.into_iter().map(InputIssue::from).collect();
+            let message = format!("Invalid MPT layers: {}", InputValidationReport { issues });
+            return Err(ApiError::new(400, code, message));
+        }
+
+        let report = precheck(&inputs, self.prover.params());
+        if !report.passed() {
+            let failed = report.failures().count();
+            let message = format!("Witness pre-check failed: {failed} check(s) did not pass");
+            return Err(ApiError::new(400, report.code(), message).with_details(&report));
+        }
+        Ok(inputs)
+    }
+
+    /// Prove, answering with one JSON body or, if the client asked for it, an event stream
+    fn run_proof(
+        &self,
+        request: &Request,
+        writer: &mut impl Write,
+        prove: impl FnOnce() -> Result<ServiceProof, ServiceError>,
+    ) -> Result<(), ApiError> {
+        let prove = || ProveResponse::new(&prove()?);
+        if !request.accepts_event_stream() {
+            write_json(writer, 200, &prove()?);
+            return Ok(());
+        }
+
+        // From here on the status is sent, so failures become `error` events
+        let mut events = EventStream::start(writer);
+        events.send("progress", &serde_json::json!({ "phase": "validated" }));
+        events.send("progress", &serde_json::json!({ "phase": "proving" }));
+        match prove() {
+            Ok(response) => events.send("result", &response),
+            Err(e) => {
+                tracing::info!("Proof failed: {}", e.message);
+                events.send("error", &e);
+            }
+        }
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        request: &Request,
+        reader: &mut impl Read,
+        writer: &mut impl Write,
+    ) -> Result<(), ApiError> {
+        let len = self.body_len(request)?;
+        let mut bytes = Vec::new();
+        reader
+            .take(len)
+            .read_to_end(&mut bytes)
+            .map_err(|e| ApiError::invalid_input(format!("Failed to read request body: {e}")))?;
+        let envelope = ProofEnvelope::from_bytes_auto(&bytes).map_err(|e| {
+            ApiError::new(400, e.code(), format!("Failed to decode proof envelope: {e}"))
+        })?;
+
+        let report = envelope.verify_offline();
+        if let Some((check, reason)) = report.failed_check() {
+            let message = format!("Proof is invalid: {check} check failed: {reason}");
+            let error = ApiError::new(422, ErrorCode::VerifyFail, message);
+            return Err(error.with_details(&report));
+        }
+        let response =
+            VerifyResponse { circuit: envelope.circuit, proof_id: envelope.proof_id(), report };
+        write_json(writer, 200, &response);
+        Ok(())
+    }
+
+    /// Length of the request body, which must be present and within `max_body_bytes`
+    fn body_len(&self, request: &Request) -> Result<u64, ApiError> {
+        if request.header("transfer-encoding").is_some() {
+            let message = "Request bodies must be sent with Content-Length";
+            return Err(ApiError::new(411, ErrorCode::InputParse, message));
+        }
+        let len = match request.header("content-length") {
+            Some(value) => value
+                .parse::<u64>()
+                .map_err(|_| ApiError::invalid_input(format!("Invalid Content-Length: {value}")))?,
+            None => 0,
+        };
+        if len == 0 {
+            // The request's counterpart of an input file that does not exist
+            let message = "Request has no body";
+            return Err(ApiError::new(400, ErrorCode::InputMissing, message));
+        }
+        if len > self.max_body_bytes {
+            return Err(too_large(self.max_body_bytes));
+        }
+        Ok(len)
+    }
+
+    fn read_body_json<T: DeserializeOwned>(
+        &self,
+        request: &Request,
+        reader: &mut impl Read,
+    ) -> Result<T, ApiError> {
+        let len = self.body_len(request)?;
+        read_json(reader.take(len), self.max_body_bytes).map_err(|e| match e {
+            InputFileError::TooLarge { limit } => too_large(limit),
+            e => ApiError::new(400, e.code(), format!("Failed to parse request body: {e}")),
+        })
+    }
+}
+
+fn too_large(limit: u64) -> ApiError {
+    ApiError::new(413, ErrorCode::InputLimits, format!("Request body exceeds {limit} bytes"))
+}
+
+/// Request line and headers of a request
+struct Request {
+    method: String,
+    /// Target without its query string
+    path: String,
+    /// Header names lowercased
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    fn accepts_event_stream(&self) -> bool {
+        self.header("accept").is_some_and(|accept| accept.contains("text/event-stream"))
+    }
+}
+
+/// Read the request line and headers, leaving `reader` at the start of the body
+fn read_head(reader: &mut impl BufRead) -> Result<Request, ApiError> {
+    let mut head = reader.take(MAX_HEAD_BYTES);
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let read = head
+            .read_line(&mut line)
+            .map_err(|e| ApiError::invalid_input(format!("Failed to read request: {e}")))?;
+        if read == 0 || !line.ends_with('\n') {
+            return Err(ApiError::invalid_input("Request head is truncated or too long"));
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line.to_string());
+    }
+
+    let mut request_line = lines.first().map(String::as_str).unwrap_or("").split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err(ApiError::invalid_input("Malformed request line"));
+    };
+    let headers = lines[1..]
+        .iter()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    Ok(Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or(target).to_string(),
+        headers,
+    })
+}
+
+/// Write a complete JSON response; the connection closes after it
+///
+/// A client that went away is only logged: there is no one left to tell.
+fn write_json(writer: &mut impl Write, status: u16, body: &impl Serialize) {
+    let body = serde_json::to_vec(body).expect("response types serialize");
+    let head = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        reason_phrase(status),
+        body.len()
+    );
+    let written = writer
+        .write_all(head.as_bytes())
+        .and_then(|()| writer.write_all(&body))
+        .and_then(|()| writer.flush());
+    if let Err(e) = written {
+        tracing::warn!("Failed to write response: {e}");
+    }
+}
+
+/// A `text/event-stream` response, ended by closing the connection
+struct EventStream<'a, W: Write> {
+    writer: &'a mut W,
+}
+
+impl<'a, W: Write> EventStream<'a, W> {
+    fn start(writer: &'a mut W) -> Self {
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\nConnection: close\r\n\r\n";
+        let mut stream = Self { writer };
+        stream.write(head.as_bytes());
+        stream
+    }
+
+    fn send(&mut self, event: &str, data: &impl Serialize) {
+        let data = serde_json::to_string(data).expect("event types serialize");
+        self.write(format!("event: {event}\ndata: {data}\n\n").as_bytes());
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        if let Err(e) = self.writer.write_all(bytes).and_then(|()| self.writer.flush()) {
+            tracing::warn!("Failed to write event: {e}");
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Compare without returning early, so response times do not reveal how much of a token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_head() {
+        let raw = "POST /prove/burn?trace=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 11\r\n\
+                   Accept: text/event-stream\r\n\r\n{\"body\": 1}";
+        let mut reader = BufReader::new(raw.as_bytes());
+        let request = read_head(&mut reader).unwrap();
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/prove/burn"));
+        assert_eq!(request.header("content-length"), Some("11"));
+        assert!(request.accepts_event_stream());
+
+        let mut body = String::new();
+        reader.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "{\"body\": 1}");
+    }
+
+    #[test]
+    fn test_read_head_refuses_truncated_and_oversized() {
+        let truncated = "GET /info HTTP/1.1\r\nHost: localhost\r\n".to_string();
+        for raw in [truncated, "x".repeat(MAX_HEAD_BYTES as usize + 1)] {
+            let error = read_head(&mut BufReader::new(raw.as_bytes())).unwrap_err();
+            assert_eq!((error.status, error.code), (400, ErrorCode::InputParse));
+        }
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
@@ -0,0 +1,372 @@
+// Mock Ethereum state and seeded circuit inputs for tests and benchmarks
+// Builds account proofs in the shape `eth_getProof` returns, so MPT verification and witness
+// building run on real trie nodes instead of zero-filled layers. `gen_pob_inputs` and
+// `gen_spend_inputs` draw whole witnesses from a seed, so a failing sweep case is reproduced from
+// its seed and profile alone.
+
+use alloy_primitives::U256;
+use alloy_rlp::{BufMut, Encodable};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::circuits::spend::SpendInputs;
+use crate::constants::circuit_params::{
+    MAX_ACTUAL_BALANCE, MAX_INTENDED_BALANCE, MAX_NUM_LAYERS, MIN_LEAF_ADDRESS_NIBBLES,
+    POW_MINIMUM_ZERO_BYTES,
+};
+use crate::constants::{M31_PRIME, MAINNET_CHAIN_ID};
+use crate::secret::Secret;
+use crate::utils::burn_address::compute_burn_address_hash;
+use crate::utils::header::{BlockHeader, STATE_ROOT_OFFSET};
+use crate::utils::keccak::keccak256;
+use crate::utils::pow::verify_pow;
+use crate::utils::rlp::{bytes_to_nibbles, hex_prefix_encode, Account};
+use crate::M31;
+
+/// Number of children of a branch node
+const BRANCH_WIDTH: usize = 16;
+
+/// Account proof of a burn account in a mock state trie
+#[derive(Debug, Clone)]
+pub struct MockAccountProof {
+    /// Trie nodes from the root branch down to the account leaf
+    pub layers: Vec<Vec<u8>>,
+    /// keccak256 of the root node
+    pub state_root: [u8; 32],
+    /// RLP block header committing to `state_root` at byte 91
+    pub block_header: Vec<u8>,
+    /// Address-hash nibbles left for the leaf key after the branch levels
+    pub num_leaf_address_nibbles: u8,
+}
+
+/// Build a `n_layers`-deep account proof: `n_layers - 1` full branch nodes and a leaf holding a
+/// burn account with `balance` under `address_hash`
+///
+/// Sibling slots of each branch hold deterministic dummy hashes, so every branch has the 532-byte
+/// size of a dense mainnet node.
+///
+/// # Panics
+/// If `n_layers` is zero, above `MAX_NUM_LAYERS`, or leaves the leaf fewer than
+/// `MIN_LEAF_ADDRESS_NIBBLES` key nibbles.
+pub fn mock_account_proof(address_hash: &[u8; 32], balance: U256, n_layers: usize) -> MockAccountProof {
+    assert!(
+        (1..=MAX_NUM_LAYERS).contains(&n_layers),
+        "n_layers must be between 1 and {}, got {}",
+        MAX_NUM_LAYERS,
+        n_layers
+    );
+    let nibbles = bytes_to_nibbles(address_hash);
+    let depth = n_layers - 1;
+    assert!(
+        nibbles.len() - depth >= MIN_LEAF_ADDRESS_NIBBLES,
+        "{} branch levels leave fewer than {} leaf nibbles",
+        depth,
+        MIN_LEAF_ADDRESS_NIBBLES
+    );
+
+    // Leaf first, then each branch wraps the hash of the node below it
+    let mut layers = vec![leaf_node(&nibbles[depth..], balance)];
+    for level in (0..depth).rev() {
+        let child = keccak256(layers.last().unwrap());
+        layers.push(branch_node(level, nibbles[level] as usize, child));
+    }
+    layers.reverse();
+
+    let state_root = keccak256(&layers[0]);
+    MockAccountProof {
+        layers,
+        state_root,
+        block_header: mock_block_header(&state_root),
+        num_leaf_address_nibbles: (nibbles.len() - depth) as u8,
+    }
+}
+
+/// RLP block header with the pre-London field list and `state_root` in its fourth field
+pub fn mock_block_header(state_root: &[u8; 32]) -> Vec<u8> {
+    mock_block_header_with(&keccak256(b"mock parent"), 19_000_000, state_root)
+}
+
+/// `mock_block_header` of block `number`, naming `parent_hash` as its parent
+pub fn mock_block_header_with(parent_hash: &[u8; 32], number: u64, state_root: &[u8; 32]) -> Vec<u8> {
+    let ommers_hash = keccak256(&[0xc0]);
+    let fields: Vec<Vec<u8>> = vec![
+        rlp_bytes(parent_hash),
+        rlp_bytes(&ommers_hash),
+        rlp_bytes(&[0u8; 20]), // beneficiary
+        rlp_bytes(state_root),
+        rlp_bytes(&keccak256(b"mock transactions")),
+        rlp_bytes(&keccak256(b"mock receipts")),
+        rlp_bytes(&[0u8; 256]), // logs bloom
+        rlp_u64(0),             // difficulty
+        rlp_u64(number),        // number
+        rlp_u64(30_000_000),    // gas limit
+        rlp_u64(12_345_678),    // gas used
+        rlp_u64(1_700_000_000), // timestamp
+        rlp_bytes(b"mock"),     // extra data
+        rlp_bytes(&[0u8; 32]),  // mix hash
+        rlp_bytes(&[0u8; 8]),   // nonce
+    ];
+    let header = rlp_list(&fields);
+    debug_assert_eq!(&header[STATE_ROOT_OFFSET..STATE_ROOT_OFFSET + 32], state_root);
+    header
+}
+
+/// `count` mock headers descending from `header`, oldest first
+///
+/// Each names the previous header's hash as its parent and has the next block number, so they
+/// verify as `header_chain::verify_descendants(header, ..)`.
+///
+/// # Panics
+/// If `header` does not decode or has no block number.
+pub fn mock_descendants(header: &[u8], count: usize) -> Vec<Vec<u8>> {
+    let number = BlockHeader::decode(header)
+        .and_then(|decoded| decoded.number())
+        .expect("parent header decodes with a block number");
+    let mut parent = header.to_vec();
+    (1..=count as u64)
+        .map(|offset| {
+            let child = mock_block_header_with(&keccak256(&parent), number + offset, &keccak256(b"mock state"));
+            parent = child.clone();
+            child
+        })
+        .collect()
+}
+
+/// Deepest mock proof whose leaf keeps `MIN_LEAF_ADDRESS_NIBBLES` key nibbles
+pub const MAX_MOCK_LAYERS: usize = {
+    let deepest = 64 - MIN_LEAF_ADDRESS_NIBBLES + 1;
+    if deepest < MAX_NUM_LAYERS { deepest } else { MAX_NUM_LAYERS }
+};
+
+/// Range of circuit inputs a generator draws from
+///
+/// Each profile pushes one dimension to its edge and leaves the others at ordinary values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputProfile {
+    /// Balances of at most a million wei
+    TinyBalances,
+    /// Balances within 2^32 of the circuit's limits
+    NearMaxBalances,
+    /// The deepest account proof the trie allows, `MAX_MOCK_LAYERS` layers
+    MaxLayers,
+    /// An odd number of leaf key nibbles, so the leaf key is hex-prefixed with an odd flag
+    OddNibbles,
+}
+
+impl InputProfile {
+    pub const ALL: [InputProfile; 4] =
+        [Self::TinyBalances, Self::NearMaxBalances, Self::MaxLayers, Self::OddNibbles];
+}
+
+/// Valid Proof of Burn inputs drawn from `seed`
+///
+/// The burn key is mined for `POW_MINIMUM_ZERO_BYTES` of PoW, and the account proof is a mock trie
+/// holding the burn address the key derives, with `actual_balance`, under a header committing to
+/// its root. `ProofOfBurnCircuit::compute_outputs` accepts the result.
+pub fn gen_pob_inputs(seed: u64, profile: InputProfile) -> ProofOfBurnInputs {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (actual_balance, intended_balance, reveal_amount) = match profile {
+        InputProfile::TinyBalances => {
+            let actual = rng.gen_range(1..=1_000_000u128);
+            let intended = rng.gen_range(0..=actual);
+            (actual, intended, rng.gen_range(0..=intended))
+        }
+        InputProfile::NearMaxBalances => {
+            let intended = MAX_INTENDED_BALANCE - rng.gen_range(0..=1u128 << 32);
+            let actual = MAX_ACTUAL_BALANCE - rng.gen_range(0..=1u128 << 32);
+            (actual, intended, intended - rng.gen_range(0..=1u128 << 32))
+        }
+        InputProfile::MaxLayers | InputProfile::OddNibbles => ordinary_balances(&mut rng),
+    };
+    let n_layers = match profile {
+        InputProfile::MaxLayers => MAX_MOCK_LAYERS,
+        // An odd number of branch levels leaves an odd number of the 64 key nibbles
+        InputProfile::OddNibbles => 2 * rng.gen_range(1..=MAX_MOCK_LAYERS / 2),
+        InputProfile::TinyBalances | InputProfile::NearMaxBalances => rng.gen_range(1..=8),
+    };
+
+    let (actual_balance, reveal_amount) = (U256::from(actual_balance), U256::from(reveal_amount));
+    let burn_extra_commitment = gen_m31(&mut rng);
+    let burn_key = mine_burn_key(&mut rng, reveal_amount, burn_extra_commitment);
+    let address_hash = compute_burn_address_hash(burn_key, reveal_amount, burn_extra_commitment);
+    let proof = mock_account_proof(&address_hash, actual_balance, n_layers);
+    ProofOfBurnInputs {
+        burn_key: Secret::new(burn_key),
+        actual_balance,
+        intended_balance: U256::from(intended_balance),
+        reveal_amount,
+        burn_extra_commitment,
+        layers: proof.layers,
+        block_header: proof.block_header,
+        header_chain: Vec::new(),
+        chain_id: MAINNET_CHAIN_ID,
+        valid_until_block: None,
+        num_leaf_address_nibbles: proof.num_leaf_address_nibbles,
+        byte_security_relax: 0,
+        proof_extra_commitment: gen_m31(&mut rng),
+        expected_burn_address: None,
+    }
+}
+
+/// Valid Spend inputs drawn from `seed`
+///
+/// Spends have no trie, so `MaxLayers` and `OddNibbles` draw ordinary balances.
+/// `NearMaxBalances` draws them within 2^32 of `u64::MAX`, the widest the Spend trace holds.
+pub fn gen_spend_inputs(seed: u64, profile: InputProfile) -> SpendInputs {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (balance, withdrawn_balance) = match profile {
+        InputProfile::TinyBalances => {
+            let balance = rng.gen_range(0..=1_000_000u64);
+            (U256::from(balance), U256::from(rng.gen_range(0..=balance)))
+        }
+        InputProfile::NearMaxBalances => {
+            let balance = u64::MAX - rng.gen_range(0..=1u64 << 32);
+            (U256::from(balance), U256::from(balance - rng.gen_range(0..=1u64 << 32)))
+        }
+        InputProfile::MaxLayers | InputProfile::OddNibbles => {
+            let (_, balance, withdrawn) = ordinary_balances(&mut rng);
+            (U256::from(balance), U256::from(withdrawn))
+        }
+    };
+    SpendInputs {
+        burn_key: Secret::new(gen_m31(&mut rng)),
+        balance,
+        withdrawn_balance,
+        extra_commitment: gen_m31(&mut rng),
+        recipient: None,
+        chain_id: MAINNET_CHAIN_ID,
+        expected_coin: None,
+    }
+}
+
+/// Actual, intended and revealed balances between a milliether and `MAX_INTENDED_BALANCE`, in
+/// decreasing order
+fn ordinary_balances(rng: &mut StdRng) -> (u128, u128, u128) {
+    let actual = rng.gen_range(1_000_000_000_000_000..=MAX_INTENDED_BALANCE);
+    let intended = rng.gen_range(actual / 2..=actual);
+    (actual, intended, rng.gen_range(0..=intended))
+}
+
+/// Uniform M31 element
+fn gen_m31(rng: &mut StdRng) -> M31 {
+    M31::from(rng.gen_range(0..M31_PRIME))
+}
+
+/// Random burn key meeting the `POW_MINIMUM_ZERO_BYTES` PoW for `reveal_amount` and
+/// `burn_extra_commitment`
+///
+/// Candidates are drawn rather than counted up from zero, so keys differ across seeds and the
+/// search is not bounded the way `find_valid_burn_key`'s is.
+fn mine_burn_key(rng: &mut StdRng, reveal_amount: U256, burn_extra_commitment: M31) -> M31 {
+    loop {
+        let candidate = gen_m31(rng);
+        if verify_pow(candidate, reveal_amount, burn_extra_commitment, POW_MINIMUM_ZERO_BYTES) {
+            return candidate;
+        }
+    }
+}
+
+/// Leaf node `[hex_prefix(key, leaf), account_rlp]`
+fn leaf_node(key_nibbles: &[u8], balance: U256) -> Vec<u8> {
+    let account = Account::new_burn_account(balance).encode_to_vec();
+    rlp_list(&[rlp_bytes(&hex_prefix_encode(key_nibbles, true)), rlp_bytes(&account)])
+}
+
+/// Branch node with `child` at `slot`, dummy hashes in the other slots and an empty value
+fn branch_node(level: usize, slot: usize, child: [u8; 32]) -> Vec<u8> {
+    let mut items: Vec<Vec<u8>> = (0..BRANCH_WIDTH)
+        .map(|i| {
+            if i == slot {
+                rlp_bytes(&child)
+            } else {
+                rlp_bytes(&keccak256(&[level as u8, i as u8]))
+            }
+        })
+        .collect();
+    items.push(rlp_bytes(&[]));
+    rlp_list(&items)
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    bytes.encode(&mut out);
+    out
+}
+
+fn rlp_u64(value: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    value.encode(&mut out);
+    out
+}
+
+fn rlp_list(encoded_items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_length = encoded_items.iter().map(Vec::len).sum();
+    let mut out = Vec::new();
+    alloy_rlp::Header { list: true, payload_length }.encode(&mut out);
+    for item in encoded_items {
+        out.put_slice(item);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mpt::verify_mpt_proof;
+
+    #[test]
+    fn test_mock_proof_verifies() {
+        let address_hash = keccak256(b"mock burn address");
+        let balance = U256::from(1_000_000_000_000_000_000u64);
+        for n_layers in [1, 3, 8] {
+            let proof = mock_account_proof(&address_hash, balance, n_layers);
+            assert_eq!(proof.layers.len(), n_layers);
+            assert_eq!(proof.num_leaf_address_nibbles as usize, 64 - (n_layers - 1));
+            assert_eq!(&proof.block_header[STATE_ROOT_OFFSET..STATE_ROOT_OFFSET + 32], &proof.state_root);
+            let proven = verify_mpt_proof(&proof.layers, &proof.state_root, &address_hash).unwrap();
+            assert_eq!(proven.balance, balance);
+            assert_eq!(proven.key_nibbles_matched, proof.num_leaf_address_nibbles as usize);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_are_valid_and_reproducible() {
+        use crate::circuits::proof_of_burn::ProofOfBurnCircuit;
+        use crate::circuits::spend::SpendCircuit;
+
+        for profile in InputProfile::ALL {
+            for seed in 0..3 {
+                let inputs = gen_pob_inputs(seed, profile);
+                let circuit = ProofOfBurnCircuit::new(inputs.clone()).unwrap();
+                circuit.compute_outputs().unwrap_or_else(|e| panic!("{profile:?} seed {seed}: {e}"));
+                let nibbles = inputs.num_leaf_address_nibbles as usize;
+                match profile {
+                    InputProfile::MaxLayers => assert_eq!(inputs.layers.len(), MAX_MOCK_LAYERS),
+                    InputProfile::OddNibbles => assert_eq!(nibbles % 2, 1),
+                    InputProfile::TinyBalances => assert!(inputs.actual_balance <= U256::from(1_000_000)),
+                    InputProfile::NearMaxBalances => {
+                        assert!(inputs.actual_balance >= U256::from(MAX_ACTUAL_BALANCE - (1 << 32)))
+                    }
+                }
+                assert_eq!(inputs.layers, gen_pob_inputs(seed, profile).layers);
+
+                let spend = gen_spend_inputs(seed, profile);
+                assert!(SpendCircuit::new(spend.clone()).is_ok(), "{profile:?} seed {seed}");
+                assert_eq!(spend.balance, gen_spend_inputs(seed, profile).balance);
+            }
+        }
+        assert_ne!(
+            gen_pob_inputs(0, InputProfile::TinyBalances).burn_extra_commitment,
+            gen_pob_inputs(1, InputProfile::TinyBalances).burn_extra_commitment
+        );
+    }
+
+    #[test]
+    fn test_branch_nodes_are_dense() {
+        let proof = mock_account_proof(&[0xAB; 32], U256::from(1u64), 8);
+        for branch in &proof.layers[..7] {
+            assert_eq!(branch.len(), 532);
+        }
+    }
+}
@@ -1,15 +1,66 @@
 // Burn address computation
 // Reference: proof-of-burn/circuits/utils/burn_address.circom
 //
-// The burn address is the first 20 bytes of:
-//   Poseidon4(POSEIDON_BURN_ADDRESS_PREFIX, burnKey, revealAmount, burnExtraCommitment)
+// The burn address is the first 20 bytes of keccak256 over the limbs of:
+//   Poseidon2(POSEIDON_BURN_ADDRESS_PREFIX, burnKey, revealAmount[0..9], burnExtraCommitment)[..8]
+// with the reveal amount split into 30-bit limbs by `u256_to_m31_array`, so every bit of it is
+// bound into the address. The preimage fits one zero-padded Poseidon2 state, so the PoB trace
+// proves the digest through the Poseidon2 table; the two keccak steps to the address and its
+// trie key are still checked outside the proof (`BurnStatement::verify`).
+//
+// Addresses are shown EIP-55 checksummed (`format_checksummed`), and `parse_address` reads them
+// back, rejecting a mixed-case string whose checksum does not match, so a mistyped address a
+// user pastes in fails to parse instead of naming another account.
 
-use crate::constants::poseidon_burn_address_prefix;
+use crate::constants::{poseidon_burn_address_prefix, OUTPUT_LIMBS};
 use crate::utils::keccak::keccak256;
-use crate::utils::poseidon::{poseidon4, u256_to_m31};
+use crate::utils::poseidon::u256_to_m31_array;
+use crate::utils::poseidon2_stwo::{permute_padded, poseidon2_permutation, Permutation};
 use alloy_primitives::{Address, U256};
 use crate::field::M31;
 
+/// Version of the burn address derivation
+///
+/// v1: Poseidon4 over the reveal amount reduced to one M31, so amounts agreeing in their low 32
+///     bits shared an address
+/// v2: the reveal amount is absorbed as `u256_to_m31_array` limbs by the `poseidon_wide` sponge
+/// v3: the same preimage is one Poseidon2 permutation, which the PoB trace proves
+///
+/// Addresses derived under different versions differ; funds burned to a v1 address can only be
+/// proven by a v1 build.
+pub const BURN_ADDRESS_VERSION: u32 = 3;
+
+/// Poseidon2 digest the burn address is the keccak of, as `OUTPUT_LIMBS` limbs
+///
+/// This is the value the PoB trace proves and exposes as a public output.
+pub fn burn_address_digest(
+    burn_key: M31,
+    reveal_amount: U256,
+    burn_extra_commitment: M31,
+) -> [M31; OUTPUT_LIMBS] {
+    burn_address_digest_with(poseidon2_permutation, burn_key, reveal_amount, burn_extra_commitment)
+}
+
+/// `burn_address_digest` under `permutation`; the circuits compute their outputs through this
+pub(crate) fn burn_address_digest_with(
+    permutation: Permutation,
+    burn_key: M31,
+    reveal_amount: U256,
+    burn_extra_commitment: M31,
+) -> [M31; OUTPUT_LIMBS] {
+    let mut preimage = vec![poseidon_burn_address_prefix(), burn_key];
+    preimage.extend(u256_to_m31_array(reveal_amount));
+    preimage.push(burn_extra_commitment);
+    let state = permute_padded(permutation, &preimage);
+    std::array::from_fn(|i| state[i])
+}
+
+/// Burn address whose Poseidon2 digest is `digest`: keccak256 of its limbs, first 20 bytes
+pub fn burn_address_from_digest(digest: &[M31; OUTPUT_LIMBS]) -> Address {
+    let limb_bytes: Vec<u8> = digest.iter().flat_map(|limb| limb.value().to_be_bytes()).collect();
+    Address::from_slice(&keccak256(&limb_bytes)[..20])
+}
+
 /// Compute burn address from burnKey and commitments
 /// 
 /// Returns the 20-byte Ethereum address where ETH should be burned
@@ -18,25 +69,7 @@ pub fn compute_burn_address(
     reveal_amount: U256,
     burn_extra_commitment: M31,
 ) -> Address {
-    // Compute Poseidon4 hash
-    let reveal_amount_m31 = u256_to_m31(reveal_amount);
-    
-    let poseidon_output = poseidon4([
-        poseidon_burn_address_prefix(),
-        burn_key,
-        reveal_amount_m31,
-        burn_extra_commitment,
-    ]);
-    
-    // Convert M31 output to bytes and hash with Keccak to get full 32 bytes
-    let value_bytes = poseidon_output.value().to_be_bytes();
-    let full_hash = keccak256(&value_bytes);
-    
-    // Take first 20 bytes as Ethereum address
-    let mut address_bytes = [0u8; 20];
-    address_bytes.copy_from_slice(&full_hash[..20]);
-    
-    Address::from(address_bytes)
+    burn_address_from_digest(&burn_address_digest(burn_key, reveal_amount, burn_extra_commitment))
 }
 
 /// Compute the Keccak256 hash of the burn address
@@ -50,6 +83,48 @@ pub fn compute_burn_address_hash(
     keccak256(address.as_slice())
 }
 
+/// `address` as 0x-prefixed EIP-55 mixed-case hex
+pub fn format_checksummed(address: Address) -> String {
+    address.to_checksum(None)
+}
+
+/// Parse a 20-byte address from hex, with or without the 0x prefix
+///
+/// All-lowercase and all-uppercase hex carry no checksum and are accepted as is; mixed case must
+/// be the EIP-55 checksum of the address.
+pub fn parse_address(value: &str) -> Result<Address, AddressParseError> {
+    let value = value.trim();
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    if digits.len() != 40 {
+        return Err(AddressParseError::Length { len: digits.len() });
+    }
+    let mut bytes = [0u8; 20];
+    hex::decode_to_slice(digits, &mut bytes)
+        .map_err(|_| AddressParseError::InvalidHex { value: value.to_owned() })?;
+    let address = Address::from(bytes);
+
+    let mixed_case = digits.bytes().any(|b| b.is_ascii_lowercase()) && digits.bytes().any(|b| b.is_ascii_uppercase());
+    if mixed_case {
+        let expected = format_checksummed(address);
+        if expected[2..] != *digits {
+            return Err(AddressParseError::Checksum { found: value.to_owned(), expected });
+        }
+    }
+    Ok(address)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AddressParseError {
+    #[error("Address must be 40 hex digits, got {len}")]
+    Length { len: usize },
+
+    #[error("Address is not hex: {value}")]
+    InvalidHex { value: String },
+
+    #[error("Bad EIP-55 checksum in {found}, expected {expected}")]
+    Checksum { found: String, expected: String },
+}
+
 /// Convert address hash to nibbles (4-bit values)
 /// Ethereum MPT uses nibbles as path elements
 pub fn address_hash_to_nibbles(address_hash: &[u8; 32]) -> Vec<u8> {
@@ -121,5 +196,94 @@ mod tests {
         // Different keys should produce different addresses
         assert_ne!(addr1, addr2);
     }
-}
 
+    #[test]
+    fn test_address_is_keccak_of_the_poseidon2_digest() {
+        use crate::utils::poseidon2_stwo::{basefield_to_custom_m31, custom_m31_to_basefield, N_STATE};
+
+        let burn_key = M31::from(42);
+        let reveal_amount = U256::from(1000000000000000000u64);
+        let burn_extra_commitment = M31::from(100);
+
+        // Prefix, key, the nine reveal limbs and the extra commitment, zero-padded
+        let mut state = [custom_m31_to_basefield(M31::from(0)); N_STATE];
+        state[0] = custom_m31_to_basefield(poseidon_burn_address_prefix());
+        state[1] = custom_m31_to_basefield(burn_key);
+        for (cell, limb) in state[2..11].iter_mut().zip(u256_to_m31_array(reveal_amount)) {
+            *cell = custom_m31_to_basefield(limb);
+        }
+        state[11] = custom_m31_to_basefield(burn_extra_commitment);
+        let output = poseidon2_permutation(state);
+
+        let digest = burn_address_digest(burn_key, reveal_amount, burn_extra_commitment);
+        assert_eq!(digest, std::array::from_fn(|i| basefield_to_custom_m31(output[i])));
+        assert_eq!(
+            compute_burn_address(burn_key, reveal_amount, burn_extra_commitment),
+            burn_address_from_digest(&digest)
+        );
+    }
+
+    #[test]
+    fn test_full_reveal_amount_is_bound() {
+        let burn_key = M31::from(42);
+        let burn_extra_commitment = M31::from(100);
+        let low = U256::from(1000000000000000000u64);
+        // Same low 128 bits, different high 128 bits
+        let high = low | (U256::from(1u64) << 200);
+
+        assert_ne!(
+            compute_burn_address(burn_key, low, burn_extra_commitment),
+            compute_burn_address(burn_key, high, burn_extra_commitment)
+        );
+        assert_ne!(
+            compute_burn_address_hash(burn_key, low, burn_extra_commitment),
+            compute_burn_address_hash(burn_key, high, burn_extra_commitment)
+        );
+        // The PoW preimage holds all 32 bytes of the amount as well
+        assert_ne!(
+            crate::utils::pow::compute_pow_hash(burn_key, low, burn_extra_commitment),
+            crate::utils::pow::compute_pow_hash(burn_key, high, burn_extra_commitment)
+        );
+    }
+
+    // The examples of EIP-55
+    const EIP55_VECTORS: [&str; 8] = [
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_eip55_vectors() {
+        for vector in EIP55_VECTORS {
+            let address = parse_address(vector).unwrap();
+            assert_eq!(format_checksummed(address), vector);
+            // Without the checksum, in either case or prefix
+            assert_eq!(parse_address(&vector.to_lowercase()), Ok(address));
+            assert_eq!(parse_address(&vector[2..].to_uppercase()), Ok(address));
+        }
+
+        let burn_address = compute_burn_address(M31::from(42), U256::from(1000), M31::from(100));
+        assert_eq!(parse_address(&format_checksummed(burn_address)), Ok(burn_address));
+    }
+
+    #[test]
+    fn test_wrong_checksum_is_rejected() {
+        // The last vector with one letter's case flipped
+        let mistyped = "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9adb";
+        assert_eq!(
+            parse_address(mistyped),
+            Err(AddressParseError::Checksum { found: mistyped.to_owned(), expected: EIP55_VECTORS[7].to_owned() })
+        );
+        assert_eq!(parse_address("0x1234"), Err(AddressParseError::Length { len: 4 }));
+        assert!(matches!(
+            parse_address("0xzz20A0cf47c7B9Be7A2E6BA89F429762e7b9aDb0"),
+            Err(AddressParseError::InvalidHex { .. })
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+// Extra commitments of burn and spend proofs
+// `burn_extra_commitment` enters the burn address and the proof's commitment, so it fixes who
+// may collect the revealed amount and at what fee before any ETH is burned.
+// `proof_extra_commitment` binds the proof to whoever submits it. A spend's `extra_commitment`
+// enters its commitment; with a recipient it is `spend_extra`, so a copied proof still pays the
+// recipient it was made for. All are free-form M31 values in the circuits; these helpers give
+// them one encoding wallets and contracts can agree on.
+//
+// Construction, with `reduce(h) = uint32(bytes4(h)) % (2^31 - 1)`:
+//
+//   burn_extra  = reduce(keccak256(abi.encodePacked("EIP-7503/burn-extra", receiver, fee, salt)))
+//   proof_extra = reduce(keccak256(abi.encodePacked("EIP-7503/proof-extra", prover)))
+//   spend_extra = reduce(keccak256(abi.encodePacked("EIP-7503/spend-extra", recipient, salt)))
+//
+// where `receiver`, `prover` and `recipient` are `address`, `fee` is `uint256` and `salt` is
+// `uint32`. The tags keep the values apart even if their preimages ever coincide.
+
+use alloy_primitives::{Address, U256};
+
+use crate::constants::M31_PRIME;
+use crate::field::M31;
+use crate::utils::keccak::keccak256;
+
+/// Domain tag of `burn_extra`
+pub const BURN_EXTRA_DOMAIN: &[u8] = b"EIP-7503/burn-extra";
+
+/// Domain tag of `proof_extra`
+pub const PROOF_EXTRA_DOMAIN: &[u8] = b"EIP-7503/proof-extra";
+
+/// Domain tag of `spend_extra`
+pub const SPEND_EXTRA_DOMAIN: &[u8] = b"EIP-7503/spend-extra";
+
+/// `burn_extra_commitment` binding the revealed amount to `receiver`, minus `fee_wei` for the relayer
+///
+/// `salt` lets one receiver burn to several addresses with the same reveal amount.
+pub fn burn_extra(receiver: Address, fee_wei: U256, salt: M31) -> M31 {
+    let mut preimage = Vec::with_capacity(BURN_EXTRA_DOMAIN.len() + 20 + 32 + 4);
+    preimage.extend_from_slice(BURN_EXTRA_DOMAIN);
+    preimage.extend_from_slice(receiver.as_slice());
+    preimage.extend_from_slice(&fee_wei.to_be_bytes::<32>());
+    preimage.extend_from_slice(&salt.value().to_be_bytes());
+    reduce(&keccak256(&preimage))
+}
+
+/// `proof_extra_commitment` binding the proof to `prover`
+pub fn proof_extra(prover: Address) -> M31 {
+    let mut preimage = Vec::with_capacity(PROOF_EXTRA_DOMAIN.len() + 20);
+    preimage.extend_from_slice(PROOF_EXTRA_DOMAIN);
+    preimage.extend_from_slice(prover.as_slice());
+    reduce(&keccak256(&preimage))
+}
+
+/// A spend's `extra_commitment` binding the withdrawal to `recipient`
+///
+/// `salt` is the extra commitment the spend would carry without a recipient.
+pub fn spend_extra(recipient: Address, salt: M31) -> M31 {
+    let mut preimage = Vec::with_capacity(SPEND_EXTRA_DOMAIN.len() + 20 + 4);
+    preimage.extend_from_slice(SPEND_EXTRA_DOMAIN);
+    preimage.extend_from_slice(recipient.as_slice());
+    preimage.extend_from_slice(&salt.value().to_be_bytes());
+    reduce(&keccak256(&preimage))
+}
+
+/// The first 4 bytes of `hash` as a big-endian u32, reduced mod P like `poseidon_prefix`
+fn reduce(hash: &[u8; 32]) -> M31 {
+    let value = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+    M31::new(value % M31_PRIME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_extra_compatibility_vector() {
+        // keccak256 of the preimage is 4eb32663a541…, and 0x4eb32663 < P
+        let value = burn_extra(Address::repeat_byte(0x11), U256::from(10u64.pow(15)), M31::from(42));
+        assert_eq!(value, M31::from(1320363619));
+    }
+
+    #[test]
+    fn test_proof_extra_compatibility_vector() {
+        // keccak256 of the preimage is 2bdde58cbfc3…, and 0x2bdde58c < P
+        assert_eq!(proof_extra(Address::repeat_byte(0x22)), M31::from(735962508));
+    }
+
+    #[test]
+    fn test_spend_extra_compatibility_vector() {
+        // keccak256 of the preimage is 0f4179209b62…, and 0x0f417920 < P
+        assert_eq!(spend_extra(Address::repeat_byte(0x33), M31::from(100)), M31::from(255949088));
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let receiver = Address::repeat_byte(0x11);
+        assert_eq!(
+            burn_extra(receiver, U256::from(1000), M31::from(7)),
+            burn_extra(receiver, U256::from(1000), M31::from(7))
+        );
+        assert_eq!(proof_extra(receiver), proof_extra(receiver));
+    }
+
+    #[test]
+    fn test_distinct_across_inputs() {
+        let fee = U256::from(1000);
+        let salt = M31::from(7);
+        let base = burn_extra(Address::repeat_byte(0x11), fee, salt);
+
+        assert_ne!(base, burn_extra(Address::repeat_byte(0x12), fee, salt));
+        assert_ne!(base, burn_extra(Address::repeat_byte(0x11), fee + U256::from(1), salt));
+        assert_ne!(base, burn_extra(Address::repeat_byte(0x11), fee, M31::from(8)));
+
+        // The domain tags separate the two values for the same address
+        assert_ne!(proof_extra(Address::repeat_byte(0x11)), proof_extra(Address::repeat_byte(0x12)));
+        assert_ne!(proof_extra(Address::ZERO), burn_extra(Address::ZERO, U256::ZERO, M31::from(0)));
+        assert_ne!(spend_extra(Address::ZERO, M31::from(0)), proof_extra(Address::ZERO));
+        assert_ne!(spend_extra(Address::ZERO, salt), spend_extra(Address::repeat_byte(0x11), salt));
+    }
+}
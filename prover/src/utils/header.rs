@@ -0,0 +1,154 @@
+// Block header parsing
+// The circuit reads the state root at a fixed byte offset of the RLP header; these helpers decode
+// the header field list and check the state root really sits there, without indexing past the end
+// of a short or malformed header.
+
+use alloy_rlp::Encodable;
+
+use crate::utils::rlp::{rlp_list_raw_items, rlp_string_payload};
+
+/// Byte offset of the state root inside the RLP header, as read by the circuit
+///
+/// 3-byte list header, parentHash and ommersHash (33 bytes each), beneficiary (21 bytes), then
+/// the 1-byte string header of stateRoot.
+pub const STATE_ROOT_OFFSET: usize = 91;
+
+/// Index of `stateRoot` in the header field list
+pub const STATE_ROOT_FIELD: usize = 3;
+
+/// Index of `number` in the header field list
+pub const NUMBER_FIELD: usize = 8;
+
+/// The 32 bytes at `STATE_ROOT_OFFSET`, or `None` if the header is too short to hold them
+///
+/// This is what the circuit reads; `BlockHeader::state_root` also checks they are the
+/// `stateRoot` field.
+pub fn state_root_at_offset(header_rlp: &[u8]) -> Option<[u8; 32]> {
+    header_rlp.get(STATE_ROOT_OFFSET..STATE_ROOT_OFFSET + 32)?.try_into().ok()
+}
+
+/// Fields of an RLP-encoded block header, borrowing from the encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeader<'a> {
+    /// Field payloads in header order
+    pub fields: Vec<&'a [u8]>,
+    encoded: &'a [u8],
+}
+
+impl<'a> BlockHeader<'a> {
+    /// Decode a header spanning all of `header_rlp`; every field must be a string
+    pub fn decode(header_rlp: &'a [u8]) -> Result<Self, HeaderError> {
+        let fields = rlp_list_raw_items(header_rlp)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, raw)| {
+                rlp_string_payload(raw).map_err(|_| HeaderError::NotAString { index })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { fields, encoded: header_rlp })
+    }
+
+    /// The `stateRoot` field, checked to be 32 bytes at `STATE_ROOT_OFFSET`
+    pub fn state_root(&self) -> Result<[u8; 32], HeaderError> {
+        let state_root = self
+            .fields
+            .get(STATE_ROOT_FIELD)
+            .ok_or(HeaderError::TooFewFields { found: self.fields.len() })?;
+        let root: [u8; 32] = (*state_root)
+            .try_into()
+            .map_err(|_| HeaderError::StateRootLength { len: state_root.len() })?;
+
+        let offset = state_root.as_ptr() as usize - self.encoded.as_ptr() as usize;
+        if offset != STATE_ROOT_OFFSET {
+            return Err(HeaderError::StateRootOffset { offset });
+        }
+        Ok(root)
+    }
+
+    /// The block `number` field
+    pub fn number(&self) -> Result<u64, HeaderError> {
+        let number = self
+            .fields
+            .get(NUMBER_FIELD)
+            .ok_or(HeaderError::TooFewFields { found: self.fields.len() })?;
+        if number.len() > 8 {
+            return Err(HeaderError::NumberLength { len: number.len() });
+        }
+        Ok(number.iter().fold(0, |value, &byte| value << 8 | byte as u64))
+    }
+
+    /// RLP encoding of the fields
+    pub fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for field in &self.fields {
+            field.encode(&mut payload);
+        }
+        let mut out = Vec::with_capacity(payload.len() + 3);
+        alloy_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut out);
+        out.extend_from_slice(&payload);
+        out
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HeaderError {
+    #[error("Malformed header RLP: {0}")]
+    Rlp(#[from] alloy_rlp::Error),
+
+    #[error("Header field {index} is a list")]
+    NotAString { index: usize },
+
+    #[error("Header has only {found} fields")]
+    TooFewFields { found: usize },
+
+    #[error("State root is {len} bytes")]
+    StateRootLength { len: usize },
+
+    #[error("State root at offset {offset}, circuit expects {}", STATE_ROOT_OFFSET)]
+    StateRootOffset { offset: usize },
+
+    #[error("Block number is {len} bytes")]
+    NumberLength { len: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER_FIXTURE: &str = include_str!("../../tests/fixtures/witness/header.hex");
+
+    fn header() -> Vec<u8> {
+        hex::decode(HEADER_FIXTURE.trim().trim_start_matches("0x")).unwrap()
+    }
+
+    #[test]
+    fn test_fixture_roundtrip() {
+        let header = header();
+        let decoded = BlockHeader::decode(&header).unwrap();
+        assert_eq!(decoded.encode(), header);
+        assert_eq!(decoded.state_root().unwrap(), state_root_at_offset(&header).unwrap());
+        assert_eq!(decoded.number().unwrap(), 21_000_000);
+    }
+
+    #[test]
+    fn test_short_headers_do_not_panic() {
+        let header = header();
+        for len in 0..STATE_ROOT_OFFSET + 32 {
+            assert_eq!(state_root_at_offset(&header[..len]), None);
+            assert!(BlockHeader::decode(&header[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_state_root_must_be_at_offset() {
+        // A one-byte beneficiary moves the state root 20 bytes earlier
+        let header = header();
+        let mut fields = BlockHeader::decode(&header).unwrap().fields;
+        fields[2] = &[0x01];
+        let shifted = BlockHeader { fields, encoded: &[] }.encode();
+        assert!(matches!(
+            BlockHeader::decode(&shifted).unwrap().state_root(),
+            Err(HeaderError::StateRootOffset { .. })
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+// Linked block header chains
+// A witness captured at block N can be submitted only while the contract still accepts block N's
+// hash. Like WORM, a burn may instead carry the headers from N to a recent block: each names its
+// parent by hash, so the chain ties the state root of block N, where the MPT proof is checked,
+// to the hash of its last header.
+
+use alloy_primitives::{keccak256, B256};
+
+use crate::utils::header::{BlockHeader, HeaderError};
+
+/// Index of `parentHash` in the header field list
+pub const PARENT_HASH_FIELD: usize = 0;
+
+/// First and last block of a verified chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainInfo {
+    pub first_hash: B256,
+    pub first_number: u64,
+    pub last_hash: B256,
+    pub last_number: u64,
+    /// Number of headers in the chain
+    pub len: usize,
+}
+
+/// Check each header of `headers`, oldest first, is the child of the one before it
+///
+/// A child names the keccak256 of its parent's RLP header as `parentHash` and has the next block
+/// number. A single header is a chain of one block.
+pub fn verify_chain<H: AsRef<[u8]>>(headers: &[H]) -> Result<ChainInfo, ChainError> {
+    let first = headers.first().ok_or(ChainError::Empty)?.as_ref();
+    let first_number = block_number(first, 0)?;
+    let (mut parent_hash, mut parent_number) = (keccak256(first), first_number);
+
+    for (index, header) in headers.iter().enumerate().skip(1) {
+        let header = header.as_ref();
+        let decoded = BlockHeader::decode(header).map_err(|source| ChainError::Header { index, source })?;
+        let named_parent = decoded.fields.get(PARENT_HASH_FIELD).ok_or_else(|| ChainError::Header {
+            index,
+            source: HeaderError::TooFewFields { found: decoded.fields.len() },
+        })?;
+        let named_parent = B256::try_from(*named_parent)
+            .map_err(|_| ChainError::ParentHashLength { index, len: named_parent.len() })?;
+        if named_parent != parent_hash {
+            return Err(ChainError::BrokenLink { index, expected: parent_hash, found: named_parent });
+        }
+        let number = decoded.number().map_err(|source| ChainError::Header { index, source })?;
+        if number != parent_number + 1 {
+            return Err(ChainError::NotConsecutive { index, expected: parent_number + 1, found: number });
+        }
+        (parent_hash, parent_number) = (keccak256(header), number);
+    }
+
+    Ok(ChainInfo {
+        first_hash: keccak256(first),
+        first_number,
+        last_hash: parent_hash,
+        last_number: parent_number,
+        len: headers.len(),
+    })
+}
+
+/// `verify_chain` of `header` followed by `descendants`
+pub fn verify_descendants(header: &[u8], descendants: &[Vec<u8>]) -> Result<ChainInfo, ChainError> {
+    let headers: Vec<&[u8]> = std::iter::once(header).chain(descendants.iter().map(Vec::as_slice)).collect();
+    verify_chain(&headers)
+}
+
+fn block_number(header: &[u8], index: usize) -> Result<u64, ChainError> {
+    BlockHeader::decode(header)
+        .and_then(|decoded| decoded.number())
+        .map_err(|source| ChainError::Header { index, source })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChainError {
+    #[error("Header chain is empty")]
+    Empty,
+
+    #[error("Header {index}: {source}")]
+    Header { index: usize, source: HeaderError },
+
+    #[error("Header {index}: parent hash is {len} bytes")]
+    ParentHashLength { index: usize, len: usize },
+
+    #[error("Header {index} names parent {found}, but the header before it hashes to {expected}")]
+    BrokenLink { index: usize, expected: B256, found: B256 },
+
+    #[error("Header {index} is block {found}, expected block {expected}")]
+    NotConsecutive { index: usize, expected: u64, found: u64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mock_block_header_with, mock_descendants};
+    use crate::utils::header::STATE_ROOT_OFFSET;
+
+    const HEADER_FIXTURE: &str = include_str!("../../tests/fixtures/witness/header.hex");
+
+    fn header() -> Vec<u8> {
+        hex::decode(HEADER_FIXTURE.trim().trim_start_matches("0x")).unwrap()
+    }
+
+    /// The mainnet fixture header and two mock descendants
+    fn chain() -> Vec<Vec<u8>> {
+        let header = header();
+        let mut chain = vec![header.clone()];
+        chain.extend(mock_descendants(&header, 2));
+        chain
+    }
+
+    #[test]
+    fn test_linked_chain_verifies() {
+        let chain = chain();
+        let info = verify_chain(&chain).unwrap();
+        assert_eq!(info.first_hash, keccak256(&chain[0]));
+        assert_eq!(info.first_number, 21_000_000);
+        assert_eq!(info.last_hash, keccak256(&chain[2]));
+        assert_eq!(info.last_number, 21_000_002);
+        assert_eq!(info.len, 3);
+
+        // One header is its own chain
+        let info = verify_chain(&chain[..1]).unwrap();
+        assert_eq!((info.last_hash, info.last_number), (info.first_hash, info.first_number));
+    }
+
+    #[test]
+    fn test_broken_link_rejected() {
+        let mut chain = chain();
+        // Editing the middle header changes its hash, which its child still names
+        let last = chain.len() - 1;
+        chain[1][STATE_ROOT_OFFSET] ^= 1;
+        assert!(matches!(verify_chain(&chain), Err(ChainError::BrokenLink { index, .. }) if index == last));
+
+        let mut chain = self::chain();
+        chain.swap(1, 2);
+        assert!(matches!(verify_chain(&chain), Err(ChainError::BrokenLink { index: 1, .. })));
+
+        // A header naming block N as its parent must be block N + 1
+        let header = header();
+        let skipped = mock_block_header_with(&keccak256(&header), 21_000_005, &[0x11; 32]);
+        assert!(matches!(
+            verify_chain(&[header, skipped]),
+            Err(ChainError::NotConsecutive { index: 1, expected: 21_000_001, found: 21_000_005 })
+        ));
+
+        assert!(matches!(verify_chain::<Vec<u8>>(&[]), Err(ChainError::Empty)));
+        assert!(matches!(verify_chain(&[vec![0xc0]]), Err(ChainError::Header { index: 0, .. })));
+    }
+}
@@ -0,0 +1,118 @@
+// Byte strings of input files, as 0x-prefixed hex or JSON integer arrays
+// MPT layers and block headers were first written as integer arrays, up to four JSON bytes per
+// byte; hex takes two, so a 16-layer witness shrinks about 3×. Both spellings are read, so
+// existing files keep working, and hex is written.
+//
+// Use with `#[serde(with = "crate::utils::hex_bytes")]` on a `Vec<u8>`, or
+// `crate::utils::hex_bytes::layers` on a `Vec<Vec<u8>>`.
+
+use std::fmt;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Bytes preallocated for an integer array before its elements are read
+const MAX_PREALLOCATED: usize = 1 << 16;
+
+pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_any(ByteStringVisitor)
+}
+
+/// A list of byte strings, each hex or an integer array
+pub mod layers {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(layers: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(layers.len()))?;
+        for layer in layers {
+            seq.serialize_element(&ByteString(layer.clone()))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error> {
+        let layers = Vec::<ByteString>::deserialize(deserializer)?;
+        Ok(layers.into_iter().map(|layer| layer.0).collect())
+    }
+}
+
+struct ByteString(Vec<u8>);
+
+impl Serialize for ByteString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(ByteString)
+    }
+}
+
+struct ByteStringVisitor;
+
+impl<'de> Visitor<'de> for ByteStringVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a 0x-prefixed hex string or an array of bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<u8>, E> {
+        let digits = value
+            .strip_prefix("0x")
+            .ok_or_else(|| E::custom(format!("hex byte string without 0x prefix: {value:.16}")))?;
+        hex::decode(digits).map_err(|e| E::custom(format!("invalid hex byte string: {e}")))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        // The length hint comes from the input, so it only bounds the first allocation
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Witness {
+        #[serde(with = "crate::utils::hex_bytes::layers")]
+        layers: Vec<Vec<u8>>,
+        #[serde(with = "crate::utils::hex_bytes")]
+        header: Vec<u8>,
+    }
+
+    #[test]
+    fn test_hex_and_arrays_read_alike() {
+        let expected = Witness { layers: vec![vec![0xf8, 0x01], vec![]], header: vec![0, 255] };
+        let arrays = r#"{"layers": [[248, 1], []], "header": [0, 255]}"#;
+        let hex = r#"{"layers": ["0xf801", "0x"], "header": "0x00FF"}"#;
+        let mixed = r#"{"layers": ["0xf801", []], "header": [0, 255]}"#;
+        for json in [arrays, hex, mixed] {
+            assert_eq!(serde_json::from_str::<Witness>(json).unwrap(), expected);
+        }
+
+        let written = serde_json::to_string(&expected).unwrap();
+        assert_eq!(written, r#"{"layers":["0xf801","0x"],"header":"0x00ff"}"#);
+        assert_eq!(serde_json::from_str::<Witness>(&written).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_malformed_byte_strings_rejected() {
+        for header in [r#""00ff""#, r#""0x0""#, r#""0xzz""#, "[256]", "[-1]", "7"] {
+            let json = format!(r#"{{"layers": [], "header": {header}}}"#);
+            assert!(serde_json::from_str::<Witness>(&json).is_err(), "{header} was accepted");
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// M31 values of input files, written as 0x-prefixed hex
+// Wallets show coins as `SpendOutputs::coin_hex` prints them, so a coin an input file carries is
+// written in that spelling. Reading is `M31`'s own: hex or an integer, canonical either way.
+//
+// Use with `#[serde(with = "crate::utils::hex_m31::option")]` on an `Option<M31>`.
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::field::M31;
+
+pub fn serialize<S: Serializer>(value: &M31, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:#x}", value.value()))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<M31, D::Error> {
+    M31::deserialize(deserializer)
+}
+
+/// An optional M31, hex or an integer when present
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<M31>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<M31>, D::Error> {
+        Option::<M31>::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Coin {
+        #[serde(default, with = "crate::utils::hex_m31::option", skip_serializing_if = "Option::is_none")]
+        coin: Option<M31>,
+    }
+
+    #[test]
+    fn test_hex_and_integers_read_alike() {
+        let expected = Coin { coin: Some(M31::new(0x7fff_fffe)) };
+        for json in [r#"{"coin": "0x7ffffffe"}"#, r#"{"coin": "0x7FFFFFFE"}"#, r#"{"coin": 2147483646}"#] {
+            assert_eq!(serde_json::from_str::<Coin>(json).unwrap(), expected);
+        }
+        for json in ["{}", r#"{"coin": null}"#] {
+            assert_eq!(serde_json::from_str::<Coin>(json).unwrap(), Coin { coin: None });
+        }
+
+        let written = serde_json::to_string(&expected).unwrap();
+        assert_eq!(written, r#"{"coin":"0x7ffffffe"}"#);
+        assert_eq!(serde_json::to_string(&Coin { coin: None }).unwrap(), "{}");
+    }
+
+    #[test]
+    fn test_non_canonical_and_malformed_rejected() {
+        for coin in [r#""0x7fffffff""#, "2147483647", r#""7f""#, r#""0x""#, r#""0x100000000""#, r#""0xzz""#, "-1", "[1]"] {
+            let json = format!(r#"{{"coin": {coin}}}"#);
+            assert!(serde_json::from_str::<Coin>(&json).is_err(), "{coin} was accepted");
+        }
+    }
+}
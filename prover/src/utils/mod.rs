@@ -2,9 +2,16 @@
 
 pub mod poseidon; // Legacy implementation
 pub mod poseidon2_stwo; // Real Poseidon2 using stwo primitives
+#[cfg(feature = "poseidon2-24")]
+pub mod poseidon2_24; // 24-wide parameters, not yet validated
 pub mod keccak;
 pub mod rlp;
 pub mod mpt;
 pub mod pow;
 pub mod burn_address;
+pub mod header;
+pub mod header_chain;
+pub mod commitments;
+pub mod hex_bytes;
+pub mod hex_m31;
 
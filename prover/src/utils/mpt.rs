@@ -1,27 +1,183 @@
 // Merkle-Patricia-Trie verification logic
 // Reference: proof-of-burn/circuits/proof_of_burn.circom lines 148-193
 //
-// Verifies that a given account balance exists at a burn address
-// in Ethereum's state trie by checking MPT proof layers
+// Verifies that an account exists at a burn address in Ethereum's state trie
+// by checking MPT proof layers, and decodes the account the leaf holds
 
+use crate::constants::circuit_params::{EMPTY_CODE_HASH, EMPTY_STORAGE_ROOT, MAX_NODE_LEN, MAX_NUM_LAYERS};
 use crate::utils::keccak::keccak256;
-use crate::utils::rlp::{Account, bytes_to_nibbles};
+use crate::utils::rlp::{
+    bytes_to_nibbles, hex_prefix_decode, hex_prefix_encode, rlp_list_items, rlp_list_raw_items,
+    rlp_string_payload, Account,
+};
 use alloy_primitives::U256;
+use alloy_rlp::{Decodable, Encodable};
 
-/// Verify MPT proof that an account with given balance exists at address_hash
+/// Account a verified MPT proof commits to, decoded from its leaf
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenAccount {
+    pub balance: U256,
+    pub nonce: u64,
+    pub storage_root: [u8; 32],
+    pub code_hash: [u8; 32],
+    /// Address-hash nibbles stored in the leaf key, all matching the end of the address hash
+    pub key_nibbles_matched: usize,
+}
+
+impl ProvenAccount {
+    /// Whether the account has the shape of a burn address: no nonce, storage or code
+    pub fn is_burn_account(&self) -> bool {
+        self.nonce == 0 && self.storage_root == EMPTY_STORAGE_ROOT && self.code_hash == EMPTY_CODE_HASH
+    }
+}
+
+/// A trie node, borrowing from its RLP encoding
+///
+/// Child references and values keep their full RLP encoding (a 32-byte hash string, the empty
+/// string, or an inline node under 32 bytes), so `encode` reproduces the input exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MptNode<'a> {
+    /// 16 child references, one per nibble, and the value stored at this path
+    Branch { children: [&'a [u8]; 16], value: &'a [u8] },
+
+    /// Path nibbles shared by every key below, and the reference to the node they lead to
+    Extension { path: Vec<u8>, child: &'a [u8] },
+
+    /// Remaining key nibbles and the stored value (the RLP-encoded account in the state trie)
+    Leaf { path: Vec<u8>, value: &'a [u8] },
+}
+
+impl<'a> MptNode<'a> {
+    /// Decode a node spanning all of `data`
+    pub fn decode(data: &'a [u8]) -> Result<Self, alloy_rlp::Error> {
+        let items = rlp_list_raw_items(data)?;
+        match items.as_slice() {
+            [path, reference] => {
+                let path = rlp_string_payload(path)?;
+                let (nibbles, leaf) =
+                    hex_prefix_decode(path).ok_or(alloy_rlp::Error::Custom("malformed hex-prefix path"))?;
+                Ok(if leaf {
+                    Self::Leaf { path: nibbles, value: *reference }
+                } else {
+                    Self::Extension { path: nibbles, child: *reference }
+                })
+            }
+            [children @ .., value] if children.len() == 16 => Ok(Self::Branch {
+                children: children.try_into().expect("16 children"),
+                value: *value,
+            }),
+            _ => Err(alloy_rlp::Error::Custom("trie node must have 2 or 17 items")),
+        }
+    }
+
+    /// RLP encoding of the node
+    pub fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        match self {
+            Self::Branch { children, value } => {
+                children.iter().for_each(|child| payload.extend_from_slice(child));
+                payload.extend_from_slice(value);
+            }
+            Self::Extension { path, child: reference } | Self::Leaf { path, value: reference } => {
+                let leaf = matches!(self, Self::Leaf { .. });
+                hex_prefix_encode(path, leaf).as_slice().encode(&mut payload);
+                payload.extend_from_slice(reference);
+            }
+        }
+
+        let mut out = Vec::with_capacity(payload.len() + 3);
+        alloy_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut out);
+        out.extend_from_slice(&payload);
+        out
+    }
+}
+
+/// Longest parent layer `contains_hash` scans
+///
+/// Stopgap: remove with `contains_hash` once `MptNode::decode` links the layers.
+pub const MAX_SCAN_LAYER_LEN: usize = MAX_NODE_LEN;
+
+/// Bytes `contains_hash` scans across one proof
+///
+/// Stopgap, like `MAX_SCAN_LAYER_LEN`.
+pub const MAX_SCAN_TOTAL_LEN: usize = MAX_NUM_LAYERS * MAX_NODE_LEN;
+
+/// Smallest plausible root layer: the root is hashed into the header, and nodes under 32 bytes
+/// only ever appear inlined in their parent
+pub const MIN_ROOT_LEN: usize = 32;
+
+/// Check the shape of MPT proof layers before hashing or scanning any of them
+///
+/// Returns the first problem `layer_issues` finds.
+pub fn check_layers(layers: &[Vec<u8>]) -> Result<(), LayerError> {
+    match layer_issues(layers).into_iter().next() {
+        Some(issue) => Err(issue),
+        None => Ok(()),
+    }
+}
+
+/// Every shape problem of the MPT proof layers, in layer order
+///
+/// Each layer must be non-empty and at most `MAX_NODE_LEN` bytes, there must be between 1 and
+/// `MAX_NUM_LAYERS` layers, the root at least `MIN_ROOT_LEN` bytes and the last layer a 2-item
+/// RLP list, the shape of a leaf.
+pub fn layer_issues(layers: &[Vec<u8>]) -> Vec<LayerError> {
+    let mut issues = Vec::new();
+    if layers.is_empty() {
+        issues.push(LayerError::NoLayers);
+        return issues;
+    }
+    if layers.len() > MAX_NUM_LAYERS {
+        issues.push(LayerError::TooManyLayers { count: layers.len(), max: MAX_NUM_LAYERS });
+    }
+
+    for (index, layer) in layers.iter().enumerate() {
+        if layer.is_empty() {
+            issues.push(LayerError::EmptyLayer { index });
+        } else if layer.len() > MAX_NODE_LEN {
+            issues.push(LayerError::LayerTooLarge { index, size: layer.len(), max: MAX_NODE_LEN });
+        } else if index == 0 && layer.len() < MIN_ROOT_LEN {
+            issues.push(LayerError::RootTooShort { size: layer.len(), min: MIN_ROOT_LEN });
+        }
+    }
+
+    // Only decode a last layer that passed the size checks
+    let index = layers.len() - 1;
+    let leaf = &layers[index];
+    if !leaf.is_empty() && leaf.len() <= MAX_NODE_LEN {
+        match rlp_list_items(leaf) {
+            Ok(items) if items.len() == 2 => {}
+            Ok(items) => issues.push(LayerError::NotALeaf {
+                index,
+                size: leaf.len(),
+                reason: format!("RLP list of {} items, expected 2", items.len()),
+            }),
+            Err(e) => issues.push(LayerError::NotALeaf { index, size: leaf.len(), reason: e.to_string() }),
+        }
+    }
+
+    issues
+}
+
+/// Verify an MPT proof for the account at address_hash and return the account it proves
 /// 
 /// Translates the Circom logic:
 /// 1. keccak(layers[0]) === stateRoot
 /// 2. For each layer i > 0: keccak(layers[i]) is substring of layers[i-1]
 /// 3. layers[numLayers - 1] === leaf node with account data
+///
+/// The account is whatever the leaf holds; callers compare it against the values they expect.
+/// The layers are checked with `check_layers` first, then each must be a canonically encoded
+/// node (`check_canonical_node`) before any is hashed.
+#[tracing::instrument(level = "debug", skip_all, fields(layers = layers.len()))]
 pub fn verify_mpt_proof(
     layers: &[Vec<u8>],
     state_root: &[u8; 32],
     address_hash: &[u8; 32],
-    balance: U256,
-) -> Result<(), MptError> {
-    if layers.is_empty() {
-        return Err(MptError::EmptyProof);
+) -> Result<ProvenAccount, MptError> {
+    check_layers(layers)?;
+    for (layer, node) in layers.iter().enumerate() {
+        check_canonical_node(node).map_err(|reason| MptError::NonCanonicalRlp { layer, reason })?;
     }
     
     // Step 1: Verify top layer hashes to state root
@@ -35,12 +191,13 @@ pub fn verify_mpt_proof(
     
     // Step 2: Verify each layer's hash is contained in previous layer
     // This verifies the path down the trie
+    let mut scanned = 0;
     for i in 1..layers.len() {
         let current_hash = keccak256(&layers[i]);
         
         // Check if current_hash appears in parent layer (layers[i-1])
         // We need to find the 32-byte hash as a substring in the RLP-encoded parent
-        if !contains_hash(&layers[i - 1], &current_hash) {
+        if !contains_hash(&layers[i - 1], &current_hash, &mut scanned)? {
             return Err(MptError::HashNotInParent {
                 layer: i,
                 hash: current_hash,
@@ -48,64 +205,86 @@ pub fn verify_mpt_proof(
         }
     }
     
-    // Step 3: Verify last layer is a valid leaf with correct account data
+    // Step 3: Decode the last layer as the account leaf for address_hash
     let leaf_layer = layers.last().unwrap();
-    verify_leaf_layer(leaf_layer, address_hash, balance)?;
-    
+    decode_leaf_layer(leaf_layer, address_hash)
+}
+
+/// Check `node_data` is the one canonical RLP encoding of a trie node
+///
+/// The substring checks of `verify_mpt_proof` only link hashes, so a node re-encoded with a padded
+/// length prefix, a zero-padded scalar or trailing bytes would link as well while claiming the
+/// same content. `MptNode::decode` rejects non-minimal headers (alloy's `Header::decode`) and
+/// trailing bytes, so a node that decodes re-encodes to its input; a leaf's account must too.
+fn check_canonical_node(node_data: &[u8]) -> Result<(), String> {
+    let node = MptNode::decode(node_data).map_err(|e| e.to_string())?;
+    if let MptNode::Leaf { value, .. } = node {
+        let mut account_rlp = rlp_string_payload(value).map_err(|e| format!("leaf value: {}", e))?;
+        let encoded = account_rlp;
+        let account = Account::decode(&mut account_rlp).map_err(|e| format!("account: {}", e))?;
+        if account.encode_to_vec() != encoded {
+            return Err("account does not re-encode to the same bytes".to_string());
+        }
+    }
     Ok(())
 }
 
 /// Check if a 32-byte hash appears in RLP-encoded node data
-fn contains_hash(node_data: &[u8], hash: &[u8; 32]) -> bool {
+///
+/// `scanned` counts the bytes scanned so far for one proof. A layer over `MAX_SCAN_LAYER_LEN`, or
+/// one that would take the count over `MAX_SCAN_TOTAL_LEN`, fails before any of it is scanned.
+/// `verify_mpt_proof` checks the layer shapes first, so this only guards callers that skip that.
+/// Stopgap: remove once the structural parser is the only path.
+fn contains_hash(node_data: &[u8], hash: &[u8; 32], scanned: &mut usize) -> Result<bool, MptError> {
+    if node_data.len() > MAX_SCAN_LAYER_LEN {
+        return Err(MptError::LayerTooLarge { size: node_data.len(), max: MAX_SCAN_LAYER_LEN });
+    }
+    let total = *scanned + node_data.len();
+    if total > MAX_SCAN_TOTAL_LEN {
+        return Err(MptError::ScanLimitExceeded { scanned: total, max: MAX_SCAN_TOTAL_LEN });
+    }
+    *scanned = total;
+
     // Search for the hash as a substring
     // In RLP-encoded MPT nodes, child hashes appear as 32-byte sequences
-    node_data.windows(32).any(|window| window == hash)
+    Ok(node_data.windows(32).any(|window| window == hash))
 }
 
-/// Verify that the leaf layer contains the correct account data
-fn verify_leaf_layer(
-    leaf_data: &[u8],
-    address_hash: &[u8; 32],
-    balance: U256,
-) -> Result<(), MptError> {
-    // The leaf should be RLP-encoded as [key_nibbles, account_rlp]
-    // where account_rlp = [nonce, balance, storage_root, code_hash]
-    
-    // For simplicity, we'll verify:
-    // 1. The leaf is properly formatted
-    // 2. It contains the expected balance value
-    
-    // Create expected account
-    let expected_account = Account::new_burn_account(balance);
-    let account_rlp = expected_account.encode_to_vec();
-    
-    // Check if account RLP appears in leaf data
-    if !contains_subsequence(leaf_data, &account_rlp) {
-        return Err(MptError::InvalidLeaf {
-            reason: "Account data not found in leaf".to_string(),
-        });
+/// Decode the leaf layer `[hex_prefix(key), account_rlp]`, checking its key against address_hash
+fn decode_leaf_layer(leaf_data: &[u8], address_hash: &[u8; 32]) -> Result<ProvenAccount, MptError> {
+    let invalid = |reason: String| MptError::InvalidLeaf { reason };
+
+    let items = rlp_list_items(leaf_data).map_err(|e| invalid(e.to_string()))?;
+    let [path, value] = items.as_slice() else {
+        return Err(invalid(format!("expected 2 items, found {}", items.len())));
+    };
+
+    // Hex-prefix encoding: high nibble of the first byte is 2 (even) or 3 (odd) for leaves
+    let (key_nibbles, leaf) =
+        hex_prefix_decode(path).ok_or_else(|| invalid("malformed hex-prefix key".to_string()))?;
+    if !leaf {
+        return Err(invalid(format!("not a leaf node (prefix {})", path[0] >> 4)));
     }
-    
-    // Verify address hash nibbles appear in leaf
-    let address_nibbles = bytes_to_nibbles(address_hash);
-    
-    // The leaf should contain at least the last portion of the address nibbles
-    // (exact verification depends on MPT depth, we'll do a relaxed check)
-    // In Circom: requires at least MIN_LEAF_ADDRESS_NIBBLES nibbles
-    
-    Ok(())
-}
 
-/// Check if haystack contains needle as a subsequence
-fn contains_subsequence(haystack: &[u8], needle: &[u8]) -> bool {
-    if needle.is_empty() {
-        return true;
+    // The branch levels above consume the leading nibbles; the leaf key holds the rest
+    let address_nibbles = bytes_to_nibbles(address_hash);
+    if key_nibbles.len() > address_nibbles.len() || !address_nibbles.ends_with(&key_nibbles) {
+        return Err(MptError::LeafKeyMismatch);
     }
-    if haystack.len() < needle.len() {
-        return false;
+
+    let mut value: &[u8] = value;
+    let account = Account::decode(&mut value).map_err(|e| invalid(format!("account: {}", e)))?;
+    if !value.is_empty() {
+        return Err(invalid(format!("{} trailing bytes after the account", value.len())));
     }
-    
-    haystack.windows(needle.len()).any(|window| window == needle)
+
+    Ok(ProvenAccount {
+        balance: account.balance,
+        nonce: account.nonce,
+        storage_root: account.storage_root,
+        code_hash: account.code_hash,
+        key_nibbles_matched: key_nibbles.len(),
+    })
 }
 
 /// Detect if a node is a leaf node
@@ -129,10 +308,31 @@ pub fn is_leaf_node(node_data: &[u8]) -> bool {
     node_data.len() < 600 // Heuristic: leaves are typically smaller than branch nodes
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LayerError {
+    #[error("Empty MPT proof: no layers")]
+    NoLayers,
+
+    #[error("Too many MPT layers: {count}, max {max}")]
+    TooManyLayers { count: usize, max: usize },
+
+    #[error("MPT layer {index} is empty")]
+    EmptyLayer { index: usize },
+
+    #[error("MPT layer {index} is {size} bytes, max {max}")]
+    LayerTooLarge { index: usize, size: usize, max: usize },
+
+    #[error("MPT layer 0 (root) is {size} bytes, min {min}")]
+    RootTooShort { size: usize, min: usize },
+
+    #[error("MPT layer {index} ({size} bytes) is not a leaf: {reason}")]
+    NotALeaf { index: usize, size: usize, reason: String },
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum MptError {
-    #[error("Empty MPT proof")]
-    EmptyProof,
+    #[error(transparent)]
+    Layers(#[from] LayerError),
     
     #[error("Invalid state root: expected {expected:?}, computed {computed:?}")]
     InvalidStateRoot {
@@ -150,6 +350,27 @@ pub enum MptError {
     InvalidLeaf {
         reason: String,
     },
+    
+    #[error("Leaf key is not a suffix of the address hash")]
+    LeafKeyMismatch,
+
+    #[error("MPT layer {layer} is not canonical RLP: {reason}")]
+    NonCanonicalRlp {
+        layer: usize,
+        reason: String,
+    },
+
+    #[error("MPT layer of {size} bytes is too large to scan, max {max}")]
+    LayerTooLarge {
+        size: usize,
+        max: usize,
+    },
+
+    #[error("MPT proof needs {scanned} bytes scanned, max {max}")]
+    ScanLimitExceeded {
+        scanned: usize,
+        max: usize,
+    },
 }
 
 #[cfg(test)]
@@ -164,7 +385,9 @@ mod tests {
         // Insert hash at position 50
         node_data[50..82].copy_from_slice(&hash);
         
-        assert!(contains_hash(&node_data, &hash));
+        let mut scanned = 0;
+        assert!(contains_hash(&node_data, &hash, &mut scanned).unwrap());
+        assert_eq!(scanned, 100);
     }
     
     #[test]
@@ -172,23 +395,38 @@ mod tests {
         let hash = [0xAB; 32];
         let node_data = vec![0x00; 100];
         
-        assert!(!contains_hash(&node_data, &hash));
+        assert!(!contains_hash(&node_data, &hash, &mut 0).unwrap());
     }
     
     #[test]
-    fn test_contains_subsequence() {
-        let haystack = b"hello world";
-        let needle = b"world";
+    fn test_contains_hash_rejects_oversized_layer_without_scanning() {
+        // 1 MB with the hash at the very end: a scan would find it
+        let hash = [0xAB; 32];
+        let mut node_data = vec![0x00; 1 << 20];
+        node_data[(1 << 20) - 32..].copy_from_slice(&hash);
         
-        assert!(contains_subsequence(haystack, needle));
+        let mut scanned = 0;
+        let result = contains_hash(&node_data, &hash, &mut scanned);
+        assert!(matches!(
+            result,
+            Err(MptError::LayerTooLarge { size, max: MAX_SCAN_LAYER_LEN }) if size == 1 << 20
+        ));
+        assert_eq!(scanned, 0);
     }
     
     #[test]
-    fn test_contains_subsequence_not_found() {
-        let haystack = b"hello world";
-        let needle = b"rust";
+    fn test_contains_hash_total_scan_limit() {
+        let hash = [0xAB; 32];
+        let node_data = vec![0x00; MAX_SCAN_LAYER_LEN];
         
-        assert!(!contains_subsequence(haystack, needle));
+        let mut scanned = 0;
+        for _ in 0..MAX_SCAN_TOTAL_LEN / MAX_SCAN_LAYER_LEN {
+            assert!(!contains_hash(&node_data, &hash, &mut scanned).unwrap());
+        }
+        assert!(matches!(
+            contains_hash(&[0x00; 32], &hash, &mut scanned),
+            Err(MptError::ScanLimitExceeded { scanned, max: MAX_SCAN_TOTAL_LEN }) if scanned == MAX_SCAN_TOTAL_LEN + 32
+        ));
     }
     
     #[test]
@@ -196,10 +434,181 @@ mod tests {
         let layers: Vec<Vec<u8>> = vec![];
         let state_root = [0u8; 32];
         let address_hash = [0u8; 32];
-        let balance = U256::from(0);
         
-        let result = verify_mpt_proof(&layers, &state_root, &address_hash, balance);
-        assert!(result.is_err());
+        let result = verify_mpt_proof(&layers, &state_root, &address_hash);
+        assert!(matches!(result, Err(MptError::Layers(LayerError::NoLayers))));
+    }
+    
+    #[test]
+    fn test_layer_shape_rejections() {
+        use crate::test_utils::mock_account_proof;
+        
+        let proof = mock_account_proof(&keccak256(b"mock burn address"), U256::from(1u64), 3);
+        assert_eq!(check_layers(&proof.layers), Ok(()));
+        let with_layer = |index: usize, layer: Vec<u8>| {
+            let mut layers = proof.layers.clone();
+            layers[index] = layer;
+            check_layers(&layers)
+        };
+        
+        assert_eq!(with_layer(1, vec![]), Err(LayerError::EmptyLayer { index: 1 }));
+        assert_eq!(
+            with_layer(1, vec![0xc0; MAX_NODE_LEN + 1]),
+            Err(LayerError::LayerTooLarge { index: 1, size: MAX_NODE_LEN + 1, max: MAX_NODE_LEN })
+        );
+        assert_eq!(with_layer(0, vec![0xc0; 31]), Err(LayerError::RootTooShort { size: 31, min: MIN_ROOT_LEN }));
+        
+        // A branch where the leaf should be
+        let branch = proof.layers[0].clone();
+        assert!(matches!(with_layer(2, branch), Err(LayerError::NotALeaf { index: 2, size: 532, .. })));
+        assert!(matches!(with_layer(2, vec![0x80; 40]), Err(LayerError::NotALeaf { index: 2, size: 40, .. })));
+        
+        let too_many = vec![proof.layers[0].clone(); MAX_NUM_LAYERS + 1];
+        assert!(matches!(
+            check_layers(&too_many),
+            Err(LayerError::TooManyLayers { count, max: MAX_NUM_LAYERS }) if count == MAX_NUM_LAYERS + 1
+        ));
+    }
+    
+    #[test]
+    fn test_oversized_layer_is_rejected_before_hashing() {
+        // A 10 MB layer must not be hashed or scanned
+        let layers = vec![vec![0xab; 10 << 20]];
+        let result = verify_mpt_proof(&layers, &[0u8; 32], &[0u8; 32]);
+        assert!(matches!(
+            result,
+            Err(MptError::Layers(LayerError::LayerTooLarge { index: 0, size, .. })) if size == 10 << 20
+        ));
+    }
+    
+    #[test]
+    fn test_random_layers_never_panic() {
+        // xorshift64, so the blobs are reproducible
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        
+        for _ in 0..2000 {
+            let n_layers = (next() % 4) as usize;
+            let layers: Vec<Vec<u8>> = (0..n_layers)
+                .map(|_| {
+                    let len = (next() % 700) as usize;
+                    let mut layer: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+                    // Give some blobs an RLP list header so the leaf decoder runs too
+                    if let Some(first) = layer.first_mut() {
+                        if next() % 2 == 0 {
+                            *first = 0xc0 | (*first & 0x3f);
+                        }
+                    }
+                    layer
+                })
+                .collect();
+            let address_hash = keccak256(&next().to_be_bytes());
+            let state_root = layers.first().map(|root| keccak256(root)).unwrap_or_default();
+            let _ = layer_issues(&layers);
+            let _ = verify_mpt_proof(&layers, &state_root, &address_hash);
+        }
+    }
+    
+    #[test]
+    fn test_verify_mpt_proof_returns_leaf_account() {
+        use crate::test_utils::mock_account_proof;
+        
+        let address_hash = keccak256(b"mock burn address");
+        let balance = U256::from(1_000_000_000_000_000_000u64);
+        let proof = mock_account_proof(&address_hash, balance, 3);
+        
+        let proven = verify_mpt_proof(&proof.layers, &proof.state_root, &address_hash).unwrap();
+        assert_eq!(proven.balance, balance);
+        assert_eq!(proven.nonce, 0);
+        assert_eq!(proven.storage_root, EMPTY_STORAGE_ROOT);
+        assert_eq!(proven.code_hash, EMPTY_CODE_HASH);
+        assert_eq!(proven.key_nibbles_matched, 62);
+        assert!(proven.is_burn_account());
+    }
+    
+    #[test]
+    fn test_verify_mpt_proof_other_address() {
+        use crate::test_utils::mock_account_proof;
+        
+        // A one-layer trie: the leaf key holds all 64 nibbles, so any other hash mismatches
+        let address_hash = keccak256(b"mock burn address");
+        let proof = mock_account_proof(&address_hash, U256::from(1u64), 1);
+        let other = keccak256(b"other address");
+        
+        let result = verify_mpt_proof(&proof.layers, &proof.state_root, &other);
+        assert!(matches!(result, Err(MptError::LeafKeyMismatch)));
+    }
+    
+    #[test]
+    fn test_mpt_node_roundtrip() {
+        use crate::test_utils::mock_account_proof;
+        
+        let proof = mock_account_proof(&keccak256(b"mock burn address"), U256::from(1u64), 3);
+        for layer in &proof.layers {
+            let node = MptNode::decode(layer).unwrap();
+            assert_eq!(node.encode(), *layer);
+        }
+        assert!(matches!(MptNode::decode(&proof.layers[0]), Ok(MptNode::Branch { .. })));
+        assert!(matches!(MptNode::decode(&proof.layers[2]), Ok(MptNode::Leaf { ref path, .. }) if path.len() == 62));
+        
+        // Three items is neither a branch nor a short node
+        assert!(MptNode::decode(&[0xc3, 0x01, 0x02, 0x03]).is_err());
+        // Trailing bytes after the node
+        let mut trailing = proof.layers[2].clone();
+        trailing.push(0);
+        assert!(MptNode::decode(&trailing).is_err());
+    }
+    
+    #[test]
+    fn test_non_canonical_layers_are_rejected() {
+        use crate::test_utils::mock_account_proof;
+        
+        let address_hash = keccak256(b"mock burn address");
+        let proof = mock_account_proof(&address_hash, U256::from(1u64), 3);
+        let verify = |index: usize, layer: Vec<u8>| {
+            let mut layers = proof.layers.clone();
+            layers[index] = layer;
+            verify_mpt_proof(&layers, &proof.state_root, &address_hash)
+        };
+        let rejected_at = |result: Result<ProvenAccount, MptError>| match result {
+            Err(MptError::NonCanonicalRlp { layer, .. }) => Some(layer),
+            _ => None,
+        };
+        
+        // The branch's two-byte length, padded to three: a lenient decoder reads the same node
+        let branch = &proof.layers[1];
+        assert_eq!(branch[0], 0xf9);
+        let padded = [&[0xfa, 0x00][..], &branch[1..]].concat();
+        assert_eq!(rejected_at(verify(1, padded)), Some(1));
+        
+        // Trailing bytes after the leaf
+        let mut trailing = proof.layers[2].clone();
+        trailing.push(0x80);
+        assert_eq!(rejected_at(verify(2, trailing)), Some(2));
+        
+        // The account balance with a leading zero byte
+        let MptNode::Leaf { path, value } = MptNode::decode(&proof.layers[2]).unwrap() else { unreachable!() };
+        let account = Account::decode(&mut rlp_string_payload(value).unwrap()).unwrap();
+        let mut fields = Vec::new();
+        account.nonce.encode(&mut fields);
+        [&[0u8][..], &account.balance.to_be_bytes_trimmed_vec()].concat().as_slice().encode(&mut fields);
+        account.storage_root.as_slice().encode(&mut fields);
+        account.code_hash.as_slice().encode(&mut fields);
+        let mut account_rlp = Vec::new();
+        alloy_rlp::Header { list: true, payload_length: fields.len() }.encode(&mut account_rlp);
+        account_rlp.extend_from_slice(&fields);
+        let mut padded_value = Vec::new();
+        account_rlp.as_slice().encode(&mut padded_value);
+        let padded_leaf = MptNode::Leaf { path, value: &padded_value }.encode();
+        assert_eq!(rejected_at(verify(2, padded_leaf)), Some(2));
+        
+        // The untouched proof still verifies
+        assert!(verify(1, proof.layers[1].clone()).is_ok());
     }
     
     #[test]
@@ -2,6 +2,7 @@
 // Implementing Poseidon sponge construction over M31
 // Reference: WORM's miner/src/poseidon.rs (adapted for M31 instead of BN254)
 
+use crate::constants::OUTPUT_LIMBS;
 use crate::field::M31;
 
 // Poseidon parameters for M31 field
@@ -110,11 +111,41 @@ pub fn poseidon3(inputs: [M31; 3]) -> M31 {
 
 /// Compute Poseidon hash of 4 M31 elements
 /// Equivalent to WORM's Poseidon(4)([a, b, c, d])
-/// Used for: burnAddress = Poseidon4(prefix, burnKey, revealAmount, burnExtraCommitment)
+/// Used for: burnAddress before v2 of the derivation (see `burn_address::BURN_ADDRESS_VERSION`)
 pub fn poseidon4(inputs: [M31; 4]) -> M31 {
     poseidon_hash(&inputs)
 }
 
+/// Poseidon sponge with a multi-limb output
+/// Used for: nullifier and commitment, which need more than 31 bits of collision resistance
+///
+/// Absorbs `inputs` at rate 4 (t=5) with the input length in the capacity element,
+/// then squeezes one limb per permutation until `OUTPUT_LIMBS` limbs are produced.
+pub fn poseidon_wide(inputs: &[M31]) -> [M31; OUTPUT_LIMBS] {
+    let mut state = [M31::zero(); T];
+    state[0] = M31::from(inputs.len() as u32);
+
+    // Absorb (an empty input still gets one permutation)
+    for chunk in inputs.chunks(T - 1) {
+        for (i, input) in chunk.iter().enumerate() {
+            state[i + 1] = state[i + 1] + *input;
+        }
+        poseidon_permutation(&mut state, ROUNDS_P_4);
+    }
+    if inputs.is_empty() {
+        poseidon_permutation(&mut state, ROUNDS_P_4);
+    }
+
+    // Squeeze
+    let mut output = [M31::zero(); OUTPUT_LIMBS];
+    output[0] = state[0];
+    for limb in output.iter_mut().skip(1) {
+        poseidon_permutation(&mut state, ROUNDS_P_4);
+        *limb = state[0];
+    }
+    output
+}
+
 /// Convert U256 to M31 by reducing modulo M31 prime
 /// Used when we need to hash large numbers like balances
 /// 
@@ -128,22 +159,31 @@ pub fn u256_to_m31(value: alloy_primitives::U256) -> M31 {
     M31::new(low)
 }
 
+/// Bits per element in `u256_to_m31_array`
+///
+/// 30 rather than 31: a 31-bit chunk of all ones equals the prime and would reduce to zero.
+pub const M31_ARRAY_CHUNK_BITS: usize = 30;
+
+/// Number of elements `u256_to_m31_array` splits a U256 into
+pub const U256_M31_LIMBS: usize = 256usize.div_ceil(M31_ARRAY_CHUNK_BITS);
+
 /// Convert U256 to multiple M31 elements for better representation
-/// Splits a U256 into chunks that fit in M31
+/// Splits a U256 into 30-bit chunks, least significant first (`U256_M31_LIMBS` elements)
 pub fn u256_to_m31_array(value: alloy_primitives::U256) -> Vec<M31> {
-    let mut result = Vec::new();
-    let limbs = value.as_limbs();
-    
-    // U256 has 4 x 64-bit limbs
-    // We split each limb into two 31-bit chunks
-    for limb in limbs {
-        let low = (limb & 0x7FFFFFFF) as u32;  // Lower 31 bits
-        let high = ((limb >> 31) & 0x7FFFFFFF) as u32;  // Next 31 bits
-        result.push(M31::new(low));
-        result.push(M31::new(high));
-    }
-    
-    result
+    let mask = alloy_primitives::U256::from((1u64 << M31_ARRAY_CHUNK_BITS) - 1);
+    (0..U256_M31_LIMBS)
+        .map(|i| {
+            let chunk = (value >> (i * M31_ARRAY_CHUNK_BITS)) & mask;
+            M31::new(chunk.as_limbs()[0] as u32)
+        })
+        .collect()
+}
+
+/// Inverse of `u256_to_m31_array`
+pub fn m31_array_to_u256(chunks: &[M31]) -> alloy_primitives::U256 {
+    chunks.iter().enumerate().fold(alloy_primitives::U256::ZERO, |acc, (i, chunk)| {
+        acc | (alloy_primitives::U256::from(chunk.value()) << (i * M31_ARRAY_CHUNK_BITS))
+    })
 }
 
 #[cfg(test)]
@@ -286,6 +326,43 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_poseidon_wide_nullifier_collisions() {
+        // 10k burn keys: whole outputs must be unique, and each limb on its own
+        // should behave like an independent 31-bit hash (~0.02 expected collisions per limb)
+        let nullifier_prefix = crate::constants::poseidon_nullifier_prefix();
+        let outputs: Vec<[M31; OUTPUT_LIMBS]> = (0..10_000u32)
+            .map(|key| poseidon_wide(&[nullifier_prefix, M31::from(key)]))
+            .collect();
+        
+        let unique: std::collections::HashSet<_> = outputs.iter().collect();
+        assert_eq!(unique.len(), outputs.len(), "Wide nullifier collision");
+        
+        for limb in 0..OUTPUT_LIMBS {
+            let distinct: std::collections::HashSet<u32> =
+                outputs.iter().map(|output| output[limb].value()).collect();
+            assert!(
+                outputs.len() - distinct.len() <= 2,
+                "Limb {} has {} collisions",
+                limb,
+                outputs.len() - distinct.len()
+            );
+        }
+        
+        // Limbs within one output are not copies of each other
+        let first = outputs[0];
+        let limbs: std::collections::HashSet<u32> = first.iter().map(|limb| limb.value()).collect();
+        assert_eq!(limbs.len(), OUTPUT_LIMBS);
+    }
+    
+    #[test]
+    fn test_poseidon_wide_length_separation() {
+        // Trailing zeros change the absorbed length, so they change the output
+        let a = poseidon_wide(&[M31::from(1), M31::from(2)]);
+        let b = poseidon_wide(&[M31::from(1), M31::from(2), M31::zero()]);
+        assert_ne!(a, b);
+    }
+    
     #[test]
     fn test_u256_to_m31() {
         let value = U256::from(12345u64);
@@ -299,13 +376,20 @@ mod tests {
         let value = U256::from(0xFFFFFFFFFFFFFFFFu64);
         let m31_array = u256_to_m31_array(value);
         
-        // Should have 8 M31 elements (4 limbs * 2 chunks each)
-        assert_eq!(m31_array.len(), 8);
+        // Should have 9 M31 elements (256 bits in 30-bit chunks)
+        assert_eq!(m31_array.len(), 9);
         
         // All elements should be in M31 field
         for elem in m31_array {
             assert!(elem.value() < crate::constants::M31_PRIME);
         }
     }
+    
+    #[test]
+    fn test_u256_to_m31_array_max_recomposes() {
+        // The former 31-bit split dropped the top two bits of every 64-bit limb
+        let m31_array = u256_to_m31_array(U256::MAX);
+        assert_eq!(m31_array_to_u256(&m31_array), U256::MAX);
+    }
 }
 
@@ -0,0 +1,136 @@
+// 24-wide Poseidon2 parameters
+// A second parameter set for `poseidon2_permutation_n::<24>`, whose sponge could absorb 16 limbs
+// per permutation instead of the 8 a 16-wide state leaves next to its capacity. Compiled only
+// with the `poseidon2-24` feature: no circuit uses it, and until the parameters are reviewed the
+// 16-wide set stays the only one this crate claims 128-bit security for.
+//
+// Round constants come from the Grain LFSR of the Poseidon2 reference parameter script, for
+// p = 2^31 - 1, alpha = 5, t = 24, R_F = 8 and R_P = 22 (the script's round numbers for this
+// width), drawn as (R_F + R_P) rows of 24: the first and last four rows are the full rounds'
+// constants, and each partial round takes the first constant of its row.
+//
+// The internal matrix keeps the 16-wide diagonal's `mu_i = 2^{i+1} + 1` for i > 0. `mu_0 = 4`
+// does not satisfy the minimal polynomial condition at this width; 57 is the smallest `mu_0`
+// for which the characteristic polynomial of M_I^k is irreducible of degree 24 for k up to 48.
+
+use stwo_prover::core::fields::m31::BaseField;
+
+use super::poseidon2_stwo::{Poseidon2Parameters, Width, N_HALF_FULL_ROUNDS};
+
+pub const N_STATE_24: usize = 24;
+pub const N_PARTIAL_ROUNDS_24: usize = 22;
+
+// External round constants (8 rounds, 24 constants each)
+pub const EXTERNAL_ROUND_CONSTS_24: [[BaseField; N_STATE_24]; 2 * N_HALF_FULL_ROUNDS] = [
+    [BaseField::from_u32_unchecked(535476833), BaseField::from_u32_unchecked(1394754644), BaseField::from_u32_unchecked(1808710114), BaseField::from_u32_unchecked(1343879604), BaseField::from_u32_unchecked(1215456658), BaseField::from_u32_unchecked(723701928), BaseField::from_u32_unchecked(1632746489), BaseField::from_u32_unchecked(793201441), BaseField::from_u32_unchecked(783676215), BaseField::from_u32_unchecked(996433287), BaseField::from_u32_unchecked(155373296), BaseField::from_u32_unchecked(1696762962), BaseField::from_u32_unchecked(2030023439), BaseField::from_u32_unchecked(1294481352), BaseField::from_u32_unchecked(236432463), BaseField::from_u32_unchecked(89417350), BaseField::from_u32_unchecked(74188492), BaseField::from_u32_unchecked(189907894), BaseField::from_u32_unchecked(1452128099), BaseField::from_u32_unchecked(951268580), BaseField::from_u32_unchecked(1475718384), BaseField::from_u32_unchecked(433268241), BaseField::from_u32_unchecked(2010132299), BaseField::from_u32_unchecked(1820518840)],
+    [BaseField::from_u32_unchecked(1900593590), BaseField::from_u32_unchecked(134642805), BaseField::from_u32_unchecked(1188161737), BaseField::from_u32_unchecked(815217415), BaseField::from_u32_unchecked(543248187), BaseField::from_u32_unchecked(1547696209), BaseField::from_u32_unchecked(810562345), BaseField::from_u32_unchecked(1232267212), BaseField::from_u32_unchecked(47306940), BaseField::from_u32_unchecked(165003174), BaseField::from_u32_unchecked(158401728), BaseField::from_u32_unchecked(2074384948), BaseField::from_u32_unchecked(1242022652), BaseField::from_u32_unchecked(616689257), BaseField::from_u32_unchecked(682011457), BaseField::from_u32_unchecked(1569786016), BaseField::from_u32_unchecked(980963783), BaseField::from_u32_unchecked(500471289), BaseField::from_u32_unchecked(313827316), BaseField::from_u32_unchecked(2074957664), BaseField::from_u32_unchecked(1609393346), BaseField::from_u32_unchecked(1727383916), BaseField::from_u32_unchecked(2125446402), BaseField::from_u32_unchecked(968055099)],
+    [BaseField::from_u32_unchecked(1248148297), BaseField::from_u32_unchecked(1963531713), BaseField::from_u32_unchecked(1457817824), BaseField::from_u32_unchecked(386179925), BaseField::from_u32_unchecked(1952337907), BaseField::from_u32_unchecked(959377774), BaseField::from_u32_unchecked(836976607), BaseField::from_u32_unchecked(46304358), BaseField::from_u32_unchecked(918751290), BaseField::from_u32_unchecked(822399482), BaseField::from_u32_unchecked(1526975907), BaseField::from_u32_unchecked(1715593647), BaseField::from_u32_unchecked(930433878), BaseField::from_u32_unchecked(963599097), BaseField::from_u32_unchecked(437929498), BaseField::from_u32_unchecked(1754146546), BaseField::from_u32_unchecked(1870829444), BaseField::from_u32_unchecked(465116521), BaseField::from_u32_unchecked(1534623766), BaseField::from_u32_unchecked(1883940293), BaseField::from_u32_unchecked(25265820), BaseField::from_u32_unchecked(1092689494), BaseField::from_u32_unchecked(216531872), BaseField::from_u32_unchecked(599782304)],
+    [BaseField::from_u32_unchecked(402354276), BaseField::from_u32_unchecked(1194422816), BaseField::from_u32_unchecked(1989233491), BaseField::from_u32_unchecked(563878149), BaseField::from_u32_unchecked(375739688), BaseField::from_u32_unchecked(1307792708), BaseField::from_u32_unchecked(1619035237), BaseField::from_u32_unchecked(860944435), BaseField::from_u32_unchecked(62705095), BaseField::from_u32_unchecked(176625674), BaseField::from_u32_unchecked(24812021), BaseField::from_u32_unchecked(1855215398), BaseField::from_u32_unchecked(356799748), BaseField::from_u32_unchecked(2013606833), BaseField::from_u32_unchecked(999407416), BaseField::from_u32_unchecked(1070038705), BaseField::from_u32_unchecked(1263375886), BaseField::from_u32_unchecked(1020869934), BaseField::from_u32_unchecked(245688694), BaseField::from_u32_unchecked(491980492), BaseField::from_u32_unchecked(981123716), BaseField::from_u32_unchecked(247605556), BaseField::from_u32_unchecked(1301780243), BaseField::from_u32_unchecked(1369709669)],
+    [BaseField::from_u32_unchecked(1574078551), BaseField::from_u32_unchecked(1900987196), BaseField::from_u32_unchecked(471586858), BaseField::from_u32_unchecked(1352123449), BaseField::from_u32_unchecked(1853909129), BaseField::from_u32_unchecked(782939231), BaseField::from_u32_unchecked(1873354978), BaseField::from_u32_unchecked(1938379837), BaseField::from_u32_unchecked(325875436), BaseField::from_u32_unchecked(931909651), BaseField::from_u32_unchecked(1712878217), BaseField::from_u32_unchecked(1069294485), BaseField::from_u32_unchecked(1533143785), BaseField::from_u32_unchecked(776194829), BaseField::from_u32_unchecked(1156806948), BaseField::from_u32_unchecked(39528003), BaseField::from_u32_unchecked(980565598), BaseField::from_u32_unchecked(418463744), BaseField::from_u32_unchecked(2138817363), BaseField::from_u32_unchecked(565544788), BaseField::from_u32_unchecked(976044660), BaseField::from_u32_unchecked(1469321268), BaseField::from_u32_unchecked(1804823552), BaseField::from_u32_unchecked(902136240)],
+    [BaseField::from_u32_unchecked(576400159), BaseField::from_u32_unchecked(1627405571), BaseField::from_u32_unchecked(1811186542), BaseField::from_u32_unchecked(580706361), BaseField::from_u32_unchecked(327214977), BaseField::from_u32_unchecked(999856918), BaseField::from_u32_unchecked(1991764116), BaseField::from_u32_unchecked(1936663472), BaseField::from_u32_unchecked(966548230), BaseField::from_u32_unchecked(793452299), BaseField::from_u32_unchecked(1544027865), BaseField::from_u32_unchecked(439782858), BaseField::from_u32_unchecked(2022644005), BaseField::from_u32_unchecked(1714691401), BaseField::from_u32_unchecked(1758527178), BaseField::from_u32_unchecked(1765168888), BaseField::from_u32_unchecked(1130764926), BaseField::from_u32_unchecked(1340671541), BaseField::from_u32_unchecked(386842565), BaseField::from_u32_unchecked(2119776004), BaseField::from_u32_unchecked(1019140554), BaseField::from_u32_unchecked(1394568824), BaseField::from_u32_unchecked(1212402640), BaseField::from_u32_unchecked(1924120432)],
+    [BaseField::from_u32_unchecked(2065474675), BaseField::from_u32_unchecked(1505618160), BaseField::from_u32_unchecked(170282881), BaseField::from_u32_unchecked(1930756932), BaseField::from_u32_unchecked(1221829945), BaseField::from_u32_unchecked(762020653), BaseField::from_u32_unchecked(143802340), BaseField::from_u32_unchecked(1604627112), BaseField::from_u32_unchecked(479558998), BaseField::from_u32_unchecked(1704276861), BaseField::from_u32_unchecked(985937792), BaseField::from_u32_unchecked(548022105), BaseField::from_u32_unchecked(1701884125), BaseField::from_u32_unchecked(12124558), BaseField::from_u32_unchecked(972594673), BaseField::from_u32_unchecked(2053628023), BaseField::from_u32_unchecked(1772649285), BaseField::from_u32_unchecked(1247153276), BaseField::from_u32_unchecked(1809486525), BaseField::from_u32_unchecked(1191800460), BaseField::from_u32_unchecked(1630048770), BaseField::from_u32_unchecked(1875973749), BaseField::from_u32_unchecked(1912394651), BaseField::from_u32_unchecked(657405196)],
+    [BaseField::from_u32_unchecked(1445845064), BaseField::from_u32_unchecked(2002136989), BaseField::from_u32_unchecked(632112793), BaseField::from_u32_unchecked(1039943744), BaseField::from_u32_unchecked(324690743), BaseField::from_u32_unchecked(1373257768), BaseField::from_u32_unchecked(1694678056), BaseField::from_u32_unchecked(118547456), BaseField::from_u32_unchecked(1576226734), BaseField::from_u32_unchecked(838183894), BaseField::from_u32_unchecked(867179246), BaseField::from_u32_unchecked(803097425), BaseField::from_u32_unchecked(1236364553), BaseField::from_u32_unchecked(1604651422), BaseField::from_u32_unchecked(1968358699), BaseField::from_u32_unchecked(874588639), BaseField::from_u32_unchecked(1083750681), BaseField::from_u32_unchecked(1032598968), BaseField::from_u32_unchecked(966505295), BaseField::from_u32_unchecked(656481798), BaseField::from_u32_unchecked(711802121), BaseField::from_u32_unchecked(668743357), BaseField::from_u32_unchecked(1465281667), BaseField::from_u32_unchecked(1273641865)],
+];
+
+// Internal round constants (22 partial rounds)
+pub const INTERNAL_ROUND_CONSTS_24: [BaseField; N_PARTIAL_ROUNDS_24] = [
+    BaseField::from_u32_unchecked(578251281),
+    BaseField::from_u32_unchecked(2058797298),
+    BaseField::from_u32_unchecked(447036254),
+    BaseField::from_u32_unchecked(1720418353),
+    BaseField::from_u32_unchecked(1591561622),
+    BaseField::from_u32_unchecked(320424859),
+    BaseField::from_u32_unchecked(822310460),
+    BaseField::from_u32_unchecked(242692004),
+    BaseField::from_u32_unchecked(1566541706),
+    BaseField::from_u32_unchecked(1654181032),
+    BaseField::from_u32_unchecked(1033099341),
+    BaseField::from_u32_unchecked(1103575363),
+    BaseField::from_u32_unchecked(1468770779),
+    BaseField::from_u32_unchecked(2041646107),
+    BaseField::from_u32_unchecked(654318738),
+    BaseField::from_u32_unchecked(365404011),
+    BaseField::from_u32_unchecked(656640370),
+    BaseField::from_u32_unchecked(544299247),
+    BaseField::from_u32_unchecked(626659562),
+    BaseField::from_u32_unchecked(1722461951),
+    BaseField::from_u32_unchecked(534627369),
+    BaseField::from_u32_unchecked(538444779),
+];
+
+/// Multipliers `mu_i` of the 24-wide internal round matrix: `mu_0 = 57`, `mu_i = 2^{i+1} + 1`
+pub const INTERNAL_MATRIX_DIAGONAL_24: [BaseField; N_STATE_24] = {
+    let mut diagonal = [BaseField::from_u32_unchecked(57); N_STATE_24];
+    let mut i = 1;
+    while i < N_STATE_24 {
+        diagonal[i] = BaseField::from_u32_unchecked((1 << (i + 1)) + 1);
+        i += 1;
+    }
+    diagonal
+};
+
+/// Keccak256 of the 24-wide parameters, laid out as `poseidon2_stwo::PARAMETERS_FINGERPRINT`
+pub const PARAMETERS_FINGERPRINT_24: [u8; 32] = [
+    0x37, 0x34, 0x5a, 0x4c, 0x8a, 0xad, 0x0a, 0xf9, 0xb9, 0x03, 0x18, 0x08, 0x6c, 0x03, 0x93, 0x8a,
+    0xf4, 0x09, 0x8d, 0xe4, 0x24, 0x89, 0x13, 0xc8, 0xa7, 0x1e, 0x7b, 0xb8, 0x2a, 0xb7, 0x2e, 0x6f,
+];
+
+impl Poseidon2Parameters<N_STATE_24> for Width<N_STATE_24> {
+    const EXTERNAL_ROUND_CONSTS: [[BaseField; N_STATE_24]; 2 * N_HALF_FULL_ROUNDS] = EXTERNAL_ROUND_CONSTS_24;
+    const INTERNAL_ROUND_CONSTS: &'static [BaseField] = &INTERNAL_ROUND_CONSTS_24;
+    const INTERNAL_MATRIX_DIAGONAL: [BaseField; N_STATE_24] = INTERNAL_MATRIX_DIAGONAL_24;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::poseidon2_stwo::{
+        poseidon2_permutation, poseidon2_permutation_n, INTERNAL_MATRIX_DIAGONAL, N_STATE,
+    };
+
+    #[test]
+    fn test_parameters_fingerprint() {
+        let values = EXTERNAL_ROUND_CONSTS_24
+            .iter()
+            .flatten()
+            .chain(&INTERNAL_ROUND_CONSTS_24)
+            .chain(&INTERNAL_MATRIX_DIAGONAL_24);
+        let bytes: Vec<u8> = values.flat_map(|value| value.0.to_be_bytes()).collect();
+        assert_eq!(bytes.len(), 4 * (2 * N_HALF_FULL_ROUNDS * N_STATE_24 + N_PARTIAL_ROUNDS_24 + N_STATE_24));
+        assert_eq!(crate::utils::keccak::keccak256(&bytes), PARAMETERS_FINGERPRINT_24);
+    }
+
+    #[test]
+    fn test_permutation_vectors() {
+        let zero = [
+            1862266280, 1138152749, 551339451, 480166270, 252625214, 1529748684, 950729877, 1243989434,
+            1254964381, 392850192, 1413804469, 1907816644, 1591485587, 2107737312, 2123056844, 325659231,
+            760614670, 1469506626, 496909982, 515957733, 1737611984, 388726762, 1821163395, 1843296215,
+        ];
+        let counting = [
+            1259118494, 784997987, 285389781, 995357370, 1478327748, 1378799971, 618855783, 2122872232,
+            980007080, 1133519523, 577962046, 1357793145, 344301253, 401859058, 88382679, 899965550,
+            1456062086, 581874319, 183561389, 292849777, 1623594665, 2002640388, 611707121, 674730527,
+        ];
+        let zero_state = [BaseField::from_u32_unchecked(0); N_STATE_24];
+        let counting_state = std::array::from_fn(|i| BaseField::from_u32_unchecked(i as u32));
+        assert_eq!(poseidon2_permutation_n::<24>(zero_state).map(|value| value.0), zero);
+        assert_eq!(poseidon2_permutation_n::<24>(counting_state).map(|value| value.0), counting);
+    }
+
+    #[test]
+    fn test_widths_are_independent() {
+        // The first 16 cells of a 24-wide permutation have nothing to do with the 16-wide one
+        let wide = poseidon2_permutation_n::<24>([BaseField::from_u32_unchecked(0); N_STATE_24]);
+        let narrow = poseidon2_permutation([BaseField::from_u32_unchecked(0); N_STATE]);
+        assert_ne!(wide[..N_STATE], narrow[..]);
+    }
+
+    #[test]
+    fn test_internal_matrix_diagonal() {
+        // The 16-wide multipliers, continued, with its own first one
+        assert_eq!(INTERNAL_MATRIX_DIAGONAL_24[0].0, 57);
+        assert_eq!(INTERNAL_MATRIX_DIAGONAL_24[1..N_STATE], INTERNAL_MATRIX_DIAGONAL[1..]);
+        assert_eq!(INTERNAL_MATRIX_DIAGONAL_24[N_STATE_24 - 1].0, (1 << 24) + 1);
+    }
+}
@@ -1,9 +1,17 @@
 // Poseidon2 implementation using stwo's primitives
 // Adapted from: https://github.com/starkware-libs/stwo/blob/main/crates/examples/src/poseidon/mod.rs
 // Paper: https://eprint.iacr.org/2023/323.pdf Section 5
+//
+// The permutation is generic over its state width through `Poseidon2Parameters`. Every proof
+// uses the 16-wide parameters below; a 24-wide set lives in `poseidon2_24` behind the
+// `poseidon2-24` feature until its parameters are validated.
 
+use std::collections::{BTreeMap, HashMap};
 use std::ops::{Add, AddAssign, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::prover::backend::simd::m31::PackedBaseField;
 
 // Poseidon2 parameters for M31 field (2^31 - 1)
 // Generated using HorizenLabs/poseidon2 parameter generation script
@@ -12,12 +20,12 @@ use stwo_prover::core::fields::m31::BaseField;
 // State size: t = 16
 // Alpha (S-box): 5
 pub const N_STATE: usize = 16;
-const N_PARTIAL_ROUNDS: usize = 26;  // Optimized for M31
-const N_HALF_FULL_ROUNDS: usize = 4; // Total R_F = 8
+pub const N_PARTIAL_ROUNDS: usize = 26;  // Optimized for M31
+pub const N_HALF_FULL_ROUNDS: usize = 4; // Total R_F = 8
 
 // External round constants (8 rounds, 16 constants each)
 // Generated using Grain LFSR as specified in Poseidon2 paper
-const EXTERNAL_ROUND_CONSTS: [[BaseField; N_STATE]; 2 * N_HALF_FULL_ROUNDS] = [
+pub const EXTERNAL_ROUND_CONSTS: [[BaseField; N_STATE]; 2 * N_HALF_FULL_ROUNDS] = [
     [BaseField::from_u32_unchecked(1323103696), BaseField::from_u32_unchecked(32820862), BaseField::from_u32_unchecked(1980729053), BaseField::from_u32_unchecked(317622338), BaseField::from_u32_unchecked(50263984), BaseField::from_u32_unchecked(427303566), BaseField::from_u32_unchecked(476470815), BaseField::from_u32_unchecked(1873216103), BaseField::from_u32_unchecked(1013492029), BaseField::from_u32_unchecked(1876243821), BaseField::from_u32_unchecked(1423021976), BaseField::from_u32_unchecked(1034880506), BaseField::from_u32_unchecked(255516447), BaseField::from_u32_unchecked(1751710500), BaseField::from_u32_unchecked(1772458188), BaseField::from_u32_unchecked(1905707724)],
     [BaseField::from_u32_unchecked(2146357039), BaseField::from_u32_unchecked(300477280), BaseField::from_u32_unchecked(1303317487), BaseField::from_u32_unchecked(1896371959), BaseField::from_u32_unchecked(1077911909), BaseField::from_u32_unchecked(1623307068), BaseField::from_u32_unchecked(1716928924), BaseField::from_u32_unchecked(1899262763), BaseField::from_u32_unchecked(561896200), BaseField::from_u32_unchecked(2147059615), BaseField::from_u32_unchecked(262690381), BaseField::from_u32_unchecked(2144164168), BaseField::from_u32_unchecked(1245079228), BaseField::from_u32_unchecked(715189338), BaseField::from_u32_unchecked(588134996), BaseField::from_u32_unchecked(1875961624)],
     [BaseField::from_u32_unchecked(727635773), BaseField::from_u32_unchecked(1044882765), BaseField::from_u32_unchecked(1256399791), BaseField::from_u32_unchecked(170160872), BaseField::from_u32_unchecked(776522156), BaseField::from_u32_unchecked(1947778522), BaseField::from_u32_unchecked(1540706240), BaseField::from_u32_unchecked(1368992253), BaseField::from_u32_unchecked(412370089), BaseField::from_u32_unchecked(1562388559), BaseField::from_u32_unchecked(1199766382), BaseField::from_u32_unchecked(257896456), BaseField::from_u32_unchecked(931242721), BaseField::from_u32_unchecked(266356162), BaseField::from_u32_unchecked(1661329514), BaseField::from_u32_unchecked(1750311239)],
@@ -29,7 +37,7 @@ const EXTERNAL_ROUND_CONSTS: [[BaseField; N_STATE]; 2 * N_HALF_FULL_ROUNDS] = [
 ];
 
 // Internal round constants (26 partial rounds)
-const INTERNAL_ROUND_CONSTS: [BaseField; N_PARTIAL_ROUNDS] = [
+pub const INTERNAL_ROUND_CONSTS: [BaseField; N_PARTIAL_ROUNDS] = [
     BaseField::from_u32_unchecked(2059409277),
     BaseField::from_u32_unchecked(1595326017),
     BaseField::from_u32_unchecked(729019563),
@@ -60,9 +68,9 @@ const INTERNAL_ROUND_CONSTS: [BaseField; N_PARTIAL_ROUNDS] = [
 
 /// S-box: x^5 (standard for Poseidon)
 #[inline(always)]
-fn pow5(x: BaseField) -> BaseField {
-    let x2 = x * x;
-    let x4 = x2 * x2;
+pub(crate) fn pow5<F: Clone + Mul<F, Output = F>>(x: F) -> F {
+    let x2 = x.clone() * x.clone();
+    let x4 = x2.clone() * x2;
     x4 * x
 }
 
@@ -86,12 +94,14 @@ where
 }
 
 /// Applies the external round matrix (Poseidon2 paper Section 5.1 and Appendix B)
-fn apply_external_round_matrix<F>(state: &mut [F; 16])
+///
+/// `N` is a multiple of 4: the matrix is `circ(2M4, M4, ..., M4)` over `N / 4` blocks.
+pub(crate) fn apply_external_round_matrix<F, const N: usize>(state: &mut [F; N])
 where
     F: Clone + AddAssign<F> + Add<F, Output = F> + Sub<F, Output = F> + Mul<BaseField, Output = F>,
 {
-    // Applies circ(2M4, M4, M4, M4)
-    for i in 0..4 {
+    // Applies circ(2M4, M4, ..., M4)
+    for i in 0..N / 4 {
         [
             state[4 * i],
             state[4 * i + 1],
@@ -105,14 +115,60 @@ where
         ]);
     }
     for j in 0..4 {
-        let s =
-            state[j].clone() + state[j + 4].clone() + state[j + 8].clone() + state[j + 12].clone();
-        for i in 0..4 {
+        let s = (1..N / 4).fold(state[j].clone(), |acc, i| acc + state[4 * i + j].clone());
+        for i in 0..N / 4 {
             state[4 * i + j] += s.clone();
         }
     }
 }
 
+/// Multipliers `mu_i` of the internal round matrix, which maps `s_i` to `mu_i * s_i + sum(s)`
+///
+/// `mu_i = 2^{i+1} + 1`, except `mu_0 = 4`; see `apply_internal_round_matrix`.
+pub const INTERNAL_MATRIX_DIAGONAL: [BaseField; N_STATE] = {
+    let mut diagonal = [BaseField::from_u32_unchecked(4); N_STATE];
+    let mut i = 1;
+    while i < N_STATE {
+        diagonal[i] = BaseField::from_u32_unchecked((1 << (i + 1)) + 1);
+        i += 1;
+    }
+    diagonal
+};
+
+/// Round constants and internal matrix of the Poseidon2 permutation over `N` cells
+///
+/// Implemented on `Width<N>` for every supported width. All widths share the S-box, the external
+/// matrix and `N_HALF_FULL_ROUNDS`; only 16, the width every proof uses, is claimed secure.
+pub trait Poseidon2Parameters<const N: usize> {
+    /// Round constants of the full rounds, first half then second half
+    const EXTERNAL_ROUND_CONSTS: [[BaseField; N]; 2 * N_HALF_FULL_ROUNDS];
+
+    /// Round constant of each partial round; their number is the number of partial rounds
+    const INTERNAL_ROUND_CONSTS: &'static [BaseField];
+
+    /// Multipliers `mu_i` of the internal round matrix
+    const INTERNAL_MATRIX_DIAGONAL: [BaseField; N];
+}
+
+/// A Poseidon2 state width, the type `Poseidon2Parameters` is implemented on
+pub struct Width<const N: usize>;
+
+impl Poseidon2Parameters<N_STATE> for Width<N_STATE> {
+    const EXTERNAL_ROUND_CONSTS: [[BaseField; N_STATE]; 2 * N_HALF_FULL_ROUNDS] = EXTERNAL_ROUND_CONSTS;
+    const INTERNAL_ROUND_CONSTS: &'static [BaseField] = &INTERNAL_ROUND_CONSTS;
+    const INTERNAL_MATRIX_DIAGONAL: [BaseField; N_STATE] = INTERNAL_MATRIX_DIAGONAL;
+}
+
+/// Keccak256 of the parameters: `EXTERNAL_ROUND_CONSTS` row by row, `INTERNAL_ROUND_CONSTS`,
+/// then `INTERNAL_MATRIX_DIAGONAL`, each value a big-endian `u32`
+///
+/// Any change to a constant changes every hash, and with it every proof; this names the
+/// parameterization a proof was made with. `test_parameters_fingerprint` recomputes it.
+pub const PARAMETERS_FINGERPRINT: [u8; 32] = [
+    0x1e, 0x8d, 0x9a, 0x57, 0xc6, 0xef, 0x48, 0x50, 0x07, 0xe1, 0x31, 0xf2, 0x34, 0x19, 0xb0, 0xe4,
+    0xa5, 0xae, 0x49, 0xbc, 0xd5, 0x80, 0x63, 0xc2, 0xfd, 0x81, 0x3d, 0x0a, 0x2f, 0x22, 0x84, 0xd6,
+];
+
 /// Applies the internal round matrix (Poseidon2 paper Section 5.2)
 /// 
 /// SECURITY FIX: Modified to satisfy minimal polynomial condition
@@ -128,13 +184,20 @@ where
 /// - Satisfies minimal polynomial condition (degree = NUM_CELLS and irreducible)
 /// - Provides proper security guarantees
 /// 
+/// stwo's example instead multiplies `s_i` by `2^{i+1}` before adding the sum, so the two
+/// permutations differ here and nowhere else in their structure; `tests/stwo_compat.rs` pins
+/// the difference.
+/// 
 /// References:
 /// - Poseidon2 paper Section 5.3: https://eprint.iacr.org/2023/323.pdf
 /// - Stwo issue discussion: https://github.com/starkware-libs/stwo/issues/ (security fix for internal matrix)
 /// - Mathematical verification: See Sage code validating minpoly condition
-fn apply_internal_round_matrix<F>(state: &mut [F; 16])
+///
+/// Other widths take their own diagonal, `Poseidon2Parameters::INTERNAL_MATRIX_DIAGONAL`.
+pub(crate) fn apply_internal_round_matrix<F, const N: usize>(state: &mut [F; N])
 where
     F: Clone + AddAssign<F> + Add<F, Output = F> + Sub<F, Output = F> + Mul<BaseField, Output = F>,
+    Width<N>: Poseidon2Parameters<N>,
 {
     // Sum of all state elements
     let sum = state[1..]
@@ -145,53 +208,62 @@ where
     // Apply: new_state[i] = mu_i * state[i] + sum
     // where mu_0 = 4 (special case for minpoly condition)
     //       mu_i = 2^{i+1} + 1 for i > 0
-    state.iter_mut().enumerate().for_each(|(i, s)| {
-        let multiplier = if i == 0 {
-            // mu_0 = 4 (changed from 3 to satisfy minpoly condition)
-            BaseField::from_u32_unchecked(4)
-        } else {
-            // mu_i = 2^{i+1} + 1
-            BaseField::from_u32_unchecked((1 << (i + 1)) + 1)
-        };
+    let diagonal = <Width<N> as Poseidon2Parameters<N>>::INTERNAL_MATRIX_DIAGONAL;
+    state.iter_mut().zip(diagonal).for_each(|(s, multiplier)| {
         *s = s.clone() * multiplier + sum.clone();
     });
 }
 
-/// Complete Poseidon2 permutation for state size 16 (in-place)
+/// Complete Poseidon2 permutation for state size `N` (in-place)
 /// This follows the exact structure from stwo's implementation
-fn poseidon2_permutation_inplace(state: &mut [BaseField; N_STATE]) {
+///
+/// Generic so the same rounds run on `BaseField` and, `N_LANES` states at a time, on
+/// `PackedBaseField`.
+fn poseidon2_permutation_inplace<F, const N: usize>(state: &mut [F; N])
+where
+    F: Clone
+        + From<BaseField>
+        + AddAssign<F>
+        + Add<F, Output = F>
+        + Sub<F, Output = F>
+        + Mul<F, Output = F>
+        + Mul<BaseField, Output = F>,
+    Width<N>: Poseidon2Parameters<N>,
+{
+    let external_round_consts = <Width<N> as Poseidon2Parameters<N>>::EXTERNAL_ROUND_CONSTS;
+
     // 4 full rounds (first half)
     for round in 0..N_HALF_FULL_ROUNDS {
         // Add round constants
-        for i in 0..N_STATE {
-            state[i] += EXTERNAL_ROUND_CONSTS[round][i];
+        for i in 0..N {
+            state[i] += external_round_consts[round][i].into();
         }
         // Apply MDS matrix
         apply_external_round_matrix(state);
         // Apply S-box
-        for i in 0..N_STATE {
-            state[i] = pow5(state[i]);
+        for i in 0..N {
+            state[i] = pow5(state[i].clone());
         }
     }
 
     // Partial rounds
-    for round in 0..N_PARTIAL_ROUNDS {
-        state[0] += INTERNAL_ROUND_CONSTS[round];
+    for &round_const in <Width<N> as Poseidon2Parameters<N>>::INTERNAL_ROUND_CONSTS {
+        state[0] += round_const.into();
         apply_internal_round_matrix(state);
-        state[0] = pow5(state[0]);
+        state[0] = pow5(state[0].clone());
     }
 
     // 4 full rounds (second half)
     for round in 0..N_HALF_FULL_ROUNDS {
         // Add round constants
-        for i in 0..N_STATE {
-            state[i] += EXTERNAL_ROUND_CONSTS[round + N_HALF_FULL_ROUNDS][i];
+        for i in 0..N {
+            state[i] += external_round_consts[round + N_HALF_FULL_ROUNDS][i].into();
         }
         // Apply MDS matrix
         apply_external_round_matrix(state);
         // Apply S-box
-        for i in 0..N_STATE {
-            state[i] = pow5(state[i]);
+        for i in 0..N {
+            state[i] = pow5(state[i].clone());
         }
     }
 }
@@ -204,6 +276,144 @@ pub fn poseidon2_permutation(state: [BaseField; N_STATE]) -> [BaseField; N_STATE
     result
 }
 
+/// Poseidon2 permutation over `N` cells, with the parameters of `Width<N>`
+///
+/// `poseidon2_permutation_n::<16>` is `poseidon2_permutation`. Other widths are not used by any
+/// circuit; 24 needs the `poseidon2-24` feature.
+pub fn poseidon2_permutation_n<const N: usize>(state: [BaseField; N]) -> [BaseField; N]
+where
+    Width<N>: Poseidon2Parameters<N>,
+{
+    let mut result = state;
+    poseidon2_permutation_inplace(&mut result);
+    result
+}
+
+/// A Poseidon2 permutation over the full state
+///
+/// The witness circuits take one so `consistency` tests can substitute a broken stand-in.
+pub type Permutation = fn([BaseField; N_STATE]) -> [BaseField; N_STATE];
+
+/// `permutation` of `preimage` zero-padded to the full state, the way the AIR traces lay out
+/// their Poseidon2 inputs
+pub(crate) fn permute_padded(
+    permutation: Permutation,
+    preimage: &[crate::field::M31],
+) -> [crate::field::M31; N_STATE] {
+    assert!(preimage.len() <= N_STATE, "preimage of {} elements exceeds the state", preimage.len());
+    let mut state = [BaseField::from_u32_unchecked(0); N_STATE];
+    for (cell, value) in state.iter_mut().zip(preimage) {
+        *cell = custom_m31_to_basefield(*value);
+    }
+    permutation(state).map(basefield_to_custom_m31)
+}
+
+/// Poseidon2 permutation of `N_LANES` independent states, one per SIMD lane
+///
+/// Lane `i` of the result is `poseidon2_permutation` of lane `i` of the input.
+pub fn poseidon2_permutation_packed(state: [PackedBaseField; N_STATE]) -> [PackedBaseField; N_STATE] {
+    let mut result = state;
+    poseidon2_permutation_inplace(&mut result);
+    result
+}
+
+/// Permutations a `PermutationCache` holds unless told otherwise
+pub const DEFAULT_PERMUTATION_CACHE_CAPACITY: usize = 4096;
+
+/// Round states of recently traced permutations, keyed by input state
+///
+/// A traced permutation is every state the trace commits, about ten times the work of the
+/// permutation alone. Batches repeat input states (every instance hashes the same block root,
+/// padding rows permute the zero state), so trace generation can look them up here instead.
+/// Holds at most `capacity` permutations and evicts the least recently used.
+pub struct PermutationCache {
+    capacity: usize,
+    /// Round states and the tick of their last use, by input state
+    entries: HashMap<[u32; N_STATE], (Vec<BaseField>, u64)>,
+    /// Input states by the tick of their last use, oldest first
+    recency: BTreeMap<u64, [u32; N_STATE]>,
+    tick: u64,
+    stats: PermutationCacheStats,
+}
+
+impl PermutationCache {
+    /// # Panics
+    /// If `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a permutation cache needs room for one permutation");
+        Self { capacity, entries: HashMap::new(), recency: BTreeMap::new(), tick: 0, stats: PermutationCacheStats::default() }
+    }
+
+    /// Round states of `input`, traced by `trace` unless cached
+    pub fn get_or_trace(
+        &mut self,
+        input: [BaseField; N_STATE],
+        trace: impl FnOnce([BaseField; N_STATE]) -> Vec<BaseField>,
+    ) -> &[BaseField] {
+        let key = input.map(|value| value.0);
+        self.tick += 1;
+        match self.entries.get_mut(&key) {
+            Some((_, last_used)) => {
+                self.stats.hits += 1;
+                self.recency.remove(last_used);
+                *last_used = self.tick;
+            }
+            None => {
+                self.stats.misses += 1;
+                if self.entries.len() == self.capacity {
+                    let (_, oldest) = self.recency.pop_first().expect("a full cache has a least recent entry");
+                    self.entries.remove(&oldest);
+                }
+                self.entries.insert(key, (trace(input), self.tick));
+            }
+        }
+        self.recency.insert(self.tick, key);
+        &self.entries[&key].0
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Hits and misses since the cache was created
+    pub fn stats(&self) -> PermutationCacheStats {
+        self.stats
+    }
+}
+
+impl Default for PermutationCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PERMUTATION_CACHE_CAPACITY)
+    }
+}
+
+/// Lookups answered by a `PermutationCache`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermutationCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl PermutationCacheStats {
+    /// Share of lookups answered from the cache, `None` before the first
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+impl std::ops::Add for PermutationCacheStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self { hits: self.hits + other.hits, misses: self.misses + other.misses }
+    }
+}
+
 /// Generic Poseidon2 hash with domain separation
 fn poseidon2_hash_n_with_domain(inputs: &[BaseField], domain_id: u32) -> BaseField {
     let mut state = [BaseField::from_u32_unchecked(0); N_STATE];
@@ -233,8 +443,10 @@ pub fn poseidon2_hash_3(inputs: [BaseField; 3]) -> BaseField {
 }
 
 /// Compute critical states for Poseidon2 verification
-/// Returns: (initial_state, after_first_round, final_result)
-pub fn poseidon2_critical_states(input_state: [BaseField; N_STATE]) -> ([BaseField; N_STATE], [BaseField; N_STATE], BaseField) {
+/// Returns: (initial_state, after_first_round, final_state)
+///
+/// Callers take `final_state[0]` for a single-limb hash or a prefix of it for a wide output.
+pub fn poseidon2_critical_states(input_state: [BaseField; N_STATE]) -> ([BaseField; N_STATE], [BaseField; N_STATE], [BaseField; N_STATE]) {
     let mut state = input_state;
 
     // Save initial state
@@ -255,11 +467,10 @@ pub fn poseidon2_critical_states(input_state: [BaseField; N_STATE]) -> ([BaseFie
     // Save state after first round
     let after_first_round = state;
 
-    // Complete the permutation to get final result
+    // Complete the permutation to get the final state
     poseidon2_permutation_inplace(&mut state);
-    let final_result = state[0];
 
-    (initial_state, after_first_round, final_result)
+    (initial_state, after_first_round, state)
 }
 
 /// Poseidon2 hash for 4 inputs with domain separation
@@ -321,10 +532,127 @@ pub fn poseidon4(inputs: [crate::field::M31; 4]) -> crate::field::M31 {
     basefield_to_custom_m31(result)
 }
 
+/// Bits of a byte string packed into each `poseidon2_hash_bytes` limb, one below M31's 31
+pub const BYTES_LIMB_BITS: u32 = 30;
+
+/// Limbs `poseidon2_hash_bytes` absorbs per permutation; the other half of the state is capacity
+pub const BYTES_HASH_RATE: usize = 8;
+
+/// Longest byte string `poseidon2_hash_bytes` takes, so its length prefix fits one limb
+pub const MAX_HASHED_BYTES: usize = (1 << BYTES_LIMB_BITS) - 1;
+
+/// Capacity tag of `poseidon2_hash_bytes` ("byte"), keeping its states apart from the fixed-width hashes'
+const BYTES_HASH_DOMAIN: u32 = 0x6279_7465;
+
+/// Canonical Poseidon2 hash of a byte string
+///
+/// Byte strings committed to anywhere in this crate (extra data, addresses, memos) go through
+/// this instead of an ad-hoc reduction such as reading the first four bytes as a `u32`. The
+/// bytes are cut into 30-bit limbs by `pack_bytes`, so no limb wraps modulo M31. The sponge then
+/// absorbs `[len, limbs..]`, zero-padded to whole blocks, `BYTES_HASH_RATE` limbs per
+/// permutation, into a state whose capacity holds a domain tag. The length prefix keeps trailing
+/// zero bytes and the padding from colliding.
+///
+/// Panics if `bytes` is longer than `MAX_HASHED_BYTES`.
+pub fn poseidon2_hash_bytes(bytes: &[u8]) -> crate::field::M31 {
+    assert!(bytes.len() <= MAX_HASHED_BYTES, "poseidon2_hash_bytes takes at most {MAX_HASHED_BYTES} bytes");
+    let mut message = Vec::with_capacity(1 + (bytes.len() * 8).div_ceil(BYTES_LIMB_BITS as usize));
+    message.push(BaseField::from_u32_unchecked(bytes.len() as u32));
+    message.extend(pack_bytes(bytes));
+
+    let mut state = [BaseField::from_u32_unchecked(0); N_STATE];
+    state[BYTES_HASH_RATE] = BaseField::from_u32_unchecked(BYTES_HASH_DOMAIN);
+    for block in message.chunks(BYTES_HASH_RATE) {
+        for (cell, &limb) in state.iter_mut().zip(block) {
+            *cell += limb;
+        }
+        poseidon2_permutation_inplace(&mut state);
+    }
+    basefield_to_custom_m31(state[0])
+}
+
+/// `bytes` as a little-endian bit string cut into `BYTES_LIMB_BITS`-bit limbs, the last one
+/// holding what is left
+pub fn pack_bytes(bytes: &[u8]) -> Vec<BaseField> {
+    let mask = (1u64 << BYTES_LIMB_BITS) - 1;
+    let mut limbs = Vec::with_capacity((bytes.len() * 8).div_ceil(BYTES_LIMB_BITS as usize));
+    let (mut acc, mut bits) = (0u64, 0u32);
+    for &byte in bytes {
+        acc |= (byte as u64) << bits;
+        bits += 8;
+        if bits >= BYTES_LIMB_BITS {
+            limbs.push(BaseField::from_u32_unchecked((acc & mask) as u32));
+            acc >>= BYTES_LIMB_BITS;
+            bits -= BYTES_LIMB_BITS;
+        }
+    }
+    if bits > 0 {
+        limbs.push(BaseField::from_u32_unchecked(acc as u32));
+    }
+    limbs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_permutation_cache_evicts_least_recent() {
+        let state = |seed: u32| std::array::from_fn(|i| BaseField::from_u32_unchecked(seed + i as u32));
+        let traced = |input: [BaseField; N_STATE]| poseidon2_permutation(input).to_vec();
+        let mut cache = PermutationCache::new(2);
+
+        assert_eq!(cache.get_or_trace(state(1), traced), poseidon2_permutation(state(1)));
+        cache.get_or_trace(state(2), traced);
+        // A hit must not re-trace, and makes state 1 the most recent
+        cache.get_or_trace(state(1), |_| unreachable!("state 1 is cached"));
+        cache.get_or_trace(state(3), traced);
+        assert_eq!(cache.len(), 2);
+        cache.get_or_trace(state(1), |_| unreachable!("state 1 outlives state 2"));
+        assert_eq!(cache.get_or_trace(state(2), traced), poseidon2_permutation(state(2)));
+
+        assert_eq!(cache.stats(), PermutationCacheStats { hits: 2, misses: 4 });
+        assert_eq!(cache.stats().hit_rate(), Some(2.0 / 6.0));
+        assert_eq!(PermutationCacheStats::default().hit_rate(), None);
+    }
+
+    #[test]
+    fn test_packed_permutation_matches_scalar() {
+        use stwo_prover::prover::backend::simd::m31::N_LANES;
+
+        let states: [[BaseField; N_STATE]; N_LANES] = std::array::from_fn(|lane| {
+            std::array::from_fn(|i| BaseField::from_u32_unchecked((lane * N_STATE + i) as u32))
+        });
+        let packed = poseidon2_permutation_packed(std::array::from_fn(|i| {
+            PackedBaseField::from_array(std::array::from_fn(|lane| states[lane][i]))
+        }));
+        for (lane, state) in states.iter().enumerate() {
+            let expected = poseidon2_permutation(*state);
+            for i in 0..N_STATE {
+                assert_eq!(packed[i].to_array()[lane], expected[i], "lane {} word {}", lane, i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_permutation_n_16_is_unchanged() {
+        // The zero state vector of tests/fixtures/test_vectors.json
+        let zero = [
+            1545871856u32, 856767185, 1290124379, 1219560306, 1723423830, 1588836746, 1030999679, 1052331250,
+            1429901583, 228089154, 391465450, 977482191, 848226086, 1807108162, 1952899746, 1446231151,
+        ];
+        let zero_state = [BaseField::from_u32_unchecked(0); N_STATE];
+        assert_eq!(poseidon2_permutation_n::<16>(zero_state).map(|value| value.0), zero);
+
+        for seed in [1u32, 1 << 20, crate::constants::M31_PRIME - 1] {
+            let state = std::array::from_fn(|i| {
+                BaseField::from_u32_unchecked((seed + 7919 * i as u32) % crate::constants::M31_PRIME)
+            });
+            assert_eq!(poseidon2_permutation_n::<16>(state), poseidon2_permutation(state));
+        }
+        assert_eq!(<Width<16> as Poseidon2Parameters<16>>::INTERNAL_ROUND_CONSTS.len(), N_PARTIAL_ROUNDS);
+    }
+
     #[test]
     fn test_poseidon2_deterministic() {
         let inputs = [
@@ -374,6 +702,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_internal_matrix_diagonal_constant() {
+        let expected = [4u32, 5, 9, 17, 33, 65, 129, 257, 513, 1025, 2049, 4097, 8193, 16385, 32769, 65537];
+        assert_eq!(INTERNAL_MATRIX_DIAGONAL.map(|mu| mu.0), expected);
+    }
+
+    #[test]
+    fn test_parameters_fingerprint() {
+        let values = EXTERNAL_ROUND_CONSTS
+            .iter()
+            .flatten()
+            .chain(&INTERNAL_ROUND_CONSTS)
+            .chain(&INTERNAL_MATRIX_DIAGONAL);
+        let bytes: Vec<u8> = values.flat_map(|value| value.0.to_be_bytes()).collect();
+        assert_eq!(bytes.len(), 4 * (2 * N_HALF_FULL_ROUNDS * N_STATE + N_PARTIAL_ROUNDS + N_STATE));
+        assert_eq!(crate::utils::keccak::keccak256(&bytes), PARAMETERS_FINGERPRINT);
+    }
+
     #[test]
     fn test_internal_matrix_minimal_polynomial_condition() {
         let mut prev = 0u32;
@@ -429,5 +775,47 @@ mod tests {
             "Different domain IDs ensure different outputs"
         );
     }
-}
 
+    #[test]
+    fn test_pack_bytes_uses_30_bit_limbs() {
+        // 32 bytes are 256 bits: eight full limbs and 16 bits left over
+        let limbs = pack_bytes(&[0xff; 32]);
+        assert_eq!(limbs.len(), 9);
+        assert!(limbs[..8].iter().all(|limb| limb.0 == (1 << 30) - 1));
+        assert_eq!(limbs[8].0, 0xffff);
+
+        // Little-endian: the fourth byte's top two bits start the second limb
+        let limbs = pack_bytes(&[0x01, 0x02, 0x03, 0xc4, 0x05]);
+        assert_eq!(limbs.iter().map(|limb| limb.0).collect::<Vec<_>>(), [0x0403_0201, 0x17]);
+        assert!(pack_bytes(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_poseidon2_hash_bytes() {
+        let inputs: Vec<Vec<u8>> = [0usize, 1, 31, 32, 136]
+            .iter()
+            .map(|&len| (0..len).map(|i| (i * 7 + 3) as u8).collect())
+            .collect();
+        let hashes: Vec<_> = inputs.iter().map(|bytes| poseidon2_hash_bytes(bytes)).collect();
+        for (i, bytes) in inputs.iter().enumerate() {
+            assert_eq!(poseidon2_hash_bytes(bytes), hashes[i], "not deterministic for {} bytes", bytes.len());
+            for other in &hashes[i + 1..] {
+                assert_ne!(hashes[i], *other);
+            }
+            if let Some(&last) = bytes.last() {
+                let mut flipped = bytes.clone();
+                *flipped.last_mut().unwrap() = last ^ 0x80;
+                assert_ne!(poseidon2_hash_bytes(&flipped), hashes[i], "{} bytes: last bit flip", bytes.len());
+            }
+        }
+
+        // The length prefix separates trailing zero bytes, which pack to the same limbs
+        assert_eq!(pack_bytes(&[0]), pack_bytes(&[0, 0]));
+        let zeros: Vec<_> = (0..=3).map(|len| poseidon2_hash_bytes(&vec![0; len])).collect();
+        for (i, hash) in zeros.iter().enumerate() {
+            assert!(zeros[i + 1..].iter().all(|other| other != hash));
+        }
+        // 136 bytes are 37 limbs and the prefix: five blocks, the last one padded
+        assert_eq!((1 + pack_bytes(&inputs[4]).len()).div_ceil(BYTES_HASH_RATE), 5);
+    }
+}
@@ -4,9 +4,15 @@
 // Verifies that keccak256(burnKey || revealAmount || burnExtraCommitment || "EIP-7503")
 // starts with `minimum_zero_bytes` zero bytes to increase security
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
 use crate::utils::keccak::keccak256;
 use alloy_primitives::U256;
+use crate::constants::M31_PRIME;
 use crate::field::M31;
+use crate::platform::{secure_random_u64, Stopwatch};
+use zeroize::Zeroize;
 
 /// Verify that burnKey satisfies Proof-of-Work requirement
 /// 
@@ -15,6 +21,9 @@ use crate::field::M31;
 /// 
 /// This adds 8 * minimum_zero_bytes bits of security to prevent
 /// address-hash collision attacks
+///
+/// Traced at `trace` level: `find_valid_burn_key` calls this once per candidate.
+#[tracing::instrument(level = "trace", skip_all, fields(minimum_zero_bytes))]
 pub fn verify_pow(
     burn_key: M31,
     reveal_amount: U256,
@@ -35,7 +44,7 @@ pub fn compute_pow_hash(
     let mut input = Vec::new();
     
     // burnKey (32 bytes, big-endian)
-    input.extend_from_slice(&burn_key.0.to_be_bytes());
+    input.extend_from_slice(&burn_key.value().to_be_bytes());
     input.extend_from_slice(&[0u8; 28]); // Pad to 32 bytes
     
     // revealAmount (32 bytes)
@@ -48,13 +57,17 @@ pub fn compute_pow_hash(
     input.extend_from_slice(&amount_bytes);
     
     // burnExtraCommitment (32 bytes, big-endian)
-    input.extend_from_slice(&burn_extra_commitment.0.to_be_bytes());
+    input.extend_from_slice(&burn_extra_commitment.value().to_be_bytes());
     input.extend_from_slice(&[0u8; 28]); // Pad to 32 bytes
     
     // "EIP-7503" string
     input.extend_from_slice(b"EIP-7503");
     
-    keccak256(&input)
+    let hash = keccak256(&input);
+    
+    // The preimage holds the burn key in the clear
+    input.zeroize();
+    hash
 }
 
 /// Check if hash starts with at least `minimum_zero_bytes` zero bytes
@@ -64,6 +77,7 @@ fn check_leading_zero_bytes(hash: &[u8; 32], minimum_zero_bytes: usize) -> bool
     }
     
     if minimum_zero_bytes > 32 {
+        tracing::warn!(minimum_zero_bytes, "PoW requirement exceeds the hash length and can never be met");
         return false;
     }
     
@@ -77,22 +91,79 @@ fn check_leading_zero_bytes(hash: &[u8; 32], minimum_zero_bytes: usize) -> bool
     true
 }
 
+/// Candidates `find_valid_burn_key` tries before giving up
+pub const DEFAULT_MAX_ATTEMPTS: u64 = 100_000;
+
+/// A burn key meeting a PoW requirement, and what finding it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinedKey {
+    pub burn_key: M31,
+    /// `compute_pow_hash` of the key, starting with the required zero bytes
+    pub pow_hash: [u8; 32],
+    /// Candidates hashed, the found key included
+    pub attempts: u64,
+    pub elapsed: Duration,
+}
+
 /// Find a valid burnKey that satisfies PoW requirement (for testing/mining)
 /// This is computationally expensive and should be done off-chain
+///
+/// Tries up to `DEFAULT_MAX_ATTEMPTS` candidates; see `mine_burn_key`.
 pub fn find_valid_burn_key(
     reveal_amount: U256,
     burn_extra_commitment: M31,
     minimum_zero_bytes: usize,
-) -> Option<M31> {
-    // Brute force search (simplified version)
-    // In practice, you'd want a more sophisticated mining algorithm
-    for i in 0..100000 {
-        let candidate = M31::from(i);
-        if verify_pow(candidate, reveal_amount, burn_extra_commitment, minimum_zero_bytes) {
-            return Some(candidate);
+) -> Result<MinedKey, MinerError> {
+    let never = AtomicBool::new(false);
+    mine_burn_key(reveal_amount, burn_extra_commitment, minimum_zero_bytes, DEFAULT_MAX_ATTEMPTS, &never)
+}
+
+/// Search up to `max_attempts` candidates for a burn key with `minimum_zero_bytes` of PoW
+///
+/// The burn key is secret, so the candidates are a run starting at a `platform::secure_random_u64`
+/// offset rather than at zero. `abort` is read before every candidate; setting it from another
+/// thread ends the search with `MinerError::Cancelled`.
+pub fn mine_burn_key(
+    reveal_amount: U256,
+    burn_extra_commitment: M31,
+    minimum_zero_bytes: usize,
+    max_attempts: u64,
+    abort: &AtomicBool,
+) -> Result<MinedKey, MinerError> {
+    if minimum_zero_bytes > 32 {
+        return Err(MinerError::InvalidDifficulty { zero_bytes: minimum_zero_bytes });
+    }
+    let stopwatch = Stopwatch::start();
+    let start = secure_random_u64() % M31_PRIME as u64;
+    for attempt in 0..max_attempts {
+        if abort.load(Ordering::Relaxed) {
+            return Err(MinerError::Cancelled);
+        }
+        let burn_key = M31::new(((start + attempt) % M31_PRIME as u64) as u32);
+        let pow_hash = compute_pow_hash(burn_key, reveal_amount, burn_extra_commitment);
+        if check_leading_zero_bytes(&pow_hash, minimum_zero_bytes) {
+            return Ok(MinedKey {
+                burn_key,
+                pow_hash,
+                attempts: attempt + 1,
+                elapsed: Duration::from_secs_f64(stopwatch.total() / 1000.0),
+            });
         }
     }
-    None
+    tracing::warn!(minimum_zero_bytes, max_attempts, "no burn key among the candidates satisfies the PoW requirement");
+    Err(MinerError::Exhausted { attempts: max_attempts })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MinerError {
+    #[error("No burn key among {attempts} candidates meets the PoW requirement")]
+    Exhausted { attempts: u64 },
+
+    #[error("{zero_bytes} leading zero bytes can never be met by a 32-byte hash")]
+    InvalidDifficulty { zero_bytes: usize },
+
+    #[error("Burn key search cancelled")]
+    Cancelled,
 }
 
 #[cfg(test)]
@@ -142,8 +213,48 @@ mod tests {
         let burn_extra_commitment = M31::from(100);
         
         // Try to find a key with 0 leading zero bytes (should be easy)
-        let result = find_valid_burn_key(reveal_amount, burn_extra_commitment, 0);
-        assert!(result.is_some());
+        let mined = find_valid_burn_key(reveal_amount, burn_extra_commitment, 0).unwrap();
+        assert_eq!(mined.attempts, 1);
+        assert_eq!(mined.pow_hash, compute_pow_hash(mined.burn_key, reveal_amount, burn_extra_commitment));
+
+        let mined = find_valid_burn_key(reveal_amount, burn_extra_commitment, 1).unwrap();
+        assert!(verify_pow(mined.burn_key, reveal_amount, burn_extra_commitment, 1));
+        assert_eq!(mined.pow_hash[0], 0);
+    }
+
+    #[test]
+    fn test_miner_errors() {
+        let reveal_amount = U256::from(1000u64);
+        let burn_extra_commitment = M31::from(100);
+        let running = AtomicBool::new(false);
+
+        assert_eq!(
+            find_valid_burn_key(reveal_amount, burn_extra_commitment, 33),
+            Err(MinerError::InvalidDifficulty { zero_bytes: 33 })
+        );
+        // Sixteen candidates meet four zero bytes with probability 2^-28
+        assert_eq!(
+            mine_burn_key(reveal_amount, burn_extra_commitment, 4, 16, &running),
+            Err(MinerError::Exhausted { attempts: 16 })
+        );
+        let aborted = AtomicBool::new(true);
+        assert_eq!(
+            mine_burn_key(reveal_amount, burn_extra_commitment, 0, 16, &aborted),
+            Err(MinerError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_abort_mid_search() {
+        let abort = AtomicBool::new(false);
+        // Twelve zero bytes are out of reach, so only the flag ends the search
+        let result = std::thread::scope(|scope| {
+            let search = scope.spawn(|| mine_burn_key(U256::from(1u64), M31::from(1), 12, u64::MAX, &abort));
+            std::thread::sleep(Duration::from_millis(20));
+            abort.store(true, Ordering::Relaxed);
+            search.join().unwrap()
+        });
+        assert_eq!(result, Err(MinerError::Cancelled));
     }
 }
 
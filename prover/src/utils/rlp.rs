@@ -2,7 +2,7 @@
 // Reference: proof-of-burn/circuits/utils/rlp/
 
 use alloy_primitives::U256;
-use alloy_rlp::{Encodable, BufMut};
+use alloy_rlp::{Decodable, Encodable, BufMut};
 
 /// Ethereum account state
 /// RLP encoding: [nonce, balance, storage_root, code_hash]
@@ -46,6 +46,30 @@ impl Encodable for Account {
     }
 }
 
+impl Decodable for Account {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = alloy_rlp::Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        let started = buf.len();
+        let account = Self {
+            nonce: u64::decode(buf)?,
+            balance: U256::decode(buf)?,
+            storage_root: <[u8; 32]>::decode(buf)?,
+            code_hash: <[u8; 32]>::decode(buf)?,
+        };
+        let consumed = started - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            });
+        }
+        Ok(account)
+    }
+}
+
 impl Account {
     /// Create a new account with given balance
     /// Uses empty storage root and code hash for burn addresses
@@ -110,6 +134,112 @@ impl MptLeaf {
     }
 }
 
+/// Hex-prefix (compact) encoding of a trie path, as stored in leaf and extension nodes
+///
+/// The first nibble holds the flags: 2 for leaves, plus 1 for odd-length paths, whose first path
+/// nibble then shares the first byte.
+pub fn hex_prefix_encode(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 0x20 } else { 0x00 };
+    let (first, rest) = if nibbles.len() % 2 == 1 {
+        (flag | 0x10 | nibbles[0], &nibbles[1..])
+    } else {
+        (flag, nibbles)
+    };
+    std::iter::once(first).chain(nibbles_to_bytes(rest)).collect()
+}
+
+/// Inverse of `hex_prefix_encode`: the path nibbles and whether the node is a leaf
+///
+/// `None` for an empty input, an unknown flag, or an even-length path with a non-zero pad nibble.
+pub fn hex_prefix_decode(encoded: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let (&first, rest) = encoded.split_first()?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return None;
+    }
+    let odd = flag & 1 == 1;
+    if !odd && first & 0x0F != 0 {
+        return None;
+    }
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if odd {
+        nibbles.push(first & 0x0F);
+    }
+    nibbles.extend(bytes_to_nibbles(rest));
+    Some((nibbles, flag & 2 == 2))
+}
+
+/// Split an RLP list into the payloads of its items
+pub fn rlp_list_items(data: &[u8]) -> Result<Vec<&[u8]>, alloy_rlp::Error> {
+    let mut buf = data;
+    let header = alloy_rlp::Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString);
+    }
+    if buf.len() < header.payload_length {
+        return Err(alloy_rlp::Error::InputTooShort);
+    }
+
+    let mut payload = &buf[..header.payload_length];
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let item = alloy_rlp::Header::decode(&mut payload)?;
+        if payload.len() < item.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+        items.push(&payload[..item.payload_length]);
+        payload = &payload[item.payload_length..];
+    }
+
+    Ok(items)
+}
+
+/// Split an RLP list spanning all of `data` into the full encodings (headers included) of its items
+///
+/// Unlike `rlp_list_items`, nested lists stay recognisable and trailing bytes are an error.
+pub fn rlp_list_raw_items(data: &[u8]) -> Result<Vec<&[u8]>, alloy_rlp::Error> {
+    let mut buf = data;
+    let header = alloy_rlp::Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString);
+    }
+    if buf.len() != header.payload_length {
+        return Err(alloy_rlp::Error::ListLengthMismatch {
+            expected: header.payload_length,
+            got: buf.len(),
+        });
+    }
+
+    let mut payload = buf;
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let mut rest = payload;
+        let item = alloy_rlp::Header::decode(&mut rest)?;
+        if rest.len() < item.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+        // A single byte below 0x80 is its own header and payload, so the header may be 0 bytes
+        let (raw, tail) = payload.split_at(payload.len() - rest.len() + item.payload_length);
+        items.push(raw);
+        payload = tail;
+    }
+
+    Ok(items)
+}
+
+/// Payload of the RLP string encoded by all of `raw`
+pub fn rlp_string_payload(raw: &[u8]) -> Result<&[u8], alloy_rlp::Error> {
+    let mut payload = raw;
+    let header = alloy_rlp::Header::decode(&mut payload)?;
+    if header.list {
+        return Err(alloy_rlp::Error::UnexpectedList);
+    }
+    if payload.len() != header.payload_length {
+        return Err(alloy_rlp::Error::UnexpectedLength);
+    }
+    Ok(payload)
+}
+
 /// Convert address hash (32 bytes) to nibbles (64 nibbles, 4 bits each)
 pub fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
     let mut nibbles = Vec::with_capacity(bytes.len() * 2);
@@ -144,6 +274,24 @@ mod tests {
         assert!(!encoded.is_empty());
     }
     
+    #[test]
+    fn test_account_decode_roundtrip() {
+        let account = Account::new_burn_account(U256::from(1000000000000000000u64));
+        let decoded = Account::decode(&mut account.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded.balance, account.balance);
+        assert_eq!(decoded.code_hash, account.code_hash);
+    }
+    
+    #[test]
+    fn test_hex_prefix() {
+        assert_eq!(hex_prefix_encode(&[1, 2, 3], true), vec![0x31, 0x23]);
+        assert_eq!(hex_prefix_encode(&[1, 2], false), vec![0x00, 0x12]);
+        assert_eq!(hex_prefix_decode(&[0x31, 0x23]), Some((vec![1, 2, 3], true)));
+        // Even paths pad the flag byte with a zero nibble
+        assert_eq!(hex_prefix_decode(&[0x21, 0x23]), None);
+        assert_eq!(hex_prefix_decode(&[]), None);
+    }
+    
     #[test]
     fn test_bytes_to_nibbles() {
         let bytes = vec![0xAB, 0xCD];
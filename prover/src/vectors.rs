@@ -0,0 +1,299 @@
+// Test vectors for other implementations of the protocol
+// The Solidity verifier and JS clients recompute burn addresses, coins, nullifiers and the
+// contract-side packings; `generate` evaluates the library's own functions on a fixed set of
+// inputs, edge values included, so they can check against this crate without running it.
+//
+// `stwo-pob-prover gen-vectors` writes them; tests/test_vectors.rs regenerates them and compares
+// against tests/fixtures/test_vectors.json, so a change to any of these derivations shows up in
+// review as a diff of that file.
+
+use std::collections::BTreeMap;
+
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::circuits::validation::MAX_AMOUNT;
+use crate::coins;
+use crate::constants::{M31_PRIME, OUTPUT_LIMBS};
+use crate::field::M31;
+use crate::prover::packaging::{compute_public_commitment, limbs_to_u256, ProofId};
+use crate::utils::burn_address::compute_burn_address;
+use crate::utils::keccak::keccak256;
+use crate::utils::pow::compute_pow_hash;
+use crate::utils::poseidon2_stwo::{poseidon2_permutation, N_STATE};
+
+/// Version of the vector file format
+pub const TEST_VECTORS_VERSION: u32 = 2;
+
+/// `burn_extra_commitment` of the burn address and PoW vectors
+const EXTRA_COMMITMENT: u32 = 100;
+
+/// Burn keys every keyed section covers: zero, one, a typical key and the largest M31
+const KEYS: [(&str, u32); 4] = [
+    ("zero key", 0),
+    ("key 1", 1),
+    ("key 12345", 12345),
+    ("max M31 key", M31_PRIME - 1),
+];
+
+/// Amounts every amount section covers, up to `MAX_AMOUNT`
+fn amounts() -> [(&'static str, U256); 4] {
+    [
+        ("zero", U256::ZERO),
+        ("1 wei", U256::from(1)),
+        ("1 ETH", U256::from(10u64.pow(18))),
+        ("max amount", MAX_AMOUNT),
+    ]
+}
+
+/// All vectors, by section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    pub version: u32,
+    pub description: String,
+    /// What each section holds
+    pub schema: BTreeMap<String, String>,
+    pub poseidon2: Vec<Poseidon2Vector>,
+    pub burn_address: Vec<BurnAddressVector>,
+    pub pow_hash: Vec<PowHashVector>,
+    pub coin: Vec<CoinVector>,
+    pub nullifier: Vec<NullifierVector>,
+    pub public_commitment: Vec<PublicCommitmentVector>,
+    pub proof_id: Vec<ProofIdVector>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poseidon2Vector {
+    pub label: String,
+    pub input: [u32; N_STATE],
+    pub output: [u32; N_STATE],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BurnAddressVector {
+    pub label: String,
+    pub burn_key: u32,
+    pub reveal_amount: String,
+    pub burn_extra_commitment: u32,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowHashVector {
+    pub label: String,
+    pub burn_key: u32,
+    pub reveal_amount: String,
+    pub burn_extra_commitment: u32,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinVector {
+    pub label: String,
+    pub burn_key: u32,
+    pub balance: String,
+    pub coin: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NullifierVector {
+    pub label: String,
+    pub burn_key: u32,
+    pub limbs: [u32; OUTPUT_LIMBS],
+    pub packed: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicCommitmentVector {
+    pub label: String,
+    pub block_hash: String,
+    pub nullifier: String,
+    pub commitment: String,
+    pub reveal_amount: String,
+    pub public_commitment: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofIdVector {
+    pub label: String,
+    pub public_commitment: String,
+    pub nullifier: String,
+    pub commitment: String,
+    pub proof_id: String,
+}
+
+impl TestVectors {
+    /// The vectors as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("test vectors serialize to JSON")
+    }
+}
+
+/// 32 bytes as 0x-prefixed lowercase hex
+fn hex32(bytes: [u8; 32]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// A uint256 as 0x-prefixed hex, zero-padded to 32 bytes
+fn u256_hex(value: U256) -> String {
+    hex32(value.to_be_bytes())
+}
+
+fn address_hex(address: Address) -> String {
+    format!("0x{}", hex::encode(address.as_slice()))
+}
+
+fn schema() -> BTreeMap<String, String> {
+    [
+        ("poseidon2", "Poseidon2 permutation (t = 16) of `input`; M31 values as JSON numbers"),
+        (
+            "burn_address",
+            "compute_burn_address(burn_key, reveal_amount, burn_extra_commitment); `address` is 20 bytes hex",
+        ),
+        (
+            "pow_hash",
+            "keccak256(burn_key || reveal_amount || burn_extra_commitment || \"EIP-7503\"), the PoW hash",
+        ),
+        ("coin", "Poseidon2([COIN_PREFIX, burn_key, 30-bit limbs of balance])[0]"),
+        (
+            "nullifier",
+            "Poseidon2([NULLIFIER_PREFIX, burn_key])[..8]; `packed` puts limb i at bits 31i, as the contracts read it",
+        ),
+        (
+            "public_commitment",
+            "keccak256(abi.encodePacked(block_hash, nullifier, commitment, reveal_amount)) >> 8",
+        ),
+        ("proof_id", "keccak256(abi.encodePacked(public_commitment, nullifier, commitment))"),
+    ]
+    .into_iter()
+    .map(|(section, description)| (section.to_owned(), description.to_owned()))
+    .collect()
+}
+
+fn poseidon2_vectors() -> Vec<Poseidon2Vector> {
+    let states: [(&str, [u32; N_STATE]); 3] = [
+        ("zero state", [0; N_STATE]),
+        ("counting state", std::array::from_fn(|i| i as u32)),
+        ("max M31 state", [M31_PRIME - 1; N_STATE]),
+    ];
+    states
+        .into_iter()
+        .map(|(label, input)| {
+            let output = poseidon2_permutation(input.map(BaseField::from_u32_unchecked));
+            Poseidon2Vector { label: label.to_owned(), input, output: output.map(|value| value.0) }
+        })
+        .collect()
+}
+
+fn burn_vectors() -> (Vec<BurnAddressVector>, Vec<PowHashVector>) {
+    let extra = M31::from(EXTRA_COMMITMENT);
+    let mut addresses = Vec::new();
+    let mut pow_hashes = Vec::new();
+    for (key_label, key) in KEYS {
+        for (amount_label, reveal_amount) in amounts() {
+            let label = format!("{key_label}, reveal {amount_label}");
+            let burn_key = M31::from(key);
+            addresses.push(BurnAddressVector {
+                label: label.clone(),
+                burn_key: key,
+                reveal_amount: u256_hex(reveal_amount),
+                burn_extra_commitment: EXTRA_COMMITMENT,
+                address: address_hex(compute_burn_address(burn_key, reveal_amount, extra)),
+            });
+            pow_hashes.push(PowHashVector {
+                label,
+                burn_key: key,
+                reveal_amount: u256_hex(reveal_amount),
+                burn_extra_commitment: EXTRA_COMMITMENT,
+                hash: hex32(compute_pow_hash(burn_key, reveal_amount, extra)),
+            });
+        }
+    }
+    (addresses, pow_hashes)
+}
+
+fn coin_vectors() -> Vec<CoinVector> {
+    KEYS.into_iter()
+        .flat_map(|(key_label, key)| {
+            amounts().into_iter().map(move |(amount_label, balance)| CoinVector {
+                label: format!("{key_label}, balance {amount_label}"),
+                burn_key: key,
+                balance: u256_hex(balance),
+                coin: coins::coin(M31::from(key), balance).value(),
+            })
+        })
+        .collect()
+}
+
+fn nullifier_vectors() -> Vec<NullifierVector> {
+    KEYS.into_iter()
+        .map(|(label, key)| {
+            let limbs = coins::nullifier(M31::from(key));
+            NullifierVector {
+                label: label.to_owned(),
+                burn_key: key,
+                limbs: limbs.map(|limb| limb.value()),
+                packed: u256_hex(limbs_to_u256(&limbs)),
+            }
+        })
+        .collect()
+}
+
+fn commitment_vectors() -> (Vec<PublicCommitmentVector>, Vec<ProofIdVector>) {
+    // The largest value `limbs_to_u256` packs to
+    let max_packed = (U256::from(1) << (31 * OUTPUT_LIMBS)) - U256::from(1);
+    let block_hash = B256::from(keccak256(b"block"));
+    let commitment = U256::from_be_bytes(keccak256(b"commitment")) >> 8;
+    let mut cases = vec![
+        ("all zero", B256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO),
+        ("all max", B256::repeat_byte(0xff), max_packed, max_packed, MAX_AMOUNT),
+    ];
+    for (label, key) in [KEYS[2], KEYS[3]] {
+        let nullifier = limbs_to_u256(&coins::nullifier(M31::from(key)));
+        cases.push((label, block_hash, nullifier, commitment, U256::from(10u64.pow(18))));
+    }
+
+    let mut public_commitments = Vec::new();
+    let mut proof_ids = Vec::new();
+    for (label, block_hash, nullifier, commitment, reveal_amount) in cases {
+        let public_commitment = compute_public_commitment(block_hash, nullifier, commitment, reveal_amount);
+        public_commitments.push(PublicCommitmentVector {
+            label: label.to_owned(),
+            block_hash: hex32(block_hash.0),
+            nullifier: u256_hex(nullifier),
+            commitment: u256_hex(commitment),
+            reveal_amount: u256_hex(reveal_amount),
+            public_commitment: u256_hex(public_commitment),
+        });
+        proof_ids.push(ProofIdVector {
+            label: label.to_owned(),
+            public_commitment: u256_hex(public_commitment),
+            nullifier: u256_hex(nullifier),
+            commitment: u256_hex(commitment),
+            proof_id: ProofId::compute(public_commitment, nullifier, commitment).to_string(),
+        });
+    }
+    (public_commitments, proof_ids)
+}
+
+/// Evaluate every vector with the library's functions
+pub fn generate() -> TestVectors {
+    let (burn_address, pow_hash) = burn_vectors();
+    let (public_commitment, proof_id) = commitment_vectors();
+    TestVectors {
+        version: TEST_VECTORS_VERSION,
+        description: "Proof of Burn (Circle STARK) test vectors. Hex values are 0x-prefixed, big-endian \
+                      and zero-padded (32 bytes, addresses 20); M31 values are JSON numbers below 2^31 - 1. \
+                      Burn address and PoW vectors use burn_extra_commitment 100."
+            .to_owned(),
+        schema: schema(),
+        poseidon2: poseidon2_vectors(),
+        burn_address,
+        pow_hash,
+        coin: coin_vectors(),
+        nullifier: nullifier_vectors(),
+        public_commitment,
+        proof_id,
+    }
+}
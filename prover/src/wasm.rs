@@ -0,0 +1,78 @@
+// WebAssembly entry points for browser usage
+// Only compiled for wasm32 targets with the `wasm` feature enabled. Entry points reject with an
+// `ErrorReport` object, `{code, message, details}`, as the CLI and the server report errors.
+// Split, Merge and Transfer proofs have no export: proving them in the browser is out of scope.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error_code::{ErrorCode, ErrorReport};
+
+#[wasm_bindgen(start)]
+pub fn start() {
+    // Initialize console logging for WASM
+    console_error_panic_hook::set_once();
+    console_log::init_with_level(log::Level::Info).expect("Failed to initialize logger");
+}
+
+#[wasm_bindgen]
+pub fn generate_burn_proof_wasm(_input_json: &str) -> Result<String, JsValue> {
+    // Parse input JSON and generate proof
+    // Return proof as JSON string
+    unimplemented!("WASM implementation pending")
+}
+
+#[wasm_bindgen]
+pub fn generate_spend_proof_wasm(_input_json: &str) -> Result<String, JsValue> {
+    // Parse input JSON and generate proof
+    // Return proof as JSON string
+    unimplemented!("WASM implementation pending")
+}
+
+/// Verify an envelope from its bytes (`to_bytes`, `to_bytes_compressed` or bare JSON) alone
+///
+/// Returns the `VerificationReport` as JSON: `{"checks": [{"check", "outcome", "ms"}]}`, where
+/// `outcome` is `{"status": "passed" | "failed" | "skipped"}` and a failure adds its `reason`.
+/// Never rejects; a malformed envelope fails its `decode` or `format` check.
+#[wasm_bindgen]
+pub fn verify_proof_wasm(envelope: &[u8]) -> String {
+    let report = crate::prover::ProofEnvelope::verify_offline_bytes(envelope);
+    serde_json::to_string(&report).expect("reports serialize")
+}
+
+/// Mine a burn key with `zero_bytes` of PoW, trying at most `max_attempts` candidates
+///
+/// Returns `{"burnKey", "powHash", "attempts", "elapsedMs"}` as JSON; a search that runs out of
+/// candidates or cannot succeed rejects with the `MinerError`'s report.
+#[wasm_bindgen]
+pub fn mine_burn_key_wasm(
+    reveal_amount: &str,
+    burn_extra_commitment: u32,
+    zero_bytes: usize,
+    max_attempts: u32,
+) -> Result<String, JsValue> {
+    use std::str::FromStr;
+    use std::sync::atomic::AtomicBool;
+
+    use crate::field::M31;
+    use crate::utils::pow::mine_burn_key;
+
+    let reveal_amount = alloy_primitives::U256::from_str(reveal_amount.trim()).map_err(|_| {
+        js_error(ErrorReport::new(ErrorCode::InputParse, format!("reveal_amount: not a number: {reveal_amount}")))
+    })?;
+    // JavaScript runs the search to completion on its one thread, so nothing can abort it
+    let never = AtomicBool::new(false);
+    let mined = mine_burn_key(reveal_amount, M31::from(burn_extra_commitment), zero_bytes, max_attempts.into(), &never)
+        .map_err(|e| js_error(ErrorReport::from_error(&e)))?;
+    let json = serde_json::json!({
+        "burnKey": mined.burn_key.value(),
+        "powHash": format!("0x{}", hex::encode(mined.pow_hash)),
+        "attempts": mined.attempts,
+        "elapsedMs": mined.elapsed.as_secs_f64() * 1000.0,
+    });
+    Ok(json.to_string())
+}
+
+/// An `ErrorReport` as the JavaScript object its JSON describes
+fn js_error(report: ErrorReport) -> JsValue {
+    js_sys::JSON::parse(&report.to_json()).unwrap_or_else(|_| JsValue::from_str(&report.message))
+}
@@ -0,0 +1,140 @@
+// Balance resolution for Proof of Burn witnesses
+//
+// `actual_balance` is whatever the account holds at the proven block, including dust anyone
+// can send to a burn address. `intended_balance` is what the prover claims; the circuit only
+// needs intended <= actual, so dust never has to be accounted for.
+
+use alloy_primitives::U256;
+
+use crate::constants::CircuitParams;
+
+/// Pick the intended balance and check the balance inequalities of `ProofOfBurnCircuit::new`
+///
+/// When `intended` is `None` it defaults to `min(actual, max_intended_balance)`.
+/// Returns `(actual, intended)`.
+pub fn resolve_balances(
+    actual: U256,
+    intended: Option<U256>,
+    reveal: U256,
+    params: &CircuitParams,
+) -> Result<(U256, U256), BalanceError> {
+    let max_actual = U256::from(params.max_actual_balance);
+    let max_intended = U256::from(params.max_intended_balance);
+
+    // actualBalance <= maxActualBalance
+    if actual > max_actual {
+        return Err(BalanceError::ActualAboveMax {
+            actual,
+            max: max_actual,
+            excess: actual - max_actual,
+        });
+    }
+
+    let intended = intended.unwrap_or_else(|| actual.min(max_intended));
+
+    // intendedBalance <= maxIntendedBalance
+    if intended > max_intended {
+        return Err(BalanceError::IntendedAboveMax {
+            intended,
+            max: max_intended,
+            excess: intended - max_intended,
+        });
+    }
+
+    // intendedBalance <= actualBalance
+    if intended > actual {
+        return Err(BalanceError::IntendedAboveActual {
+            intended,
+            actual,
+            excess: intended - actual,
+        });
+    }
+
+    // revealAmount <= intendedBalance
+    if reveal > intended {
+        return Err(BalanceError::RevealAboveIntended {
+            reveal,
+            intended,
+            excess: reveal - intended,
+        });
+    }
+
+    Ok((actual, intended))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BalanceError {
+    #[error("actual balance {actual} exceeds maxActualBalance {max} by {excess}")]
+    ActualAboveMax { actual: U256, max: U256, excess: U256 },
+
+    #[error("intended balance {intended} exceeds maxIntendedBalance {max} by {excess}")]
+    IntendedAboveMax { intended: U256, max: U256, excess: U256 },
+
+    #[error("intended balance {intended} exceeds actual balance {actual} by {excess}")]
+    IntendedAboveActual { intended: U256, actual: U256, excess: U256 },
+
+    #[error("reveal amount {reveal} exceeds intended balance {intended} by {excess}")]
+    RevealAboveIntended { reveal: U256, intended: U256, excess: U256 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eth(n: u64) -> U256 {
+        U256::from(n) * U256::from(1_000_000_000_000_000_000u64)
+    }
+
+    #[test]
+    fn test_defaults_to_actual_balance() {
+        let (actual, intended) =
+            resolve_balances(eth(1), None, eth(1) / U256::from(2), &CircuitParams::default()).unwrap();
+        assert_eq!(actual, eth(1));
+        assert_eq!(intended, eth(1));
+    }
+
+    #[test]
+    fn test_default_capped_at_max_intended() {
+        let (actual, intended) = resolve_balances(eth(50), None, eth(1), &CircuitParams::default()).unwrap();
+        assert_eq!(actual, eth(50));
+        assert_eq!(intended, eth(10));
+    }
+
+    #[test]
+    fn test_dusted_account() {
+        // 1 ETH burn, then an attacker sends 1000 wei of dust after the witness was captured
+        let dust = U256::from(1000);
+        let (actual, intended) =
+            resolve_balances(eth(1) + dust, Some(eth(1)), eth(1), &CircuitParams::default()).unwrap();
+        assert_eq!(actual, eth(1) + dust);
+        assert_eq!(intended, eth(1));
+    }
+
+    #[test]
+    fn test_intended_above_actual_reports_excess() {
+        // Witness captured before the dust arrived, intended copied from a later balance
+        let dust = U256::from(1000);
+        let err = resolve_balances(eth(1), Some(eth(1) + dust), U256::ZERO, &CircuitParams::default())
+            .unwrap_err();
+        assert!(matches!(err, BalanceError::IntendedAboveActual { excess, .. } if excess == dust));
+    }
+
+    #[test]
+    fn test_actual_above_max() {
+        let err = resolve_balances(eth(101), None, U256::ZERO, &CircuitParams::default()).unwrap_err();
+        assert!(matches!(err, BalanceError::ActualAboveMax { excess, .. } if excess == eth(1)));
+    }
+
+    #[test]
+    fn test_intended_above_max() {
+        let err = resolve_balances(eth(20), Some(eth(12)), U256::ZERO, &CircuitParams::default())
+            .unwrap_err();
+        assert!(matches!(err, BalanceError::IntendedAboveMax { excess, .. } if excess == eth(2)));
+    }
+
+    #[test]
+    fn test_reveal_above_intended() {
+        let err = resolve_balances(eth(2), Some(eth(1)), eth(3), &CircuitParams::default()).unwrap_err();
+        assert!(matches!(err, BalanceError::RevealAboveIntended { excess, .. } if excess == eth(2)));
+    }
+}
@@ -0,0 +1,386 @@
+// Proof of Burn witness from an `eth_getProof` response and a raw block header
+// Reference: EIP-1186 (eth_getProof), Ethereum Yellow Paper appendix D (MPT)
+
+use alloy_primitives::{Address, Bytes, B256, U256, U64};
+use serde::{Deserialize, Deserializer};
+
+use crate::circuits::builder::{InputValidationReport, ProofOfBurnInputsBuilder};
+use crate::circuits::proof_of_burn::{check_header_matches_proof, ProofOfBurnError, ProofOfBurnInputs};
+use crate::constants::circuit_params::{EMPTY_CODE_HASH, EMPTY_STORAGE_ROOT};
+use crate::constants::CircuitParams;
+use crate::field::M31;
+use crate::utils::burn_address::{compute_burn_address, parse_address};
+use crate::utils::header::BlockHeader;
+use crate::utils::keccak::keccak256;
+use crate::utils::mpt::{verify_mpt_proof, MptError};
+use super::balances::{resolve_balances, BalanceError};
+
+/// Account part of an `eth_getProof` result (EIP-1186)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthGetProofResponse {
+    /// Checked against its EIP-55 checksum when the node returns it mixed-case
+    #[serde(deserialize_with = "deserialize_address")]
+    pub address: Address,
+    pub account_proof: Vec<Bytes>,
+    pub balance: U256,
+    pub code_hash: B256,
+    pub nonce: U64,
+    pub storage_hash: B256,
+}
+
+impl EthGetProofResponse {
+    /// Parse either a bare result object or a full JSON-RPC envelope (`{"result": {...}}`)
+    pub fn from_json(json: &str) -> Result<Self, WitnessError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let result = value.get("result").cloned().unwrap_or(value);
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+fn deserialize_address<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Address, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_address(&value).map_err(serde::de::Error::custom)
+}
+
+/// Prover-side choices that are not part of the RPC data
+#[derive(Debug, Clone, Default)]
+pub struct WitnessOptions {
+    /// Balance the prover claims; defaults to the account balance capped at maxIntendedBalance
+    pub intended_balance: Option<U256>,
+
+    /// Security relaxation parameter for PoW
+    pub byte_security_relax: u8,
+
+    /// Extra commitment for proof metadata (e.g., prover address)
+    pub proof_extra_commitment: M31,
+}
+
+/// Assemble `ProofOfBurnInputs` for a burn address from RPC data
+///
+/// Checks, in order: the response is for the burn address derived from the key, the account
+/// is a plain burn account, the header's state root is the hash of the first proof node
+/// (`check_header_matches_proof`), and the leaf commits to the address hash and the reported
+/// balance. The response's address becomes the inputs' `expected_burn_address`.
+pub fn build_pob_inputs(
+    burn_key: M31,
+    reveal_amount: U256,
+    extra_commitment: M31,
+    proof_response: &EthGetProofResponse,
+    header_rlp: &[u8],
+    opts: WitnessOptions,
+) -> Result<ProofOfBurnInputs, WitnessError> {
+    let expected = compute_burn_address(burn_key, reveal_amount, extra_commitment);
+    if proof_response.address != expected {
+        return Err(WitnessError::AddressMismatch {
+            expected,
+            found: proof_response.address,
+        });
+    }
+
+    if proof_response.nonce != U64::ZERO {
+        return Err(WitnessError::NotBurnAccount { reason: "nonzero nonce".to_string() });
+    }
+    if proof_response.code_hash.0 != EMPTY_CODE_HASH {
+        return Err(WitnessError::NotBurnAccount { reason: "account has code".to_string() });
+    }
+    if proof_response.storage_hash.0 != EMPTY_STORAGE_ROOT {
+        return Err(WitnessError::NotBurnAccount { reason: "account has storage".to_string() });
+    }
+
+    let layers: Vec<Vec<u8>> = proof_response.account_proof.iter().map(|node| node.to_vec()).collect();
+    let Some(root_node) = layers.first() else {
+        return Err(WitnessError::EmptyAccountProof);
+    };
+
+    let header_state_root = header_state_root(header_rlp)?;
+    check_header_matches_proof(header_rlp, root_node)?;
+
+    let address_hash = keccak256(expected.as_slice());
+    let proven = verify_mpt_proof(&layers, &header_state_root, &address_hash)?;
+    if proven.balance != proof_response.balance || !proven.is_burn_account() {
+        return Err(WitnessError::LeafAccountMismatch { balance: proof_response.balance });
+    }
+    let num_leaf_address_nibbles = proven.key_nibbles_matched as u8;
+
+    let (actual_balance, intended_balance) = resolve_balances(
+        proof_response.balance,
+        opts.intended_balance,
+        reveal_amount,
+        &CircuitParams::default(),
+    )?;
+
+    let inputs = ProofOfBurnInputsBuilder::new()
+        .burn_key(burn_key)
+        .balances(actual_balance, intended_balance)
+        .reveal(reveal_amount)
+        .burn_extra_commitment(extra_commitment)
+        .mpt_layers(layers)
+        .block_header(header_rlp.to_vec())
+        .leaf_nibbles(num_leaf_address_nibbles, opts.byte_security_relax)
+        .proof_extra_commitment(opts.proof_extra_commitment)
+        .expected_burn_address(proof_response.address)
+        .build()?;
+
+    Ok(inputs)
+}
+
+/// Extract the state root, checking it sits where the circuit reads it
+fn header_state_root(header_rlp: &[u8]) -> Result<[u8; 32], WitnessError> {
+    BlockHeader::decode(header_rlp)
+        .and_then(|header| header.state_root())
+        .map_err(|e| WitnessError::InvalidHeader { reason: e.to_string() })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WitnessError {
+    #[error("Invalid eth_getProof JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Proof is for {found}, burn address is {expected}")]
+    AddressMismatch { expected: Address, found: Address },
+
+    #[error("Not a burn account: {reason}")]
+    NotBurnAccount { reason: String },
+
+    #[error("Empty account proof")]
+    EmptyAccountProof,
+
+    #[error("Invalid block header: {reason}")]
+    InvalidHeader { reason: String },
+
+    #[error(transparent)]
+    HeaderProofMismatch(#[from] ProofOfBurnError),
+
+    #[error("Leaf account does not match a burn account with balance {balance}")]
+    LeafAccountMismatch { balance: U256 },
+
+    #[error("MPT proof rejected: {0}")]
+    Mpt(#[from] MptError),
+
+    #[error("Balance check failed: {0}")]
+    Balance(#[from] BalanceError),
+
+    #[error(transparent)]
+    Validation(#[from] InputValidationReport),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::burn_address::format_checksummed;
+    use crate::utils::header::{NUMBER_FIELD, STATE_ROOT_FIELD, STATE_ROOT_OFFSET};
+
+    // Synthetic fixture in the exact shape of a mainnet eth_getProof response: a two-branch
+    // account trie holding a burn account at the address derived from the key below
+    const GET_PROOF_FIXTURE: &str = include_str!("../../tests/fixtures/witness/eth_get_proof.json");
+    const HEADER_FIXTURE: &str = include_str!("../../tests/fixtures/witness/header.hex");
+
+    const BURN_KEY: u32 = 12345;
+    const EXTRA_COMMITMENT: u32 = 100;
+
+    fn reveal_amount() -> U256 {
+        U256::from(500_000_000_000_000_000u64)
+    }
+
+    fn fixture() -> (EthGetProofResponse, Vec<u8>) {
+        let response = EthGetProofResponse::from_json(GET_PROOF_FIXTURE).unwrap();
+        let header = hex::decode(HEADER_FIXTURE.trim().trim_start_matches("0x")).unwrap();
+        (response, header)
+    }
+
+    fn build(response: &EthGetProofResponse, header: &[u8]) -> Result<ProofOfBurnInputs, WitnessError> {
+        build_pob_inputs(
+            M31::from(BURN_KEY),
+            reveal_amount(),
+            M31::from(EXTRA_COMMITMENT),
+            response,
+            header,
+            WitnessOptions::default(),
+        )
+    }
+
+    #[test]
+    fn test_build_from_fixture() {
+        let (response, header) = fixture();
+        let inputs = build(&response, &header).unwrap();
+
+        assert_eq!(*inputs.burn_key.expose_secret(), M31::from(BURN_KEY));
+        assert_eq!(inputs.actual_balance, U256::from(1_000_000_000_000_000_000u64));
+        assert_eq!(inputs.intended_balance, inputs.actual_balance);
+        assert_eq!(inputs.reveal_amount, reveal_amount());
+        assert_eq!(inputs.layers.len(), 3);
+        assert_eq!(inputs.block_header, header);
+        // Two branch levels consume two nibbles of the 64-nibble key
+        assert_eq!(inputs.num_leaf_address_nibbles, 62);
+        // The address the proof was fetched for is recorded, so the inputs cannot drift from it
+        assert_eq!(inputs.expected_burn_address, Some(response.address));
+    }
+
+    #[test]
+    fn test_fixture_proves_reported_account() {
+        let (response, header) = fixture();
+        let layers: Vec<Vec<u8>> = response.account_proof.iter().map(|node| node.to_vec()).collect();
+        let address_hash = keccak256(response.address.as_slice());
+        let proven = verify_mpt_proof(&layers, &header_state_root(&header).unwrap(), &address_hash).unwrap();
+
+        assert_eq!(proven.balance, response.balance);
+        assert_eq!(proven.nonce, response.nonce.to::<u64>());
+        assert_eq!(proven.storage_root, response.storage_hash.0);
+        assert_eq!(proven.code_hash, response.code_hash.0);
+        assert_eq!(proven.key_nibbles_matched, 62);
+    }
+
+    #[test]
+    fn test_address_mismatch() {
+        let (response, header) = fixture();
+        let err = build_pob_inputs(
+            M31::from(BURN_KEY + 1),
+            reveal_amount(),
+            M31::from(EXTRA_COMMITMENT),
+            &response,
+            &header,
+            WitnessOptions::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, WitnessError::AddressMismatch { .. }));
+    }
+
+    #[test]
+    fn test_address_checksum() {
+        let (response, _) = fixture();
+        let checksummed = format_checksummed(response.address);
+        let mut value: serde_json::Value = serde_json::from_str(GET_PROOF_FIXTURE).unwrap();
+        let result = &mut value["result"];
+
+        result["address"] = checksummed.clone().into();
+        assert_eq!(EthGetProofResponse::from_json(&result.to_string()).unwrap().address, response.address);
+
+        // Flip the case of the first letter of the checksummed address
+        let (i, letter) = checksummed.char_indices().skip(2).find(|(_, c)| c.is_ascii_alphabetic()).unwrap();
+        let mut mistyped = checksummed.clone();
+        let flipped = if letter.is_ascii_uppercase() { letter.to_ascii_lowercase() } else { letter.to_ascii_uppercase() };
+        mistyped.replace_range(i..=i, &flipped.to_string());
+        result["address"] = mistyped.into();
+        let err = EthGetProofResponse::from_json(&result.to_string()).unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{err}");
+    }
+
+    /// The fixture header renumbered to `number`, with `state_root` if given
+    fn header_at(header: &[u8], number: u64, state_root: Option<[u8; 32]>) -> Vec<u8> {
+        let number = number.to_be_bytes();
+        let mut decoded = BlockHeader::decode(header).unwrap();
+        decoded.fields[NUMBER_FIELD] = &number[number.iter().take_while(|&&byte| byte == 0).count()..];
+        if let Some(state_root) = &state_root {
+            decoded.fields[STATE_ROOT_FIELD] = state_root.as_slice();
+        }
+        decoded.encode()
+    }
+
+    #[test]
+    fn test_state_root_mismatch() {
+        let (response, mut header) = fixture();
+        header[STATE_ROOT_OFFSET] ^= 0xFF;
+        let err = build(&response, &header).unwrap_err();
+        assert!(matches!(
+            err,
+            WitnessError::HeaderProofMismatch(ProofOfBurnError::HeaderProofMismatch { block_number: 21_000_000, .. })
+        ));
+    }
+
+    #[test]
+    fn test_header_from_an_older_block() {
+        // The proof was fetched at block N + 1; the header is block N, with that block's own root
+        let (response, fixture_header) = fixture();
+        let n = BlockHeader::decode(&fixture_header).unwrap().number().unwrap();
+        let proof_root = keccak256(&response.account_proof[0]);
+        let stale_root = keccak256(b"state at block N");
+        let header_n = header_at(&fixture_header, n, Some(stale_root));
+        let header_n1 = header_at(&fixture_header, n + 1, None);
+
+        match build(&response, &header_n).unwrap_err() {
+            WitnessError::HeaderProofMismatch(ProofOfBurnError::HeaderProofMismatch {
+                header_state_root,
+                proof_root: found,
+                block_number,
+            }) => {
+                assert_eq!(header_state_root, B256::from(stale_root));
+                assert_eq!(found, B256::from(proof_root));
+                assert_eq!(block_number, n);
+            }
+            other => panic!("expected a header/proof mismatch, got {:?}", other),
+        }
+        let message = check_header_matches_proof(&header_n, &response.account_proof[0]).unwrap_err().to_string();
+        assert!(message.contains(&format!("block {}", n)), "{}", message);
+
+        let inputs = build(&response, &header_n1).unwrap();
+        assert_eq!(inputs.block_header, header_n1);
+    }
+
+    #[test]
+    fn test_truncated_header() {
+        let (response, header) = fixture();
+        let err = build(&response, &header[..64]).unwrap_err();
+        assert!(matches!(err, WitnessError::InvalidHeader { .. }));
+    }
+
+    #[test]
+    fn test_empty_account_proof() {
+        let (mut response, header) = fixture();
+        response.account_proof.clear();
+        let err = build(&response, &header).unwrap_err();
+        assert!(matches!(err, WitnessError::EmptyAccountProof));
+    }
+
+    #[test]
+    fn test_balance_not_in_leaf() {
+        let (mut response, header) = fixture();
+        response.balance += U256::from(1);
+        let err = build(&response, &header).unwrap_err();
+        assert!(matches!(err, WitnessError::LeafAccountMismatch { .. }));
+    }
+
+    #[test]
+    fn test_not_burn_account() {
+        let (mut response, header) = fixture();
+        response.nonce = U64::from(1);
+        let err = build(&response, &header).unwrap_err();
+        assert!(matches!(err, WitnessError::NotBurnAccount { .. }));
+    }
+
+    #[test]
+    fn test_reveal_above_balance() {
+        let (response, header) = fixture();
+        let err = build_pob_inputs(
+            M31::from(BURN_KEY),
+            reveal_amount(),
+            M31::from(EXTRA_COMMITMENT),
+            &response,
+            &header,
+            WitnessOptions {
+                intended_balance: Some(U256::from(100_000_000_000_000_000u64)),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, WitnessError::Balance(BalanceError::RevealAboveIntended { .. })));
+    }
+
+    #[test]
+    fn test_intended_balance_option() {
+        let (response, header) = fixture();
+        let inputs = build_pob_inputs(
+            M31::from(BURN_KEY),
+            reveal_amount(),
+            M31::from(EXTRA_COMMITMENT),
+            &response,
+            &header,
+            WitnessOptions {
+                intended_balance: Some(U256::from(800_000_000_000_000_000u64)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(inputs.intended_balance, U256::from(800_000_000_000_000_000u64));
+    }
+}
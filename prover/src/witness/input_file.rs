@@ -0,0 +1,121 @@
+// Reading JSON input files with a size limit
+// A 16-layer witness written as integer arrays runs to several megabytes. Reading it to a string
+// first holds the text and the parsed inputs at once, so inputs are parsed from a buffered reader
+// instead, and anything past `max_bytes` is refused before a runaway file is parsed.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+/// Largest input file read unless the caller sets another limit: 16 MiB
+pub const DEFAULT_MAX_INPUT_BYTES: u64 = 16 << 20;
+
+/// Parse a JSON file of at most `max_bytes` bytes
+pub fn read_json_file<T: DeserializeOwned>(path: &Path, max_bytes: u64) -> Result<T, InputFileError> {
+    let file = File::open(path)?;
+    // A regular file's size is known up front; pipes and devices are counted as they are read
+    let size = file.metadata()?.len();
+    if size > max_bytes {
+        return Err(InputFileError::TooLarge { limit: max_bytes });
+    }
+    read_json(BufReader::new(file), max_bytes)
+}
+
+/// Parse JSON from `reader`, refusing it once more than `max_bytes` bytes are read
+pub fn read_json<T: DeserializeOwned, R: Read>(reader: R, max_bytes: u64) -> Result<T, InputFileError> {
+    let mut reader = LimitedReader { inner: reader, remaining: max_bytes, exceeded: false };
+    serde_json::from_reader(&mut reader).map_err(|e| match reader.exceeded {
+        true => InputFileError::TooLarge { limit: max_bytes },
+        false => InputFileError::Json(e),
+    })
+}
+
+/// Fails reads past its limit, and records that it did
+struct LimitedReader<R> {
+    inner: R,
+    remaining: u64,
+    exceeded: bool,
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            // At the limit: fine if the input ends here
+            if self.inner.read(&mut [0u8])? == 0 {
+                return Ok(0);
+            }
+            self.exceeded = true;
+            return Err(io::Error::other("input size limit exceeded"));
+        }
+        let len = buf.len().min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let read = self.inner.read(&mut buf[..len])?;
+        self.remaining -= read as u64;
+        Ok(read)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InputFileError {
+    #[error("Input exceeds the size limit of {limit} bytes")]
+    TooLarge { limit: u64 },
+
+    #[error("Failed to read input: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+    use std::io::Write;
+
+    /// Burn inputs whose layers, written as integer arrays, fill about `size` bytes
+    fn burn_inputs_json(size: usize) -> String {
+        // "255," is four bytes per layer byte
+        let layer = vec!["255"; size / 4 / 16].join(",");
+        let layers = vec![format!("[{layer}]"); 16].join(",");
+        format!(
+            r#"{{"burn_key": 12345, "actual_balance": "1000", "intended_balance": "1000",
+                "reveal_amount": "400", "burn_extra_commitment": 100, "layers": [{layers}],
+                "block_header": [0, 1, 2], "num_leaf_address_nibbles": 50,
+                "byte_security_relax": 0, "proof_extra_commitment": 200}}"#
+        )
+    }
+
+    #[test]
+    fn test_oversized_input_refused() {
+        let json = burn_inputs_json(DEFAULT_MAX_INPUT_BYTES as usize + (1 << 20));
+        assert!(json.len() as u64 > DEFAULT_MAX_INPUT_BYTES);
+
+        let path = std::env::temp_dir().join("pob_oversized_inputs.json");
+        File::create(&path).unwrap().write_all(json.as_bytes()).unwrap();
+        let result = read_json_file::<ProofOfBurnInputs>(&path, DEFAULT_MAX_INPUT_BYTES);
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(result, Err(InputFileError::TooLarge { limit: DEFAULT_MAX_INPUT_BYTES })));
+
+        // A stream of unknown size is cut off at the limit too
+        let result = read_json::<ProofOfBurnInputs, _>(json.as_bytes(), 1 << 20);
+        assert!(matches!(result, Err(InputFileError::TooLarge { limit: 1048576 })));
+    }
+
+    #[test]
+    fn test_input_within_limit_parsed() {
+        let json = burn_inputs_json(1 << 16);
+        let inputs: ProofOfBurnInputs = read_json(json.as_bytes(), json.len() as u64).unwrap();
+        assert_eq!(inputs.layers.len(), 16);
+        assert!(inputs.layers.iter().all(|layer| layer.iter().all(|&byte| byte == 255)));
+        assert_eq!(inputs.block_header, [0, 1, 2]);
+
+        // Malformed JSON under the limit is a parse error, not a size error
+        let result = read_json::<ProofOfBurnInputs, _>(&json.as_bytes()[..json.len() / 2], json.len() as u64);
+        assert!(matches!(result, Err(InputFileError::Json(_))));
+    }
+}
@@ -0,0 +1,15 @@
+// Witness assembly: turns Ethereum RPC data into circuit inputs
+// Pure library code, no network access; callers fetch the data themselves. `seal` encrypts the
+// result for a prover that must not learn the burn key.
+
+pub mod balances;
+pub mod eth_proof;
+pub mod input_file;
+pub mod precheck;
+pub mod seal;
+
+pub use balances::{resolve_balances, BalanceError};
+pub use eth_proof::{build_pob_inputs, EthGetProofResponse, WitnessError, WitnessOptions};
+pub use input_file::{read_json, read_json_file, InputFileError, DEFAULT_MAX_INPUT_BYTES};
+pub use precheck::{precheck, Check, CheckOutcome, CheckStatus, PrecheckReport};
+pub use seal::{seal, seal_with_policy, unseal, SealError, SealPolicy, SealedInputs, SealedWitness};
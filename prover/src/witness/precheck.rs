@@ -0,0 +1,449 @@
+// Witness pre-check
+// Every check on burn inputs that needs no trace: balance bounds, the funded address, proof of
+// work, header parsing, the header chain, the state-root match, the MPT walk and the
+// burn-address leaf. They run in milliseconds, so a bad witness is reported before a proving run
+// spends half a minute failing on it.
+// Each check reports on its own; a check whose prerequisite failed is skipped, not failed.
+
+use std::fmt;
+
+use alloy_primitives::U256;
+use alloy_rlp::Decodable;
+use serde::Serialize;
+
+use crate::circuits::proof_of_burn::{check_header_matches_proof, ProofOfBurnInputs};
+use crate::constants::circuit_params::{EMPTY_CODE_HASH, EMPTY_STORAGE_ROOT};
+use crate::constants::CircuitParams;
+use crate::utils::burn_address::{compute_burn_address, compute_burn_address_hash, format_checksummed};
+use crate::utils::header::{state_root_at_offset, BlockHeader};
+use crate::utils::keccak::keccak256;
+use crate::utils::mpt::{verify_mpt_proof, MptNode};
+use crate::utils::pow::compute_pow_hash;
+use crate::utils::rlp::{bytes_to_nibbles, rlp_string_payload, Account};
+
+/// Keccak rate in bytes; the header is absorbed in blocks of this size
+const KECCAK_BLOCK_BYTES: usize = 136;
+
+/// Category of a pre-check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// Balances within `CircuitParams` and ordered reveal <= intended <= actual
+    Balances,
+    /// The burn address the inputs derive is their `expected_burn_address`, if they name one
+    FundedAddress,
+    /// The PoW hash has the leading zero bytes the security level requires
+    ProofOfWork,
+    /// The header fits the circuit and decodes, with the state root at `STATE_ROOT_OFFSET`
+    Header,
+    /// The headers of `header_chain`, if any, descend from the header one block at a time
+    HeaderChain,
+    /// The header's state root is the hash of the first MPT layer
+    StateRoot,
+    /// The layers chain down to an account leaf for the burn address holding `actual_balance`
+    Mpt,
+    /// The leaf key is the tail of the burn address hash, `num_leaf_address_nibbles` long, and
+    /// the account has no nonce, storage or code
+    BurnAddress,
+}
+
+/// Result of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+    /// Not run because a check it depends on failed
+    Skipped,
+}
+
+/// One check's status and what it found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckOutcome {
+    pub check: Check,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// Outcome of every pre-check, in `Check` order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrecheckReport {
+    pub outcomes: Vec<CheckOutcome>,
+}
+
+impl PrecheckReport {
+    /// Whether every check passed
+    pub fn passed(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.status == CheckStatus::Passed)
+    }
+
+    /// Outcome of `check`
+    pub fn outcome(&self, check: Check) -> &CheckOutcome {
+        self.outcomes
+            .iter()
+            .find(|outcome| outcome.check == check)
+            .expect("precheck runs every check")
+    }
+
+    /// Checks that did not pass
+    pub fn failures(&self) -> impl Iterator<Item = &CheckOutcome> {
+        self.outcomes.iter().filter(|outcome| outcome.status != CheckStatus::Passed)
+    }
+}
+
+impl fmt::Display for PrecheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for outcome in &self.outcomes {
+            let status = match outcome.status {
+                CheckStatus::Passed => "ok",
+                CheckStatus::Failed => "FAILED",
+                CheckStatus::Skipped => "skipped",
+            };
+            writeln!(f, "  {:<13} {:<8} {}", format!("{:?}", outcome.check), status, outcome.detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run every non-STARK check on `inputs` against the limits in `params`
+pub fn precheck(inputs: &ProofOfBurnInputs, params: &CircuitParams) -> PrecheckReport {
+    let burn_key = *inputs.burn_key.expose_secret();
+    let address_hash =
+        compute_burn_address_hash(burn_key, inputs.reveal_amount, inputs.burn_extra_commitment);
+    let relax = inputs.byte_security_relax as usize;
+
+    let header_state_root = check_header(inputs, params);
+    let outcomes = vec![
+        outcome(Check::Balances, check_balances(inputs, params)),
+        outcome(Check::FundedAddress, check_funded_address(inputs)),
+        outcome(Check::ProofOfWork, check_pow(inputs, params.pow_minimum_zero_bytes + relax)),
+        outcome(Check::Header, header_state_root.clone().map(|(detail, _)| detail)),
+        outcome(Check::HeaderChain, check_header_chain(inputs)),
+        outcome(Check::StateRoot, check_state_root(inputs, header_state_root.ok().map(|(_, root)| root))),
+        outcome(Check::Mpt, check_mpt(inputs, params, &address_hash)),
+        outcome(
+            Check::BurnAddress,
+            check_burn_address(
+                inputs,
+                &address_hash,
+                params.min_leaf_address_nibbles.saturating_sub(relax * 2),
+            ),
+        ),
+    ];
+    PrecheckReport { outcomes }
+}
+
+/// Why a check did not pass
+#[derive(Clone)]
+enum Failure {
+    Failed(String),
+    Skipped,
+}
+
+/// Passes with a detail
+type CheckResult = Result<String, Failure>;
+
+fn outcome(check: Check, result: CheckResult) -> CheckOutcome {
+    let (status, detail) = match result {
+        Ok(detail) => (CheckStatus::Passed, detail),
+        Err(Failure::Failed(detail)) => (CheckStatus::Failed, detail),
+        Err(Failure::Skipped) => (CheckStatus::Skipped, "A check it depends on failed".to_string()),
+    };
+    CheckOutcome { check, status, detail }
+}
+
+fn check_balances(inputs: &ProofOfBurnInputs, params: &CircuitParams) -> CheckResult {
+    let (actual, intended, reveal) = (inputs.actual_balance, inputs.intended_balance, inputs.reveal_amount);
+    if actual > U256::from(params.max_actual_balance) {
+        return Err(Failure::Failed(format!("Actual balance {} exceeds {}", actual, params.max_actual_balance)));
+    }
+    if intended > U256::from(params.max_intended_balance) {
+        return Err(Failure::Failed(format!("Intended balance {} exceeds {}", intended, params.max_intended_balance)));
+    }
+    if intended > actual {
+        return Err(Failure::Failed(format!("Intended balance {} > actual balance {}", intended, actual)));
+    }
+    if reveal > intended {
+        return Err(Failure::Failed(format!("Reveal amount {} > intended balance {}", reveal, intended)));
+    }
+    Ok(format!("Reveal {} <= intended {} <= actual {} wei", reveal, intended, actual))
+}
+
+fn check_funded_address(inputs: &ProofOfBurnInputs) -> CheckResult {
+    let derived = compute_burn_address(
+        *inputs.burn_key.expose_secret(),
+        inputs.reveal_amount,
+        inputs.burn_extra_commitment,
+    );
+    match inputs.expected_burn_address {
+        None => Ok(format!("None given; the inputs derive {}", format_checksummed(derived))),
+        Some(expected) if expected == derived => Ok(format_checksummed(derived)),
+        // A reveal amount or extra commitment other than the address was derived with
+        Some(expected) => Err(Failure::Failed(format!(
+            "Inputs derive {}, not the funded {}",
+            format_checksummed(derived),
+            format_checksummed(expected)
+        ))),
+    }
+}
+
+fn check_pow(inputs: &ProofOfBurnInputs, required_zeros: usize) -> CheckResult {
+    let hash = compute_pow_hash(
+        *inputs.burn_key.expose_secret(),
+        inputs.reveal_amount,
+        inputs.burn_extra_commitment,
+    );
+    let zeros = hash.iter().take_while(|&&byte| byte == 0).count();
+    if zeros < required_zeros {
+        return Err(Failure::Failed(format!("PoW hash has {} leading zero bytes, {} required", zeros, required_zeros)));
+    }
+    Ok(format!("{} leading zero bytes, {} required", zeros, required_zeros))
+}
+
+/// Passes with the header's state root
+fn check_header(inputs: &ProofOfBurnInputs, params: &CircuitParams) -> Result<(String, [u8; 32]), Failure> {
+    let max_len = params.max_header_blocks * KECCAK_BLOCK_BYTES;
+    if inputs.block_header.len() > max_len {
+        return Err(Failure::Failed(format!("Header is {} bytes, max {}", inputs.block_header.len(), max_len)));
+    }
+    let header = BlockHeader::decode(&inputs.block_header).map_err(|e| Failure::Failed(e.to_string()))?;
+    let state_root = header.state_root().map_err(|e| Failure::Failed(e.to_string()))?;
+    Ok((format!("{} fields, {} bytes", header.fields.len(), inputs.block_header.len()), state_root))
+}
+
+fn check_header_chain(inputs: &ProofOfBurnInputs) -> CheckResult {
+    if inputs.header_chain.is_empty() {
+        return Ok("None; the header's own block is bound".to_string());
+    }
+    let chain = inputs.verify_header_chain().map_err(|e| Failure::Failed(e.to_string()))?;
+    Ok(format!("Blocks {} to {} linked, binding {}", chain.first_number, chain.last_number, chain.last_hash))
+}
+
+fn check_state_root(inputs: &ProofOfBurnInputs, header_state_root: Option<[u8; 32]>) -> CheckResult {
+    if let Some(state_root) = header_state_root {
+        // A decoded header also names its block on a mismatch
+        let root_layer = inputs.layers.first().ok_or(Failure::Skipped)?;
+        check_header_matches_proof(&inputs.block_header, root_layer).map_err(|e| Failure::Failed(e.to_string()))?;
+        return Ok(format!("0x{}", hex::encode(state_root)));
+    }
+    // The circuit reads the root at the fixed offset even if the header does not decode
+    let state_root = state_root_at_offset(&inputs.block_header).ok_or(Failure::Skipped)?;
+    let root_layer = inputs.layers.first().ok_or(Failure::Skipped)?;
+    let computed = keccak256(root_layer);
+    if computed != state_root {
+        return Err(Failure::Failed(format!(
+            "Header state root 0x{} but the root layer hashes to 0x{}",
+            hex::encode(state_root),
+            hex::encode(computed)
+        )));
+    }
+    Ok(format!("0x{}", hex::encode(state_root)))
+}
+
+fn check_mpt(inputs: &ProofOfBurnInputs, params: &CircuitParams, address_hash: &[u8; 32]) -> CheckResult {
+    if inputs.layers.len() > params.max_num_layers {
+        return Err(Failure::Failed(format!("{} layers, max {}", inputs.layers.len(), params.max_num_layers)));
+    }
+    // Walk from the root layer itself: whether the header commits to it is `Check::StateRoot`
+    let root = inputs.layers.first().map(|layer| keccak256(layer)).unwrap_or_default();
+    let proven = verify_mpt_proof(&inputs.layers, &root, address_hash).map_err(|e| Failure::Failed(e.to_string()))?;
+    if proven.balance != inputs.actual_balance {
+        return Err(Failure::Failed(format!(
+            "Leaf holds {} wei, actual balance is {}",
+            proven.balance, inputs.actual_balance
+        )));
+    }
+    Ok(format!("{} layers down to a leaf holding {} wei", inputs.layers.len(), proven.balance))
+}
+
+fn check_burn_address(inputs: &ProofOfBurnInputs, address_hash: &[u8; 32], min_nibbles: usize) -> CheckResult {
+    let leaf = inputs.layers.last().ok_or(Failure::Skipped)?;
+    let Ok(MptNode::Leaf { path, value }) = MptNode::decode(leaf) else {
+        return Err(Failure::Failed("Last layer is not a leaf node".to_string()));
+    };
+
+    let address_nibbles = bytes_to_nibbles(address_hash);
+    if !address_nibbles.ends_with(&path) {
+        return Err(Failure::Failed(format!(
+            "Leaf key is not the tail of keccak256(burn address) 0x{}",
+            hex::encode(address_hash)
+        )));
+    }
+    let claimed = inputs.num_leaf_address_nibbles as usize;
+    if path.len() != claimed {
+        return Err(Failure::Failed(format!("Leaf key has {} nibbles, inputs claim {}", path.len(), claimed)));
+    }
+    if claimed < min_nibbles {
+        return Err(Failure::Failed(format!("{} leaf nibbles, {} required", claimed, min_nibbles)));
+    }
+
+    let account = rlp_string_payload(value)
+        .ok()
+        .and_then(|mut account| Account::decode(&mut account).ok())
+        .ok_or_else(|| Failure::Failed("Leaf value is not an account".to_string()))?;
+    if account.nonce != 0 || account.storage_root != EMPTY_STORAGE_ROOT || account.code_hash != EMPTY_CODE_HASH {
+        return Err(Failure::Failed("Account has a nonce, storage or code".to_string()));
+    }
+    Ok(format!("{} leaf nibbles of 0x{}", claimed, hex::encode(address_hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::MAINNET_CHAIN_ID;
+    use crate::field::M31;
+    use crate::secret::Secret;
+    use crate::test_utils::{mock_account_proof, mock_descendants};
+
+    /// Satisfies the 2-byte PoW for the reveal amount and extra commitment below
+    const BURN_KEY: u32 = 29405;
+
+    fn valid_inputs() -> ProofOfBurnInputs {
+        let reveal_amount = U256::from(500000000000000000u64);
+        let actual_balance = U256::from(1000000000000000000u64);
+        let burn_extra_commitment = M31::from(100);
+        let address_hash = compute_burn_address_hash(M31::from(BURN_KEY), reveal_amount, burn_extra_commitment);
+        let proof = mock_account_proof(&address_hash, actual_balance, 3);
+        ProofOfBurnInputs {
+            burn_key: Secret::new(M31::from(BURN_KEY)),
+            actual_balance,
+            intended_balance: actual_balance,
+            reveal_amount,
+            burn_extra_commitment,
+            layers: proof.layers,
+            block_header: proof.block_header,
+            header_chain: Vec::new(),
+            chain_id: MAINNET_CHAIN_ID,
+            valid_until_block: None,
+            num_leaf_address_nibbles: proof.num_leaf_address_nibbles,
+            byte_security_relax: 0,
+            proof_extra_commitment: M31::from(200),
+            expected_burn_address: None,
+        }
+    }
+
+    fn status(inputs: &ProofOfBurnInputs, check: Check) -> CheckStatus {
+        precheck(inputs, &CircuitParams::default()).outcome(check).status
+    }
+
+    #[test]
+    fn test_valid_inputs_pass() {
+        let report = precheck(&valid_inputs(), &CircuitParams::default());
+        assert!(report.passed(), "{}", report);
+        assert_eq!(report.outcomes.len(), 8);
+    }
+
+    #[test]
+    fn test_balances() {
+        let mut inputs = valid_inputs();
+        inputs.reveal_amount = inputs.intended_balance + U256::from(1);
+        assert_eq!(status(&inputs, Check::Balances), CheckStatus::Failed);
+
+        let params = CircuitParams { max_actual_balance: 1, ..CircuitParams::default() };
+        let report = precheck(&valid_inputs(), &params);
+        assert_eq!(report.outcome(Check::Balances).status, CheckStatus::Failed);
+        assert_eq!(report.failures().count(), 1);
+    }
+
+    #[test]
+    fn test_proof_of_work() {
+        // One more zero byte than BURN_KEY happens to have
+        let mut inputs = valid_inputs();
+        inputs.byte_security_relax = 1;
+        assert_eq!(status(&inputs, Check::ProofOfWork), CheckStatus::Failed);
+    }
+
+    #[test]
+    fn test_header() {
+        let mut inputs = valid_inputs();
+        inputs.block_header.push(0);
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert_eq!(report.outcome(Check::Header).status, CheckStatus::Failed);
+        // The state root is still read at its offset
+        assert_eq!(report.outcome(Check::StateRoot).status, CheckStatus::Passed);
+
+        inputs.block_header.truncate(50);
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert_eq!(report.outcome(Check::Header).status, CheckStatus::Failed);
+        assert_eq!(report.outcome(Check::StateRoot).status, CheckStatus::Skipped);
+    }
+
+    #[test]
+    fn test_header_chain() {
+        let mut inputs = valid_inputs();
+        inputs.header_chain = mock_descendants(&inputs.block_header, 2);
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert!(report.passed(), "{}", report);
+        assert!(report.outcome(Check::HeaderChain).detail.contains("19000000 to 19000002"), "{}", report);
+
+        // A header that does not descend from the previous one breaks the chain, not the MPT checks
+        inputs.header_chain.remove(0);
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert_eq!(report.outcome(Check::HeaderChain).status, CheckStatus::Failed);
+        assert_eq!(report.failures().count(), 1, "{}", report);
+    }
+
+    #[test]
+    fn test_state_root() {
+        // A consistent trie for another balance, under the original header
+        let mut inputs = valid_inputs();
+        let address_hash = compute_burn_address_hash(M31::from(BURN_KEY), inputs.reveal_amount, inputs.burn_extra_commitment);
+        inputs.layers = mock_account_proof(&address_hash, inputs.actual_balance + U256::from(1), 3).layers;
+        let report = precheck(&inputs, &CircuitParams::default());
+        let state_root = report.outcome(Check::StateRoot);
+        assert_eq!(state_root.status, CheckStatus::Failed);
+        assert!(state_root.detail.contains("block 19000000"), "{}", state_root.detail);
+        assert_eq!(report.outcome(Check::BurnAddress).status, CheckStatus::Passed);
+    }
+
+    #[test]
+    fn test_mpt() {
+        let mut inputs = valid_inputs();
+        inputs.actual_balance -= U256::from(1);
+        inputs.intended_balance = inputs.actual_balance;
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert_eq!(report.outcome(Check::Mpt).status, CheckStatus::Failed);
+        assert_eq!(report.failures().count(), 1);
+
+        // A middle layer that is not committed to by its parent
+        let mut inputs = valid_inputs();
+        inputs.layers[1][40] ^= 1;
+        assert_eq!(status(&inputs, Check::Mpt), CheckStatus::Failed);
+    }
+
+    #[test]
+    fn test_burn_address() {
+        let mut inputs = valid_inputs();
+        inputs.num_leaf_address_nibbles -= 1;
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert_eq!(report.outcome(Check::BurnAddress).status, CheckStatus::Failed);
+        assert_eq!(report.failures().count(), 1);
+
+        // Another extra commitment derives another burn address
+        let mut inputs = valid_inputs();
+        inputs.burn_extra_commitment = M31::from(101);
+        assert_eq!(status(&inputs, Check::BurnAddress), CheckStatus::Failed);
+    }
+
+    #[test]
+    fn test_funded_address() {
+        let mut inputs = valid_inputs();
+        let funded = compute_burn_address(M31::from(BURN_KEY), inputs.reveal_amount, inputs.burn_extra_commitment);
+        inputs.expected_burn_address = Some(funded);
+        let report = precheck(&inputs, &CircuitParams::default());
+        assert!(report.passed(), "{}", report);
+
+        // Proving with another reveal amount than the address was funded with names the address,
+        // ahead of the PoW and trie checks it also breaks
+        inputs.reveal_amount -= U256::from(1);
+        let report = precheck(&inputs, &CircuitParams::default());
+        let outcome = report.outcome(Check::FundedAddress);
+        assert_eq!(outcome.status, CheckStatus::Failed);
+        assert!(outcome.detail.contains(&format_checksummed(funded)), "{}", outcome.detail);
+        assert_eq!(report.failures().next().unwrap().check, Check::FundedAddress);
+
+        // Without an expected address there is nothing to compare
+        inputs.expected_burn_address = None;
+        assert_eq!(status(&inputs, Check::FundedAddress), CheckStatus::Passed);
+    }
+}
@@ -0,0 +1,275 @@
+// Sealed witness files
+// A burn witness encrypted to the X25519 key of the box that proves it, so the file can sit on
+// disk or travel to that box without exposing the burn key. Each seal draws an ephemeral X25519
+// key; the shared secret goes through SHA3-256 with a label per box to give one ChaCha20Poly1305
+// key per box. Every key encrypts exactly once, so the nonce is fixed at zero.
+//
+// The burn key always has a box of its own. The other fields are either sealed in a second box
+// or left in the clear (`SealPolicy::KeyOnly`), for operators that let the prover see balances
+// and MPT data but not the key. Either way both boxes authenticate the version, the ephemeral key
+// and any clear fields, so none of them can be altered without the witness failing to open.
+
+use alloy_primitives::{Bytes, B256};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+
+use crate::circuits::proof_of_burn::ProofOfBurnInputs;
+use crate::field::M31;
+use crate::platform::secure_random_bytes;
+use crate::secret::Secret;
+
+/// Format version of `SealedWitness`
+pub const SEALED_WITNESS_VERSION: u8 = 1;
+
+/// Domain separation of the box keys from any other use of the shared secret
+const KDF_DOMAIN: &[u8] = b"pob-stwo sealed witness v1";
+
+/// Which fields `seal_with_policy` encrypts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealPolicy {
+    /// The burn key and every other field
+    All,
+    /// Only the burn key; balances, MPT layers and headers stay readable
+    KeyOnly,
+}
+
+/// Burn inputs sealed to a prover's X25519 public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SealedWitness {
+    pub version: u8,
+    /// Public half of the key the sealer drew for this witness
+    pub ephemeral_key: B256,
+    /// The burn key, encrypted
+    pub burn_key: Bytes,
+    /// Every input but the burn key
+    pub inputs: SealedInputs,
+}
+
+/// The inputs other than the burn key, encrypted or in the clear
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SealedInputs {
+    Sealed(Bytes),
+    Visible(serde_json::Value),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SealError {
+    #[error("Unsupported sealed witness version {found} (expected {SEALED_WITNESS_VERSION})")]
+    Version { found: u8 },
+
+    #[error("Sealed witness does not open with this key, or was altered")]
+    Decrypt,
+
+    #[error("Sealed witness holds malformed inputs: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A fresh X25519 secret key for receiving sealed witnesses
+pub fn generate_secret() -> StaticSecret {
+    StaticSecret::from(secure_random_bytes::<32>())
+}
+
+/// Seal every field of `inputs` to `recipient`
+pub fn seal(inputs: &ProofOfBurnInputs, recipient: &PublicKey) -> SealedWitness {
+    seal_with_policy(inputs, recipient, SealPolicy::All)
+}
+
+/// Seal `inputs` to `recipient`, leaving the fields `policy` does not cover in the clear
+pub fn seal_with_policy(
+    inputs: &ProofOfBurnInputs,
+    recipient: &PublicKey,
+    policy: SealPolicy,
+) -> SealedWitness {
+    let ephemeral = generate_secret();
+    let ephemeral_key = B256::from(PublicKey::from(&ephemeral).to_bytes());
+    let shared = Zeroizing::new(ephemeral.diffie_hellman(recipient).to_bytes());
+    let keys = BoxKeys::derive(&shared, &ephemeral_key, recipient);
+
+    // The key is serialized on its own; the other fields around a zero placeholder, dropped
+    let burn_key = Zeroizing::new(serde_json::to_vec(&inputs.burn_key).expect("M31 serializes"));
+    let without_key = ProofOfBurnInputs { burn_key: Secret::new(M31::zero()), ..inputs.clone() };
+    let mut fields = serde_json::to_value(&without_key).expect("burn inputs serialize");
+    fields.as_object_mut().expect("burn inputs are an object").remove("burn_key");
+
+    let visible = (policy == SealPolicy::KeyOnly).then_some(&fields);
+    let aad = associated_data(SEALED_WITNESS_VERSION, &ephemeral_key, visible);
+    let inputs = match policy {
+        SealPolicy::All => {
+            let plaintext = serde_json::to_vec(&fields).expect("JSON values serialize");
+            SealedInputs::Sealed(encrypt(&keys.inputs, &plaintext, &aad))
+        }
+        SealPolicy::KeyOnly => SealedInputs::Visible(fields),
+    };
+    SealedWitness {
+        version: SEALED_WITNESS_VERSION,
+        ephemeral_key,
+        burn_key: encrypt(&keys.burn_key, &burn_key, &aad),
+        inputs,
+    }
+}
+
+/// Open `sealed` with the recipient's secret key
+pub fn unseal(
+    sealed: &SealedWitness,
+    secret: &StaticSecret,
+) -> Result<ProofOfBurnInputs, SealError> {
+    if sealed.version != SEALED_WITNESS_VERSION {
+        return Err(SealError::Version { found: sealed.version });
+    }
+    let ephemeral = PublicKey::from(sealed.ephemeral_key.0);
+    let shared = Zeroizing::new(secret.diffie_hellman(&ephemeral).to_bytes());
+    let keys = BoxKeys::derive(&shared, &sealed.ephemeral_key, &PublicKey::from(secret));
+    let visible = match &sealed.inputs {
+        SealedInputs::Sealed(_) => None,
+        SealedInputs::Visible(fields) => Some(fields),
+    };
+    let aad = associated_data(sealed.version, &sealed.ephemeral_key, visible);
+
+    let burn_key = Zeroizing::new(decrypt(&keys.burn_key, &sealed.burn_key, &aad)?);
+    let burn_key: Secret<M31> = serde_json::from_slice(&burn_key)?;
+    let mut fields = match &sealed.inputs {
+        SealedInputs::Sealed(ciphertext) => {
+            serde_json::from_slice(&decrypt(&keys.inputs, ciphertext, &aad)?)?
+        }
+        SealedInputs::Visible(fields) => fields.clone(),
+    };
+    // Deserialized around a placeholder, so the key never sits in a JSON value
+    if let Some(fields) = fields.as_object_mut() {
+        fields.insert("burn_key".to_string(), serde_json::Value::from(0u32));
+    }
+    let inputs: ProofOfBurnInputs = serde_json::from_value(fields)?;
+    Ok(ProofOfBurnInputs { burn_key, ..inputs })
+}
+
+/// One ChaCha20Poly1305 key per box
+struct BoxKeys {
+    burn_key: Zeroizing<[u8; 32]>,
+    inputs: Zeroizing<[u8; 32]>,
+}
+
+impl BoxKeys {
+    fn derive(shared: &[u8; 32], ephemeral_key: &B256, recipient: &PublicKey) -> Self {
+        let derive = |label: &[u8]| {
+            let mut hasher = Sha3_256::new();
+            let parts: [&[u8]; 5] =
+                [KDF_DOMAIN, label, shared, ephemeral_key.as_slice(), recipient.as_bytes()];
+            for part in parts {
+                hasher.update(part);
+            }
+            Zeroizing::new(<[u8; 32]>::from(hasher.finalize()))
+        };
+        Self { burn_key: derive(b"burn_key"), inputs: derive(b"inputs") }
+    }
+}
+
+/// What both boxes authenticate: the version, the ephemeral key and the clear fields, if any
+fn associated_data(
+    version: u8,
+    ephemeral_key: &B256,
+    visible: Option<&serde_json::Value>,
+) -> Vec<u8> {
+    let mut aad = vec![version];
+    aad.extend_from_slice(ephemeral_key.as_slice());
+    if let Some(fields) = visible {
+        aad.extend(serde_json::to_vec(fields).expect("JSON values serialize"));
+    }
+    aad
+}
+
+fn encrypt(key: &[u8; 32], plaintext: &[u8], aad: &[u8]) -> Bytes {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let payload = Payload { msg: plaintext, aad };
+    let ciphertext = cipher.encrypt(&Nonce::default(), payload).expect("witness fits one message");
+    ciphertext.into()
+}
+
+fn decrypt(key: &[u8; 32], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>, SealError> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let payload = Payload { msg: ciphertext, aad };
+    cipher.decrypt(&Nonce::default(), payload).map_err(|_| SealError::Decrypt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::small_burn;
+
+    fn keypair() -> (StaticSecret, PublicKey) {
+        let secret = generate_secret();
+        let public = PublicKey::from(&secret);
+        (secret, public)
+    }
+
+    fn json(inputs: &ProofOfBurnInputs) -> serde_json::Value {
+        serde_json::to_value(inputs).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_under_each_policy() {
+        let (inputs, _) = small_burn();
+        let (secret, public) = keypair();
+        for policy in [SealPolicy::All, SealPolicy::KeyOnly] {
+            let sealed = seal_with_policy(&inputs, &public, policy);
+            match &sealed.inputs {
+                SealedInputs::Sealed(_) => assert_eq!(policy, SealPolicy::All),
+                SealedInputs::Visible(fields) => {
+                    assert_eq!(policy, SealPolicy::KeyOnly);
+                    assert_eq!(fields["actual_balance"], json(&inputs)["actual_balance"]);
+                    assert!(fields.get("burn_key").is_none());
+                }
+            }
+            let file = serde_json::to_string(&sealed).unwrap();
+
+            let opened = unseal(&serde_json::from_str(&file).unwrap(), &secret).unwrap();
+            assert_eq!(json(&opened), json(&inputs), "{policy:?}");
+        }
+
+        // Each seal draws its own ephemeral key
+        assert_ne!(seal(&inputs, &public).ephemeral_key, seal(&inputs, &public).ephemeral_key);
+    }
+
+    #[test]
+    fn test_tampered_or_misaddressed_witness_does_not_open() {
+        let (inputs, _) = small_burn();
+        let (secret, public) = keypair();
+        let sealed = seal(&inputs, &public);
+
+        let (other, _) = keypair();
+        assert!(matches!(unseal(&sealed, &other), Err(SealError::Decrypt)));
+
+        let mut flipped = sealed.clone();
+        let mut burn_key = flipped.burn_key.to_vec();
+        burn_key[0] ^= 1;
+        flipped.burn_key = burn_key.into();
+        assert!(matches!(unseal(&flipped, &secret), Err(SealError::Decrypt)));
+
+        let SealedInputs::Sealed(ciphertext) = &sealed.inputs else { unreachable!() };
+        let mut truncated = sealed.clone();
+        truncated.inputs = SealedInputs::Sealed(ciphertext[..ciphertext.len() - 1].to_vec().into());
+        assert!(matches!(unseal(&truncated, &secret), Err(SealError::Decrypt)));
+
+        let mut rekeyed = sealed.clone();
+        rekeyed.ephemeral_key = B256::from(public.to_bytes());
+        assert!(matches!(unseal(&rekeyed, &secret), Err(SealError::Decrypt)));
+
+        let future = SealedWitness { version: SEALED_WITNESS_VERSION + 1, ..sealed };
+        assert!(matches!(unseal(&future, &secret), Err(SealError::Version { found: 2 })));
+    }
+
+    #[test]
+    fn test_visible_fields_are_authenticated() {
+        let (inputs, _) = small_burn();
+        let (secret, public) = keypair();
+        let mut sealed = seal_with_policy(&inputs, &public, SealPolicy::KeyOnly);
+
+        // Raising the clear balance breaks the key's box, so the altered inputs never come out
+        let SealedInputs::Visible(fields) = &mut sealed.inputs else { unreachable!() };
+        fields["intended_balance"] = serde_json::Value::from("1");
+        assert!(matches!(unseal(&sealed, &secret), Err(SealError::Decrypt)));
+    }
+}
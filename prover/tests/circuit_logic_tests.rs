@@ -64,7 +64,7 @@ mod proof_of_burn_logic_tests {
 
     #[test]
     fn test_burn_address_derivation() {
-        // burnAddress = Poseidon4(BURN_ADDRESS_PREFIX, burnKey, revealAmount, burnExtraCommitment)
+        // v1 burnAddress = Poseidon4(BURN_ADDRESS_PREFIX, burnKey, revealAmount, burnExtraCommitment)
         let prefix = poseidon_burn_address_prefix();
         
         for i in 0..500 {
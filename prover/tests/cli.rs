@@ -0,0 +1,243 @@
+// CLI end-to-end tests
+// Run the pob-prover binary the way a user does: arguments, files on disk, stdout, stderr and
+// exit status. Burn inputs come from `test_utils::gen_pob_inputs`, a mock trie the burn address
+// is really in, so generate-burn passes its pre-check and proves.
+
+use std::path::PathBuf;
+
+use alloy_primitives::U256;
+use assert_cmd::Command;
+use proof_of_burn_stwo::field::M31;
+use proof_of_burn_stwo::prover::ProofEnvelope;
+use proof_of_burn_stwo::test_utils::{gen_pob_inputs, InputProfile};
+use proof_of_burn_stwo::utils::burn_address::{compute_burn_address, format_checksummed};
+
+/// A fresh directory for one test's files
+fn work_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("pob_cli_{test}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn pob_prover() -> Command {
+    Command::cargo_bin("pob-prover").unwrap()
+}
+
+fn stdout(output: &std::process::Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &std::process::Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+/// Write valid burn inputs, prove them with generate-burn and return the envelope's path
+fn generate_burn_envelope(dir: &std::path::Path) -> PathBuf {
+    let input = dir.join("input.json");
+    let envelope = dir.join("envelope.bin");
+    let inputs = gen_pob_inputs(7, InputProfile::TinyBalances);
+    std::fs::write(&input, serde_json::to_vec(&inputs).unwrap()).unwrap();
+
+    let output = pob_prover()
+        .arg("generate-burn")
+        .arg("--input")
+        .arg(&input)
+        .arg("--output")
+        .arg(dir.join("proof.json"))
+        .arg("--emit-envelope")
+        .arg(&envelope)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    // Results on stdout, progress on stderr
+    assert!(stdout(&output).contains("Public values extracted"));
+    assert!(!stdout(&output).contains("Generating complete STWO proof"));
+    envelope
+}
+
+#[test]
+fn test_generate_burn_then_verify() {
+    let dir = work_dir("roundtrip");
+    let envelope = generate_burn_envelope(&dir);
+
+    let output = pob_prover()
+        .args(["verify", "--proof-type", "burn", "--proof"])
+        .arg(&envelope)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let printed = stdout(&output);
+    assert!(printed.contains("Proof envelope structure is valid"));
+    // The envelope carries its component claims, so the proof itself is verified
+    assert!(printed.contains("[pass] proof"), "{printed}");
+}
+
+#[test]
+fn test_verify_tampered_envelope_exits_4() {
+    let dir = work_dir("tampered");
+    let envelope_path = generate_burn_envelope(&dir);
+
+    let mut envelope = ProofEnvelope::from_bytes_auto(&std::fs::read(&envelope_path).unwrap()).unwrap();
+    envelope.public_inputs[1] += U256::from(1);
+    std::fs::write(&envelope_path, envelope.to_bytes().unwrap()).unwrap();
+
+    let output = pob_prover()
+        .args(["verify", "--proof-type", "burn", "--proof"])
+        .arg(&envelope_path)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+    let message = stderr(&output);
+    assert!(message.contains("Proof is invalid: public_values check failed"), "{message}");
+    assert!(stdout(&output).contains("[skip] proof"));
+}
+
+#[test]
+fn test_verify_envelope_without_claims_exits_4() {
+    let dir = work_dir("no_claims");
+    let envelope_path = generate_burn_envelope(&dir);
+
+    // Without its claims a current envelope could only be checked for its structure
+    let mut envelope = ProofEnvelope::from_bytes_auto(&std::fs::read(&envelope_path).unwrap()).unwrap();
+    envelope.claims = None;
+    std::fs::write(&envelope_path, envelope.to_bytes().unwrap()).unwrap();
+
+    for statement in [false, true] {
+        let mut command = pob_prover();
+        command.args(["verify", "--proof-type", "burn", "--proof"]).arg(&envelope_path);
+        if statement {
+            let path = dir.join("statement.json");
+            let expected = envelope.statement.as_ref().unwrap();
+            std::fs::write(&path, serde_json::to_vec(expected).unwrap()).unwrap();
+            command.arg("--statement").arg(&path);
+        }
+        let output = command.output().unwrap();
+        assert_eq!(output.status.code(), Some(4), "{}", stderr(&output));
+        let message = stderr(&output);
+        assert!(message.contains("carries no component claims"), "{message}");
+        assert!(!stdout(&output).contains("valid"), "{}", stdout(&output));
+    }
+}
+
+#[test]
+fn test_generate_with_missing_input_exits_2() {
+    let dir = work_dir("missing_input");
+    let missing = dir.join("does_not_exist.json");
+
+    let output = pob_prover()
+        .arg("generate-burn")
+        .arg("--input")
+        .arg(&missing)
+        .arg("--output")
+        .arg(dir.join("proof.json"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let message = stderr(&output);
+    assert!(message.contains("Input file does not exist"), "{message}");
+    assert!(message.contains("does_not_exist.json"), "{message}");
+    assert!(stdout(&output).is_empty());
+}
+
+#[test]
+fn test_error_format_json() {
+    let dir = work_dir("error_format_json");
+    let output = pob_prover()
+        .args(["--error-format", "json", "generate-spend", "--input"])
+        .arg(dir.join("does_not_exist.json"))
+        .arg("--output")
+        .arg(dir.join("proof.json"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    // Logs may precede it; the error is the last line of stderr
+    let message = stderr(&output);
+    let error: serde_json::Value =
+        serde_json::from_str(message.lines().last().unwrap()).unwrap_or_else(|e| panic!("{e}: {message}"));
+    assert_eq!(error["code"], "E_INPUT_MISSING");
+    assert!(error["message"].as_str().unwrap().contains("does_not_exist.json"));
+}
+
+#[test]
+fn test_info_json_output_parses() {
+    let output = pob_prover().args(["info", "--output-format", "json"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout is one JSON document");
+    assert!(info["circuit_params"]["max_num_layers"].is_u64());
+    assert!(info["air"]["Proof of Burn"]["n_trace_columns"].as_u64().unwrap() > 0);
+    assert!(info.get("analysis").is_none());
+}
+
+#[test]
+fn test_derive_address_matches_library() {
+    let (burn_key, reveal_amount, extra) = (12345u32, U256::from(500_000_000_000_000_000u64), 100u32);
+
+    let output = pob_prover()
+        .args(["derive-address", "--burn-key", &burn_key.to_string()])
+        .args(["--reveal-amount", &reveal_amount.to_string()])
+        .args(["--burn-extra-commitment", &extra.to_string()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    let expected = compute_burn_address(M31::from(burn_key), reveal_amount, M31::from(extra));
+    let printed = stdout(&output);
+    let address = printed
+        .lines()
+        .find_map(|line| line.strip_prefix("Burn address:"))
+        .map(str::trim)
+        .expect("derive-address prints the burn address");
+    assert_eq!(address, format_checksummed(expected));
+}
+
+#[test]
+fn test_sealed_witness_opens_only_with_its_key() {
+    let dir = work_dir("sealed_witness");
+    let input = dir.join("input.json");
+    std::fs::write(&input, serde_json::to_vec(&gen_pob_inputs(7, InputProfile::TinyBalances)).unwrap())
+        .unwrap();
+
+    let keygen = |name: &str| {
+        let output = pob_prover().args(["witness-key", "--output"]).arg(dir.join(name)).output().unwrap();
+        assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+        let printed = stdout(&output);
+        let public = printed.lines().find_map(|line| line.strip_prefix("Public key:"));
+        public.expect("witness-key prints the public key").trim().to_string()
+    };
+    let public = keygen("prover.key");
+    keygen("other.key");
+    // An existing key is never overwritten
+    let again = pob_prover().args(["witness-key", "--output"]).arg(dir.join("prover.key")).output().unwrap();
+    assert_ne!(again.status.code(), Some(0));
+
+    let sealed = dir.join("sealed.json");
+    let output = pob_prover()
+        .args(["seal-witness", "--seal-to", &public, "--input"])
+        .arg(&input)
+        .arg("--output")
+        .arg(&sealed)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(!std::fs::read_to_string(&sealed).unwrap().contains("actual_balance"));
+
+    let precheck = |key: &str| {
+        pob_prover()
+            .args(["--error-format", "json", "precheck", "--input"])
+            .arg(&sealed)
+            .arg("--unseal-key")
+            .arg(dir.join(key))
+            .output()
+            .unwrap()
+    };
+    let opened = precheck("prover.key");
+    assert_eq!(opened.status.code(), Some(0), "{}", stderr(&opened));
+
+    let refused = precheck("other.key");
+    let message = stderr(&refused);
+    let error: serde_json::Value =
+        serde_json::from_str(message.lines().last().unwrap()).unwrap_or_else(|e| panic!("{e}: {message}"));
+    assert_eq!(error["code"], "E_WITNESS_SEAL");
+}
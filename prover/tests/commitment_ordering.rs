@@ -0,0 +1,115 @@
+// Commitment ordering audit
+// The verifier contract reads the main trace and composition roots from fixed slots of the
+// calldata's `commitments`, `CONTRACT_TREE_ORDER`. These tests label the roots of real proofs with
+// `ProofCommitments`, swap two labeled roots and require verification to fail, which pins every
+// slot to the tree stwo actually checks there. Should stwo ever commit in another order, the
+// layout, the calldata or these swaps stop agreeing and a test here fails first.
+
+use alloy_primitives::B256;
+use proof_of_burn_stwo::constants::CircuitParams;
+use proof_of_burn_stwo::prover::solidity::{
+    contract_commitments, decode_proof_calldata, encode_proof_calldata,
+};
+use proof_of_burn_stwo::prover::{
+    CommitmentLayout, PobProver, ProofCommitments, TreeRole, CONTRACT_TREE_ORDER,
+};
+use proof_of_burn_stwo::test_utils::{gen_pob_inputs, gen_spend_inputs, InputProfile};
+use proof_of_burn_stwo::{
+    prove_proof_of_burn, prove_spend, verify_proof_of_burn, verify_spend, StarkConfig,
+};
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_hash::Blake2sHash;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+
+const SEED: u64 = 3;
+const LOG_N_ROWS: u32 = 6;
+
+/// Pairs of trees whose roots are swapped; trace and composition are the pair the contract reads
+const SWAPS: [(TreeRole, TreeRole); 4] = [
+    (TreeRole::Trace, TreeRole::Composition),
+    (TreeRole::Preprocessed, TreeRole::Trace),
+    (TreeRole::Trace, TreeRole::Interaction),
+    (TreeRole::Interaction, TreeRole::Composition),
+];
+
+/// `proof` with the roots of `a` and `b` trading places
+fn swapped(
+    proof: &StarkProof<Blake2sMerkleHasher>,
+    layout: &CommitmentLayout,
+    (a, b): (TreeRole, TreeRole),
+) -> StarkProof<Blake2sMerkleHasher> {
+    let mut proof = proof.clone();
+    let (a, b) = (layout.position(a).unwrap(), layout.position(b).unwrap());
+    proof.0.commitments.swap(a, b);
+    proof
+}
+
+/// The labeled roots sit in the contract's slots
+fn assert_contract_order(proof: &StarkProof<Blake2sMerkleHasher>, layout: &CommitmentLayout) {
+    assert_eq!(layout.roles(), CONTRACT_TREE_ORDER);
+    let commitments = ProofCommitments::from_layout(proof, layout).unwrap();
+    let slot = |role: TreeRole| role.contract_slot().unwrap();
+    assert_eq!(proof.commitments[slot(TreeRole::Preprocessed)], commitments.preprocessed);
+    assert_eq!(proof.commitments[slot(TreeRole::Trace)], commitments.trace);
+    assert_eq!(Some(proof.commitments[slot(TreeRole::Interaction)]), commitments.interaction);
+    assert_eq!(proof.commitments[slot(TreeRole::Composition)], commitments.composition);
+}
+
+#[test]
+fn test_burn_roots_are_bound_to_their_slots() {
+    let inputs = gen_pob_inputs(SEED, InputProfile::TinyBalances);
+    let (components, proof) = prove_proof_of_burn(&inputs, LOG_N_ROWS, StarkConfig::default())
+        .expect("Failed to generate proof");
+    assert_contract_order(&proof, &components.layout);
+    assert!(verify_proof_of_burn(&components, proof.clone()).is_ok());
+
+    for swap in SWAPS {
+        let result = verify_proof_of_burn(&components, swapped(&proof, &components.layout, swap));
+        assert!(result.is_err(), "burn proof verified with {swap:?} roots swapped");
+    }
+}
+
+#[test]
+fn test_spend_roots_are_bound_to_their_slots() {
+    let inputs = gen_spend_inputs(SEED, InputProfile::TinyBalances);
+    let (components, proof) = prove_spend(&inputs, LOG_N_ROWS, StarkConfig::default())
+        .expect("Failed to generate proof");
+    assert_contract_order(&proof, &components.layout);
+    assert!(verify_spend(&components, proof.clone()).is_ok());
+
+    for swap in SWAPS {
+        let result = verify_spend(&components, swapped(&proof, &components.layout, swap));
+        assert!(result.is_err(), "spend proof verified with {swap:?} roots swapped");
+    }
+}
+
+#[test]
+fn test_calldata_places_roots_by_role() {
+    let config = StarkConfig { deterministic: true, ..StarkConfig::default() };
+    let prover = PobProver::new(config, CircuitParams::default()).with_log_n_rows(LOG_N_ROWS);
+    let mut envelope = prover
+        .prove_burn(&gen_pob_inputs(SEED, InputProfile::TinyBalances))
+        .unwrap()
+        .envelope;
+
+    let proof = envelope.stark_proof::<Blake2sMerkleHasher>().unwrap();
+    let commitments = ProofCommitments::from_layout(&proof, &envelope.layout).unwrap();
+    let root = |hash: Blake2sHash| B256::from(hash.0);
+    let in_slots = [
+        root(commitments.preprocessed),
+        root(commitments.trace),
+        root(commitments.interaction.unwrap()),
+        root(commitments.composition),
+    ];
+    let decoded = decode_proof_calldata(&encode_proof_calldata(&envelope)).unwrap();
+    assert_eq!(decoded.commitments, in_slots);
+
+    // A root the contract has no slot for is left out rather than shifting the others
+    let mut layout = envelope.layout.clone();
+    let mut proof = proof;
+    layout.insert_auxiliary_tree(&mut proof, 1, Blake2sHash([0x5a; 32]));
+    envelope.layout = layout;
+    envelope.commitments = proof.commitments.iter().map(|&hash| root(hash)).collect();
+    assert_eq!(envelope.commitments.len(), 5);
+    assert_eq!(contract_commitments(&envelope), in_slots);
+}
@@ -0,0 +1,197 @@
+//! Structure snapshots of burn and spend envelopes
+//!
+//! Proves a fixed burn and spend in deterministic mode and compares the shape of their envelopes
+//! against tests/fixtures/envelope/<circuit>.json: the byte-format header, every field name, the
+//! type of every value and the length of every list, and for the embedded `StarkProof` its
+//! fields, configuration and number of roots. Proof bytes themselves are not compared.
+//!
+//! A snapshot records the `ENVELOPE_VERSION` it was taken at, so the two change together: a
+//! format change fails here until the version is bumped and the snapshots are rewritten with
+//!
+//! ```text
+//! UPDATE_ENVELOPE_SNAPSHOTS=1 cargo test --test envelope_snapshots
+//! ```
+//!
+//! which refuses to rewrite a changed structure under an unchanged version.
+
+use std::path::PathBuf;
+
+use alloy_primitives::U256;
+use proof_of_burn_stwo::circuits::proof_of_burn::ProofOfBurnInputs;
+use proof_of_burn_stwo::circuits::spend::SpendInputs;
+use proof_of_burn_stwo::constants::{CircuitParams, MAINNET_CHAIN_ID};
+use proof_of_burn_stwo::prover::{PobProver, ProofEnvelope, StarkConfig, ENVELOPE_VERSION};
+use proof_of_burn_stwo::secret::Secret;
+use proof_of_burn_stwo::M31;
+use serde_json::{json, Value};
+
+const LOG_N_ROWS: u32 = 5;
+
+fn prover() -> PobProver {
+    let config = StarkConfig { deterministic: true, ..StarkConfig::default() };
+    PobProver::new(config, CircuitParams::default()).with_log_n_rows(LOG_N_ROWS)
+}
+
+fn burn_envelope() -> ProofEnvelope {
+    let inputs = ProofOfBurnInputs {
+        burn_key: Secret::new(M31::from(12345)),
+        actual_balance: U256::from(1000000u64),
+        intended_balance: U256::from(1000000u64),
+        reveal_amount: U256::from(500000u64),
+        burn_extra_commitment: M31::from(100),
+        layers: vec![vec![0u8; 100]],
+        block_header: vec![0u8; 643],
+        header_chain: Vec::new(),
+        chain_id: MAINNET_CHAIN_ID,
+        valid_until_block: None,
+        num_leaf_address_nibbles: 50,
+        byte_security_relax: 0,
+        proof_extra_commitment: M31::from(200),
+        expected_burn_address: None,
+    };
+    prover().prove_burn(&inputs).unwrap().envelope
+}
+
+fn spend_envelope() -> ProofEnvelope {
+    let inputs = SpendInputs {
+        burn_key: Secret::new(M31::from(12345)),
+        balance: U256::from(1000),
+        withdrawn_balance: U256::from(400),
+        extra_commitment: M31::from(100),
+        recipient: None,
+        chain_id: MAINNET_CHAIN_ID,
+        expected_coin: None,
+    };
+    prover().prove_spend(&inputs).unwrap().envelope
+}
+
+/// Type of a JSON value, with the length of every list
+///
+/// 0x-prefixed hex strings are `"hex"` whatever their length: uint256 values serialize without
+/// leading zeros.
+fn shape(value: &Value) -> Value {
+    match value {
+        Value::Null => json!("null"),
+        Value::Bool(_) => json!("bool"),
+        Value::Number(_) => json!("number"),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some(digits) if digits.bytes().all(|b| b.is_ascii_hexdigit()) => json!("hex"),
+            _ => json!("string"),
+        },
+        Value::Array(items) => {
+            let shapes: Vec<Value> = items.iter().map(shape).collect();
+            match shapes.first() {
+                Some(first) if shapes.iter().all(|s| s == first) => json!({ "len": items.len(), "item": first }),
+                _ => json!({ "len": items.len(), "items": shapes }),
+            }
+        }
+        Value::Object(fields) => Value::Object(fields.iter().map(|(name, value)| (name.clone(), shape(value))).collect()),
+    }
+}
+
+/// Everything the snapshot compares
+fn structure(envelope: &ProofEnvelope) -> Value {
+    let bytes = envelope.to_bytes().unwrap();
+    let compressed = envelope.to_bytes_compressed().unwrap();
+    let proof: Value = serde_json::from_slice(&envelope.proof).unwrap();
+    let mut proof_fields: Vec<&String> = proof.as_object().expect("StarkProof serializes as an object").keys().collect();
+    proof_fields.sort();
+    json!({
+        "envelope_version": envelope.version,
+        "header": hex::encode(&bytes[..5]),
+        "header_compressed": hex::encode(&compressed[..5]),
+        "circuit": envelope.circuit,
+        "layout": envelope.layout,
+        "merkle_hasher": envelope.merkle_hasher,
+        "statement_bytes": envelope
+            .statement
+            .map(|statement| statement.to_bytes().len())
+            .or(envelope.spend_statement.map(|statement| statement.to_bytes().len())),
+        "envelope": shape(&serde_json::to_value(envelope).unwrap()),
+        "proof": {
+            "fields": proof_fields,
+            "config": proof["config"],
+            "commitments": proof["commitments"].as_array().map(Vec::len),
+        },
+    })
+}
+
+fn snapshot_path(circuit: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/envelope")
+        .join(format!("{circuit}.json"))
+}
+
+fn read_snapshot(circuit: &str) -> Option<Value> {
+    let snapshot = std::fs::read_to_string(snapshot_path(circuit)).ok()?;
+    Some(serde_json::from_str(&snapshot).unwrap())
+}
+
+/// `value` without its `envelope_version`
+fn unversioned(value: &Value) -> Value {
+    let mut value = value.clone();
+    value.as_object_mut().unwrap().remove("envelope_version");
+    value
+}
+
+fn check_snapshot(circuit: &str, envelope: &ProofEnvelope) {
+    let generated = structure(envelope);
+    let snapshot = read_snapshot(circuit);
+    let changed = snapshot.as_ref().is_none_or(|snapshot| unversioned(snapshot) != unversioned(&generated));
+
+    if std::env::var_os("UPDATE_ENVELOPE_SNAPSHOTS").is_some() {
+        if let Some(snapshot) = &snapshot {
+            assert!(
+                !changed || snapshot["envelope_version"] != ENVELOPE_VERSION,
+                "{circuit} envelope structure changed but ENVELOPE_VERSION is still {ENVELOPE_VERSION}; bump it \
+                 (and document the change) before updating the snapshot"
+            );
+        }
+        let path = snapshot_path(circuit);
+        std::fs::write(&path, serde_json::to_string_pretty(&generated).unwrap() + "\n").unwrap();
+        return;
+    }
+
+    let path = snapshot_path(circuit);
+    let snapshot = snapshot.unwrap_or_else(|| panic!("Failed to read {}", path.display()));
+    assert!(
+        !changed,
+        "{circuit} envelope structure differs from {}; bump ENVELOPE_VERSION and rerun with \
+         UPDATE_ENVELOPE_SNAPSHOTS=1 if the format change is intended\n{}",
+        path.display(),
+        serde_json::to_string_pretty(&generated).unwrap()
+    );
+    assert_eq!(
+        snapshot["envelope_version"], ENVELOPE_VERSION,
+        "ENVELOPE_VERSION changed without a new {circuit} snapshot; rerun with UPDATE_ENVELOPE_SNAPSHOTS=1"
+    );
+}
+
+#[test]
+fn test_burn_envelope_matches_snapshot() {
+    check_snapshot("burn", &burn_envelope());
+}
+
+#[test]
+fn test_spend_envelope_matches_snapshot() {
+    check_snapshot("spend", &spend_envelope());
+}
+
+#[test]
+fn test_snapshots_are_taken_at_one_version() {
+    // Both circuits share one envelope format, so their snapshots move together
+    let versions: Vec<Value> = ["burn", "spend"]
+        .into_iter()
+        .map(|circuit| read_snapshot(circuit).expect("snapshot exists")["envelope_version"].clone())
+        .collect();
+    assert_eq!(versions, [json!(ENVELOPE_VERSION), json!(ENVELOPE_VERSION)]);
+}
+
+#[test]
+fn test_shape_ignores_values() {
+    let a = json!({ "roots": ["0x01", "0xff"], "n": 1, "tag": "burn" });
+    let b = json!({ "roots": ["0x0200", "0x"], "n": 7, "tag": "spend" });
+    assert_eq!(shape(&a), shape(&b));
+    assert_eq!(shape(&a)["roots"], json!({ "len": 2, "item": "hex" }));
+    assert_ne!(shape(&a), shape(&json!({ "roots": ["0x01"], "n": 1, "tag": "burn" })));
+}
@@ -4,8 +4,10 @@
 use proof_of_burn_stwo::field::M31;
 use proof_of_burn_stwo::utils::poseidon::{poseidon2, poseidon3, poseidon4, u256_to_m31};
 use proof_of_burn_stwo::constants::{
-    poseidon_burn_address_prefix, poseidon_coin_prefix, poseidon_nullifier_prefix,
+    poseidon_burn_address_prefix, poseidon_coin_prefix, poseidon_nullifier_prefix, MAINNET_CHAIN_ID,
 };
+use proof_of_burn_stwo::circuits::spend::{SpendCircuit, SpendInputs, SpendOutputs};
+use proof_of_burn_stwo::secret::Secret;
 use alloy_primitives::U256;
 
 #[cfg(test)]
@@ -369,6 +371,47 @@ mod error_scenario_tests {
     }
 }
 
+#[cfg(test)]
+mod output_equality_tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn spend(burn_key: u32, balance: u64, withdrawn: u64) -> SpendOutputs {
+        SpendCircuit::new(SpendInputs {
+            burn_key: Secret::new(M31::from(burn_key)),
+            balance: U256::from(balance),
+            withdrawn_balance: U256::from(withdrawn),
+            extra_commitment: M31::from(100u32),
+            recipient: None,
+            chain_id: MAINNET_CHAIN_ID,
+            expected_coin: None,
+        })
+        .unwrap()
+        .compute_outputs()
+    }
+
+    #[test]
+    fn test_resubmitted_spend_has_equal_outputs() {
+        assert_eq!(spend(888888, 500000, 100000), spend(888888, 500000, 100000));
+        assert_ne!(spend(888888, 500000, 100000), spend(888888, 500000, 100001));
+        assert_ne!(spend(888888, 500000, 100000), spend(888889, 500000, 100000));
+    }
+
+    #[test]
+    fn test_spend_outputs_dedup() {
+        // A relayer dropping duplicate submissions keeps one per distinct spend
+        let submitted: Vec<SpendOutputs> = (0..200u64)
+            .map(|i| spend(271828 + (i % 50) as u32, 1_000_000, 1000 * (i % 50)))
+            .collect();
+        let unique: HashSet<&SpendOutputs> = submitted.iter().collect();
+        assert_eq!(unique.len(), 50);
+
+        let coins: HashSet<String> = unique.iter().map(|outputs| outputs.coin_hex()).collect();
+        assert_eq!(coins.len(), 50);
+        assert!(coins.iter().all(|coin| coin.starts_with("0x")));
+    }
+}
+
 #[cfg(test)]
 mod stress_tests {
     use super::*;
@@ -0,0 +1,223 @@
+// Randomized property tests for the field, hash and encoding layers
+// Complements the fixed-range loops in field_arithmetic_tests.rs; proptest shrinks any failure to
+// a minimal counterexample, which then belongs in a fixed unit test next to the code it broke
+
+use alloy_primitives::U256;
+use alloy_rlp::Decodable;
+use proof_of_burn_stwo::field::M31;
+use proof_of_burn_stwo::utils::poseidon::{m31_array_to_u256, poseidon4, u256_to_m31_array};
+use proof_of_burn_stwo::utils::rlp::{hex_prefix_decode, hex_prefix_encode, Account};
+use proptest::prelude::*;
+
+const P: u64 = M31::PRIME as u64;
+
+fn any_m31() -> impl Strategy<Value = M31> {
+    any::<u64>().prop_map(M31::from)
+}
+
+fn any_u256() -> impl Strategy<Value = U256> {
+    any::<[u64; 4]>().prop_map(U256::from_limbs)
+}
+
+#[cfg(test)]
+mod field_properties {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_u64_reduces(value in any::<u64>()) {
+            prop_assert_eq!(M31::from(value).value() as u64, value % P);
+        }
+
+        #[test]
+        fn from_i32_reduces(value in any::<i32>()) {
+            prop_assert_eq!(M31::from(value).value() as u64, (value as i64).rem_euclid(P as i64) as u64);
+        }
+
+        #[test]
+        fn addition_commutes_and_associates(a in any_m31(), b in any_m31(), c in any_m31()) {
+            prop_assert_eq!(a + b, b + a);
+            prop_assert_eq!((a + b) + c, a + (b + c));
+        }
+
+        #[test]
+        fn multiplication_commutes_and_associates(a in any_m31(), b in any_m31(), c in any_m31()) {
+            prop_assert_eq!(a * b, b * a);
+            prop_assert_eq!((a * b) * c, a * (b * c));
+        }
+
+        #[test]
+        fn multiplication_distributes(a in any_m31(), b in any_m31(), c in any_m31()) {
+            prop_assert_eq!(a * (b + c), a * b + a * c);
+        }
+
+        #[test]
+        fn identities_and_inverse(a in any_m31()) {
+            prop_assert_eq!(a + M31::zero(), a);
+            prop_assert_eq!(a * M31::one(), a);
+            prop_assert_eq!(a + (M31::zero() - a), M31::zero());
+        }
+
+        #[test]
+        fn results_stay_reduced(a in any_m31(), b in any_m31()) {
+            for result in [a + b, a - b, a * b] {
+                prop_assert!((result.value() as u64) < P);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod field_ops_properties {
+    use super::*;
+    use proof_of_burn_stwo::field::ops::simd::{
+        add_columns, add_columns_in_place, dot_columns, mul_scalar_column, mul_scalar_column_in_place,
+    };
+    use proof_of_burn_stwo::field::ops::{
+        add_slices, add_slices_in_place, dot, mul_scalar, mul_scalar_in_place,
+    };
+    use stwo_prover::core::fields::m31::BaseField;
+    use stwo_prover::prover::backend::simd::column::BaseColumn;
+    use stwo_prover::prover::backend::Column;
+
+    /// Two slices of one length, long enough to cover several packed vectors and a partial one
+    fn slice_pair() -> impl Strategy<Value = (Vec<M31>, Vec<M31>)> {
+        (0usize..80).prop_flat_map(|len| {
+            (prop::collection::vec(any_m31(), len), prop::collection::vec(any_m31(), len))
+        })
+    }
+
+    fn column(values: &[M31]) -> BaseColumn {
+        values.iter().map(|value| BaseField::from(value.value())).collect()
+    }
+
+    fn m31s(column: &BaseColumn) -> Vec<M31> {
+        column.to_cpu().into_iter().map(|value| M31::new(value.0)).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn add_matches_loop((a, b) in slice_pair()) {
+            let mut expected = Vec::new();
+            for (&x, &y) in a.iter().zip(&b) {
+                expected.push(x + y);
+            }
+            prop_assert_eq!(add_slices(&a, &b).unwrap(), expected.clone());
+            let mut in_place = a.clone();
+            add_slices_in_place(&mut in_place, &b).unwrap();
+            prop_assert_eq!(&in_place, &expected);
+
+            prop_assert_eq!(m31s(&add_columns(&column(&a), &column(&b)).unwrap()), expected.clone());
+            let mut in_place = column(&a);
+            add_columns_in_place(&mut in_place, &column(&b)).unwrap();
+            prop_assert_eq!(m31s(&in_place), expected);
+        }
+
+        #[test]
+        fn mul_scalar_matches_loop((a, _) in slice_pair(), scalar in any_m31()) {
+            let mut expected = Vec::new();
+            for value in &a {
+                expected.push(*value * scalar);
+            }
+            prop_assert_eq!(mul_scalar(&a, scalar), expected.clone());
+            let mut in_place = a.clone();
+            mul_scalar_in_place(&mut in_place, scalar);
+            prop_assert_eq!(&in_place, &expected);
+
+            let packed_scalar = BaseField::from(scalar.value());
+            prop_assert_eq!(m31s(&mul_scalar_column(&column(&a), packed_scalar)), expected.clone());
+            let mut in_place = column(&a);
+            mul_scalar_column_in_place(&mut in_place, packed_scalar);
+            prop_assert_eq!(m31s(&in_place), expected);
+        }
+
+        #[test]
+        fn dot_matches_loop((a, b) in slice_pair()) {
+            let mut expected = M31::zero();
+            for (&x, &y) in a.iter().zip(&b) {
+                expected = expected + x * y;
+            }
+            prop_assert_eq!(dot(&a, &b).unwrap(), expected);
+            prop_assert_eq!(dot_columns(&column(&a), &column(&b)).unwrap().0, expected.value());
+        }
+
+        #[test]
+        fn mismatched_lengths_are_errors(a in prop::collection::vec(any_m31(), 0..40), extra in 1usize..20) {
+            let b = vec![M31::one(); a.len() + extra];
+            prop_assert!(add_slices(&a, &b).is_err());
+            prop_assert!(dot(&a, &b).is_err());
+            prop_assert!(add_columns(&column(&a), &column(&b)).is_err());
+            prop_assert!(dot_columns(&column(&a), &column(&b)).is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod conversion_properties {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn u256_to_m31_array_recomposes(value in any_u256()) {
+            let chunks = u256_to_m31_array(value);
+            prop_assert!(chunks.iter().all(|c| (c.value() as u64) < P));
+            prop_assert_eq!(m31_array_to_u256(&chunks), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod poseidon_properties {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn poseidon4_deterministic(inputs in any::<[u64; 4]>()) {
+            let inputs = inputs.map(M31::from);
+            prop_assert_eq!(poseidon4(inputs), poseidon4(inputs));
+        }
+
+        #[test]
+        fn poseidon4_depends_on_order(
+            inputs in any::<[u64; 4]>(),
+            order in Just(vec![0usize, 1, 2, 3]).prop_shuffle(),
+        ) {
+            let inputs = inputs.map(M31::from);
+            let permuted = [inputs[order[0]], inputs[order[1]], inputs[order[2]], inputs[order[3]]];
+            prop_assume!(permuted != inputs);
+            prop_assert_ne!(poseidon4(permuted), poseidon4(inputs));
+        }
+    }
+}
+
+#[cfg(test)]
+mod encoding_properties {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn hex_prefix_roundtrip(nibbles in prop::collection::vec(0u8..16, 0..=64), leaf in any::<bool>()) {
+            let encoded = hex_prefix_encode(&nibbles, leaf);
+            prop_assert_eq!(encoded.len(), nibbles.len() / 2 + 1);
+            prop_assert_eq!(hex_prefix_decode(&encoded), Some((nibbles, leaf)));
+        }
+
+        #[test]
+        fn account_rlp_roundtrip(
+            nonce in any::<u64>(),
+            balance in any_u256(),
+            storage_root in any::<[u8; 32]>(),
+            code_hash in any::<[u8; 32]>(),
+        ) {
+            let account = Account { nonce, balance, storage_root, code_hash };
+            let encoded = account.encode_to_vec();
+            let mut buf = encoded.as_slice();
+            let decoded = Account::decode(&mut buf).unwrap();
+            prop_assert!(buf.is_empty());
+            prop_assert_eq!(decoded.nonce, nonce);
+            prop_assert_eq!(decoded.balance, balance);
+            prop_assert_eq!(decoded.storage_root, storage_root);
+            prop_assert_eq!(decoded.code_hash, code_hash);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! JSON Schema snapshots of the input and envelope files
+//!
+//! Regenerates every schema and compares it against tests/fixtures/schema/<type>.json, so a change
+//! to the serde format of `ProofOfBurnInputs`, `SpendInputs` or `ProofEnvelope` fails here and
+//! shows up as a snapshot diff in review. After an intended change, rewrite the snapshots with
+//!
+//! ```text
+//! UPDATE_SCHEMAS=1 cargo test --test schema_snapshots
+//! ```
+
+#![cfg(feature = "schema")]
+
+use std::path::PathBuf;
+
+use proof_of_burn_stwo::schema::SchemaType;
+
+fn snapshot_path(schema_type: SchemaType) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/schema")
+        .join(format!("{}.json", schema_type.name()))
+}
+
+#[test]
+fn test_schemas_match_snapshots() {
+    let update = std::env::var_os("UPDATE_SCHEMAS").is_some();
+    for schema_type in SchemaType::ALL {
+        let path = snapshot_path(schema_type);
+        let generated = schema_type.to_json();
+        if update {
+            std::fs::write(&path, generated + "\n").unwrap();
+            continue;
+        }
+
+        let snapshot = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("Failed to read {}: {err}", path.display()));
+        let snapshot: serde_json::Value = serde_json::from_str(&snapshot).unwrap();
+        let generated: serde_json::Value = serde_json::from_str(&generated).unwrap();
+        assert!(
+            snapshot == generated,
+            "{schema_type} schema differs from {}; rerun with UPDATE_SCHEMAS=1 if the serde change is \
+             intended\n{}",
+            path.display(),
+            serde_json::to_string_pretty(&generated).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_hex_fields_are_patterned_strings() {
+    let schema = serde_json::to_value(SchemaType::Envelope.schema()).unwrap();
+    for name in ["U256", "B256", "Bytes"] {
+        let definition = &schema["definitions"][name];
+        assert_eq!(definition["type"], "string", "{name}");
+        assert!(definition["pattern"].as_str().unwrap().starts_with('^'), "{name}");
+    }
+    assert_eq!(schema["properties"]["commitments"]["items"]["$ref"], "#/definitions/B256");
+}
+
+#[test]
+fn test_schema_type_names_roundtrip() {
+    for schema_type in SchemaType::ALL {
+        assert_eq!(schema_type.name().parse::<SchemaType>().unwrap(), schema_type);
+    }
+    assert!("inputs".parse::<SchemaType>().is_err());
+}
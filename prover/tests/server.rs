@@ -0,0 +1,180 @@
+// HTTP server end-to-end tests
+// Start a `ProofServer` in-process on a random localhost port and talk plain HTTP/1.1 to it
+// over a `TcpStream`, the way a wallet would. Inputs are the shipped small fixtures, which pass
+// every pre-check and prove as they are.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::OnceLock;
+
+use alloy_primitives::U256;
+use proof_of_burn_stwo::constants::CircuitParams;
+use proof_of_burn_stwo::fixtures::{small_burn, small_spend};
+use proof_of_burn_stwo::prover::{PobProver, ProofEnvelope, StarkConfig};
+use proof_of_burn_stwo::server::ProofServer;
+use serde_json::Value;
+
+const TOKEN: &str = "test-token";
+
+/// Start a server on a free port and return its address; it serves until the test binary exits
+fn start(token: Option<&str>) -> SocketAddr {
+    let prover = PobProver::new(StarkConfig::default(), CircuitParams::default());
+    let mut server = ProofServer::bind("127.0.0.1:0", prover).unwrap();
+    if let Some(token) = token {
+        server = server.with_token(token);
+    }
+    let addr = server.local_addr().unwrap();
+    std::thread::spawn(move || server.run(2));
+    addr
+}
+
+/// The server most tests share, without a token
+fn server() -> SocketAddr {
+    static SERVER: OnceLock<SocketAddr> = OnceLock::new();
+    *SERVER.get_or_init(|| start(None))
+}
+
+/// A response: status, headers as sent and the body
+struct Response {
+    status: u16,
+    head: String,
+    body: String,
+}
+
+impl Response {
+    fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or_else(|e| panic!("{e}: {}", self.body))
+    }
+}
+
+fn request(addr: SocketAddr, method: &str, path: &str, headers: &[&str], body: &[u8]) -> Response {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let mut head = format!("{method} {path} HTTP/1.1\r\nHost: {addr}\r\n");
+    for header in headers {
+        head.push_str(&format!("{header}\r\n"));
+    }
+    head.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+    stream.write_all(head.as_bytes()).unwrap();
+    stream.write_all(body).unwrap();
+
+    // Every response closes the connection
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("response has a head");
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    Response { status, head: head.to_string(), body: body.to_string() }
+}
+
+fn post_json(path: &str, body: &impl serde::Serialize) -> Response {
+    request(server(), "POST", path, &[], &serde_json::to_vec(body).unwrap())
+}
+
+/// Events of an event-stream body, as (event, data) pairs
+fn events(body: &str) -> Vec<(String, Value)> {
+    body.split("\n\n")
+        .filter(|event| !event.is_empty())
+        .map(|event| {
+            let field = |name: &str| {
+                event.lines().find_map(|line| line.strip_prefix(name)).unwrap().to_string()
+            };
+            (field("event: "), serde_json::from_str(&field("data: ")).unwrap())
+        })
+        .collect()
+}
+
+fn envelope_bytes(response: &Value) -> Vec<u8> {
+    let hex = response["envelope"].as_str().unwrap();
+    hex::decode(hex.strip_prefix("0x").unwrap()).unwrap()
+}
+
+#[test]
+fn test_info() {
+    let response = request(server(), "GET", "/info", &[], &[]);
+    assert_eq!(response.status, 200);
+    let info = response.json();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert!(info["envelope_version"].as_u64().is_some());
+    assert!(info["circuit_params_hash"].as_str().unwrap().starts_with("0x"));
+}
+
+#[test]
+fn test_prove_burn_then_verify() {
+    let (inputs, _) = small_burn();
+    let response = post_json("/prove/burn", &inputs);
+    assert_eq!(response.status, 200, "{}", response.body);
+    let proof = response.json();
+    let bytes = envelope_bytes(&proof);
+
+    let verified = request(server(), "POST", "/verify", &[], &bytes);
+    assert_eq!(verified.status, 200, "{}", verified.body);
+    assert_eq!(verified.json()["proof_id"], proof["proof_id"]);
+
+    // A proof of another statement than the one it claims is refused
+    let mut envelope = ProofEnvelope::from_bytes_auto(&bytes).unwrap();
+    envelope.public_inputs[1] += U256::from(1);
+    let tampered = request(server(), "POST", "/verify", &[], &envelope.to_bytes().unwrap());
+    assert_eq!(tampered.status, 422);
+    let error = tampered.json();
+    assert_eq!(error["code"], "E_VERIFY_FAIL");
+    assert!(error["details"].is_object());
+}
+
+#[test]
+fn test_prove_spend_streams_progress() {
+    let (inputs, outputs) = small_spend();
+    let body = serde_json::to_vec(&inputs).unwrap();
+    let response =
+        request(server(), "POST", "/prove/spend", &["Accept: text/event-stream"], &body);
+    assert_eq!(response.status, 200);
+    assert!(response.head.contains("Content-Type: text/event-stream"));
+
+    let events = events(&response.body);
+    let names: Vec<_> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["progress", "progress", "result"]);
+    assert_eq!(events[0].1["phase"], "validated");
+    assert_eq!(events[1].1["phase"], "proving");
+
+    let result = &events[2].1;
+    assert_eq!(result["circuit"], "spend");
+    let public_inputs: Vec<U256> = serde_json::from_value(result["public_inputs"].clone()).unwrap();
+    assert_eq!(public_inputs[1], U256::from(outputs.coin.value()));
+}
+
+#[test]
+fn test_token_required() {
+    let addr = start(Some(TOKEN));
+    let refused = request(addr, "GET", "/info", &[], &[]);
+    assert_eq!(refused.status, 401);
+    assert_eq!(refused.json()["code"], "E_UNAUTHORIZED");
+    let wrong = request(addr, "GET", "/info", &["Authorization: Bearer other"], &[]);
+    assert_eq!(wrong.status, 401);
+
+    let authorization = format!("Authorization: Bearer {TOKEN}");
+    assert_eq!(request(addr, "GET", "/info", &[&authorization], &[]).status, 200);
+}
+
+#[test]
+fn test_errors_carry_error_codes() {
+    // No body: the counterpart of a missing input file
+    let empty = request(server(), "POST", "/prove/burn", &[], &[]);
+    assert_eq!(empty.status, 400);
+    assert_eq!(empty.json()["code"], "E_INPUT_MISSING");
+
+    // A burn key below the smallest one with enough PoW fails the pre-check on it first
+    let mut inputs = serde_json::to_value(small_burn().0).unwrap();
+    inputs["burn_key"] = Value::from(1u32);
+    let rejected = post_json("/prove/burn", &inputs);
+    assert_eq!(rejected.status, 400);
+    let error = rejected.json();
+    assert_eq!(error["code"], "E_POW_FAIL");
+    assert!(error["details"].is_object(), "{error}");
+
+    let malformed = request(server(), "POST", "/prove/spend", &[], b"{");
+    assert_eq!(malformed.status, 400);
+    assert_eq!(malformed.json()["code"], "E_INPUT_PARSE");
+
+    let missing = request(server(), "GET", "/nowhere", &[], &[]);
+    assert_eq!(missing.status, 404);
+    assert_eq!(missing.json()["code"], "E_ROUTE");
+    assert_eq!(request(server(), "GET", "/verify", &[], &[]).status, 405);
+}
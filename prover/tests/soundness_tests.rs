@@ -0,0 +1,444 @@
+// Negative-path tests: the verifier must reject anything that is not an honest proof
+// Every test here starts from a proof that verifies and changes exactly one thing about it
+
+use alloy_primitives::U256;
+use proof_of_burn_stwo::circuits::layout::{MERGE_LAYOUT, POB_LAYOUT, SPEND_LAYOUT, SPLIT_LAYOUT};
+use proof_of_burn_stwo::circuits::range_check_air::{balance_chunks, BALANCE_CHUNKS, RANGE_CHECK_BITS};
+use proof_of_burn_stwo::circuits::{MergeInputs, ProofOfBurnInputs, SpendInputs, SplitInputs};
+use proof_of_burn_stwo::constants::MAINNET_CHAIN_ID;
+use proof_of_burn_stwo::prover::{
+    prove_merge_with_trace_hook, prove_proof_of_burn_with_trace_hook, prove_spend_with_trace_hook,
+    prove_split_with_trace_hook, TraceColumns,
+};
+use proof_of_burn_stwo::secret::Secret;
+use proof_of_burn_stwo::{
+    prove_merge, prove_proof_of_burn, prove_spend, prove_split, verify_merge, verify_proof_of_burn,
+    verify_spend, verify_spend_against, verify_split, StarkConfig, M31,
+};
+use stwo_prover::core::fields::m31::BaseField;
+use stwo_prover::core::proof::StarkProof;
+use stwo_prover::core::vcs::blake2_merkle::Blake2sMerkleHasher;
+use stwo_prover::prover::backend::simd::m31::N_LANES;
+use stwo_prover::prover::backend::Column;
+
+const LOG_N_ROWS: u32 = 6;
+
+/// Number of positions of the serialized proof to corrupt, spread evenly over it
+const FLIPPED_POSITIONS: usize = 16;
+
+fn pob_inputs(burn_key: u32) -> ProofOfBurnInputs {
+    ProofOfBurnInputs {
+        burn_key: Secret::new(M31::from(burn_key)),
+        actual_balance: U256::from(1000000000000000000u64),
+        intended_balance: U256::from(1000000000000000000u64),
+        reveal_amount: U256::from(500000000000000000u64),
+        burn_extra_commitment: M31::from(100),
+        layers: vec![vec![0u8; 100], vec![0u8; 80]],
+        block_header: vec![0u8; 643],
+        header_chain: Vec::new(),
+        chain_id: MAINNET_CHAIN_ID,
+        valid_until_block: None,
+        num_leaf_address_nibbles: 50,
+        byte_security_relax: 0,
+        proof_extra_commitment: M31::from(200),
+        expected_burn_address: None,
+    }
+}
+
+fn spend_inputs(burn_key: u32) -> SpendInputs {
+    SpendInputs {
+        burn_key: Secret::new(M31::from(burn_key)),
+        balance: U256::from(1000),
+        withdrawn_balance: U256::from(400),
+        extra_commitment: M31::from(100),
+        recipient: None,
+        chain_id: MAINNET_CHAIN_ID,
+        expected_coin: None,
+    }
+}
+
+fn split_inputs(burn_key: u32) -> SplitInputs {
+    SplitInputs {
+        burn_key: Secret::new(M31::from(burn_key)),
+        balance: U256::from(1000),
+        amount_a: U256::from(300),
+        amount_b: U256::from(700),
+        extra_commitment: M31::from(100),
+    }
+}
+
+fn merge_inputs(burn_key: u32) -> MergeInputs {
+    MergeInputs {
+        burn_key: Secret::new(M31::from(burn_key)),
+        balance_a: U256::from(300),
+        balance_b: U256::from(700),
+        merged_balance: U256::from(1000),
+        extra_commitment: M31::from(100),
+    }
+}
+
+/// Copies of `proof`'s JSON, each with the last digit of one number flipped (0 <-> 1, 2 <-> 3, ...)
+///
+/// Changing only a last digit keeps the JSON well-formed and nearly always deserializable, so
+/// the corrupted proof reaches the verifier.
+fn flipped_proofs(proof: &StarkProof<Blake2sMerkleHasher>) -> Vec<Vec<u8>> {
+    let json = serde_json::to_vec(proof).unwrap();
+    let last_digits: Vec<usize> = (0..json.len())
+        .filter(|&i| json[i].is_ascii_digit() && !json.get(i + 1).is_some_and(u8::is_ascii_digit))
+        .collect();
+    let step = last_digits.len() / FLIPPED_POSITIONS;
+    last_digits
+        .iter()
+        .step_by(step.max(1))
+        .take(FLIPPED_POSITIONS)
+        .map(|&i| {
+            let mut flipped = json.clone();
+            flipped[i] = b'0' + ((flipped[i] - b'0') ^ 1);
+            flipped
+        })
+        .collect()
+}
+
+/// Add one to the witness cell at row 0 of `column`
+fn bump_cell(column: usize) -> impl FnOnce(&mut TraceColumns) {
+    move |trace| {
+        let values = &mut trace[column].values;
+        values.set(0, values.at(0) + BaseField::from(1));
+    }
+}
+
+/// Rewrite the reveal amount of a PoB trace for `intended_balance`, redoing the remaining-balance
+/// subtraction modulo 2^64 so that every chunk stays in range
+///
+/// Above the intended balance the only cell left inconsistent is the missing borrow out of the
+/// top chunk; at the honest reveal amount the trace is unchanged.
+fn set_reveal_amount(intended_balance: u64, reveal_amount: u64) -> impl FnOnce(&mut TraceColumns) {
+    move |trace| {
+        let borrows = (1..BALANCE_CHUNKS).map(|i| {
+            let mask = (1u64 << (RANGE_CHECK_BITS as usize * i)) - 1;
+            BaseField::from(((intended_balance & mask) < (reveal_amount & mask)) as u32)
+        });
+        let remaining_balance = intended_balance.wrapping_sub(reveal_amount);
+        let reveal_limbs = [reveal_amount as u32, (reveal_amount >> 32) as u32].map(BaseField::from);
+        let cells = [
+            (POB_LAYOUT.inputs().start + 5, reveal_limbs.to_vec()),
+            (POB_LAYOUT.reveal_amount_chunks().start, balance_chunks(reveal_amount).to_vec()),
+            (POB_LAYOUT.remaining_balance_chunks().start, balance_chunks(remaining_balance).to_vec()),
+            (POB_LAYOUT.remaining_balance_borrows().start, borrows.collect()),
+        ];
+        for (start, values) in cells {
+            for (column, value) in (start..).zip(values) {
+                trace[column].values.set(0, value);
+            }
+        }
+    }
+}
+
+/// Rewrite the actual balance of a PoB trace for `intended_balance`, redoing the excess-balance
+/// subtraction modulo 2^64 so that every chunk stays in range
+///
+/// Below the intended balance the only cell left inconsistent is the missing borrow out of the
+/// top chunk; at the honest actual balance the trace is unchanged.
+fn set_actual_balance(intended_balance: u64, actual_balance: u64) -> impl FnOnce(&mut TraceColumns) {
+    move |trace| {
+        let borrows = (1..BALANCE_CHUNKS).map(|i| {
+            let mask = (1u64 << (RANGE_CHECK_BITS as usize * i)) - 1;
+            BaseField::from(((actual_balance & mask) < (intended_balance & mask)) as u32)
+        });
+        let excess_balance = actual_balance.wrapping_sub(intended_balance);
+        let actual_limbs = [actual_balance as u32, (actual_balance >> 32) as u32].map(BaseField::from);
+        let cells = [
+            (POB_LAYOUT.inputs().start + 1, actual_limbs.to_vec()),
+            (POB_LAYOUT.actual_balance_chunks().start, balance_chunks(actual_balance).to_vec()),
+            (POB_LAYOUT.excess_balance_chunks().start, balance_chunks(excess_balance).to_vec()),
+            (POB_LAYOUT.excess_balance_borrows().start, borrows.collect()),
+        ];
+        for (start, values) in cells {
+            for (column, value) in (start..).zip(values) {
+                trace[column].values.set(0, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod pob_soundness {
+    use super::*;
+
+    #[test]
+    fn test_rejects_flipped_proof_bytes() {
+        let (components, proof) = prove_proof_of_burn(&pob_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        for (n, bytes) in flipped_proofs(&proof).into_iter().enumerate() {
+            // A flip that no longer deserializes is rejected too
+            if let Ok(corrupted) = serde_json::from_slice::<StarkProof<Blake2sMerkleHasher>>(&bytes) {
+                assert!(
+                    verify_proof_of_burn(&components, corrupted).is_err(),
+                    "corruption {} was accepted",
+                    n
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampered_trace() {
+        let column = POB_LAYOUT.remaining_balance_chunks().start;
+        let result = prove_proof_of_burn_with_trace_hook(
+            &pob_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            bump_cell(column),
+        );
+        // The prover may already refuse; if it does not, the verifier must
+        if let Ok((components, proof)) = result {
+            assert!(verify_proof_of_burn(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_forged_commitment() {
+        // Arbitrary limbs on every witness row, claimed consistently as the public output: only
+        // the Poseidon2 lookup ties them to the constrained input state, and nothing answers it
+        let forge = |trace: &mut TraceColumns| {
+            for (i, column) in POB_LAYOUT.commitment_out().enumerate() {
+                for row in 0..N_LANES {
+                    trace[column].values.set(row, BaseField::from(0x2545f491u32.wrapping_mul(i as u32 + 1)));
+                }
+            }
+        };
+        let result =
+            prove_proof_of_burn_with_trace_hook(&pob_inputs(12345), LOG_N_ROWS, StarkConfig::default(), forge);
+        if let Ok((components, proof)) = result {
+            assert!(verify_proof_of_burn(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_reveal_above_intended_balance() {
+        // pob_inputs burns 1 ETH and reveals 0.5 ETH
+        let intended_balance = 1000000000000000000u64;
+        let honest = prove_proof_of_burn_with_trace_hook(
+            &pob_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            set_reveal_amount(intended_balance, 500000000000000000),
+        );
+        let (components, proof) = honest.expect("Failed to generate proof");
+        assert!(verify_proof_of_burn(&components, proof).is_ok());
+
+        // Same low limb, but a high limb one above the intended balance's: the remaining balance
+        // wraps to about 2^64
+        let reveal_amount = (((intended_balance >> 32) + 1) << 32) | (500000000000000000u64 & 0xFFFFFFFF);
+        let result = prove_proof_of_burn_with_trace_hook(
+            &pob_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            set_reveal_amount(intended_balance, reveal_amount),
+        );
+        if let Ok((components, proof)) = result {
+            assert!(verify_proof_of_burn(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_intended_above_actual_balance() {
+        // pob_inputs burns 1 ETH and intends all of it
+        let intended_balance = 1000000000000000000u64;
+        let honest = prove_proof_of_burn_with_trace_hook(
+            &pob_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            set_actual_balance(intended_balance, intended_balance),
+        );
+        let (components, proof) = honest.expect("Failed to generate proof");
+        assert!(verify_proof_of_burn(&components, proof).is_ok());
+
+        // One wei short of the intended balance: the excess wraps to 2^64 - 1
+        let result = prove_proof_of_burn_with_trace_hook(
+            &pob_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            set_actual_balance(intended_balance, intended_balance - 1),
+        );
+        if let Ok((components, proof)) = result {
+            assert!(verify_proof_of_burn(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_components_of_other_inputs() {
+        let (_, proof) = prove_proof_of_burn(&pob_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_proof_of_burn(&pob_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(verify_proof_of_burn(&other_components, proof).is_err());
+    }
+
+    #[test]
+    fn test_rejects_public_outputs_of_other_inputs() {
+        let (mut components, proof) = prove_proof_of_burn(&pob_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_proof_of_burn(&pob_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        components.public_values = other_components.public_values;
+        assert!(
+            verify_proof_of_burn(&components, proof).is_err(),
+            "proof for burn key 12345 accepted with the outputs of burn key 54321"
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_log_n_rows() {
+        let (_, proof) = prove_proof_of_burn(&pob_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (larger_components, _) = prove_proof_of_burn(&pob_inputs(12345), LOG_N_ROWS + 1, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(verify_proof_of_burn(&larger_components, proof).is_err());
+    }
+}
+
+#[cfg(test)]
+mod spend_soundness {
+    use super::*;
+
+    #[test]
+    fn test_rejects_flipped_proof_bytes() {
+        let (components, proof) = prove_spend(&spend_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        for (n, bytes) in flipped_proofs(&proof).into_iter().enumerate() {
+            if let Ok(corrupted) = serde_json::from_slice::<StarkProof<Blake2sMerkleHasher>>(&bytes) {
+                assert!(verify_spend(&components, corrupted).is_err(), "corruption {} was accepted", n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_tampered_trace() {
+        let column = SPEND_LAYOUT.remaining_balance_chunks().start;
+        let result = prove_spend_with_trace_hook(
+            &spend_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            bump_cell(column),
+        );
+        if let Ok((components, proof)) = result {
+            assert!(verify_spend(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_components_of_other_inputs() {
+        let (_, proof) = prove_spend(&spend_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_spend(&spend_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(verify_spend(&other_components, proof).is_err());
+    }
+
+    // Spend outputs are bound through the `SpendStatement` the channel mixes in; unlike
+    // `PobPublicValues`, the trace does not expose them as public values yet
+    #[test]
+    fn test_rejects_public_outputs_of_other_inputs() {
+        let (components, proof) = prove_spend(&spend_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_spend(&spend_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(
+            verify_spend_against(&components, &other_components.statement, proof).is_err(),
+            "proof for burn key 12345 accepted against the outputs of burn key 54321"
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_log_n_rows() {
+        let (_, proof) = prove_spend(&spend_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (larger_components, _) = prove_spend(&spend_inputs(12345), LOG_N_ROWS + 1, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(verify_spend(&larger_components, proof).is_err());
+    }
+}
+
+#[cfg(test)]
+mod split_soundness {
+    use super::*;
+
+    #[test]
+    fn test_rejects_flipped_proof_bytes() {
+        let (components, proof) = prove_split(&split_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        for (n, bytes) in flipped_proofs(&proof).into_iter().enumerate() {
+            if let Ok(corrupted) = serde_json::from_slice(&bytes) {
+                assert!(verify_split(&components, corrupted).is_err(), "corruption {} was accepted", n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_inflated_child() {
+        // One more unit in child B's lowest chunk would mint value out of nothing
+        let column = SPLIT_LAYOUT.amount_b_chunks().start;
+        let result = prove_split_with_trace_hook(
+            &split_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            bump_cell(column),
+        );
+        if let Ok((components, proof)) = result {
+            assert!(verify_split(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_forged_child_coin() {
+        // The coin cell must be the permutation of the preimage the Poseidon2 table proves
+        let column = SPLIT_LAYOUT.child_coin_a().start;
+        let result = prove_split_with_trace_hook(
+            &split_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            bump_cell(column),
+        );
+        if let Ok((components, proof)) = result {
+            assert!(verify_split(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_components_of_other_inputs() {
+        let (_, proof) = prove_split(&split_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_split(&split_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(verify_split(&other_components, proof).is_err());
+    }
+}
+
+#[cfg(test)]
+mod merge_soundness {
+    use super::*;
+
+    #[test]
+    fn test_rejects_inflated_merged_balance() {
+        let column = MERGE_LAYOUT.merged_balance_chunks().start;
+        let result = prove_merge_with_trace_hook(
+            &merge_inputs(12345),
+            LOG_N_ROWS,
+            StarkConfig::default(),
+            bump_cell(column),
+        );
+        if let Ok((components, proof)) = result {
+            assert!(verify_merge(&components, proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_components_of_other_inputs() {
+        let (_, proof) = prove_merge(&merge_inputs(12345), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        let (other_components, _) = prove_merge(&merge_inputs(54321), LOG_N_ROWS, StarkConfig::default())
+            .expect("Failed to generate proof");
+        assert!(verify_merge(&other_components, proof).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+// Poseidon2 cross-check against stwo's example
+// `utils::poseidon2_stwo` was adapted from stwo's Poseidon example
+// (crates/examples/src/poseidon/mod.rs). `reference` below is that permutation, copied from the
+// stwo revision Cargo.toml pins, with its parameters passed in. Ours differs from it in two
+// ways:
+//
+// - Parameters: the example hashes with placeholder round constants (1234 everywhere) and 14
+//   partial rounds; ours uses Grain LFSR constants and 26 partial rounds.
+// - Internal round matrix: the example maps `s_i` to `2^{i+1} * s_i + sum(s)`, ours to
+//   `mu_i * s_i + sum(s)` with `mu_i = 2^{i+1} + 1`, and `mu_0 = 4` for the minimal polynomial
+//   condition (Poseidon2 paper, Section 5.3). Every diagonal entry of ours is one larger than
+//   the example's, except the first, which is two larger.
+//
+// The M4 and external round matrices, the S-box and the round order are the same. These tests
+// fail if any of that changes, on either side, and when the pinned stwo revision moves: copy
+// the example again from the new revision and recheck the pattern before updating `STWO_REV`.
+//
+// Needs the `stwo-compat-tests` feature: cargo test --features stwo-compat-tests --test stwo_compat
+
+use proof_of_burn_stwo::utils::poseidon2_stwo::{
+    poseidon2_permutation, EXTERNAL_ROUND_CONSTS, INTERNAL_MATRIX_DIAGONAL, INTERNAL_ROUND_CONSTS,
+    N_HALF_FULL_ROUNDS, N_PARTIAL_ROUNDS, N_STATE,
+};
+use stwo_prover::core::fields::m31::BaseField;
+
+/// stwo revision `reference` was copied from
+const STWO_REV: &str = "699ae6e8f014d59749aca058be18b081e44628ea";
+
+/// stwo's example permutation, its constants and internal matrix swappable
+mod reference {
+    use super::{BaseField, N_HALF_FULL_ROUNDS, N_STATE};
+
+    /// The example's partial round count
+    pub const N_PARTIAL_ROUNDS: usize = 14;
+
+    /// The example's placeholder round constant
+    pub const PLACEHOLDER: BaseField = BaseField::from_u32_unchecked(1234);
+
+    /// Multiplier `s_i` is scaled by before the example adds the state sum
+    pub fn internal_multiplier(i: usize) -> BaseField {
+        BaseField::from_u32_unchecked(1 << (i + 1))
+    }
+
+    pub struct Params<'a> {
+        pub external: &'a [[BaseField; N_STATE]; 2 * N_HALF_FULL_ROUNDS],
+        pub internal: &'a [BaseField],
+        pub multipliers: [BaseField; N_STATE],
+    }
+
+    fn apply_m4(x: [BaseField; 4]) -> [BaseField; 4] {
+        let t0 = x[0] + x[1];
+        let t02 = t0 + t0;
+        let t1 = x[2] + x[3];
+        let t12 = t1 + t1;
+        let t2 = x[1] + x[1] + t1;
+        let t3 = x[3] + x[3] + t0;
+        let t4 = t12 + t12 + t3;
+        let t5 = t02 + t02 + t2;
+        let t6 = t3 + t5;
+        let t7 = t2 + t4;
+        [t6, t5, t7, t4]
+    }
+
+    fn apply_external_round_matrix(state: &mut [BaseField; N_STATE]) {
+        for i in 0..4 {
+            [state[4 * i], state[4 * i + 1], state[4 * i + 2], state[4 * i + 3]] =
+                apply_m4([state[4 * i], state[4 * i + 1], state[4 * i + 2], state[4 * i + 3]]);
+        }
+        for j in 0..4 {
+            let s = state[j] + state[j + 4] + state[j + 8] + state[j + 12];
+            for i in 0..4 {
+                state[4 * i + j] += s;
+            }
+        }
+    }
+
+    pub fn apply_internal_round_matrix(state: &mut [BaseField; N_STATE], multipliers: &[BaseField; N_STATE]) {
+        let sum = state[1..].iter().fold(state[0], |acc, s| acc + *s);
+        state.iter_mut().zip(multipliers).for_each(|(s, multiplier)| {
+            *s = *s * *multiplier + sum;
+        });
+    }
+
+    fn pow5(x: BaseField) -> BaseField {
+        let x4 = x * x * x * x;
+        x4 * x
+    }
+
+    fn full_round(state: &mut [BaseField; N_STATE], constants: &[BaseField; N_STATE]) {
+        for (s, c) in state.iter_mut().zip(constants) {
+            *s += *c;
+        }
+        apply_external_round_matrix(state);
+        state.iter_mut().for_each(|s| *s = pow5(*s));
+    }
+
+    pub fn permutation(mut state: [BaseField; N_STATE], params: &Params) -> [BaseField; N_STATE] {
+        for round in 0..N_HALF_FULL_ROUNDS {
+            full_round(&mut state, &params.external[round]);
+        }
+        for constant in params.internal {
+            state[0] += *constant;
+            apply_internal_round_matrix(&mut state, &params.multipliers);
+            state[0] = pow5(state[0]);
+        }
+        for round in 0..N_HALF_FULL_ROUNDS {
+            full_round(&mut state, &params.external[round + N_HALF_FULL_ROUNDS]);
+        }
+        state
+    }
+
+    /// The example as it ships
+    pub fn stwo_permutation(state: [BaseField; N_STATE]) -> [BaseField; N_STATE] {
+        let external = [[PLACEHOLDER; N_STATE]; 2 * N_HALF_FULL_ROUNDS];
+        let params = Params {
+            external: &external,
+            internal: &[PLACEHOLDER; N_PARTIAL_ROUNDS],
+            multipliers: std::array::from_fn(internal_multiplier),
+        };
+        permutation(state, &params)
+    }
+}
+
+/// Fixed states: zero, unit vectors, edge values and a spread of mixed ones
+fn fixed_states() -> Vec<[BaseField; N_STATE]> {
+    let m = |v: u32| BaseField::from_u32_unchecked(v);
+    let mut states = vec![[m(0); N_STATE], [m(1); N_STATE], [m((1 << 31) - 2); N_STATE]];
+    states.extend((0..N_STATE).map(|i| std::array::from_fn(|j| m((i == j) as u32))));
+    states.extend((0..8u32).map(|seed| {
+        let mix = |j: usize| seed.wrapping_mul(0x9e37_79b9).wrapping_add((j as u32).wrapping_mul(0x85eb_ca6b));
+        std::array::from_fn(|j| m(mix(j) % ((1 << 31) - 1)))
+    }));
+    states
+}
+
+#[test]
+fn test_reference_copied_from_pinned_rev() {
+    let manifest = include_str!("../Cargo.toml");
+    let pinned = manifest
+        .lines()
+        .filter(|line| line.starts_with("stwo-") && line.contains("rev = "))
+        .map(|line| line.split("rev = \"").nth(1).and_then(|rest| rest.split('"').next()))
+        .collect::<Vec<_>>();
+    assert!(!pinned.is_empty());
+    assert!(
+        pinned.iter().all(|rev| *rev == Some(STWO_REV)),
+        "stwo moved to {pinned:?}: copy its Poseidon example into `reference` again and update STWO_REV"
+    );
+}
+
+#[test]
+fn test_stwo_example_diverges_on_every_state() {
+    for state in fixed_states() {
+        assert_ne!(poseidon2_permutation(state), reference::stwo_permutation(state), "{state:?}");
+    }
+}
+
+#[test]
+fn test_parameters_differ_from_the_example() {
+    assert_eq!((N_PARTIAL_ROUNDS, reference::N_PARTIAL_ROUNDS), (26, 14));
+    let placeholders = EXTERNAL_ROUND_CONSTS
+        .iter()
+        .flatten()
+        .chain(&INTERNAL_ROUND_CONSTS)
+        .filter(|&&constant| constant == reference::PLACEHOLDER)
+        .count();
+    assert_eq!(placeholders, 0);
+}
+
+#[test]
+fn test_internal_matrix_is_the_only_structural_difference() {
+    let with_multipliers = |multipliers| reference::Params {
+        external: &EXTERNAL_ROUND_CONSTS,
+        internal: &INTERNAL_ROUND_CONSTS,
+        multipliers,
+    };
+    let ours = with_multipliers(INTERNAL_MATRIX_DIAGONAL);
+    let examples = with_multipliers(std::array::from_fn(reference::internal_multiplier));
+
+    for state in fixed_states() {
+        // Under our constants and our internal matrix, the example's rounds are ours exactly
+        assert_eq!(reference::permutation(state, &ours), poseidon2_permutation(state), "{state:?}");
+        // With the example's internal matrix they are not
+        assert_ne!(reference::permutation(state, &examples), poseidon2_permutation(state), "{state:?}");
+    }
+}
+
+#[test]
+fn test_internal_matrix_divergence_pattern() {
+    // Column j of each matrix is its image of the unit vector e_j
+    let column = |multipliers: &[BaseField; N_STATE], j: usize| {
+        let mut state = [BaseField::from_u32_unchecked(0); N_STATE];
+        state[j] = BaseField::from_u32_unchecked(1);
+        reference::apply_internal_round_matrix(&mut state, multipliers);
+        state
+    };
+    let examples: [BaseField; N_STATE] = std::array::from_fn(reference::internal_multiplier);
+
+    let mut diagonal_gaps = Vec::new();
+    for j in 0..N_STATE {
+        let (ours, theirs) = (column(&INTERNAL_MATRIX_DIAGONAL, j), column(&examples, j));
+        for i in 0..N_STATE {
+            if i == j {
+                diagonal_gaps.push((ours[i] - theirs[i]).0);
+            } else {
+                // Both add the full state sum, so off the diagonal both matrices are all ones
+                assert_eq!((ours[i].0, theirs[i].0), (1, 1), "entry ({i}, {j})");
+            }
+        }
+    }
+    // mu_0 = 4 against the example's 2, then 2^{i+1} + 1 against 2^{i+1}
+    let expected: Vec<u32> = std::iter::once(2).chain([1; N_STATE - 1]).collect();
+    assert_eq!(diagonal_gaps, expected);
+}
@@ -0,0 +1,64 @@
+//! Cross-implementation test vectors
+//!
+//! Regenerates the vectors and compares them against tests/fixtures/test_vectors.json, the file
+//! the Solidity and JS implementations check themselves against. A change to a hash, address or
+//! packing derivation fails here and shows up as a fixture diff in review. After an intended
+//! change, bump `TEST_VECTORS_VERSION` and rewrite the fixture with
+//!
+//! ```text
+//! UPDATE_VECTORS=1 cargo test --test test_vectors
+//! ```
+
+use std::path::PathBuf;
+
+use proof_of_burn_stwo::vectors::{self, TEST_VECTORS_VERSION};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test_vectors.json")
+}
+
+#[test]
+fn test_vectors_match_fixture() {
+    let generated = vectors::generate();
+    let path = fixture_path();
+    if std::env::var_os("UPDATE_VECTORS").is_some() {
+        std::fs::write(&path, generated.to_json() + "\n").unwrap();
+        return;
+    }
+
+    let fixture = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {err}", path.display()));
+    let fixture: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+    let generated = serde_json::to_value(&generated).unwrap();
+    // Section by section, so a failure names the derivation that changed
+    for (section, vectors) in generated.as_object().unwrap() {
+        assert!(
+            fixture[section] == *vectors,
+            "{section} differs from {}; rerun with UPDATE_VECTORS=1 if the change is intended\n{}",
+            path.display(),
+            serde_json::to_string_pretty(vectors).unwrap()
+        );
+    }
+    assert_eq!(fixture.as_object().unwrap().len(), generated.as_object().unwrap().len());
+}
+
+#[test]
+fn test_vectors_cover_edge_values() {
+    let vectors = vectors::generate();
+    assert_eq!(vectors.version, TEST_VECTORS_VERSION);
+    for section in ["poseidon2", "burn_address", "pow_hash", "coin", "nullifier", "public_commitment", "proof_id"] {
+        assert!(vectors.schema.contains_key(section), "{section} is not described");
+    }
+
+    let keys: Vec<u32> = vectors.burn_address.iter().map(|vector| vector.burn_key).collect();
+    assert!(keys.contains(&0) && keys.contains(&(proof_of_burn_stwo::M31_PRIME - 1)));
+    let reveals: Vec<&str> = vectors.burn_address.iter().map(|vector| vector.reveal_amount.as_str()).collect();
+    assert!(reveals.contains(&format!("0x{}", "0".repeat(64)).as_str()));
+    assert!(reveals.contains(&format!("0x00{}", "f".repeat(62)).as_str()));
+
+    // Labels identify a vector within its section
+    let mut labels: Vec<&str> = vectors.coin.iter().map(|vector| vector.label.as_str()).collect();
+    labels.sort_unstable();
+    labels.dedup();
+    assert_eq!(labels.len(), vectors.coin.len());
+}
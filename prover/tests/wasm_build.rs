@@ -0,0 +1,40 @@
+// WebAssembly build test
+// Ensures the library core (field, utils, circuits, prover) builds for
+// wasm32-unknown-unknown without the native-only `cli` feature, including the
+// pure-Rust DEFLATE backend of envelope compression and the `platform` module's
+// browser clock and `getrandom` backend.
+//
+// Ignored by default: it needs the wasm32 target installed
+// (`rustup target add wasm32-unknown-unknown`) and performs a full build.
+// Run with: cargo test --test wasm_build -- --ignored
+
+use std::process::Command;
+
+#[test]
+#[ignore = "requires the wasm32-unknown-unknown target; run with --ignored"]
+fn test_library_builds_for_wasm32() {
+    let cargo = env!("CARGO");
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+
+    let output = Command::new(cargo)
+        .current_dir(manifest_dir)
+        .args([
+            "build",
+            "--lib",
+            "--target",
+            "wasm32-unknown-unknown",
+            "--no-default-features",
+            "--features",
+            "wasm",
+            "--target-dir",
+            "target/wasm-build-test",
+        ])
+        .output()
+        .expect("Failed to invoke cargo");
+
+    assert!(
+        output.status.success(),
+        "wasm32 build failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}